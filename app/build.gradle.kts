@@ -4,6 +4,7 @@ plugins {
     alias(libs.plugins.android.application)
     alias(libs.plugins.kotlin.android)
     alias(libs.plugins.kotlin.compose)
+    alias(libs.plugins.protobuf)
 }
 
 val gitCommitCount = providers.exec {
@@ -149,6 +150,9 @@ dependencies {
     // kotlin-csv for CSV file handling
     implementation("com.jsoizo:kotlin-csv-jvm:1.10.0")
 
+    // Protobuf messages returned by the native layer (src/main/proto)
+    implementation(libs.protobuf.javalite)
+
     testImplementation(libs.junit)
     testImplementation(libs.kotest.runner.junit5)
     testImplementation(libs.kotest.property)
@@ -255,6 +259,13 @@ tasks.register<Exec>("buildRustAndroid") {
     val isRelease = isReleaseBuild()
     val buildMode = if (isRelease) "release" else "debug"
 
+    // 宿主校验策略，改名或重新签名的构建在 local.properties 中配置
+    // mamu.signerSha256: 逗号分隔的签名证书 SHA-256；mamu.processNames: 逗号分隔的进程名子串
+    // 签名校验按包名定位本应用的 base.apk
+    environment("MAMU_PACKAGE_NAME", android.defaultConfig.applicationId ?: "moe.fuqiuluo.mamu")
+    localProperties.getProperty("mamu.signerSha256")?.let { environment("MAMU_SIGNER_SHA256", it) }
+    localProperties.getProperty("mamu.processNames")?.let { environment("MAMU_PROCESS_NAMES", it) }
+
     val args = mutableListOf("cargo", "build", "--target", "aarch64-linux-android")
     if (isRelease) {
         args.add("--release")
@@ -292,4 +303,19 @@ tasks.register("createJniLibsDir") {
 
 tasks.named("copyRustLibs") {
     dependsOn("createJniLibsDir")
-}
\ No newline at end of file
+}
+
+protobuf {
+    protoc {
+        artifact = libs.protobuf.protoc.get().toString()
+    }
+    generateProtoTasks {
+        all().forEach { task ->
+            task.builtins {
+                create("java") {
+                    option("lite")
+                }
+            }
+        }
+    }
+}
@@ -14,6 +14,7 @@ import moe.fuqiuluo.mamu.data.settings.compatibilityMode
 import moe.fuqiuluo.mamu.data.settings.memoryAccessMode
 import moe.fuqiuluo.mamu.data.settings.memoryBufferSize
 import moe.fuqiuluo.mamu.driver.PointerScanner
+import moe.fuqiuluo.mamu.driver.ScanProfiles
 import moe.fuqiuluo.mamu.driver.SearchEngine
 import moe.fuqiuluo.mamu.driver.WuwaDriver
 import java.io.File
@@ -78,6 +79,11 @@ class MamuApplication : Application() {
             exitProcess(1)
         }
 
+        // 扫描配置需持久保存，不放在 cache 目录
+        if (!ScanProfiles.init(filesDir.absoluteFile.resolve("scan_profiles").absolutePath)) {
+            Log.e(TAG, "Failed to initialize ScanProfiles")
+        }
+
         WuwaDriver.setMemoryAccessMode(mmkv.memoryAccessMode) // 设置内存访问模式，同步到 WuwaDriver
         SearchEngine.setCompatibilityMode(mmkv.compatibilityMode) // 设置兼容模式，同步到 SearchEngine
 
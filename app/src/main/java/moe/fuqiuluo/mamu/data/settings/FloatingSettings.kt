@@ -134,6 +134,8 @@ var MMKV.freezeInterval: Int
  * 2 = 无缓
  * 3 = 普通
  * 4 = 深度
+ * 5 = process_vm_readv（用户态，无需驱动）
+ * 6 = /proc/pid/mem（用户态，无需驱动）
  */
 var MMKV.memoryAccessMode: Int
     get() = decodeInt(KEY_MEMORY_ACCESS_MODE, DEFAULT_MEMORY_ACCESS_MODE)
@@ -0,0 +1,16 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 一个内存访问模式的测速结果
+ *
+ * @property mode 访问模式 id，与设置中的内存读写模式一致
+ * @property available 该模式下能否读取，为 false 时其余字段无意义
+ * @property throughput 顺序读取吞吐，MB/s
+ * @property latencyNs 随机 8 字节读取的平均延迟，纳秒
+ */
+data class AccessModeBenchmark(
+    val mode: Int,
+    val available: Boolean,
+    val throughput: Double,
+    val latencyNs: Long
+)
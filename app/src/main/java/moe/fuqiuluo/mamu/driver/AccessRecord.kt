@@ -0,0 +1,16 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 访问追踪记录
+ *
+ * @property pc 访问指令地址
+ * @property tid 执行访问的线程
+ * @property address 实际访问的数据地址
+ * @property timestampNs 访问时间（CLOCK_BOOTTIME，纳秒）
+ */
+data class AccessRecord(
+    val pc: Long,
+    val tid: Int,
+    val address: Long,
+    val timestampNs: Long
+)
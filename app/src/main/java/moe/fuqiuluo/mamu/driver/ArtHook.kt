@@ -0,0 +1,86 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import android.os.Build
+
+/**
+ * ART Java 方法入口替换
+ *
+ * 改写绑定进程中 ArtMethod 的 quick 入口点，让方法调用跳到指定的 trampoline。
+ * ArtMethod 地址需自行定位，trampoline 需位于目标进程的可执行内存中。
+ */
+object ArtHook {
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * ArtMethod 关键字段
+     */
+    data class ArtMethodInfo(
+        val accessFlags: Int,
+        val data: Long,
+        val entryPoint: Long
+    )
+
+    /**
+     * 读取 ArtMethod 字段，可用于确认地址是否正确
+     *
+     * @param method ArtMethod 地址
+     * @param sdkInt 目标进程的 Android SDK 版本
+     */
+    fun inspectMethod(method: Long, sdkInt: Int = Build.VERSION.SDK_INT): ArtMethodInfo? {
+        val values = nativeInspectMethod(method, sdkInt) ?: return null
+        return ArtMethodInfo(values[0].toInt(), values[1], values[2])
+    }
+
+    /**
+     * 在 cave 处写入跳转到 target 的 16 字节跳板
+     *
+     * @return 跳板地址（即 cave），可直接传给 [installHook]
+     */
+    fun writeTrampoline(cave: Long, target: Long): Long {
+        return nativeWriteTrampoline(cave, target)
+    }
+
+    /**
+     * 替换方法入口点
+     *
+     * @return 原始入口点，可供 trampoline 回调原方法
+     */
+    fun installHook(method: Long, trampoline: Long, sdkInt: Int = Build.VERSION.SDK_INT): Long {
+        return nativeInstallHook(method, trampoline, sdkInt)
+    }
+
+    /**
+     * 恢复方法入口点
+     */
+    fun uninstallHook(method: Long): Boolean {
+        return nativeUninstallHook(method)
+    }
+
+    /**
+     * 恢复所有 hook
+     *
+     * @return 恢复成功的数量
+     */
+    fun uninstallAll(): Int {
+        return nativeUninstallAll()
+    }
+
+    /**
+     * 已 hook 的 ArtMethod 地址
+     */
+    fun getHookedMethods(): LongArray {
+        return nativeGetHookedMethods()
+    }
+
+    private external fun nativeInspectMethod(method: Long, sdkInt: Int): LongArray?
+    private external fun nativeWriteTrampoline(cave: Long, target: Long): Long
+    private external fun nativeInstallHook(method: Long, trampoline: Long, sdkInt: Int): Long
+    private external fun nativeUninstallHook(method: Long): Boolean
+    private external fun nativeUninstallAll(): Int
+    private external fun nativeGetHookedMethods(): LongArray
+}
@@ -0,0 +1,31 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * A file owned by mamu in the cache directory (pointer library, scan temp chunk,
+ * search result spill file, ...), as reported by [PointerScanner.listCacheFiles].
+ */
+data class CacheFileEntry(
+    /** File name inside the cache directory */
+    val name: String,
+    /** One of [Kind] constants */
+    val kind: Int,
+    /** File size in bytes */
+    val size: Long,
+    /** Last modification time, seconds since the unix epoch */
+    val modifiedTime: Long
+) {
+    /** Cache file kind constants. */
+    object Kind {
+        const val POINTER_LIBRARY = 0
+        const val SCAN_CHUNK = 1
+        const val SEARCH_RESULTS = 2
+        const val FUZZY_RESULTS = 3
+        const val RUN_REPORT = 4
+        const val SCAN_CHECKPOINT = 5
+    }
+
+    /**
+     * Gets the age of the file in seconds.
+     */
+    val ageSeconds: Long get() = System.currentTimeMillis() / 1000 - modifiedTime
+}
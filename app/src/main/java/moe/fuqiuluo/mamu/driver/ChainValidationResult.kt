@@ -0,0 +1,17 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Result of re-resolving a saved pointer chain in the live process.
+ */
+data class ChainValidationResult(
+    /** Index of the chain in the current results */
+    val index: Int,
+    /** Whether every dereference along the chain succeeded */
+    val isValid: Boolean,
+    /** Final address the chain resolves to (0 if invalid) */
+    val resolvedAddress: Long,
+    /** 8 bytes read at the resolved address */
+    val value: Long,
+    /** Index of the step that failed, or -1 if valid */
+    val failedStep: Int
+)
@@ -0,0 +1,98 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.DisplayValueType
+
+/**
+ * 值变化触发搜索
+ *
+ * 高频轮询一个已知地址，值一旦变化立即发起预先配置好的改善搜索，
+ * 用于捕捉伤害数字这类一闪而过的状态。搜索进度仍通过 [SearchEngine] 的共享缓冲区获取。
+ */
+object ChangeTrigger {
+
+    /** 默认轮询间隔（微秒） */
+    const val DEFAULT_INTERVAL_US = 1000L
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 值变化时发起精确改善搜索，已布置的触发器会被替换
+     *
+     * @param address 监视的地址
+     * @param size 监视的字节数（1..8）
+     * @param query 改善搜索内容
+     * @param type 改善搜索的数据类型
+     * @param intervalUs 轮询间隔（微秒）
+     * @param oneShot 触发一次后自动解除
+     */
+    fun armRefine(
+        address: Long,
+        size: Int,
+        query: String,
+        type: DisplayValueType,
+        intervalUs: Long = DEFAULT_INTERVAL_US,
+        oneShot: Boolean = true,
+    ): Boolean {
+        SearchEngine.prepareSharedBuffer()
+        return nativeArmRefine(address, size, intervalUs, query, type.nativeId, oneShot)
+    }
+
+    /**
+     * 值变化时发起模糊改善搜索，已布置的触发器会被替换
+     *
+     * @param condition 模糊条件，不能为 [FuzzyCondition.INITIAL]
+     */
+    fun armFuzzyRefine(
+        address: Long,
+        size: Int,
+        condition: FuzzyCondition,
+        param1: Long = 0,
+        param2: Long = 0,
+        intervalUs: Long = DEFAULT_INTERVAL_US,
+        oneShot: Boolean = true,
+    ): Boolean {
+        SearchEngine.prepareSharedBuffer()
+        return nativeArmFuzzyRefine(address, size, intervalUs, condition.nativeId, param1, param2, oneShot)
+    }
+
+    fun disarm() {
+        nativeDisarm()
+    }
+
+    /** 是否处于布置状态，单次触发器触发后变为 false */
+    fun isArmed(): Boolean = nativeIsArmed()
+
+    /** 已发起的搜索次数 */
+    fun getFireCount(): Long = nativeGetFireCount()
+
+    /** 因搜索进行中被跳过的变化次数 */
+    fun getSkippedCount(): Long = nativeGetSkippedCount()
+
+    private external fun nativeArmRefine(
+        address: Long,
+        size: Int,
+        intervalUs: Long,
+        query: String,
+        valueType: Int,
+        oneShot: Boolean
+    ): Boolean
+
+    private external fun nativeArmFuzzyRefine(
+        address: Long,
+        size: Int,
+        intervalUs: Long,
+        conditionId: Int,
+        param1: Long,
+        param2: Long,
+        oneShot: Boolean
+    ): Boolean
+
+    private external fun nativeDisarm()
+    private external fun nativeIsArmed(): Boolean
+    private external fun nativeGetFireCount(): Long
+    private external fun nativeGetSkippedCount(): Long
+}
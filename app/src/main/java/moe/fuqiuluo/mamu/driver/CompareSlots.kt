@@ -0,0 +1,81 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.DisplayValueType
+
+/**
+ * 地址比较槽
+ *
+ * 把少量候选地址钉到槽位中，一次调用刷新全部当前值，
+ * 并判断每个槽与参考槽的值是否相等，用于手动排除最终候选。
+ */
+object CompareSlots {
+
+    /** 最多可钉住的地址数 */
+    const val MAX_SLOTS = 16
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    enum class State {
+        EQUAL,
+        DIFFERENT,
+        /** 本槽或参考槽读取失败 */
+        UNREADABLE
+    }
+
+    /**
+     * 刷新后的槽位
+     *
+     * @param rawValue 当前值的原始位（小端，不足 8 字节补 0）
+     */
+    data class Slot(
+        val slot: Int,
+        val address: Long,
+        val valueType: Int,
+        val rawValue: Long,
+        val state: State
+    )
+
+    /**
+     * 钉住地址，地址已被钉住时更新其类型
+     *
+     * @return 槽位编号
+     */
+    fun pin(address: Long, valueType: DisplayValueType): Int {
+        return nativePin(address, valueType.nativeId)
+    }
+
+    fun unpin(slot: Int): Boolean {
+        return nativeUnpin(slot)
+    }
+
+    fun clear() {
+        nativeClear()
+    }
+
+    /**
+     * 重新读取所有槽位，并与参考槽比较
+     *
+     * @param reference 参考槽位
+     */
+    fun refresh(reference: Int): List<Slot> {
+        val values = nativeRefresh(reference)
+        return (values.indices step 5).map { i ->
+            Slot(
+                slot = values[i].toInt(),
+                address = values[i + 1],
+                valueType = values[i + 2].toInt(),
+                rawValue = values[i + 3],
+                state = State.entries[values[i + 4].toInt()]
+            )
+        }
+    }
+
+    private external fun nativePin(address: Long, valueType: Int): Int
+    private external fun nativeUnpin(slot: Int): Boolean
+    private external fun nativeClear()
+    private external fun nativeRefresh(reference: Int): LongArray
+}
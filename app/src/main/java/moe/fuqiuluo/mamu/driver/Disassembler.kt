@@ -18,6 +18,22 @@ data class DisassemblyResult(
     val pseudoCode: String?
 )
 
+/**
+ * Code cross-reference found by [Disassembler.findCodeXrefs].
+ * @param address Address of the referencing instruction.
+ * @param moduleName Name of the region containing the instruction.
+ * @param moduleOffset Instruction offset from the module base.
+ * @param kind One of [Disassembler.XrefKind] constants.
+ * @param refAddress Address the instruction resolves to.
+ */
+data class CodeXrefResult(
+    val address: Long,
+    val moduleName: String,
+    val moduleOffset: Long,
+    val kind: Int,
+    val refAddress: Long
+)
+
 /**
  * ARM instruction disassembler using Capstone engine.
  * Supports ARM32, Thumb, and ARM64 architectures.
@@ -36,6 +52,17 @@ object Disassembler {
         const val ARM64 = 2
     }
 
+    /**
+     * Code cross-reference kinds.
+     */
+    object XrefKind {
+        const val ADR = 0
+        const val ADRP_ADD = 1
+        const val ADRP_LOAD = 2
+        const val ADRP_STORE = 3
+        const val LITERAL_LOAD = 4
+    }
+
     /**
      * Disassembles ARM32 instructions.
      * @param bytes Instruction bytes to disassemble.
@@ -115,6 +142,60 @@ object Disassembler {
         return nativeDisassemble(architecture, bytes, address, 0)
     }
 
+    /**
+     * Reads ARM64 code from the bound process through the driver and disassembles it.
+     * Words that don't decode (literal pools, padding) are returned as `.inst` entries,
+     * and the result is cut short at the first unreadable page.
+     * @param address Address of the first instruction, rounded down to 4 bytes.
+     * @param count Number of instructions to disassemble (at most 4096).
+     * @return Array of disassembly results.
+     */
+    fun disassembleRemote(
+        address: Long,
+        count: Int
+    ): Array<DisassemblyResult> {
+        return nativeDisassemble(address, count)
+    }
+
+    /**
+     * Assembles ARM64 source into machine code.
+     * Statements are separated by `;` or newlines and may define `label:`s;
+     * branch targets are labels or absolute `#address`es.
+     * @param source Assembly source, e.g. `mov w0, #1; ret`.
+     * @param address Address the code will be placed at, used for branches.
+     * @return Little-endian machine code.
+     * @throws RuntimeException on syntax errors or unsupported instructions.
+     */
+    fun assembleARM64(
+        source: String,
+        address: Long = 0
+    ): ByteArray {
+        return nativeAssemble(source, address)
+    }
+
+    /**
+     * Scans ARM64 code regions of the bound process for instructions referencing a data address
+     * (ADR, ADRP+ADD, ADRP+LDR/STR, LDR literal).
+     * @param regions Executable regions to scan; pass every segment of a module so offsets
+     *                are relative to the module base.
+     * @param targetAddress Start of the referenced data.
+     * @param targetSize Size of the referenced data in bytes.
+     * @return Array of code cross-references.
+     */
+    fun findCodeXrefs(
+        regions: List<MemoryRegionInfo>,
+        targetAddress: Long,
+        targetSize: Int = 1
+    ): Array<CodeXrefResult> {
+        val addresses = LongArray(regions.size * 2)
+        regions.forEachIndexed { index, region ->
+            addresses[index * 2] = region.start
+            addresses[index * 2 + 1] = region.end
+        }
+        val names = Array(regions.size) { regions[it].name }
+        return nativeFindCodeXrefs(addresses, names, targetAddress, targetSize)
+    }
+
     private external fun nativeDisassemble(
         architecture: Int,
         bytes: ByteArray,
@@ -122,10 +203,27 @@ object Disassembler {
         count: Int
     ): Array<DisassemblyResult>
 
+    private external fun nativeDisassemble(
+        address: Long,
+        count: Int
+    ): Array<DisassemblyResult>
+
+    private external fun nativeAssemble(
+        source: String,
+        address: Long
+    ): ByteArray
+
     private external fun nativeGeneratePseudoCode(
         architecture: Int,
         bytes: ByteArray,
         address: Long,
         count: Int
     ): Array<DisassemblyResult>
+
+    private external fun nativeFindCodeXrefs(
+        regions: LongArray,
+        regionNames: Array<String>,
+        targetAddress: Long,
+        targetSize: Int
+    ): Array<CodeXrefResult>
 }
@@ -0,0 +1,36 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 结构体解析中的一个槽位
+ *
+ * @property offset 相对起始地址的偏移
+ * @property size 槽位大小，4 或 8
+ * @property kind 槽位类型（KIND_* 常量）
+ * @property value 原始值，4 字节槽位零扩展
+ * @property target 指针类槽位的目标地址，其余为 0
+ * @property annotation 指针为目标位置（如 libfoo.so+0x1A0），字符串为内容，数值为格式化后的值
+ */
+data class DissectedSlot(
+    val offset: Int,
+    val size: Int,
+    val kind: Int,
+    val value: Long,
+    val target: Long,
+    val annotation: String
+) {
+    companion object {
+        const val KIND_UNREADABLE = 0
+        const val KIND_ZERO = 1
+        const val KIND_POINTER = 2
+        const val KIND_VTABLE_POINTER = 3
+        const val KIND_STRING_POINTER = 4
+        const val KIND_WIDE_STRING_POINTER = 5
+        const val KIND_FLOAT = 6
+        const val KIND_DOUBLE = 7
+        const val KIND_INT = 8
+        const val KIND_UNKNOWN = 9
+    }
+
+    val isPointer: Boolean
+        get() = kind in KIND_POINTER..KIND_WIDE_STRING_POINTER
+}
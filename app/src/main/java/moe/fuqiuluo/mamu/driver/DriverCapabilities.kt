@@ -0,0 +1,33 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 当前内存后端的版本和功能
+ *
+ * @property backend 后端名，见 [WuwaDriver.backends]
+ * @property version 驱动版本（major << 16 | minor << 8 | patch），驱动早于握手协议或为用户态后端时为 0
+ * @property versionName 可读的版本号，早于握手协议的驱动为 "legacy"
+ * @property features 功能位（FEATURE_* 常量）
+ */
+data class DriverCapabilities(
+    val backend: String,
+    val version: Int,
+    val versionName: String,
+    val features: Long
+) {
+    companion object {
+        /** 读取时给出每页的成功状态 */
+        const val FEATURE_PAGE_STATUS = 1L shl 0
+        /** 物理内存读写与地址转换 */
+        const val FEATURE_PHYSICAL = 1L shl 1
+        /** 隐藏进程与 maps 条目 */
+        const val FEATURE_HIDE_MAPS = 1L shl 2
+        /** 硬件断点 */
+        const val FEATURE_WATCHPOINTS = 1L shl 3
+        /** 批量读写 */
+        const val FEATURE_VECTORIZED = 1L shl 4
+        /** 远程分配、改权限与调用 */
+        const val FEATURE_REMOTE = 1L shl 5
+    }
+
+    fun supports(feature: Long): Boolean = features and feature == feature
+}
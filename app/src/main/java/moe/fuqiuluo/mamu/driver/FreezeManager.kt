@@ -39,12 +39,33 @@ object FreezeManager {
      * @param address 要冻结的内存地址
      * @param value 要写入的值（字节数组）
      * @param valueType 值类型 ID
-     * @return 是否添加成功
+     * @return 是否添加成功（与已冻结条目或补丁重叠时返回 false）
      */
     fun addFrozen(address: Long, value: ByteArray, valueType: Int): Boolean {
+        return nativeAddFrozen(address, value, valueType)?.isEmpty() == true
+    }
+    
+    /**
+     * 添加冻结地址，并报告冲突
+     * 
+     * 与已冻结条目的字节范围重叠时拒绝添加，避免两个条目交替写入导致数值闪烁。
+     * 同一地址重复添加视为替换，不算冲突。
+     * 
+     * @return 冲突条目的地址，空数组表示添加成功，null 表示添加失败
+     */
+    fun addFrozenChecked(address: Long, value: ByteArray, valueType: Int): LongArray? {
         return nativeAddFrozen(address, value, valueType)
     }
     
+    /**
+     * 查找与 [address, address + size) 重叠的已冻结条目和补丁
+     * 
+     * @return 冲突的冻结条目与补丁地址（不含 address 处的冻结本身）
+     */
+    fun findConflicts(address: Long, size: Int): LongArray {
+        return nativeFindConflicts(address, size) ?: LongArray(0)
+    }
+    
     /**
      * 添加冻结地址（使用字符串值）
      * 
@@ -108,7 +129,8 @@ object FreezeManager {
     // Native methods
     private external fun nativeStart()
     private external fun nativeStop()
-    private external fun nativeAddFrozen(address: Long, value: ByteArray, valueType: Int): Boolean
+    private external fun nativeAddFrozen(address: Long, value: ByteArray, valueType: Int): LongArray?
+    private external fun nativeFindConflicts(address: Long, size: Int): LongArray?
     private external fun nativeRemoveFrozen(address: Long): Boolean
     private external fun nativeClearAll()
     private external fun nativeSetInterval(microseconds: Long)
@@ -0,0 +1,60 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Il2Cpp 分析
+ *
+ * 从绑定进程读取已加载的 global-metadata.dat 和 libil2cpp.so 的注册结构，
+ * 列出全部类、字段偏移和方法地址。分析结果保存在 native 侧，切换进程后需要重新分析。
+ */
+object Il2Cpp {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 分析绑定进程
+     * @param module libil2cpp.so 的文件名或路径，部分游戏会改名
+     * @return 类数量
+     */
+    fun analyze(module: String = "libil2cpp.so"): Int = nativeAnalyze(module)
+
+    /**
+     * 按完整类名查找（不区分大小写的子串匹配）
+     * @param query 为空时按顺序返回
+     * @param max 最多返回的条数
+     */
+    fun findClasses(query: String, max: Int = 500): Array<Il2CppClass> = nativeFindClasses(query, max)
+
+    /**
+     * 类的字段
+     * @param classIndex [Il2CppClass.index]
+     */
+    fun getFields(classIndex: Int): Array<Il2CppField> = nativeGetFields(classIndex)
+
+    /**
+     * 类的方法
+     * @param classIndex [Il2CppClass.index]
+     */
+    fun getMethods(classIndex: Int): Array<Il2CppMethod> = nativeGetMethods(classIndex)
+
+    /**
+     * 导出 C# 伪头文件（dump.cs 格式）
+     * @param path 输出文件路径，已存在时覆盖
+     * @return 写出的类数
+     */
+    fun exportHeaders(path: String): Int = nativeExportHeaders(path)
+
+    /**
+     * 释放分析结果
+     */
+    fun release() = nativeRelease()
+
+    private external fun nativeAnalyze(module: String): Int
+    private external fun nativeFindClasses(query: String, max: Int): Array<Il2CppClass>
+    private external fun nativeGetFields(classIndex: Int): Array<Il2CppField>
+    private external fun nativeGetMethods(classIndex: Int): Array<Il2CppMethod>
+    private external fun nativeExportHeaders(path: String): Int
+    private external fun nativeRelease()
+}
@@ -0,0 +1,27 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Il2Cpp 类
+ *
+ * @property index 类序号，用于 [Il2Cpp.getFields] / [Il2Cpp.getMethods]
+ * @property image 所属程序集，如 Assembly-CSharp.dll
+ * @property namespace 命名空间，可能为空
+ * @property name 类名，含泛型参数，嵌套类型以外层类型名为前缀
+ * @property parent 基类名，没有时为 null
+ * @property flags TypeAttributes
+ * @property fieldCount 字段数
+ * @property methodCount 方法数
+ */
+data class Il2CppClass(
+    val index: Int,
+    val image: String,
+    val namespace: String,
+    val name: String,
+    val parent: String?,
+    val flags: Int,
+    val fieldCount: Int,
+    val methodCount: Int
+) {
+    val fullName: String
+        get() = if (namespace.isEmpty()) name else "$namespace.$name"
+}
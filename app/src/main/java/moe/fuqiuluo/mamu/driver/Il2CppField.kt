@@ -0,0 +1,22 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Il2Cpp 字段
+ *
+ * @property name 字段名
+ * @property typeName 字段类型
+ * @property offset 实例字段相对对象起始的偏移，静态字段相对静态数据区；未知时为 -1
+ * @property attrs FieldAttributes
+ */
+data class Il2CppField(
+    val name: String,
+    val typeName: String,
+    val offset: Int,
+    val attrs: Int
+) {
+    val isStatic: Boolean
+        get() = attrs and 0x10 != 0
+
+    val isConst: Boolean
+        get() = attrs and 0x40 != 0
+}
@@ -0,0 +1,23 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Il2Cpp 方法
+ *
+ * @property name 方法名
+ * @property returnType 返回类型
+ * @property parameters 参数列表，如 `int a, float b`
+ * @property flags MethodAttributes
+ * @property address 运行时地址，抽象方法和未实例化的泛型方法为 0
+ * @property rva 相对 libil2cpp.so 的偏移，没有地址时为 0
+ */
+data class Il2CppMethod(
+    val name: String,
+    val returnType: String,
+    val parameters: String,
+    val flags: Int,
+    val address: Long,
+    val rva: Long
+) {
+    val isStatic: Boolean
+        get() = flags and 0x10 != 0
+}
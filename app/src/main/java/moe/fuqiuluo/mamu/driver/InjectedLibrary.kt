@@ -0,0 +1,14 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 注入到绑定进程的共享库
+ *
+ * @property path 库路径
+ * @property handle `dlopen` 返回的句柄，用于卸载
+ * @property base 库的最低映射地址，找不到映射时为 0
+ */
+data class InjectedLibrary(
+    val path: String,
+    val handle: Long,
+    val base: Long
+)
@@ -0,0 +1,139 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 代码注入
+ *
+ * 通过驱动在绑定进程内分配内存并写入代码，用于 hook 跳板和自定义 code cave，
+ * 也可以让目标进程加载共享库。解绑或切换进程前会自动释放全部分配。
+ * 驱动不支持远程分配或远程调用时调用会抛出异常。
+ */
+object Injector {
+
+    /** 远程页的访问权限 */
+    enum class Protection(val nativeId: Int) {
+        READ_WRITE(0),
+        READ_EXEC(1),
+        READ_WRITE_EXEC(2),
+    }
+
+    /**
+     * 绑定进程内的一块分配
+     *
+     * @property size 按页对齐后的大小
+     */
+    data class Allocation(
+        val address: Long,
+        val size: Long,
+        val protection: Protection
+    )
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 分配至少 [size] 字节
+     *
+     * @return 起始地址
+     */
+    fun allocate(size: Long, protection: Protection = Protection.READ_WRITE): Long {
+        return nativeAllocate(size, protection.nativeId)
+    }
+
+    /**
+     * 分配可执行内存并写入载荷
+     *
+     * @param rwx 为 false 时先以 RW 写入再改为 RX，不出现可写可执行的页
+     * @return 起始地址
+     */
+    fun loadPayload(payload: ByteArray, rwx: Boolean = false): Long {
+        return nativeLoadPayload(payload, rwx)
+    }
+
+    /**
+     * 向分配内写入数据，只读可执行的页会临时改为可写
+     */
+    fun write(address: Long, bytes: ByteArray): Boolean {
+        return nativeWrite(address, bytes)
+    }
+
+    /**
+     * 修改整块分配的权限
+     *
+     * @param address 分配的起始地址
+     */
+    fun protect(address: Long, protection: Protection): Boolean {
+        return nativeProtect(address, protection.nativeId)
+    }
+
+    /**
+     * 释放分配
+     *
+     * @param address 分配的起始地址
+     * @return 分配不存在时返回 false
+     */
+    fun free(address: Long): Boolean {
+        return nativeFree(address)
+    }
+
+    /**
+     * 释放全部分配
+     *
+     * @return 释放的数量
+     */
+    fun freeAll(): Int {
+        return nativeFreeAll()
+    }
+
+    /**
+     * 全部分配，按地址排序
+     */
+    fun listAllocations(): List<Allocation> {
+        val values = nativeListAllocations()
+        return (values.indices step 3).map { i ->
+            Allocation(values[i], values[i + 1], Protection.entries[values[i + 2].toInt()])
+        }
+    }
+
+    /**
+     * 让绑定进程通过 `dlopen` 加载共享库
+     *
+     * 库文件须能被目标进程读取（文件权限与 SELinux 上下文），失败时异常信息来自目标的 `dlerror()`。
+     *
+     * @param path 库的绝对路径
+     * @return 库的基址，找不到映射时为 0
+     */
+    fun injectLibrary(path: String): Long {
+        return nativeInjectLibrary(path)
+    }
+
+    /**
+     * 通过 `dlclose` 卸载注入的库
+     *
+     * @param handle [InjectedLibrary.handle]
+     * @return 未找到该库时返回 false
+     */
+    fun unloadLibrary(handle: Long): Boolean {
+        return nativeUnloadLibrary(handle)
+    }
+
+    /**
+     * 已注入的库，按注入顺序
+     */
+    fun listInjectedLibraries(): Array<InjectedLibrary> {
+        return nativeListInjectedLibraries()
+    }
+
+    private external fun nativeAllocate(size: Long, prot: Int): Long
+    private external fun nativeLoadPayload(payload: ByteArray, rwx: Boolean): Long
+    private external fun nativeWrite(address: Long, bytes: ByteArray): Boolean
+    private external fun nativeProtect(address: Long, prot: Int): Boolean
+    private external fun nativeFree(address: Long): Boolean
+    private external fun nativeFreeAll(): Int
+    private external fun nativeListAllocations(): LongArray
+    private external fun nativeInjectLibrary(path: String): Long
+    private external fun nativeUnloadLibrary(handle: Long): Boolean
+    private external fun nativeListInjectedLibraries(): Array<InjectedLibrary>
+}
@@ -0,0 +1,72 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 长时间操作的任务句柄
+ *
+ * 搜索、指针扫描、链构建、后台转储和脚本开始时分配一个任务 id，
+ * 可以按 id 查询状态、取消或等待结束。已结束的任务只保留最近的若干个。
+ */
+object Jobs {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    const val KIND_SEARCH = 0
+    const val KIND_POINTER_SCAN = 1
+    const val KIND_CHAIN_BUILD = 2
+    const val KIND_DUMP = 3
+    const val KIND_SCRIPT = 4
+
+    /** 任务不存在或已被清理 */
+    const val STATUS_UNKNOWN = -1
+    const val STATUS_RUNNING = 0
+    const val STATUS_COMPLETED = 1
+    const val STATUS_CANCELLED = 2
+    const val STATUS_FAILED = 3
+
+    /**
+     * 请求取消任务，任务在下一个检查点停止后状态才变为 [STATUS_CANCELLED]
+     * @return 任务不存在或已结束时返回 false
+     */
+    fun cancel(job: Int): Boolean = nativeCancelJob(job)
+
+    /**
+     * @return STATUS_* 之一
+     */
+    fun status(job: Int): Int = nativeGetJobStatus(job)
+
+    /**
+     * @return KIND_* 之一，任务不存在时返回 -1
+     */
+    fun kind(job: Int): Int = nativeGetJobKind(job)
+
+    /**
+     * 阻塞等待任务结束，不要在主线程调用
+     * @param timeoutMs 小于 0 时一直等待
+     * @return STATUS_* 之一，超时返回 [STATUS_RUNNING]
+     */
+    fun await(job: Int, timeoutMs: Long = -1): Int = nativeAwaitJob(job, timeoutMs)
+
+    /**
+     * 某类型中正在运行的任务
+     * @param kind KIND_* 之一
+     * @return 任务 id，没有时返回 0
+     */
+    fun activeJob(kind: Int): Int = nativeGetActiveJob(kind)
+
+    /**
+     * 在后台把多个区域转储到同一个文件，格式同 [WuwaDriver.dumpRegions]
+     * @param ranges [start0, end0, start1, end1, ...]
+     * @return 任务 id
+     */
+    fun startDump(ranges: LongArray, path: String): Int = nativeStartDump(ranges, path)
+
+    private external fun nativeCancelJob(job: Int): Boolean
+    private external fun nativeGetJobStatus(job: Int): Int
+    private external fun nativeGetJobKind(job: Int): Int
+    private external fun nativeAwaitJob(job: Int, timeoutMs: Long): Int
+    private external fun nativeGetActiveJob(kind: Int): Int
+    private external fun nativeStartDump(ranges: LongArray, path: String): Int
+}
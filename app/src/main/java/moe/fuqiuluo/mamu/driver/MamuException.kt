@@ -0,0 +1,30 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * native 方法抛出的异常，[code] 为 CODE_* 之一，便于按原因处理而不用匹配错误信息
+ *
+ * 内存读写错误带有出错的地址 [address] 和驱动命令 [ioctl]，没有时为 -1。
+ */
+class MamuException(
+    val code: Int,
+    message: String,
+    val address: Long = -1,
+    val ioctl: Long = -1,
+) : RuntimeException(message) {
+    val hasAddress: Boolean
+        get() = address != -1L
+
+    val isMemoryFault: Boolean
+        get() = code == CODE_READ_FAULT || code == CODE_WRITE_FAULT
+
+    companion object {
+        const val CODE_UNKNOWN = 0
+        const val CODE_DRIVER_NOT_LOADED = 1
+        const val CODE_NOT_BOUND = 2
+        const val CODE_READ_FAULT = 3
+        const val CODE_WRITE_FAULT = 4
+        const val CODE_PERMISSION = 5
+        const val CODE_CANCELLED = 6
+        const val CODE_OUT_OF_MEMORY = 7
+    }
+}
@@ -0,0 +1,61 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 内存结构分析结果，按 4 字节单元给出类型猜测
+ *
+ * @property address 第一个单元的地址
+ * @property cellTypes 每个单元的类型（CELL_* 常量）
+ * @property strides 候选结构体步长（字节），最可能的在前
+ * @property strideScores 对应步长的得分（0..1）
+ * @property fieldTypes 按最可能步长统计的每个字段的主要类型，没有候选步长时为空
+ */
+data class MemoryLayoutAnalysis(
+    val address: Long,
+    val cellTypes: ByteArray,
+    val strides: IntArray,
+    val strideScores: FloatArray,
+    val fieldTypes: ByteArray
+) {
+    companion object {
+        const val CELL_SIZE = 4
+
+        const val CELL_UNREADABLE = 0
+        const val CELL_ZERO = 1
+        const val CELL_POINTER = 2
+        const val CELL_FLOAT = 3
+        const val CELL_DOUBLE = 4
+        const val CELL_INT = 5
+        const val CELL_TEXT = 6
+        const val CELL_UNKNOWN = 7
+    }
+
+    val cellCount: Int
+        get() = cellTypes.size
+
+    /** 最可能的结构体步长，没有候选时为 null */
+    val bestStride: Int?
+        get() = strides.firstOrNull()
+
+    fun cellType(index: Int): Int = cellTypes[index].toInt()
+
+    fun cellAddress(index: Int): Long = address + index.toLong() * CELL_SIZE
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is MemoryLayoutAnalysis) return false
+        return address == other.address &&
+            cellTypes.contentEquals(other.cellTypes) &&
+            strides.contentEquals(other.strides) &&
+            strideScores.contentEquals(other.strideScores) &&
+            fieldTypes.contentEquals(other.fieldTypes)
+    }
+
+    override fun hashCode(): Int {
+        var result = address.hashCode()
+        result = 31 * result + cellTypes.contentHashCode()
+        result = 31 * result + strides.contentHashCode()
+        result = 31 * result + strideScores.contentHashCode()
+        result = 31 * result + fieldTypes.contentHashCode()
+        return result
+    }
+}
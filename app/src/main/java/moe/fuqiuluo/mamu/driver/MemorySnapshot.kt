@@ -0,0 +1,39 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+
+/**
+ * 进程内存快照
+ *
+ * 把选定类型的区域整页写入快照文件（相同内容的页只存一份），
+ * 之后离线对比两份快照，找出变化的页和值。适合"暂停 → 操作 → 对比"的场景，
+ * 代替来不及做的实时模糊搜索。
+ */
+object MemorySnapshot {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 拍摄绑定进程的快照
+     * @param ranges 要包含的区域类型
+     * @param path 快照文件路径，已存在时覆盖
+     */
+    fun take(ranges: Collection<MemoryRange>, path: String): SnapshotSummary =
+        nativeTake(MemoryRange.toMask(ranges), path)
+
+    /**
+     * 对比两份快照，只比较两边都可读的页
+     * @param oldPath 较早的快照
+     * @param newPath 较晚的快照
+     * @param valueSize 值宽度，1/2/4/8 字节，按该宽度对齐比较
+     * @param maxChanges 值变化的最大条数，超出时 [SnapshotDiff.truncated] 为 true
+     */
+    fun diff(oldPath: String, newPath: String, valueSize: Int = 4, maxChanges: Int = 100_000): SnapshotDiff =
+        nativeDiff(oldPath, newPath, valueSize, maxChanges)
+
+    private external fun nativeTake(regionMask: Long, path: String): SnapshotSummary
+    private external fun nativeDiff(oldPath: String, newPath: String, valueSize: Int, maxChanges: Int): SnapshotDiff
+}
@@ -0,0 +1,20 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 模块转储结果
+ *
+ * @property path 模块路径
+ * @property base 模块基址，在 IDA/Ghidra 中以此为镜像基址加载
+ * @property fileSize 输出文件大小
+ * @property sections 恢复的节数，0 表示输出不含节头
+ * @property headerRestored 内存中的 ELF 头已损坏，改用磁盘文件的
+ * @property unreadablePages 无法读取、以 0 填充的页数
+ */
+data class ModuleDumpResult(
+    val path: String,
+    val base: Long,
+    val fileSize: Long,
+    val sections: Int,
+    val headerRestored: Boolean,
+    val unreadablePages: Int
+)
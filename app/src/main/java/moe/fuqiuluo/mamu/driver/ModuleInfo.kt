@@ -0,0 +1,19 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 绑定进程中已加载的 ELF 模块
+ *
+ * @property path 模块文件路径
+ * @property base 首个映射的地址
+ * @property size 从基址到最高加载段结尾的长度
+ * @property loadBias 加载偏移，ELF 虚拟地址加上它得到运行时地址
+ */
+data class ModuleInfo(
+    val path: String,
+    val base: Long,
+    val size: Long,
+    val loadBias: Long
+) {
+    val fileName: String
+        get() = path.substringAfterLast('/')
+}
@@ -0,0 +1,37 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 已登记的补丁
+ *
+ * @property id 补丁 id
+ * @property address 补丁地址
+ * @property original 原始字节
+ * @property patched 补丁字节
+ * @property forced 通过物理内存写入只读页
+ * @property enabled 当前内存中是否为补丁字节
+ */
+data class PatchInfo(
+    val id: Int,
+    val address: Long,
+    val original: ByteArray,
+    val patched: ByteArray,
+    val forced: Boolean,
+    val enabled: Boolean
+) {
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is PatchInfo) return false
+        return id == other.id && address == other.address && original.contentEquals(other.original) &&
+            patched.contentEquals(other.patched) && forced == other.forced && enabled == other.enabled
+    }
+
+    override fun hashCode(): Int {
+        var result = id
+        result = 31 * result + address.hashCode()
+        result = 31 * result + original.contentHashCode()
+        result = 31 * result + patched.contentHashCode()
+        result = 31 * result + forced.hashCode()
+        result = 31 * result + enabled.hashCode()
+        return result
+    }
+}
@@ -0,0 +1,161 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+import java.nio.ByteBuffer
+import java.nio.ByteOrder
+
+/**
+ * 字节补丁管理
+ *
+ * 记录每处补丁的原始字节，可单个或全部恢复。解绑进程后记录自动清空。
+ * 代码补丁有独立 id，可以在补丁与原始字节之间切换。
+ */
+object PatchManager {
+
+    /** arm64 nop 指令 */
+    const val ARM64_NOP = 0xD503201F.toInt()
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 查找所有特征码匹配处并替换
+     *
+     * 特征码与替换内容均为十六进制字节，`??` 为通配；替换内容中的通配字节保留原值，长度不能超过特征码。
+     *
+     * @param ranges 搜索的内存区域
+     * @param limit 最多替换数量，0 表示不限
+     * @param force 通过物理内存写入只读页（如代码段）
+     * @return 已修改的地址
+     */
+    fun aobReplace(
+        pattern: String,
+        replacement: String,
+        ranges: Set<MemoryRange>,
+        limit: Int = 0,
+        force: Boolean = false
+    ): LongArray {
+        return nativeAobReplace(pattern, replacement, MemoryRange.toMask(ranges), limit, force)
+    }
+
+    /**
+     * 在指定地址写入补丁
+     */
+    fun apply(address: Long, bytes: ByteArray, force: Boolean = false): Boolean {
+        return nativeApply(address, bytes, force)
+    }
+
+    /**
+     * 恢复单个补丁的原始字节
+     */
+    fun restore(address: Long): Boolean {
+        return nativeRestore(address)
+    }
+
+    /**
+     * 恢复所有补丁
+     *
+     * @return 恢复成功的数量
+     */
+    fun restoreAll(): Int {
+        return nativeRestoreAll()
+    }
+
+    /**
+     * 写入代码补丁
+     *
+     * 地址与长度须按 4 字节对齐，总是通过物理内存写入代码段。
+     *
+     * @return 补丁 id
+     */
+    fun applyPatch(address: Long, bytes: ByteArray): Int {
+        return nativeApplyPatch(address, bytes)
+    }
+
+    /**
+     * 汇编并写入代码补丁，例如 `mov w0, #1; ret`
+     *
+     * 支持的指令见 [Disassembler.assembleARM64]。
+     *
+     * @return 补丁 id
+     */
+    fun applyAsm(address: Long, source: String): Int {
+        return nativeApplyAsm(address, source)
+    }
+
+    /**
+     * 把 [count] 条指令替换为 nop
+     *
+     * @return 补丁 id
+     */
+    fun nopOut(address: Long, count: Int = 1): Int {
+        val bytes = ByteBuffer.allocate(count * 4).order(ByteOrder.LITTLE_ENDIAN)
+        repeat(count) { bytes.putInt(ARM64_NOP) }
+        return applyPatch(address, bytes.array())
+    }
+
+    /**
+     * 把 [address] 处的指令改为跳转到 [target] 的 `b` 指令（±128MB 内）
+     *
+     * @return 补丁 id
+     */
+    fun redirectBranch(address: Long, target: Long): Int {
+        return applyPatch(address, nativeEncodeBranch(address, target))
+    }
+
+    /**
+     * 替换指令中的立即数，支持 movz/movn/movk 与 add/sub/cmp 立即数形式
+     *
+     * @param instruction 原指令
+     * @return 新指令
+     */
+    fun encodeImmediate(instruction: Int, value: Int): Int {
+        return nativeEncodeImmediate(instruction, value)
+    }
+
+    /**
+     * 按 id 恢复原始字节并移除记录
+     *
+     * @return 补丁不存在时返回 false
+     */
+    fun revertPatch(id: Int): Boolean {
+        return nativeRevertPatch(id)
+    }
+
+    /**
+     * 在补丁字节与原始字节之间切换，记录保留
+     */
+    fun setPatchEnabled(id: Int, enabled: Boolean): Boolean {
+        return nativeSetPatchEnabled(id, enabled)
+    }
+
+    /**
+     * 全部补丁，按地址排序
+     */
+    fun listPatches(): Array<PatchInfo> {
+        return nativeListPatches()
+    }
+
+    /**
+     * 已打补丁的地址
+     */
+    fun getPatchedAddresses(): LongArray {
+        return nativeGetPatchedAddresses()
+    }
+
+    private external fun nativeAobReplace(pattern: String, replacement: String, regionMask: Long, limit: Int, force: Boolean): LongArray
+    private external fun nativeApply(address: Long, bytes: ByteArray, force: Boolean): Boolean
+    private external fun nativeRestore(address: Long): Boolean
+    private external fun nativeRestoreAll(): Int
+    private external fun nativeGetPatchedAddresses(): LongArray
+    private external fun nativeApplyPatch(address: Long, bytes: ByteArray): Int
+    private external fun nativeApplyAsm(address: Long, source: String): Int
+    private external fun nativeRevertPatch(id: Int): Boolean
+    private external fun nativeSetPatchEnabled(id: Int, enabled: Boolean): Boolean
+    private external fun nativeListPatches(): Array<PatchInfo>
+    private external fun nativeEncodeBranch(from: Long, to: Long): ByteArray
+    private external fun nativeEncodeImmediate(instruction: Int, value: Int): Int
+}
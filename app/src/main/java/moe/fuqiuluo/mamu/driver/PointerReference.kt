@@ -0,0 +1,15 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * One entry of the pointer library: the slot at [address] held [value] when it was scanned.
+ *
+ * @property address Address of the pointer slot
+ * @property value Address the slot points to
+ */
+data class PointerReference(
+    val address: Long,
+    val value: Long
+) {
+    /** Distance from [value] to [target], as used in a chain offset. */
+    fun offsetTo(target: Long): Long = target - value
+}
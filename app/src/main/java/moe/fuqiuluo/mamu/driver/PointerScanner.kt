@@ -2,6 +2,7 @@
 
 package moe.fuqiuluo.mamu.driver
 
+import moe.fuqiuluo.mamu.driver.proto.ChainList
 import java.nio.ByteBuffer
 import java.nio.ByteOrder
 
@@ -88,6 +89,7 @@ object PointerScanner {
         const val HEARTBEAT = 32
         const val CANCEL_FLAG = 36
         const val ERROR_CODE = 40
+        const val CANDIDATES_DROPPED = 44
     }
 
     private var sharedBuffer: ByteBuffer? = null
@@ -149,6 +151,11 @@ object PointerScanner {
      */
     fun getCurrentDepth(): Int = sharedBuffer?.getInt(Offset.CURRENT_DEPTH) ?: 0
 
+    /**
+     * Reads how many chain-building candidates were pruned so far.
+     */
+    fun getCandidatesDropped(): Int = sharedBuffer?.getInt(Offset.CANDIDATES_DROPPED) ?: 0
+
     /**
      * Reads heartbeat value from shared buffer.
      */
@@ -203,8 +210,11 @@ object PointerScanner {
      * @param targetAddress The address to find pointer chains to.
      * @param maxDepth Maximum depth of pointer chain (default: 5).
      * @param maxOffset Maximum offset per level in bytes (default: 0x1000).
-     * @param align Pointer alignment in bytes (default: 4).
+     * @param align Pointer alignment in bytes: 1, 2, 4 or 8 (default: 4). Use 1 for pointers
+     *   inside packed structures; Phase 1 then checks 4x the positions and takes about 4x as long.
      * @param regions Memory regions to scan as list of (start, end, name, isStatic).
+     * @param background Run at low priority (idle scheduling, throttled reads) so the game
+     *   keeps its frame rate while the scan runs; the scan takes longer.
      * @return Whether the scan started successfully.
      */
     fun startScan(
@@ -213,7 +223,8 @@ object PointerScanner {
         maxOffset: Int = 0x1000,
         align: Int = 4,
         regions: List<MemoryRegionInfo>,
-        isLayerBFS: Boolean
+        isLayerBFS: Boolean,
+        background: Boolean = false
     ): Boolean {
         if (!isInitialized) {
             return false
@@ -242,7 +253,8 @@ object PointerScanner {
             regionAddresses,
             regionNames,
             staticFlags,
-            isLayerBFS
+            isLayerBFS,
+            background
         )
     }
 
@@ -261,6 +273,13 @@ object PointerScanner {
         return nativeGetChains(start, count)
     }
 
+    /**
+     * Get a range of chain results decoded from a protobuf buffer, including every step of each chain.
+     */
+    fun getChainsProto(start: Int, count: Int): ChainList {
+        return ChainList.parseFrom(nativeGetChainsProto(start, count))
+    }
+
     /**
      * Clear all scan results and reset state.
      */
@@ -269,6 +288,364 @@ object PointerScanner {
         resetSharedBuffer()
     }
 
+    /**
+     * Re-resolve chains against fresh module bases in the bound process,
+     * e.g. after the game was restarted.
+     * @param modules Static modules of the current process (same form as passed to [startScan]).
+     * @param start Index of the first chain.
+     * @param count Number of chains to validate.
+     * @return Validation result for each chain in the range.
+     */
+    fun validateChains(modules: List<MemoryRegionInfo>, start: Int, count: Int): Array<ChainValidationResult> {
+        val (addresses, names) = packModules(modules)
+        return nativeValidateChains(addresses, names, start, count)
+    }
+
+    /**
+     * Remove every chain that no longer resolves in the bound process.
+     * @param modules Static modules of the current process.
+     * @return Number of chains left.
+     */
+    fun removeInvalidChains(modules: List<MemoryRegionInfo>): Int {
+        val (addresses, names) = packModules(modules)
+        return nativeRemoveInvalidChains(addresses, names)
+    }
+
+    /**
+     * Find the static data segments (.data, .bss, .got, ...) of every ELF image loaded in
+     * the bound process, from its memory maps and program headers. ART boot images
+     * contribute their writable mappings.
+     *
+     * Use the result as the static regions of [startScan] instead of whole file mappings,
+     * so chain roots only land in memory that can actually hold pointers.
+     * @return Static regions, with [MemoryRegionInfo.isStatic] set.
+     */
+    fun discoverStaticModules(): Array<MemoryRegionInfo> = nativeDiscoverStaticModules()
+
+    private fun packModules(modules: List<MemoryRegionInfo>): Pair<LongArray, Array<String>> {
+        val staticModules = modules.filter { it.isStatic }
+        val addresses = LongArray(staticModules.size * 2)
+        staticModules.forEachIndexed { index, module ->
+            addresses[index * 2] = module.start
+            addresses[index * 2 + 1] = module.end
+        }
+        return addresses to Array(staticModules.size) { staticModules[it].name }
+    }
+
+    /**
+     * Seed chain building with the accesses a watchpoint on the target caught.
+     *
+     * Arm a read or access watch on [targetAddress] with [WatchManager] and let the game run
+     * first. The base register of each accessing instruction (e.g. x19 for `ldr w0, [x19, #0x48]`)
+     * gives one last-level offset; scans and chain builds for the same target then only follow
+     * those offsets at the first level, yielding far fewer but real chains.
+     *
+     * @param targetAddress Address the watch was armed on.
+     * @param watchId Watch id returned by [WatchManager.arm].
+     * @return Number of distinct offsets seen.
+     */
+    fun seedFromWatch(targetAddress: Long, watchId: Int): Int = nativeSeedFromWatch(targetAddress, watchId)
+
+    /**
+     * Drop the seed set by [seedFromWatch].
+     */
+    fun clearAccessSeed() = nativeClearAccessSeed()
+
+    /**
+     * Keep only chains whose last pointer value matches a base register seen by [seedFromWatch]
+     * for the current target. Chains without a dereference cannot be checked and are kept.
+     *
+     * @return Number of chains left.
+     */
+    fun filterChainsByAccess(): Int = nativeFilterChainsByAccess()
+
+    /**
+     * Save the current chain results to a file.
+     * @param path Destination file path.
+     * @return Number of chains written.
+     */
+    fun saveChains(path: String): Int = nativeSaveChains(path)
+
+    /**
+     * Load chain results from a file saved by [saveChains], replacing the current results.
+     * @param path Source file path.
+     * @return Number of chains loaded.
+     */
+    fun loadChains(path: String): Int = nativeLoadChains(path)
+
+    /**
+     * Save the pointer library of the last scan, so later chain-building runs
+     * on the same process can skip the memory scan.
+     * @param path Destination file path.
+     * @return Number of pointers saved.
+     */
+    fun savePointerLibrary(path: String): Long = nativeSavePointerLibrary(path)
+
+    /**
+     * Export the pointers whose address or value lies in the given ranges to a standalone
+     * library file that [loadPointerLibrary] can read.
+     * @param path Destination file path.
+     * @param ranges Address ranges, format [start1, end1, start2, end2, ...].
+     * @return Number of pointers exported.
+     */
+    fun exportPointerLibrarySubset(path: String, ranges: LongArray): Long =
+        nativeExportPointerLibrarySubset(path, ranges)
+
+    /** Pointer graph export formats. */
+    object GraphFormat {
+        const val DOT = 0
+        const val JSON = 1
+    }
+
+    /**
+     * Export the pointer graph around a target address for external graph tools.
+     * Nodes are the pointer slots reached walking the pointer library backwards from the
+     * target, edges are the references between them. Static slots are not expanded.
+     * @param path Destination file path.
+     * @param maxDepth Maximum number of references from the target.
+     * @param maxNodes Node limit, the graph is truncated beyond it.
+     * @param format [GraphFormat.DOT] or [GraphFormat.JSON].
+     * @param modules Static modules of the current process, used to label root slots.
+     * @return Number of nodes written.
+     */
+    fun exportPointerGraph(
+        path: String,
+        targetAddress: Long,
+        maxDepth: Int = 3,
+        maxOffset: Int = 0x1000,
+        maxNodes: Int = 500,
+        format: Int = GraphFormat.DOT,
+        modules: List<MemoryRegionInfo>
+    ): Int {
+        val (addresses, names) = packModules(modules)
+        return nativeExportPointerGraph(path, targetAddress, maxDepth, maxOffset, maxNodes, format, addresses, names)
+    }
+
+    /**
+     * Export the pointers whose address or value lies within a module's mappings,
+     * for sharing a compact per-module pointer map.
+     * @param path Destination file path.
+     * @param moduleName Module file name (e.g. "libil2cpp.so") or full path.
+     * @return Number of pointers exported.
+     */
+    fun exportPointerLibraryForModule(path: String, moduleName: String): Long {
+        val ranges = WuwaDriver.queryMemRegions()
+            .filter { it.name == moduleName || it.name.endsWith("/$moduleName") }
+            .flatMap { listOf(it.start, it.end) }
+            .toLongArray()
+        require(ranges.isNotEmpty()) { "Module not found: $moduleName" }
+        return exportPointerLibrarySubset(path, ranges)
+    }
+
+    /**
+     * Load a pointer library saved by [savePointerLibrary], replacing the current one.
+     * The library is only meaningful for the process instance it was scanned from.
+     * @param path Source file path.
+     * @return Number of pointers loaded.
+     */
+    fun loadPointerLibrary(path: String): Long = nativeLoadPointerLibrary(path)
+
+    /**
+     * Find the slots in the pointer library that point at or near [target].
+     * Requires a pointer library from the last scan or [loadPointerLibrary].
+     * @param target Address being referenced.
+     * @param maxOffset Largest distance below [target] a pointer may point to.
+     * @param maxBackwardOffset Largest distance above [target] a pointer may point to.
+     * @param limit Maximum number of results.
+     * @return References ordered by pointer value.
+     */
+    fun findReferencesTo(
+        target: Long,
+        maxOffset: Int = 0x1000,
+        maxBackwardOffset: Int = 0,
+        limit: Int = 1000
+    ): List<PointerReference> = toReferences(nativeFindReferencesTo(target, maxOffset, maxBackwardOffset, limit))
+
+    /**
+     * Find the pointers the library recorded inside `[address, address + span)`.
+     * This is a linear pass over the library and may take a moment on large ones.
+     * @param address Start of the block.
+     * @param span Size of the block in bytes.
+     * @param limit Maximum number of results.
+     * @return Pointers ordered by slot address.
+     */
+    fun findPointersIn(address: Long, span: Long = 0x400, limit: Int = 1000): List<PointerReference> =
+        toReferences(nativeFindPointersIn(address, span, limit))
+
+    private fun toReferences(pairs: LongArray): List<PointerReference> =
+        List(pairs.size / 2) { PointerReference(pairs[it * 2], pairs[it * 2 + 1]) }
+
+    /**
+     * Build pointer chains from the current pointer library without rescanning memory.
+     * Progress is reported through the shared buffer like [startScan].
+     *
+     * @param targetAddress The address to find pointer chains to.
+     * @param maxDepth Maximum depth of pointer chain.
+     * @param maxOffset Maximum offset per level in bytes.
+     * @param modules Static modules of the current process (same form as passed to [startScan]).
+     * @param background Build at low priority, see [startScan].
+     * @return Whether chain building started successfully.
+     */
+    fun startChainBuild(
+        targetAddress: Long,
+        maxDepth: Int = 5,
+        maxOffset: Int = 0x1000,
+        modules: List<MemoryRegionInfo>,
+        isLayerBFS: Boolean,
+        background: Boolean = false
+    ): Boolean {
+        if (!isInitialized) {
+            return false
+        }
+
+        val (addresses, names) = packModules(modules)
+        resetSharedBuffer()
+        clearCancelFlag()
+
+        return nativeStartChainBuild(targetAddress, maxDepth, maxOffset, addresses, names, isLayerBFS, background)
+    }
+
+    /**
+     * Drop the first levels of a chain and search new static paths to the slot they reached,
+     * reusing the current pointer library. The kept levels are appended to every chain found,
+     * and the found chains replace the current results.
+     * @param chainIndex Index of the chain in the current results.
+     * @param dropLevels Number of leading levels to drop, at least 1 and less than the chain depth.
+     * @param maxDepth Maximum depth of the new prefix.
+     * @param modules Static modules of the current process.
+     * @param background Build at low priority, see [startScan].
+     * @return Address of the new root slot, or 0 if not initialized.
+     */
+    fun startReroot(
+        chainIndex: Int,
+        dropLevels: Int,
+        maxDepth: Int = 5,
+        maxOffset: Int = 0x1000,
+        modules: List<MemoryRegionInfo>,
+        isLayerBFS: Boolean,
+        background: Boolean = false
+    ): Long {
+        if (!isInitialized) {
+            return 0
+        }
+
+        val (addresses, names) = packModules(modules)
+        resetSharedBuffer()
+        clearCancelFlag()
+
+        return nativeStartReroot(chainIndex, dropLevels, maxDepth, maxOffset, addresses, names, isLayerBFS, background)
+    }
+
+    /**
+     * Intersect the current results with a chain file saved from an earlier scan of the
+     * same target (typically before a game restart). Only chains whose module+offset
+     * path appears in both are kept.
+     * @param path Chain file saved by [saveChains].
+     * @return Number of chains left.
+     */
+    fun intersectWithFile(path: String): Int = nativeIntersectWithFile(path)
+
+    /**
+     * Drop chains whose module+offset path repeats an earlier result, keeping the first
+     * occurrence. Shared prefixes are folded in a trie while this runs, so it stays cheap
+     * on large result sets.
+     * @return Number of chains left.
+     */
+    fun deduplicateChains(): Int = nativeDeduplicateChains()
+
+    /**
+     * List mamu-owned files in the cache directory, largest first.
+     */
+    fun listCacheFiles(): Array<CacheFileEntry> = nativeListCacheFiles()
+
+    /**
+     * Delete the given cache files. Files still in use are skipped.
+     * @param names File names as returned by [listCacheFiles].
+     * @return Number of bytes freed.
+     */
+    fun deleteCacheFiles(names: Array<String>): Long = nativeDeleteCacheFiles(names)
+
+    /**
+     * Delete all cache files older than the given age. Files still in use are skipped.
+     * @param maxAgeSeconds Minimum age in seconds of files to delete.
+     * @return Number of bytes freed.
+     */
+    fun pruneCacheFiles(maxAgeSeconds: Long): Long = nativePruneCacheFiles(maxAgeSeconds)
+
+    /**
+     * Shrink the pointer library backing file to its used size.
+     * @return Number of bytes released.
+     */
+    fun compactStorage(): Long = nativeCompactStorage()
+
+    /**
+     * Tune memory usage and parallelism of subsequent scans.
+     * @param chunkSize Phase 1 read chunk size in bytes, rounded up to the page size (default 512KB).
+     * @param maxThreads Maximum worker threads, 0 = one per CPU core.
+     * @param batchThreshold Upper bound of pointers buffered before a sorted temp file is flushed
+     * (default 10M, ~160MB, at most 100M). Lowered at runtime when memory is short or storage is slow.
+     * @throws RuntimeException if a scan is in progress or a value is out of range.
+     */
+    fun setPerformanceConfig(
+        chunkSize: Int = 512 * 1024,
+        maxThreads: Int = 0,
+        batchThreshold: Int = 10_000_000
+    ): Boolean = nativeSetPerformanceConfig(chunkSize, maxThreads, batchThreshold)
+
+    /**
+     * Narrow the offsets followed when building chains, for subsequent scans and chain builds.
+     * @param levelMaxOffsets Max offset per level, index 0 = pointers to the target itself.
+     * Deeper levels use the max offset passed to [startScan].
+     * @param offsetWhitelist Known structure offsets; when not empty only these are followed.
+     * Pass empty arrays to go back to a single max offset.
+     * @param maxBackwardOffset Largest negative offset followed, for targets that sit below
+     * the address their pointer holds. Max offsets above only bound forward offsets; leave
+     * this at 0 when the target never sits below its pointer, it keeps the candidate set small.
+     * @throws RuntimeException if a scan is in progress or an offset is negative.
+     */
+    fun setOffsetRules(
+        levelMaxOffsets: IntArray = IntArray(0),
+        offsetWhitelist: IntArray = IntArray(0),
+        maxBackwardOffset: Int = 0
+    ): Boolean = nativeSetOffsetRules(levelMaxOffsets, offsetWhitelist, maxBackwardOffset)
+
+    /**
+     * Phase 1 writer statistics of the last scan as JSON
+     * (chosen batch threshold, available memory, write speed, temp files, timings).
+     * @return JSON string, or null if no scan has finished Phase 1.
+     */
+    fun getLastScanReport(): String? = nativeGetLastScanReport()
+
+    /**
+     * Progress of a scan interrupted before Phase 1 finished (e.g. the app was killed
+     * in the background), as JSON with pid, target, background mode, region and pointer counts.
+     * @return JSON string, or null if there is no scan to resume.
+     */
+    fun getScanCheckpoint(): String? = nativeGetScanCheckpoint()
+
+    /**
+     * Resume the interrupted scan reported by [getScanCheckpoint]. Regions already written
+     * to disk are skipped, and chains are built with the settings of the original scan.
+     * The same process must still be bound.
+     * @return Whether the scan resumed.
+     */
+    fun resumeScan(): Boolean {
+        if (!isInitialized) {
+            return false
+        }
+
+        resetSharedBuffer()
+        clearCancelFlag()
+
+        return nativeResumeScan()
+    }
+
+    /**
+     * Delete the interrupted scan checkpoint and its temp files.
+     * @return Whether there was one.
+     */
+    fun discardScanCheckpoint(): Boolean = nativeDiscardScanCheckpoint()
+
     /**
      * Get phase as human-readable string.
      */
@@ -308,14 +685,75 @@ object PointerScanner {
         regions: LongArray,
         regionNames: Array<String>,
         staticFlags: BooleanArray,
-        isLayerBFS: Boolean
+        isLayerBFS: Boolean,
+        background: Boolean
     ): Boolean
     private external fun nativeIsScanning(): Boolean
     private external fun nativeRequestCancel()
     private external fun nativeGetChainCount(): Long
     private external fun nativeGetChains(start: Int, count: Int): Array<PointerChainResult>
+    private external fun nativeGetChainsProto(start: Int, count: Int): ByteArray
     private external fun nativeClear()
     private external fun nativeGetPhase(): Int
+    private external fun nativeListCacheFiles(): Array<CacheFileEntry>
+    private external fun nativeDeleteCacheFiles(names: Array<String>): Long
+    private external fun nativePruneCacheFiles(maxAgeSeconds: Long): Long
+    private external fun nativeCompactStorage(): Long
+    private external fun nativeSetPerformanceConfig(chunkSize: Int, maxThreads: Int, batchThreshold: Int): Boolean
+    private external fun nativeSetOffsetRules(levelMaxOffsets: IntArray, offsetWhitelist: IntArray, maxBackwardOffset: Int): Boolean
+    private external fun nativeGetLastScanReport(): String?
+    private external fun nativeGetScanCheckpoint(): String?
+    private external fun nativeResumeScan(): Boolean
+    private external fun nativeDiscardScanCheckpoint(): Boolean
+    private external fun nativeSaveChains(path: String): Int
+    private external fun nativeLoadChains(path: String): Int
+    private external fun nativeIntersectWithFile(path: String): Int
+    private external fun nativeDiscoverStaticModules(): Array<MemoryRegionInfo>
+    private external fun nativeDeduplicateChains(): Int
+    private external fun nativeSavePointerLibrary(path: String): Long
+    private external fun nativeLoadPointerLibrary(path: String): Long
+    private external fun nativeFindReferencesTo(target: Long, maxOffset: Int, maxBackwardOffset: Int, limit: Int): LongArray
+    private external fun nativeFindPointersIn(address: Long, span: Long, limit: Int): LongArray
+    private external fun nativeExportPointerLibrarySubset(path: String, ranges: LongArray): Long
+    private external fun nativeExportPointerGraph(
+        path: String,
+        targetAddress: Long,
+        maxDepth: Int,
+        maxOffset: Int,
+        maxNodes: Int,
+        format: Int,
+        modules: LongArray,
+        moduleNames: Array<String>
+    ): Int
+    private external fun nativeStartChainBuild(
+        targetAddress: Long,
+        maxDepth: Int,
+        maxOffset: Int,
+        modules: LongArray,
+        moduleNames: Array<String>,
+        isLayerBFS: Boolean,
+        background: Boolean
+    ): Boolean
+    private external fun nativeStartReroot(
+        chainIndex: Int,
+        dropLevels: Int,
+        maxDepth: Int,
+        maxOffset: Int,
+        modules: LongArray,
+        moduleNames: Array<String>,
+        isLayerBFS: Boolean,
+        background: Boolean
+    ): Long
+    private external fun nativeSeedFromWatch(targetAddress: Long, watchId: Int): Int
+    private external fun nativeClearAccessSeed()
+    private external fun nativeFilterChainsByAccess(): Int
+    private external fun nativeValidateChains(
+        modules: LongArray,
+        moduleNames: Array<String>,
+        start: Int,
+        count: Int
+    ): Array<ChainValidationResult>
+    private external fun nativeRemoveInvalidChains(modules: LongArray, moduleNames: Array<String>): Int
 }
 
 /**
@@ -5,65 +5,41 @@ import kotlinx.coroutines.*
 
 private const val TAG = "ProcessDeathMonitor"
 
+/**
+ * 进程死亡监听，检测由 native 侧完成（进程退出时已自动释放绑定），这里只把回调转到主线程
+ */
 object ProcessDeathMonitor {
     interface Callback {
         fun onProcessDied(pid: Int)
     }
 
-    private val scope = CoroutineScope(Dispatchers.Default + SupervisorJob())
-    private var monitorJob: Job? = null
+    private val scope = CoroutineScope(Dispatchers.Main + SupervisorJob())
+    private var callback: Callback? = null
     private var currentPid: Int = 0
     private val lock = Any()
 
-    fun start(pid: Int, callback: Callback, intervalMs: Long = 1000) {
-        synchronized(lock) {
-            // 取消旧的监听任务（非阻塞，立即返回）
-            monitorJob?.cancel()
+    private val exitCallback = object : ProcessExitCallback {
+        override fun onProcessExit(pid: Int, wasCurrent: Boolean) {
+            val target = synchronized(lock) {
+                if (pid != currentPid) return
+                currentPid = 0
+                callback.also { callback = null }
+            } ?: return
+
+            Log.i(TAG, "Process $pid died, invoking callback")
+            scope.launch {
+                target.onProcessDied(pid)
+            }
+        }
+    }
 
+    fun start(pid: Int, callback: Callback) {
+        synchronized(lock) {
             currentPid = pid
-
-            // 启动新的协程监听任务
-            monitorJob = scope.launch {
-                Log.d(TAG, "Process death monitor started for PID: $pid with interval: ${intervalMs}ms")
-
-                try {
-                    while (isActive) {
-                        val alive = try {
-                            WuwaDriver.isProcessAlive(pid)
-                        } catch (e: Exception) {
-                            Log.e(TAG, "Error checking process alive status", e)
-                            false
-                        }
-
-                        if (!alive) {
-                            Log.i(TAG, "Process $pid died, invoking callback")
-
-                            // 在主线程回调
-                            withContext(Dispatchers.Main) {
-                                callback.onProcessDied(pid)
-                            }
-
-                            synchronized(lock) {
-                                currentPid = 0
-                            }
-                            break
-                        }
-
-                        delay(intervalMs.coerceAtLeast(100))
-                    }
-                } catch (e: CancellationException) {
-                    Log.d(TAG, "Process death monitor cancelled for PID: $pid")
-                    throw e
-                } catch (e: Exception) {
-                    Log.e(TAG, "Unexpected error in process death monitor", e)
-                } finally {
-                    synchronized(lock) {
-                        currentPid = 0
-                    }
-                    Log.d(TAG, "Process death monitor stopped for PID: $pid")
-                }
-            }
+            this.callback = callback
         }
+        WuwaDriver.setProcessExitCallback(exitCallback)
+        Log.d(TAG, "Process death monitor started for PID: $pid")
     }
 
     fun stop() {
@@ -74,12 +50,8 @@ object ProcessDeathMonitor {
                 return
             }
 
-            Log.i(TAG, "Stopping process death monitor for PID: $pid")
-
-            // 取消协程（非阻塞，立即返回）
-            monitorJob?.cancel()
-            monitorJob = null
             currentPid = 0
+            callback = null
 
             Log.i(TAG, "Stopped process death monitor for PID: $pid")
         }
@@ -87,11 +59,11 @@ object ProcessDeathMonitor {
 
     val isMonitoring: Boolean
         get() = synchronized(lock) {
-            monitorJob?.isActive == true
+            currentPid != 0
         }
 
     val monitoredPid: Int
         get() = synchronized(lock) {
-            if (monitorJob?.isActive == true) currentPid else 0
+            currentPid
         }
 }
@@ -0,0 +1,10 @@
+package moe.fuqiuluo.mamu.driver
+
+interface ProcessExitCallback {
+    /**
+     * 绑定的进程已退出，native 侧已释放绑定。在后台线程调用
+     * @param pid 退出的进程
+     * @param wasCurrent 是否为当前绑定的进程，false 表示附加的进程
+     */
+    fun onProcessExit(pid: Int, wasCurrent: Boolean)
+}
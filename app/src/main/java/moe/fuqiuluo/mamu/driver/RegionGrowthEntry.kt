@@ -0,0 +1,34 @@
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+
+/**
+ * 内存区域增长记录
+ *
+ * @property start 区域起始地址
+ * @property end 当前结束地址
+ * @property name 区域名称
+ * @property rangeOrdinal 区域类型（MemoryRange 的 ordinal），未分类时为 -1
+ * @property firstSize 首次采集时的大小，基准之后新出现的区域为 0
+ * @property growth 相对首次采集增长的字节数
+ * @property bytesPerSec 平均增长速度（字节/秒）
+ */
+data class RegionGrowthEntry(
+    val start: Long,
+    val end: Long,
+    val name: String,
+    val rangeOrdinal: Int,
+    val firstSize: Long,
+    val growth: Long,
+    val bytesPerSec: Double
+) {
+    val range: MemoryRange?
+        get() = MemoryRange.entries.getOrNull(rangeOrdinal)
+
+    val size: Long
+        get() = end - start
+
+    /** 基准之后新出现的区域 */
+    val isNew: Boolean
+        get() = firstSize == 0L
+}
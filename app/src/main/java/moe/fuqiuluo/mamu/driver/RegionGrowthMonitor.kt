@@ -0,0 +1,48 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+
+/**
+ * 内存区域增长监视
+ *
+ * 多次采集绑定进程的内存映射，找出增长最快的区域，
+ * 用于把未知值搜索集中在游戏正在使用的分配器区域上。解绑进程时记录会被清空。
+ */
+object RegionGrowthMonitor {
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 采集一次内存映射，首次采集作为基准
+     *
+     * @return 当前区域数
+     */
+    fun snapshot(): Int = nativeSnapshot()
+
+    /** 清空记录，下次采集作为新的基准 */
+    fun reset() {
+        nativeReset()
+    }
+
+    /** 已采集的次数 */
+    fun getGeneration(): Int = nativeGetGeneration()
+
+    /**
+     * 增长最快的区域，按增长字节数降序
+     *
+     * @param limit 最多返回的数量
+     * @param ranges 只统计这些类型的区域，为空时不过滤
+     */
+    fun getFastestGrowing(limit: Int = 20, ranges: Collection<MemoryRange> = emptyList()): Array<RegionGrowthEntry> {
+        return nativeGetFastestGrowing(limit, MemoryRange.toMask(ranges))
+    }
+
+    private external fun nativeSnapshot(): Int
+    private external fun nativeReset()
+    private external fun nativeGetGeneration(): Int
+    private external fun nativeGetFastestGrowing(limit: Int, rangeMask: Long): Array<RegionGrowthEntry>
+}
@@ -0,0 +1,43 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 远程控制服务
+ *
+ * 在本机 TCP 端口或 Unix 套接字上暴露驱动和搜索接口，供电脑端工具经 `adb forward` 驱动扫描和内存修改。
+ * 每帧为 4 字节小端长度加 JSON，请求为 `{"id", "method", "params"}`，
+ * 方法包括 `ping`、`status`、`process.list`、`regions`、`mem.read`、`mem.write` 和 `search.*`。
+ * 同一时间只运行一个服务。本机任何应用都能连上监听端口，所以必须设置令牌。
+ */
+object RemoteServer {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 在 127.0.0.1 上启动服务
+     * @param token 不能为空，连接的第一个请求必须是 `auth` 并带上该令牌
+     * @throws MamuException 令牌为空、服务已在运行或端口被占用
+     */
+    fun startTcp(port: Int, token: String) = nativeStartTcp(port, token)
+
+    /**
+     * 在 Unix 套接字上启动服务，已存在的文件会被替换，套接字权限为 0600
+     * @param token 不能为空，同 [startTcp]
+     */
+    fun startUnix(path: String, token: String) = nativeStartUnix(path, token)
+
+    /**
+     * 停止服务，已建立的连接随后关闭
+     * @return 没有运行中的服务时返回 false
+     */
+    fun stop(): Boolean = nativeStop()
+
+    fun isRunning(): Boolean = nativeIsRunning()
+
+    private external fun nativeStartTcp(port: Int, token: String)
+    private external fun nativeStartUnix(path: String, token: String)
+    private external fun nativeStop(): Boolean
+    private external fun nativeIsRunning(): Boolean
+}
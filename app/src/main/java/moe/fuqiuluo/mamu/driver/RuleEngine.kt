@@ -0,0 +1,148 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 原生条件规则引擎
+ *
+ * 规则监视一个地址或指针链上的值，条件成立时在原生层执行动作（写入值、修改保存列表条目的冻结、
+ * 运行 Lua 脚本、通知 [RuleListener]），例如血量低于 100 时写回满血，不需要 Java 侧轮询。
+ * 电平条件在成立期间每过冷却时间触发一次，穿越和变化条件只在发生的那次求值触发。
+ */
+object RuleEngine {
+    /** 值等于 operand */
+    const val COND_EQUALS = 0
+    /** 值不等于 operand */
+    const val COND_NOT_EQUALS = 1
+    /** 值低于 threshold */
+    const val COND_BELOW = 2
+    /** 值高于 threshold */
+    const val COND_ABOVE = 3
+    /** 值从不低于 threshold 变为低于 */
+    const val COND_CROSSED_BELOW = 4
+    /** 值从不高于 threshold 变为高于 */
+    const val COND_CROSSED_ABOVE = 5
+    /** 值发生变化 */
+    const val COND_CHANGED = 6
+    /** 目标能解析且可读 */
+    const val COND_RESOLVES = 7
+
+    const val FREEZE = 0
+    const val UNFREEZE = 1
+    const val TOGGLE_FREEZE = 2
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 添加监视固定地址的规则
+     * @param valueType 值类型 id
+     * @param operand [COND_EQUALS] / [COND_NOT_EQUALS] 比较的原始值，长度须与类型一致
+     * @param threshold 阈值条件的阈值
+     * @return 规则 id
+     */
+    fun addRule(
+        address: Long,
+        valueType: Int,
+        condition: Int,
+        operand: ByteArray? = null,
+        threshold: Double = 0.0,
+        intervalMs: Long = 100,
+        cooldownMs: Long = 1000,
+        oneShot: Boolean = false
+    ): Long = nativeAddRule(address, null, false, valueType, condition, operand, threshold, intervalMs, cooldownMs, oneShot)
+
+    /**
+     * 添加监视指针链的规则，链按当前进程定期重新解析
+     * @param chain `libil2cpp.so[0]+0x1A2B3C0->+0x18` 形式
+     */
+    fun addPointerRule(
+        chain: String,
+        dataStart: Boolean,
+        valueType: Int,
+        condition: Int,
+        operand: ByteArray? = null,
+        threshold: Double = 0.0,
+        intervalMs: Long = 100,
+        cooldownMs: Long = 1000,
+        oneShot: Boolean = false
+    ): Long = nativeAddRule(0, chain, dataStart, valueType, condition, operand, threshold, intervalMs, cooldownMs, oneShot)
+
+    /**
+     * 触发时写入值
+     * @param address 写入地址，为 0 时写入规则监视的地址
+     * @return 规则不存在时返回 false
+     */
+    fun addWriteAction(ruleId: Long, value: ByteArray, address: Long = 0): Boolean =
+        nativeAddWriteAction(ruleId, address, null, false, value)
+
+    /**
+     * 触发时写入指针链指向的地址
+     */
+    fun addPointerWriteAction(ruleId: Long, value: ByteArray, chain: String, dataStart: Boolean): Boolean =
+        nativeAddWriteAction(ruleId, 0, chain, dataStart, value)
+
+    /**
+     * 触发时修改 [SavedList] 条目的冻结状态
+     * @param mode [FREEZE]、[UNFREEZE] 或 [TOGGLE_FREEZE]
+     */
+    fun addFreezeAction(ruleId: Long, entryId: Long, mode: Int): Boolean = nativeAddFreezeAction(ruleId, entryId, mode)
+
+    /**
+     * 触发时运行 Lua 脚本，已有脚本在运行时跳过
+     */
+    fun addScriptAction(ruleId: Long, source: String, name: String = "rule"): Boolean =
+        nativeAddScriptAction(ruleId, source, name)
+
+    /**
+     * 触发时通知 [RuleListener]
+     */
+    fun addNotifyAction(ruleId: Long): Boolean = nativeAddNotifyAction(ruleId)
+
+    fun removeRule(ruleId: Long): Boolean = nativeRemoveRule(ruleId)
+
+    fun clear() = nativeClearRules()
+
+    /**
+     * 启用或停用规则，单次规则触发后自动停用
+     */
+    fun setEnabled(ruleId: Long, enabled: Boolean): Boolean = nativeSetRuleEnabled(ruleId, enabled)
+
+    /**
+     * 规则已触发的次数，规则不存在时返回 -1
+     */
+    fun getFireCount(ruleId: Long): Long = nativeGetFireCount(ruleId)
+
+    val ruleIds: LongArray
+        get() = nativeGetRuleIds()
+
+    /**
+     * 设置通知监听器，同一时间只有一个
+     * @param listener 传 null 取消
+     */
+    fun setListener(listener: RuleListener?) = nativeSetListener(listener)
+
+    private external fun nativeAddRule(
+        address: Long,
+        chain: String?,
+        dataStart: Boolean,
+        valueType: Int,
+        condition: Int,
+        operand: ByteArray?,
+        threshold: Double,
+        intervalMs: Long,
+        cooldownMs: Long,
+        oneShot: Boolean
+    ): Long
+    private external fun nativeAddWriteAction(ruleId: Long, address: Long, chain: String?, dataStart: Boolean, value: ByteArray): Boolean
+    private external fun nativeAddFreezeAction(ruleId: Long, entryId: Long, mode: Int): Boolean
+    private external fun nativeAddScriptAction(ruleId: Long, source: String, name: String): Boolean
+    private external fun nativeAddNotifyAction(ruleId: Long): Boolean
+    private external fun nativeRemoveRule(ruleId: Long): Boolean
+    private external fun nativeClearRules()
+    private external fun nativeSetRuleEnabled(ruleId: Long, enabled: Boolean): Boolean
+    private external fun nativeGetFireCount(ruleId: Long): Long
+    private external fun nativeGetRuleIds(): LongArray
+    private external fun nativeSetListener(listener: RuleListener?)
+}
@@ -0,0 +1,12 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 规则触发通知，在原生规则线程中调用，回调中不要同步修改规则
+ */
+interface RuleListener {
+    /**
+     * 带通知动作的规则触发
+     * @param value 触发时读到的小端原始值，目标不可读时为空
+     */
+    fun onRuleFired(ruleId: Long, value: ByteArray)
+}
@@ -0,0 +1,50 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 保存列表中的一个条目
+ *
+ * @property id 列表内唯一的条目 id
+ * @property label 名称
+ * @property address 当前地址，指针链无法解析时为 0
+ * @property pointer 指针链文本，固定地址条目为 null
+ * @property dataStart 链根偏移相对模块第一个段的基址
+ * @property valueType 值类型 id
+ * @property value 冻结时写入的值
+ * @property frozen 是否冻结
+ * @property hotkey 热键 id，0 表示没有
+ */
+data class SavedEntry(
+    val id: Long,
+    val label: String,
+    val address: Long,
+    val pointer: String?,
+    val dataStart: Boolean,
+    val valueType: Int,
+    val value: ByteArray,
+    val frozen: Boolean,
+    val hotkey: Int
+) {
+    val isPointer: Boolean
+        get() = pointer != null
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is SavedEntry) return false
+        return id == other.id && label == other.label && address == other.address &&
+            pointer == other.pointer && dataStart == other.dataStart && valueType == other.valueType &&
+            value.contentEquals(other.value) && frozen == other.frozen && hotkey == other.hotkey
+    }
+
+    override fun hashCode(): Int {
+        var result = id.hashCode()
+        result = 31 * result + label.hashCode()
+        result = 31 * result + address.hashCode()
+        result = 31 * result + (pointer?.hashCode() ?: 0)
+        result = 31 * result + dataStart.hashCode()
+        result = 31 * result + valueType
+        result = 31 * result + value.contentHashCode()
+        result = 31 * result + frozen.hashCode()
+        result = 31 * result + hotkey
+        return result
+    }
+}
@@ -0,0 +1,113 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 保存的地址列表
+ *
+ * 条目可以是固定地址或指针链，冻结的条目交给原生冻结管理器。
+ * 指针链条目在重新绑定进程后调用 [syncFrozen] 即可指向新的地址。
+ */
+object SavedList {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 添加固定地址条目
+     * @return 条目 id
+     */
+    fun addAddress(
+        label: String,
+        address: Long,
+        valueType: Int,
+        value: ByteArray,
+        frozen: Boolean = false,
+        hotkey: Int = 0
+    ): Long = nativeAddAddress(label, address, valueType, value, frozen, hotkey)
+
+    /**
+     * 添加指针链条目
+     * @param chain 指针链文本，格式同 [PointerChainResult.chainString]，如 `libil2cpp.so[0]+0x1234->+0x18`
+     * @return 条目 id
+     * @throws RuntimeException 指针链格式错误
+     */
+    fun addPointer(
+        label: String,
+        chain: String,
+        dataStart: Boolean,
+        valueType: Int,
+        value: ByteArray,
+        frozen: Boolean = false,
+        hotkey: Int = 0
+    ): Long = nativeAddPointer(label, chain, dataStart, valueType, value, frozen, hotkey)
+
+    /**
+     * 删除条目，它的冻结一并撤下
+     */
+    fun remove(id: Long): Boolean = nativeRemove(id)
+
+    fun clear() = nativeClear()
+
+    /**
+     * 修改名称和热键
+     */
+    fun update(id: Long, label: String, hotkey: Int): Boolean = nativeUpdate(id, label, hotkey)
+
+    /**
+     * 冻结或解冻条目
+     * @param value 冻结值，为 null 时沿用条目原有的值
+     */
+    fun setFrozen(id: Long, frozen: Boolean, value: ByteArray? = null): Boolean = nativeSetFrozen(id, frozen, value)
+
+    /**
+     * 全部条目，指针链按当前进程解析
+     */
+    fun entries(): Array<SavedEntry> = nativeGetEntries()
+
+    /**
+     * 保存为 JSON 文件
+     */
+    fun save(path: String) = nativeSave(path)
+
+    /**
+     * 从 JSON 文件加载并替换当前列表，冻结的条目立即生效
+     * @return 条目数
+     */
+    fun load(path: String): Int = nativeLoad(path)
+
+    /**
+     * 导入 Cheat Engine 表（.CT）
+     *
+     * 支持十六进制地址、`"模块"+偏移` 和带偏移的指针记录，分组展开为平铺的条目。
+     * 脚本、字符串、字节数组和符号地址被跳过。
+     * @param replace true 时替换当前列表，否则追加
+     * @return 导入的条目数
+     */
+    fun importCheatTable(path: String, replace: Boolean = false): Int = nativeImportCheatTable(path, replace)
+
+    /**
+     * 导出为 Cheat Engine 表（.CT），XOR 等 CE 没有的类型被跳过
+     * @return 导出的条目数
+     */
+    fun exportCheatTable(path: String): Int = nativeExportCheatTable(path)
+
+    /**
+     * 按当前进程重新解析冻结条目，重新绑定进程后调用
+     * @return 生效的冻结数
+     */
+    fun syncFrozen(): Int = nativeSyncFrozen()
+
+    private external fun nativeAddAddress(label: String, address: Long, valueType: Int, value: ByteArray, frozen: Boolean, hotkey: Int): Long
+    private external fun nativeAddPointer(label: String, chain: String, dataStart: Boolean, valueType: Int, value: ByteArray, frozen: Boolean, hotkey: Int): Long
+    private external fun nativeRemove(id: Long): Boolean
+    private external fun nativeClear()
+    private external fun nativeUpdate(id: Long, label: String, hotkey: Int): Boolean
+    private external fun nativeSetFrozen(id: Long, frozen: Boolean, value: ByteArray?): Boolean
+    private external fun nativeGetEntries(): Array<SavedEntry>
+    private external fun nativeSave(path: String)
+    private external fun nativeLoad(path: String): Int
+    private external fun nativeSyncFrozen(): Int
+    private external fun nativeImportCheatTable(path: String, replace: Boolean): Int
+    private external fun nativeExportCheatTable(path: String): Int
+}
@@ -0,0 +1,38 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.driver.proto.ScanEventBatch
+
+/**
+ * 扫描事件推送
+ *
+ * 搜索和指针扫描写共享缓冲区的同时把进度、完成和错误推送给 [ScanListener]，
+ * UI 不用再定时读取共享缓冲区。
+ */
+object ScanEvents {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 设置监听器，同一时间只有一个
+     * @param listener 传 null 取消
+     */
+    fun setListener(listener: ScanListener?) = nativeSetScanListener(listener)
+
+    /**
+     * 改为把事件缓存在原生队列中，由 [drainEvents] 批量取出，替换已设置的监听器
+     * @param capacity 队列容量，满时丢弃最早的事件；不大于 0 时关闭队列
+     */
+    fun setEventQueue(capacity: Int) = nativeSetEventQueue(capacity)
+
+    /**
+     * 取出队列中的全部事件
+     */
+    fun drainEvents(): ScanEventBatch = ScanEventBatch.parseFrom(nativeDrainEvents())
+
+    private external fun nativeSetScanListener(listener: ScanListener?)
+    private external fun nativeSetEventQueue(capacity: Int)
+    private external fun nativeDrainEvents(): ByteArray
+}
@@ -0,0 +1,32 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 搜索和指针扫描的事件推送，在单独的分发线程中按发生顺序调用，回调中可以读取结果
+ */
+interface ScanListener {
+    companion object {
+        const val KIND_SEARCH = 0
+        const val KIND_POINTER_SCAN = 1
+    }
+
+    /**
+     * 进度变化，同一次扫描中每个百分比只回调一次
+     * @param kind [KIND_SEARCH] 或 [KIND_POINTER_SCAN]
+     * @param progress 0-100
+     * @param found 目前找到的结果数（指针扫描为指针数或链数）
+     */
+    fun onProgress(kind: Int, progress: Int, found: Long)
+
+    /**
+     * 扫描结束
+     * @param found 结果数
+     * @param cancelled 是否被取消
+     */
+    fun onFinished(kind: Int, found: Long, cancelled: Boolean)
+
+    /**
+     * 扫描出错
+     * @param errorCode 搜索为 SearchErrorCode，指针扫描为 ScanErrorCode
+     */
+    fun onError(kind: Int, errorCode: Int)
+}
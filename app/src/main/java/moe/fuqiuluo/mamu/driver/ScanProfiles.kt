@@ -0,0 +1,93 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+import org.json.JSONArray
+import org.json.JSONObject
+
+/**
+ * 按包名保存的扫描配置
+ *
+ * 绑定进程时 native 层按包名自动加载配置，通过 [getActiveProfile] 读取。
+ * 同一包的子进程（`pkg:remote`）共用主进程的配置。
+ */
+object ScanProfiles {
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 扫描配置
+     *
+     * @property packageName 包名
+     * @property regionMask 区域类型掩码，见 [MemoryRange.toMask]
+     * @property valueTypes 常用值类型（ValueType 的 nativeId）
+     * @property alignment 搜索和指针扫描的对齐字节数
+     * @property pointerMask 指针有效位掩码，用于去掉 TBI/MTE 标签
+     */
+    data class ScanProfile(
+        val packageName: String,
+        val regionMask: Long,
+        val valueTypes: List<Int> = emptyList(),
+        val alignment: Int = 4,
+        val pointerMask: Long = 0x0000_FFFF_FFFF_FFFFL
+    ) {
+        val ranges: Set<MemoryRange>
+            get() = MemoryRange.fromMask(regionMask)
+
+        internal fun toJson(): String = JSONObject()
+            .put("package", packageName)
+            .put("region_mask", regionMask)
+            .put("value_types", JSONArray(valueTypes))
+            .put("alignment", alignment)
+            .put("pointer_mask", pointerMask)
+            .toString()
+
+        companion object {
+            internal fun fromJson(json: String): ScanProfile {
+                val obj = JSONObject(json)
+                val types = obj.getJSONArray("value_types")
+                return ScanProfile(
+                    packageName = obj.getString("package"),
+                    regionMask = obj.getLong("region_mask"),
+                    valueTypes = List(types.length()) { types.getInt(it) },
+                    alignment = obj.getInt("alignment"),
+                    pointerMask = obj.getLong("pointer_mask")
+                )
+            }
+        }
+    }
+
+    /**
+     * 设置配置目录，应位于不会被清理的 files 目录下
+     */
+    fun init(dir: String): Boolean = nativeInit(dir)
+
+    /**
+     * 保存配置，若该包正在绑定则立即生效
+     */
+    fun save(profile: ScanProfile): Boolean = nativeSave(profile.toJson())
+
+    fun load(packageName: String): ScanProfile? = nativeLoad(packageName)?.let(ScanProfile::fromJson)
+
+    fun delete(packageName: String): Boolean = nativeDelete(packageName)
+
+    /**
+     * 已保存配置的包名
+     */
+    fun list(): Array<String> = nativeList()
+
+    /**
+     * 当前绑定进程的配置，没有时返回 null
+     */
+    fun getActiveProfile(): ScanProfile? = nativeGetActive()?.let(ScanProfile::fromJson)
+
+    private external fun nativeInit(dir: String): Boolean
+    private external fun nativeSave(json: String): Boolean
+    private external fun nativeLoad(packageName: String): String?
+    private external fun nativeDelete(packageName: String): Boolean
+    private external fun nativeList(): Array<String>
+    private external fun nativeGetActive(): String?
+}
@@ -0,0 +1,50 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 原生 Lua 5.4 脚本引擎
+ *
+ * 脚本通过全局表 `mamu` 读写内存、搜索、查询区域、解析指针链和冻结，
+ * `print` 的输出交给 [ScriptOutputListener]。同一时间只运行一个脚本，
+ * 运行时登记为 [Jobs.KIND_SCRIPT] 任务，也可以用 [Jobs.cancel] 停止。
+ */
+object ScriptEngine {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 在后台运行脚本
+     * @param name 出错信息中显示的脚本名
+     * @return 任务 id
+     * @throws MamuException 已有脚本在运行
+     */
+    fun run(source: String, name: String = "script"): Int = nativeRunScript(source, name)
+
+    /**
+     * 加载并在后台运行脚本文件
+     * @return 任务 id
+     */
+    fun runFile(path: String): Int = nativeRunScriptFile(path)
+
+    /**
+     * 请求停止当前脚本，脚本在下一个检查点退出
+     * @return 没有运行中的脚本时返回 false
+     */
+    fun stop(): Boolean = nativeStopScript()
+
+    fun isRunning(): Boolean = nativeIsScriptRunning()
+
+    /**
+     * 设置输出监听器，同一时间只有一个
+     * @param listener 传 null 取消，此时输出写入日志
+     */
+    fun setOutputListener(listener: ScriptOutputListener?) = nativeSetOutputListener(listener)
+
+    private external fun nativeRunScript(source: String, name: String): Int
+    private external fun nativeRunScriptFile(path: String): Int
+    private external fun nativeStopScript(): Boolean
+    private external fun nativeIsScriptRunning(): Boolean
+    private external fun nativeSetOutputListener(listener: ScriptOutputListener?)
+}
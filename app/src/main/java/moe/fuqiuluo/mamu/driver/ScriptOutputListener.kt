@@ -0,0 +1,18 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Lua 脚本的输出，在脚本线程中调用
+ */
+interface ScriptOutputListener {
+    /**
+     * 脚本 `print` 输出的一行
+     */
+    fun onPrint(line: String)
+
+    /**
+     * 脚本结束
+     * @param error 出错信息，正常结束或被停止时为 null
+     * @param stopped 是否被 [ScriptEngine.stop] 停止
+     */
+    fun onFinished(error: String?, stopped: Boolean)
+}
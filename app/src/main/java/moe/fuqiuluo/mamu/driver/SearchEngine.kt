@@ -3,6 +3,7 @@
 package moe.fuqiuluo.mamu.driver
 
 import android.util.Log
+import moe.fuqiuluo.mamu.driver.proto.SearchResultPage as ProtoSearchResultPage
 import moe.fuqiuluo.mamu.floating.ext.divideToSimpleMemoryRange
 import moe.fuqiuluo.mamu.floating.data.model.DisplayValueType
 import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
@@ -25,6 +26,51 @@ object SearchEngine {
      */
     const val SHARED_BUFFER_SIZE = 32
 
+    /** Maximum near-by search distance in bytes, mirrors the native limit. */
+    const val MAX_NEARBY_DISTANCE = 64L * 1024
+
+    /**
+     * Obfuscated value transforms, combined as a bit mask.
+     * Values are decoded as little-endian Int32.
+     */
+    object Obfuscation {
+        /** Stored value XOR the 4 bytes before it. */
+        const val XOR_PREV = 1 shl 0
+        /** Stored value XOR the 4 bytes after it. */
+        const val XOR_NEXT = 1 shl 1
+        /** Stored value XOR the 4 bytes 8 bytes before it. */
+        const val XOR_PREV_8 = 1 shl 2
+        /** Stored value XOR the 4 bytes 8 bytes after it. */
+        const val XOR_NEXT_8 = 1 shl 3
+        /** Stored value divided by 2. */
+        const val SCALE_2 = 1 shl 4
+        const val SCALE_4 = 1 shl 5
+        const val SCALE_8 = 1 shl 6
+        const val SCALE_10 = 1 shl 7
+        const val SCALE_100 = 1 shl 8
+        const val SCALE_1000 = 1 shl 9
+        /** The 4 bytes before plus the stored value. */
+        const val SPLIT_PREV = 1 shl 10
+        /** The stored value plus the 4 bytes after. */
+        const val SPLIT_NEXT = 1 shl 11
+        const val ALL = (1 shl 12) - 1
+    }
+
+    /**
+     * How fixed Float/Double values are compared in exact searches and refines.
+     * Ranges and integer values are not affected.
+     */
+    object FloatCompare {
+        /** Bit-exact for Float, within machine epsilon for Double. */
+        const val EXACT = 0
+        /** |x - value| <= epsilon. */
+        const val ABSOLUTE = 1
+        /** |x - value| <= epsilon * max(|x|, |value|). */
+        const val RELATIVE = 2
+        /** x truncated to an integer equals the integer part of value; epsilon is ignored. */
+        const val TRUNCATED = 3
+    }
+
     /** Search status constants. */
     object Status {
         const val IDLE = 0
@@ -151,6 +197,8 @@ object SearchEngine {
      * @param type Data type.
      * @param ranges Memory range set.
      * @param useDeepSearch Whether to use deep search.
+     * @param floatCompare [FloatCompare] mode for fixed float values.
+     * @param floatEpsilon Tolerance for [FloatCompare.ABSOLUTE] and [FloatCompare.RELATIVE].
      * @return Whether the search started successfully.
      */
     fun startSearchAsync(
@@ -159,26 +207,21 @@ object SearchEngine {
         ranges: Set<MemoryRange>,
         useDeepSearch: Boolean,
         keepResult: Boolean = false,
+        floatCompare: Int = FloatCompare.EXACT,
+        floatEpsilon: Double = 0.0,
     ): Boolean {
-        val nativeRegions = mutableListOf<Long>()
-
-        WuwaDriver.queryMemRegionsWithRetry()
-            .divideToSimpleMemoryRange()
-            .filter { ranges.contains(it.range) }
-            .forEach {
-                nativeRegions.add(it.start)
-                nativeRegions.add(it.end)
-            }
-
         clearSharedBuffer()
         newSharedBuffer()
 
-        return nativeStartSearchAsync(
+        // Regions are classified and selected natively.
+        return nativeStartSearchInRangesAsync(
             query,
             type.nativeId,
-            nativeRegions.toLongArray(),
+            MemoryRange.toMask(ranges),
             useDeepSearch,
-            keepResult
+            keepResult,
+            floatCompare,
+            floatEpsilon
         )
     }
 
@@ -189,6 +232,8 @@ object SearchEngine {
      * @param regions Memory region array, format [start1, end1, start2, end2, ...].
      * @param useDeepSearch Whether to use deep search.
      * @param keepResult Whether to keep existing results when switching modes.
+     * @param floatCompare [FloatCompare] mode for fixed float values.
+     * @param floatEpsilon Tolerance for [FloatCompare.ABSOLUTE] and [FloatCompare.RELATIVE].
      * @return Whether the search started successfully.
      */
     fun startSearchAsyncWithCustomRange(
@@ -197,27 +242,134 @@ object SearchEngine {
         regions: LongArray,
         useDeepSearch: Boolean,
         keepResult: Boolean = false,
+        floatCompare: Int = FloatCompare.EXACT,
+        floatEpsilon: Double = 0.0,
     ): Boolean {
         clearSharedBuffer()
         if (!newSharedBuffer()) {
             throw RuntimeException("failed to init SharedBuffer")
         }
-        return nativeStartSearchAsync(query, type.nativeId, regions, useDeepSearch, keepResult)
+        return nativeStartSearchAsync(query, type.nativeId, regions, useDeepSearch, keepResult, floatCompare, floatEpsilon)
+    }
+
+    /**
+     * Starts an async exact/group search over the memory pages of the current results only.
+     * Much faster than a full search for second-stage searches on huge heaps, e.g. a group
+     * search next to a value found earlier. The current results are replaced.
+     * @param query Search content.
+     * @param type Data type.
+     * @param useDeepSearch Whether to use deep search.
+     * @param floatCompare [FloatCompare] mode for fixed float values.
+     * @param floatEpsilon Tolerance for [FloatCompare.ABSOLUTE] and [FloatCompare.RELATIVE].
+     * @return Whether the search started successfully.
+     */
+    fun startSearchInResultPagesAsync(
+        query: String,
+        type: DisplayValueType,
+        useDeepSearch: Boolean,
+        floatCompare: Int = FloatCompare.EXACT,
+        floatEpsilon: Double = 0.0,
+    ): Boolean {
+        clearSharedBuffer()
+        newSharedBuffer()
+        return nativeStartSearchInResultPagesAsync(query, type.nativeId, useDeepSearch, floatCompare, floatEpsilon)
     }
 
     /**
      * Starts an async refine search. Returns immediately.
      * @param query Search content.
      * @param type Data type.
+     * @param floatCompare [FloatCompare] mode for fixed float values.
+     * @param floatEpsilon Tolerance for [FloatCompare.ABSOLUTE] and [FloatCompare.RELATIVE].
      * @return Whether the search started successfully.
      */
     fun startRefineAsync(
         query: String,
         type: DisplayValueType,
+        floatCompare: Int = FloatCompare.EXACT,
+        floatEpsilon: Double = 0.0,
     ): Boolean {
         clearSharedBuffer()
         newSharedBuffer()
-        return nativeStartRefineAsync(query, type.nativeId)
+        return nativeStartRefineAsync(query, type.nativeId, floatCompare, floatEpsilon)
+    }
+
+    /**
+     * Starts an async near-by search around the current results. Returns immediately.
+     * Keeps only the results that have a value matching [query] within [distance] bytes
+     * before or after them. Undo restores the previous results.
+     * @param query A single search value.
+     * @param type Data type of the neighbor value.
+     * @param distance Search distance in bytes, 1..[MAX_NEARBY_DISTANCE].
+     * @return Whether the search started successfully.
+     */
+    fun startNearbyRefineAsync(
+        query: String,
+        type: DisplayValueType,
+        distance: Long,
+    ): Boolean {
+        clearSharedBuffer()
+        newSharedBuffer()
+        return nativeStartNearbyRefineAsync(query, type.nativeId, distance)
+    }
+
+    /**
+     * Starts an async obfuscated value search over the current results. Returns immediately.
+     * Keeps only the results that decode to [value] under one of [transforms]. Results kept by an
+     * earlier obfuscated search only test the transforms that matched then, so after a few value
+     * changes usually a single transform is left. Start from a fuzzy search to cover every address.
+     * @param value The plain value as shown in game.
+     * @param transforms [Obfuscation] bits to test.
+     * @return Whether the search started successfully.
+     */
+    fun startObfuscatedRefineAsync(value: Int, transforms: Int = Obfuscation.ALL): Boolean {
+        clearSharedBuffer()
+        newSharedBuffer()
+        return nativeStartObfuscatedRefineAsync(value, transforms)
+    }
+
+    /**
+     * Gets the [Obfuscation] bits still matching each result of a page.
+     * @return One mask per result, 0 when no obfuscated search ran on it.
+     */
+    fun getObfuscationMasks(offset: Int, count: Int): IntArray = nativeGetObfuscationMasks(offset, count)
+
+    /**
+     * Reads the value at [address] and decodes it with a single [Obfuscation] bit.
+     */
+    fun decodeObfuscated(address: Long, transform: Int): Int = nativeDecodeObfuscated(address, transform)
+
+    /**
+     * Takes a named snapshot of the current values of all results, replacing one with the same name.
+     * Snapshots survive new searches and refines; to snapshot whole regions, run a fuzzy initial search first.
+     * Reads every result on the calling thread, so avoid calling it from the UI thread for large result sets.
+     * @param name Snapshot name.
+     * @return Number of values recorded.
+     */
+    fun takeSnapshot(name: String): Int = nativeTakeSnapshot(name)
+
+    /**
+     * Deletes a named snapshot.
+     * @return Whether the snapshot existed.
+     */
+    fun removeSnapshot(name: String): Boolean = nativeRemoveSnapshot(name)
+
+    /**
+     * Gets the names of all snapshots, sorted.
+     */
+    fun getSnapshotNames(): Array<String> = nativeGetSnapshotNames()
+
+    /**
+     * Starts an async refine against a named snapshot instead of the last refine. Returns immediately.
+     * Results missing from the snapshot are dropped. Undo restores the previous results.
+     * @param name Snapshot name.
+     * @param changed True to keep the values changed since the snapshot, false to keep the unchanged ones.
+     * @return Whether the refine started successfully.
+     */
+    fun startSnapshotRefineAsync(name: String, changed: Boolean): Boolean {
+        clearSharedBuffer()
+        newSharedBuffer()
+        return nativeStartSnapshotRefineAsync(name, changed)
     }
 
     // Legacy synchronous methods kept for backward compatibility.
@@ -278,6 +430,17 @@ object SearchEngine {
         }
     }
 
+    /**
+     * Samples results across the whole result set for a quick preview.
+     * Values are read fresh from memory, also in fuzzy mode.
+     * @param count Maximum number of results to return.
+     * @param random If true pick random results, otherwise evenly spaced ones.
+     * @return Sampled results in index order.
+     */
+    fun sampleResults(count: Int, random: Boolean = false): Array<SearchResultItem> {
+        return nativeSampleResults(count, random)
+    }
+
     /**
      * Gets total result count.
      */
@@ -285,6 +448,41 @@ object SearchEngine {
         return nativeGetTotalResultCount()
     }
 
+    /**
+     * Gets one page of results as primitive arrays, for scrolling through large result sets.
+     * Indices are not affected by the filter and match [removeResult].
+     * @param offset Index of the first result.
+     * @param count Maximum number of results in the page.
+     * @param reread If true values are read fresh from memory, otherwise only fuzzy results have a value.
+     */
+    fun getSearchResults(offset: Int, count: Int, reread: Boolean = true): SearchResultPage {
+        return nativeGetSearchResults(offset, count, reread)
+    }
+
+    /**
+     * Same page as [getSearchResults], decoded from a protobuf buffer.
+     */
+    fun getSearchResultsProto(offset: Int, count: Int, reread: Boolean = true): ProtoSearchResultPage {
+        return ProtoSearchResultPage.parseFrom(nativeGetSearchResultsProto(offset, count, reread))
+    }
+
+    /**
+     * Re-reads the values of the results starting at [offset], e.g. the rows visible on screen.
+     * Fills one slot of [values] / [valid] per result, so the arrays can be reused on every refresh.
+     * Values use the same raw format as [SearchResultPage.values].
+     * @return Number of results read, at most `values.size`.
+     */
+    fun refreshResultValues(offset: Int, values: LongArray, valid: BooleanArray): Int {
+        return nativeRefreshResultValues(offset, values, valid)
+    }
+
+    /**
+     * Gets the result count addressable by [getSearchResults].
+     */
+    fun getSearchResultCount(): Int {
+        return nativeGetSearchResultCount()
+    }
+
     /**
      * Clears search results.
      */
@@ -292,6 +490,35 @@ object SearchEngine {
         nativeClearSearchResults()
     }
 
+    /**
+     * Restores the result set from before the last refine step.
+     * Snapshots are taken before each refine; a new search or clearing the results drops them.
+     * @return Restored result count, or -1 if there is nothing to undo.
+     */
+    fun undoSearch(): Long {
+        return nativeUndoSearch()
+    }
+
+    /**
+     * Gets the undo steps of the current search session, oldest first.
+     * The last entry is the one [undoSearch] restores.
+     */
+    fun getSearchHistory(): Array<SearchStepEntry> {
+        return nativeGetSearchHistory()
+    }
+
+    /**
+     * Writes the same value to every result in a range in one native call.
+     * Results excluded by the active filter are skipped.
+     * @param offset Index of the first result.
+     * @param count Number of results, negative for all results from [offset] on.
+     * @param value Little-endian value bytes, written as-is to each address.
+     * @return Number of successful writes.
+     */
+    fun writeToResults(offset: Int, count: Int, value: ByteArray): Int {
+        return nativeWriteToResults(offset, count, value)
+    }
+
     /**
      * Removes a single search result.
      * @param index Search result index.
@@ -387,6 +614,69 @@ object SearchEngine {
         return nativeGetCompatibilityMode()
     }
 
+    /**
+     * Sets unaligned search (off by default).
+     * When enabled, new exact and fuzzy searches check every byte offset instead of stepping
+     * by the value alignment, so values inside packed structures can be found. A Dword scan
+     * then checks 4x the positions and a Qword/Double scan 8x, and takes correspondingly longer.
+     * @param enabled Whether to enable unaligned search.
+     */
+    fun setUnalignedSearch(enabled: Boolean) {
+        nativeSetUnalignedSearch(enabled)
+    }
+
+    /**
+     * Gets unaligned search.
+     * @return Whether unaligned search is enabled.
+     */
+    fun getUnalignedSearch(): Boolean {
+        return nativeGetUnalignedSearch()
+    }
+
+    /**
+     * Sets background mode (off by default).
+     * When enabled, new searches and refines run on low-priority workers (idle scheduling,
+     * throttled reads), so the game keeps its frame rate; they take longer.
+     * @param enabled Whether to enable background mode.
+     */
+    fun setBackgroundMode(enabled: Boolean) {
+        nativeSetBackgroundMode(enabled)
+    }
+
+    /**
+     * Gets background mode.
+     * @return Whether background mode is enabled.
+     */
+    fun getBackgroundMode(): Boolean {
+        return nativeGetBackgroundMode()
+    }
+
+    /**
+     * Sets the in-memory budget of fuzzy initial scan results (default 256MB).
+     * Results beyond it are spilled to the cache directory during the scan,
+     * so the first scan of large games does not run out of memory.
+     * @param bytes Budget in bytes, 0 spills everything.
+     */
+    fun setFuzzyMemoryBudget(bytes: Long) {
+        nativeSetFuzzyMemoryBudget(bytes)
+    }
+
+    /**
+     * Gets the in-memory budget of fuzzy initial scan results in bytes.
+     */
+    fun getFuzzyMemoryBudget(): Long {
+        return nativeGetFuzzyMemoryBudget()
+    }
+
+    /**
+     * Gets the JSON report of the last finished search operation
+     * (parameters, region stats, phase timings, result counts, warnings).
+     * @return Report file path, or null if no operation has finished yet.
+     */
+    fun getLastReportPath(): String? {
+        return nativeGetLastReportPath()
+    }
+
     /**
      * Starts an async fuzzy initial search. Records all values in memory regions.
      * @param type Data type to search for.
@@ -399,20 +689,11 @@ object SearchEngine {
         ranges: Set<MemoryRange>,
         keepResult: Boolean = false,
     ): Boolean {
-        val nativeRegions = mutableListOf<Long>()
-
-        WuwaDriver.queryMemRegionsWithRetry()
-            .divideToSimpleMemoryRange()
-            .filter { ranges.contains(it.range) }
-            .forEach {
-                nativeRegions.add(it.start)
-                nativeRegions.add(it.end)
-            }
-
         clearSharedBuffer()
         newSharedBuffer()
 
-        return nativeStartFuzzySearchAsync(type.nativeId, nativeRegions.toLongArray(), keepResult)
+        // Regions are classified and selected natively.
+        return nativeStartFuzzySearchInRangesAsync(type.nativeId, MemoryRange.toMask(ranges), keepResult)
     }
 
     /**
@@ -434,6 +715,18 @@ object SearchEngine {
         return nativeStartFuzzySearchAsync(type.nativeId, regions, keepResult)
     }
 
+    /**
+     * Starts an async fuzzy initial search over the memory pages of the current results only.
+     * The current results are replaced.
+     * @param type Data type to search for.
+     * @return Whether the search started successfully.
+     */
+    fun startFuzzySearchInResultPagesAsync(type: DisplayValueType): Boolean {
+        clearSharedBuffer()
+        newSharedBuffer()
+        return nativeStartFuzzySearchInResultPagesAsync(type.nativeId)
+    }
+
     /**
      * Starts an async fuzzy refine search with a condition.
      * @param condition Fuzzy condition to apply.
@@ -499,6 +792,14 @@ object SearchEngine {
         sharedBuffer = null
     }
 
+    /**
+     * Resets the shared buffer for a search started from native code (e.g. ChangeTrigger).
+     */
+    internal fun prepareSharedBuffer() {
+        clearSharedBuffer()
+        newSharedBuffer()
+    }
+
     // Legacy methods for backward compatibility.
 
     @Deprecated("Use setSharedBuffer instead", ReplaceWith("setSharedBuffer(buffer)"))
@@ -522,10 +823,38 @@ object SearchEngine {
         defaultType: Int,
         regions: LongArray,
         useDeepSearch: Boolean,
-        keepResult: Boolean
+        keepResult: Boolean,
+        floatCompare: Int,
+        floatEpsilon: Double
+    ): Boolean
+
+    private external fun nativeStartSearchInRangesAsync(
+        query: String,
+        defaultType: Int,
+        regionMask: Long,
+        useDeepSearch: Boolean,
+        keepResult: Boolean,
+        floatCompare: Int,
+        floatEpsilon: Double
     ): Boolean
 
-    private external fun nativeStartRefineAsync(query: String, defaultType: Int): Boolean
+    private external fun nativeStartSearchInResultPagesAsync(
+        query: String,
+        defaultType: Int,
+        useDeepSearch: Boolean,
+        floatCompare: Int,
+        floatEpsilon: Double
+    ): Boolean
+
+    private external fun nativeStartRefineAsync(query: String, defaultType: Int, floatCompare: Int, floatEpsilon: Double): Boolean
+    private external fun nativeStartNearbyRefineAsync(query: String, defaultType: Int, distance: Long): Boolean
+    private external fun nativeStartObfuscatedRefineAsync(value: Int, transforms: Int): Boolean
+    private external fun nativeGetObfuscationMasks(offset: Int, count: Int): IntArray
+    private external fun nativeDecodeObfuscated(address: Long, transform: Int): Int
+    private external fun nativeTakeSnapshot(name: String): Int
+    private external fun nativeRemoveSnapshot(name: String): Boolean
+    private external fun nativeGetSnapshotNames(): Array<String>
+    private external fun nativeStartSnapshotRefineAsync(name: String, changed: Boolean): Boolean
     private external fun nativeIsSearching(): Boolean
     private external fun nativeRequestCancel()
 
@@ -539,8 +868,16 @@ object SearchEngine {
     ): Long
 
     private external fun nativeGetResults(start: Int, count: Int): Array<SearchResultItem>
+    private external fun nativeSampleResults(count: Int, random: Boolean): Array<SearchResultItem>
     private external fun nativeGetTotalResultCount(): Long
+    private external fun nativeGetSearchResults(offset: Int, count: Int, reread: Boolean): SearchResultPage
+    private external fun nativeGetSearchResultsProto(offset: Int, count: Int, reread: Boolean): ByteArray
+    private external fun nativeGetSearchResultCount(): Int
+    private external fun nativeRefreshResultValues(offset: Int, values: LongArray, valid: BooleanArray): Int
+    private external fun nativeWriteToResults(offset: Int, count: Int, value: ByteArray): Int
     private external fun nativeClearSearchResults()
+    private external fun nativeUndoSearch(): Long
+    private external fun nativeGetSearchHistory(): Array<SearchStepEntry>
     private external fun nativeRemoveResult(index: Int): Boolean
     private external fun nativeRemoveResults(indices: IntArray): Boolean
     private external fun nativeKeepOnlyResults(indices: IntArray): Boolean
@@ -556,6 +893,13 @@ object SearchEngine {
     private external fun nativeGetCurrentSearchMode(): Int
     private external fun nativeSetCompatibilityMode(enabled: Boolean)
     private external fun nativeGetCompatibilityMode(): Boolean
+    private external fun nativeSetUnalignedSearch(enabled: Boolean)
+    private external fun nativeGetUnalignedSearch(): Boolean
+    private external fun nativeSetBackgroundMode(enabled: Boolean)
+    private external fun nativeGetBackgroundMode(): Boolean
+    private external fun nativeSetFuzzyMemoryBudget(bytes: Long)
+    private external fun nativeGetFuzzyMemoryBudget(): Long
+    private external fun nativeGetLastReportPath(): String?
     @Deprecated("同步搜索版本已废弃")
     private external fun nativeRefineSearch(
         query: String,
@@ -568,12 +912,20 @@ object SearchEngine {
         types: IntArray
     ): Boolean
 
+    private external fun nativeStartFuzzySearchInRangesAsync(
+        valueType: Int,
+        regionMask: Long,
+        keepResult: Boolean
+    ): Boolean
+
     private external fun nativeStartFuzzySearchAsync(
         valueType: Int,
         regions: LongArray,
         keepResult: Boolean
     ): Boolean
 
+    private external fun nativeStartFuzzySearchInResultPagesAsync(valueType: Int): Boolean
+
     private external fun nativeStartFuzzyRefineAsync(
         conditionId: Int,
         param1: Long,
@@ -0,0 +1,59 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 搜索结果的一页，见 [SearchEngine.getSearchResults]
+ *
+ * 每条结果不单独创建对象，[addresses]、[types]、[values]、[valid] 一一对应，
+ * 第 i 条在整个结果集中的下标为 [offset] + i。
+ *
+ * @property offset 本页第一条结果的下标
+ * @property total 取页时的结果总数
+ * @property addresses 结果地址
+ * @property types 值类型的 native id
+ * @property values 值的原始字节（小端，零扩展到 8 字节）
+ * @property valid 对应的值是否有效（读取失败或精确结果未重新读取时为 false）
+ */
+data class SearchResultPage(
+    val offset: Int,
+    val total: Long,
+    val addresses: LongArray,
+    val types: IntArray,
+    val values: LongArray,
+    val valid: BooleanArray
+) {
+    val size: Int
+        get() = addresses.size
+
+    /**
+     * 按类型格式化第 [index] 条的值，无效时返回 "N/A"
+     */
+    fun formatValue(index: Int): String {
+        if (!valid[index]) return "N/A"
+        val raw = values[index]
+        return when (types[index]) {
+            0 -> (raw and 0xFF).toString()
+            1 -> (raw and 0xFFFF).toString()
+            3 -> raw.toULong().toString()
+            4 -> Float.fromBits(raw.toInt()).toString()
+            5 -> Double.fromBits(raw).toString()
+            else -> (raw and 0xFFFFFFFFL).toString()
+        }
+    }
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is SearchResultPage) return false
+        return offset == other.offset && total == other.total && addresses.contentEquals(other.addresses) &&
+            types.contentEquals(other.types) && values.contentEquals(other.values) && valid.contentEquals(other.valid)
+    }
+
+    override fun hashCode(): Int {
+        var result = offset
+        result = 31 * result + total.hashCode()
+        result = 31 * result + addresses.contentHashCode()
+        result = 31 * result + types.contentHashCode()
+        result = 31 * result + values.contentHashCode()
+        result = 31 * result + valid.contentHashCode()
+        return result
+    }
+}
@@ -0,0 +1,19 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 搜索会话中的一个可撤销步骤
+ *
+ * @property label 替换该结果集的改善搜索描述
+ * @property mode 结果集的搜索模式（SearchMode 的 nativeValue）
+ * @property count 改善搜索前的结果数
+ * @property spilled 快照是否已写入磁盘
+ */
+data class SearchStepEntry(
+    val label: String,
+    val mode: Int,
+    val count: Long,
+    val spilled: Boolean
+) {
+    val searchMode: SearchMode
+        get() = SearchMode.fromNativeValue(mode)
+}
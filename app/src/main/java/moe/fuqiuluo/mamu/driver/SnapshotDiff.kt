@@ -0,0 +1,40 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 两份快照的对比结果
+ *
+ * [addresses]、[oldValues]、[newValues] 一一对应。
+ *
+ * @property changedPages 内容变化的页地址
+ * @property addresses 变化值的地址
+ * @property oldValues 旧快照中的值
+ * @property newValues 新快照中的值
+ * @property truncated 值变化超过上限被截断
+ */
+data class SnapshotDiff(
+    val changedPages: LongArray,
+    val addresses: LongArray,
+    val oldValues: LongArray,
+    val newValues: LongArray,
+    val truncated: Boolean
+) {
+    val changeCount: Int
+        get() = addresses.size
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is SnapshotDiff) return false
+        return changedPages.contentEquals(other.changedPages) && addresses.contentEquals(other.addresses) &&
+            oldValues.contentEquals(other.oldValues) && newValues.contentEquals(other.newValues) &&
+            truncated == other.truncated
+    }
+
+    override fun hashCode(): Int {
+        var result = changedPages.contentHashCode()
+        result = 31 * result + addresses.contentHashCode()
+        result = 31 * result + oldValues.contentHashCode()
+        result = 31 * result + newValues.contentHashCode()
+        result = 31 * result + truncated.hashCode()
+        return result
+    }
+}
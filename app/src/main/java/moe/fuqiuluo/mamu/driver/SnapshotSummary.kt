@@ -0,0 +1,20 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 快照统计
+ *
+ * @property regions 区域数
+ * @property pages 总页数
+ * @property uniquePages 去重后实际存储的页数
+ * @property zeroPages 全零页数，不占存储
+ * @property unreadablePages 无法读取的页数
+ * @property fileSize 快照文件大小（字节）
+ */
+data class SnapshotSummary(
+    val regions: Int,
+    val pages: Long,
+    val uniquePages: Long,
+    val zeroPages: Long,
+    val unreadablePages: Long,
+    val fileSize: Long
+)
@@ -0,0 +1,14 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 绑定进程中的线程
+ *
+ * @property tid 线程 id，与 [WatchHit.tid] 对应
+ * @property name 线程名
+ * @property state 调度状态，如 'R' 运行、'S' 睡眠、'D' 不可中断
+ */
+data class ThreadInfo(
+    val tid: Int,
+    val name: String,
+    val state: Char
+)
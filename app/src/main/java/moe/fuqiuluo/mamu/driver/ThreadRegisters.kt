@@ -0,0 +1,38 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 线程的用户态寄存器
+ *
+ * @property tid 线程 id
+ * @property registers x0..x30
+ * @property sp 栈指针
+ * @property pc 程序计数器
+ * @property pstate 处理器状态
+ */
+data class ThreadRegisters(
+    val tid: Int,
+    val registers: LongArray,
+    val sp: Long,
+    val pc: Long,
+    val pstate: Long
+) {
+    /** 链接寄存器 x30 */
+    val lr: Long
+        get() = registers[30]
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is ThreadRegisters) return false
+        return tid == other.tid && registers.contentEquals(other.registers) &&
+            sp == other.sp && pc == other.pc && pstate == other.pstate
+    }
+
+    override fun hashCode(): Int {
+        var result = tid
+        result = 31 * result + registers.contentHashCode()
+        result = 31 * result + sp.hashCode()
+        result = 31 * result + pc.hashCode()
+        result = 31 * result + pstate.hashCode()
+        return result
+    }
+}
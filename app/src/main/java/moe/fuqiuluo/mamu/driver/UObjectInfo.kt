@@ -0,0 +1,20 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Unreal Engine 对象
+ *
+ * @property index GUObjectArray 中的槽位
+ * @property address 对象地址
+ * @property name 对象名
+ * @property className 所属类名
+ * @property classAddress 所属 UClass 的地址，可传给 [Unreal.getProperties]
+ * @property outer 外层对象（通常是所在 Package）的名字，没有时为 null
+ */
+data class UObjectInfo(
+    val index: Int,
+    val address: Long,
+    val name: String,
+    val className: String,
+    val classAddress: Long,
+    val outer: String?
+)
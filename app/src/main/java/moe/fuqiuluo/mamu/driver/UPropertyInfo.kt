@@ -0,0 +1,18 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Unreal Engine 属性
+ *
+ * @property owner 声明该属性的类或结构
+ * @property name 属性名
+ * @property typeName 属性类，如 IntProperty、ObjectProperty
+ * @property offset 相对对象起始的偏移
+ * @property arrayDim 静态数组长度，普通属性为 1
+ */
+data class UPropertyInfo(
+    val owner: String,
+    val name: String,
+    val typeName: String,
+    val offset: Int,
+    val arrayDim: Int
+)
@@ -0,0 +1,59 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * Unreal Engine 分析
+ *
+ * 在引擎模块中定位 FNamePool 和 GUObjectArray，按名字搜索对象、展开类层级、
+ * 列出属性偏移。支持 UE 4.23+ 和 UE5，会话保存在 native 侧，切换进程后需要重新附加。
+ */
+object Unreal {
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 附加到绑定进程
+     * @param module 引擎模块的文件名或路径，部分游戏为 libUnreal.so
+     * @return 对象槽位数
+     */
+    fun attach(module: String = "libUE4.so"): Int = nativeAttach(module)
+
+    /**
+     * 按对象名查找（不区分大小写的子串匹配）
+     * @param query 为空时按槽位顺序返回
+     * @param max 最多返回的条数
+     */
+    fun findObjects(query: String, max: Int = 500): Array<UObjectInfo> = nativeFindObjects(query, max)
+
+    /**
+     * 对象所属类及其全部基类，从派生到基类排列
+     * @param objectAddress 对象地址
+     */
+    fun getClassHierarchy(objectAddress: Long): Array<String> = nativeGetClassHierarchy(objectAddress)
+
+    /**
+     * UClass/UScriptStruct 的全部属性，包括继承的，基类的在前
+     * @param structAddress 类或结构的地址，如 [UObjectInfo.classAddress]
+     */
+    fun getProperties(structAddress: Long): Array<UPropertyInfo> = nativeGetProperties(structAddress)
+
+    /**
+     * 按 FName 的 ComparisonIndex 取名字
+     * @return 无法解析时为 null
+     */
+    fun getName(index: Int): String? = nativeGetName(index)
+
+    /**
+     * 释放会话
+     */
+    fun release() = nativeRelease()
+
+    private external fun nativeAttach(module: String): Int
+    private external fun nativeFindObjects(query: String, max: Int): Array<UObjectInfo>
+    private external fun nativeGetClassHierarchy(objectAddress: Long): Array<String>
+    private external fun nativeGetProperties(structAddress: Long): Array<UPropertyInfo>
+    private external fun nativeGetName(index: Int): String?
+    private external fun nativeRelease()
+}
@@ -0,0 +1,94 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 值历史记录
+ *
+ * 原生线程按设定频率采样登记的地址，带时间戳的值保存在每个地址的环形缓冲区中，
+ * 用于画出值随时间变化的曲线，识别经过异或、缩放或混淆的值。
+ * 设置转存目录后，缓冲区满时最旧的样本写入磁盘，长时间记录也能查询完整历史。
+ * 解绑进程时全部记录被清除。
+ */
+object ValueHistory {
+    /** 内存中每个地址的默认样本数 */
+    const val DEFAULT_CAPACITY = 4096
+    /** 内存中每个地址的最大样本数 */
+    const val MAX_CAPACITY = 1 shl 20
+    /** 同时记录的最大地址数 */
+    const val MAX_TRACKS = 64
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 一个样本
+     * @param timestampMs Unix 毫秒时间
+     * @param value 小端原始值，零扩展到 8 字节
+     */
+    data class Sample(val timestampMs: Long, val value: Long)
+
+    /**
+     * 开始记录一个地址
+     * @param size 值的字节数，1..8
+     * @param intervalMs 采样间隔，最小 1ms
+     * @param capacity 内存中保留的样本数
+     * @return 记录 id
+     */
+    fun track(address: Long, size: Int, intervalMs: Int, capacity: Int = DEFAULT_CAPACITY): Long =
+        nativeTrack(address, size, intervalMs, capacity)
+
+    /**
+     * 停止记录并丢弃历史
+     */
+    fun untrack(id: Long): Boolean = nativeUntrack(id)
+
+    fun clear() = nativeClear()
+
+    /**
+     * 设置转存目录
+     * @param dir 传 null 时缓冲区满后丢弃最旧的样本
+     */
+    fun setSpillDir(dir: String?) = nativeSetSpillDir(dir)
+
+    val trackIds: LongArray
+        get() = nativeGetTrackIds()
+
+    /**
+     * 已保留的样本数（内存和磁盘），记录不存在时返回 -1
+     */
+    fun getSampleCount(id: Long): Long = nativeGetSampleCount(id)
+
+    /**
+     * 没有转存目录时丢弃的样本数，记录不存在时返回 -1
+     */
+    fun getDroppedCount(id: Long): Long = nativeGetDroppedCount(id)
+
+    /**
+     * 查询时间范围内的样本，按时间排序
+     * @param toMs 结束时间（含），负数表示不限
+     * @param max 最多返回的样本数，超出时等间隔抽取
+     * @return 记录不存在时返回 null
+     */
+    fun getHistory(id: Long, fromMs: Long = 0, toMs: Long = -1, max: Int = 2048): List<Sample>? =
+        nativeGetHistory(id, fromMs, toMs, max)?.toSamples()
+
+    /**
+     * 最新的样本，还没有样本时返回 null
+     */
+    fun getLatest(id: Long): Sample? = nativeGetLatest(id).toSamples().firstOrNull()
+
+    private fun LongArray.toSamples(): List<Sample> =
+        (0 until size / 2).map { Sample(this[it * 2], this[it * 2 + 1]) }
+
+    private external fun nativeTrack(address: Long, size: Int, intervalMs: Int, capacity: Int): Long
+    private external fun nativeUntrack(id: Long): Boolean
+    private external fun nativeClear()
+    private external fun nativeSetSpillDir(dir: String?)
+    private external fun nativeGetTrackIds(): LongArray
+    private external fun nativeGetSampleCount(id: Long): Long
+    private external fun nativeGetDroppedCount(id: Long): Long
+    private external fun nativeGetHistory(id: Long, fromMs: Long, toMs: Long, max: Int): LongArray?
+    private external fun nativeGetLatest(id: Long): LongArray
+}
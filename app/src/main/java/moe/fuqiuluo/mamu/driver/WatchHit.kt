@@ -0,0 +1,41 @@
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 硬件观察点命中记录
+ *
+ * @property tid 执行访问的线程
+ * @property pc 访问指令地址
+ * @property address 实际访问的数据地址
+ * @property registers 命中时的 x0..x30
+ * @property sp 命中时的栈指针
+ * @property timestampNs 命中时间（CLOCK_BOOTTIME，纳秒）
+ */
+data class WatchHit(
+    val tid: Int,
+    val pc: Long,
+    val address: Long,
+    val registers: LongArray,
+    val sp: Long,
+    val timestampNs: Long
+) {
+    /** 链接寄存器 x30，通常是调用者的返回地址 */
+    val lr: Long
+        get() = registers[30]
+
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is WatchHit) return false
+        return tid == other.tid && pc == other.pc && address == other.address &&
+            registers.contentEquals(other.registers) && sp == other.sp && timestampNs == other.timestampNs
+    }
+
+    override fun hashCode(): Int {
+        var result = tid
+        result = 31 * result + pc.hashCode()
+        result = 31 * result + address.hashCode()
+        result = 31 * result + registers.contentHashCode()
+        result = 31 * result + sp.hashCode()
+        result = 31 * result + timestampNs.hashCode()
+        return result
+    }
+}
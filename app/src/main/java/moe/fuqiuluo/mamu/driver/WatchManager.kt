@@ -0,0 +1,132 @@
+@file:Suppress("KotlinJniMissingFunction")
+
+package moe.fuqiuluo.mamu.driver
+
+/**
+ * 硬件观察点
+ *
+ * 通过驱动布置 CPU 调试观察点，记录访问指定地址的指令、线程与寄存器，
+ * 用于找出"是什么写了这个地址"；读访问追踪用于找出"是什么访问了这个地址"。观察点数量受硬件限制（通常 4 个），
+ * 单个最多覆盖 8 字节且不能跨 8 字节边界。驱动不支持时布置会抛出异常。
+ * 解绑进程后观察点自动释放。
+ */
+object WatchManager {
+
+    /** 观察点触发的访问类型 */
+    enum class Kind(val nativeId: Int) {
+        WRITE(0),
+        READ(1),
+
+        /** 读或写 */
+        ACCESS(2),
+    }
+
+    /** 追踪缓冲区默认容量 */
+    const val DEFAULT_TRACE_CAPACITY = 4096
+
+    init {
+        System.loadLibrary("mamu_core")
+    }
+
+    /**
+     * 布置观察点
+     *
+     * @param address 监视的地址
+     * @param size 监视的字节数（1..8）
+     * @return 观察点 id
+     */
+    fun arm(address: Long, size: Int, kind: Kind = Kind.WRITE): Int {
+        return nativeArm(address, size, kind.nativeId)
+    }
+
+    /** 监视写入 */
+    fun armWrite(address: Long, size: Int): Int = arm(address, size, Kind.WRITE)
+
+    /**
+     * 解除观察点
+     *
+     * @return 观察点不存在时返回 false
+     */
+    fun disarm(id: Int): Boolean {
+        return nativeDisarm(id)
+    }
+
+    /**
+     * 开始追踪一段地址上的访问
+     *
+     * 地址段按 8 字节对齐块拆分，每块占用一个观察点，最多 8 块。
+     * 访问记录写入固定容量的环形缓冲区，满后覆盖最旧的记录，用 [pollTrace] 取走。
+     *
+     * @param length 追踪的字节数
+     * @param capacity 环形缓冲区容量
+     * @return 追踪 id
+     */
+    fun startTrace(
+        address: Long,
+        length: Int,
+        kind: Kind = Kind.READ,
+        capacity: Int = DEFAULT_TRACE_CAPACITY,
+    ): Int {
+        return nativeStartTrace(address, length, kind.nativeId, capacity)
+    }
+
+    /**
+     * 取走追踪缓冲区中的记录，按时间顺序
+     *
+     * @param max 最多取走的数量
+     */
+    fun pollTrace(traceId: Int, max: Int = DEFAULT_TRACE_CAPACITY): Array<AccessRecord> {
+        return nativePollTrace(traceId, max)
+    }
+
+    /**
+     * 停止追踪并释放观察点
+     *
+     * @return 追踪不存在时返回 false
+     */
+    fun stopTrace(traceId: Int): Boolean {
+        return nativeStopTrace(traceId)
+    }
+
+    /**
+     * 追踪累计记录的访问次数，包括被覆盖的
+     *
+     * @return 追踪不存在时返回 -1
+     */
+    fun getTraceCount(traceId: Int): Long = nativeGetTraceCount(traceId)
+
+    /**
+     * 解除全部观察点和追踪
+     *
+     * @return 解除的数量
+     */
+    fun disarmAll(): Int {
+        return nativeDisarmAll()
+    }
+
+    /**
+     * 取走观察点自上次调用以来的命中记录，按时间顺序
+     *
+     * 每个观察点最多保留最近 4096 条。
+     */
+    fun takeHits(id: Int): Array<WatchHit> {
+        return nativeTakeHits(id)
+    }
+
+    /**
+     * 累计命中次数
+     *
+     * @return 观察点不存在时返回 -1
+     */
+    fun getHitCount(id: Int): Long = nativeGetHitCount(id)
+
+    private external fun nativeArm(address: Long, size: Int, kind: Int): Int
+    private external fun nativeDisarm(id: Int): Boolean
+    private external fun nativeDisarmAll(): Int
+    private external fun nativeTakeHits(id: Int): Array<WatchHit>
+    private external fun nativeGetHitCount(id: Int): Long
+    private external fun nativeStartTrace(address: Long, length: Int, kind: Int, capacity: Int): Int
+    private external fun nativePollTrace(traceId: Int, max: Int): Array<AccessRecord>
+    private external fun nativeStopTrace(traceId: Int): Boolean
+    private external fun nativeGetTraceCount(traceId: Int): Long
+}
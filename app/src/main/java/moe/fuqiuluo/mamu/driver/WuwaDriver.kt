@@ -4,8 +4,28 @@ package moe.fuqiuluo.mamu.driver
 
 import moe.fuqiuluo.mamu.data.model.DriverInfo
 import moe.fuqiuluo.mamu.data.model.DriverInstallResult
+import moe.fuqiuluo.mamu.driver.proto.ProcessList
+import moe.fuqiuluo.mamu.driver.proto.RegionList
+import moe.fuqiuluo.mamu.floating.data.model.MemoryRange
+import java.nio.ByteBuffer
 
 object WuwaDriver {
+    /** [writeMemoryEx] 标志：对只读私有映射做写时复制，写完恢复原权限 */
+    const val WRITE_FLAG_COW = 1
+
+    /** [benchmarkAccessModes] 标志：测完切换到最快的模式 */
+    private const val BENCHMARK_AUTO_SELECT = 1
+
+    const val PROT_READ = 1
+    const val PROT_WRITE = 2
+    const val PROT_EXEC = 4
+
+    /** [pauseProcess] 返回值：用 cgroup 冻结器暂停 */
+    const val PAUSE_CGROUP_FREEZER = 0
+
+    /** [pauseProcess] 返回值：用 SIGSTOP 暂停 */
+    const val PAUSE_SIGNAL = 1
+
     init {
         System.loadLibrary("mamu_core")
     }
@@ -19,8 +39,68 @@ object WuwaDriver {
     val isProcessBound: Boolean
         get() = nativeIsProcessBound()
 
+    /**
+     * 暂停绑定进程的全部线程，用于取得一致的快照或一次完成多处修改，解绑时自动恢复
+     * @return [PAUSE_CGROUP_FREEZER] 或 [PAUSE_SIGNAL]
+     * @throws MamuException 未绑定进程或已暂停其他进程
+     */
+    fun pauseProcess(): Int = nativePauseProcess()
+
+    /**
+     * 恢复暂停的进程
+     * @return 没有暂停的进程时返回 false
+     */
+    fun resumeProcess(): Boolean = nativeResumeProcess()
+
+    val isProcessPaused: Boolean
+        get() = nativeIsProcessPaused()
+
     fun setMemoryAccessMode(mode: Int) = nativeSetMemoryAccessMode(mode)
 
+    /**
+     * 设置驱动模式下小读取的合并窗口（微秒），0 表示不合并（默认）
+     *
+     * 多个线程在窗口内发起的小读取由一次向量化 ioctl 提交，驱动不支持时不生效。
+     */
+    fun setReadCoalesceWindow(windowUs: Int) = nativeSetReadCoalesceWindow(windowUs)
+
+    /**
+     * 已登记的内存后端名，内置 wuwa（驱动加载后）、process_vm、proc_mem
+     */
+    val backends: Array<String>
+        get() = nativeGetBackends()
+
+    /** 当前选中的内存后端 */
+    val currentBackend: String
+        get() = nativeGetCurrentBackend()
+
+    /**
+     * 在绑定进程中测试各内存访问模式的吞吐和延迟
+     * 自动选择时底层会切换到吞吐最高的模式，调用方需自行保存该设置
+     * @param autoSelect 是否切换到最快的模式，否则恢复原模式
+     */
+    fun benchmarkAccessModes(autoSelect: Boolean = false): Array<AccessModeBenchmark> =
+        nativeBenchmarkAccessModes(if (autoSelect) BENCHMARK_AUTO_SELECT else 0)
+
+    /**
+     * 当前后端的版本和功能，用于禁用不支持的功能
+     */
+    fun getDriverCapabilities(): DriverCapabilities = nativeGetDriverCapabilities()
+
+    /**
+     * 选中内存后端，须先解绑进程
+     * 用户态后端会把读写模式切换为对应的模式
+     * @param name 后端名
+     */
+    fun selectBackend(name: String) = nativeSelectBackend(name)
+
+    /**
+     * 以指定名字登记一个兼容 WuWa 协议的驱动，登记后可用 [selectBackend] 选中
+     * @param name 后端名，同名的会被替换
+     * @param fd 驱动文件描述符
+     */
+    fun registerDriverBackend(name: String, fd: Int) = nativeRegisterDriverBackend(name, fd)
+
     fun isProcessAlive(pid: Int) = nativeIsProcessAlive(pid)
 
     fun listProcesses() = nativeGetProcessList()
@@ -31,12 +111,121 @@ object WuwaDriver {
         return nativeGetProcessListWithInfo()
     }
 
+    /**
+     * 进程列表，一次解析 protobuf 缓冲区而不是逐个创建 [CProcInfo]
+     */
+    fun listProcessesProto(): ProcessList = ProcessList.parseFrom(nativeGetProcessListProto())
+
+    /**
+     * 在 native 侧过滤进程列表，只为符合条件的进程创建 CProcInfo
+     * @param name 进程名须包含的子串，不区分大小写，空串不过滤
+     * @param minUid uid 下限，如 1000 过滤掉 Linux 原生进程
+     * @param appsOnly 只保留有名字的应用进程
+     */
+    fun listProcessesFiltered(name: String = "", minUid: Int = 0, appsOnly: Boolean = false): Array<CProcInfo> =
+        nativeGetProcessListFiltered(name, minUid, appsOnly)
+
+    /**
+     * 按进程名或包名查找进程，优先完全匹配，其次该包的子进程
+     * @return pid，未找到返回 0
+     */
+    fun findProcessByName(name: String): Int = nativeFindProcessByName(name)
+
+    /**
+     * 当前前台应用的主进程（cgroup 和 oom_score_adj 推断）
+     * @return pid，判断不出时返回 0
+     */
+    fun getForegroundAppPid(): Int = nativeGetForegroundAppPid()
+
     fun bindProcess(pid: Int) = nativeBindProcess(pid)
 
     fun unbindProcess() = nativeUnbindProcess()
 
+    /**
+     * 设置进程退出回调，绑定进程后 native 侧会定时检测，进程退出时自动释放绑定再回调
+     * @param callback 传 null 取消
+     */
+    fun setProcessExitCallback(callback: ProcessExitCallback?) = nativeSetProcessExitCallback(callback)
+
+    /**
+     * 某次底层调用崩溃导致全局状态被锁死时调用：清除锁的中毒状态并解绑进程
+     * 只在 debug 构建中有用，release 构建的底层崩溃会直接结束进程
+     * @return 被清除的中毒锁数量，0 表示状态正常
+     */
+    fun resetDriverState(): Int = nativeResetDriverState()
+
+    /**
+     * 在当前进程之外再绑定一个进程（如游戏的子进程），之后可按 pid 读写或用 [selectProcess] 切换
+     * @return 绑定是否成功
+     */
+    fun attachProcess(pid: Int): Boolean = nativeAttachProcess(pid)
+
+    /**
+     * 解除附加的进程，当前进程需用 [unbindProcess] 解绑
+     * @return 该进程之前是否已附加
+     */
+    fun detachProcess(pid: Int): Boolean = nativeDetachProcess(pid)
+
+    /**
+     * 把附加的进程切换为当前进程，原当前进程转为附加。扫描、模块、线程等功能只针对当前进程
+     */
+    fun selectProcess(pid: Int) = nativeSelectProcess(pid)
+
+    /** 全部已绑定的进程，当前进程在前 */
+    val attachedProcesses: IntArray
+        get() = nativeGetAttachedProcesses()
+
     fun queryMemRegions(pid: Int = currentBindPid) = nativeQueryMemRegions(pid)
 
+    /**
+     * 列出绑定进程中已加载的 ELF 模块
+     * @param pid 只支持当前绑定的进程
+     */
+    fun getModules(pid: Int = currentBindPid): Array<ModuleInfo> = nativeGetModules(pid)
+
+    /**
+     * 解析模块导出符号的运行时地址
+     * @param module 模块路径或文件名，如 "libil2cpp.so"
+     * @return 符号地址，未找到时返回 0
+     */
+    fun resolveSymbol(module: String, symbol: String): Long = nativeResolveSymbol(module, symbol)
+
+    /**
+     * 从内存转储模块为可加载的 ELF 文件
+     * 代码和数据取自内存，磁盘上的文件可读时用来恢复 ELF 头和节头
+     * @param module 模块路径或文件名
+     * @param outputPath 输出文件路径，已存在时覆盖
+     */
+    fun dumpModule(module: String, outputPath: String): ModuleDumpResult = nativeDumpModule(module, outputPath)
+
+    /**
+     * 列出绑定进程的线程
+     */
+    fun getThreads(): Array<ThreadInfo> = nativeGetThreads()
+
+    /**
+     * 读取线程的寄存器，线程不属于绑定进程时抛出异常
+     */
+    fun getThreadRegisters(tid: Int): ThreadRegisters = nativeGetThreadRegisters(tid)
+
+    /**
+     * 只查询指定类型的内存区域，分类规则与 divideToSimpleMemoryRange 一致
+     * @param ranges 需要的区域类型
+     */
+    fun queryMemRegionsFiltered(
+        ranges: Collection<MemoryRange>,
+        pid: Int = currentBindPid
+    ): Array<MemRegionEntry> = nativeQueryMemRegionsFiltered(pid, MemoryRange.toMask(ranges))
+
+    /**
+     * 以 protobuf 缓冲区返回内存区域，区域很多时比 [queryMemRegionsFiltered] 快
+     * @param ranges 需要的区域类型，null 时返回全部区域（包括无法分类的）
+     */
+    fun queryMemRegionsProto(
+        ranges: Collection<MemoryRange>? = null,
+        pid: Int = currentBindPid
+    ): RegionList = RegionList.parseFrom(nativeQueryMemRegionsProto(pid, ranges?.let { MemoryRange.toMask(it) } ?: -1L))
+
     fun queryMemRegionsWithRetry(
         pid: Int = currentBindPid,
         retryCount: Int = 3
@@ -64,7 +253,30 @@ object WuwaDriver {
     fun readMemory(addr: Long, size: Int): ByteArray? = nativeReadMemory(addr, size)
 
     /**
-     * 批量读取内存
+     * 读取当前进程或附加的进程
+     * @param pid 已绑定或已附加的进程
+     * @param addr 要读取的虚拟地址
+     * @param size 读取大小
+     */
+    fun readMemory(pid: Int, addr: Long, size: Int): ByteArray = nativeReadMemory(pid, addr, size)
+
+    /**
+     * 直接读取到 DirectByteBuffer，不分配中间数组，适合高频刷新（如 hex 视图）
+     * 不会修改 buffer 的 position / limit
+     * @param addr 要读取的虚拟地址
+     * @param buffer 通过 ByteBuffer.allocateDirect 创建的缓冲区
+     * @param offset 写入 buffer 的起始偏移
+     * @param size 读取大小
+     * @return 读取的字节数
+     * @throws RuntimeException 读取失败或缓冲区容量不足
+     */
+    fun readMemoryInto(addr: Long, buffer: ByteBuffer, offset: Int = 0, size: Int = buffer.capacity() - offset): Int {
+        require(buffer.isDirect) { "buffer must be a direct ByteBuffer" }
+        return nativeReadMemoryInto(addr, buffer, offset, size)
+    }
+
+    /**
+     * 批量读取内存，相邻地址会合并为一次驱动读取
      * @param addrs 要读取的地址数组
      * @param sizes 每个地址对应的读取大小
      * @return 读取的字节数组，失败的位置为null
@@ -80,6 +292,86 @@ object WuwaDriver {
      */
     fun writeMemory(addr: Long, data: ByteArray): Boolean = nativeWriteMemory(addr, data)
 
+    /**
+     * 写入当前进程或附加的进程
+     * @param pid 已绑定或已附加的进程
+     */
+    fun writeMemory(pid: Int, addr: Long, data: ByteArray): Boolean = nativeWriteMemory(pid, addr, data)
+
+    /**
+     * 按标志写入内存
+     * [WRITE_FLAG_COW]：目标页只读时（代码、.rodata 等）临时加写权限并触发写时复制，
+     * 只改本进程的私有副本，写完恢复原权限；共享映射会被拒绝
+     * @param addr 要写入的虚拟地址
+     * @param data 要写入的数据
+     * @param flags WRITE_FLAG_* 组合，0 等同 [writeMemory]
+     * @return 写入是否成功
+     */
+    fun writeMemoryEx(addr: Long, data: ByteArray, flags: Int): Boolean = nativeWriteMemoryEx(addr, data, flags)
+
+    /**
+     * 查询地址所在映射的权限
+     * @return PROT_* 组合（[PROT_READ] / [PROT_WRITE] / [PROT_EXEC]）
+     */
+    fun getMemoryProtection(addr: Long): Int = nativeGetMemoryProtection(addr)
+
+    /**
+     * 修改绑定进程中一段内存的权限，范围向外扩展到页边界
+     * 用于准备补丁，如让数据可执行、让代码可写
+     * @param addr 起始地址
+     * @param size 大小
+     * @param prot PROT_* 组合
+     * @return 修改前的权限，传回本方法即可恢复
+     */
+    fun setMemoryProtection(addr: Long, size: Long, prot: Int): Int {
+        val previous = nativeGetMemoryProtection(addr)
+        nativeSetMemoryProtection(addr, size, prot)
+        return previous
+    }
+
+    /**
+     * 绑定进程中虚拟地址对应的物理地址，需要驱动支持物理访问
+     * 可用于判断两个映射是否共享同一物理页
+     * @param addr 虚拟地址
+     * @return 物理地址，页不在内存中时抛出异常
+     */
+    fun virtToPhys(addr: Long): Long = nativeVirtToPhys(addr)
+
+    /**
+     * 按物理地址读取，不能跨页
+     * @param physAddr 物理地址
+     * @param size 字节数
+     */
+    fun readPhysical(physAddr: Long, size: Int): ByteArray = nativeReadPhysical(physAddr, size)
+
+    /**
+     * 按类型读写单个值（小端），失败时抛出 RuntimeException
+     * 省去 ByteArray 封装和字节序转换，适合编辑器逐格显示
+     */
+    fun readByte(addr: Long): Byte = nativeReadByte(addr)
+    fun readShort(addr: Long): Short = nativeReadShort(addr)
+    fun readInt(addr: Long): Int = nativeReadInt(addr)
+    fun readLong(addr: Long): Long = nativeReadLong(addr)
+    fun readFloat(addr: Long): Float = nativeReadFloat(addr)
+    fun readDouble(addr: Long): Double = nativeReadDouble(addr)
+
+    fun writeByte(addr: Long, value: Byte): Boolean = nativeWriteByte(addr, value)
+    fun writeShort(addr: Long, value: Short): Boolean = nativeWriteShort(addr, value)
+    fun writeInt(addr: Long, value: Int): Boolean = nativeWriteInt(addr, value)
+    fun writeLong(addr: Long, value: Long): Boolean = nativeWriteLong(addr, value)
+    fun writeFloat(addr: Long, value: Float): Boolean = nativeWriteFloat(addr, value)
+    fun writeDouble(addr: Long, value: Double): Boolean = nativeWriteDouble(addr, value)
+
+    /**
+     * 强制写入只读映射（如 .rodata 常量），绕过页表写保护
+     * 注意：文件映射的只读页通常与其他进程共享，改写会影响所有映射了该文件的进程
+     * @param addr 要写入的虚拟地址
+     * @param data 要写入的数据
+     * @return 被覆盖的原始字节，可用于恢复
+     * @throws RuntimeException 驱动不支持或写入失败
+     */
+    fun writeMemoryForce(addr: Long, data: ByteArray): ByteArray = nativeWriteMemoryForce(addr, data)
+
     /**
      * 批量写入内存
      * @param addrs 要写入的地址数组
@@ -89,6 +381,55 @@ object WuwaDriver {
     fun batchWriteMemory(addrs: LongArray, dataArray: Array<ByteArray>): BooleanArray =
         nativeBatchWriteMemory(addrs, dataArray)
 
+    /**
+     * 分析一段内存的结构，用于结构热力图
+     * 只返回分析结果，不传输原始内存
+     * @param addr 起始地址（向下 4 字节对齐）
+     * @param size 分析大小，最大 1MB
+     * @param maxStride 候选结构体步长上限（字节）
+     * @param bigEndian 是否按大端解释
+     * @return 分析结果
+     */
+    fun analyzeMemoryLayout(
+        addr: Long,
+        size: Int,
+        maxStride: Int = 256,
+        bigEndian: Boolean = false
+    ): MemoryLayoutAnalysis = nativeAnalyzeMemoryLayout(addr, size, maxStride, bigEndian)
+
+    /**
+     * 逐个槽位解析一段内存的结构
+     * 8 字节对齐处优先识别指针（含虚表、字符串指针）和 double，其余按 4 字节识别
+     * @param addr 起始地址（向下 4 字节对齐）
+     * @param size 解析大小，最大 64KB
+     */
+    fun dissectStructure(addr: Long, size: Int): Array<DissectedSlot> = nativeDissectStructure(addr, size)
+
+    /**
+     * 将一段内存转储到文件，供 Ghidra/IDA 离线分析
+     * 无法读取的页以 0 填充，文件头的页位图标记了这些空洞
+     * @param addr 起始地址
+     * @param size 转储大小
+     * @param path 输出文件路径，已存在时覆盖
+     * @return 至少有一页可读时返回 true
+     */
+    fun dumpMemory(addr: Long, size: Long, path: String): Boolean = nativeDumpMemory(addr, size, path)
+
+    /**
+     * 将多个内存区域转储到同一个文件
+     * @param regions 要转储的区域
+     * @param path 输出文件路径，已存在时覆盖
+     * @return 至少有一页可读时返回 true
+     */
+    fun dumpRegions(regions: List<MemRegionEntry>, path: String): Boolean {
+        val ranges = LongArray(regions.size * 2)
+        regions.forEachIndexed { i, region ->
+            ranges[i * 2] = region.start
+            ranges[i * 2 + 1] = region.end
+        }
+        return nativeDumpRegions(ranges, path)
+    }
+
     /**
      * 获取可用的驱动列表
      * @return 可用驱动信息数组
@@ -119,25 +460,83 @@ object WuwaDriver {
     private external fun nativeIsLoaded(): Boolean
     private external fun nativeSetDriverFd(fd: Int): Boolean
     private external fun nativeSetMemoryAccessMode(mode: Int)
+    private external fun nativeSetReadCoalesceWindow(windowUs: Int)
+    private external fun nativeGetBackends(): Array<String>
+    private external fun nativeGetCurrentBackend(): String
+    private external fun nativeSelectBackend(name: String)
+    private external fun nativeRegisterDriverBackend(name: String, fd: Int)
+    private external fun nativeGetDriverCapabilities(): DriverCapabilities
+    private external fun nativeBenchmarkAccessModes(flags: Int): Array<AccessModeBenchmark>
     private external fun nativeIsProcessAlive(pid: Int): Boolean
     private external fun nativeGetProcessList(): IntArray
     private external fun nativeGetProcessInfo(pid: Int): CProcInfo
     private external fun nativeGetProcessListWithInfo(): Array<CProcInfo>
+    private external fun nativeGetProcessListProto(): ByteArray
+    private external fun nativeGetProcessListFiltered(name: String, minUid: Int, appsOnly: Boolean): Array<CProcInfo>
+    private external fun nativeFindProcessByName(name: String): Int
+    private external fun nativeGetForegroundAppPid(): Int
     private external fun nativeBindProcess(pid: Int): Boolean
     private external fun nativeIsProcessBound(): Boolean
+    private external fun nativePauseProcess(): Int
+    private external fun nativeResumeProcess(): Boolean
+    private external fun nativeIsProcessPaused(): Boolean
     private external fun nativeUnbindProcess(): Boolean
+    private external fun nativeSetProcessExitCallback(callback: ProcessExitCallback?)
+    private external fun nativeResetDriverState(): Int
+    private external fun nativeAttachProcess(pid: Int): Boolean
+    private external fun nativeDetachProcess(pid: Int): Boolean
+    private external fun nativeSelectProcess(pid: Int)
+    private external fun nativeGetAttachedProcesses(): IntArray
     private external fun nativeGetCurrentBindPid(): Int
     private external fun nativeQueryMemRegions(pid: Int): Array<MemRegionEntry>
+    private external fun nativeQueryMemRegionsFiltered(pid: Int, rangeMask: Long): Array<MemRegionEntry>
+    private external fun nativeQueryMemRegionsProto(pid: Int, rangeMask: Long): ByteArray
     private external fun nativeReadMemory(addr: Long, size: Int): ByteArray?
+    private external fun nativeReadMemory(pid: Int, addr: Long, size: Int): ByteArray
+    private external fun nativeReadMemoryInto(addr: Long, buffer: ByteBuffer, offset: Int, size: Int): Int
     private external fun nativeBatchReadMemory(addrs: LongArray, sizes: IntArray): Array<ByteArray?>
     private external fun nativeWriteMemory(addr: Long, data: ByteArray): Boolean
+    private external fun nativeWriteMemory(pid: Int, addr: Long, data: ByteArray): Boolean
+    private external fun nativeWriteMemoryEx(addr: Long, data: ByteArray, flags: Int): Boolean
+    private external fun nativeGetMemoryProtection(addr: Long): Int
+    private external fun nativeSetMemoryProtection(addr: Long, size: Long, prot: Int): Boolean
+    private external fun nativeVirtToPhys(addr: Long): Long
+    private external fun nativeReadPhysical(physAddr: Long, size: Int): ByteArray
+    private external fun nativeReadByte(addr: Long): Byte
+    private external fun nativeReadShort(addr: Long): Short
+    private external fun nativeReadInt(addr: Long): Int
+    private external fun nativeReadLong(addr: Long): Long
+    private external fun nativeReadFloat(addr: Long): Float
+    private external fun nativeReadDouble(addr: Long): Double
+    private external fun nativeWriteByte(addr: Long, value: Byte): Boolean
+    private external fun nativeWriteShort(addr: Long, value: Short): Boolean
+    private external fun nativeWriteInt(addr: Long, value: Int): Boolean
+    private external fun nativeWriteLong(addr: Long, value: Long): Boolean
+    private external fun nativeWriteFloat(addr: Long, value: Float): Boolean
+    private external fun nativeWriteDouble(addr: Long, value: Double): Boolean
+    private external fun nativeWriteMemoryForce(addr: Long, data: ByteArray): ByteArray
     private external fun nativeBatchWriteMemory(
         addrs: LongArray,
         dataArray: Array<ByteArray>
     ): BooleanArray
 
+    private external fun nativeAnalyzeMemoryLayout(
+        addr: Long,
+        size: Int,
+        maxStride: Int,
+        bigEndian: Boolean
+    ): MemoryLayoutAnalysis
+    private external fun nativeDissectStructure(addr: Long, size: Int): Array<DissectedSlot>
+
     private external fun nativeGetAvailableDrivers(): Array<DriverInfo>
     private external fun nativeDownloadAndInstallDriver(driverName: String): DriverInstallResult
     private external fun nativeIsDriverInstalled(): Boolean
     private external fun nativeAllowBindProc(packageName: String): Boolean
+    private external fun nativeGetModules(pid: Int): Array<ModuleInfo>
+    private external fun nativeResolveSymbol(module: String, symbol: String): Long
+    private external fun nativeDumpModule(module: String, outputPath: String): ModuleDumpResult
+    private external fun nativeGetThreads(): Array<ThreadInfo>
+    private external fun nativeGetThreadRegisters(tid: Int): ThreadRegisters
+    private external fun nativeDumpMemory(addr: Long, size: Long, path: String): Boolean
+    private external fun nativeDumpRegions(ranges: LongArray, path: String): Boolean
 }
\ No newline at end of file
@@ -411,6 +411,8 @@ class SettingsController(
                 context.getString(R.string.settings_memory_rw_mode_nocache),
                 context.getString(R.string.settings_memory_rw_mode_normal),
                 context.getString(R.string.settings_memory_rw_mode_pgfault),
+                context.getString(R.string.settings_memory_rw_mode_process_vm),
+                context.getString(R.string.settings_memory_rw_mode_proc_mem),
             )
         }
 
@@ -434,6 +436,8 @@ class SettingsController(
             2 -> context.getString(R.string.settings_memory_rw_mode_nocache)
             3 -> context.getString(R.string.settings_memory_rw_mode_normal)
             4 -> context.getString(R.string.settings_memory_rw_mode_pgfault)
+            5 -> context.getString(R.string.settings_memory_rw_mode_process_vm)
+            6 -> context.getString(R.string.settings_memory_rw_mode_proc_mem)
             else -> context.getString(R.string.settings_memory_rw_mode_normal)
         }
         binding.memoryRwModeValue.text = text
@@ -53,6 +53,15 @@ enum class DisplayValueType(
         nativeId = 5,
         memorySize = 8,
     ),
+    VEC3(
+        code = "V",
+        displayName = "Vec3 (3 × Float)",
+        rangeDescription = "输入三个浮点数，用 | 分隔，如 1.5|2|0.25",
+        iconRes = R.drawable.type_float_24px,
+        textColor = 0xFFE8B86B.toInt(),
+        nativeId = 8,
+        memorySize = 12,
+    ),
     WORD(
         code = "W",
         displayName = "Word (-32,768 - 65,535)",
@@ -57,6 +57,20 @@ enum class MemoryRange(
             return entries.map { it.displayName }.toTypedArray()
         }
 
+        /**
+         * 转换为 native 区域掩码，第 n 位对应 ordinal 为 n 的类型（与 Rust RegionType 顺序一致）
+         */
+        fun toMask(ranges: Collection<MemoryRange>): Long {
+            return ranges.fold(0L) { mask, range -> mask or (1L shl range.ordinal) }
+        }
+
+        /**
+         * 从 native 区域掩码还原，[toMask] 的逆操作
+         */
+        fun fromMask(mask: Long): Set<MemoryRange> {
+            return entries.filter { mask and (1L shl it.ordinal) != 0L }.toSet()
+        }
+
         /**
          * 获取所有代码
          */
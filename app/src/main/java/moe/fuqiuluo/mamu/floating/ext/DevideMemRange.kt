@@ -83,36 +83,56 @@ fun Array<MemRegionEntry>.divideToSimpleMemoryRange(): List<DisplayMemRegionEntr
     return classified
 }
 
+private fun isArtJitRegion(name: String): Boolean {
+    return name.contains("jit-cache") ||
+            name.contains("jit-code-cache") ||
+            name.contains("jit-zygote-cache") ||
+            name.contains("dalvik-jit")
+}
+
+private fun isArtOatFile(name: String): Boolean {
+    return name.endsWith(".oat") ||
+            name.endsWith(".odex") ||
+            name.contains(".oat (del") ||
+            name.contains(".odex (del")
+}
+
 private fun classifyRegion(entry: MemRegionEntry, procName: String): DisplayMemRegionEntry? {
     if (entry.start == entry.end) {
         return null
     }
 
     val range = run {
-        if (!entry.isWritable && entry.isExecutable) {
-            if (entry.name.contains(procName)) {
-                return@run MemoryRange.Xa
-            }
-
-            // OAT files: executable segments only
-            if ((entry.name.endsWith(".oat") && entry.name.startsWith("/data/misc"))) {
-                if (entry.isExecutable)
-                    return@run MemoryRange.Oa
+        // JIT code cache: ART maps the same memfd twice, an executable view for code
+        // and a writable view for data/profiling info. Only the executable view is code.
+        if (isArtJitRegion(entry.name)) {
+            return@run if (entry.isExecutable) MemoryRange.Jc else MemoryRange.J
+        }
 
+        // AOT compiled code: executable segments of .oat/.odex files
+        // (boot image in /system, /apex or /data/misc, app odex under /data/app/.../oat/)
+        // Non-executable .odex segments fall through to the DEX classification below.
+        if (isArtOatFile(entry.name)) {
+            if (entry.isExecutable) {
+                return@run MemoryRange.Oa
+            }
+            if (entry.name.endsWith(".oat") || entry.name.contains(".oat (del")) {
                 return@run MemoryRange.J
             }
+        }
 
-            // JIT code cache: shared + executable
-            if ((entry.name.contains("jit-cache") ||
-                        entry.name.contains("jit-code-cache") ||
-                        entry.name.contains("dalvik-jit")) &&
-                (entry.isExecutable || entry.isShared)
-            ) {
-                return@run MemoryRange.Jc
+        // Boot/app images (.art): ArtMethod and mirror::Object data, not code
+        if (entry.name.endsWith(".art") || entry.name.contains(".art (del")) {
+            return@run MemoryRange.J
+        }
+
+        if (!entry.isWritable && entry.isExecutable) {
+            if (entry.name.contains(procName)) {
+                return@run MemoryRange.Xa
             }
 
             if (entry.name.contains("/data/")) {
-                MemoryRange.Xa
+                return@run MemoryRange.Xa
             }
 
             return@run MemoryRange.Xs
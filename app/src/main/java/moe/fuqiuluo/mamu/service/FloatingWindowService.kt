@@ -43,6 +43,7 @@ import moe.fuqiuluo.mamu.databinding.FloatingFullscreenLayoutBinding
 import moe.fuqiuluo.mamu.databinding.FloatingWindowLayoutBinding
 import moe.fuqiuluo.mamu.driver.FreezeManager
 import moe.fuqiuluo.mamu.driver.ProcessDeathMonitor
+import moe.fuqiuluo.mamu.driver.ScanProfiles
 import moe.fuqiuluo.mamu.driver.WuwaDriver
 import moe.fuqiuluo.mamu.floating.FloatingWindowStateManager
 import moe.fuqiuluo.mamu.floating.adapter.ProcessListAdapter
@@ -370,6 +371,11 @@ class FloatingWindowService : Service(), ProcessDeathMonitor.Callback {
             
             // 启动冻结管理器
             FreezeManager.start()
+
+            // 应用该包保存的扫描配置
+            ScanProfiles.getActiveProfile()?.let { profile ->
+                MMKV.defaultMMKV().selectedMemoryRanges = profile.ranges
+            }
         }.onFailure {
             it.printStackTrace()
             notification.showError(
@@ -463,15 +469,12 @@ class FloatingWindowService : Service(), ProcessDeathMonitor.Callback {
                 val filterLinux = mmkv.filterLinuxProcess
 
                 val processList = withContext(Dispatchers.IO) {
-                    WuwaDriver.listProcessesWithInfo().filter { process ->
-                        when {
-                            filterSystem && ApplicationUtils.isSystemApp(
-                                this@FloatingWindowService, process.uid
-                            ) -> false
-
-                            filterLinux && process.uid < 1000 -> false
-                            else -> true
-                        }
+                    WuwaDriver.listProcessesFiltered(
+                        minUid = if (filterLinux) 1000 else 0
+                    ).filter { process ->
+                        !(filterSystem && ApplicationUtils.isSystemApp(
+                            this@FloatingWindowService, process.uid
+                        ))
                     }.map { process ->
                         when {
                             process.name.isEmpty() || ApplicationUtils.isSystemApp(
@@ -98,6 +98,18 @@ object ValueTypeUtils {
                     .array()
             }
 
+            DisplayValueType.VEC3 -> {
+                val components = formattedExpr.split('|').map {
+                    it.trim().toFloatOrNull() ?: throw IllegalArgumentException("Invalid vec3 value: $formattedExpr")
+                }
+                if (components.size != 3) {
+                    throw IllegalArgumentException("Vec3 value must have 3 components: $formattedExpr")
+                }
+                val buffer = ByteBuffer.allocate(12).order(ByteOrder.LITTLE_ENDIAN)
+                components.forEach { buffer.putFloat(it) }
+                return buffer.array()
+            }
+
             DisplayValueType.XOR -> {
                 // XOR type uses same format as DWORD but indicates XOR encryption context
                 val value = formattedExpr.toLongOrNull()
@@ -186,6 +198,12 @@ object ValueTypeUtils {
                 } else ""
             }
 
+            DisplayValueType.VEC3 -> {
+                if (bytes.size >= 12) {
+                    List(3) { "%.6g".format(buffer.float) }.joinToString("|")
+                } else ""
+            }
+
             DisplayValueType.UTF_8 -> {
                 String(bytes, Charsets.UTF_8)
             }
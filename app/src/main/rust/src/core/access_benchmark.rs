@@ -0,0 +1,126 @@
+//! 内存访问模式测速
+//!
+//! 不同内核/SoC 上各访问模式的速度差异很大，这里在绑定进程中选一块可读写的匿名内存，
+//! 依次切换到每个可用模式，测顺序读取的吞吐和随机 8 字节读取的延迟。
+//! 测完恢复原模式，或切换到吞吐最高的模式。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::memory_mode::MemoryAccessMode;
+use crate::core::region_type::query_mem_regions;
+use crate::wuwa::{MEM_READABLE, MEM_SHARED, MEM_WRITABLE, WUWA_CAP_PHYSICAL};
+use anyhow::{anyhow, Result};
+use log::info;
+use std::time::{Duration, Instant};
+
+/// 测试缓冲区大小
+const BENCH_SIZE: usize = 1024 * 1024;
+/// 单次读取大小，BindProc 单次最多 64KB
+const CHUNK_SIZE: usize = 64 * 1024;
+/// 每个模式顺序读取的时间预算
+const THROUGHPUT_BUDGET: Duration = Duration::from_millis(200);
+/// 随机读取次数
+const LATENCY_SAMPLES: usize = 256;
+
+/// 一个模式的测速结果
+#[derive(Debug, Clone)]
+pub struct AccessModeBenchmark {
+    pub mode: MemoryAccessMode,
+    /// 该模式下读取失败时为 false，其余字段无意义
+    pub available: bool,
+    /// 顺序读取吞吐，MB/s
+    pub throughput: f64,
+    /// 随机 8 字节读取的平均延迟，纳秒
+    pub latency_ns: u64,
+}
+
+/// 当前后端可用的模式：驱动上是全部驱动模式加用户态模式，其他后端只有用户态模式
+fn candidate_modes(manager: &DriverManager) -> Vec<MemoryAccessMode> {
+    let mut modes = Vec::new();
+    if let Some(driver) = manager.get_driver() {
+        if driver.capabilities().supports(WUWA_CAP_PHYSICAL) {
+            modes.push(MemoryAccessMode::None);
+        }
+        if manager.get_bound_process().is_some() {
+            modes.extend([MemoryAccessMode::NonCacheable, MemoryAccessMode::WriteThrough, MemoryAccessMode::Normal]);
+        }
+        modes.push(MemoryAccessMode::PageFault);
+    }
+    modes.extend([MemoryAccessMode::ProcessVm, MemoryAccessMode::ProcMem]);
+    modes
+}
+
+/// 选一块私有可读写映射作为测试缓冲区，优先匿名内存，返回 (起点, 长度)
+fn pick_buffer(manager: &DriverManager) -> Result<(u64, usize)> {
+    let regions = query_mem_regions(manager, manager.get_bound_pid())?;
+    regions
+        .iter()
+        .filter(|region| region.flags & (MEM_READABLE | MEM_WRITABLE) == MEM_READABLE | MEM_WRITABLE && region.flags & MEM_SHARED == 0)
+        .filter(|region| region.end - region.start >= CHUNK_SIZE as u64)
+        .max_by_key(|region| (region.name.is_empty() || region.name.starts_with("[anon:"), (region.end - region.start).min(BENCH_SIZE as u64)))
+        .map(|region| (region.start, ((region.end - region.start) as usize).min(BENCH_SIZE)))
+        .ok_or_else(|| anyhow!("No suitable readable region to benchmark"))
+}
+
+fn measure(manager: &DriverManager, start: u64, len: usize) -> Option<(f64, u64)> {
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    // 预读一遍，排除缺页换入的影响
+    manager.read_memory_unified(start, &mut chunk[..CHUNK_SIZE.min(len)], None).ok()?;
+
+    let mut total = 0usize;
+    let begin = Instant::now();
+    while begin.elapsed() < THROUGHPUT_BUDGET {
+        for offset in (0..len).step_by(CHUNK_SIZE) {
+            let size = CHUNK_SIZE.min(len - offset);
+            manager.read_memory_unified(start + offset as u64, &mut chunk[..size], None).ok()?;
+            total += size;
+        }
+    }
+    let throughput = total as f64 / (1024.0 * 1024.0) / begin.elapsed().as_secs_f64();
+
+    let mut word = [0u8; 8];
+    let slots = (len / 8) as u64;
+    let begin = Instant::now();
+    for i in 0..LATENCY_SAMPLES as u64 {
+        // 乘法散列打乱访问顺序，避免顺序预取
+        let slot = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) % slots;
+        manager.read_memory_unified(start + slot * 8, &mut word, None).ok()?;
+    }
+    let latency_ns = (begin.elapsed().as_nanos() / LATENCY_SAMPLES as u128) as u64;
+
+    Some((throughput, latency_ns))
+}
+
+/// 依次测试每个可用模式
+///
+/// `auto_select` 为 true 时切换到吞吐最高的可用模式，否则恢复原模式。
+pub fn benchmark_access_modes(manager: &mut DriverManager, auto_select: bool) -> Result<Vec<AccessModeBenchmark>> {
+    if !manager.is_process_bound() {
+        return Err(anyhow!("No process bound"));
+    }
+    let (start, len) = pick_buffer(manager)?;
+    let original = manager.get_access_mode();
+
+    let mut results = Vec::new();
+    for mode in candidate_modes(manager) {
+        let measured = manager.set_access_mode(mode).ok().and_then(|_| measure(manager, start, len));
+        let (throughput, latency_ns) = measured.unwrap_or_default();
+        info!("Benchmark {:?}: {:.1} MB/s, {} ns", mode, throughput, latency_ns);
+        results.push(AccessModeBenchmark {
+            mode,
+            available: measured.is_some(),
+            throughput,
+            latency_ns,
+        });
+    }
+
+    let fastest = results
+        .iter()
+        .filter(|result| result.available)
+        .max_by(|a, b| a.throughput.total_cmp(&b.throughput))
+        .map(|result| result.mode);
+    match fastest {
+        Some(mode) if auto_select => manager.set_access_mode(mode)?,
+        _ => manager.set_access_mode(original)?,
+    }
+    Ok(results)
+}
@@ -0,0 +1,137 @@
+//! Aob - 特征码（Array of Bytes）匹配
+//!
+//! 特征码为十六进制字节序列，`??` 表示通配字节，空格可省略，例如 `1F 20 03 D5 ?? ?? 00 94`。
+//! 用作替换内容时，通配字节保留目标地址的原始值。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
+use crate::wuwa::PageStatusBitmap;
+use anyhow::{anyhow, Result};
+use memchr::memchr_iter;
+
+/// 单次读取的块大小
+const AOB_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 特征码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AobPattern {
+    bytes: Vec<u8>,
+    /// true 表示该字节需要匹配，false 为通配
+    fixed: Vec<bool>,
+}
+
+impl AobPattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let compact: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.is_empty() || !compact.len().is_multiple_of(2) {
+            return Err(anyhow!("Invalid AOB pattern: {:?}", pattern));
+        }
+
+        let mut bytes = Vec::with_capacity(compact.len() / 2);
+        let mut fixed = Vec::with_capacity(compact.len() / 2);
+        for pair in compact.chunks(2) {
+            if pair == ['?', '?'] {
+                bytes.push(0);
+                fixed.push(false);
+                continue;
+            }
+            let text: String = pair.iter().collect();
+            let byte = u8::from_str_radix(&text, 16).map_err(|_| anyhow!("Invalid AOB byte {:?} in {:?}", text, pattern))?;
+            bytes.push(byte);
+            fixed.push(true);
+        }
+
+        Ok(Self { bytes, fixed })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// 是否全部为通配字节
+    pub fn is_wildcard_only(&self) -> bool {
+        !self.fixed.iter().any(|&f| f)
+    }
+
+    /// `data` 开头是否匹配
+    #[inline]
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.len() >= self.bytes.len() && self.bytes.iter().zip(&self.fixed).zip(data).all(|((&b, &f), &d)| !f || b == d)
+    }
+
+    /// 在 `data` 中查找所有匹配的偏移
+    pub fn find_all(&self, data: &[u8]) -> Vec<usize> {
+        let Some(anchor) = self.fixed.iter().position(|&f| f) else {
+            return Vec::new();
+        };
+        if data.len() < self.bytes.len() {
+            return Vec::new();
+        }
+
+        // 用第一个确定字节做 memchr 锚点
+        let last_start = data.len() - self.bytes.len();
+        memchr_iter(self.bytes[anchor], &data[anchor..=last_start + anchor])
+            .filter(|&offset| self.matches(&data[offset..]))
+            .collect()
+    }
+
+    /// 以 `original` 为底生成替换后的字节，通配位置保留原值
+    pub fn apply_to(&self, original: &[u8]) -> Vec<u8> {
+        self.bytes
+            .iter()
+            .zip(&self.fixed)
+            .zip(original)
+            .map(|((&b, &f), &o)| if f { b } else { o })
+            .collect()
+    }
+}
+
+/// 在 `ranges` 中查找特征码，返回按地址排序的匹配地址，最多 `limit` 个（0 表示不限）
+pub fn find_pattern(manager: &DriverManager, pattern: &AobPattern, ranges: &[(u64, u64)], limit: usize) -> Vec<u64> {
+    let page_size = *PAGE_SIZE;
+    let overlap = pattern.len() - 1;
+    let mut found = Vec::new();
+    let mut buffer = vec![0u8; AOB_CHUNK_SIZE + overlap];
+
+    for &(start, end) in ranges {
+        let mut current = start & !(page_size as u64 - 1);
+        while current < end {
+            let chunk_end = (current + AOB_CHUNK_SIZE as u64).min(end);
+            // 多读 overlap 字节，避免漏掉跨块的匹配
+            let read_end = (chunk_end + overlap as u64).min(end);
+            let read_len = (read_end - current) as usize;
+
+            let mut page_status = PageStatusBitmap::new(read_len, current as usize);
+            let read_ok = {
+                let _op = OP_QUEUE.bulk();
+                manager.read_memory_unified(current, &mut buffer[..read_len], Some(&mut page_status)).is_ok()
+            };
+
+            if read_ok && page_status.success_count() > 0 {
+                for offset in pattern.find_all(&buffer[..read_len]) {
+                    let addr = current + offset as u64;
+                    if addr < start || addr >= chunk_end {
+                        continue;
+                    }
+                    // 匹配范围内的页都必须读取成功
+                    let first_page = offset / page_size;
+                    let last_page = (offset + pattern.len() - 1) / page_size;
+                    if (first_page..=last_page).all(|page| page_status.is_page_success(page)) {
+                        found.push(addr);
+                        if limit != 0 && found.len() >= limit {
+                            return found;
+                        }
+                    }
+                }
+            }
+
+            current = chunk_end;
+        }
+    }
+
+    found
+}
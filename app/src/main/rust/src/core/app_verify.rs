@@ -0,0 +1,209 @@
+//! 宿主应用校验
+//!
+//! 设置驱动 fd 时确认调用方是本应用，防止 so 被其他进程加载后直接驱动内核模块。
+//! 策略在构建时配置：
+//! - `MAMU_SIGNER_SHA256`：逗号分隔的签名证书 SHA-256（与 `apksigner verify --print-certs` 输出一致），
+//!   配置后从 `/proc/self/maps` 找到本应用安装目录下的 base.apk，解析 v2/v3 签名块比对第一个签名证书，不再看进程名
+//! - `MAMU_PACKAGE_NAME`：本应用包名，用于定位安装目录（`/data/app/[~~xxx/]<包名>-xxx/base.apk`），
+//!   默认为原包名。只认这个目录，WebView 等其他 APK 的映射或注入代码另行映射的 APK 不算数
+//! - `MAMU_PROCESS_NAMES`：逗号分隔的进程名子串，未配置签名时使用，默认为原包名
+//!
+//! 改名或重新打包的构建配置自己的签名或包名即可。
+
+use crate::wuwa::WuWaDriver;
+use anyhow::{anyhow, Result};
+use log::debug;
+use nix::libc;
+use obfstr::obfstr as s;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+const APK_SIG_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+const APK_SIGNATURE_SCHEME_V2_ID: u32 = 0x7109_871a;
+const APK_SIGNATURE_SCHEME_V3_ID: u32 = 0xf053_68c0;
+/// End of Central Directory 记录的最小长度
+const EOCD_SIZE: usize = 22;
+/// EOCD 注释的最大长度
+const MAX_COMMENT_SIZE: usize = 0xFFFF;
+/// 签名块的大小上限
+const MAX_SIG_BLOCK_SIZE: u64 = 16 * 1024 * 1024;
+
+fn parse_hex_digest(text: &str) -> Option<[u8; 32]> {
+    let hex: Vec<u8> = text.bytes().filter(|b| b.is_ascii_hexdigit()).collect();
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (i, pair) in hex.chunks_exact(2).enumerate() {
+        digest[i] = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(digest)
+}
+
+fn allowed_signers() -> Vec<[u8; 32]> {
+    option_env!("MAMU_SIGNER_SHA256")
+        .unwrap_or("")
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(parse_hex_digest)
+        .collect()
+}
+
+fn allowed_process_names() -> Vec<String> {
+    match option_env!("MAMU_PROCESS_NAMES") {
+        Some(names) if !names.trim().is_empty() => names.split(',').map(|name| name.trim().to_string()).filter(|name| !name.is_empty()).collect(),
+        _ => vec![s!("fuqiuluo").to_string()],
+    }
+}
+
+fn own_package_name() -> String {
+    match option_env!("MAMU_PACKAGE_NAME") {
+        Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => s!("moe.fuqiuluo.mamu").to_string(),
+    }
+}
+
+/// `path` 是否为包 `package` 安装目录下的 base.apk
+fn is_package_apk(path: &str, package: &str) -> bool {
+    let Some(dir) = path.strip_prefix(s!("/data/app/")).and_then(|rest| rest.strip_suffix(s!("/base.apk"))) else {
+        return false;
+    };
+    // Android 11 起多一层随机目录
+    let dir = dir.rsplit('/').next().unwrap_or(dir);
+    dir == package || dir.strip_prefix(package).is_some_and(|suffix| suffix.starts_with('-'))
+}
+
+/// 本应用安装目录下的 base.apk 在本进程中的映射路径
+fn own_apk_path() -> Result<String> {
+    let package = own_package_name();
+    let maps = std::fs::read_to_string(s!("/proc/self/maps"))?;
+    maps.lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .find(|path| is_package_apk(path, &package))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("APK mapping not found"))
+}
+
+/// 依次取出 `u32` 长度前缀的片段
+struct LengthPrefixed<'a>(&'a [u8]);
+
+impl<'a> Iterator for LengthPrefixed<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let len = u32::from_le_bytes(self.0.get(..4)?.try_into().ok()?) as usize;
+        let end = 4usize.checked_add(len)?;
+        let item = self.0.get(4..end)?;
+        self.0 = &self.0[end..];
+        Some(item)
+    }
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// APK v2/v3 签名块中第一个签名者的第一个证书（DER）
+fn signing_certificate(path: &str) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    if file_size < EOCD_SIZE as u64 {
+        return Err(anyhow!("APK is too small"));
+    }
+
+    // 从末尾找 EOCD，取中央目录偏移
+    let tail_len = (EOCD_SIZE + MAX_COMMENT_SIZE).min(file_size as usize);
+    let tail = read_at(&mut file, file_size - tail_len as u64, tail_len)?;
+    let eocd = (0..=tail_len - EOCD_SIZE)
+        .rev()
+        .find(|&i| tail[i..i + 4] == [0x50, 0x4b, 0x05, 0x06])
+        .ok_or_else(|| anyhow!("End of central directory not found"))?;
+    let cd_offset = u32::from_le_bytes(tail[eocd + 16..eocd + 20].try_into()?) as u64;
+
+    // 签名块紧贴中央目录之前，以 size + magic 结尾
+    if cd_offset < 24 {
+        return Err(anyhow!("APK is not signed with scheme v2+"));
+    }
+    let footer = read_at(&mut file, cd_offset - 24, 24)?;
+    if &footer[8..] != APK_SIG_BLOCK_MAGIC {
+        return Err(anyhow!("APK is not signed with scheme v2+"));
+    }
+    let block_size = u64::from_le_bytes(footer[..8].try_into()?);
+    if !(24..=MAX_SIG_BLOCK_SIZE).contains(&block_size) || block_size + 8 > cd_offset {
+        return Err(anyhow!("Invalid APK signing block size"));
+    }
+    // 去掉开头的 size 和结尾的 size + magic，剩下 ID-value 对
+    let pairs = read_at(&mut file, cd_offset - block_size, (block_size - 24) as usize)?;
+
+    let mut values = Vec::new();
+    let mut rest = pairs.as_slice();
+    while rest.len() >= 12 {
+        let len = u64::from_le_bytes(rest[..8].try_into()?) as usize;
+        let end = 8usize.checked_add(len).ok_or_else(|| anyhow!("Invalid APK signing block entry size"))?;
+        let Some(pair) = rest.get(8..end).filter(|pair| pair.len() >= 4) else {
+            break;
+        };
+        values.push((u32::from_le_bytes(pair[..4].try_into()?), &pair[4..]));
+        rest = &rest[end..];
+    }
+
+    // v3 优先，签名轮换后 v3 中是当前证书
+    let value = [APK_SIGNATURE_SCHEME_V3_ID, APK_SIGNATURE_SCHEME_V2_ID]
+        .iter()
+        .find_map(|id| values.iter().find(|(pair_id, _)| pair_id == id))
+        .map(|(_, value)| *value)
+        .ok_or_else(|| anyhow!("No v2/v3 signature found"))?;
+
+    // signers -> signer -> signed data -> (digests, certificates) -> certificate
+    let signer = LengthPrefixed(value).next().and_then(|signers| LengthPrefixed(signers).next());
+    let signed_data = signer.and_then(|signer| LengthPrefixed(signer).next());
+    let certificate = signed_data
+        .and_then(|data| LengthPrefixed(data).nth(1))
+        .and_then(|certificates| LengthPrefixed(certificates).next())
+        .ok_or_else(|| anyhow!("Malformed APK signature"))?;
+    Ok(certificate.to_vec())
+}
+
+/// 按构建配置的策略校验当前进程
+pub fn verify_host_app(driver: &WuWaDriver) -> Result<()> {
+    let signers = allowed_signers();
+    if !signers.is_empty() {
+        let certificate = own_apk_path().and_then(|path| signing_certificate(&path))?;
+        let digest: [u8; 32] = Sha256::digest(&certificate).into();
+        if !signers.contains(&digest) {
+            return Err(anyhow!("Signing certificate verification failed"));
+        }
+        debug!("{}", s!("签名证书校验通过"));
+        return Ok(());
+    }
+
+    let proc_info = driver
+        .get_process_info(unsafe { libc::getpid() })
+        .map_err(|_| anyhow!("Failed to get process info"))?;
+    let end = proc_info.name.iter().position(|&c| c == 0).unwrap_or(proc_info.name.len());
+    let cmdline = String::from_utf8_lossy(&proc_info.name[..end]);
+    if !allowed_process_names().iter().any(|name| cmdline.contains(name.as_str())) {
+        return Err(anyhow!("Current process name verification failed"));
+    }
+    debug!("{}: {}", s!("驱动初始化成功，当前进程名称"), cmdline);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_package_apk() {
+        let package = "moe.fuqiuluo.mamu";
+        assert!(is_package_apk("/data/app/~~AbC==/moe.fuqiuluo.mamu-XyZ==/base.apk", package));
+        assert!(is_package_apk("/data/app/moe.fuqiuluo.mamu-1/base.apk", package));
+        assert!(!is_package_apk("/data/app/~~AbC==/com.google.android.trichromelibrary_1-XyZ==/base.apk", package));
+        assert!(!is_package_apk("/data/app/~~AbC==/moe.fuqiuluo.mamu2-XyZ==/base.apk", package));
+        assert!(!is_package_apk("/data/local/tmp/moe.fuqiuluo.mamu-1/base.apk", package));
+    }
+}
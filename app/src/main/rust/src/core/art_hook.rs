@@ -0,0 +1,220 @@
+//! ArtHookManager - ART Java 方法入口替换
+//!
+//! 通过改写目标进程中 ArtMethod 的 `entry_point_from_quick_compiled_code_`，
+//! 让 Java 方法调用跳到调用方准备好的 trampoline，无需注入完整的 hook 框架。
+//!
+//! ArtMethod 地址需要调用方自行定位（例如通过指针扫描或 jmethodID），
+//! trampoline 代码也需要事先写入目标进程的可执行区域（可用 [`build_branch_stub`] 生成跳板）。
+
+use crate::core::globals::DRIVER_MANAGER;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use std::collections::HashMap;
+
+/// ArtMethod.access_flags_ 偏移（所有支持的版本一致）
+const ACCESS_FLAGS_OFFSET: u64 = 4;
+
+/// 阻止 JIT 重新编译并覆盖入口
+const K_ACC_COMPILE_DONT_BOTHER_O: u32 = 0x0100_0000;
+const K_ACC_COMPILE_DONT_BOTHER: u32 = 0x0200_0000;
+/// 解释器间直接调用会绕过入口点（Android 10+）
+const K_ACC_FAST_INTERPRETER_TO_INTERPRETER_INVOKE: u32 = 0x4000_0000;
+/// 预编译标记，ART 会据此恢复入口（Android 11 / 12+ 取值不同）
+const K_ACC_PRE_COMPILED_R: u32 = 0x0020_0000;
+const K_ACC_PRE_COMPILED: u32 = 0x0080_0000;
+
+/// 按 Android SDK 版本确定的 ArtMethod 布局（arm64）
+#[derive(Debug, Clone, Copy)]
+pub struct ArtMethodLayout {
+    /// PtrSizedFields.data_ 偏移
+    pub data_offset: u64,
+    /// PtrSizedFields.entry_point_from_quick_compiled_code_ 偏移
+    pub entry_point_offset: u64,
+    sdk_int: i32,
+}
+
+impl ArtMethodLayout {
+    /// 支持 Android 8.0 (26) 及以上
+    pub fn for_sdk(sdk_int: i32) -> Result<Self> {
+        match sdk_int {
+            // 8.0 仍保留 dex_cache_resolved_methods_
+            26 => Ok(Self { data_offset: 24, entry_point_offset: 32, sdk_int }),
+            27.. => Ok(Self { data_offset: 16, entry_point_offset: 24, sdk_int }),
+            _ => Err(anyhow!("Unsupported SDK level for ART hook: {}", sdk_int)),
+        }
+    }
+
+    /// hook 后需要设置的 access_flags
+    fn hooked_flags(&self, flags: u32) -> u32 {
+        let mut flags = flags;
+        flags |= if self.sdk_int == 26 { K_ACC_COMPILE_DONT_BOTHER_O } else { K_ACC_COMPILE_DONT_BOTHER };
+        if self.sdk_int >= 29 {
+            flags &= !K_ACC_FAST_INTERPRETER_TO_INTERPRETER_INVOKE;
+        }
+        if self.sdk_int == 30 {
+            flags &= !K_ACC_PRE_COMPILED_R;
+        } else if self.sdk_int >= 31 {
+            flags &= !K_ACC_PRE_COMPILED;
+        }
+        flags
+    }
+}
+
+/// 从目标进程读出的 ArtMethod 关键字段
+#[derive(Debug, Clone, Copy)]
+pub struct ArtMethodInfo {
+    pub access_flags: u32,
+    pub data: u64,
+    pub entry_point: u64,
+}
+
+/// 已安装的 hook
+#[derive(Debug, Clone, Copy)]
+pub struct ArtHookEntry {
+    /// 原始入口点，可供 trampoline 回调原方法
+    pub original_entry: u64,
+    /// 原始 access_flags，卸载时恢复
+    pub original_flags: u32,
+    pub trampoline: u64,
+    layout: ArtMethodLayout,
+}
+
+/// 生成 16 字节的 arm64 绝对跳转：`ldr x17, #8; br x17; .quad target`
+///
+/// x17 (IP1) 是过程间临时寄存器，ART 的 quick 调用约定不会用它传参。
+pub fn build_branch_stub(target: u64) -> [u8; 16] {
+    let mut stub = [0u8; 16];
+    stub[0..4].copy_from_slice(&0x5800_0051u32.to_le_bytes());
+    stub[4..8].copy_from_slice(&0xD61F_0220u32.to_le_bytes());
+    stub[8..16].copy_from_slice(&target.to_le_bytes());
+    stub
+}
+
+fn read_u32(addr: u64) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    manager.read_memory_unified(addr, &mut buf, None)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(addr: u64) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    manager.read_memory_unified(addr, &mut buf, None)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn write_bytes(addr: u64, buf: &[u8]) -> Result<()> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    manager.write_memory_unified(addr, buf)
+}
+
+/// 在 `cave` 处写入跳转到 `target` 的 [`build_branch_stub`]，返回 `cave` 作为 trampoline 地址
+pub fn write_branch_stub(cave: u64, target: u64) -> Result<u64> {
+    write_bytes(cave, &build_branch_stub(target))?;
+    Ok(cave)
+}
+
+/// 读取 ArtMethod 的 access_flags / data_ / 入口点
+pub fn inspect_art_method(method: u64, layout: &ArtMethodLayout) -> Result<ArtMethodInfo> {
+    Ok(ArtMethodInfo {
+        access_flags: read_u32(method + ACCESS_FLAGS_OFFSET)?,
+        data: read_u64(method + layout.data_offset)?,
+        entry_point: read_u64(method + layout.entry_point_offset)?,
+    })
+}
+
+/// ART 方法 hook 管理器
+#[derive(Default)]
+pub struct ArtHookManager {
+    /// ArtMethod 地址 -> hook 条目
+    hooks: HashMap<u64, ArtHookEntry>,
+}
+
+impl ArtHookManager {
+    pub fn new() -> Self {
+        Self { hooks: HashMap::new() }
+    }
+
+    /// 将 `trampoline` 写入为 `method` 的入口点，返回原始入口点
+    pub fn install(&mut self, method: u64, trampoline: u64, sdk_int: i32) -> Result<u64> {
+        if let Some(entry) = self.hooks.get(&method) {
+            return Err(anyhow!("Method 0x{:X} already hooked (trampoline 0x{:X})", method, entry.trampoline));
+        }
+
+        let layout = ArtMethodLayout::for_sdk(sdk_int)?;
+        let info = inspect_art_method(method, &layout)?;
+        if info.entry_point == 0 {
+            return Err(anyhow!("ArtMethod 0x{:X} has no entry point, wrong address or layout?", method));
+        }
+
+        // 先改 flags，避免入口改写后被 JIT 立刻覆盖
+        write_bytes(method + ACCESS_FLAGS_OFFSET, &layout.hooked_flags(info.access_flags).to_le_bytes())?;
+        if let Err(e) = write_bytes(method + layout.entry_point_offset, &trampoline.to_le_bytes()) {
+            let _ = write_bytes(method + ACCESS_FLAGS_OFFSET, &info.access_flags.to_le_bytes());
+            return Err(e);
+        }
+
+        debug!(
+            "ArtHookManager: hook 0x{:X} 入口 0x{:X} -> 0x{:X}",
+            method, info.entry_point, trampoline
+        );
+        self.hooks.insert(
+            method,
+            ArtHookEntry {
+                original_entry: info.entry_point,
+                original_flags: info.access_flags,
+                trampoline,
+                layout,
+            },
+        );
+        Ok(info.entry_point)
+    }
+
+    /// 恢复原始入口点和 access_flags
+    pub fn uninstall(&mut self, method: u64) -> Result<()> {
+        let entry = self.hooks.get(&method).copied().ok_or_else(|| anyhow!("Method 0x{:X} is not hooked", method))?;
+
+        write_bytes(method + entry.layout.entry_point_offset, &entry.original_entry.to_le_bytes())?;
+        write_bytes(method + ACCESS_FLAGS_OFFSET, &entry.original_flags.to_le_bytes())?;
+        self.hooks.remove(&method);
+
+        debug!("ArtHookManager: 已恢复 0x{:X}", method);
+        Ok(())
+    }
+
+    /// 卸载所有 hook，返回成功恢复的数量
+    pub fn uninstall_all(&mut self) -> usize {
+        let methods: Vec<u64> = self.hooks.keys().copied().collect();
+        let mut restored = 0;
+        for method in methods {
+            match self.uninstall(method) {
+                Ok(()) => restored += 1,
+                Err(e) => warn!("ArtHookManager: 恢复 0x{:X} 失败: {}", method, e),
+            }
+        }
+        restored
+    }
+
+    /// trampoline 位于 `ranges`（[起始, 结束)）内的 hook，须在释放对应内存前卸载
+    pub fn hooks_into(&self, ranges: &[(u64, u64)]) -> Vec<u64> {
+        self.hooks
+            .iter()
+            .filter(|(_, entry)| ranges.iter().any(|&(start, end)| (start..end).contains(&entry.trampoline)))
+            .map(|(&method, _)| method)
+            .collect()
+    }
+
+    /// 进程解绑后调用，丢弃记录但不写内存
+    pub fn forget_all(&mut self) {
+        self.hooks.clear();
+    }
+
+    pub fn get(&self, method: u64) -> Option<&ArtHookEntry> {
+        self.hooks.get(&method)
+    }
+
+    pub fn hooked_methods(&self) -> Vec<u64> {
+        self.hooks.keys().copied().collect()
+    }
+}
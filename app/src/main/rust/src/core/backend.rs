@@ -0,0 +1,235 @@
+//! 内存后端
+//!
+//! [`MemoryBackend`] 抽象读写、内存区域、进程信息和绑定，[`DriverManager`](super::driver_manager::DriverManager)
+//! 按名字登记多个后端并选择其中一个使用。内置后端：
+//! - `wuwa`：内核模块，设置驱动 fd 后登记并选中
+//! - `process_vm` / `proc_mem`：用户态，始终可用，未加载驱动时默认选中 `process_vm`
+//!
+//! 驱动专有的能力（物理内存读写、BindProc 的内存类型、远程 mprotect 等）经 [`MemoryBackend::as_wuwa`] 取得驱动，
+//! 其他后端上这些功能报错即可。
+
+use crate::core::globals::PAGE_SIZE;
+use crate::core::memory_mode::MemoryAccessMode;
+use crate::core::region_type::MemRegion;
+use crate::core::user_memory::{proc_name, read_proc_maps, UserMemory, UserMemoryKind};
+use crate::wuwa::{BindProc, DriverCapabilities, WuWaDriver, WuwaGetProcInfoCmd, WuwaMemRegionEntry, WUWA_CAP_PAGE_STATUS};
+use anyhow::{anyhow, Result};
+use log::info;
+use nix::libc::close;
+use nix::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
+use std::num::NonZeroUsize;
+use std::os::fd::BorrowedFd;
+
+/// 内核模块后端的名字
+pub const WUWA_BACKEND: &str = "wuwa";
+pub const PROCESS_VM_BACKEND: &str = "process_vm";
+pub const PROC_MEM_BACKEND: &str = "proc_mem";
+
+pub trait MemoryBackend: Send + Sync {
+    /// 按虚拟地址读取，要求整段读到
+    fn read_memory(&self, pid: i32, addr: u64, buf: &mut [u8]) -> Result<()>;
+
+    /// 按虚拟地址写入，要求整段写入
+    fn write_memory(&self, pid: i32, addr: u64, buf: &[u8]) -> Result<()>;
+
+    /// 进程的全部内存区域
+    fn query_mem_regions(&self, pid: i32) -> Result<Vec<MemRegion>>;
+
+    fn list_processes(&self) -> Vec<i32>;
+
+    fn process_info(&self, pid: i32) -> Result<WuwaGetProcInfoCmd>;
+
+    fn is_process_alive(&self, pid: i32) -> bool;
+
+    /// 绑定进程。返回驱动的 BindProc；没有该概念的后端返回 None，此时按用户态模式读写
+    fn bind_process(&self, pid: i32) -> Result<Option<BindProc>>;
+
+    /// 该后端要求的访问模式，选中时切换过去；None 表示沿用当前设置
+    fn access_mode(&self) -> Option<MemoryAccessMode> {
+        None
+    }
+
+    /// 版本和支持的功能（WUWA_CAP_*），UI 据此禁用不支持的功能
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities { version: 0, features: 0 }
+    }
+
+    /// 后端是 WuWa 驱动时返回它，用于驱动专有的功能
+    fn as_wuwa(&self) -> Option<&WuWaDriver> {
+        None
+    }
+}
+
+pub(crate) fn extract_cstring(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+impl MemoryBackend for WuWaDriver {
+    fn read_memory(&self, pid: i32, addr: u64, buf: &mut [u8]) -> Result<()> {
+        WuWaDriver::read_memory(self, pid, addr as usize, buf.as_mut_ptr() as usize, buf.len()).map(|_| ())
+    }
+
+    fn write_memory(&self, pid: i32, addr: u64, buf: &[u8]) -> Result<()> {
+        WuWaDriver::write_memory(self, pid, buf.as_ptr() as usize, addr as usize, buf.len()).map(|_| ())
+    }
+
+    fn query_mem_regions(&self, pid: i32) -> Result<Vec<MemRegion>> {
+        let result = WuWaDriver::query_mem_regions(self, pid, 0, 0).map_err(|e| anyhow!("Unable to get memory regions for pid {}: {}", pid, e))?;
+
+        info!(
+            "Query memory regions: fd={}, buffer_size={}, entry_count={}",
+            result.fd, result.buffer_size, result.entry_count
+        );
+
+        let borrowed_fd = unsafe { BorrowedFd::borrow_raw(result.fd) };
+        let mapped = NonZeroUsize::new(result.buffer_size)
+            .ok_or_else(|| anyhow!("Invalid buffer size"))
+            .and_then(|size| {
+                unsafe { mmap(None, size, ProtFlags::PROT_READ, MapFlags::MAP_PRIVATE, borrowed_fd, 0) }
+                    .map_err(|e| anyhow!("Failed to mmap memory regions buffer: {}", e))
+            });
+        let mapped_ptr = match mapped {
+            Ok(ptr) => ptr,
+            Err(e) => {
+                unsafe { close(result.fd) };
+                return Err(e);
+            },
+        };
+
+        let entries = mapped_ptr.as_ptr() as *const WuwaMemRegionEntry;
+        let regions = (0..result.entry_count)
+            .map(|i| {
+                let entry = unsafe { &*entries.add(i) };
+                MemRegion {
+                    start: entry.start,
+                    end: entry.end,
+                    flags: entry.type_,
+                    name: extract_cstring(&entry.name),
+                }
+            })
+            .collect();
+
+        unsafe {
+            let _ = munmap(mapped_ptr, result.buffer_size);
+            close(result.fd);
+        }
+
+        Ok(regions)
+    }
+
+    fn list_processes(&self) -> Vec<i32> {
+        WuWaDriver::list_processes(self)
+    }
+
+    fn process_info(&self, pid: i32) -> Result<WuwaGetProcInfoCmd> {
+        WuWaDriver::get_process_info(self, pid)
+    }
+
+    fn is_process_alive(&self, pid: i32) -> bool {
+        WuWaDriver::is_process_alive(self, pid).unwrap_or(false)
+    }
+
+    fn bind_process(&self, pid: i32) -> Result<Option<BindProc>> {
+        WuWaDriver::bind_process(self, pid).map(Some)
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        WuWaDriver::capabilities(self)
+    }
+
+    fn as_wuwa(&self) -> Option<&WuWaDriver> {
+        Some(self)
+    }
+}
+
+/// 基于 [`UserMemory`] 的用户态后端，不需要驱动
+pub struct UserspaceBackend {
+    kind: UserMemoryKind,
+}
+
+impl UserspaceBackend {
+    pub fn new(kind: UserMemoryKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl MemoryBackend for UserspaceBackend {
+    fn read_memory(&self, pid: i32, addr: u64, buf: &mut [u8]) -> Result<()> {
+        UserMemory::open(pid, self.kind)?.read(addr, buf, None)
+    }
+
+    fn write_memory(&self, pid: i32, addr: u64, buf: &[u8]) -> Result<()> {
+        UserMemory::open(pid, self.kind)?.write(addr, buf)
+    }
+
+    fn query_mem_regions(&self, pid: i32) -> Result<Vec<MemRegion>> {
+        read_proc_maps(pid)
+    }
+
+    fn list_processes(&self) -> Vec<i32> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        let mut pids: Vec<i32> = entries.filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok()).collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    fn process_info(&self, pid: i32) -> Result<WuwaGetProcInfoCmd> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).map_err(|e| anyhow!("Unable to read /proc/{}/stat: {}", pid, e))?;
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+
+        // pid (comm) state ppid ...，comm 可能含空格，从最后一个 ')' 之后按字段切分
+        let (head, tail) = stat.rsplit_once(')').ok_or_else(|| anyhow!("Malformed /proc/{}/stat", pid))?;
+        let comm = head.split_once('(').map_or("", |(_, comm)| comm);
+        let fields: Vec<&str> = tail.split_whitespace().collect();
+        let field = |index: usize| fields.get(index).and_then(|value| value.parse::<i64>().ok()).unwrap_or(0);
+        let status_field = |key: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .and_then(|value| value.split_whitespace().next()?.parse::<i64>().ok())
+        };
+
+        let mut info = WuwaGetProcInfoCmd {
+            pid,
+            tgid: status_field("Tgid:").unwrap_or(pid as i64) as i32,
+            name: [0; 256],
+            uid: status_field("Uid:").unwrap_or(0) as u32,
+            ppid: field(1) as i32,
+            prio: field(15) as i32,
+            rss: field(21) as usize * *PAGE_SIZE,
+        };
+        let name = proc_name(pid).unwrap_or_else(|| comm.to_string());
+        let len = name.len().min(info.name.len() - 1);
+        info.name[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Ok(info)
+    }
+
+    fn is_process_alive(&self, pid: i32) -> bool {
+        std::path::Path::new(&format!("/proc/{}", pid)).exists()
+    }
+
+    fn bind_process(&self, pid: i32) -> Result<Option<BindProc>> {
+        if !self.is_process_alive(pid) {
+            return Err(anyhow!("Process {} does not exist", pid));
+        }
+        Ok(None)
+    }
+
+    /// 按页重试读取，可以给出页状态
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            version: 0,
+            features: WUWA_CAP_PAGE_STATUS,
+        }
+    }
+
+    fn access_mode(&self) -> Option<MemoryAccessMode> {
+        Some(match self.kind {
+            UserMemoryKind::ProcessVm => MemoryAccessMode::ProcessVm,
+            UserMemoryKind::ProcMem => MemoryAccessMode::ProcMem,
+        })
+    }
+}
@@ -0,0 +1,115 @@
+//! 后台优先级扫描
+//!
+//! 长时间的扫描（例如指针扫描）会占满 CPU 和驱动通道，边玩游戏边扫描时会掉帧。后台模式下：
+//! - 工作线程设为 SCHED_IDLE，nice 调到 19，只在 CPU 空闲时运行
+//! - 读取带宽限制在 [`BACKGROUND_READ_BYTES_PER_SEC`]
+//! - 每读完一个分块让出 CPU
+//!
+//! 是否使用后台模式由每个任务单独指定：指针扫描见 `PointerScanConfig::background`，
+//! 数值搜索见 `SearchEngineManager::set_background_mode`。
+
+use log::{debug, warn};
+use nix::libc;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 后台模式的读取带宽：64MB/s
+pub const BACKGROUND_READ_BYTES_PER_SEC: u64 = 64 * 1024 * 1024;
+
+/// 后台线程的 nice 值
+pub const BACKGROUND_NICE: i32 = 19;
+
+/// 把当前线程设为 SCHED_IDLE 并调低 nice，失败时只记录日志
+pub fn lower_current_thread_priority() {
+    let tid = nix::unistd::gettid().as_raw();
+    let param = libc::sched_param { sched_priority: 0 };
+
+    if unsafe { libc::sched_setscheduler(tid, libc::SCHED_IDLE, &param) } != 0 {
+        debug!("sched_setscheduler(SCHED_IDLE) failed for thread {}: {}", tid, std::io::Error::last_os_error());
+    }
+    // Linux 上 setpriority 作用于单个线程
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, BACKGROUND_NICE) } != 0 {
+        debug!(
+            "setpriority({}) failed for thread {}: {}",
+            BACKGROUND_NICE,
+            tid,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+thread_local! {
+    /// 后台线程池的工作线程共享的读取带宽限制，见 [`install`]
+    static READ_THROTTLE: RefCell<Option<Arc<ReadThrottle>>> = const { RefCell::new(None) };
+}
+
+/// 后台模式下在每核一个工作线程的专用 rayon 线程池中运行 `op`，否则直接运行。
+///
+/// 工作线程启动时调低优先级，并共享一个 [`BACKGROUND_READ_BYTES_PER_SEC`] 的读取带宽限制：
+/// 它们经 `DriverManager` 发起的读取都会计入，超出时睡眠，每次读取后让出 CPU。
+/// 线程池创建失败时退回全局线程池，只是不再降低优先级。
+pub fn install<R, OP>(background: bool, op: OP) -> R
+where
+    R: Send,
+    OP: FnOnce() -> R + Send,
+{
+    if !background {
+        return op();
+    }
+
+    let throttle = Arc::new(ReadThrottle::new(BACKGROUND_READ_BYTES_PER_SEC));
+    let pool = rayon::ThreadPoolBuilder::new()
+        .thread_name(|i| format!("bg-scan-{}", i))
+        .start_handler(move |_| {
+            lower_current_thread_priority();
+            READ_THROTTLE.set(Some(throttle.clone()));
+        })
+        .build();
+    match pool {
+        Ok(pool) => pool.install(op),
+        Err(e) => {
+            warn!("Failed to build background pool, running at normal priority: {}", e);
+            op()
+        },
+    }
+}
+
+/// 当前线程属于 [`install`] 的后台线程池时，把读取的 `bytes` 字节计入共享的带宽限制
+pub fn throttle_read(bytes: usize) {
+    READ_THROTTLE.with_borrow(|throttle| {
+        if let Some(throttle) = throttle {
+            throttle.consume(bytes);
+        }
+    });
+}
+
+/// 多个线程共享的读取带宽限制
+pub struct ReadThrottle {
+    bytes_per_sec: u64,
+    start: Instant,
+    consumed: AtomicU64,
+}
+
+impl ReadThrottle {
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.max(1),
+            start: Instant::now(),
+            consumed: AtomicU64::new(0),
+        }
+    }
+
+    /// 记录读取了 `bytes` 字节，超出带宽时睡眠到预算恢复，之后让出 CPU
+    pub fn consume(&self, bytes: usize) {
+        let consumed = self.consumed.fetch_add(bytes as u64, Ordering::Relaxed) + bytes as u64;
+        let due = Duration::from_secs_f64(consumed as f64 / self.bytes_per_sec as f64);
+        let elapsed = self.start.elapsed();
+        if due > elapsed {
+            thread::sleep(due - elapsed);
+        }
+        thread::yield_now();
+    }
+}
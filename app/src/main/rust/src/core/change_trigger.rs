@@ -0,0 +1,191 @@
+//! ChangeTrigger - 值变化触发搜索
+//!
+//! 对一个已知地址做高频轮询，值一旦变化立即发起预先配置好的改善搜索。
+//! 用于捕捉伤害数字这类一闪而过的状态，手动点击搜索往往赶不上。
+//!
+//! 轮询在独立线程中进行（tokio 定时器精度只有毫秒级）；触发时如果已有搜索在进行则跳过本次变化。
+
+use crate::core::error::MamuError;
+use crate::core::globals::{DRIVER_MANAGER, OP_QUEUE};
+use crate::search::engine::SEARCH_ENGINE_MANAGER;
+use crate::search::types::{FuzzyCondition, SearchQuery};
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// 监视值的最大字节数
+pub const MAX_TRIGGER_SIZE: usize = 8;
+
+/// 最小轮询间隔（微秒）
+pub const MIN_POLL_INTERVAL_US: u64 = 100;
+
+/// 值变化时执行的操作
+#[derive(Debug, Clone)]
+pub enum TriggerAction {
+    /// 精确值改善搜索
+    Refine(SearchQuery),
+    /// 模糊改善搜索
+    FuzzyRefine(FuzzyCondition),
+}
+
+/// 触发器配置
+#[derive(Debug, Clone)]
+pub struct TriggerConfig {
+    pub address: u64,
+    pub size: usize,
+    pub interval: Duration,
+    pub action: TriggerAction,
+    /// 触发一次后自动解除
+    pub one_shot: bool,
+}
+
+/// 值变化触发器
+pub struct ChangeTrigger {
+    /// 轮询线程是否应继续运行
+    armed: Arc<AtomicBool>,
+    /// 成功发起搜索的次数
+    fire_count: Arc<AtomicU64>,
+    /// 因搜索进行中被跳过的变化次数
+    skipped_count: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Default for ChangeTrigger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangeTrigger {
+    pub fn new() -> Self {
+        Self {
+            armed: Arc::new(AtomicBool::new(false)),
+            fire_count: Arc::new(AtomicU64::new(0)),
+            skipped_count: Arc::new(AtomicU64::new(0)),
+            handle: None,
+        }
+    }
+
+    /// 布置触发器，已布置时先解除旧的
+    pub fn arm(&mut self, config: TriggerConfig) -> Result<()> {
+        if config.size == 0 || config.size > MAX_TRIGGER_SIZE {
+            return Err(anyhow!("Trigger size must be between 1 and {} bytes", MAX_TRIGGER_SIZE));
+        }
+
+        self.disarm();
+
+        // 先读一次作为基准，地址不可读时直接报错
+        let baseline = read_value(config.address, config.size)?;
+
+        self.armed.store(true, Ordering::SeqCst);
+        self.fire_count.store(0, Ordering::Relaxed);
+        self.skipped_count.store(0, Ordering::Relaxed);
+
+        let armed = Arc::clone(&self.armed);
+        let fire_count = Arc::clone(&self.fire_count);
+        let skipped_count = Arc::clone(&self.skipped_count);
+        let interval = config.interval.max(Duration::from_micros(MIN_POLL_INTERVAL_US));
+
+        debug!("ChangeTrigger: 布置 addr=0x{:X}, size={}, interval={:?}", config.address, config.size, interval);
+
+        let handle = std::thread::Builder::new()
+            .name("mamu-change-trigger".into())
+            .spawn(move || {
+                let mut last = baseline;
+                while armed.load(Ordering::SeqCst) {
+                    std::thread::sleep(interval);
+
+                    let current = match read_value(config.address, config.size) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    if current == last {
+                        continue;
+                    }
+                    last = current;
+
+                    match fire(&config.action) {
+                        Ok(true) => {
+                            fire_count.fetch_add(1, Ordering::Relaxed);
+                            debug!("ChangeTrigger: 0x{:X} 值变化，已发起搜索", config.address);
+                            if config.one_shot {
+                                armed.store(false, Ordering::SeqCst);
+                            }
+                        },
+                        Ok(false) => {
+                            skipped_count.fetch_add(1, Ordering::Relaxed);
+                        },
+                        Err(e) => {
+                            warn!("ChangeTrigger: 发起搜索失败，触发器已解除: {}", e);
+                            armed.store(false, Ordering::SeqCst);
+                        },
+                    }
+                }
+                debug!("ChangeTrigger: 轮询线程已退出");
+            })
+            .map_err(|e| anyhow!("Failed to spawn trigger thread: {}", e))?;
+
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// 解除触发器并等待轮询线程退出
+    pub fn disarm(&mut self) {
+        self.armed.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// 是否处于布置状态（单次触发器触发后会自动变为 false）
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::SeqCst)
+    }
+
+    pub fn fire_count(&self) -> u64 {
+        self.fire_count.load(Ordering::Relaxed)
+    }
+
+    pub fn skipped_count(&self) -> u64 {
+        self.skipped_count.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for ChangeTrigger {
+    fn drop(&mut self) {
+        self.disarm();
+    }
+}
+
+fn read_value(address: u64, size: usize) -> Result<[u8; MAX_TRIGGER_SIZE]> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+
+    // 触发时机依赖读取延迟，按交互操作对待
+    let _op = OP_QUEUE.interactive();
+    let mut buf = [0u8; MAX_TRIGGER_SIZE];
+    manager.read_memory_unified(address, &mut buf[..size], None)?;
+    Ok(buf)
+}
+
+/// 发起搜索，已有搜索在进行时返回 Ok(false)
+fn fire(action: &TriggerAction) -> Result<bool> {
+    let mut manager = SEARCH_ENGINE_MANAGER
+        .write()
+        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+    if manager.is_searching() {
+        return Ok(false);
+    }
+
+    match action {
+        TriggerAction::Refine(query) => manager.start_refine_async(query.clone())?,
+        TriggerAction::FuzzyRefine(condition) => manager.start_fuzzy_refine_async(*condition)?,
+    }
+    Ok(true)
+}
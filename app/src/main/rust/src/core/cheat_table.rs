@@ -0,0 +1,597 @@
+//! Cheat Engine 表（.CT）导入导出
+//!
+//! .CT 是 XML，每个 `CheatEntry` 有描述、变量类型、地址表达式和可选的偏移列表，
+//! 分组条目内嵌套 `CheatEntries`。导入时支持：
+//! - 纯十六进制地址 -> [`SavedLocation::Address`]
+//! - `"libfoo.so"+1234` / `libfoo.so+1234` -> 只有根节点的指针链
+//! - 带 `Offsets` 的指针记录 -> 完整指针链（CE 把最后一级偏移写在最前面）
+//!
+//! CE 的模块基址是模块第一个段，导入的链一律 `data_start = true`。
+//! 脚本、字符串、字节数组和带符号/方括号的地址表达式无法对应，导入时跳过。
+
+use crate::core::saved_list::{SavedEntry, SavedLocation};
+use crate::pointer_scan::types::{parse_signed_hex, PointerChain, PointerChainStep, VmStaticData};
+use crate::search::types::ValueType;
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::fmt::Write;
+
+/// 导出时写入的表版本
+const CHEAT_TABLE_VERSION: u32 = 45;
+
+/// 分组嵌套的最大深度，更深的分组整体跳过，避免恶意表耗尽栈
+const MAX_GROUP_DEPTH: usize = 64;
+
+/// 导入结果
+#[derive(Debug, Default)]
+pub struct CheatTableImport {
+    pub entries: Vec<SavedEntry>,
+    /// 无法对应而跳过的条目描述
+    pub skipped: Vec<String>,
+}
+
+/// 解析 .CT 内容，分组条目展开为平铺的列表
+pub fn import_cheat_table(text: &str) -> Result<CheatTableImport> {
+    let root = xml::parse(text)?;
+    if root.name != "CheatTable" {
+        return Err(anyhow!("Not a cheat table: root element is <{}>", root.name));
+    }
+
+    let mut import = CheatTableImport::default();
+    if let Some(entries) = root.child("CheatEntries") {
+        collect_entries(entries, &mut import, 0);
+    }
+    Ok(import)
+}
+
+fn collect_entries(entries: &xml::Element, import: &mut CheatTableImport, depth: usize) {
+    for node in entries.children.iter().filter(|node| node.name == "CheatEntry") {
+        let description = node.child_text("Description").map(unquote).unwrap_or_default();
+        if depth >= MAX_GROUP_DEPTH {
+            debug!("CheatTable: 分组嵌套超过 {} 层，跳过 {}", MAX_GROUP_DEPTH, description);
+            import.skipped.push(description);
+            continue;
+        }
+        // 分组和脚本条目没有地址，只展开子条目
+        if node.child("Address").is_some() || node.child("VariableType").is_some() {
+            match convert_entry(node, &description) {
+                Some(entry) => import.entries.push(entry),
+                None => {
+                    debug!("CheatTable: 跳过条目 {}", description);
+                    import.skipped.push(description);
+                },
+            }
+        }
+        if let Some(children) = node.child("CheatEntries") {
+            collect_entries(children, import, depth + 1);
+        }
+    }
+}
+
+fn convert_entry(node: &xml::Element, description: &str) -> Option<SavedEntry> {
+    let value_type = value_type_from_name(node.child_text("VariableType")?)?;
+    let base = parse_address(node.child_text("Address")?)?;
+
+    let location = match node.child("Offsets") {
+        Some(offsets) => {
+            let AddressExpr::Module { name, offset } = base else {
+                // 绝对地址作根的指针链无法在进程重启后复用
+                return None;
+            };
+            let mut chain = PointerChain::new(0);
+            chain.push(PointerChainStep::static_root(name, 0, offset));
+            // CE 把最后一级偏移写在最前面
+            let offsets: Option<Vec<i64>> = offsets
+                .children
+                .iter()
+                .filter(|child| child.name == "Offset")
+                .map(|child| parse_signed_hex(&child.text))
+                .collect();
+            for offset in offsets?.into_iter().rev() {
+                chain.push(PointerChainStep::dynamic_offset(offset));
+            }
+            SavedLocation::Pointer { chain, data_start: true }
+        },
+        None => match base {
+            AddressExpr::Absolute(address) => SavedLocation::Address { address },
+            AddressExpr::Module { name, offset } => {
+                let mut chain = PointerChain::new(0);
+                chain.push(PointerChainStep::static_root(name, 0, offset));
+                SavedLocation::Pointer { chain, data_start: true }
+            },
+        },
+    };
+
+    let hex = node.child_text("ShowAsHex") == Some("1");
+    let last_state = node.child("LastState");
+    let value = last_state
+        .and_then(|state| state.attr("Value"))
+        .and_then(|text| encode_value(value_type, text, hex))
+        .unwrap_or_default();
+    let frozen = !value.is_empty() && last_state.and_then(|state| state.attr("Activated")) == Some("1");
+
+    Some(SavedEntry {
+        id: 0,
+        label: description.to_string(),
+        location,
+        value_type: value_type.to_id(),
+        value,
+        frozen,
+        hotkey: 0,
+    })
+}
+
+enum AddressExpr {
+    Absolute(u64),
+    Module { name: String, offset: i64 },
+}
+
+/// 解析 `7FAB1234`、`"libfoo.so"+1234`、`libfoo.so+10-4` 形式的地址
+///
+/// 不带引号的模块名可能含 `-`（`lib-foo.so+10`），带扩展名时只在扩展名之后的 `+`/`-` 处切分。
+fn parse_address(text: &str) -> Option<AddressExpr> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(['[', ']', '*']) {
+        return None;
+    }
+
+    let (head, rest) = if let Some(quoted) = text.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        (&quoted[..end], &quoted[end + 1..])
+    } else {
+        let name_end = text.find('.').unwrap_or(0);
+        let end = text[name_end..].find(['+', '-']).map_or(text.len(), |i| name_end + i);
+        (&text[..end], &text[end..])
+    };
+
+    let mut offset = 0i64;
+    let mut rest = rest.trim();
+    while !rest.is_empty() {
+        let end = rest.char_indices().skip(1).find(|(_, c)| *c == '+' || *c == '-').map_or(rest.len(), |(i, _)| i);
+        offset = offset.wrapping_add(parse_signed_hex(&rest[..end])?);
+        rest = rest[end..].trim_start();
+    }
+
+    let head = head.trim();
+    let numeric = head.strip_prefix("0x").or_else(|| head.strip_prefix("0X")).unwrap_or(head);
+    match u64::from_str_radix(numeric, 16) {
+        Ok(address) if !text.starts_with('"') => Some(AddressExpr::Absolute(address.wrapping_add_signed(offset))),
+        _ if head.is_empty() => None,
+        _ => Some(AddressExpr::Module {
+            name: head.to_string(),
+            offset,
+        }),
+    }
+}
+
+fn value_type_from_name(name: &str) -> Option<ValueType> {
+    match name.trim() {
+        "Byte" => Some(ValueType::Byte),
+        "2 Bytes" => Some(ValueType::Word),
+        "4 Bytes" => Some(ValueType::Dword),
+        "8 Bytes" => Some(ValueType::Qword),
+        "Float" => Some(ValueType::Float),
+        "Double" => Some(ValueType::Double),
+        _ => None,
+    }
+}
+
+fn value_type_name(value_type: ValueType) -> Option<&'static str> {
+    match value_type {
+        ValueType::Byte => Some("Byte"),
+        ValueType::Word => Some("2 Bytes"),
+        ValueType::Dword | ValueType::Auto => Some("4 Bytes"),
+        ValueType::Qword => Some("8 Bytes"),
+        ValueType::Float => Some("Float"),
+        ValueType::Double => Some("Double"),
+        ValueType::Xor | ValueType::Vec3 => None,
+    }
+}
+
+/// 把 LastState 中显示的值转成小端字节，"??" 等无法解析的返回 None
+fn encode_value(value_type: ValueType, text: &str, hex: bool) -> Option<Vec<u8>> {
+    let text = text.trim();
+    match value_type {
+        ValueType::Float => Some(text.parse::<f32>().ok()?.to_le_bytes().to_vec()),
+        ValueType::Double => Some(text.parse::<f64>().ok()?.to_le_bytes().to_vec()),
+        _ => {
+            let value = if hex {
+                u64::from_str_radix(text, 16).ok()?
+            } else {
+                text.parse::<i64>().map(|v| v as u64).or_else(|_| text.parse::<u64>()).ok()?
+            };
+            Some(value.to_le_bytes()[..value_type.size()].to_vec())
+        },
+    }
+}
+
+fn decode_value(value_type: ValueType, bytes: &[u8]) -> Option<String> {
+    if bytes.len() < value_type.size() {
+        return None;
+    }
+    let mut buf = [0u8; 8];
+    buf[..value_type.size()].copy_from_slice(&bytes[..value_type.size()]);
+    Some(match value_type {
+        ValueType::Float => f32::from_le_bytes(buf[..4].try_into().ok()?).to_string(),
+        ValueType::Double => f64::from_le_bytes(buf).to_string(),
+        _ => u64::from_le_bytes(buf).to_string(),
+    })
+}
+
+/// 导出为 .CT 内容，返回文本和跳过的条目数
+///
+/// 不带 `data_start` 且根节点不在模块第一个段的链，CE 无法直接表示，
+/// 需要 `static_modules` 换算成相对模块第一个段的偏移，找不到对应模块时跳过。
+pub fn export_cheat_table(entries: &[SavedEntry], static_modules: &[VmStaticData]) -> (String, usize) {
+    let mut out = String::with_capacity(256 + entries.len() * 256);
+    let mut skipped = 0;
+    let _ = writeln!(out, "<?xml version=\"1.0\" encoding=\"utf-8\"?>");
+    let _ = writeln!(out, "<CheatTable CheatEngineTableVersion=\"{}\">", CHEAT_TABLE_VERSION);
+    let _ = writeln!(out, "  <CheatEntries>");
+    for entry in entries {
+        if write_entry(&mut out, entry, static_modules).is_none() {
+            debug!("CheatTable: 无法导出条目 {}", entry.label);
+            skipped += 1;
+        }
+    }
+    let _ = writeln!(out, "  </CheatEntries>");
+    let _ = writeln!(out, "</CheatTable>");
+    (out, skipped)
+}
+
+fn write_entry(out: &mut String, entry: &SavedEntry, static_modules: &[VmStaticData]) -> Option<()> {
+    let value_type = ValueType::from_id(entry.value_type)?;
+    let type_name = value_type_name(value_type)?;
+
+    let (address, offsets) = match &entry.location {
+        SavedLocation::Address { address } => (format!("{:X}", address), &[][..]),
+        SavedLocation::Pointer { chain, data_start } => {
+            let (root, offsets) = chain.steps.split_first()?;
+            let name = root.module_name.as_deref()?;
+            let mut offset = root.offset;
+            if !data_start && root.module_index != 0 {
+                let module = static_modules.iter().find(|m| m.name == name && m.index == root.module_index)?;
+                offset = offset.wrapping_add(module.base_address.wrapping_sub(module.first_module_base_addr) as i64);
+            }
+            (format!("\"{}\"{}", name, signed_hex(offset)), offsets)
+        },
+    };
+
+    let _ = writeln!(out, "    <CheatEntry>");
+    let _ = writeln!(out, "      <ID>{}</ID>", entry.id);
+    let _ = writeln!(out, "      <Description>\"{}\"</Description>", xml::escape(&entry.label));
+    if let Some(value) = decode_value(value_type, &entry.value) {
+        let activated = if entry.frozen { " Activated=\"1\"" } else { "" };
+        let _ = writeln!(out, "      <LastState Value=\"{}\"{}/>", value, activated);
+    }
+    let _ = writeln!(out, "      <VariableType>{}</VariableType>", type_name);
+    let _ = writeln!(out, "      <Address>{}</Address>", xml::escape(&address));
+    if !offsets.is_empty() {
+        let _ = writeln!(out, "      <Offsets>");
+        for step in offsets.iter().rev() {
+            let _ = writeln!(out, "        <Offset>{}</Offset>", signed_hex(step.offset).trim_start_matches('+'));
+        }
+        let _ = writeln!(out, "      </Offsets>");
+    }
+    let _ = writeln!(out, "    </CheatEntry>");
+    Some(())
+}
+
+fn signed_hex(value: i64) -> String {
+    if value < 0 { format!("-{:X}", value.unsigned_abs()) } else { format!("+{:X}", value) }
+}
+
+/// 去掉描述两侧的引号
+fn unquote(text: &str) -> String {
+    let text = text.trim();
+    text.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(text).to_string()
+}
+
+/// 够读 .CT 的最小 XML 解析：元素、属性、文本、注释、CDATA 和常见实体
+mod xml {
+    use anyhow::{anyhow, Result};
+
+    #[derive(Debug, Default)]
+    pub struct Element {
+        pub name: String,
+        pub attrs: Vec<(String, String)>,
+        pub children: Vec<Element>,
+        pub text: String,
+    }
+
+    impl Element {
+        pub fn child(&self, name: &str) -> Option<&Element> {
+            self.children.iter().find(|child| child.name == name)
+        }
+
+        pub fn child_text(&self, name: &str) -> Option<&str> {
+            self.child(name).map(|child| child.text.as_str())
+        }
+
+        pub fn attr(&self, name: &str) -> Option<&str> {
+            self.attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// 转义元素文本，CE 的地址和描述带原样的引号
+    pub fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    fn unescape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('&') {
+            out.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find(';') else {
+                out.push_str(&rest[start..]);
+                return out;
+            };
+            let entity = &rest[start + 1..start + end];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16).ok())
+                    .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            match decoded {
+                Some(c) => out.push(c),
+                None => out.push_str(&rest[start..start + end + 1]),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// 解析文档，返回根元素
+    pub fn parse(text: &str) -> Result<Element> {
+        let mut stack: Vec<Element> = Vec::new();
+        let mut root = None;
+        let mut rest = text.trim_start_matches('\u{feff}');
+
+        while let Some(start) = rest.find('<') {
+            if let Some(top) = stack.last_mut() {
+                top.text.push_str(&unescape(&rest[..start]));
+            }
+            rest = &rest[start..];
+
+            if let Some(body) = rest.strip_prefix("<!--") {
+                let end = body.find("-->").ok_or_else(|| anyhow!("Unterminated comment"))?;
+                rest = &body[end + 3..];
+            } else if let Some(body) = rest.strip_prefix("<![CDATA[") {
+                let end = body.find("]]>").ok_or_else(|| anyhow!("Unterminated CDATA"))?;
+                if let Some(top) = stack.last_mut() {
+                    top.text.push_str(&body[..end]);
+                }
+                rest = &body[end + 3..];
+            } else if rest.starts_with("<?") || rest.starts_with("<!") {
+                let end = rest.find('>').ok_or_else(|| anyhow!("Unterminated declaration"))?;
+                rest = &rest[end + 1..];
+            } else if let Some(body) = rest.strip_prefix("</") {
+                let end = body.find('>').ok_or_else(|| anyhow!("Unterminated closing tag"))?;
+                let name = body[..end].trim();
+                let mut element = stack.pop().ok_or_else(|| anyhow!("Unexpected </{}>", name))?;
+                if element.name != name {
+                    return Err(anyhow!("Mismatched </{}>, expected </{}>", name, element.name));
+                }
+                element.text = element.text.trim().to_string();
+                rest = &body[end + 1..];
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            } else {
+                let (element, self_closing, remaining) = parse_open_tag(&rest[1..])?;
+                rest = remaining;
+                if self_closing {
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => root = Some(element),
+                    }
+                } else {
+                    stack.push(element);
+                }
+            }
+
+            if root.is_some() {
+                break;
+            }
+        }
+
+        if let Some(open) = stack.last() {
+            return Err(anyhow!("Unclosed <{}>", open.name));
+        }
+        root.ok_or_else(|| anyhow!("Empty document"))
+    }
+
+    /// 解析 `<` 之后的开始标签，返回元素、是否自闭合和剩余文本
+    fn parse_open_tag(text: &str) -> Result<(Element, bool, &str)> {
+        let name_end = text
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .ok_or_else(|| anyhow!("Unterminated tag"))?;
+        let mut element = Element {
+            name: text[..name_end].to_string(),
+            ..Default::default()
+        };
+        if element.name.is_empty() {
+            return Err(anyhow!("Empty tag name"));
+        }
+
+        let mut rest = text[name_end..].trim_start();
+        loop {
+            if let Some(remaining) = rest.strip_prefix("/>") {
+                return Ok((element, true, remaining));
+            }
+            if let Some(remaining) = rest.strip_prefix('>') {
+                return Ok((element, false, remaining));
+            }
+
+            let eq = rest.find('=').ok_or_else(|| anyhow!("Malformed attribute in <{}>", element.name))?;
+            let key = rest[..eq].trim().to_string();
+            let value_part = rest[eq + 1..].trim_start();
+            let quote = value_part.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let quote = quote.ok_or_else(|| anyhow!("Unquoted attribute {} in <{}>", key, element.name))?;
+            let end = value_part[1..].find(quote).ok_or_else(|| anyhow!("Unterminated attribute {}", key))?;
+            element.attrs.push((key, unescape(&value_part[1..end + 1])));
+            rest = value_part[end + 2..].trim_start();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str, location: SavedLocation, value_type: ValueType, value: Vec<u8>) -> SavedEntry {
+        SavedEntry {
+            id: 0,
+            label: label.to_string(),
+            location,
+            value_type: value_type.to_id(),
+            value,
+            frozen: false,
+            hotkey: 0,
+        }
+    }
+
+    fn pointer(module: &str, root: i64, offsets: &[i64]) -> SavedLocation {
+        let mut chain = PointerChain::new(0);
+        chain.push(PointerChainStep::static_root(module.to_string(), 0, root));
+        for &offset in offsets {
+            chain.push(PointerChainStep::dynamic_offset(offset));
+        }
+        SavedLocation::Pointer { chain, data_start: true }
+    }
+
+    fn pointer_parts(entry: &SavedEntry) -> Option<(String, Vec<i64>)> {
+        match &entry.location {
+            SavedLocation::Pointer { chain, .. } => {
+                let (root, rest) = chain.steps.split_first()?;
+                let mut offsets = vec![root.offset];
+                offsets.extend(rest.iter().map(|step| step.offset));
+                Some((root.module_name.clone()?, offsets))
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let module = |text| match parse_address(text) {
+            Some(AddressExpr::Module { name, offset }) => Some((name, offset)),
+            _ => None,
+        };
+        assert!(matches!(parse_address("7FAB1234"), Some(AddressExpr::Absolute(0x7FAB1234))));
+        assert!(matches!(parse_address("7FAB1234+10"), Some(AddressExpr::Absolute(0x7FAB1244))));
+        assert_eq!(module("\"libfoo.so\"+1234"), Some(("libfoo.so".to_string(), 0x1234)));
+        assert_eq!(module("libfoo.so+10-4"), Some(("libfoo.so".to_string(), 0xC)));
+        assert_eq!(module("lib-foo.so+10"), Some(("lib-foo.so".to_string(), 0x10)));
+        assert_eq!(module("\"lib-foo+bar.so\"-8"), Some(("lib-foo+bar.so".to_string(), -8)));
+        assert!(parse_address("[libfoo.so+10]+4").is_none());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let entries = vec![
+            entry("health", SavedLocation::Address { address: 0x7FAB1234 }, ValueType::Dword, 100u32.to_le_bytes().to_vec()),
+            entry("ammo <clip>", pointer("lib-game.so", 0x1A0, &[0x18, -0x8, 0x40]), ValueType::Float, 2.5f32.to_le_bytes().to_vec()),
+            entry("root", pointer("libunity.so", 0x10, &[]), ValueType::Qword, Vec::new()),
+        ];
+
+        let (text, skipped) = export_cheat_table(&entries, &[]);
+        assert_eq!(skipped, 0);
+        // CE 把最后一级偏移写在最前面
+        let first = text.find("<Offset>40</Offset>").unwrap();
+        assert!(first < text.find("<Offset>-8</Offset>").unwrap());
+        assert!(text.find("<Offset>-8</Offset>").unwrap() < text.find("<Offset>18</Offset>").unwrap());
+
+        let import = import_cheat_table(&text).unwrap();
+        assert!(import.skipped.is_empty());
+        assert_eq!(import.entries.len(), 3);
+
+        let labels: Vec<&str> = import.entries.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["health", "ammo <clip>", "root"]);
+        assert!(matches!(import.entries[0].location, SavedLocation::Address { address: 0x7FAB1234 }));
+        assert_eq!(import.entries[0].value, 100u32.to_le_bytes());
+        assert_eq!(pointer_parts(&import.entries[1]), Some(("lib-game.so".to_string(), vec![0x1A0, 0x18, -0x8, 0x40])));
+        assert_eq!(import.entries[1].value, 2.5f32.to_le_bytes());
+        assert_eq!(pointer_parts(&import.entries[2]), Some(("libunity.so".to_string(), vec![0x10])));
+    }
+
+    #[test]
+    fn test_import_nested_groups() {
+        let text = r#"<?xml version="1.0" encoding="utf-8"?>
+<CheatTable CheatEngineTableVersion="45">
+  <CheatEntries>
+    <CheatEntry>
+      <Description>"Player"</Description>
+      <GroupHeader>1</GroupHeader>
+      <CheatEntries>
+        <CheatEntry>
+          <Description>"Stats"</Description>
+          <CheatEntries>
+            <CheatEntry>
+              <Description>"gold"</Description>
+              <VariableType>4 Bytes</VariableType>
+              <Address>libgame.so+200</Address>
+              <Offsets>
+                <Offset>C</Offset>
+                <Offset>30</Offset>
+              </Offsets>
+            </CheatEntry>
+          </CheatEntries>
+        </CheatEntry>
+        <CheatEntry>
+          <Description>"script"</Description>
+          <VariableType>Auto Assembler Script</VariableType>
+        </CheatEntry>
+      </CheatEntries>
+    </CheatEntry>
+    <CheatEntry>
+      <Description>"speed"</Description>
+      <VariableType>Float</VariableType>
+      <Address>"libgame.so"+300</Address>
+    </CheatEntry>
+  </CheatEntries>
+</CheatTable>"#;
+
+        let import = import_cheat_table(text).unwrap();
+        let labels: Vec<&str> = import.entries.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["gold", "speed"]);
+        assert_eq!(import.skipped, ["script"]);
+        assert_eq!(pointer_parts(&import.entries[0]), Some(("libgame.so".to_string(), vec![0x200, 0x30, 0xC])));
+    }
+
+    #[test]
+    fn test_import_depth_cap() {
+        let depth = MAX_GROUP_DEPTH + 8;
+        let mut text = String::from("<CheatTable><CheatEntries>");
+        for _ in 0..depth {
+            text.push_str("<CheatEntry><Description>\"group\"</Description><CheatEntries>");
+        }
+        for _ in 0..depth {
+            text.push_str("</CheatEntries></CheatEntry>");
+        }
+        text.push_str("</CheatEntries></CheatTable>");
+
+        let import = import_cheat_table(&text).unwrap();
+        assert!(import.entries.is_empty());
+        assert_eq!(import.skipped, ["group"]);
+    }
+}
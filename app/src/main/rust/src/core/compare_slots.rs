@@ -0,0 +1,129 @@
+//! CompareSlots - 固定地址比较槽
+//!
+//! 用户把少量候选地址钉到槽位中，一次调用刷新全部槽位的当前值，
+//! 并报告每个槽与参考槽是否相等，便于手动排除最终候选。
+
+use crate::core::globals::{DRIVER_MANAGER, OP_QUEUE};
+use crate::search::types::ValueType;
+use anyhow::{anyhow, Result};
+
+/// 最多可钉住的地址数
+pub const MAX_COMPARE_SLOTS: usize = 16;
+
+/// 槽位与参考槽的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState {
+    Equal = 0,
+    Different = 1,
+    /// 本槽或参考槽读取失败
+    Unreadable = 2,
+}
+
+/// 被钉住的地址
+#[derive(Debug, Clone, Copy)]
+pub struct CompareSlot {
+    pub address: u64,
+    pub value_type: ValueType,
+}
+
+/// 刷新后的槽位快照
+#[derive(Debug, Clone, Copy)]
+pub struct SlotSnapshot {
+    pub slot: usize,
+    pub address: u64,
+    pub value_type: ValueType,
+    /// 当前值（小端，不足 8 字节补 0），读取失败时为 None
+    pub value: Option<[u8; 8]>,
+    pub state: SlotState,
+}
+
+/// 比较槽管理器
+#[derive(Default)]
+pub struct CompareSlotManager {
+    slots: [Option<CompareSlot>; MAX_COMPARE_SLOTS],
+}
+
+impl CompareSlotManager {
+    pub fn new() -> Self {
+        Self { slots: [None; MAX_COMPARE_SLOTS] }
+    }
+
+    /// 钉住地址，返回占用的槽位；地址已存在时返回原槽位
+    pub fn pin(&mut self, address: u64, value_type: ValueType) -> Result<usize> {
+        if let Some(slot) = self.slots.iter().position(|s| matches!(s, Some(s) if s.address == address)) {
+            self.slots[slot] = Some(CompareSlot { address, value_type });
+            return Ok(slot);
+        }
+
+        let slot = self
+            .slots
+            .iter()
+            .position(|s| s.is_none())
+            .ok_or_else(|| anyhow!("All {} compare slots are in use", MAX_COMPARE_SLOTS))?;
+        self.slots[slot] = Some(CompareSlot { address, value_type });
+        Ok(slot)
+    }
+
+    pub fn unpin(&mut self, slot: usize) -> bool {
+        self.slots.get_mut(slot).and_then(|s| s.take()).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.slots = [None; MAX_COMPARE_SLOTS];
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&CompareSlot> {
+        self.slots.get(slot).and_then(|s| s.as_ref())
+    }
+
+    pub fn pinned_count(&self) -> usize {
+        self.slots.iter().filter(|s| s.is_some()).count()
+    }
+
+    /// 重新读取所有槽位，并与 `reference` 槽按字节比较
+    pub fn refresh(&self, reference: usize) -> Result<Vec<SlotSnapshot>> {
+        let reference_slot = self.get(reference).ok_or_else(|| anyhow!("Compare slot {} is empty", reference))?;
+
+        let pinned: Vec<(usize, &CompareSlot)> = self.slots.iter().enumerate().filter_map(|(index, slot)| slot.as_ref().map(|slot| (index, slot))).collect();
+        let requests: Vec<(u64, usize)> = pinned.iter().map(|(_, slot)| (slot.address, slot.value_type.size().min(8))).collect();
+
+        // 所有槽位一次批量读取
+        let values: Vec<Option<[u8; 8]>> = {
+            let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            let _op = OP_QUEUE.interactive();
+            manager
+                .read_memory_batch(&requests)
+                .into_iter()
+                .map(|result| {
+                    result.ok().map(|bytes| {
+                        let mut buf = [0u8; 8];
+                        buf[..bytes.len()].copy_from_slice(&bytes);
+                        buf
+                    })
+                })
+                .collect()
+        };
+
+        let reference_value = pinned.iter().zip(&values).find(|((index, _), _)| *index == reference).and_then(|(_, value)| *value);
+        let reference_size = reference_slot.value_type.size();
+
+        Ok(pinned
+            .into_iter()
+            .zip(values)
+            .map(|((index, slot), value)| {
+                let state = match (value, reference_value) {
+                    (Some(a), Some(b)) if slot.value_type.size() == reference_size && a == b => SlotState::Equal,
+                    (Some(_), Some(_)) => SlotState::Different,
+                    _ => SlotState::Unreadable,
+                };
+                SlotSnapshot {
+                    slot: index,
+                    address: slot.address,
+                    value_type: slot.value_type,
+                    value,
+                    state,
+                }
+            })
+            .collect())
+    }
+}
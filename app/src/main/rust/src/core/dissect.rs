@@ -0,0 +1,226 @@
+//! 结构体解析
+//!
+//! 把一段内存逐个槽位注释：指针（目标所在模块或区域）、虚表指针、字符串指针、浮点、整数等。
+//! 与 [`layout_analyzer`](super::layout_analyzer) 的统计视角不同，这里给出每个槽位的具体含义，
+//! 8 字节对齐处优先按指针/double 解释，其余按 4 字节解释。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
+use crate::core::layout_analyzer::{looks_like_double, looks_like_float, looks_like_pointer};
+use crate::core::region_type::{query_mem_regions, MemRegion};
+use crate::wuwa::{PageStatusBitmap, MEM_EXECUTABLE, MEM_WRITABLE};
+use anyhow::{anyhow, Result};
+
+/// 单次解析的最大字节数
+pub const MAX_DISSECT_SIZE: usize = 64 * 1024;
+
+/// 判断字符串时读取的目标字节数
+const STRING_PROBE: usize = 64;
+/// 字符串的最少字符数
+const MIN_STRING_LEN: usize = 4;
+/// 去掉 arm64 TBI/MTE 标签字节
+const POINTER_MASK: u64 = 0x00FF_FFFF_FFFF_FFFF;
+
+/// 槽位类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SlotKind {
+    Unreadable = 0,
+    Zero = 1,
+    Pointer = 2,
+    /// 指向只读数据中的函数指针表
+    VtablePointer = 3,
+    /// 指向 UTF-8 字符串
+    StringPointer = 4,
+    /// 指向 UTF-16 字符串
+    WideStringPointer = 5,
+    Float = 6,
+    Double = 7,
+    Int = 8,
+    Unknown = 9,
+}
+
+/// 一个槽位的解析结果
+#[derive(Debug, Clone)]
+pub struct DissectedSlot {
+    /// 相对起始地址的偏移
+    pub offset: u32,
+    /// 4 或 8
+    pub size: u8,
+    pub kind: SlotKind,
+    /// 原始值，4 字节槽位零扩展
+    pub value: u64,
+    /// 指针类槽位去掉标签后的目标地址，其余为 0
+    pub target: u64,
+    /// 指针为目标位置（如 `libfoo.so+0x1A0`），字符串为内容，数值为格式化后的值
+    pub annotation: String,
+}
+
+/// 按起始地址排序的区域，用于查找指针目标所在位置
+struct RegionIndex {
+    regions: Vec<MemRegion>,
+}
+
+impl RegionIndex {
+    fn new(mut regions: Vec<MemRegion>) -> Self {
+        regions.sort_by_key(|region| region.start);
+        Self { regions }
+    }
+
+    fn find(&self, address: u64) -> Option<&MemRegion> {
+        let index = self.regions.partition_point(|region| region.start <= address).checked_sub(1)?;
+        self.regions.get(index).filter(|region| address < region.end)
+    }
+
+    /// `libfoo.so+0x1A0` 或 `[anon:scudo]+0x40`，文件映射以同名的最低映射为基址
+    fn describe(&self, address: u64) -> String {
+        let Some(region) = self.find(address) else {
+            return "?".to_string();
+        };
+        if region.name.is_empty() {
+            return format!("anon+0x{:X}", address - region.start);
+        }
+        let base = if region.name.starts_with('/') {
+            self.regions.iter().find(|r| r.name == region.name).map_or(region.start, |r| r.start)
+        } else {
+            region.start
+        };
+        let name = region.name.rsplit('/').next().unwrap_or(&region.name);
+        format!("{}+0x{:X}", name, address - base)
+    }
+
+    fn is_executable(&self, address: u64) -> bool {
+        self.find(address).is_some_and(|region| region.flags & MEM_EXECUTABLE != 0)
+    }
+}
+
+fn utf8_string(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let text = std::str::from_utf8(&bytes[..end]).ok()?;
+    if text.chars().count() < MIN_STRING_LEN || text.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return None;
+    }
+    Some(if end == bytes.len() { format!("{}…", text) } else { text.to_string() })
+}
+
+fn utf16_string(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).collect();
+    let end = units.iter().position(|&unit| unit == 0).unwrap_or(units.len());
+    // 只认以可打印字符开头的串，避免把小整数数组当成字符串
+    if end < MIN_STRING_LEN || !units[..MIN_STRING_LEN].iter().all(|&unit| (0x20..0x7F).contains(&unit)) {
+        return None;
+    }
+    let text = String::from_utf16(&units[..end]).ok()?;
+    if text.chars().any(char::is_control) {
+        return None;
+    }
+    Some(if end == units.len() { format!("{}…", text) } else { text })
+}
+
+fn scalar_slot(offset: u32, raw: u32) -> DissectedSlot {
+    let (kind, annotation) = if raw == 0 {
+        (SlotKind::Zero, String::new())
+    } else if looks_like_float(f32::from_bits(raw)) {
+        (SlotKind::Float, format!("{}", f32::from_bits(raw)))
+    } else if (raw as i32).unsigned_abs() <= 100_000 {
+        (SlotKind::Int, format!("{}", raw as i32))
+    } else {
+        (SlotKind::Unknown, format!("0x{:X}", raw))
+    };
+    DissectedSlot { offset, size: 4, kind, value: raw as u64, target: 0, annotation }
+}
+
+/// 解析绑定进程中 `[address, address + size)` 的结构
+pub fn dissect_structure(manager: &DriverManager, address: u64, size: usize) -> Result<Vec<DissectedSlot>> {
+    if size == 0 || size > MAX_DISSECT_SIZE {
+        return Err(anyhow!("Dissect size must be between 1 and {} bytes", MAX_DISSECT_SIZE));
+    }
+    if !manager.is_process_bound() {
+        return Err(anyhow!("No process bound"));
+    }
+
+    let address = address & !3;
+    let size = size.next_multiple_of(4);
+    let mut buf = vec![0u8; size];
+    let mut page_status = PageStatusBitmap::new(size, address as usize);
+    let _op = OP_QUEUE.interactive();
+    manager.read_memory_unified(address, &mut buf, Some(&mut page_status))?;
+
+    let page_size = *PAGE_SIZE;
+    let page_offset = address as usize & (page_size - 1);
+    let readable = |offset: usize| page_status.is_page_success((page_offset + offset) / page_size);
+    let regions = RegionIndex::new(query_mem_regions(manager, manager.get_bound_pid())?);
+
+    let mut slots = Vec::with_capacity(size / 4);
+    let mut offset = 0;
+    while offset < size {
+        if !readable(offset) {
+            slots.push(DissectedSlot {
+                offset: offset as u32,
+                size: 4,
+                kind: SlotKind::Unreadable,
+                value: 0,
+                target: 0,
+                annotation: String::new(),
+            });
+            offset += 4;
+            continue;
+        }
+
+        if (address + offset as u64).is_multiple_of(8) && offset + 8 <= size && readable(offset + 4) {
+            let raw = u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap());
+            let (low, high) = (raw as u32, (raw >> 32) as u32);
+            let target = raw & POINTER_MASK;
+            if looks_like_pointer(raw) && regions.find(target).is_some() {
+                slots.push(DissectedSlot {
+                    offset: offset as u32,
+                    size: 8,
+                    kind: SlotKind::Pointer,
+                    value: raw,
+                    target,
+                    annotation: regions.describe(target),
+                });
+                offset += 8;
+                continue;
+            }
+            if low != 0 && high != 0 && !looks_like_float(f32::from_bits(low)) && looks_like_double(f64::from_bits(raw)) {
+                slots.push(DissectedSlot {
+                    offset: offset as u32,
+                    size: 8,
+                    kind: SlotKind::Double,
+                    value: raw,
+                    target: 0,
+                    annotation: format!("{}", f64::from_bits(raw)),
+                });
+                offset += 8;
+                continue;
+            }
+        }
+
+        slots.push(scalar_slot(offset as u32, u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())));
+        offset += 4;
+    }
+
+    // 再读一次各指针的目标，细分为虚表和字符串
+    let pointers: Vec<usize> = slots.iter().enumerate().filter(|(_, slot)| slot.kind == SlotKind::Pointer).map(|(i, _)| i).collect();
+    let probes: Vec<(u64, usize)> = pointers.iter().map(|&i| (slots[i].target, STRING_PROBE)).collect();
+    for (&i, probe) in pointers.iter().zip(manager.read_memory_batch(&probes)) {
+        let Ok(bytes) = probe else {
+            continue;
+        };
+        let slot = &mut slots[i];
+        let first = u64::from_le_bytes(bytes[..8].try_into().unwrap()) & POINTER_MASK;
+        let read_only = regions.find(slot.target).is_some_and(|region| region.flags & (MEM_WRITABLE | MEM_EXECUTABLE) == 0);
+        if read_only && regions.is_executable(first) {
+            slot.kind = SlotKind::VtablePointer;
+        } else if let Some(text) = utf8_string(&bytes) {
+            slot.kind = SlotKind::StringPointer;
+            slot.annotation = text;
+        } else if let Some(text) = utf16_string(&bytes) {
+            slot.kind = SlotKind::WideStringPointer;
+            slot.annotation = text;
+        }
+    }
+
+    Ok(slots)
+}
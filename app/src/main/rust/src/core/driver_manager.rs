@@ -1,57 +1,195 @@
 //! Driver manager implementation
 
+use crate::core::background;
+use crate::core::backend::{MemoryBackend, UserspaceBackend, PROCESS_VM_BACKEND, PROC_MEM_BACKEND, WUWA_BACKEND};
+use crate::core::error::{find_mamu_error, MamuError};
+use crate::core::globals::PAGE_SIZE;
 use crate::core::memory_mode::MemoryAccessMode;
-use crate::wuwa::{BindProc, PageStatusBitmap, WuWaDriver, WuwaMemoryType};
+use crate::core::region_type::query_mem_regions;
+use crate::core::user_memory::{UserMemory, UserMemoryKind};
+use crate::wuwa::{BindProc, PageStatusBitmap, WuWaDriver, WuwaMemoryType, MEM_EXECUTABLE, MEM_READABLE, MEM_SHARED, MEM_WRITABLE, WUWA_CAP_PHYSICAL};
 use log::error;
+use nix::errno::Errno;
+use nix::libc;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 批量读取时单次合并读取的最大跨度
+pub const BATCH_READ_MAX_SPAN: usize = 64 * 1024;
+
+/// MEM_* 权限位转为 PROT_*
+fn prot_of(flags: u32) -> libc::c_int {
+    let mut prot = 0;
+    if flags & MEM_READABLE != 0 {
+        prot |= libc::PROT_READ;
+    }
+    if flags & MEM_WRITABLE != 0 {
+        prot |= libc::PROT_WRITE;
+    }
+    if flags & MEM_EXECUTABLE != 0 {
+        prot |= libc::PROT_EXEC;
+    }
+    prot
+}
+
+/// [`DriverManager::write_memory_ex`] 标志：对只读的私有映射先临时加写权限，
+/// 借缺页写入触发写时复制，写完恢复原权限，不会改到共享的 page cache
+pub const WRITE_FLAG_COW: u32 = 1;
+
+/// 让进程的绑定适配访问模式：驱动模式设置 BindProc 的内存类型，用户态模式打开对应的读写器
+///
+/// 切回驱动模式时保留已打开的读写器，缺页模式和物理模式不需要设置内存类型。
+fn prepare_binding(mode: MemoryAccessMode, pid: i32, bind_proc: Option<&BindProc>, user_memory: &mut Option<UserMemory>) -> anyhow::Result<()> {
+    if let Some(kind) = mode.user_kind()
+        && user_memory.as_ref().is_none_or(|memory| memory.kind() != kind)
+    {
+        *user_memory = Some(UserMemory::open(pid, kind)?);
+    }
+    if let Some(bind_proc) = bind_proc {
+        match mode {
+            MemoryAccessMode::None => {}, // do nothing
+            MemoryAccessMode::NonCacheable => {
+                bind_proc.set_memory_type(WuwaMemoryType::DeviceNGnRnE)?;
+            },
+            MemoryAccessMode::WriteThrough => {
+                bind_proc.set_memory_type(WuwaMemoryType::NormalWt)?;
+            },
+            MemoryAccessMode::Normal => {
+                bind_proc.set_memory_type(WuwaMemoryType::Normal)?;
+            },
+            MemoryAccessMode::PageFault | MemoryAccessMode::ProcessVm | MemoryAccessMode::ProcMem => {}, // do nothing
+        };
+    }
+    Ok(())
+}
+
+/// 当前进程之外同时绑定的进程
+struct AttachedProcess {
+    bind_proc: Option<BindProc>,
+    user_memory: Option<UserMemory>,
+}
+
+/// 一次读写的目标进程
+#[derive(Clone, Copy)]
+struct Target<'a> {
+    pid: i32,
+    bind_proc: Option<&'a BindProc>,
+    user_memory: Option<&'a UserMemory>,
+}
+
+impl<'a> Target<'a> {
+    fn bind_proc(&self) -> anyhow::Result<&'a BindProc> {
+        self.bind_proc.ok_or_else(|| MamuError::not_bound().into())
+    }
+
+    fn user_memory(&self) -> anyhow::Result<&'a UserMemory> {
+        self.user_memory.ok_or_else(|| MamuError::not_bound().into())
+    }
+}
 
 pub struct DriverManager {
-    driver: Option<WuWaDriver>,
+    /// 按名字登记的后端
+    backends: HashMap<String, Box<dyn MemoryBackend>>,
+    /// 当前选中的后端，总在 `backends` 中
+    backend: String,
     bound_process: Option<BindProc>,
     bound_pid: i32,
     access_mode: MemoryAccessMode,
+    /// 用户态模式下的读写器
+    user_memory: Option<UserMemory>,
+    /// 按 pid 附加的其他进程，可按 pid 读写或切换为当前进程
+    attached: HashMap<i32, AttachedProcess>,
+    /// 小读取的合并窗口，应用到之后绑定的每个 BindProc
+    read_coalesce_window: Duration,
 }
 
 impl DriverManager {
     pub fn new() -> Self {
+        let mut backends: HashMap<String, Box<dyn MemoryBackend>> = HashMap::new();
+        backends.insert(PROCESS_VM_BACKEND.to_string(), Box::new(UserspaceBackend::new(UserMemoryKind::ProcessVm)));
+        backends.insert(PROC_MEM_BACKEND.to_string(), Box::new(UserspaceBackend::new(UserMemoryKind::ProcMem)));
         Self {
-            driver: None,
+            backends,
+            backend: PROCESS_VM_BACKEND.to_string(),
             bound_process: None,
             bound_pid: 0,
             access_mode: MemoryAccessMode::None,
+            user_memory: None,
+            attached: HashMap::new(),
+            read_coalesce_window: Duration::ZERO,
         }
     }
 
+    /// 登记 WuWa 驱动并选中它
     pub fn set_driver(&mut self, driver: WuWaDriver) {
-        self.driver = Some(driver);
+        self.backends.insert(WUWA_BACKEND.to_string(), Box::new(driver));
+        self.backend = WUWA_BACKEND.to_string();
     }
 
+    /// 当前后端为 WuWa 驱动时返回它
     pub fn get_driver(&self) -> Option<&WuWaDriver> {
-        self.driver.as_ref()
+        self.backend().as_wuwa()
     }
 
+    /// WuWa 驱动是否已登记（不一定是当前后端）
     pub fn is_driver_loaded(&self) -> bool {
-        self.driver.is_some()
+        self.backends.contains_key(WUWA_BACKEND)
+    }
+
+    /// 登记后端，同名的会被替换（不能替换当前选中且已绑定进程的后端）
+    pub fn register_backend(&mut self, name: &str, backend: Box<dyn MemoryBackend>) -> anyhow::Result<()> {
+        if name == self.backend && self.is_process_bound() {
+            return Err(anyhow::anyhow!("Backend {} is in use", name));
+        }
+        self.backends.insert(name.to_string(), backend);
+        Ok(())
+    }
+
+    /// 选中后端，须先解绑进程；后端要求特定访问模式时一并切换
+    pub fn select_backend(&mut self, name: &str) -> anyhow::Result<()> {
+        let backend = self.backends.get(name).ok_or_else(|| anyhow::anyhow!("Unknown backend: {}", name))?;
+        if name == self.backend {
+            return Ok(());
+        }
+        if self.is_process_bound() {
+            return Err(anyhow::anyhow!("Unbind the process before switching backends"));
+        }
+        if let Some(mode) = backend.access_mode() {
+            self.access_mode = mode;
+        }
+        self.backend = name.to_string();
+        Ok(())
+    }
+
+    /// 当前选中的后端
+    pub fn backend(&self) -> &dyn MemoryBackend {
+        self.backends[&self.backend].as_ref()
+    }
+
+    pub fn backend_name(&self) -> &str {
+        &self.backend
+    }
+
+    /// 按名字取已登记的后端
+    pub fn find_backend(&self, name: &str) -> Option<&dyn MemoryBackend> {
+        self.backends.get(name).map(|backend| backend.as_ref())
+    }
+
+    /// 已登记的后端名，按名字排序
+    pub fn backend_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.backends.keys().cloned().collect();
+        names.sort();
+        names
     }
 
     /// 设置内存访问模式
     pub fn set_access_mode(&mut self, mode: MemoryAccessMode) -> anyhow::Result<()> {
         self.access_mode = mode;
         if self.is_process_bound() {
-            if let Some(bind_proc) = &self.bound_process {
-                match self.get_access_mode() {
-                    MemoryAccessMode::None => {}, // do nothing
-                    MemoryAccessMode::NonCacheable => {
-                        bind_proc.set_memory_type(WuwaMemoryType::DeviceNGnRnE)?;
-                    },
-                    MemoryAccessMode::WriteThrough => {
-                        bind_proc.set_memory_type(WuwaMemoryType::NormalWt)?;
-                    },
-                    MemoryAccessMode::Normal => {
-                        bind_proc.set_memory_type(WuwaMemoryType::Normal)?;
-                    },
-                    MemoryAccessMode::PageFault => {}, // do nothing
-                };
-            }
+            prepare_binding(mode, self.bound_pid, self.bound_process.as_ref(), &mut self.user_memory)?;
+        }
+        for (&pid, process) in &mut self.attached {
+            prepare_binding(mode, pid, process.bind_proc.as_ref(), &mut process.user_memory)?;
         }
 
         Ok(())
@@ -61,35 +199,119 @@ impl DriverManager {
         self.access_mode
     }
 
+    /// 设置 BindProc 模式下小读取的合并窗口，零表示不合并
+    ///
+    /// 多个线程（界面刷新、监视、脚本）在窗口内发起的小读取由驱动的向量化命令
+    /// 一次提交，减少 ioctl 次数和设备节点上的争用，代价是每次读取最多多等一个窗口。
+    /// 驱动不支持向量化命令时不生效。
+    pub fn set_read_coalesce_window(&mut self, window: Duration) {
+        self.read_coalesce_window = window;
+        let attached = self.attached.values().filter_map(|process| process.bind_proc.as_ref());
+        for bind_proc in self.bound_process.iter().chain(attached) {
+            bind_proc.set_read_coalesce_window(window);
+        }
+    }
+
     /// 绑定进程以进行内存访问
     pub fn bind_process(&mut self, bind_proc: BindProc, pid: i32) -> anyhow::Result<()> {
-        match self.get_access_mode() {
-            MemoryAccessMode::None => {}, // do nothing
-            MemoryAccessMode::NonCacheable => {
-                bind_proc.set_memory_type(WuwaMemoryType::DeviceNGnRnE)?;
-            },
-            MemoryAccessMode::WriteThrough => {
-                bind_proc.set_memory_type(WuwaMemoryType::NormalWt)?;
-            },
-            MemoryAccessMode::Normal => {
-                bind_proc.set_memory_type(WuwaMemoryType::Normal)?;
-            },
-            MemoryAccessMode::PageFault => {}, // do nothing
-        };
         // 缺页模式和物理模式不需要设置内存类型，这个时候不走bindproc去读写内存
+        let mut user_memory = None;
+        prepare_binding(self.access_mode, pid, Some(&bind_proc), &mut user_memory)?;
+        bind_proc.set_read_coalesce_window(self.read_coalesce_window);
+        self.attached.remove(&pid);
+        self.user_memory = user_memory;
         self.bound_process = Some(bind_proc);
         self.bound_pid = pid;
         Ok(())
     }
 
-    /// 解绑当前绑定的进程
+    /// 不经驱动绑定进程，只能使用用户态模式读写
+    ///
+    /// 当前模式不是用户态模式时使用 `process_vm_readv`。
+    pub fn bind_process_userspace(&mut self, pid: i32) -> anyhow::Result<()> {
+        let kind = self.access_mode.user_kind().unwrap_or(UserMemoryKind::ProcessVm);
+        self.user_memory = Some(UserMemory::open(pid, kind)?);
+        if self.access_mode.user_kind().is_none() {
+            self.access_mode = MemoryAccessMode::ProcessVm;
+        }
+        self.attached.remove(&pid);
+        self.bound_process = None;
+        self.bound_pid = pid;
+        Ok(())
+    }
+
+    /// 解绑当前进程和全部附加的进程
     pub fn unbind_process(&mut self) {
         self.bound_process = None;
+        self.user_memory = None;
         self.bound_pid = 0;
+        self.attached.clear();
+    }
+
+    /// 在当前进程之外再绑定一个进程（如游戏的子进程），之后可按 pid 读写或切换为当前进程
+    ///
+    /// `bind_proc` 为 None（用户态后端）时只能在用户态模式下读写。
+    /// 已是当前进程或已附加时不做处理。
+    pub fn attach_process(&mut self, pid: i32, bind_proc: Option<BindProc>) -> anyhow::Result<()> {
+        if (pid == self.bound_pid && self.is_process_bound()) || self.attached.contains_key(&pid) {
+            return Ok(());
+        }
+        if bind_proc.is_none() && self.access_mode.user_kind().is_none() {
+            return Err(anyhow::anyhow!("Process {} can only be attached in a userspace access mode", pid));
+        }
+        let mut user_memory = None;
+        prepare_binding(self.access_mode, pid, bind_proc.as_ref(), &mut user_memory)?;
+        if let Some(bind_proc) = &bind_proc {
+            bind_proc.set_read_coalesce_window(self.read_coalesce_window);
+        }
+        self.attached.insert(pid, AttachedProcess { bind_proc, user_memory });
+        Ok(())
+    }
+
+    /// 解除附加的进程，当前进程需用 [`Self::unbind_process`] 解绑
+    ///
+    /// # Returns
+    /// 该进程之前是否已附加
+    pub fn detach_process(&mut self, pid: i32) -> bool {
+        self.attached.remove(&pid).is_some()
+    }
+
+    /// 把附加的进程切换为当前进程，原当前进程转为附加
+    ///
+    /// 扫描、模块、线程等功能都只针对当前进程。
+    pub fn select_process(&mut self, pid: i32) -> anyhow::Result<()> {
+        if pid == self.bound_pid && self.is_process_bound() {
+            return Ok(());
+        }
+        let process = self
+            .attached
+            .remove(&pid)
+            .ok_or_else(|| anyhow::anyhow!("Process {} is not attached", pid))?;
+        if self.is_process_bound() {
+            let previous = AttachedProcess {
+                bind_proc: self.bound_process.take(),
+                user_memory: self.user_memory.take(),
+            };
+            self.attached.insert(self.bound_pid, previous);
+        }
+        self.bound_process = process.bind_proc;
+        self.user_memory = process.user_memory;
+        self.bound_pid = pid;
+        Ok(())
+    }
+
+    /// 全部已绑定的进程，当前进程在前，其余按 pid 排序
+    pub fn attached_pids(&self) -> Vec<i32> {
+        let mut pids: Vec<i32> = self.attached.keys().copied().collect();
+        pids.sort_unstable();
+        if self.is_process_bound() {
+            pids.insert(0, self.bound_pid);
+        }
+        pids
     }
 
     pub fn is_process_bound(&self) -> bool {
-        self.bound_process.is_some() && self.bound_pid != 0
+        (self.bound_process.is_some() || self.user_memory.is_some()) && self.bound_pid != 0
     }
 
     pub fn get_bound_pid(&self) -> i32 {
@@ -100,6 +322,30 @@ impl DriverManager {
         self.bound_process.as_ref()
     }
 
+    fn current_target(&self) -> Target<'_> {
+        Target {
+            pid: self.bound_pid,
+            bind_proc: self.bound_process.as_ref(),
+            user_memory: self.user_memory.as_ref(),
+        }
+    }
+
+    /// 当前进程或附加的进程
+    fn target(&self, pid: i32) -> anyhow::Result<Target<'_>> {
+        if pid == self.bound_pid && self.is_process_bound() {
+            return Ok(self.current_target());
+        }
+        let process = self
+            .attached
+            .get(&pid)
+            .ok_or_else(|| anyhow::anyhow!("Process {} is not bound", pid))?;
+        Ok(Target {
+            pid,
+            bind_proc: process.bind_proc.as_ref(),
+            user_memory: process.user_memory.as_ref(),
+        })
+    }
+
     /// 统一的内存读取方法，使用当前配置的 access_mode
     ///
     /// # Arguments
@@ -116,13 +362,35 @@ impl DriverManager {
         buf: &mut [u8],
         page_status: Option<&mut PageStatusBitmap>,
     ) -> anyhow::Result<()> {
+        self.read_target(self.current_target(), addr, buf, page_status)
+    }
+
+    /// 按 pid 读取当前进程或附加的进程，参数同 [`Self::read_memory_unified`]
+    pub fn read_memory_of(
+        &self,
+        pid: i32,
+        addr: u64,
+        buf: &mut [u8],
+        page_status: Option<&mut PageStatusBitmap>,
+    ) -> anyhow::Result<()> {
+        self.read_target(self.target(pid)?, addr, buf, page_status)
+    }
+
+    fn read_target(
+        &self,
+        target: Target<'_>,
+        addr: u64,
+        buf: &mut [u8],
+        page_status: Option<&mut PageStatusBitmap>,
+    ) -> anyhow::Result<()> {
+        background::throttle_read(buf.len());
         match self.access_mode {
             MemoryAccessMode::None => {
                 // 物理内存读取（绕过 access_mode）
                 let driver = self
                     .get_driver()
-                    .ok_or_else(|| anyhow::anyhow!("Driver not initialized"))?;
-                let pid = self.get_bound_pid();
+                    .ok_or_else(MamuError::driver_not_loaded)?;
+                let pid = target.pid;
 
                 if let Some(status) = page_status {
                     driver.read_physical_memory_with_status(
@@ -146,12 +414,8 @@ impl DriverManager {
                 Ok(())
             },
             MemoryAccessMode::PageFault => {
-                // 缺页模式：通过 driver 正常读取（不跟踪页状态）
-                let driver = self
-                    .get_driver()
-                    .ok_or_else(|| anyhow::anyhow!("Driver not initialized"))?;
-                let pid = self.get_bound_pid();
-                driver.read_memory(pid, addr as usize, buf.as_mut_ptr() as usize, buf.len())?;
+                // 缺页模式：通过当前后端正常读取（不跟踪页状态）
+                self.backend().read_memory(target.pid, addr, buf)?;
 
                 // 标记所有页为成功，因为这个方法不跟踪每页状态
                 if let Some(status) = page_status {
@@ -160,12 +424,139 @@ impl DriverManager {
                 Ok(())
             },
             MemoryAccessMode::NonCacheable | MemoryAccessMode::WriteThrough | MemoryAccessMode::Normal => {
-                // 使用 bind_proc 和配置的 access_mode
-                let bind_proc = self
-                    .get_bound_process()
-                    .ok_or_else(|| anyhow::anyhow!("Process not bound"))?;
-                bind_proc.read_memory(addr as usize, buf, page_status)
+                // 使用 bind_proc 和配置的 access_mode，不跟踪页状态的小读取可与其他线程合并
+                match page_status {
+                    Some(status) => target.bind_proc()?.read_memory(addr as usize, buf, Some(status)),
+                    None => target.bind_proc()?.read_memory_coalesced(addr as usize, buf),
+                }
             },
+            MemoryAccessMode::ProcessVm | MemoryAccessMode::ProcMem => target.user_memory()?.read(addr, buf, page_status),
+        }
+    }
+
+    /// 批量读取多个地址（scatter-gather）
+    ///
+    /// 按地址排序后把相邻的请求（间隙不超过一页、合并跨度不超过 [`BATCH_READ_MAX_SPAN`]）
+    /// 合并为一次读取，再从合并缓冲区切出各自的结果，减少驱动往返次数。
+    /// 各组读取通过 [`Self::read_memory_many`] 一起提交；
+    /// 合并读取失败（例如跨越了未映射页）时，该组退化为逐个读取。
+    ///
+    /// # Returns
+    /// 与 `requests` 顺序一一对应的读取结果
+    pub fn read_memory_batch(&self, requests: &[(u64, usize)]) -> Vec<anyhow::Result<Vec<u8>>> {
+        let mut results: Vec<Option<anyhow::Result<Vec<u8>>>> = (0..requests.len()).map(|_| None).collect();
+
+        let mut order: Vec<usize> = (0..requests.len()).filter(|&i| requests[i].1 > 0).collect();
+        order.sort_unstable_by_key(|&i| requests[i].0);
+
+        // (合并起点, 合并长度, 组内请求)
+        let page_size = *PAGE_SIZE as u64;
+        let mut groups: Vec<(u64, usize, &[usize])> = Vec::new();
+        let mut group_start = 0;
+        while group_start < order.len() {
+            let base = requests[order[group_start]].0;
+            let mut end = base.saturating_add(requests[order[group_start]].1 as u64);
+            let mut group_end = group_start + 1;
+            while group_end < order.len() {
+                let (addr, size) = requests[order[group_end]];
+                let new_end = end.max(addr.saturating_add(size as u64));
+                if addr > end.saturating_add(page_size) || new_end - base > BATCH_READ_MAX_SPAN as u64 {
+                    break;
+                }
+                end = new_end;
+                group_end += 1;
+            }
+
+            groups.push((base, (end - base) as usize, &order[group_start..group_end]));
+            group_start = group_end;
+        }
+
+        let group_reads: Vec<(u64, usize)> = groups.iter().map(|&(base, len, _)| (base, len)).collect();
+        let mut retry = Vec::new();
+        for (&(base, _, group), result) in groups.iter().zip(self.read_memory_many(&group_reads)) {
+            match result {
+                Ok(buffer) => {
+                    for &i in group {
+                        let (addr, size) = requests[i];
+                        let offset = (addr - base) as usize;
+                        results[i] = Some(Ok(buffer[offset..offset + size].to_vec()));
+                    }
+                },
+                Err(e) if group.len() == 1 => results[group[0]] = Some(Err(e)),
+                Err(_) => retry.extend_from_slice(group),
+            }
+        }
+
+        if !retry.is_empty() {
+            let reads: Vec<(u64, usize)> = retry.iter().map(|&i| requests[i]).collect();
+            for (&i, result) in retry.iter().zip(self.read_memory_many(&reads)) {
+                results[i] = Some(result);
+            }
+        }
+
+        results.into_iter().map(|r| r.unwrap_or_else(|| Ok(Vec::new()))).collect()
+    }
+
+    /// 读取多段互不相关的内存
+    ///
+    /// BindProc 模式下通过驱动的向量化命令合并为尽量少的 ioctl，
+    /// 驱动不支持或其他模式时逐段读取。
+    ///
+    /// # Returns
+    /// 与 `reads` 顺序一一对应的读取结果
+    pub fn read_memory_many(&self, reads: &[(u64, usize)]) -> Vec<anyhow::Result<Vec<u8>>> {
+        let mut buffers: Vec<Vec<u8>> = reads.iter().map(|&(_, size)| vec![0u8; size]).collect();
+
+        if let Some(bind_proc) = self.batched_bind_proc() {
+            background::throttle_read(reads.iter().map(|&(_, size)| size).sum());
+            let mut requests: Vec<(usize, &mut [u8])> = reads
+                .iter()
+                .zip(buffers.iter_mut())
+                .map(|(&(addr, _), buf)| (addr as usize, buf.as_mut_slice()))
+                .collect();
+            let succeeded = bind_proc.read_memory_vec(&mut requests);
+
+            return buffers
+                .into_iter()
+                .zip(succeeded)
+                .zip(reads)
+                .map(|((buf, ok), &(addr, size))| {
+                    if ok {
+                        Ok(buf)
+                    } else {
+                        Err(anyhow::anyhow!("BindProc read failed: va=0x{:x} size={}", addr, size))
+                    }
+                })
+                .collect();
+        }
+
+        reads
+            .iter()
+            .zip(buffers)
+            .map(|(&(addr, _), mut buf)| self.read_memory_unified(addr, &mut buf, None).map(|_| buf))
+            .collect()
+    }
+
+    /// 批量写入多个地址（冻结等每个周期写入大量小值的场景）
+    ///
+    /// BindProc 模式下相邻地址先合并，再通过驱动的向量化命令提交；其他模式逐个写入。
+    ///
+    /// # Returns
+    /// 与 `writes` 顺序一一对应的写入是否成功
+    pub fn write_memory_batch(&self, writes: &[(u64, &[u8])]) -> Vec<bool> {
+        if let Some(bind_proc) = self.batched_bind_proc() {
+            let requests: Vec<(usize, &[u8])> = writes.iter().map(|&(addr, buf)| (addr as usize, buf)).collect();
+            return bind_proc.write_memory_vec(&requests);
+        }
+
+        writes.iter().map(|&(addr, buf)| self.write_memory_unified(addr, buf).is_ok()).collect()
+    }
+
+    /// 当前模式走 BindProc 时返回它，可使用向量化命令
+    fn batched_bind_proc(&self) -> Option<&BindProc> {
+        match self.access_mode {
+            MemoryAccessMode::NonCacheable | MemoryAccessMode::WriteThrough | MemoryAccessMode::Normal => self.get_bound_process(),
+            MemoryAccessMode::None | MemoryAccessMode::PageFault | MemoryAccessMode::ProcessVm | MemoryAccessMode::ProcMem => None,
         }
     }
 
@@ -183,13 +574,22 @@ impl DriverManager {
         addr: u64,
         buf: &[u8],
     ) -> anyhow::Result<()> {
+        self.write_target(self.current_target(), addr, buf)
+    }
+
+    /// 按 pid 写入当前进程或附加的进程
+    pub fn write_memory_of(&self, pid: i32, addr: u64, buf: &[u8]) -> anyhow::Result<()> {
+        self.write_target(self.target(pid)?, addr, buf)
+    }
+
+    fn write_target(&self, target: Target<'_>, addr: u64, buf: &[u8]) -> anyhow::Result<()> {
         match self.access_mode {
             MemoryAccessMode::None => {
                 // 物理内存写入（绕过 access_mode）
                 let driver = self
                     .get_driver()
-                    .ok_or_else(|| anyhow::anyhow!("Driver not initialized"))?;
-                let pid = self.get_bound_pid();
+                    .ok_or_else(MamuError::driver_not_loaded)?;
+                let pid = target.pid;
                 driver.write_physical_memory(
                     pid,
                     buf.as_ptr() as usize,
@@ -199,26 +599,205 @@ impl DriverManager {
                 Ok(())
             },
             MemoryAccessMode::PageFault => {
-                // 缺页模式：通过 driver 正常写入
-                let driver = self
-                    .get_driver()
-                    .ok_or_else(|| anyhow::anyhow!("Driver not initialized"))?;
-                let pid = self.get_bound_pid();
-                driver.write_memory(
-                    pid,
-                    buf.as_ptr() as usize,
-                    addr as usize,
-                    buf.len(),
-                )?;
-                Ok(())
+                // 缺页模式：通过当前后端正常写入
+                self.backend().write_memory(target.pid, addr, buf)
             },
             MemoryAccessMode::NonCacheable | MemoryAccessMode::WriteThrough | MemoryAccessMode::Normal => {
                 // 使用 bind_proc 和配置的 access_mode
-                let bind_proc = self
-                    .get_bound_process()
-                    .ok_or_else(|| anyhow::anyhow!("Process not bound"))?;
-                bind_proc.write_memory(addr as usize, buf)
+                target.bind_proc()?.write_memory(addr as usize, buf)
             },
+            MemoryAccessMode::ProcessVm | MemoryAccessMode::ProcMem => target.user_memory()?.write(addr, buf),
+        }
+    }
+
+    /// 强制写入只读映射（如 .rodata 常量）
+    ///
+    /// 先通过缺页读取把目标页换入并保存原始字节，再用驱动的物理内存写入直接改写物理页，
+    /// 绕过页表写保护位。文件映射的只读页通常与其他进程共享 page cache，
+    /// 改写会影响所有映射了同一文件的进程。
+    ///
+    /// # Returns
+    /// * `Ok(原始字节)` 写入成功，可用于恢复
+    /// * `Err` 驱动不支持物理写入或写入失败
+    pub fn write_memory_force(&self, addr: u64, buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let driver = self
+            .get_driver()
+            .ok_or_else(MamuError::driver_not_loaded)?;
+        if !driver.capabilities().supports(WUWA_CAP_PHYSICAL) {
+            return Err(anyhow::anyhow!("This driver build does not support writing to read-only pages"));
+        }
+        if !self.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+        let pid = self.get_bound_pid();
+
+        let mut original = vec![0u8; buf.len()];
+        driver.read_memory(pid, addr as usize, original.as_mut_ptr() as usize, original.len())?;
+
+        driver
+            .write_physical_memory(pid, buf.as_ptr() as usize, addr as usize, buf.len())
+            .map_err(|e| {
+                // 能力位可能与驱动实际实现不符，ioctl 不存在时同样报不支持
+                let unsupported = find_mamu_error(&e)
+                    .and_then(|mamu| mamu.errno)
+                    .is_some_and(|errno| matches!(errno, Errno::ENOTTY | Errno::EOPNOTSUPP | Errno::ENOSYS));
+                if unsupported {
+                    anyhow::anyhow!("This driver build does not support writing to read-only pages")
+                } else {
+                    e
+                }
+            })?;
+
+        Ok(original)
+    }
+
+    /// 绑定进程中虚拟地址对应的物理地址（软件页表遍历），页不在内存中时报错
+    pub fn virt_to_phys(&self, addr: u64) -> anyhow::Result<u64> {
+        let driver = self.physical_driver()?;
+        if !self.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+        driver.addr_translate(self.get_bound_pid(), addr as usize)
+    }
+
+    /// 按物理地址读取，不能跨页
+    pub fn read_physical(&self, phys_addr: u64, size: usize) -> anyhow::Result<Vec<u8>> {
+        let driver = self.physical_driver()?;
+        let page_size = *PAGE_SIZE as u64;
+        if size == 0 || (phys_addr & (page_size - 1)) + size as u64 > page_size {
+            return Err(anyhow::anyhow!("Physical read must stay within one page"));
+        }
+        let mut buf = vec![0u8; size];
+        driver.read_physical_addr(phys_addr, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// 支持物理内存访问的驱动
+    fn physical_driver(&self) -> anyhow::Result<&WuWaDriver> {
+        let driver = self
+            .get_driver()
+            .ok_or_else(MamuError::driver_not_loaded)?;
+        if !driver.capabilities().supports(WUWA_CAP_PHYSICAL) {
+            return Err(anyhow::anyhow!("Driver does not support physical memory access"));
+        }
+        Ok(driver)
+    }
+
+    /// `addr` 所在映射的当前权限（PROT_*）
+    pub fn memory_protection(&self, addr: u64) -> anyhow::Result<libc::c_int> {
+        if !self.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+        query_mem_regions(self, self.get_bound_pid())?
+            .iter()
+            .find(|region| region.start <= addr && addr < region.end)
+            .map(|region| prot_of(region.flags))
+            .ok_or_else(|| anyhow::anyhow!("0x{:x} is not mapped", addr))
+    }
+
+    /// 修改绑定进程中 `[addr, addr + size)` 所在页的权限（PROT_*），范围向外扩展到页边界
+    ///
+    /// # Returns
+    /// * `Ok(原权限)` `addr` 所在映射修改前的权限，可用于恢复
+    pub fn set_memory_protection(&self, addr: u64, size: u64, prot: libc::c_int) -> anyhow::Result<libc::c_int> {
+        if prot & !(libc::PROT_READ | libc::PROT_WRITE | libc::PROT_EXEC) != 0 {
+            return Err(anyhow::anyhow!("Invalid protection: 0x{:x}", prot));
+        }
+        let bind_proc = self
+            .get_bound_process()
+            .ok_or_else(MamuError::not_bound)?;
+        let previous = self.memory_protection(addr)?;
+
+        let page_size = *PAGE_SIZE as u64;
+        let start = addr & !(page_size - 1);
+        let end = addr
+            .checked_add(size.max(1))
+            .ok_or_else(|| anyhow::anyhow!("Protection range overflows"))?
+            .next_multiple_of(page_size);
+        bind_proc.remote_mprotect(start as usize, (end - start) as usize, prot)?;
+        Ok(previous)
+    }
+
+    /// 按 `flags`（`WRITE_FLAG_*`）选择写入方式，不带标志时等同 [`Self::write_memory_unified`]
+    pub fn write_memory_ex(&self, addr: u64, buf: &[u8], flags: u32) -> anyhow::Result<()> {
+        if flags & WRITE_FLAG_COW != 0 {
+            self.write_memory_cow(addr, buf)
+        } else {
+            self.write_memory_unified(addr, buf)
+        }
+    }
+
+    /// 写时复制安全的写入，用于只读数据或代码页
+    ///
+    /// 目标区域已可写时直接写入。否则把涉及的页临时改为原权限加写，
+    /// 经缺页路径写入让内核为进程复制出私有页，再恢复原权限（写入失败也会恢复）。
+    /// 共享映射不做处理，因为写入会落到文件或其他进程。
+    pub fn write_memory_cow(&self, addr: u64, buf: &[u8]) -> anyhow::Result<()> {
+        if !self.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+        let pid = self.get_bound_pid();
+        let end = addr
+            .checked_add(buf.len() as u64)
+            .ok_or_else(|| anyhow::anyhow!("Write range overflows"))?;
+
+        let mut regions: Vec<_> = query_mem_regions(self, pid)?
+            .into_iter()
+            .filter(|region| region.start < end && region.end > addr)
+            .collect();
+        regions.sort_by_key(|region| region.start);
+
+        // 目标范围必须被连续的映射完整覆盖
+        let mut covered = addr;
+        for region in &regions {
+            if region.start > covered {
+                break;
+            }
+            covered = covered.max(region.end);
+        }
+        if covered < end {
+            return Err(anyhow::anyhow!("0x{:x} is not mapped", covered));
+        }
+        if let Some(region) = regions.iter().find(|region| region.flags & MEM_SHARED != 0) {
+            return Err(anyhow::anyhow!("Refusing copy-on-write into shared mapping {}", region.name));
+        }
+        // /proc/<pid>/mem 的写入带 FOLL_FORCE，内核会自行为私有映射复制页
+        if self.access_mode == MemoryAccessMode::ProcMem || regions.iter().all(|region| region.flags & MEM_WRITABLE != 0) {
+            return self.write_memory_unified(addr, buf);
+        }
+
+        let bind_proc = self
+            .get_bound_process()
+            .ok_or_else(MamuError::not_bound)?;
+        let driver = self
+            .get_driver()
+            .ok_or_else(MamuError::driver_not_loaded)?;
+
+        // 只改动写入范围所在的页，(起点, 长度, 原权限)
+        let page_size = *PAGE_SIZE as u64;
+        let mut changed: Vec<(usize, usize, libc::c_int)> = Vec::new();
+        let mut result = Ok(());
+        for region in regions.iter().filter(|region| region.flags & MEM_WRITABLE == 0) {
+            let start = region.start.max(addr & !(page_size - 1));
+            let stop = region.end.min(end.next_multiple_of(page_size));
+            let prot = prot_of(region.flags);
+            if let Err(e) = bind_proc.remote_mprotect(start as usize, (stop - start) as usize, prot | libc::PROT_WRITE) {
+                result = Err(e);
+                break;
+            }
+            changed.push((start as usize, (stop - start) as usize, prot));
+        }
+
+        if result.is_ok() {
+            result = driver.write_memory(pid, buf.as_ptr() as usize, addr as usize, buf.len()).map(|_| ());
         }
+
+        for &(start, len, prot) in &changed {
+            if let Err(e) = bind_proc.remote_mprotect(start, len, prot) {
+                error!("Failed to restore protection of 0x{:x}+0x{:x}: {}", start, len, e);
+            }
+        }
+
+        result
     }
 }
@@ -0,0 +1,210 @@
+//! 内存转储
+//!
+//! 把绑定进程中的地址范围按块读出并写入文件，供 Ghidra/IDA 离线分析。
+//! 无法读取的页以 0 填充，文件头中的页位图记录哪些页是真实数据。
+//!
+//! 文件格式（小端）：
+//!
+//! ```text
+//! 文件头  magic "MAMUDUMP" | version u32 | page_size u32 | region_count u32 | reserved u32
+//! 区域表  每项 start u64 | size u64 | data_offset u64 | bitmap_offset u64
+//! 页位图  每个区域一份，按区域起始地址所在页计，1 位一页，1 = 已读取，0 = 空洞
+//! 数据    每个区域从 data_offset 开始（按页对齐），长度为 size
+//! ```
+//!
+//! 数据段按页对齐存放，单个区域可以直接以 raw binary 的方式在反汇编工具中按 start 加载。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::error::MamuError;
+use crate::core::globals::{DRIVER_MANAGER, JOB_REGISTRY, OP_QUEUE, PAGE_SIZE};
+use crate::core::jobs::{JobKind, JobStatus};
+use crate::wuwa::PageStatusBitmap;
+use anyhow::{anyhow, Result};
+use log::{debug, error};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub const DUMP_MAGIC: [u8; 8] = *b"MAMUDUMP";
+pub const DUMP_VERSION: u32 = 1;
+
+const HEADER_SIZE: u64 = 24;
+const REGION_ENTRY_SIZE: u64 = 32;
+
+/// 单次读取的块大小
+const DUMP_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 转储结果
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DumpSummary {
+    pub regions: usize,
+    pub bytes: u64,
+    pub readable_pages: usize,
+    pub unreadable_pages: usize,
+}
+
+/// 区域在文件中的布局
+struct RegionLayout {
+    start: u64,
+    size: u64,
+    data_offset: u64,
+    bitmap_offset: u64,
+    bitmap: Vec<u8>,
+}
+
+fn page_count(start: u64, size: u64) -> usize {
+    let page_size = *PAGE_SIZE as u64;
+    ((start & (page_size - 1)) + size).div_ceil(page_size) as usize
+}
+
+fn align_up(value: u64, align: u64) -> u64 {
+    value.div_ceil(align) * align
+}
+
+/// 把 `ranges`（起始地址, 结束地址）转储到 `path`，已存在的文件会被覆盖
+///
+/// `cancel` 在每块读取前检查，被置位时删除写了一半的文件并返回错误。
+pub fn dump_ranges(manager: &DriverManager, ranges: &[(u64, u64)], path: &str, cancel: Option<&AtomicBool>) -> Result<DumpSummary> {
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+    if ranges.is_empty() {
+        return Err(anyhow!("Nothing to dump"));
+    }
+    if let Some(&(start, end)) = ranges.iter().find(|&&(start, end)| end <= start) {
+        return Err(anyhow!("Invalid dump range 0x{:X}-0x{:X}", start, end));
+    }
+
+    let page_size = *PAGE_SIZE as u64;
+    let mut offset = HEADER_SIZE + REGION_ENTRY_SIZE * ranges.len() as u64;
+    let mut layouts: Vec<RegionLayout> = ranges
+        .iter()
+        .map(|&(start, end)| {
+            let bitmap = vec![0u8; page_count(start, end - start).div_ceil(8)];
+            let bitmap_offset = offset;
+            offset += bitmap.len() as u64;
+            RegionLayout {
+                start,
+                size: end - start,
+                data_offset: 0,
+                bitmap_offset,
+                bitmap,
+            }
+        })
+        .collect();
+    for layout in &mut layouts {
+        layout.data_offset = align_up(offset, page_size);
+        offset = layout.data_offset + layout.size;
+    }
+
+    let mut writer = BufWriter::new(File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?);
+    let mut summary = DumpSummary {
+        regions: layouts.len(),
+        ..Default::default()
+    };
+    let mut buffer = vec![0u8; DUMP_CHUNK_SIZE];
+
+    for layout in &mut layouts {
+        writer.seek(SeekFrom::Start(layout.data_offset))?;
+        let end = layout.start + layout.size;
+        let first_page = layout.start & !(page_size - 1);
+        let mut current = layout.start;
+
+        while current < end {
+            if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                drop(writer);
+                let _ = std::fs::remove_file(path);
+                return Err(MamuError::cancelled("Dump").into());
+            }
+            // 第一块之后都从页边界开始
+            let chunk_end = ((current & !(page_size - 1)) + DUMP_CHUNK_SIZE as u64).min(end);
+            let chunk = &mut buffer[..(chunk_end - current) as usize];
+            let mut page_status = PageStatusBitmap::new(chunk.len(), current as usize);
+
+            let read_ok = {
+                let _op = OP_QUEUE.bulk();
+                manager.read_memory_unified(current, chunk, Some(&mut page_status)).is_ok()
+            };
+
+            let chunk_page = current & !(page_size - 1);
+            let base_index = ((chunk_page - first_page) / page_size) as usize;
+            for page in 0..page_count(current, chunk.len() as u64) {
+                let page_start = (chunk_page + page as u64 * page_size).max(current);
+                let page_end = (chunk_page + (page as u64 + 1) * page_size).min(chunk_end);
+                let range = (page_start - current) as usize..(page_end - current) as usize;
+
+                if read_ok && page_status.is_page_success(page) {
+                    let index = base_index + page;
+                    layout.bitmap[index / 8] |= 1 << (index % 8);
+                    summary.readable_pages += 1;
+                } else {
+                    chunk[range].fill(0);
+                    summary.unreadable_pages += 1;
+                }
+            }
+
+            writer.write_all(chunk)?;
+            summary.bytes += chunk.len() as u64;
+            current = chunk_end;
+        }
+    }
+
+    writer.seek(SeekFrom::Start(0))?;
+    writer.write_all(&DUMP_MAGIC)?;
+    writer.write_all(&DUMP_VERSION.to_le_bytes())?;
+    writer.write_all(&(page_size as u32).to_le_bytes())?;
+    writer.write_all(&(layouts.len() as u32).to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?;
+    for layout in &layouts {
+        for value in [layout.start, layout.size, layout.data_offset, layout.bitmap_offset] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    for layout in &layouts {
+        writer.write_all(&layout.bitmap)?;
+    }
+    writer.flush()?;
+
+    debug!(
+        "内存转储完成: {}, {} 个区域, {} 字节, {} 页可读, {} 页空洞",
+        path, summary.regions, summary.bytes, summary.readable_pages, summary.unreadable_pages
+    );
+    Ok(summary)
+}
+
+/// 在后台线程中转储，返回任务 id，通过 [`JOB_REGISTRY`] 查询、取消或等待
+///
+/// 转储期间持有 DRIVER_MANAGER 的读锁，绑定进程等操作会等到转储结束。
+pub fn start_dump_job(ranges: Vec<(u64, u64)>, path: String) -> Result<i32> {
+    if !DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let job = JOB_REGISTRY.start(JobKind::Dump, {
+        let cancel = Arc::clone(&cancel);
+        move || cancel.store(true, Ordering::Relaxed)
+    });
+
+    let spawned = std::thread::Builder::new().name("mamu-dump".into()).spawn(move || {
+        let result = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))
+            .and_then(|manager| dump_ranges(&manager, &ranges, &path, Some(&cancel)));
+        let status = match result {
+            Ok(_) => JobStatus::Completed,
+            Err(_) if cancel.load(Ordering::Relaxed) => JobStatus::Cancelled,
+            Err(e) => {
+                error!("转储任务 {} 失败: {:?}", job, e);
+                JobStatus::Failed
+            },
+        };
+        JOB_REGISTRY.finish(job, status);
+    });
+    if let Err(e) = spawned {
+        JOB_REGISTRY.finish(job, JobStatus::Failed);
+        return Err(anyhow!("Failed to spawn dump thread: {}", e));
+    }
+    Ok(job)
+}
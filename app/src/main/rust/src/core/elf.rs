@@ -0,0 +1,236 @@
+//! 远程 ELF 解析
+//!
+//! 通过驱动读取绑定进程中已加载镜像的 ELF 头、程序头和动态段。
+//! 只能依赖加载进内存的部分：`.symtab` 不在任何 PT_LOAD 段内，
+//! 因此符号解析只使用动态符号表，即库的导出符号。
+
+use crate::core::driver_manager::DriverManager;
+use anyhow::{anyhow, Result};
+
+pub(crate) const ELF_MAGIC: [u8; 4] = *b"\x7fELF";
+pub(crate) const ELF_CLASS_64: u8 = 2;
+pub(crate) const ELF_HEADER_SIZE: usize = 64;
+pub(crate) const PHDR_SIZE: usize = 56;
+/// `e_phnum` 上限，正常镜像只有十几个
+pub(crate) const MAX_PHDRS: usize = 64;
+pub(crate) const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PF_W: u32 = 2;
+
+const DT_NULL: u64 = 0;
+const DT_HASH: u64 = 4;
+const DT_STRTAB: u64 = 5;
+const DT_SYMTAB: u64 = 6;
+const DT_STRSZ: u64 = 10;
+const DT_GNU_HASH: u64 = 0x6FFF_FEF5;
+/// 动态段条目数上限
+const MAX_DYNAMIC_ENTRIES: usize = 512;
+
+const SYM_SIZE: usize = 24;
+/// 动态符号数上限，防止读到错误的哈希表时分配过大
+const MAX_SYMBOLS: usize = 1 << 20;
+const MAX_STRTAB_SIZE: usize = 16 << 20;
+
+/// 单次读取的最大字节数
+const READ_CHUNK: usize = 64 * 1024;
+
+pub(crate) fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+pub(crate) fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+pub(crate) fn read_u64(buf: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+}
+
+/// 分块读取绑定进程内存
+fn read_remote(manager: &DriverManager, address: u64, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    for (index, chunk) in buf.chunks_mut(READ_CHUNK).enumerate() {
+        manager.read_memory_unified(address + (index * READ_CHUNK) as u64, chunk, None)?;
+    }
+    Ok(buf)
+}
+
+/// PT_LOAD 段
+#[derive(Debug, Clone, Copy)]
+pub struct LoadSegment {
+    pub vaddr: u64,
+    pub memsz: u64,
+    pub flags: u32,
+}
+
+/// 已加载到绑定进程中的 ELF 镜像
+#[derive(Debug, Clone)]
+pub struct RemoteElf {
+    /// 镜像首个映射的地址
+    pub base: u64,
+    /// 加载偏移，镜像内的虚拟地址加上它得到运行时地址
+    pub bias: u64,
+    pub loads: Vec<LoadSegment>,
+    /// PT_DYNAMIC 的虚拟地址
+    dynamic: Option<u64>,
+}
+
+impl RemoteElf {
+    /// 解析映射在 `base` 处的 ELF 镜像，不是 ELF64 时返回 None
+    ///
+    /// 加载偏移为 `base` 减去首个 PT_LOAD 按页对齐的虚拟地址，与动态链接器一致。
+    pub fn read(manager: &DriverManager, base: u64) -> Option<Self> {
+        let mut header = [0u8; ELF_HEADER_SIZE];
+        manager.read_memory_unified(base, &mut header, None).ok()?;
+        if header[..4] != ELF_MAGIC || header[4] != ELF_CLASS_64 {
+            return None;
+        }
+
+        let phoff = read_u64(&header, 0x20);
+        let phentsize = read_u16(&header, 0x36) as usize;
+        let phnum = read_u16(&header, 0x38) as usize;
+        if phentsize != PHDR_SIZE || phnum == 0 || phnum > MAX_PHDRS {
+            return None;
+        }
+
+        let mut phdrs = vec![0u8; phnum * PHDR_SIZE];
+        manager.read_memory_unified(base.checked_add(phoff)?, &mut phdrs, None).ok()?;
+
+        let mut loads = Vec::new();
+        let mut dynamic = None;
+        for phdr in phdrs.chunks_exact(PHDR_SIZE) {
+            match read_u32(phdr, 0) {
+                PT_LOAD => loads.push(LoadSegment {
+                    vaddr: read_u64(phdr, 16),
+                    memsz: read_u64(phdr, 40),
+                    flags: read_u32(phdr, 4),
+                }),
+                PT_DYNAMIC => dynamic = Some(read_u64(phdr, 16)),
+                _ => {},
+            }
+        }
+
+        let page_size = *crate::core::globals::PAGE_SIZE as u64;
+        let min_vaddr = loads.iter().map(|load| load.vaddr).min()? & !(page_size - 1);
+        Some(Self {
+            base,
+            bias: base.wrapping_sub(min_vaddr),
+            loads,
+            dynamic,
+        })
+    }
+
+    /// 最高 PT_LOAD 段的结束地址，包括 .bss
+    pub fn end(&self) -> u64 {
+        self.loads
+            .iter()
+            .map(|load| self.bias.wrapping_add(load.vaddr).wrapping_add(load.memsz))
+            .max()
+            .unwrap_or(self.base)
+    }
+
+    /// 可写 PT_LOAD 段的运行时地址范围
+    pub fn data_segments(&self) -> Vec<(u64, u64)> {
+        self.loads
+            .iter()
+            .filter(|load| load.flags & PF_W != 0 && load.memsz > 0)
+            .map(|load| {
+                let start = self.bias.wrapping_add(load.vaddr);
+                (start, start.wrapping_add(load.memsz))
+            })
+            .collect()
+    }
+
+    /// 动态段中的指针值转为运行时地址
+    ///
+    /// bionic 不改写 .dynamic，值是镜像内的虚拟地址；个别加载器会预先加上偏移，小于基址的才需要换算。
+    fn runtime_address(&self, ptr: u64) -> u64 {
+        if ptr >= self.base { ptr } else { self.bias.wrapping_add(ptr) }
+    }
+
+    /// 读取动态段的 (tag, value) 条目
+    fn dynamic_entries(&self, manager: &DriverManager) -> Result<Vec<(u64, u64)>> {
+        let dynamic = self.dynamic.ok_or_else(|| anyhow!("Image at 0x{:X} has no dynamic segment", self.base))?;
+        let raw = read_remote(manager, self.bias.wrapping_add(dynamic), MAX_DYNAMIC_ENTRIES * 16)
+            .or_else(|_| read_remote(manager, self.bias.wrapping_add(dynamic), 64 * 16))?;
+
+        Ok(raw
+            .chunks_exact(16)
+            .map(|entry| (read_u64(entry, 0), read_u64(entry, 8)))
+            .take_while(|&(tag, _)| tag != DT_NULL)
+            .collect())
+    }
+
+    /// 动态符号数，优先取 DT_HASH 的 nchain，其次遍历 DT_GNU_HASH
+    fn symbol_count(&self, manager: &DriverManager, entries: &[(u64, u64)], symtab: u64, strtab: u64) -> Result<usize> {
+        let find = |tag| entries.iter().find(|&&(t, _)| t == tag).map(|&(_, value)| self.runtime_address(value));
+
+        if let Some(hash) = find(DT_HASH) {
+            let header = read_remote(manager, hash, 8)?;
+            return Ok(read_u32(&header, 4) as usize);
+        }
+
+        if let Some(gnu_hash) = find(DT_GNU_HASH) {
+            let header = read_remote(manager, gnu_hash, 16)?;
+            let nbuckets = read_u32(&header, 0) as usize;
+            let symoffset = read_u32(&header, 4) as usize;
+            let bloom_size = read_u32(&header, 8) as usize;
+            let buckets_addr = gnu_hash + 16 + bloom_size as u64 * 8;
+            let buckets = read_remote(manager, buckets_addr, nbuckets * 4)?;
+
+            let max_bucket = (0..nbuckets).map(|i| read_u32(&buckets, i * 4) as usize).max().unwrap_or(0);
+            if max_bucket < symoffset {
+                return Ok(symoffset);
+            }
+            // 从最大的桶沿链表走到结尾标记
+            let chains_addr = buckets_addr + nbuckets as u64 * 4;
+            let mut index = max_bucket;
+            loop {
+                let chain = read_remote(manager, chains_addr + ((index - symoffset) * 4) as u64, 4)?;
+                if read_u32(&chain, 0) & 1 != 0 || index >= MAX_SYMBOLS {
+                    return Ok(index + 1);
+                }
+                index += 1;
+            }
+        }
+
+        // 没有哈希表时，符号表通常紧挨在字符串表之前
+        if strtab > symtab {
+            return Ok(((strtab - symtab) as usize) / SYM_SIZE);
+        }
+        Err(anyhow!("Unable to determine the dynamic symbol count"))
+    }
+
+    /// 在动态符号表中查找已定义的 `name`，返回运行时地址
+    pub fn find_symbol(&self, manager: &DriverManager, name: &str) -> Result<Option<u64>> {
+        let entries = self.dynamic_entries(manager)?;
+        let find = |tag| entries.iter().find(|&&(t, _)| t == tag).map(|&(_, value)| value);
+
+        let symtab = self.runtime_address(find(DT_SYMTAB).ok_or_else(|| anyhow!("Missing DT_SYMTAB"))?);
+        let strtab = self.runtime_address(find(DT_STRTAB).ok_or_else(|| anyhow!("Missing DT_STRTAB"))?);
+        let strsz = find(DT_STRSZ).ok_or_else(|| anyhow!("Missing DT_STRSZ"))? as usize;
+        if strsz > MAX_STRTAB_SIZE {
+            return Err(anyhow!("String table too large: {} bytes", strsz));
+        }
+
+        let count = self.symbol_count(manager, &entries, symtab, strtab)?.min(MAX_SYMBOLS);
+        let symbols = read_remote(manager, symtab, count * SYM_SIZE)?;
+        let strings = read_remote(manager, strtab, strsz)?;
+
+        let name = name.as_bytes();
+        for symbol in symbols.chunks_exact(SYM_SIZE) {
+            let name_offset = read_u32(symbol, 0) as usize;
+            let shndx = read_u16(symbol, 6);
+            let value = read_u64(symbol, 8);
+            if shndx == 0 || value == 0 || name_offset >= strings.len() {
+                continue;
+            }
+
+            let symbol_name = &strings[name_offset..];
+            if symbol_name.starts_with(name) && symbol_name.get(name.len()) == Some(&0) {
+                return Ok(Some(self.bias.wrapping_add(value)));
+            }
+        }
+        Ok(None)
+    }
+}
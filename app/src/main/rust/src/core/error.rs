@@ -0,0 +1,139 @@
+//! 结构化错误
+//!
+//! JNI 层原本把所有错误转成 RuntimeException 的字符串，Java 只能靠匹配文本判断原因。
+//! 需要区分原因的地方返回 [`MamuError`]，`or_throw` 沿错误链找到它后抛出带错误码的
+//! `MamuException`，内存错误同时带上出错的地址和 ioctl 命令。
+//! 其他错误按链中的 errno / io::Error 推断错误码，推断不出时为 [`ErrorCode::Unknown`]。
+
+use nix::errno::Errno;
+use std::collections::TryReserveError;
+use std::fmt;
+
+/// 错误码，与 Java 侧 `MamuException.CODE_*` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ErrorCode {
+    Unknown = 0,
+    DriverNotLoaded = 1,
+    NotBound = 2,
+    ReadFault = 3,
+    WriteFault = 4,
+    Permission = 5,
+    Cancelled = 6,
+    OutOfMemory = 7,
+}
+
+impl ErrorCode {
+    /// 由 errno 推断，无法对应时返回 None
+    pub fn from_errno(errno: Errno) -> Option<Self> {
+        match errno {
+            Errno::EPERM | Errno::EACCES => Some(ErrorCode::Permission),
+            Errno::ENOMEM => Some(ErrorCode::OutOfMemory),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MamuError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// 出错的目标进程地址
+    pub address: Option<u64>,
+    /// 出错的 ioctl 命令
+    pub ioctl: Option<u32>,
+    pub errno: Option<Errno>,
+}
+
+impl MamuError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            address: None,
+            ioctl: None,
+            errno: None,
+        }
+    }
+
+    pub fn driver_not_loaded() -> Self {
+        Self::new(ErrorCode::DriverNotLoaded, "Driver not initialized")
+    }
+
+    pub fn not_bound() -> Self {
+        Self::new(ErrorCode::NotBound, "No process is bound. Please bind a process first.")
+    }
+
+    /// `what` 为被取消的操作，如 "Dump"
+    pub fn cancelled(what: &str) -> Self {
+        Self::new(ErrorCode::Cancelled, format!("{} cancelled", what))
+    }
+
+    pub fn read_fault(address: u64, message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ReadFault, message).at(address)
+    }
+
+    pub fn write_fault(address: u64, message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::WriteFault, message).at(address)
+    }
+
+    pub fn at(mut self, address: u64) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn with_ioctl(mut self, request: u32) -> Self {
+        self.ioctl = Some(request);
+        self
+    }
+
+    /// 记录 errno，权限不足和内存不足时改用对应的错误码
+    pub fn with_errno(mut self, errno: Errno) -> Self {
+        self.errno = Some(errno);
+        if let Some(code) = ErrorCode::from_errno(errno) {
+            self.code = code;
+        }
+        self
+    }
+}
+
+impl fmt::Display for MamuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(errno) = self.errno {
+            write!(f, ": {}", errno)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MamuError {}
+
+/// 错误链中的 MamuError
+pub fn find_mamu_error(err: &anyhow::Error) -> Option<&MamuError> {
+    err.chain().find_map(|cause| cause.downcast_ref::<MamuError>())
+}
+
+/// 错误的错误码，链中没有 MamuError 时按 errno / io::Error 推断
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    if let Some(mamu) = find_mamu_error(err) {
+        return mamu.code;
+    }
+    err.chain()
+        .find_map(|cause| {
+            if let Some(errno) = cause.downcast_ref::<Errno>() {
+                ErrorCode::from_errno(*errno)
+            } else if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                match io.kind() {
+                    std::io::ErrorKind::PermissionDenied => Some(ErrorCode::Permission),
+                    std::io::ErrorKind::OutOfMemory => Some(ErrorCode::OutOfMemory),
+                    _ => io.raw_os_error().map(Errno::from_raw).and_then(ErrorCode::from_errno),
+                }
+            } else if cause.is::<TryReserveError>() {
+                Some(ErrorCode::OutOfMemory)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ErrorCode::Unknown)
+}
@@ -5,8 +5,9 @@
 use crate::core::globals::DRIVER_MANAGER;
 use dashmap::DashMap;
 use log::{debug, error, warn};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::Duration;
 use tokio::sync::Notify;
 use tokio::task::JoinHandle;
@@ -20,10 +21,22 @@ pub struct FrozenEntry {
     pub value_type: i32,
 }
 
+/// 一个进程的冻结条目和补丁范围，切换当前进程时暂存
+#[derive(Default)]
+pub struct FrozenState {
+    entries: Vec<(u64, FrozenEntry)>,
+    patched_ranges: BTreeMap<u64, usize>,
+}
+
 /// 冻结管理器
 pub struct FreezeManager {
     /// 冻结地址映射表：地址 -> 冻结条目
     frozen_entries: Arc<DashMap<u64, FrozenEntry>>,
+    /// 已打补丁的字节范围：起始地址 -> 长度，由 PatchManager 登记
+    ///
+    /// 冻结条目和补丁的冲突检查与登记都在这把锁内完成，
+    /// 调用方只持有 `FREEZE_MANAGER` 读锁时也不会让重叠的写入者同时加入
+    patched_ranges: Mutex<BTreeMap<u64, usize>>,
     /// 冻结间隔（微秒）
     interval_us: Arc<AtomicU64>,
     /// 是否正在运行
@@ -38,6 +51,7 @@ impl FreezeManager {
     pub fn new() -> Self {
         Self {
             frozen_entries: Arc::new(DashMap::new()),
+            patched_ranges: Mutex::new(BTreeMap::new()),
             interval_us: Arc::new(AtomicU64::new(33000)), // 默认 33ms
             running: Arc::new(AtomicBool::new(false)),
             stop_notify: Arc::new(Notify::new()),
@@ -124,20 +138,114 @@ impl FreezeManager {
             return;
         }
 
-        for entry in entries.iter() {
-            let addr = *entry.key();
-            let frozen = entry.value();
+        // 一个周期内的写入合并提交，减少 ioctl 次数
+        let frozen: Vec<_> = entries.iter().collect();
+        let writes: Vec<(u64, &[u8])> = frozen.iter().map(|entry| (*entry.key(), entry.value().value.as_slice())).collect();
 
-            if let Err(e) = manager.write_memory_unified(addr, &frozen.value) {
-                warn!("FreezeManager: 写入地址 0x{:X} 失败: {}", addr, e);
+        for (&(addr, _), ok) in writes.iter().zip(manager.write_memory_batch(&writes)) {
+            if !ok {
+                warn!("FreezeManager: 写入地址 0x{:X} 失败", addr);
             }
         }
     }
 
+    fn lock_patched_ranges(&self) -> MutexGuard<'_, BTreeMap<u64, usize>> {
+        self.patched_ranges.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// 添加冻结地址
-    pub fn add_frozen(&self, address: u64, value: Vec<u8>, value_type: i32) {
+    ///
+    /// 与其他已冻结条目或补丁的字节范围重叠时拒绝添加，返回冲突条目的地址，
+    /// 避免两个写入者交替写入导致数值闪烁。同一地址重复添加视为替换。
+    pub fn add_frozen(&self, address: u64, value: Vec<u8>, value_type: i32) -> Result<(), Vec<u64>> {
+        let patched = self.lock_patched_ranges();
+        let conflicts = self.conflicts_with(&patched, address, value.len(), false);
+        if !conflicts.is_empty() {
+            warn!("FreezeManager: 冻结 addr=0x{:X} 与 {} 个条目冲突", address, conflicts.len());
+            return Err(conflicts);
+        }
+
         debug!("FreezeManager: 添加冻结 addr=0x{:X}, type={}, len={}", address, value_type, value.len());
         self.frozen_entries.insert(address, FrozenEntry { value, value_type });
+        Ok(())
+    }
+
+    /// 查找与 `[address, address + len)` 重叠的补丁和其他冻结条目（不含 `address` 处的冻结本身）
+    pub fn find_conflicts(&self, address: u64, len: usize) -> Vec<u64> {
+        let patched = self.lock_patched_ranges();
+        self.conflicts_with(&patched, address, len, false)
+    }
+
+    /// 与 `[address, address + len)` 重叠的补丁和冻结条目，`include_same` 为 false 时跳过 `address` 处的冻结
+    fn conflicts_with(&self, patched: &BTreeMap<u64, usize>, address: u64, len: usize, include_same: bool) -> Vec<u64> {
+        let end = address.saturating_add(len as u64);
+        let mut conflicts: Vec<u64> = self
+            .frozen_entries
+            .iter()
+            .filter(|entry| {
+                let other = *entry.key();
+                let other_end = other.saturating_add(entry.value().value.len() as u64);
+                (include_same || other != address) && other < end && address < other_end
+            })
+            .map(|entry| *entry.key())
+            .collect();
+        conflicts.extend(
+            patched
+                .range(..end)
+                .filter(|&(&other, &other_len)| address < other.saturating_add(other_len as u64))
+                .map(|(&other, _)| other),
+        );
+        conflicts.sort_unstable();
+        conflicts.dedup();
+        conflicts
+    }
+
+    /// 登记补丁覆盖的字节范围，与冻结条目重叠时拒绝并返回冲突的冻结地址
+    ///
+    /// 补丁之间的重叠由 PatchManager 自己检查，同一地址重复登记视为替换。
+    pub fn reserve_patch(&self, address: u64, len: usize) -> Result<(), Vec<u64>> {
+        let mut patched = self.lock_patched_ranges();
+        let previous = patched.remove(&address);
+        let conflicts: Vec<u64> = self
+            .conflicts_with(&patched, address, len, true)
+            .into_iter()
+            .filter(|addr| self.frozen_entries.contains_key(addr))
+            .collect();
+        if !conflicts.is_empty() {
+            if let Some(previous) = previous {
+                patched.insert(address, previous);
+            }
+            warn!("FreezeManager: 补丁 addr=0x{:X} 与 {} 个冻结条目冲突", address, conflicts.len());
+            return Err(conflicts);
+        }
+        patched.insert(address, len);
+        Ok(())
+    }
+
+    /// 撤销补丁范围登记
+    pub fn release_patch(&self, address: u64) {
+        self.lock_patched_ranges().remove(&address);
+    }
+
+    /// 撤销全部补丁范围登记
+    pub fn release_all_patches(&self) {
+        self.lock_patched_ranges().clear();
+    }
+
+    /// 取出全部冻结条目和补丁范围并换入 `state`，冻结循环继续运行
+    ///
+    /// 调用方需持有 DriverManager 写锁，冻结循环不会在换入一半时写入。
+    pub fn swap_state(&self, state: FrozenState) -> FrozenState {
+        let mut patched = self.lock_patched_ranges();
+        let entries = self.frozen_entries.iter().map(|entry| (*entry.key(), entry.value().clone())).collect();
+        self.frozen_entries.clear();
+        for (address, entry) in state.entries {
+            self.frozen_entries.insert(address, entry);
+        }
+        FrozenState {
+            entries,
+            patched_ranges: std::mem::replace(&mut *patched, state.patched_ranges),
+        }
     }
 
     /// 移除冻结地址
@@ -179,3 +287,75 @@ impl Drop for FreezeManager {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    #[test]
+    fn test_overlapping_freeze_rejected() {
+        let manager = FreezeManager::new();
+        manager.add_frozen(0x1000, vec![0; 4], 0).unwrap();
+        assert_eq!(manager.add_frozen(0x1002, vec![0; 4], 0), Err(vec![0x1000]));
+        manager.add_frozen(0x1004, vec![0; 4], 0).unwrap();
+        // 同一地址视为替换
+        manager.add_frozen(0x1000, vec![1; 4], 0).unwrap();
+        assert_eq!(manager.get_frozen_count(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_overlapping_freezes() {
+        let manager = FreezeManager::new();
+        let barrier = Barrier::new(8);
+        let accepted = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8u64)
+                .map(|i| {
+                    let (manager, barrier) = (&manager, &barrier);
+                    scope.spawn(move || {
+                        barrier.wait();
+                        manager.add_frozen(0x2000 + i, vec![0; 8], 0).is_ok()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).filter(|&ok| ok).count()
+        });
+        assert_eq!(accepted, 1);
+        assert_eq!(manager.get_frozen_count(), 1);
+    }
+
+    #[test]
+    fn test_patch_and_freeze_conflict() {
+        let manager = FreezeManager::new();
+        manager.reserve_patch(0x3000, 8).unwrap();
+        assert_eq!(manager.add_frozen(0x3004, vec![0; 4], 0), Err(vec![0x3000]));
+        assert_eq!(manager.find_conflicts(0x2FFC, 8), vec![0x3000]);
+
+        manager.add_frozen(0x3008, vec![0; 4], 0).unwrap();
+        assert_eq!(manager.reserve_patch(0x3006, 4), Err(vec![0x3008]));
+        assert_eq!(manager.reserve_patch(0x3000, 12), Err(vec![0x3008]));
+        // 失败的重新登记保留原范围
+        assert_eq!(manager.add_frozen(0x3000, vec![0; 4], 0), Err(vec![0x3000]));
+
+        manager.release_patch(0x3000);
+        manager.add_frozen(0x3000, vec![0; 4], 0).unwrap();
+    }
+
+    #[test]
+    fn test_swap_state() {
+        let manager = FreezeManager::new();
+        manager.add_frozen(0x1000, vec![1; 4], 0).unwrap();
+        manager.reserve_patch(0x2000, 4).unwrap();
+
+        let parked = manager.swap_state(FrozenState::default());
+        assert_eq!(manager.get_frozen_count(), 0);
+        // 换出后的范围不再冲突
+        manager.add_frozen(0x2000, vec![2; 4], 0).unwrap();
+
+        let other = manager.swap_state(parked);
+        assert_eq!(manager.get_frozen_addresses(), [0x1000]);
+        assert_eq!(manager.find_conflicts(0x2000, 4), [0x2000]);
+        assert_eq!(other.entries.len(), 1);
+        assert!(other.patched_ranges.is_empty());
+    }
+}
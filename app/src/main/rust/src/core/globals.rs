@@ -1,9 +1,28 @@
 //! Global state management for core components
 
+use crate::core::art_hook::ArtHookManager;
+use crate::core::change_trigger::ChangeTrigger;
+use crate::core::compare_slots::CompareSlotManager;
 use crate::core::driver_manager::DriverManager;
 use crate::core::freeze_manager::FreezeManager;
+use crate::core::jobs::JobRegistry;
+use crate::core::op_queue::OpQueue;
+use crate::core::patch_manager::PatchManager;
+use crate::core::process_state::ProcessStateStore;
+use crate::core::process_watcher::ProcessWatcher;
+use crate::core::region_growth::RegionGrowthTracker;
+use crate::core::rules::RuleEngine;
+use crate::core::saved_list::SavedList;
+use crate::core::scan_profile::ScanProfileStore;
+use crate::core::value_history::ValueRecorder;
+use crate::core::watch::WatchManager;
+use crate::il2cpp::Il2CppDump;
+use crate::inject::{LibraryInjector, RemoteAllocator};
+use crate::pointer_scan::manager::{POINTER_SCAN_MANAGER, PointerScanManager};
+use crate::search::engine::manager::{SEARCH_ENGINE_MANAGER, SearchEngineManager};
+use crate::unreal::UnrealSession;
 use lazy_static::lazy_static;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 use tokio::runtime::Runtime;
 
 lazy_static! {
@@ -12,6 +31,60 @@ lazy_static! {
     /// Global freeze manager for value freezing
     pub static ref FREEZE_MANAGER: RwLock<FreezeManager> = RwLock::new(FreezeManager::new());
 
+    /// Global ART method hook bookkeeping
+    pub static ref ART_HOOK_MANAGER: RwLock<ArtHookManager> = RwLock::new(ArtHookManager::new());
+
+    /// Global byte patch bookkeeping
+    pub static ref PATCH_MANAGER: RwLock<PatchManager> = RwLock::new(PatchManager::new());
+
+    /// Global driver operation scheduler, UI reads take priority over bulk scans
+    pub static ref OP_QUEUE: OpQueue = OpQueue::default();
+
+    /// Global pinned compare slots
+    pub static ref COMPARE_SLOTS: RwLock<CompareSlotManager> = RwLock::new(CompareSlotManager::new());
+
+    /// Global value change trigger for scan-on-write
+    pub static ref CHANGE_TRIGGER: RwLock<ChangeTrigger> = RwLock::new(ChangeTrigger::new());
+
+    /// Global region growth tracker for heap discovery
+    pub static ref REGION_GROWTH: RwLock<RegionGrowthTracker> = RwLock::new(RegionGrowthTracker::new());
+
+    /// Global per-package scan profiles, the bound package's profile is active
+    pub static ref SCAN_PROFILES: RwLock<ScanProfileStore> = RwLock::new(ScanProfileStore::new());
+
+    /// Global hardware watchpoints and their collected hits
+    pub static ref WATCH_MANAGER: RwLock<WatchManager> = RwLock::new(WatchManager::new());
+
+    /// Global remote allocations in the bound process, freed before unbinding
+    pub static ref REMOTE_ALLOCATOR: RwLock<RemoteAllocator> = RwLock::new(RemoteAllocator::new());
+
+    /// Global shared libraries injected into the bound process
+    pub static ref LIBRARY_INJECTOR: RwLock<LibraryInjector> = RwLock::new(LibraryInjector::new());
+
+    /// Last Il2Cpp analysis of the bound process
+    pub static ref IL2CPP_DUMP: RwLock<Option<Il2CppDump>> = RwLock::new(None);
+
+    /// Unreal Engine session of the bound process
+    pub static ref UNREAL_SESSION: RwLock<Option<UnrealSession>> = RwLock::new(None);
+
+    /// State of the attached processes that are not the current one, swapped in when selected
+    pub static ref PROCESS_STATES: RwLock<ProcessStateStore> = RwLock::new(ProcessStateStore::new());
+
+    /// Global watcher that releases the binding when a bound process exits
+    pub static ref PROCESS_WATCHER: RwLock<ProcessWatcher> = RwLock::new(ProcessWatcher::new());
+
+    /// Global registry of long-running jobs (searches, pointer scans, dumps)
+    pub static ref JOB_REGISTRY: JobRegistry = JobRegistry::new();
+
+    /// Global saved address list, frozen entries are handed to FREEZE_MANAGER
+    pub static ref SAVED_LIST: RwLock<SavedList> = RwLock::new(SavedList::new());
+
+    /// Global condition rules evaluated on a native background thread
+    pub static ref RULE_ENGINE: RuleEngine = RuleEngine::new();
+
+    /// Global value history recorder for watched addresses
+    pub static ref VALUE_HISTORY: ValueRecorder = ValueRecorder::new();
+
     /// Global tokio runtime for async tasks
     /// 使用多线程运行时，worker threads 数量为 CPU 核心数
     pub static ref TOKIO_RUNTIME: Runtime = Runtime::new().expect("Failed to create tokio runtime");
@@ -27,4 +100,74 @@ lazy_static! {
             .unwrap_or(4096)
     };
     pub static ref PAGE_MASK: usize = !(*PAGE_SIZE - 1);
-}
\ No newline at end of file
+}
+
+/// Clears the poison flag of `lock`, returns 1 if it was poisoned
+pub(crate) fn clear_rwlock_poison<T: ?Sized>(lock: &RwLock<T>) -> usize {
+    if lock.is_poisoned() {
+        lock.clear_poison();
+        1
+    } else {
+        0
+    }
+}
+
+/// Clears the poison flag of `lock`, returns 1 if it was poisoned
+pub(crate) fn clear_mutex_poison<T: ?Sized>(lock: &Mutex<T>) -> usize {
+    if lock.is_poisoned() {
+        lock.clear_poison();
+        1
+    } else {
+        0
+    }
+}
+
+/// Clears the poison flag of every global lock after a panic in a native call.
+///
+/// Returns how many locks were poisoned. The guarded state may be half-updated,
+/// callers are expected to drop the process binding afterwards. The search and
+/// pointer scan managers, the usual victims of a panicking scan, are reset here.
+/// Locks private to the JNI layer are cleared by its reset call.
+///
+/// Only debug and other unwinding builds can get here: the release profile sets
+/// `panic = "abort"`, so a panic ends the process before any lock is poisoned.
+pub fn clear_poisoned_locks() -> usize {
+    use clear_rwlock_poison as clear;
+
+    fn recover<T>(lock: &RwLock<T>, reset: fn(&mut T)) -> usize {
+        if !lock.is_poisoned() {
+            return 0;
+        }
+        lock.clear_poison();
+        if let Ok(mut guard) = lock.write() {
+            reset(&mut guard);
+        }
+        1
+    }
+
+    clear(&DRIVER_MANAGER)
+        + clear(&FREEZE_MANAGER)
+        + clear(&ART_HOOK_MANAGER)
+        + clear(&PATCH_MANAGER)
+        + clear(&COMPARE_SLOTS)
+        + clear(&CHANGE_TRIGGER)
+        + clear(&REGION_GROWTH)
+        + clear(&SCAN_PROFILES)
+        + clear(&WATCH_MANAGER)
+        + clear(&REMOTE_ALLOCATOR)
+        + clear(&LIBRARY_INJECTOR)
+        + clear(&IL2CPP_DUMP)
+        + clear(&UNREAL_SESSION)
+        + clear(&PROCESS_WATCHER)
+        + clear(&PROCESS_STATES)
+        + clear(&SAVED_LIST)
+        + JOB_REGISTRY.clear_poison()
+        + RULE_ENGINE.clear_poison()
+        + VALUE_HISTORY.clear_poison()
+        + crate::core::process_pause::clear_poisoned_lock()
+        + crate::core::scan_events::clear_poisoned_lock()
+        + crate::script::clear_poisoned_locks()
+        + crate::remote::clear_poisoned_lock()
+        + recover(&SEARCH_ENGINE_MANAGER, SearchEngineManager::recover_from_poison)
+        + recover(&POINTER_SCAN_MANAGER, PointerScanManager::recover_from_poison)
+}
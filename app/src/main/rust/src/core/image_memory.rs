@@ -0,0 +1,81 @@
+//! 镜像可写段的本地副本
+//!
+//! 运行时结构（注册表、全局对象表等）大多没有导出符号，只能在模块的数据段中按特征搜索。
+//! 数据段整体读入本地后，搜索和指针追踪都在本地完成，落在段外的地址再经驱动读取。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::RemoteElf;
+
+/// 模块的数据段副本
+pub struct ImageMemory<'a> {
+    manager: &'a DriverManager,
+    segments: Vec<(u64, Vec<u8>)>,
+}
+
+impl<'a> ImageMemory<'a> {
+    /// 读取镜像的全部可写段，不可读的部分以 0 填充
+    pub fn read(manager: &'a DriverManager, image: &RemoteElf) -> Self {
+        let segments = image
+            .data_segments()
+            .into_iter()
+            .map(|(start, end)| {
+                let mut bytes = vec![0u8; (end - start) as usize];
+                for (index, chunk) in bytes.chunks_mut(1024 * 1024).enumerate() {
+                    let _ = manager.read_memory_unified(start + (index * 1024 * 1024) as u64, chunk, None);
+                }
+                (start, bytes)
+            })
+            .collect();
+        Self { manager, segments }
+    }
+
+    fn local(&self, address: u64, len: usize) -> Option<&[u8]> {
+        self.segments.iter().find_map(|(start, bytes)| {
+            let offset = address.checked_sub(*start)? as usize;
+            bytes.get(offset..offset.checked_add(len)?)
+        })
+    }
+
+    pub fn read_bytes(&self, address: u64, len: usize) -> Option<Vec<u8>> {
+        if let Some(bytes) = self.local(address, len) {
+            return Some(bytes.to_vec());
+        }
+        let mut buf = vec![0u8; len];
+        self.manager.read_memory_unified(address, &mut buf, None).ok()?;
+        Some(buf)
+    }
+
+    pub fn read_u64(&self, address: u64) -> Option<u64> {
+        self.read_bytes(address, 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn read_u32(&self, address: u64) -> Option<u32> {
+        self.read_bytes(address, 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn read_cstring(&self, address: u64, max_len: usize) -> Option<String> {
+        let bytes = self.read_bytes(address, max_len).or_else(|| self.read_bytes(address, 32))?;
+        let end = bytes.iter().position(|&b| b == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+
+    /// `address` 是否落在数据段内
+    pub fn is_data_pointer(&self, address: u64) -> bool {
+        self.local(address, 8).is_some()
+    }
+
+    /// 在数据段中按 8 字节对齐滑动 `N` 个字的窗口，返回第一个让 `matcher` 给出结果的位置
+    pub fn scan<const N: usize, T>(&self, mut matcher: impl FnMut(u64, [u64; N]) -> Option<T>) -> Option<T> {
+        for (start, bytes) in &self.segments {
+            let words = bytes.len() / 8;
+            for index in 0..words.saturating_sub(N - 1) {
+                let window: [u64; N] =
+                    std::array::from_fn(|k| u64::from_le_bytes(bytes[(index + k) * 8..(index + k + 1) * 8].try_into().unwrap()));
+                if let Some(found) = matcher(start + (index * 8) as u64, window) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+}
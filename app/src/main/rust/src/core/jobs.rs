@@ -0,0 +1,170 @@
+//! 长时间操作的任务登记
+//!
+//! 搜索、指针扫描、链构建、内存转储和 Lua 脚本开始时登记为一个任务并分配 id，Java 层可以按 id 查询状态、取消或等待结束，
+//! 同时跟踪多个并发的操作。搜索和指针扫描经 [`scan_events`](super::scan_events) 在开始/结束时自动登记，
+//! 取消时转给各模块原有的取消方式。
+//!
+//! 已结束的任务保留最近 [`MAX_FINISHED_JOBS`] 个供查询。
+
+use crate::core::globals::clear_mutex_poison;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// 保留的已结束任务数
+pub const MAX_FINISHED_JOBS: usize = 64;
+
+/// 任务类型，与 Java 侧 `Jobs.KIND_*` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum JobKind {
+    Search = 0,
+    PointerScan = 1,
+    ChainBuild = 2,
+    Dump = 3,
+    Script = 4,
+}
+
+impl JobKind {
+    pub fn from_i32(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(JobKind::Search),
+            1 => Ok(JobKind::PointerScan),
+            2 => Ok(JobKind::ChainBuild),
+            3 => Ok(JobKind::Dump),
+            4 => Ok(JobKind::Script),
+            _ => Err(anyhow!("Invalid job kind: {}", value)),
+        }
+    }
+}
+
+/// 任务状态，与 Java 侧 `Jobs.STATUS_*` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum JobStatus {
+    Running = 0,
+    Completed = 1,
+    Cancelled = 2,
+    Failed = 3,
+}
+
+type Canceller = Arc<dyn Fn() + Send + Sync>;
+
+struct Job {
+    kind: JobKind,
+    status: JobStatus,
+    cancel: Canceller,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: i32,
+    jobs: HashMap<i32, Job>,
+    /// 已结束任务的 id，按结束顺序
+    finished: Vec<i32>,
+}
+
+/// 任务登记表
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<Jobs>,
+    changed: Condvar,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清除锁的中毒状态，返回中毒的锁数
+    pub fn clear_poison(&self) -> usize {
+        clear_mutex_poison(&self.jobs)
+    }
+
+    /// 登记一个运行中的任务，`cancel` 在取消时调用，返回任务 id（从 1 开始）
+    pub fn start(&self, kind: JobKind, cancel: impl Fn() + Send + Sync + 'static) -> i32 {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return 0;
+        };
+        jobs.next_id = jobs.next_id.checked_add(1).unwrap_or(1);
+        let id = jobs.next_id;
+        jobs.jobs.insert(
+            id,
+            Job {
+                kind,
+                status: JobStatus::Running,
+                cancel: Arc::new(cancel),
+            },
+        );
+        id
+    }
+
+    /// 结束任务，已结束的不再改变
+    pub fn finish(&self, id: i32, status: JobStatus) {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return;
+        };
+        let Some(job) = jobs.jobs.get_mut(&id).filter(|job| job.status == JobStatus::Running) else {
+            return;
+        };
+        job.status = status;
+        jobs.finished.push(id);
+        if jobs.finished.len() > MAX_FINISHED_JOBS {
+            let expired = jobs.finished.remove(0);
+            jobs.jobs.remove(&expired);
+        }
+        self.changed.notify_all();
+    }
+
+    /// 请求取消，任务由各模块在下一个检查点停止后才变为 Cancelled
+    ///
+    /// # Returns
+    /// 任务是否存在且仍在运行
+    pub fn cancel(&self, id: i32) -> bool {
+        let cancel = {
+            let Ok(jobs) = self.jobs.lock() else {
+                return false;
+            };
+            match jobs.jobs.get(&id) {
+                Some(job) if job.status == JobStatus::Running => Arc::clone(&job.cancel),
+                _ => return false,
+            }
+        };
+        // 取消函数会获取各模块的锁，不能在持有登记表的锁时调用
+        cancel();
+        true
+    }
+
+    pub fn status(&self, id: i32) -> Option<JobStatus> {
+        self.jobs.lock().ok()?.jobs.get(&id).map(|job| job.status)
+    }
+
+    pub fn kind(&self, id: i32) -> Option<JobKind> {
+        self.jobs.lock().ok()?.jobs.get(&id).map(|job| job.kind)
+    }
+
+    /// 等待任务结束，`timeout` 为 None 时一直等待
+    ///
+    /// # Returns
+    /// 等待结束时的状态，超时返回 Running；任务不存在时返回 None
+    pub fn wait(&self, id: i32, timeout: Option<Duration>) -> Option<JobStatus> {
+        let jobs = self.jobs.lock().ok()?;
+        let running = |jobs: &mut Jobs| jobs.jobs.get(&id).is_some_and(|job| job.status == JobStatus::Running);
+        let jobs = match timeout {
+            Some(timeout) => self.changed.wait_timeout_while(jobs, timeout, running).ok()?.0,
+            None => self.changed.wait_while(jobs, running).ok()?,
+        };
+        jobs.jobs.get(&id).map(|job| job.status)
+    }
+
+    /// 某类型中正在运行的最新任务
+    pub fn active(&self, kind: JobKind) -> Option<i32> {
+        let jobs = self.jobs.lock().ok()?;
+        jobs.jobs
+            .iter()
+            .filter(|(_, job)| job.kind == kind && job.status == JobStatus::Running)
+            .map(|(&id, _)| id)
+            .max()
+    }
+}
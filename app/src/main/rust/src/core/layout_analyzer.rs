@@ -0,0 +1,289 @@
+//! LayoutAnalyzer - 内存结构分析
+//!
+//! 为"内存结构热力图"提供数据：把一段内存按 4 字节单元猜测类型（指针、浮点、整数、文本等），
+//! 再根据类型序列的自相关找出可能的结构体步长，并按该步长统计每个字段的主要类型。
+//! 只把分析结果交给 Java 层，不传输原始内存。
+
+use crate::core::globals::{DRIVER_MANAGER, OP_QUEUE, PAGE_SIZE};
+use crate::wuwa::PageStatusBitmap;
+use anyhow::{anyhow, Result};
+
+/// 单次分析的最大字节数
+pub const MAX_ANALYZE_SIZE: usize = 1024 * 1024;
+
+/// 分析单元大小
+const CELL_SIZE: usize = 4;
+
+/// 参与步长评分所需的最少有效单元对
+const MIN_STRIDE_SAMPLES: usize = 16;
+
+/// 返回的候选步长数量
+const MAX_STRIDE_CANDIDATES: usize = 5;
+
+/// 单元类型猜测
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CellType {
+    Unreadable = 0,
+    Zero = 1,
+    /// 8 字节对齐的用户态地址（两个单元都标记）
+    Pointer = 2,
+    Float = 3,
+    /// 8 字节对齐的 double（两个单元都标记）
+    Double = 4,
+    /// 绝对值较小的整数
+    Int = 5,
+    /// 4 个可打印 ASCII 字符
+    Text = 6,
+    Unknown = 7,
+}
+
+impl CellType {
+    /// 是否携带有效信息（参与步长评分）
+    fn is_informative(self) -> bool {
+        !matches!(self, CellType::Unreadable | CellType::Zero)
+    }
+}
+
+/// 候选结构体步长
+#[derive(Debug, Clone, Copy)]
+pub struct StrideCandidate {
+    pub stride: usize,
+    /// 相隔一个步长的单元类型一致的比例（0..1）
+    pub score: f32,
+}
+
+/// 分析结果
+#[derive(Debug, Clone)]
+pub struct LayoutAnalysis {
+    /// 第一个单元的地址（4 字节对齐）
+    pub address: u64,
+    /// 每 4 字节一个类型
+    pub cells: Vec<CellType>,
+    /// 候选步长，最可能的在前
+    pub strides: Vec<StrideCandidate>,
+    /// 按最可能步长统计的每个字段（4 字节）的主要类型，没有候选步长时为空
+    pub field_types: Vec<CellType>,
+}
+
+#[inline]
+pub(crate) fn looks_like_pointer(value: u64) -> bool {
+    // 去掉 arm64 TBI 标签字节
+    let value = value & 0x00FF_FFFF_FFFF_FFFF;
+    (0x1_0000..0x0000_8000_0000_0000).contains(&value) && value.is_multiple_of(4)
+}
+
+#[inline]
+pub(crate) fn looks_like_float(value: f32) -> bool {
+    value.is_finite() && (1e-4..=1e7).contains(&value.abs())
+}
+
+#[inline]
+pub(crate) fn looks_like_double(value: f64) -> bool {
+    value.is_finite() && (1e-6..=1e9).contains(&value.abs())
+}
+
+#[inline]
+fn is_text(bytes: &[u8]) -> bool {
+    bytes.iter().all(|b| (0x20..0x7F).contains(b))
+}
+
+fn classify_cell(bytes: [u8; 4], big_endian: bool) -> CellType {
+    let raw = if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) };
+    if raw == 0 {
+        CellType::Zero
+    } else if looks_like_float(f32::from_bits(raw)) {
+        CellType::Float
+    } else if (raw as i32).unsigned_abs() <= 100_000 {
+        CellType::Int
+    } else if is_text(&bytes) {
+        CellType::Text
+    } else {
+        CellType::Unknown
+    }
+}
+
+/// 按 4 字节单元猜测类型
+///
+/// `address` 为 `buf[0]` 的地址，需 4 字节对齐；`readable(offset)` 判断该偏移是否读取成功。
+pub fn classify_cells(buf: &[u8], address: u64, big_endian: bool, readable: impl Fn(usize) -> bool) -> Vec<CellType> {
+    let count = buf.len() / CELL_SIZE;
+    let mut cells = vec![CellType::Unknown; count];
+
+    let mut i = 0;
+    while i < count {
+        let offset = i * CELL_SIZE;
+        if !readable(offset) {
+            cells[i] = CellType::Unreadable;
+            i += 1;
+            continue;
+        }
+
+        // 8 字节对齐处先按 qword 判断指针 / double
+        if (address + offset as u64).is_multiple_of(8) && i + 1 < count && readable(offset + CELL_SIZE) {
+            let bytes: [u8; 8] = buf[offset..offset + 8].try_into().unwrap();
+            let raw = if big_endian { u64::from_be_bytes(bytes) } else { u64::from_le_bytes(bytes) };
+            let (low, high) = (raw as u32, (raw >> 32) as u32);
+
+            // 两个相邻的小整数拼起来也落在地址范围内，不算指针
+            let small_ints = (low as i32).unsigned_abs() <= 100_000 && (high as i32).unsigned_abs() <= 100_000;
+
+            let qword_type = if !small_ints && looks_like_pointer(raw) {
+                Some(CellType::Pointer)
+            } else if low != 0 && high != 0 && !looks_like_float(f32::from_bits(low)) && looks_like_double(f64::from_bits(raw)) {
+                Some(CellType::Double)
+            } else {
+                None
+            };
+            if let Some(typ) = qword_type {
+                cells[i] = typ;
+                cells[i + 1] = typ;
+                i += 2;
+                continue;
+            }
+        }
+
+        cells[i] = classify_cell(buf[offset..offset + CELL_SIZE].try_into().unwrap(), big_endian);
+        i += 1;
+    }
+
+    cells
+}
+
+/// 根据类型序列的自相关给出候选步长（字节）
+///
+/// 步长的整数倍得分往往接近真实步长，因此在最高分 95% 以内的候选中优先取最小步长。
+pub fn detect_strides(cells: &[CellType], max_stride: usize) -> Vec<StrideCandidate> {
+    let max_cells = (max_stride / CELL_SIZE).min(cells.len() / 2);
+    let mut candidates = Vec::new();
+
+    for stride_cells in 2..=max_cells {
+        let mut matches = 0usize;
+        let mut compared = 0usize;
+        for (a, b) in cells.iter().zip(&cells[stride_cells..]) {
+            if *a == CellType::Unreadable || *b == CellType::Unreadable {
+                continue;
+            }
+            if a.is_informative() || b.is_informative() {
+                compared += 1;
+                if a == b {
+                    matches += 1;
+                }
+            }
+        }
+        if compared >= MIN_STRIDE_SAMPLES {
+            candidates.push(StrideCandidate {
+                stride: stride_cells * CELL_SIZE,
+                score: matches as f32 / compared as f32,
+            });
+        }
+    }
+
+    let Some(best) = candidates.iter().map(|c| c.score).reduce(f32::max) else {
+        return candidates;
+    };
+    let likely = candidates.iter().find(|c| c.score >= best * 0.95).map(|c| c.stride);
+
+    candidates.sort_by(|a, b| {
+        let a_key = (Some(a.stride) != likely, std::cmp::Reverse((a.score * 1_000_000.0) as u32));
+        let b_key = (Some(b.stride) != likely, std::cmp::Reverse((b.score * 1_000_000.0) as u32));
+        a_key.cmp(&b_key)
+    });
+    candidates.truncate(MAX_STRIDE_CANDIDATES);
+    candidates
+}
+
+/// 按步长统计每个字段出现最多的有效类型
+pub fn field_types(cells: &[CellType], stride: usize) -> Vec<CellType> {
+    let stride_cells = stride / CELL_SIZE;
+    (0..stride_cells)
+        .map(|field| {
+            let mut counts = [0usize; 8];
+            for cell in cells.iter().skip(field).step_by(stride_cells) {
+                counts[*cell as usize] += 1;
+            }
+            // Zero 只在没有其他有效类型时采用
+            let best = [CellType::Pointer, CellType::Float, CellType::Double, CellType::Int, CellType::Text, CellType::Unknown]
+                .into_iter()
+                .max_by_key(|t| counts[*t as usize])
+                .filter(|t| counts[*t as usize] > 0);
+            best.unwrap_or(if counts[CellType::Zero as usize] > 0 { CellType::Zero } else { CellType::Unreadable })
+        })
+        .collect()
+}
+
+/// 读取绑定进程中 `[address, address + size)` 并分析结构
+pub fn analyze_layout(address: u64, size: usize, max_stride: usize, big_endian: bool) -> Result<LayoutAnalysis> {
+    if size == 0 || size > MAX_ANALYZE_SIZE {
+        return Err(anyhow!("Analyze size must be between 1 and {} bytes", MAX_ANALYZE_SIZE));
+    }
+
+    let address = address & !(CELL_SIZE as u64 - 1);
+    let size = size.next_multiple_of(CELL_SIZE);
+    let mut buf = vec![0u8; size];
+    let mut page_status = PageStatusBitmap::new(size, address as usize);
+    {
+        let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let _op = OP_QUEUE.interactive();
+        manager.read_memory_unified(address, &mut buf, Some(&mut page_status))?;
+    }
+
+    let page_size = *PAGE_SIZE;
+    let page_offset = address as usize & (page_size - 1);
+    let cells = classify_cells(&buf, address, big_endian, |offset| page_status.is_page_success((page_offset + offset) / page_size));
+
+    let strides = detect_strides(&cells, max_stride);
+    let field_types = strides.first().map(|c| field_types(&cells, c.stride)).unwrap_or_default();
+
+    Ok(LayoutAnalysis { address, cells, strides, field_types })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_cells() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&0x7000_1234_5670u64.to_le_bytes());
+        buf.extend_from_slice(&1.1f64.to_le_bytes());
+        // 对齐处的 float + 小整数拼起来像地址，这里让 float 落在非对齐单元
+        buf.extend_from_slice(&[0; 4]);
+        buf.extend_from_slice(&1.5f32.to_le_bytes());
+        buf.extend_from_slice(&42i32.to_le_bytes());
+        buf.extend_from_slice(b"abcd");
+        // 两个相邻的小整数，拼起来也像地址
+        buf.extend_from_slice(&0x0000_0002_0001_0000u64.to_le_bytes());
+        buf.extend_from_slice(&[0xFF; 4]);
+        buf.extend_from_slice(&0xDEAD_BEEFu32.to_le_bytes());
+
+        let cells = classify_cells(&buf, 0x1000, false, |offset| offset != 40);
+        use CellType::*;
+        assert_eq!(cells, [Pointer, Pointer, Double, Double, Zero, Float, Int, Text, Int, Int, Unreadable, Unknown]);
+
+        // 不在 8 字节对齐处的 qword 不按指针判断
+        let cells = classify_cells(&buf[..8], 0x1004, false, |_| true);
+        assert!(!cells.contains(&Pointer));
+
+        assert_eq!(classify_cells(&42i32.to_be_bytes(), 0x1000, true, |_| true), [Int]);
+    }
+
+    #[test]
+    fn test_detect_strides() {
+        use CellType::*;
+        let layout = [Pointer, Pointer, Float, Int, Zero, Text];
+        let mut cells: Vec<CellType> = layout.iter().copied().cycle().take(layout.len() * 20).collect();
+        cells[7] = Unreadable;
+
+        let strides = detect_strides(&cells, 256);
+        // 24 的整数倍同样满分，取最小的
+        assert_eq!(strides[0].stride, 24);
+        assert_eq!(strides[0].score, 1.0);
+        assert!(strides.len() <= MAX_STRIDE_CANDIDATES);
+        assert_eq!(field_types(&cells, 24), layout);
+
+        // 样本不足时不给候选
+        assert!(detect_strides(&cells[..12], 256).is_empty());
+        assert!(detect_strides(&[Zero; 200], 256).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Memory access mode definitions
 
+use crate::core::user_memory::UserMemoryKind;
 use crate::wuwa::WuwaMemoryType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,6 +10,10 @@ pub enum MemoryAccessMode {
     WriteThrough,
     Normal,
     PageFault,
+    /// 用户态 process_vm_readv/writev，不需要驱动
+    ProcessVm,
+    /// 用户态 /proc/<pid>/mem，不需要驱动
+    ProcMem,
 }
 
 impl MemoryAccessMode {
@@ -20,6 +25,32 @@ impl MemoryAccessMode {
             2 => Some(MemoryAccessMode::WriteThrough),
             3 => Some(MemoryAccessMode::Normal),
             4 => Some(MemoryAccessMode::PageFault),
+            5 => Some(MemoryAccessMode::ProcessVm),
+            6 => Some(MemoryAccessMode::ProcMem),
+            _ => None,
+        }
+    }
+
+    /// 与 [`Self::from_id`] 对应的 id
+    #[inline]
+    pub fn id(self) -> i32 {
+        match self {
+            MemoryAccessMode::None => 0,
+            MemoryAccessMode::NonCacheable => 1,
+            MemoryAccessMode::WriteThrough => 2,
+            MemoryAccessMode::Normal => 3,
+            MemoryAccessMode::PageFault => 4,
+            MemoryAccessMode::ProcessVm => 5,
+            MemoryAccessMode::ProcMem => 6,
+        }
+    }
+
+    /// 用户态模式对应的访问方式，驱动模式为 None
+    #[inline]
+    pub fn user_kind(self) -> Option<UserMemoryKind> {
+        match self {
+            MemoryAccessMode::ProcessVm => Some(UserMemoryKind::ProcessVm),
+            MemoryAccessMode::ProcMem => Some(UserMemoryKind::ProcMem),
             _ => None,
         }
     }
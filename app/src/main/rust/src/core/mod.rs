@@ -6,9 +6,55 @@ pub mod memory_mode;
 pub mod driver_manager;
 pub mod globals;
 pub mod freeze_manager;
+pub mod art_hook;
+pub mod compare_slots;
+pub mod op_queue;
+pub mod layout_analyzer;
+pub mod change_trigger;
+pub mod region_type;
+pub mod region_growth;
+pub mod aob;
+pub mod patch_manager;
+pub mod scan_profile;
+pub mod watch;
+pub mod elf;
+pub mod modules;
+pub mod threads;
+pub mod dump;
+pub mod snapshot;
+pub mod so_dump;
+pub mod image_memory;
+pub mod dissect;
+pub mod user_memory;
+pub mod backend;
+pub mod access_benchmark;
+pub mod app_verify;
+pub mod process_watcher;
+pub mod processes;
+pub mod scan_events;
+pub mod jobs;
+pub mod error;
+pub mod saved_list;
+pub mod cheat_table;
+pub mod wire;
+pub mod process_pause;
+pub mod rules;
+pub mod value_history;
+pub mod background;
+pub mod process_state;
 
 // Re-export commonly used items
 pub use memory_mode::MemoryAccessMode;
 pub use driver_manager::DriverManager;
 pub use globals::DRIVER_MANAGER;
-pub use freeze_manager::FreezeManager;
\ No newline at end of file
+pub use freeze_manager::FreezeManager;
+pub use art_hook::ArtHookManager;
+pub use compare_slots::CompareSlotManager;
+pub use op_queue::{OpPriority, OpQueue};
+pub use change_trigger::ChangeTrigger;
+pub use region_type::RegionType;
+pub use region_growth::RegionGrowthTracker;
+pub use aob::AobPattern;
+pub use patch_manager::PatchManager;
+pub use scan_profile::{ScanProfile, ScanProfileStore};
+pub use watch::WatchManager;
\ No newline at end of file
@@ -0,0 +1,79 @@
+//! 模块与符号枚举
+//!
+//! 从绑定进程的内存映射中找出已加载的 ELF 镜像（.so、.oat、.odex 以及直接从 APK 加载的库），
+//! 通过远程 ELF 解析得到加载偏移，并在动态符号表中解析导出符号。
+//! 指针链写成 `libfoo.so+symbol+offset` 的形式，比裸偏移更能适应游戏更新。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::RemoteElf;
+use crate::core::region_type::{query_mem_regions, MemRegion};
+use anyhow::{anyhow, Result};
+
+/// 已加载的模块
+#[derive(Debug, Clone)]
+pub struct ModuleInfo {
+    pub path: String,
+    /// 首个映射的地址
+    pub base: u64,
+    /// 从基址到最高 PT_LOAD 段结尾（含 .bss）的大小
+    pub size: u64,
+    /// 加载偏移，符号值加上它得到运行时地址
+    pub load_bias: u64,
+}
+
+impl ModuleInfo {
+    /// 文件名部分
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+fn is_image_name(name: &str) -> bool {
+    [".so", ".oat", ".odex", ".apk"].iter().any(|ext| name.ends_with(ext))
+}
+
+/// 枚举绑定进程中已加载的 ELF 模块，按基址排序
+pub fn enumerate_modules(manager: &DriverManager) -> Result<Vec<ModuleInfo>> {
+    if !manager.is_process_bound() {
+        return Err(anyhow!("No process bound"));
+    }
+
+    let mut regions: Vec<MemRegion> = query_mem_regions(manager, manager.get_bound_pid())?;
+    regions.sort_by_key(|region| region.start);
+
+    let mut modules = Vec::new();
+    let mut covered_until = 0u64;
+    for region in &regions {
+        // 已归入上一个镜像的后续段
+        if region.start < covered_until || !is_image_name(&region.name) {
+            continue;
+        }
+        let Some(image) = RemoteElf::read(manager, region.start) else {
+            continue;
+        };
+
+        covered_until = image.end();
+        modules.push(ModuleInfo {
+            path: region.name.clone(),
+            base: image.base,
+            size: image.end() - image.base,
+            load_bias: image.bias,
+        });
+    }
+    Ok(modules)
+}
+
+/// 按完整路径或文件名查找模块，同名时取基址最低的
+pub fn find_module(manager: &DriverManager, name: &str) -> Result<ModuleInfo> {
+    enumerate_modules(manager)?
+        .into_iter()
+        .find(|module| module.path == name || module.file_name() == name)
+        .ok_or_else(|| anyhow!("Module {} is not loaded", name))
+}
+
+/// 解析模块导出的符号，未找到时返回 None
+pub fn resolve_symbol(manager: &DriverManager, module: &str, symbol: &str) -> Result<Option<u64>> {
+    let module = find_module(manager, module)?;
+    let image = RemoteElf::read(manager, module.base).ok_or_else(|| anyhow!("{} is no longer mapped", module.path))?;
+    image.find_symbol(manager, symbol)
+}
@@ -0,0 +1,120 @@
+//! OpQueue - 驱动操作优先级调度
+//!
+//! 所有内存访问共享同一个驱动通道。指针扫描、全量搜索这类批量读取会持续占满通道，
+//! 导致 UI 发起的单次读写（hex 视图刷新、结果列表取值）排在后面，表现为界面卡顿。
+//!
+//! 这里把操作分为两级：
+//! - 交互操作：进入时登记，不会被阻塞
+//! - 批量操作：每次读取前检查，有交互操作在进行时让出通道，等待其完成（最多等待
+//!   [`BULK_MAX_WAIT`]，保证批量任务不会被持续刷新的界面饿死）；同时限制并发批量读取数
+//!
+//! 调用方只需在读写前持有对应的 guard。
+
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// 批量操作为交互操作让路的最长等待时间
+pub const BULK_MAX_WAIT: Duration = Duration::from_millis(50);
+
+/// 操作优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpPriority {
+    /// UI 发起的单次读写
+    Interactive,
+    /// 扫描、搜索等批量读取
+    Bulk,
+}
+
+#[derive(Default)]
+struct QueueState {
+    interactive: usize,
+    bulk: usize,
+}
+
+/// 驱动操作调度器
+pub struct OpQueue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+    /// 同时进行的批量读取上限
+    max_bulk: usize,
+}
+
+impl Default for OpQueue {
+    fn default() -> Self {
+        Self::new(std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4))
+    }
+}
+
+impl OpQueue {
+    pub fn new(max_bulk: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            changed: Condvar::new(),
+            max_bulk: max_bulk.max(1),
+        }
+    }
+
+    /// 登记一次操作，返回的 guard 释放时注销
+    pub fn enter(&self, priority: OpPriority) -> OpGuard<'_> {
+        match priority {
+            OpPriority::Interactive => self.interactive(),
+            OpPriority::Bulk => self.bulk(),
+        }
+    }
+
+    /// 登记交互操作，立即返回
+    pub fn interactive(&self) -> OpGuard<'_> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.interactive += 1;
+        OpGuard { queue: self, priority: OpPriority::Interactive }
+    }
+
+    /// 登记批量操作，有交互操作进行中或批量并发已满时等待
+    pub fn bulk(&self) -> OpGuard<'_> {
+        let deadline = Instant::now() + BULK_MAX_WAIT;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        while state.interactive > 0 || state.bulk >= self.max_bulk {
+            let now = Instant::now();
+            if now < deadline {
+                state = self.changed.wait_timeout(state, deadline - now).unwrap_or_else(|e| e.into_inner()).0;
+            } else if state.bulk < self.max_bulk {
+                // 等待超时后不再让路，只受并发上限约束
+                break;
+            } else {
+                state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+        }
+
+        state.bulk += 1;
+        OpGuard { queue: self, priority: OpPriority::Bulk }
+    }
+
+    /// 当前进行中的 (交互, 批量) 操作数
+    pub fn in_flight(&self) -> (usize, usize) {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        (state.interactive, state.bulk)
+    }
+
+    fn leave(&self, priority: OpPriority) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match priority {
+            OpPriority::Interactive => state.interactive = state.interactive.saturating_sub(1),
+            OpPriority::Bulk => state.bulk = state.bulk.saturating_sub(1),
+        }
+        drop(state);
+        self.changed.notify_all();
+    }
+}
+
+/// 操作登记凭证，drop 时注销
+pub struct OpGuard<'a> {
+    queue: &'a OpQueue,
+    priority: OpPriority,
+}
+
+impl Drop for OpGuard<'_> {
+    fn drop(&mut self) {
+        self.queue.leave(self.priority);
+    }
+}
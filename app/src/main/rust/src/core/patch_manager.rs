@@ -0,0 +1,401 @@
+//! PatchManager - 字节补丁记录
+//!
+//! 记录每处补丁写入前的原始字节，支持单个或全部恢复。
+//! 特征码替换（搜索并修改所有匹配处）的结果也登记在这里。
+//!
+//! 代码补丁写入可执行页（只读），通过写时复制只改动绑定进程自己的私有页，
+//! 不会改到映射了同一 .so 的其他进程；每个补丁有独立 id，记录所属进程，
+//! 可以在原始字节和补丁字节之间切换而不丢失记录。下面的编码函数用于生成
+//! 常见的 arm64 补丁：NOP、跳转重定向和立即数修改。
+
+use crate::core::aob::{find_pattern, AobPattern};
+use crate::core::error::MamuError;
+use crate::core::globals::{DRIVER_MANAGER, FREEZE_MANAGER};
+use crate::core::region_type::query_ranges_by_mask;
+use crate::disasm::assemble_arm64;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use std::collections::BTreeMap;
+
+/// arm64 `nop`
+pub const ARM64_NOP: u32 = 0xD503_201F;
+
+/// `b` 指令的跳转范围 ±128MB
+const BRANCH_RANGE: i64 = 1 << 27;
+
+/// 生成 `count` 条 nop
+pub fn encode_nops(count: usize) -> Vec<u8> {
+    ARM64_NOP.to_le_bytes().repeat(count)
+}
+
+/// 生成位于 `from` 处、跳转到 `to` 的 `b` 指令
+pub fn encode_branch(from: u64, to: u64) -> Result<u32> {
+    let offset = to.wrapping_sub(from) as i64;
+    if !from.is_multiple_of(4) || !to.is_multiple_of(4) {
+        return Err(anyhow!("Branch 0x{:X} -> 0x{:X} is not 4-byte aligned", from, to));
+    }
+    if !(-BRANCH_RANGE..BRANCH_RANGE).contains(&offset) {
+        return Err(anyhow!("Branch 0x{:X} -> 0x{:X} is out of range", from, to));
+    }
+    Ok(0x1400_0000 | ((offset >> 2) as u32 & 0x03FF_FFFF))
+}
+
+/// 替换指令中的立即数，保留寄存器与其他字段
+///
+/// 支持 `movz/movn/movk`（imm16）以及 `add/sub/adds/subs/cmp/cmn` 立即数形式（imm12）。
+pub fn encode_immediate(insn: u32, value: u32) -> Result<u32> {
+    // movz/movn/movk: sf opc 100101 hw imm16 Rd
+    if insn & 0x1F80_0000 == 0x1280_0000 && (insn >> 29) & 0b11 != 0b01 {
+        if value > 0xFFFF {
+            return Err(anyhow!("Immediate {} does not fit in 16 bits", value));
+        }
+        return Ok((insn & !(0xFFFF << 5)) | (value << 5));
+    }
+    // add/sub (immediate): sf op S 100010 sh imm12 Rn Rd
+    if insn & 0x1F80_0000 == 0x1100_0000 {
+        if value > 0xFFF {
+            return Err(anyhow!("Immediate {} does not fit in 12 bits", value));
+        }
+        return Ok((insn & !(0xFFF << 10)) | (value << 10));
+    }
+    Err(anyhow!("Instruction 0x{:08X} has no supported immediate field", insn))
+}
+
+/// 补丁字节中可能的跳转目标：`b`/`bl` 的目标以及 8 字节对齐的字面量（如 [`crate::core::art_hook::build_branch_stub`] 中的地址）
+fn branch_targets(address: u64, bytes: &[u8]) -> Vec<u64> {
+    let mut targets = Vec::new();
+    for (index, word) in bytes.chunks_exact(4).enumerate() {
+        let insn = u32::from_le_bytes(word.try_into().unwrap());
+        // b / bl: x00101 imm26
+        if insn & 0x7C00_0000 == 0x1400_0000 {
+            let offset = (((insn & 0x03FF_FFFF) << 6) as i32 >> 4) as i64;
+            targets.push((address + (index * 4) as u64).wrapping_add_signed(offset));
+        }
+    }
+    for offset in 0..bytes.len().saturating_sub(7) {
+        if (address + offset as u64).is_multiple_of(8) {
+            targets.push(u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()));
+        }
+    }
+    targets
+}
+
+/// 补丁的写入方式，同一地址重复打补丁时取更强的一种
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PatchWrite {
+    /// 按当前访问模式写入
+    Normal,
+    /// 写时复制，见 [`crate::core::driver_manager::DriverManager::write_memory_cow`]
+    Cow,
+    /// 通过物理内存写入只读页，共享的文件页会影响所有进程
+    Force,
+}
+
+/// 已应用的补丁
+#[derive(Debug, Clone)]
+pub struct PatchEntry {
+    pub id: i32,
+    /// 补丁所属的进程
+    pub pid: i32,
+    pub original: Vec<u8>,
+    pub patched: Vec<u8>,
+    pub write: PatchWrite,
+    /// 当前内存中是补丁字节还是原始字节
+    pub enabled: bool,
+}
+
+/// 字节补丁管理器
+#[derive(Default)]
+pub struct PatchManager {
+    /// 地址 -> 补丁，按地址排序便于检查重叠
+    patches: BTreeMap<u64, PatchEntry>,
+    next_id: i32,
+}
+
+fn bound_pid() -> Result<i32> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+    Ok(manager.get_bound_pid())
+}
+
+/// 写入 `pid` 进程，返回写入前的字节；`pid` 不是当前进程时拒绝
+fn write_patch(pid: i32, addr: u64, bytes: &[u8], write: PatchWrite) -> Result<Vec<u8>> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() || manager.get_bound_pid() != pid {
+        return Err(anyhow!("Patch at 0x{:X} belongs to process {}, which is not the current process", addr, pid));
+    }
+    if write == PatchWrite::Force {
+        return manager.write_memory_force(addr, bytes);
+    }
+
+    let mut original = vec![0u8; bytes.len()];
+    manager.read_memory_unified(addr, &mut original, None)?;
+    match write {
+        PatchWrite::Cow => manager.write_memory_cow(addr, bytes)?,
+        _ => manager.write_memory_unified(addr, bytes)?,
+    }
+    Ok(original)
+}
+
+/// 在冻结管理器中登记补丁范围，与冻结条目重叠时拒绝
+fn reserve_range(address: u64, len: usize) -> Result<()> {
+    let freeze = FREEZE_MANAGER.read().map_err(|_| anyhow!("Failed to acquire FreezeManager read lock"))?;
+    freeze
+        .reserve_patch(address, len)
+        .map_err(|frozen| anyhow!("Patch at 0x{:X} overlaps frozen entries {:X?}", address, frozen))
+}
+
+fn release_range(address: u64) {
+    match FREEZE_MANAGER.read() {
+        Ok(freeze) => freeze.release_patch(address),
+        Err(_) => warn!("PatchManager: 无法获取 FreezeManager 读锁，0x{:X} 的范围登记未撤销", address),
+    }
+}
+
+impl PatchManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 与 [address, address + len) 重叠的已有补丁地址
+    fn overlapping(&self, address: u64, len: usize) -> Vec<u64> {
+        let end = address.saturating_add(len as u64);
+        self.patches
+            .range(..end)
+            .filter(|(addr, entry)| **addr + entry.patched.len() as u64 > address)
+            .map(|(&addr, _)| addr)
+            .collect()
+    }
+
+    /// 写入补丁并记录原始字节，返回补丁 id，`force` 为 true 时通过物理内存写入
+    pub fn apply(&mut self, address: u64, bytes: &[u8], force: bool) -> Result<i32> {
+        self.apply_with(address, bytes, if force { PatchWrite::Force } else { PatchWrite::Normal })
+    }
+
+    /// 按 `write` 方式写入补丁并记录原始字节，返回补丁 id
+    ///
+    /// 同一地址重复打补丁时保留最早的原始字节和 id，与其他补丁部分重叠或与冻结条目重叠时拒绝。
+    fn apply_with(&mut self, address: u64, bytes: &[u8], write: PatchWrite) -> Result<i32> {
+        if bytes.is_empty() {
+            return Err(anyhow!("Empty patch"));
+        }
+
+        let conflicts = self.overlapping(address, bytes.len());
+        let previous = match conflicts.as_slice() {
+            [] => None,
+            [addr] if *addr == address && self.patches[addr].patched.len() == bytes.len() => self.patches.get(addr).cloned(),
+            _ => return Err(anyhow!("Patch at 0x{:X} overlaps existing patches {:X?}", address, conflicts)),
+        };
+
+        let pid = match &previous {
+            Some(prev) => prev.pid,
+            None => bound_pid()?,
+        };
+        reserve_range(address, bytes.len())?;
+        let original = match write_patch(pid, address, bytes, write) {
+            Ok(original) => original,
+            Err(e) => {
+                if previous.is_none() {
+                    release_range(address);
+                }
+                return Err(e);
+            },
+        };
+        let entry = match previous {
+            Some(prev) => PatchEntry {
+                id: prev.id,
+                pid,
+                original: prev.original,
+                patched: bytes.to_vec(),
+                write: prev.write.max(write),
+                enabled: true,
+            },
+            None => {
+                self.next_id += 1;
+                PatchEntry {
+                    id: self.next_id,
+                    pid,
+                    original,
+                    patched: bytes.to_vec(),
+                    write,
+                    enabled: true,
+                }
+            },
+        };
+        let id = entry.id;
+        self.patches.insert(address, entry);
+        Ok(id)
+    }
+
+    /// 写入代码补丁，返回补丁 id
+    ///
+    /// 地址和长度须按指令（4 字节）对齐。代码段只读，通过写时复制写入，
+    /// 共享的 page cache 保持不变。
+    pub fn apply_code(&mut self, address: u64, bytes: &[u8]) -> Result<i32> {
+        if !address.is_multiple_of(4) || !bytes.len().is_multiple_of(4) {
+            return Err(anyhow!("Code patch 0x{:X}+{} is not instruction aligned", address, bytes.len()));
+        }
+        self.apply_with(address, bytes, PatchWrite::Cow)
+    }
+
+    /// 汇编 `source`（见 [`crate::disasm::assemble_arm64`]）并作为代码补丁写入，返回补丁 id
+    pub fn apply_asm(&mut self, address: u64, source: &str) -> Result<i32> {
+        let code = assemble_arm64(source, address)?;
+        self.apply_code(address, &code)
+    }
+
+    fn address_of(&self, id: i32) -> Option<u64> {
+        self.patches.iter().find(|(_, entry)| entry.id == id).map(|(&addr, _)| addr)
+    }
+
+    /// 在补丁字节和原始字节之间切换，保留记录
+    pub fn set_enabled(&mut self, id: i32, enabled: bool) -> Result<()> {
+        let address = self.address_of(id).ok_or_else(|| anyhow!("No patch with id {}", id))?;
+        let entry = self.patches.get_mut(&address).unwrap();
+        if entry.enabled == enabled {
+            return Ok(());
+        }
+
+        let bytes = if enabled { &entry.patched } else { &entry.original };
+        write_patch(entry.pid, address, bytes, entry.write)?;
+        entry.enabled = enabled;
+        Ok(())
+    }
+
+    /// 按 id 恢复原始字节并移除记录，未找到时返回 false
+    pub fn revert(&mut self, id: i32) -> Result<bool> {
+        match self.address_of(id) {
+            Some(address) => self.restore(address).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// 查找所有特征码匹配处并替换，返回已修改的地址
+    ///
+    /// `mask` 为区域类型掩码（见 [`crate::core::region_type`]），`limit` 为 0 表示不限数量。
+    /// 替换内容中的通配字节保留原值；单处写入失败不影响其他匹配。
+    pub fn aob_replace(&mut self, pattern: &str, replacement: &str, mask: u64, limit: usize, force: bool) -> Result<Vec<u64>> {
+        let pattern = AobPattern::parse(pattern)?;
+        let replacement = AobPattern::parse(replacement)?;
+        if pattern.is_wildcard_only() {
+            return Err(anyhow!("AOB pattern must contain at least one fixed byte"));
+        }
+        if replacement.len() > pattern.len() {
+            return Err(anyhow!("Replacement ({} bytes) is longer than the pattern ({} bytes)", replacement.len(), pattern.len()));
+        }
+
+        let matches = {
+            let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            let ranges = query_ranges_by_mask(&manager, mask)?;
+            find_pattern(&manager, &pattern, &ranges, limit)
+        };
+
+        let mut patched = Vec::with_capacity(matches.len());
+        for address in matches {
+            let mut original = vec![0u8; replacement.len()];
+            let read = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?
+                .read_memory_unified(address, &mut original, None);
+            if let Err(e) = read {
+                warn!("PatchManager: 读取 0x{:X} 失败: {}", address, e);
+                continue;
+            }
+
+            // 通配字节已在匹配时确定，这里以当前值为底填充
+            let bytes = replacement.apply_to(&original);
+            match self.apply(address, &bytes, force) {
+                Ok(_) => patched.push(address),
+                Err(e) => warn!("PatchManager: 修改 0x{:X} 失败: {}", address, e),
+            }
+        }
+
+        debug!("PatchManager: 特征码替换 {} 处", patched.len());
+        Ok(patched)
+    }
+
+    /// 恢复原始字节并移除记录
+    pub fn restore(&mut self, address: u64) -> Result<()> {
+        let entry = self.patches.get(&address).ok_or_else(|| anyhow!("No patch at 0x{:X}", address))?;
+        if entry.enabled {
+            write_patch(entry.pid, address, &entry.original, entry.write)?;
+        }
+        self.patches.remove(&address);
+        release_range(address);
+        Ok(())
+    }
+
+    /// 恢复全部补丁，返回成功恢复的数量
+    pub fn restore_all(&mut self) -> usize {
+        let addresses: Vec<u64> = self.patches.keys().copied().collect();
+        let mut restored = 0;
+        for address in addresses {
+            match self.restore(address) {
+                Ok(()) => restored += 1,
+                Err(e) => warn!("PatchManager: 恢复 0x{:X} 失败: {}", address, e),
+            }
+        }
+        restored
+    }
+
+    /// 补丁字节会跳入 `ranges`（[起始, 结束)）的已启用补丁地址，这些补丁须在释放对应内存前恢复
+    pub fn branching_into(&self, ranges: &[(u64, u64)]) -> Vec<u64> {
+        let inside = |target: &u64| ranges.iter().any(|&(start, end)| (start..end).contains(target));
+        self.patches
+            .iter()
+            .filter(|&(&address, entry)| entry.enabled && branch_targets(address, &entry.patched).iter().any(inside))
+            .map(|(&address, _)| address)
+            .collect()
+    }
+
+    /// 进程解绑后调用，丢弃记录但不写内存
+    pub fn forget_all(&mut self) {
+        self.patches.clear();
+        if let Ok(freeze) = FREEZE_MANAGER.read() {
+            freeze.release_all_patches();
+        }
+    }
+
+    pub fn get(&self, address: u64) -> Option<&PatchEntry> {
+        self.patches.get(&address)
+    }
+
+    /// 全部补丁，按地址排序
+    pub fn list(&self) -> Vec<(u64, &PatchEntry)> {
+        self.patches.iter().map(|(&addr, entry)| (addr, entry)).collect()
+    }
+
+    pub fn patched_addresses(&self) -> Vec<u64> {
+        self.patches.keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.patches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patches.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::art_hook::build_branch_stub;
+
+    #[test]
+    fn test_branch_targets() {
+        let from = 0x7000_1000;
+        let mut bytes = encode_nops(1);
+        bytes.extend_from_slice(&encode_branch(from + 4, 0x7000_0000).unwrap().to_le_bytes());
+        bytes.extend_from_slice(&(encode_branch(from + 8, 0x7100_0000).unwrap() | 0x8000_0000).to_le_bytes());
+        assert_eq!(branch_targets(from, &bytes)[..2], [0x7000_0000, 0x7100_0000]);
+
+        let stub = build_branch_stub(0x7200_0040);
+        assert!(branch_targets(0x7000_2000, &stub).contains(&0x7200_0040));
+        // 字面量不在 8 字节对齐处时不算
+        assert!(!branch_targets(0x7000_2004, &stub).contains(&0x7200_0040));
+    }
+}
@@ -0,0 +1,167 @@
+//! 暂停 / 恢复目标进程
+//!
+//! 暂停期间目标进程的全部线程停止运行，可以取得一致的快照，或一次完成多处写入而不被游戏逻辑打断。
+//! 驱动没有冻结线程的命令，优先使用 cgroup v2 冻结器：Android 11 起每个应用进程有自己的
+//! `uid_<uid>/pid_<pid>` cgroup，向其 `cgroup.freeze` 写 1 即冻结且进程感知不到信号。
+//! 进程不在独占的 cgroup 中（冻结会波及其他进程）或写入失败时退回 SIGSTOP / SIGCONT。
+//!
+//! 同一时间只暂停一个进程，恢复时使用暂停时的方式。
+
+use crate::core::globals::clear_mutex_poison;
+use anyhow::{Result, anyhow};
+use log::{info, warn};
+use nix::libc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 等待进程真正停下的最长时间
+const SETTLE_TIMEOUT: Duration = Duration::from_millis(500);
+
+const SETTLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// 暂停方式，与 Java 侧 `WuwaDriver.PAUSE_*` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum PauseMethod {
+    CgroupFreezer = 0,
+    Signal = 1,
+}
+
+struct PausedProcess {
+    pid: i32,
+    method: PauseMethod,
+    /// cgroup 目录，只在冻结器方式下有
+    cgroup: Option<PathBuf>,
+}
+
+static PAUSED: Mutex<Option<PausedProcess>> = Mutex::new(None);
+
+/// 进程独占的 cgroup v2 目录，不是 `.../pid_<pid>` 形式时返回 None
+fn process_cgroup(pid: i32) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let path = content.lines().find_map(|line| line.strip_prefix("0::"))?;
+    if !path.ends_with(&format!("/pid_{}", pid)) {
+        return None;
+    }
+    let dir = PathBuf::from(format!("/sys/fs/cgroup{}", path));
+    dir.join("cgroup.freeze").exists().then_some(dir)
+}
+
+fn write_freeze(cgroup: &Path, frozen: bool) -> std::io::Result<()> {
+    std::fs::write(cgroup.join("cgroup.freeze"), if frozen { "1" } else { "0" })
+}
+
+/// 轮询直到 `done` 返回 true 或超时
+fn wait_until(done: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + SETTLE_TIMEOUT;
+    loop {
+        if done() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(SETTLE_INTERVAL);
+    }
+}
+
+fn cgroup_frozen(cgroup: &Path) -> bool {
+    std::fs::read_to_string(cgroup.join("cgroup.events")).is_ok_and(|events| events.lines().any(|line| line == "frozen 1"))
+}
+
+/// 全部线程都处于停止状态（`T`）
+fn all_threads_stopped(pid: i32) -> bool {
+    let Ok(tasks) = std::fs::read_dir(format!("/proc/{}/task", pid)) else {
+        return false;
+    };
+    tasks.flatten().all(|task| {
+        std::fs::read_to_string(task.path().join("stat"))
+            .ok()
+            // comm 可能含空格，状态字段在最后一个 ')' 之后
+            .and_then(|stat| stat.rfind(')').and_then(|end| stat[end + 1..].trim_start().chars().next()))
+            .is_some_and(|state| state == 'T' || state == 't')
+    })
+}
+
+fn signal(pid: i32, signal: libc::c_int) -> Result<()> {
+    if unsafe { libc::kill(pid, signal) } != 0 {
+        return Err(anyhow!("Failed to signal pid {}: {}", pid, std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// 暂停 `pid`，返回使用的方式。已暂停同一进程时直接返回，已暂停其他进程时返回错误
+pub fn pause_process(pid: i32) -> Result<PauseMethod> {
+    if pid <= 0 {
+        return Err(anyhow!("Invalid pid: {}", pid));
+    }
+    let mut paused = PAUSED.lock().map_err(|_| anyhow!("Failed to acquire pause lock"))?;
+    if let Some(current) = paused.as_ref() {
+        if current.pid == pid {
+            return Ok(current.method);
+        }
+        return Err(anyhow!("Process {} is already paused", current.pid));
+    }
+
+    if let Some(cgroup) = process_cgroup(pid) {
+        match write_freeze(&cgroup, true) {
+            Ok(()) => {
+                if !wait_until(|| cgroup_frozen(&cgroup)) {
+                    warn!("Pause: pid {} 的 cgroup 未在超时内冻结", pid);
+                }
+                info!("Pause: 已用 cgroup 冻结器暂停 pid {}", pid);
+                *paused = Some(PausedProcess {
+                    pid,
+                    method: PauseMethod::CgroupFreezer,
+                    cgroup: Some(cgroup),
+                });
+                return Ok(PauseMethod::CgroupFreezer);
+            },
+            Err(e) => warn!("Pause: 写入 {} 失败，改用 SIGSTOP: {}", cgroup.display(), e),
+        }
+    }
+
+    signal(pid, libc::SIGSTOP)?;
+    if !wait_until(|| all_threads_stopped(pid)) {
+        warn!("Pause: pid {} 的线程未在超时内全部停止", pid);
+    }
+    info!("Pause: 已用 SIGSTOP 暂停 pid {}", pid);
+    *paused = Some(PausedProcess {
+        pid,
+        method: PauseMethod::Signal,
+        cgroup: None,
+    });
+    Ok(PauseMethod::Signal)
+}
+
+/// 恢复暂停的进程，没有暂停的进程时返回 false
+pub fn resume_process() -> Result<bool> {
+    let mut paused = PAUSED.lock().map_err(|_| anyhow!("Failed to acquire pause lock"))?;
+    let Some(current) = paused.take() else {
+        return Ok(false);
+    };
+    let result = match &current.cgroup {
+        Some(cgroup) => write_freeze(cgroup, false).map_err(|e| anyhow!("Failed to thaw {}: {}", cgroup.display(), e)),
+        None => signal(current.pid, libc::SIGCONT),
+    };
+    if let Err(e) = result {
+        // 进程已退出时 cgroup 和 pid 都不存在了，不再保留暂停状态
+        if Path::new(&format!("/proc/{}", current.pid)).exists() {
+            *paused = Some(current);
+        }
+        return Err(e);
+    }
+    info!("Pause: 已恢复 pid {}", current.pid);
+    Ok(true)
+}
+
+/// 当前暂停的进程
+pub fn paused_pid() -> Option<i32> {
+    PAUSED.lock().ok()?.as_ref().map(|paused| paused.pid)
+}
+
+/// 清除暂停记录锁的中毒状态，返回中毒的锁数
+pub(crate) fn clear_poisoned_lock() -> usize {
+    clear_mutex_poison(&PAUSED)
+}
@@ -0,0 +1,110 @@
+//! ProcessState - 按进程暂存的状态
+//!
+//! 冻结、补丁、hook、远程分配、比较槽、Il2Cpp/Unreal 分析和搜索结果中的地址只对当前进程有效。
+//! 切换当前进程时把这些状态整体存到原进程名下，再换入新进程之前存下的状态（没有时为空），
+//! 不会把一个进程的地址和数值写进另一个进程，也不会释放仍附加的进程中的远程分配。
+
+use crate::core::art_hook::ArtHookManager;
+use crate::core::compare_slots::CompareSlotManager;
+use crate::core::freeze_manager::FrozenState;
+use crate::core::globals::{
+    ART_HOOK_MANAGER, COMPARE_SLOTS, DRIVER_MANAGER, FREEZE_MANAGER, IL2CPP_DUMP, PATCH_MANAGER, PROCESS_STATES, REMOTE_ALLOCATOR,
+    UNREAL_SESSION,
+};
+use crate::core::patch_manager::PatchManager;
+use crate::il2cpp::Il2CppDump;
+use crate::inject::RemoteAllocator;
+use crate::search::engine::manager::{ParkedSearch, SEARCH_ENGINE_MANAGER};
+use crate::unreal::UnrealSession;
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::collections::HashMap;
+
+/// 一个附加进程暂存的状态
+#[derive(Default)]
+pub struct ProcessState {
+    frozen: FrozenState,
+    patches: PatchManager,
+    hooks: ArtHookManager,
+    allocator: RemoteAllocator,
+    compare_slots: CompareSlotManager,
+    il2cpp: Option<Il2CppDump>,
+    unreal: Option<UnrealSession>,
+    /// 进程还没有过搜索结果时为 None
+    search: Option<ParkedSearch>,
+}
+
+/// 附加进程 pid -> 暂存的状态
+#[derive(Default)]
+pub struct ProcessStateStore {
+    states: HashMap<i32, ProcessState>,
+}
+
+impl ProcessStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 丢弃 `pid` 暂存的状态，进程解除附加或退出时调用
+    ///
+    /// 补丁、hook 和远程分配留在该进程中，不再追踪。
+    pub fn discard(&mut self, pid: i32) -> bool {
+        self.states.remove(&pid).is_some()
+    }
+
+    /// 丢弃全部暂存的状态，解绑时调用
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+}
+
+/// 把附加的进程 `pid` 切换为当前进程，原当前进程的状态存下，换入 `pid` 之前存下的状态
+///
+/// 搜索进行中时拒绝切换。调用方需已停止变化触发器的轮询。
+pub fn select_process(pid: i32) -> Result<()> {
+    // 这些状态的持有者会在持锁期间读写内存（获取 DriverManager 读锁），须先于 DriverManager 写锁获取
+    let mut search = SEARCH_ENGINE_MANAGER.write().map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+    let mut patches = PATCH_MANAGER.write().map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+    let mut hooks = ART_HOOK_MANAGER.write().map_err(|_| anyhow!("Failed to acquire ArtHookManager write lock"))?;
+    let mut compare_slots = COMPARE_SLOTS.write().map_err(|_| anyhow!("Failed to acquire CompareSlots write lock"))?;
+    let mut il2cpp = IL2CPP_DUMP.write().map_err(|_| anyhow!("Failed to acquire Il2CppDump write lock"))?;
+    let mut unreal = UNREAL_SESSION.write().map_err(|_| anyhow!("Failed to acquire UnrealSession write lock"))?;
+    let freeze = FREEZE_MANAGER.read().map_err(|_| anyhow!("Failed to acquire FreezeManager read lock"))?;
+    let mut manager = DRIVER_MANAGER.write().map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+    let mut allocator = REMOTE_ALLOCATOR.write().map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+    let mut store = PROCESS_STATES.write().map_err(|_| anyhow!("Failed to acquire ProcessStateStore write lock"))?;
+
+    let previous = manager.get_bound_pid();
+    let was_bound = manager.is_process_bound();
+    if was_bound && previous == pid {
+        return Ok(());
+    }
+    if !manager.attached_pids().contains(&pid) {
+        return Err(anyhow!("Process {} is not attached", pid));
+    }
+    if search.is_searching() {
+        return Err(anyhow!("Cannot switch process while a search is in progress"));
+    }
+
+    // 只有为没有结果的进程新建缓存目录时会失败，此时暂存的状态保持不变
+    let parked_search = store.states.get_mut(&pid).and_then(|state| state.search.take());
+    let search_state = search.swap_process_results(parked_search, pid)?;
+    manager.select_process(pid)?;
+
+    let incoming = store.states.remove(&pid).unwrap_or_default();
+    let outgoing = ProcessState {
+        frozen: freeze.swap_state(incoming.frozen),
+        patches: std::mem::replace(&mut *patches, incoming.patches),
+        hooks: std::mem::replace(&mut *hooks, incoming.hooks),
+        allocator: std::mem::replace(&mut *allocator, incoming.allocator),
+        compare_slots: std::mem::replace(&mut *compare_slots, incoming.compare_slots),
+        il2cpp: std::mem::replace(&mut *il2cpp, incoming.il2cpp),
+        unreal: std::mem::replace(&mut *unreal, incoming.unreal),
+        search: Some(search_state),
+    };
+    if was_bound {
+        store.states.insert(previous, outgoing);
+    }
+    debug!("ProcessState: 当前进程 {} -> {}", previous, pid);
+    Ok(())
+}
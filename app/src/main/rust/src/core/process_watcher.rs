@@ -0,0 +1,91 @@
+//! 绑定进程的退出检测
+//!
+//! 定时检查当前进程和附加的进程是否仍然存活，发现退出时交给处理函数（释放绑定并通知 Java）。
+//! 不处理的话，游戏崩溃后每次读写都会报错，驱动侧的绑定资源也一直不释放。
+//! 驱动没有进程退出事件，这里用当前后端的 `is_process_alive` 轮询。
+
+use crate::core::globals::DRIVER_MANAGER;
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// 默认轮询间隔
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 进程退出检测器
+pub struct ProcessWatcher {
+    /// 轮询线程是否应继续运行
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Default for ProcessWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessWatcher {
+    pub fn new() -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
+    /// 启动轮询线程，已在运行时不做处理
+    ///
+    /// `on_exit` 在轮询线程中调用，调用时不持有 DRIVER_MANAGER 的锁。
+    pub fn start(&mut self, interval: Duration, on_exit: impl Fn(i32) + Send + 'static) -> Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+        self.stop();
+        self.running.store(true, Ordering::SeqCst);
+
+        let running = Arc::clone(&self.running);
+        let handle = std::thread::Builder::new()
+            .name("mamu-process-watcher".into())
+            .spawn(move || {
+                while running.load(Ordering::SeqCst) {
+                    std::thread::sleep(interval);
+                    for pid in exited_pids() {
+                        on_exit(pid);
+                    }
+                }
+                debug!("ProcessWatcher: 轮询线程已退出");
+            })
+            .map_err(|e| anyhow!("Failed to spawn process watcher thread: {}", e))?;
+
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// 停止轮询并等待线程退出，不能在持有 DRIVER_MANAGER 的锁时调用
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// 轮询线程是否在运行（处理函数 panic 后线程会退出）
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst) && self.handle.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+}
+
+/// 已绑定但不再存活的进程
+fn exited_pids() -> Vec<i32> {
+    let Ok(manager) = DRIVER_MANAGER.read() else {
+        return Vec::new();
+    };
+    manager
+        .attached_pids()
+        .into_iter()
+        .filter(|&pid| !manager.backend().is_process_alive(pid))
+        .collect()
+}
@@ -0,0 +1,112 @@
+//! 进程查找
+//!
+//! 按包名查找进程、判断前台应用、按条件过滤进程列表，省得 Java 层每次刷新都取回全部进程信息自己解析。
+//! 进程名来自当前后端（驱动或 `/proc/<pid>/cmdline`），前台判断读取 `/proc/<pid>/cgroup` 和 `oom_score_adj`。
+
+use crate::core::backend::{extract_cstring, MemoryBackend};
+use crate::wuwa::WuwaGetProcInfoCmd;
+use nix::libc;
+
+/// 应用进程 uid 的范围（按 user id 取模后），见 android.os.Process
+const FIRST_APPLICATION_UID: u32 = 10000;
+const LAST_APPLICATION_UID: u32 = 19999;
+const PER_USER_RANGE: u32 = 100000;
+
+/// 前台应用的 oom_score_adj（ProcessList.FOREGROUND_APP_ADJ）
+const FOREGROUND_APP_ADJ: i32 = 0;
+
+/// 进程列表的过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct ProcessFilter {
+    /// 进程名须包含的子串，不区分大小写，空串不过滤
+    pub name: String,
+    /// uid 下限，低于它的进程被过滤（如 1000 过滤掉 Linux 原生进程）
+    pub min_uid: u32,
+    /// 只保留有名字的应用进程
+    pub apps_only: bool,
+}
+
+impl ProcessFilter {
+    fn matches(&self, info: &WuwaGetProcInfoCmd) -> bool {
+        if info.uid < self.min_uid || (self.apps_only && !is_app_uid(info.uid)) {
+            return false;
+        }
+        let name = extract_cstring(&info.name);
+        if self.apps_only && name.is_empty() {
+            return false;
+        }
+        self.name.is_empty() || name.to_lowercase().contains(&self.name.to_lowercase())
+    }
+}
+
+/// 按条件过滤后的进程信息，取信息时已退出的进程被跳过
+pub fn filter_processes(backend: &dyn MemoryBackend, filter: &ProcessFilter) -> Vec<WuwaGetProcInfoCmd> {
+    backend
+        .list_processes()
+        .into_iter()
+        .filter_map(|pid| backend.process_info(pid).ok())
+        .filter(|info| filter.matches(info))
+        .collect()
+}
+
+/// 进程的名字（cmdline 的第一段）
+pub fn process_cmdline(backend: &dyn MemoryBackend, pid: i32) -> Option<String> {
+    let info = backend.process_info(pid).ok()?;
+    Some(extract_cstring(&info.name)).filter(|name| !name.is_empty())
+}
+
+/// 是否为普通应用的 uid（含多用户）
+pub fn is_app_uid(uid: u32) -> bool {
+    (FIRST_APPLICATION_UID..=LAST_APPLICATION_UID).contains(&(uid % PER_USER_RANGE))
+}
+
+/// 按进程名或包名查找进程
+///
+/// 优先完全匹配的进程；没有时取该包的第一个子进程（`包名:xxx`）。
+pub fn find_process_by_name(backend: &dyn MemoryBackend, name: &str) -> Option<i32> {
+    let mut sub_process = None;
+    for pid in backend.list_processes() {
+        let Some(cmdline) = process_cmdline(backend, pid) else {
+            continue;
+        };
+        if cmdline == name {
+            return Some(pid);
+        }
+        if sub_process.is_none() && cmdline.split_once(':').is_some_and(|(package, _)| package == name) {
+            sub_process = Some(pid);
+        }
+    }
+    sub_process
+}
+
+fn is_top_app(pid: i32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).is_ok_and(|cgroup| cgroup.lines().any(|line| line.ends_with("/top-app")))
+}
+
+fn oom_score_adj(pid: i32) -> Option<i32> {
+    std::fs::read_to_string(format!("/proc/{}/oom_score_adj", pid)).ok()?.trim().parse().ok()
+}
+
+/// 当前前台应用的主进程
+///
+/// 在应用进程中按以下优先级挑选：位于 `top-app` cpuset、oom_score_adj 为前台值、是主进程（名字不含 ':'）、占用内存多。
+/// 本进程（悬浮窗也可能处于前台）不参与。
+pub fn foreground_app_pid(backend: &dyn MemoryBackend) -> Option<i32> {
+    let own_pid = unsafe { libc::getpid() };
+    backend
+        .list_processes()
+        .into_iter()
+        .filter(|&pid| pid != own_pid)
+        .filter_map(|pid| {
+            let info = backend.process_info(pid).ok().filter(|info| is_app_uid(info.uid))?;
+            let top_app = is_top_app(pid);
+            let foreground = oom_score_adj(pid) == Some(FOREGROUND_APP_ADJ);
+            if !top_app && !foreground {
+                return None;
+            }
+            let main_process = !extract_cstring(&info.name).contains(':');
+            Some(((top_app, foreground, main_process, info.rss), pid))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, pid)| pid)
+}
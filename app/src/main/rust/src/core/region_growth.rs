@@ -0,0 +1,138 @@
+//! RegionGrowth - 内存区域增长监视
+//!
+//! 多次采集绑定进程的内存映射，记录每个区域（按起始地址区分）的大小变化，
+//! 找出增长最快的区域，帮助用户把未知值搜索集中在游戏正在使用的分配器区域上。
+//! 基准之后新出现的区域按从 0 增长计算。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::error::MamuError;
+use crate::core::region_type::{process_name, query_mem_regions, RegionType};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// 单个区域的增长记录
+#[derive(Debug, Clone)]
+struct RegionRecord {
+    end: u64,
+    name: String,
+    region_type: Option<RegionType>,
+    /// 首次出现时的大小，基准之后新出现的区域为 0
+    first_size: u64,
+    /// 首次出现的时间
+    first_seen: Instant,
+}
+
+/// 增长查询结果
+#[derive(Debug, Clone)]
+pub struct RegionGrowth {
+    pub start: u64,
+    pub end: u64,
+    pub name: String,
+    pub region_type: Option<RegionType>,
+    pub first_size: u64,
+    /// 相对首次出现时增长的字节数（缩小时为负）
+    pub growth: i64,
+    /// 平均增长速度（字节/秒）
+    pub bytes_per_sec: f64,
+}
+
+/// 区域增长监视器
+#[derive(Default)]
+pub struct RegionGrowthTracker {
+    records: HashMap<u64, RegionRecord>,
+    generation: u32,
+    last_snapshot: Option<Instant>,
+}
+
+impl RegionGrowthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清空记录，下次采集作为新的基准
+    pub fn reset(&mut self) {
+        self.records.clear();
+        self.generation = 0;
+        self.last_snapshot = None;
+    }
+
+    /// 已采集的次数
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// 采集一次绑定进程的内存映射，返回当前区域数
+    pub fn snapshot(&mut self, manager: &DriverManager) -> Result<usize> {
+        if !manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+
+        let pid = manager.get_bound_pid();
+        let proc_name = process_name(manager, pid);
+        let regions = query_mem_regions(manager, pid)?;
+        let now = Instant::now();
+        let is_baseline = self.generation == 0;
+
+        let mut records = HashMap::with_capacity(regions.len());
+        for region in regions {
+            let region_type = region.classify(&proc_name);
+            let record = match self.records.remove(&region.start) {
+                Some(mut record) => {
+                    record.end = region.end;
+                    record.region_type = region_type;
+                    record
+                },
+                None => RegionRecord {
+                    end: region.end,
+                    first_size: if is_baseline { region.end - region.start } else { 0 },
+                    first_seen: self.last_snapshot.unwrap_or(now),
+                    name: region.name,
+                    region_type,
+                },
+            };
+            records.insert(region.start, record);
+        }
+
+        // 已消失的区域直接丢弃
+        self.records = records;
+        self.generation += 1;
+        self.last_snapshot = Some(now);
+        Ok(self.records.len())
+    }
+
+    /// 增长最快的区域，按增长字节数降序，只包含有增长的区域
+    ///
+    /// `mask` 为区域类型掩码，0 表示不过滤。
+    pub fn fastest_growing(&self, limit: usize, mask: u64) -> Vec<RegionGrowth> {
+        let Some(last) = self.last_snapshot else {
+            return Vec::new();
+        };
+
+        let mut result: Vec<RegionGrowth> = self
+            .records
+            .iter()
+            .filter(|(_, record)| mask == 0 || record.region_type.is_some_and(|t| t.matches(mask)))
+            .filter_map(|(&start, record)| {
+                let growth = (record.end - start) as i64 - record.first_size as i64;
+                if growth <= 0 {
+                    return None;
+                }
+                let elapsed = last.duration_since(record.first_seen).as_secs_f64();
+                Some(RegionGrowth {
+                    start,
+                    end: record.end,
+                    name: record.name.clone(),
+                    region_type: record.region_type,
+                    first_size: record.first_size,
+                    growth,
+                    bytes_per_sec: if elapsed > 0.0 { growth as f64 / elapsed } else { 0.0 },
+                })
+            })
+            .collect();
+
+        result.sort_unstable_by(|a, b| b.growth.cmp(&a.growth).then(a.start.cmp(&b.start)));
+        result.truncate(limit);
+        result
+    }
+}
@@ -0,0 +1,297 @@
+//! RegionType - 内存区域分类
+//!
+//! 按 GameGuardian 的方式根据映射名和权限给内存区域打标签（Jh、Ch、Ca、Cd、Cb、Xa、S、A、O 等），
+//! 规则与 Java 层 `DevideMemRange.kt` 的 `classifyRegion` 保持一致，
+//! 枚举顺序与 `MemoryRange` 的 ordinal 一致，掩码中第 n 位对应 ordinal 为 n 的类型。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::error::MamuError;
+use crate::core::backend::extract_cstring;
+use crate::wuwa::{MEM_EXECUTABLE, MEM_READABLE, MEM_SHARED, MEM_WRITABLE};
+use anyhow::Result;
+use log::debug;
+
+/// 内存区域类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RegionType {
+    /// Java Heap
+    Jh = 0,
+    /// C++ heap
+    Ch,
+    /// C++ alloc
+    Ca,
+    /// C++ .data
+    Cd,
+    /// C++ .bss
+    Cb,
+    /// PPSSPP
+    Ps,
+    /// Anonymous
+    An,
+    /// Java
+    J,
+    /// Stack
+    S,
+    /// Ashmem
+    As,
+    /// Video
+    V,
+    /// Other
+    O,
+    /// Bad
+    B,
+    /// Code app
+    Xa,
+    /// Code system
+    Xs,
+    /// DEX
+    Dx,
+    /// JIT cache code
+    Jc,
+    /// OAT Code
+    Oa,
+    /// VDEX
+    Vx,
+    /// Thread stack
+    Ts,
+    /// No perm
+    Xx,
+}
+
+impl RegionType {
+    /// 该类型在区域掩码中对应的位
+    #[inline]
+    pub fn mask(self) -> u64 {
+        1u64 << self as u8
+    }
+
+    /// 是否包含在掩码中
+    #[inline]
+    pub fn matches(self, mask: u64) -> bool {
+        mask & self.mask() != 0
+    }
+}
+
+fn is_art_jit_region(name: &str) -> bool {
+    name.contains("jit-cache") || name.contains("jit-code-cache") || name.contains("jit-zygote-cache") || name.contains("dalvik-jit")
+}
+
+fn is_art_oat_file(name: &str) -> bool {
+    name.ends_with(".oat") || name.ends_with(".odex") || name.contains(".oat (del") || name.contains(".odex (del")
+}
+
+fn is_video_device(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower.contains("/dev/mali")
+        || lower.contains("/dev/kgsl")
+        || [
+            "/dev/nv",
+            "/dev/tegra",
+            "/dev/ion",
+            "/dev/pvr",
+            "/dev/render",
+            "/dev/galcore",
+            "/dev/fimg2d",
+            "/dev/quadd",
+            "/dev/graphics",
+            "/dev/mm_",
+            "/dev/dri/",
+        ]
+        .iter()
+        .any(|p| name.contains(p))
+}
+
+fn is_dalvik_specific_chunk(name: &str) -> bool {
+    let hit = name.contains("eap")
+        || name.contains("dalvik-alloc")
+        || name.contains("dalvik-main")
+        || name.contains("dalvik-large")
+        || name.contains("dalvik-free");
+    hit && !["itmap", "ygote", "ard", "jit", "inear"].iter().any(|p| name.contains(p))
+}
+
+/// 名称是否参与堆/库等细分判断
+fn is_classifiable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains("system@")
+        && !name.contains("gralloc")
+        && !name.starts_with("[vdso]")
+        && !name.starts_with("[vectors]")
+        && !name.starts_with("/dev/ashmem")
+}
+
+/// 对单个内存区域分类，空区域返回 None
+///
+/// `proc_name` 为目标进程名，用于区分应用自身的代码和数据段。
+pub fn classify_region(start: u64, end: u64, flags: u32, name: &str, proc_name: &str) -> Option<RegionType> {
+    if start == end {
+        return None;
+    }
+
+    let readable = flags & MEM_READABLE != 0;
+    let writable = flags & MEM_WRITABLE != 0;
+    let executable = flags & MEM_EXECUTABLE != 0;
+    let shared = flags & MEM_SHARED != 0;
+    let own = !proc_name.is_empty() && name.contains(proc_name);
+
+    // JIT 代码缓存：ART 将同一 memfd 映射两次，只有可执行视图是代码
+    if is_art_jit_region(name) {
+        return Some(if executable { RegionType::Jc } else { RegionType::J });
+    }
+
+    // AOT 编译代码；不可执行的 .odex 段按 DEX 处理
+    if is_art_oat_file(name) {
+        if executable {
+            return Some(RegionType::Oa);
+        }
+        if name.ends_with(".oat") || name.contains(".oat (del") {
+            return Some(RegionType::J);
+        }
+    }
+
+    // boot/app image：ArtMethod 与对象数据
+    if name.ends_with(".art") || name.contains(".art (del") {
+        return Some(RegionType::J);
+    }
+
+    if !writable && executable {
+        return Some(if own || name.contains("/data/") { RegionType::Xa } else { RegionType::Xs });
+    }
+
+    if name.starts_with("/dev/") && is_video_device(name) {
+        return Some(RegionType::V);
+    }
+
+    if name.starts_with("/dev/") && name.contains("/dev/xLog") {
+        return Some(RegionType::B);
+    }
+
+    if name.starts_with("/system/fonts/")
+        || name.starts_with("/product/fonts/")
+        || name.starts_with("/data/data/com.google.android.gms/files/fonts/")
+    {
+        return Some(if readable && shared { RegionType::B } else { RegionType::O });
+    }
+
+    if name.starts_with("anon_inode:dma_buf") {
+        return Some(RegionType::B);
+    }
+
+    if start == 0x1000_1000 && readable && writable {
+        return Some(RegionType::S);
+    }
+
+    if !name.is_empty() {
+        if (name.contains("[anon:stack_and_tls:") || name.contains("[anon:thread signal stack]")) && readable && writable {
+            return Some(RegionType::Ts);
+        }
+
+        if name.ends_with(".vdex") && readable {
+            return Some(RegionType::Vx);
+        }
+
+        if (name.ends_with(".dex") || name.ends_with(".odex") || name.contains(".dex (del") || name.contains(".odex (del")) && readable {
+            return Some(RegionType::Dx);
+        }
+
+        if name.contains("[anon:.bss]") {
+            return Some(RegionType::Cb);
+        }
+
+        if name.starts_with("/system/") || name.starts_with("/dev/zero") {
+            return Some(RegionType::O);
+        }
+
+        if name.contains("PPSSPP_RAM") {
+            return Some(RegionType::Ps);
+        }
+
+        if is_classifiable_name(name) {
+            if name.contains("dalvik") {
+                return Some(if is_dalvik_specific_chunk(name) { RegionType::Jh } else { RegionType::J });
+            }
+
+            if name.contains("/lib") && name.contains(".so") && (own || name.contains("/data/")) {
+                return Some(RegionType::Cd);
+            }
+
+            if name.contains("malloc") || name.contains("scudo:secondary") {
+                return Some(RegionType::Ca);
+            }
+
+            if name.contains("[heap]") {
+                return Some(RegionType::Ch);
+            }
+
+            if name.contains("[stack") {
+                return Some(RegionType::S);
+            }
+
+            if name.starts_with("/dev/ashmem") && !name.contains("MemoryHeapBase") {
+                return Some(RegionType::As);
+            }
+        }
+    }
+
+    if name.is_empty() {
+        return Some(RegionType::An);
+    }
+
+    if !readable && !writable && !executable {
+        return Some(RegionType::Xx);
+    }
+
+    Some(RegionType::O)
+}
+
+/// 进程内存区域
+#[derive(Debug, Clone)]
+pub struct MemRegion {
+    pub start: u64,
+    pub end: u64,
+    /// MEM_* 权限位
+    pub flags: u32,
+    pub name: String,
+}
+
+impl MemRegion {
+    pub fn classify(&self, proc_name: &str) -> Option<RegionType> {
+        classify_region(self.start, self.end, self.flags, &self.name, proc_name)
+    }
+}
+
+/// 目标进程名，获取失败时为空字符串
+pub fn process_name(manager: &DriverManager, pid: i32) -> String {
+    manager
+        .backend()
+        .process_info(pid)
+        .map(|info| extract_cstring(&info.name))
+        .unwrap_or_default()
+}
+
+/// 读取进程的全部内存区域，来源取决于当前选中的后端
+pub fn query_mem_regions(manager: &DriverManager, pid: i32) -> Result<Vec<MemRegion>> {
+    manager.backend().query_mem_regions(pid)
+}
+
+/// 绑定进程中类型在 `mask` 内的区域，返回 (start, end)
+pub fn query_ranges_by_mask(manager: &DriverManager, mask: u64) -> Result<Vec<(u64, u64)>> {
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+
+    let pid = manager.get_bound_pid();
+    let proc_name = process_name(manager, pid);
+    let regions = query_mem_regions(manager, pid)?;
+    let total = regions.len();
+
+    let ranges: Vec<(u64, u64)> = regions
+        .into_iter()
+        .filter(|region| region.classify(&proc_name).is_some_and(|typ| typ.matches(mask)))
+        .map(|region| (region.start, region.end))
+        .collect();
+
+    debug!("RegionType: mask=0x{:X} 匹配 {}/{} 个区域", mask, ranges.len(), total);
+    Ok(ranges)
+}
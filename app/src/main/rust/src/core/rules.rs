@@ -0,0 +1,428 @@
+//! 条件规则引擎
+//!
+//! 每条规则监视一个地址或指针链上的值，条件成立时在原生层执行动作：写入值、切换保存列表条目的冻结、
+//! 运行 Lua 脚本或通知 Java。"血量低于 100 时回满" 这类自动化不需要 Java 侧轮询。
+//!
+//! 全部规则由一个后台线程按各自的间隔求值，添加第一条规则时启动，规则清空后退出。
+//! 指针链目标按 [`RESOLVE_INTERVAL`] 重新解析，读取失败时立即重新解析。
+//!
+//! 条件分两类：
+//! - 电平条件（等于、不等于、低于、高于、可解析）在成立期间每过冷却时间触发一次
+//! - 边沿条件（向下穿越、向上穿越、变化）只在值跨过阈值或变化的那次求值触发，同样受冷却时间限制
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{clear_mutex_poison, clear_rwlock_poison, DRIVER_MANAGER, FREEZE_MANAGER, OP_QUEUE, SAVED_LIST};
+use crate::core::saved_list::{Resolver, SavedLocation};
+use crate::search::types::ValueType;
+use anyhow::{Result, anyhow};
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// 规则的最小求值间隔
+pub const MIN_RULE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 指针链目标的重新解析间隔
+const RESOLVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 没有规则到期时线程最长休眠时间，新规则最迟在这之后开始求值
+const MAX_IDLE: Duration = Duration::from_millis(50);
+
+/// 触发条件，编号与 Java 侧 `RuleEngine.COND_*` 一致
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleCondition {
+    /// 原始字节相等
+    Equals(Vec<u8>),
+    NotEquals(Vec<u8>),
+    Below(f64),
+    Above(f64),
+    /// 上次不低于阈值、这次低于阈值
+    CrossedBelow(f64),
+    /// 上次不高于阈值、这次高于阈值
+    CrossedAbove(f64),
+    Changed,
+    /// 目标能解析且可读，用于等待指针链在游戏加载后生效
+    Resolves,
+}
+
+impl RuleCondition {
+    /// 由 Java 传入的编号构造，`operand` 用于相等比较，`threshold` 用于阈值比较
+    pub fn from_id(id: i32, operand: Vec<u8>, threshold: f64) -> Result<Self> {
+        Ok(match id {
+            0 => Self::Equals(operand),
+            1 => Self::NotEquals(operand),
+            2 => Self::Below(threshold),
+            3 => Self::Above(threshold),
+            4 => Self::CrossedBelow(threshold),
+            5 => Self::CrossedAbove(threshold),
+            6 => Self::Changed,
+            7 => Self::Resolves,
+            _ => return Err(anyhow!("Invalid rule condition: {}", id)),
+        })
+    }
+}
+
+/// 冻结动作的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeMode {
+    Freeze,
+    Unfreeze,
+    Toggle,
+}
+
+/// 条件成立时执行的动作
+#[derive(Debug, Clone)]
+pub enum RuleAction {
+    /// 写入 `value`，`location` 为 None 时写入规则监视的地址
+    Write { location: Option<SavedLocation>, value: Vec<u8> },
+    /// 修改保存列表条目的冻结状态
+    Freeze { entry_id: u64, mode: FreezeMode },
+    /// 运行脚本，已有脚本在运行时跳过
+    RunScript { source: String, name: String },
+    /// 通知 [`RuleListener`]
+    Notify,
+}
+
+/// 规则触发通知的接收者，在规则线程中调用，实现中不能同步修改规则
+pub trait RuleListener: Send + Sync {
+    /// `value` 为触发时读到的原始值，可解析条件下目标不可读时为空
+    fn on_rule_fired(&self, rule_id: u64, value: &[u8]);
+}
+
+/// 规则配置
+#[derive(Debug, Clone)]
+pub struct RuleConfig {
+    pub location: SavedLocation,
+    pub value_type: ValueType,
+    pub condition: RuleCondition,
+    pub interval: Duration,
+    pub cooldown: Duration,
+    /// 触发一次后自动停用
+    pub one_shot: bool,
+}
+
+struct Rule {
+    id: u64,
+    config: RuleConfig,
+    actions: Vec<RuleAction>,
+    enabled: bool,
+    fire_count: u64,
+    next_due: Instant,
+    last_fired: Option<Instant>,
+    /// 上次读到的值，用于边沿条件
+    last_value: Option<Vec<u8>>,
+    /// 缓存的目标地址及其解析时间
+    resolved: Option<(u64, Instant)>,
+}
+
+/// 一次触发需要执行的内容，在释放规则锁后执行
+struct Firing {
+    rule_id: u64,
+    address: Option<u64>,
+    value: Vec<u8>,
+    actions: Vec<RuleAction>,
+}
+
+#[derive(Default)]
+struct EngineState {
+    rules: Vec<Rule>,
+    thread_running: bool,
+}
+
+/// 条件规则引擎
+pub struct RuleEngine {
+    state: Arc<Mutex<EngineState>>,
+    next_id: AtomicU64,
+    listener: Arc<RwLock<Option<Arc<dyn RuleListener>>>>,
+}
+
+impl Default for RuleEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(EngineState::default())),
+            next_id: AtomicU64::new(1),
+            listener: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, EngineState>> {
+        self.state.lock().map_err(|_| anyhow!("Failed to acquire rule engine lock"))
+    }
+
+    /// 清除锁的中毒状态，返回中毒的锁数
+    pub fn clear_poison(&self) -> usize {
+        clear_mutex_poison(&self.state) + clear_rwlock_poison(&self.listener)
+    }
+
+    /// 添加规则并在需要时启动求值线程，返回规则 id。规则没有动作时只计数
+    pub fn add_rule(&self, config: RuleConfig) -> Result<u64> {
+        if let RuleCondition::Equals(operand) | RuleCondition::NotEquals(operand) = &config.condition
+            && operand.len() != config.value_type.size()
+        {
+            return Err(anyhow!("Operand is {} bytes, value type needs {}", operand.len(), config.value_type.size()));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.lock()?;
+        state.rules.push(Rule {
+            id,
+            config,
+            actions: Vec::new(),
+            enabled: true,
+            fire_count: 0,
+            next_due: Instant::now(),
+            last_fired: None,
+            last_value: None,
+            resolved: None,
+        });
+        if !state.thread_running {
+            let thread_state = self.state.clone();
+            let listener = self.listener.clone();
+            std::thread::Builder::new()
+                .name("mamu-rules".to_string())
+                .spawn(move || run(thread_state, listener))
+                .map_err(|e| anyhow!("Failed to spawn rule thread: {}", e))?;
+            state.thread_running = true;
+            info!("Rules: 求值线程已启动");
+        }
+        Ok(id)
+    }
+
+    /// 给规则追加动作，规则不存在时返回 false
+    pub fn add_action(&self, rule_id: u64, action: RuleAction) -> Result<bool> {
+        let mut state = self.lock()?;
+        let Some(rule) = state.rules.iter_mut().find(|rule| rule.id == rule_id) else {
+            return Ok(false);
+        };
+        if let RuleAction::Write { value, .. } = &action
+            && value.is_empty()
+        {
+            return Err(anyhow!("Write action needs a value"));
+        }
+        rule.actions.push(action);
+        Ok(true)
+    }
+
+    pub fn remove_rule(&self, rule_id: u64) -> Result<bool> {
+        let mut state = self.lock()?;
+        let len = state.rules.len();
+        state.rules.retain(|rule| rule.id != rule_id);
+        Ok(state.rules.len() != len)
+    }
+
+    /// 移除全部规则，求值线程随后退出
+    pub fn clear(&self) -> Result<()> {
+        self.lock()?.rules.clear();
+        Ok(())
+    }
+
+    /// 启用或停用规则，重新启用时清除边沿条件的上次值
+    pub fn set_enabled(&self, rule_id: u64, enabled: bool) -> Result<bool> {
+        let mut state = self.lock()?;
+        let Some(rule) = state.rules.iter_mut().find(|rule| rule.id == rule_id) else {
+            return Ok(false);
+        };
+        if enabled && !rule.enabled {
+            rule.last_value = None;
+            rule.next_due = Instant::now();
+        }
+        rule.enabled = enabled;
+        Ok(true)
+    }
+
+    /// 规则已触发的次数，规则不存在时返回 None
+    pub fn fire_count(&self, rule_id: u64) -> Option<u64> {
+        let state = self.state.lock().ok()?;
+        state.rules.iter().find(|rule| rule.id == rule_id).map(|rule| rule.fire_count)
+    }
+
+    pub fn rule_ids(&self) -> Vec<u64> {
+        self.state
+            .lock()
+            .map(|state| state.rules.iter().map(|rule| rule.id).collect())
+            .unwrap_or_default()
+    }
+
+    /// 设置通知接收者，None 取消
+    pub fn set_listener(&self, listener: Option<Arc<dyn RuleListener>>) {
+        if let Ok(mut current) = self.listener.write() {
+            *current = listener;
+        }
+    }
+}
+
+fn as_f64(typ: ValueType, bytes: &[u8]) -> f64 {
+    let mut raw = [0u8; 8];
+    let len = bytes.len().min(8);
+    raw[..len].copy_from_slice(&bytes[..len]);
+    match typ {
+        ValueType::Byte => raw[0] as i8 as f64,
+        ValueType::Word => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+        ValueType::Dword | ValueType::Auto | ValueType::Xor => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+        ValueType::Qword => i64::from_le_bytes(raw) as f64,
+        ValueType::Float => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+        ValueType::Double => f64::from_le_bytes(raw),
+        ValueType::Vec3 => {
+            let component = |i: usize| f32::from_le_bytes(bytes.get(i * 4..i * 4 + 4).and_then(|b| b.try_into().ok()).unwrap_or([0; 4])) as f64;
+            (0..3).map(|i| component(i) * component(i)).sum::<f64>().sqrt()
+        },
+    }
+}
+
+/// 条件是否成立，`value` 为 None 表示目标不可读
+fn evaluate(condition: &RuleCondition, typ: ValueType, value: Option<&[u8]>, last: Option<&[u8]>) -> bool {
+    let Some(value) = value else {
+        return false;
+    };
+    let current = as_f64(typ, value);
+    let previous = last.map(|last| as_f64(typ, last));
+    match condition {
+        RuleCondition::Equals(operand) => value == operand.as_slice(),
+        RuleCondition::NotEquals(operand) => value != operand.as_slice(),
+        RuleCondition::Below(threshold) => current < *threshold,
+        RuleCondition::Above(threshold) => current > *threshold,
+        RuleCondition::CrossedBelow(threshold) => previous.is_some_and(|previous| previous >= *threshold) && current < *threshold,
+        RuleCondition::CrossedAbove(threshold) => previous.is_some_and(|previous| previous <= *threshold) && current > *threshold,
+        RuleCondition::Changed => last.is_some_and(|last| last != value),
+        RuleCondition::Resolves => true,
+    }
+}
+
+/// 解析并读取规则目标，返回地址和值
+fn read_target(rule: &mut Rule, manager: &DriverManager, resolver: &mut Resolver, now: Instant) -> (Option<u64>, Option<Vec<u8>>) {
+    let stale = rule.resolved.is_none_or(|(_, at)| now.duration_since(at) >= RESOLVE_INTERVAL);
+    if stale {
+        rule.resolved = resolver.resolve(&rule.config.location).map(|address| (address, now));
+    }
+    let Some((address, _)) = rule.resolved else {
+        return (None, None);
+    };
+
+    let _op = OP_QUEUE.interactive();
+    let mut buf = vec![0u8; rule.config.value_type.size()];
+    if manager.read_memory_unified(address, &mut buf, None).is_ok() {
+        return (Some(address), Some(buf));
+    }
+    // 读取失败时指针链可能已经指向别处，下一次求值重新解析
+    if matches!(rule.config.location, SavedLocation::Pointer { .. }) {
+        rule.resolved = None;
+    }
+    (Some(address), None)
+}
+
+/// 求值线程，规则清空后退出
+fn run(state: Arc<Mutex<EngineState>>, listener: Arc<RwLock<Option<Arc<dyn RuleListener>>>>) {
+    loop {
+        let mut firings = Vec::new();
+        let sleep = {
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            if state.rules.is_empty() {
+                state.thread_running = false;
+                info!("Rules: 没有规则，求值线程退出");
+                return;
+            }
+
+            let now = Instant::now();
+            let manager = DRIVER_MANAGER.read();
+            let Ok(manager) = manager else {
+                return;
+            };
+            let mut resolver = Resolver::new(&manager);
+            let mut next_due = now + MAX_IDLE;
+            for rule in state.rules.iter_mut().filter(|rule| rule.enabled) {
+                if rule.next_due > now {
+                    next_due = next_due.min(rule.next_due);
+                    continue;
+                }
+                rule.next_due = now + rule.config.interval.max(MIN_RULE_INTERVAL);
+                next_due = next_due.min(rule.next_due);
+
+                let (address, value) = read_target(rule, &manager, &mut resolver, now);
+                let holds = evaluate(&rule.config.condition, rule.config.value_type, value.as_deref(), rule.last_value.as_deref());
+                // 目标暂时不可读时保留上次值，恢复后仍能判断穿越
+                if value.is_some() {
+                    rule.last_value = value.clone();
+                }
+                let cooled = rule.last_fired.is_none_or(|at| now.duration_since(at) >= rule.config.cooldown);
+                if !holds || !cooled {
+                    continue;
+                }
+
+                rule.last_fired = Some(now);
+                rule.fire_count += 1;
+                if rule.config.one_shot {
+                    rule.enabled = false;
+                }
+                debug!("Rules: 规则 {} 触发", rule.id);
+                firings.push(Firing {
+                    rule_id: rule.id,
+                    address,
+                    value: value.unwrap_or_default(),
+                    actions: rule.actions.clone(),
+                });
+            }
+            next_due.saturating_duration_since(Instant::now())
+        };
+
+        for firing in &firings {
+            execute(firing, &listener);
+        }
+        if !sleep.is_zero() {
+            std::thread::sleep(sleep);
+        }
+    }
+}
+
+fn execute(firing: &Firing, listener: &RwLock<Option<Arc<dyn RuleListener>>>) {
+    for action in &firing.actions {
+        let result = match action {
+            RuleAction::Write { location, value } => write_value(location.as_ref(), firing.address, value),
+            RuleAction::Freeze { entry_id, mode } => set_entry_frozen(*entry_id, *mode),
+            RuleAction::RunScript { source, name } => crate::script::run_script(source.clone(), name.clone()).map(|_| ()),
+            RuleAction::Notify => {
+                let listener = listener.read().ok().and_then(|listener| listener.clone());
+                if let Some(listener) = listener {
+                    listener.on_rule_fired(firing.rule_id, &firing.value);
+                }
+                Ok(())
+            },
+        };
+        if let Err(e) = result {
+            warn!("Rules: 规则 {} 的动作执行失败: {:#}", firing.rule_id, e);
+        }
+    }
+}
+
+fn write_value(location: Option<&SavedLocation>, target: Option<u64>, value: &[u8]) -> Result<()> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let address = match location {
+        Some(location) => Resolver::new(&manager).resolve(location),
+        None => target,
+    }
+    .ok_or_else(|| anyhow!("Write target cannot be resolved"))?;
+    let _op = OP_QUEUE.interactive();
+    manager.write_memory_unified(address, value)?;
+    Ok(())
+}
+
+fn set_entry_frozen(entry_id: u64, mode: FreezeMode) -> Result<()> {
+    let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+    let entry = list.get_mut(entry_id).ok_or_else(|| anyhow!("Saved entry {} not found", entry_id))?;
+    entry.frozen = match mode {
+        FreezeMode::Freeze => true,
+        FreezeMode::Unfreeze => false,
+        FreezeMode::Toggle => !entry.frozen,
+    };
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let freeze = FREEZE_MANAGER.read().map_err(|_| anyhow!("Failed to acquire FreezeManager read lock"))?;
+    list.sync_freezes(&manager, &freeze);
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! 保存的地址列表
+//!
+//! 每个条目记录名称、地址或指针链、值类型、冻结值和热键，整张表可以保存为 JSON 文件、再加载回来。
+//! 冻结的条目由 [`SavedList::sync_freezes`] 解析出当前地址后交给 [`FreezeManager`]，
+//! 指针链条目在进程重启后重新同步即可指向新的地址。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::freeze_manager::FreezeManager;
+use crate::pointer_scan::static_modules::discover_static_modules;
+use crate::pointer_scan::types::{PointerChain, VmStaticData};
+use crate::pointer_scan::validator::validate_chain;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 文件格式版本
+const SAVED_LIST_VERSION: u32 = 1;
+
+/// 条目指向的位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SavedLocation {
+    Address {
+        address: u64,
+    },
+    Pointer {
+        chain: PointerChain,
+        /// 链根偏移相对模块第一个段的基址，同指针扫描的 data_start
+        #[serde(default)]
+        data_start: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedEntry {
+    /// 列表内唯一，添加时分配
+    #[serde(default)]
+    pub id: u64,
+    pub label: String,
+    pub location: SavedLocation,
+    /// 值类型 id（`ValueType::to_id`）
+    pub value_type: i32,
+    /// 冻结时写入的值
+    #[serde(default)]
+    pub value: Vec<u8>,
+    #[serde(default)]
+    pub frozen: bool,
+    /// 热键 id，0 表示没有
+    #[serde(default)]
+    pub hotkey: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedListFile {
+    version: u32,
+    entries: Vec<SavedEntry>,
+}
+
+/// 解析条目地址时使用，指针链条目第一次用到时才枚举静态模块
+pub struct Resolver<'a> {
+    manager: &'a DriverManager,
+    static_modules: Option<Vec<VmStaticData>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(manager: &'a DriverManager) -> Self {
+        Self {
+            manager,
+            static_modules: None,
+        }
+    }
+
+    /// 条目当前的地址，指针链无法解析时返回 None
+    pub fn resolve(&mut self, location: &SavedLocation) -> Option<u64> {
+        let (chain, data_start) = match location {
+            SavedLocation::Address { address } => return Some(*address),
+            SavedLocation::Pointer { chain, data_start } => (chain, *data_start),
+        };
+        if !self.manager.is_process_bound() {
+            return None;
+        }
+        if self.static_modules.is_none() {
+            self.static_modules = Some(discover_static_modules(self.manager).unwrap_or_else(|e| {
+                warn!("SavedList: 枚举静态模块失败: {}", e);
+                Vec::new()
+            }));
+        }
+
+        let manager = self.manager;
+        let read_u64 = |addr: u64| {
+            let mut buf = [0u8; 8];
+            manager.read_memory_unified(addr, &mut buf, None).ok().map(|_| u64::from_le_bytes(buf))
+        };
+        validate_chain(chain, self.static_modules.as_deref().unwrap_or_default(), data_start, &read_u64).resolved_address
+    }
+}
+
+#[derive(Default)]
+pub struct SavedList {
+    entries: Vec<SavedEntry>,
+    next_id: u64,
+    /// 由本列表交给冻结管理器的地址：条目 id -> 地址
+    applied_freezes: HashMap<u64, u64>,
+}
+
+impl SavedList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[SavedEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&SavedEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut SavedEntry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+
+    /// 添加条目并分配 id
+    pub fn add(&mut self, mut entry: SavedEntry) -> u64 {
+        self.next_id += 1;
+        entry.id = self.next_id;
+        self.entries.push(entry);
+        self.next_id
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 保存到 `path`，先写临时文件再替换
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = SavedListFile {
+            version: SAVED_LIST_VERSION,
+            entries: self.entries.clone(),
+        };
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(&file)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// 从 `path` 加载并替换当前条目，返回条目数。没有 id 的条目重新分配
+    pub fn load(&mut self, path: &Path) -> Result<usize> {
+        let file: SavedListFile = serde_json::from_slice(&std::fs::read(path)?)?;
+        if file.version > SAVED_LIST_VERSION {
+            return Err(anyhow!("Unsupported saved list version: {}", file.version));
+        }
+        self.replace(file.entries);
+        Ok(self.entries.len())
+    }
+
+    /// 替换全部条目，id 重复或缺失的重新分配
+    pub fn replace(&mut self, entries: Vec<SavedEntry>) {
+        self.entries.clear();
+        self.next_id = entries.iter().map(|entry| entry.id).max().unwrap_or(0);
+        for entry in entries {
+            if entry.id == 0 || self.get(entry.id).is_some() {
+                self.add(entry);
+            } else {
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// 按当前进程重新解析冻结条目并交给冻结管理器
+    ///
+    /// 先撤下上次同步加入的冻结，再加入仍冻结且能解析出地址的条目。
+    /// 与其他冻结条目重叠的被跳过。返回生效的冻结数。
+    pub fn sync_freezes(&mut self, manager: &DriverManager, freeze: &FreezeManager) -> usize {
+        for (_, address) in self.applied_freezes.drain() {
+            freeze.remove_frozen(address);
+        }
+
+        let mut resolver = Resolver::new(manager);
+        for entry in self.entries.iter().filter(|entry| entry.frozen && !entry.value.is_empty()) {
+            let Some(address) = resolver.resolve(&entry.location) else {
+                debug!("SavedList: 条目 {} 无法解析，跳过冻结", entry.label);
+                continue;
+            };
+            match freeze.add_frozen(address, entry.value.clone(), entry.value_type) {
+                Ok(()) => {
+                    self.applied_freezes.insert(entry.id, address);
+                },
+                Err(conflicts) => warn!("SavedList: 条目 {} 与 {} 个冻结条目重叠", entry.label, conflicts.len()),
+            }
+        }
+        self.applied_freezes.len()
+    }
+}
@@ -0,0 +1,209 @@
+//! 扫描事件推送
+//!
+//! 搜索和指针扫描的进度原本只写入共享缓冲区，由 Java 定时读取。共享缓冲区写入进度、状态和错误码时
+//! 顺带调用这里的 `report_*`，推送给注册的 [`ScanListener`]。
+//! 进度按百分比去重，同一次扫描中同一百分比只推送一次。
+//! 每次扫描同时在 [`JOB_REGISTRY`] 中登记为一个任务，结束或出错时更新任务状态。
+//!
+//! 共享缓冲区常在持有扫描管理器锁时写入，事件因此只入队，由专门的分发线程按顺序交给监听器，
+//! 监听器中可以同步调用读取结果等需要扫描锁的方法。
+
+use crate::core::globals::{clear_rwlock_poison, JOB_REGISTRY};
+use crate::core::jobs::{JobKind, JobStatus};
+use crate::pointer_scan::manager::POINTER_SCAN_MANAGER;
+use crate::search::engine::manager::SEARCH_ENGINE_MANAGER;
+use log::error;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// 扫描类型，与 Java 侧 `ScanListener.KIND_*` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ScanKind {
+    Search = 0,
+    PointerScan = 1,
+}
+
+/// 扫描事件接收者，在分发线程中按发生顺序调用
+pub trait ScanListener: Send + Sync {
+    fn on_progress(&self, kind: ScanKind, progress: i32, found: i64);
+
+    fn on_finished(&self, kind: ScanKind, found: i64, cancelled: bool);
+
+    /// `error_code` 为对应模块的 SearchErrorCode / ScanErrorCode
+    fn on_error(&self, kind: ScanKind, error_code: i32);
+}
+
+/// 一种扫描的推送状态
+struct KindState {
+    running: AtomicBool,
+    /// 上次推送的百分比
+    progress: AtomicI32,
+    found: AtomicI64,
+    /// 当前扫描的任务 id，0 表示没有
+    job: AtomicI32,
+}
+
+impl KindState {
+    const fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            progress: AtomicI32::new(-1),
+            found: AtomicI64::new(0),
+            job: AtomicI32::new(0),
+        }
+    }
+}
+
+static STATES: [KindState; 2] = [KindState::new(), KindState::new()];
+
+static LISTENER: RwLock<Option<Arc<dyn ScanListener>>> = RwLock::new(None);
+
+/// 待分发的事件
+enum ScanEvent {
+    Progress { kind: ScanKind, progress: i32, found: i64 },
+    Finished { kind: ScanKind, found: i64, cancelled: bool },
+    Error { kind: ScanKind, error_code: i32 },
+}
+
+/// 分发线程的队列，首次推送时启动线程
+static DISPATCHER: OnceLock<Option<Sender<ScanEvent>>> = OnceLock::new();
+
+fn start_dispatcher() -> Option<Sender<ScanEvent>> {
+    let (sender, receiver) = mpsc::channel::<ScanEvent>();
+    let spawned = std::thread::Builder::new().name("mamu-scan-events".to_string()).spawn(move || {
+        for event in receiver {
+            let Some(listener) = listener() else {
+                continue;
+            };
+            match event {
+                ScanEvent::Progress { kind, progress, found } => listener.on_progress(kind, progress, found),
+                ScanEvent::Finished { kind, found, cancelled } => listener.on_finished(kind, found, cancelled),
+                ScanEvent::Error { kind, error_code } => listener.on_error(kind, error_code),
+            }
+        }
+    });
+    match spawned {
+        Ok(_) => Some(sender),
+        Err(e) => {
+            error!("Failed to start scan event dispatcher: {}", e);
+            None
+        },
+    }
+}
+
+/// 交给分发线程，没有监听器时直接丢弃
+fn dispatch(event: ScanEvent) {
+    if LISTENER.read().map_or(true, |listener| listener.is_none()) {
+        return;
+    }
+    if let Some(sender) = DISPATCHER.get_or_init(start_dispatcher) {
+        let _ = sender.send(event);
+    }
+}
+
+fn state(kind: ScanKind) -> &'static KindState {
+    &STATES[kind as usize]
+}
+
+/// 取出监听器后释放锁，回调期间不持有
+fn listener() -> Option<Arc<dyn ScanListener>> {
+    LISTENER.read().ok()?.clone()
+}
+
+/// 设置监听器，None 取消
+pub fn set_scan_listener(listener: Option<Arc<dyn ScanListener>>) {
+    if let Ok(mut current) = LISTENER.write() {
+        *current = listener;
+    }
+}
+
+/// 清除监听器锁的中毒状态，返回中毒的锁数
+pub(crate) fn clear_poisoned_lock() -> usize {
+    clear_rwlock_poison(&LISTENER)
+}
+
+pub fn is_running(kind: ScanKind) -> bool {
+    state(kind).running.load(Ordering::Acquire)
+}
+
+/// 当前扫描的任务 id
+pub fn current_job(kind: ScanKind) -> Option<i32> {
+    Some(state(kind).job.load(Ordering::Acquire)).filter(|&id| id != 0)
+}
+
+fn cancel_scan(kind: ScanKind) {
+    match kind {
+        ScanKind::Search => {
+            if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
+                manager.request_cancel();
+            }
+        },
+        ScanKind::PointerScan => {
+            if let Ok(manager) = POINTER_SCAN_MANAGER.read() {
+                manager.request_cancel();
+            }
+        },
+    }
+}
+
+/// 结束当前扫描的任务
+fn finish_job(kind: ScanKind, status: JobStatus) {
+    let id = state(kind).job.swap(0, Ordering::AcqRel);
+    if id != 0 {
+        JOB_REGISTRY.finish(id, status);
+    }
+}
+
+/// 扫描开始，重置进度和数量并登记任务
+pub fn report_started(kind: ScanKind, job_kind: JobKind) {
+    let state = state(kind);
+    state.progress.store(-1, Ordering::Relaxed);
+    state.found.store(0, Ordering::Relaxed);
+    // 上一次扫描没有正常结束时不留下一直运行的任务
+    finish_job(kind, JobStatus::Failed);
+    state.job.store(JOB_REGISTRY.start(job_kind, move || cancel_scan(kind)), Ordering::Release);
+    state.running.store(true, Ordering::Release);
+}
+
+pub fn report_found(kind: ScanKind, found: i64) {
+    state(kind).found.store(found, Ordering::Relaxed);
+}
+
+/// 扫描进行中且百分比变化时推送进度
+pub fn report_progress(kind: ScanKind, progress: i32) {
+    let state = state(kind);
+    if !state.running.load(Ordering::Acquire) || state.progress.swap(progress, Ordering::Relaxed) == progress {
+        return;
+    }
+    dispatch(ScanEvent::Progress {
+        kind,
+        progress,
+        found: state.found.load(Ordering::Relaxed),
+    });
+}
+
+/// 扫描结束。没有可改善的结果等情况下扫描不经开始直接完成，同样推送
+pub fn report_finished(kind: ScanKind, cancelled: bool) {
+    let state = state(kind);
+    state.running.store(false, Ordering::Release);
+    finish_job(kind, if cancelled { JobStatus::Cancelled } else { JobStatus::Completed });
+    dispatch(ScanEvent::Finished {
+        kind,
+        found: state.found.load(Ordering::Relaxed),
+        cancelled,
+    });
+}
+
+/// 扫描出错，未初始化等启动前的错误也会推送
+pub fn report_error(kind: ScanKind, error_code: i32) {
+    state(kind).running.store(false, Ordering::Release);
+    finish_job(kind, JobStatus::Failed);
+    dispatch(ScanEvent::Error { kind, error_code });
+}
+
+/// 已有扫描在进行而拒绝新的扫描，只推送错误，不影响进行中的扫描
+pub fn report_rejected(kind: ScanKind, error_code: i32) {
+    dispatch(ScanEvent::Error { kind, error_code });
+}
@@ -0,0 +1,137 @@
+//! ScanProfile - 按包名保存的扫描配置
+//!
+//! 每个包一个 JSON 文件，绑定进程时按包名自动加载为当前配置，
+//! 切换游戏时不必重新设置区域、值类型、对齐和指针掩码。
+
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PROFILE_EXTENSION: &str = "json";
+
+/// 单个包的扫描配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanProfile {
+    pub package: String,
+    /// 区域类型掩码，第 n 位对应 `MemoryRange` 序号 n
+    pub region_mask: u64,
+    /// 常用值类型 id（`ValueType::to_id`）
+    pub value_types: Vec<i32>,
+    /// 搜索和指针扫描的对齐字节数
+    pub alignment: u32,
+    /// 指针有效位掩码，用于去掉 TBI/MTE 标签
+    pub pointer_mask: u64,
+}
+
+/// 子进程（`pkg:remote`）与主进程共用一个配置
+pub fn package_of(process_name: &str) -> &str {
+    process_name.split(':').next().unwrap_or(process_name)
+}
+
+fn is_valid_package(package: &str) -> bool {
+    !package.is_empty()
+        && !package.starts_with('.')
+        && package.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+}
+
+/// 配置目录与当前生效的配置
+#[derive(Default)]
+pub struct ScanProfileStore {
+    dir: Option<PathBuf>,
+    active: Option<ScanProfile>,
+}
+
+impl ScanProfileStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_dir(&mut self, dir: impl Into<PathBuf>) -> Result<()> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        self.dir = Some(dir);
+        Ok(())
+    }
+
+    fn dir(&self) -> Result<&Path> {
+        self.dir.as_deref().ok_or_else(|| anyhow!("Scan profile directory is not set"))
+    }
+
+    fn path_for(&self, package: &str) -> Result<PathBuf> {
+        if !is_valid_package(package) {
+            return Err(anyhow!("Invalid package name: {:?}", package));
+        }
+        Ok(self.dir()?.join(format!("{}.{}", package, PROFILE_EXTENSION)))
+    }
+
+    /// 保存配置，覆盖同包的旧配置；若该包正在绑定则立即生效
+    pub fn save(&mut self, profile: &ScanProfile) -> Result<()> {
+        let path = self.path_for(&profile.package)?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(profile)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+
+        if self.active.as_ref().is_some_and(|active| active.package == profile.package) {
+            self.active = Some(profile.clone());
+        }
+        Ok(())
+    }
+
+    pub fn load(&self, package: &str) -> Result<Option<ScanProfile>> {
+        let path = self.path_for(package)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&std::fs::read(&path)?)?))
+    }
+
+    /// 删除配置，返回是否存在
+    pub fn delete(&mut self, package: &str) -> Result<bool> {
+        let path = self.path_for(package)?;
+        if self.active.as_ref().is_some_and(|active| active.package == package) {
+            self.active = None;
+        }
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(&path)?;
+        Ok(true)
+    }
+
+    /// 已保存配置的包名，按字母排序
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut packages: Vec<String> = std::fs::read_dir(self.dir()?)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == PROFILE_EXTENSION))
+            .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string))
+            .collect();
+        packages.sort();
+        Ok(packages)
+    }
+
+    /// 绑定进程后调用，加载该进程所属包的配置
+    pub fn activate(&mut self, process_name: &str) -> Option<&ScanProfile> {
+        let package = package_of(process_name);
+        self.active = match self.load(package) {
+            Ok(profile) => profile,
+            Err(e) => {
+                warn!("ScanProfile: 加载 {} 的配置失败: {}", package, e);
+                None
+            },
+        };
+        if self.active.is_some() {
+            debug!("ScanProfile: 已加载 {} 的配置", package);
+        }
+        self.active.as_ref()
+    }
+
+    pub fn deactivate(&mut self) {
+        self.active = None;
+    }
+
+    pub fn active(&self) -> Option<&ScanProfile> {
+        self.active.as_ref()
+    }
+}
@@ -0,0 +1,322 @@
+//! 进程内存快照与离线对比
+//!
+//! 把选定类型的区域整页读出写入快照文件，内容相同的页只存一份，全零页不占空间。
+//! 之后对比两份快照即可列出发生变化的页和值，适合"暂停 → 操作 → 对比"这类
+//! 对时机敏感、来不及做实时模糊搜索的场景。
+//!
+//! 文件格式（小端）：
+//!
+//! ```text
+//! 文件头  magic "MAMUSNAP" | version u32 | page_size u32 | region_count u32 | unique_pages u32 | pool_offset u64
+//! 区域表  每项 start u64 | end u64
+//! 页表    按区域顺序，每页一个 u32：页池索引，或 ZERO_PAGE / UNREADABLE_PAGE
+//! 页池    从 pool_offset 开始（按页对齐），每项 page_size 字节
+//! ```
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
+use crate::core::region_type::query_ranges_by_mask;
+use crate::wuwa::PageStatusBitmap;
+use anyhow::{anyhow, Result};
+use log::debug;
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"MAMUSNAP";
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 32;
+const REGION_ENTRY_SIZE: usize = 16;
+
+/// 页表中的全零页
+const ZERO_PAGE: u32 = u32::MAX - 1;
+/// 页表中无法读取的页
+const UNREADABLE_PAGE: u32 = u32::MAX;
+
+/// 单次读取的块大小
+const SNAPSHOT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 快照统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapshotSummary {
+    pub regions: usize,
+    pub pages: usize,
+    /// 去重后实际存储的页数
+    pub unique_pages: usize,
+    pub zero_pages: usize,
+    pub unreadable_pages: usize,
+    pub file_size: u64,
+}
+
+/// 单个值的变化
+#[derive(Debug, Clone, Copy)]
+pub struct ValueChange {
+    pub address: u64,
+    pub old_value: u64,
+    pub new_value: u64,
+}
+
+/// 两份快照的对比结果
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    /// 内容变化的页地址，按地址排序
+    pub changed_pages: Vec<u64>,
+    /// 逐值的变化，最多 `max_changes` 条
+    pub changes: Vec<ValueChange>,
+    /// 变化数超过上限被截断
+    pub truncated: bool,
+}
+
+/// 页内容的 128 位指纹，两个不同种子的 SipHash 拼接，碰撞概率可以忽略
+fn page_fingerprint(page: &[u8]) -> (u64, u64) {
+    let mut first = DefaultHasher::new();
+    page.hash(&mut first);
+    let mut second = DefaultHasher::new();
+    0x5A5Au16.hash(&mut second);
+    page.hash(&mut second);
+    (first.finish(), second.finish())
+}
+
+/// 拍摄绑定进程中类型在 `region_mask` 内的区域，写入 `path`
+pub fn take_snapshot(manager: &DriverManager, region_mask: u64, path: &str) -> Result<SnapshotSummary> {
+    let ranges = query_ranges_by_mask(manager, region_mask)?;
+    if ranges.is_empty() {
+        return Err(anyhow!("No regions match mask 0x{:X}", region_mask));
+    }
+
+    let page_size = *PAGE_SIZE;
+    let total_pages: usize = ranges.iter().map(|&(start, end)| ((end - start) as usize).div_ceil(page_size)).sum();
+    if total_pages >= ZERO_PAGE as usize {
+        return Err(anyhow!("Too many pages for one snapshot: {}", total_pages));
+    }
+
+    let tables_end = HEADER_SIZE + REGION_ENTRY_SIZE * ranges.len() + total_pages * 4;
+    let pool_offset = tables_end.div_ceil(page_size) * page_size;
+
+    let mut writer = BufWriter::new(File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?);
+    writer.seek(SeekFrom::Start(pool_offset as u64))?;
+
+    let mut page_table: Vec<u32> = Vec::with_capacity(total_pages);
+    let mut pool: HashMap<(u64, u64), u32> = HashMap::new();
+    let mut summary = SnapshotSummary {
+        regions: ranges.len(),
+        pages: total_pages,
+        ..Default::default()
+    };
+    let mut buffer = vec![0u8; SNAPSHOT_CHUNK_SIZE];
+
+    for &(start, end) in &ranges {
+        let mut current = start;
+        while current < end {
+            let chunk_end = (current + SNAPSHOT_CHUNK_SIZE as u64).min(end);
+            let chunk = &mut buffer[..(chunk_end - current) as usize];
+            let mut page_status = PageStatusBitmap::new(chunk.len(), current as usize);
+            let read_ok = {
+                let _op = OP_QUEUE.bulk();
+                manager.read_memory_unified(current, chunk, Some(&mut page_status)).is_ok()
+            };
+
+            for (index, page) in chunk.chunks(page_size).enumerate() {
+                if !read_ok || !page_status.is_page_success(index) {
+                    page_table.push(UNREADABLE_PAGE);
+                    summary.unreadable_pages += 1;
+                    continue;
+                }
+                if page.iter().all(|&b| b == 0) {
+                    page_table.push(ZERO_PAGE);
+                    summary.zero_pages += 1;
+                    continue;
+                }
+
+                let next = pool.len() as u32;
+                let slot = *pool.entry(page_fingerprint(page)).or_insert(next);
+                if slot == next {
+                    // 区域末尾不足一页时补零，页池每项都是整页
+                    writer.write_all(page)?;
+                    if page.len() < page_size {
+                        writer.write_all(&vec![0u8; page_size - page.len()])?;
+                    }
+                }
+                page_table.push(slot);
+            }
+            current = chunk_end;
+        }
+    }
+    summary.unique_pages = pool.len();
+
+    writer.seek(SeekFrom::Start(0))?;
+    writer.write_all(&SNAPSHOT_MAGIC)?;
+    writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+    writer.write_all(&(page_size as u32).to_le_bytes())?;
+    writer.write_all(&(ranges.len() as u32).to_le_bytes())?;
+    writer.write_all(&(summary.unique_pages as u32).to_le_bytes())?;
+    writer.write_all(&(pool_offset as u64).to_le_bytes())?;
+    for &(start, end) in &ranges {
+        writer.write_all(&start.to_le_bytes())?;
+        writer.write_all(&end.to_le_bytes())?;
+    }
+    for entry in &page_table {
+        writer.write_all(&entry.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    summary.file_size = (pool_offset + summary.unique_pages * page_size) as u64;
+    debug!(
+        "快照完成: {}, {} 个区域, {} 页, 去重后 {} 页, 零页 {}, 不可读 {}",
+        path, summary.regions, summary.pages, summary.unique_pages, summary.zero_pages, summary.unreadable_pages
+    );
+    Ok(summary)
+}
+
+/// 只读映射的快照文件
+struct SnapshotFile {
+    map: Mmap,
+    page_size: usize,
+    pool_offset: usize,
+    unique_pages: usize,
+    /// (start, end, 该区域首页在页表中的序号)
+    regions: Vec<(u64, u64, usize)>,
+    page_table_offset: usize,
+}
+
+impl SnapshotFile {
+    fn open(path: &str) -> Result<Self> {
+        let file = File::open(path).map_err(|e| anyhow!("Failed to open {}: {}", path, e))?;
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER_SIZE || map[..8] != SNAPSHOT_MAGIC {
+            return Err(anyhow!("{} is not a memory snapshot", path));
+        }
+
+        let read_u32 = |offset: usize| u32::from_le_bytes(map[offset..offset + 4].try_into().unwrap());
+        let version = read_u32(8);
+        if version != SNAPSHOT_VERSION {
+            return Err(anyhow!("Unsupported snapshot version {} in {}", version, path));
+        }
+        let page_size = read_u32(12) as usize;
+        let region_count = read_u32(16) as usize;
+        let unique_pages = read_u32(20) as usize;
+        let pool_offset = u64::from_le_bytes(map[24..32].try_into().unwrap()) as usize;
+
+        let page_table_offset = HEADER_SIZE + region_count * REGION_ENTRY_SIZE;
+        if page_size == 0 || map.len() < page_table_offset {
+            return Err(anyhow!("Truncated snapshot {}", path));
+        }
+
+        let mut regions = Vec::with_capacity(region_count);
+        let mut first_page = 0;
+        for index in 0..region_count {
+            let offset = HEADER_SIZE + index * REGION_ENTRY_SIZE;
+            let start = u64::from_le_bytes(map[offset..offset + 8].try_into().unwrap());
+            let end = u64::from_le_bytes(map[offset + 8..offset + 16].try_into().unwrap());
+            regions.push((start, end, first_page));
+            first_page += ((end.saturating_sub(start)) as usize).div_ceil(page_size);
+        }
+
+        if map.len() < page_table_offset + first_page * 4 || map.len() < pool_offset + unique_pages * page_size {
+            return Err(anyhow!("Truncated snapshot {}", path));
+        }
+
+        Ok(Self {
+            map,
+            page_size,
+            pool_offset,
+            unique_pages,
+            regions,
+            page_table_offset,
+        })
+    }
+
+    fn page_entry(&self, index: usize) -> u32 {
+        let offset = self.page_table_offset + index * 4;
+        u32::from_le_bytes(self.map[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// 所有可读页的地址与页表项
+    fn page_entries(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.regions.iter().flat_map(move |&(start, end, first_page)| {
+            let pages = ((end - start) as usize).div_ceil(self.page_size);
+            (0..pages).filter_map(move |page| {
+                let entry = self.page_entry(first_page + page);
+                (entry != UNREADABLE_PAGE).then_some((start + (page * self.page_size) as u64, entry))
+            })
+        })
+    }
+
+    /// 页表项对应的内容，全零页返回 None
+    fn page_data(&self, entry: u32) -> Option<&[u8]> {
+        if entry == ZERO_PAGE || entry as usize >= self.unique_pages {
+            return None;
+        }
+        let offset = self.pool_offset + entry as usize * self.page_size;
+        Some(&self.map[offset..offset + self.page_size])
+    }
+}
+
+fn read_value(bytes: &[u8], offset: usize, size: usize) -> u64 {
+    let mut value = [0u8; 8];
+    value[..size].copy_from_slice(&bytes[offset..offset + size]);
+    u64::from_le_bytes(value)
+}
+
+/// 对比两份快照，列出两边都可读且内容变化的页，以及按 `value_size` 对齐的值变化
+pub fn diff_snapshots(old_path: &str, new_path: &str, value_size: usize, max_changes: usize) -> Result<SnapshotDiff> {
+    if !matches!(value_size, 1 | 2 | 4 | 8) {
+        return Err(anyhow!("Invalid value size: {}", value_size));
+    }
+
+    let old = SnapshotFile::open(old_path)?;
+    let new = SnapshotFile::open(new_path)?;
+    if old.page_size != new.page_size {
+        return Err(anyhow!("Snapshots use different page sizes: {} vs {}", old.page_size, new.page_size));
+    }
+
+    let old_pages: HashMap<u64, u32> = old.page_entries().collect();
+    let zero_page = vec![0u8; new.page_size];
+    let mut diff = SnapshotDiff::default();
+
+    for (address, new_entry) in new.page_entries() {
+        let Some(&old_entry) = old_pages.get(&address) else {
+            continue;
+        };
+        let old_data = old.page_data(old_entry).unwrap_or(&zero_page);
+        let new_data = new.page_data(new_entry).unwrap_or(&zero_page);
+        if old_data == new_data {
+            continue;
+        }
+
+        diff.changed_pages.push(address);
+        if diff.truncated {
+            continue;
+        }
+        for offset in (0..new.page_size).step_by(value_size) {
+            let old_value = read_value(old_data, offset, value_size);
+            let new_value = read_value(new_data, offset, value_size);
+            if old_value == new_value {
+                continue;
+            }
+            if diff.changes.len() >= max_changes {
+                diff.truncated = true;
+                break;
+            }
+            diff.changes.push(ValueChange {
+                address: address + offset as u64,
+                old_value,
+                new_value,
+            });
+        }
+    }
+
+    diff.changed_pages.sort_unstable();
+    diff.changes.sort_unstable_by_key(|change| change.address);
+    debug!(
+        "快照对比: {} 页变化, {} 个值变化{}",
+        diff.changed_pages.len(),
+        diff.changes.len(),
+        if diff.truncated { "（已截断）" } else { "" }
+    );
+    Ok(diff)
+}
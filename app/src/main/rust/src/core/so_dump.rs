@@ -0,0 +1,223 @@
+//! 模块转储
+//!
+//! 从绑定进程读出已加载的 .so，整理成可以被 IDA/Ghidra 直接加载的 ELF 文件。
+//! 加固过的库在内存中才被解密，磁盘上的文件没有分析价值，所以代码和数据都取自内存；
+//! 磁盘文件只用来补回被抹掉的 ELF 头和节头。
+//!
+//! 重建规则：
+//! - 内存镜像按虚拟地址排布，每个程序头的 `p_offset` 改为 `p_vaddr`（相对首个加载段），
+//!   PT_LOAD 的 `p_filesz` 改为 `p_memsz`，.bss 以内存内容落盘
+//! - 能读到磁盘文件时，可分配节的 `sh_offset` 同样改为 `sh_addr`，
+//!   不可分配的节（.shstrtab、.symtab 等）从磁盘复制到镜像末尾
+//! - 内存中的 ELF 头被抹掉时，用磁盘文件的 ELF 头和程序头替换
+//!
+//! 重定位已经生效，GOT 和 .data.rel.ro 中保存的是运行时地址，加载时以模块基址为镜像基址即可对上。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::{read_u16, read_u32, read_u64, ELF_CLASS_64, ELF_HEADER_SIZE, ELF_MAGIC, MAX_PHDRS, PHDR_SIZE, PT_LOAD};
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
+use crate::core::modules::{find_module, ModuleInfo};
+use crate::wuwa::PageStatusBitmap;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+
+const SHDR_SIZE: usize = 64;
+const SHF_ALLOC: u64 = 2;
+const SHT_NULL: u32 = 0;
+const SHT_NOBITS: u32 = 8;
+
+/// 单次读取的块大小
+const DUMP_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 模块转储结果
+#[derive(Debug, Clone)]
+pub struct ModuleDump {
+    pub module: ModuleInfo,
+    /// 写出的文件大小
+    pub file_size: u64,
+    /// 从磁盘文件恢复的节数，0 表示输出不含节头
+    pub sections: usize,
+    /// ELF 头取自磁盘文件
+    pub header_restored: bool,
+    /// 无法读取、以 0 填充的页数
+    pub unreadable_pages: usize,
+}
+
+fn write_u16(buf: &mut [u8], offset: usize, value: u16) {
+    buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u64(buf: &mut [u8], offset: usize, value: u64) {
+    buf[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+/// ELF64 头和程序头表完整可用
+fn has_valid_header(elf: &[u8]) -> bool {
+    if elf.len() < ELF_HEADER_SIZE || elf[..4] != ELF_MAGIC || elf[4] != ELF_CLASS_64 {
+        return false;
+    }
+    let phoff = read_u64(elf, 0x20) as usize;
+    let phnum = read_u16(elf, 0x38) as usize;
+    read_u16(elf, 0x36) as usize == PHDR_SIZE
+        && phnum > 0
+        && phnum <= MAX_PHDRS
+        && phoff.checked_add(phnum * PHDR_SIZE).is_some_and(|end| end <= elf.len())
+}
+
+/// 程序头表中最低 PT_LOAD 的页对齐虚拟地址
+fn min_load_vaddr(elf: &[u8]) -> Option<u64> {
+    let phoff = read_u64(elf, 0x20) as usize;
+    let phnum = read_u16(elf, 0x38) as usize;
+    let page_size = *PAGE_SIZE as u64;
+    (0..phnum)
+        .map(|index| &elf[phoff + index * PHDR_SIZE..])
+        .filter(|phdr| read_u32(phdr, 0) == PT_LOAD)
+        .map(|phdr| read_u64(phdr, 16) & !(page_size - 1))
+        .min()
+}
+
+/// 分块读取模块的内存镜像，不可读的页以 0 填充
+fn read_image(manager: &DriverManager, base: u64, size: usize) -> (Vec<u8>, usize) {
+    let mut image = vec![0u8; size];
+    let mut unreadable = 0;
+    for (index, chunk) in image.chunks_mut(DUMP_CHUNK_SIZE).enumerate() {
+        let address = base + (index * DUMP_CHUNK_SIZE) as u64;
+        let mut page_status = PageStatusBitmap::new(chunk.len(), address as usize);
+        let read_ok = {
+            let _op = OP_QUEUE.bulk();
+            manager.read_memory_unified(address, chunk, Some(&mut page_status)).is_ok()
+        };
+
+        for (page, data) in chunk.chunks_mut(*PAGE_SIZE).enumerate() {
+            if !read_ok || !page_status.is_page_success(page) {
+                data.fill(0);
+                unreadable += 1;
+            }
+        }
+    }
+    (image, unreadable)
+}
+
+/// 把程序头改成按虚拟地址排布的文件偏移
+fn fix_program_headers(image: &mut [u8], min_vaddr: u64) {
+    let phoff = read_u64(image, 0x20) as usize;
+    let phnum = read_u16(image, 0x38) as usize;
+    for index in 0..phnum {
+        let offset = phoff + index * PHDR_SIZE;
+        let vaddr = read_u64(image, offset + 16);
+        let memsz = read_u64(image, offset + 40);
+        write_u64(image, offset + 8, vaddr.saturating_sub(min_vaddr));
+        if read_u32(image, offset) == PT_LOAD {
+            write_u64(image, offset + 32, memsz);
+        }
+    }
+}
+
+/// 按磁盘文件的节头重建节表，追加到 `image` 末尾，返回节数
+fn rebuild_sections(image: &mut Vec<u8>, disk: &[u8], min_vaddr: u64) -> Result<usize> {
+    let shoff = read_u64(disk, 0x28) as usize;
+    let shentsize = read_u16(disk, 0x3A) as usize;
+    let shnum = read_u16(disk, 0x3C) as usize;
+    let shstrndx = read_u16(disk, 0x3E);
+    if shoff == 0 || shnum == 0 {
+        return Ok(0);
+    }
+    if shentsize != SHDR_SIZE || shoff.checked_add(shnum * SHDR_SIZE).is_none_or(|end| end > disk.len()) {
+        return Err(anyhow!("Malformed section header table"));
+    }
+
+    let mut sections = disk[shoff..shoff + shnum * SHDR_SIZE].to_vec();
+    for index in 0..shnum {
+        let shdr = &mut sections[index * SHDR_SIZE..(index + 1) * SHDR_SIZE];
+        let sh_type = read_u32(shdr, 4);
+        let flags = read_u64(shdr, 8);
+        let addr = read_u64(shdr, 16);
+        if sh_type == SHT_NULL {
+            continue;
+        }
+
+        if flags & SHF_ALLOC != 0 && addr != 0 {
+            write_u64(shdr, 24, addr.saturating_sub(min_vaddr));
+            continue;
+        }
+        if sh_type == SHT_NOBITS {
+            continue;
+        }
+
+        // 不可分配的节不在内存中，内容从磁盘复制
+        let offset = read_u64(shdr, 24) as usize;
+        let size = read_u64(shdr, 32) as usize;
+        let data = offset.checked_add(size).and_then(|end| disk.get(offset..end)).ok_or_else(|| anyhow!("Section {} is out of bounds", index))?;
+        let align = read_u64(shdr, 48).max(1) as usize;
+        image.resize(image.len().div_ceil(align) * align, 0);
+        write_u64(shdr, 24, image.len() as u64);
+        image.extend_from_slice(data);
+    }
+
+    image.resize(image.len().div_ceil(8) * 8, 0);
+    let table_offset = image.len() as u64;
+    image.extend_from_slice(&sections);
+    write_u64(image, 0x28, table_offset);
+    write_u16(image, 0x3A, SHDR_SIZE as u16);
+    write_u16(image, 0x3C, shnum as u16);
+    write_u16(image, 0x3E, shstrndx);
+    Ok(shnum)
+}
+
+/// 转储模块 `name`（完整路径或文件名）并重建为可加载的 ELF，写入 `output`
+pub fn dump_module(manager: &DriverManager, name: &str, output: &str) -> Result<ModuleDump> {
+    let module = find_module(manager, name)?;
+    let (mut image, unreadable_pages) = read_image(manager, module.base, module.size as usize);
+
+    // 直接从 APK 加载的库没有独立的磁盘文件
+    let disk = std::fs::read(&module.path).ok().filter(|disk| has_valid_header(disk));
+
+    let mut header_restored = false;
+    if !has_valid_header(&image) {
+        let disk = disk.as_ref().ok_or_else(|| anyhow!("ELF header of {} is damaged and no file on disk to restore it from", module.path))?;
+        let phoff = read_u64(disk, 0x20) as usize;
+        let phdrs_end = phoff + read_u16(disk, 0x38) as usize * PHDR_SIZE;
+        if phdrs_end > image.len() {
+            return Err(anyhow!("Program headers of {} do not fit in the loaded image", module.path));
+        }
+        image[..ELF_HEADER_SIZE].copy_from_slice(&disk[..ELF_HEADER_SIZE]);
+        image[phoff..phdrs_end].copy_from_slice(&disk[phoff..phdrs_end]);
+        header_restored = true;
+    }
+
+    let min_vaddr = min_load_vaddr(&image).ok_or_else(|| anyhow!("{} has no PT_LOAD segment", module.path))?;
+    fix_program_headers(&mut image, min_vaddr);
+
+    // 内存中的节头表指向磁盘偏移，先清掉，能从磁盘重建时再写回
+    write_u64(&mut image, 0x28, 0);
+    write_u16(&mut image, 0x3C, 0);
+    write_u16(&mut image, 0x3E, 0);
+    let image_len = image.len();
+    let sections = match &disk {
+        Some(disk) => rebuild_sections(&mut image, disk, min_vaddr).unwrap_or_else(|e| {
+            warn!("模块转储: {} 的节头无法重建: {}", module.path, e);
+            image.truncate(image_len);
+            0
+        }),
+        None => 0,
+    };
+
+    std::fs::write(output, &image).map_err(|e| anyhow!("Failed to write {}: {}", output, e))?;
+    debug!(
+        "模块转储完成: {} -> {}, 基址 0x{:X}, {} 字节, {} 个节, {} 页不可读",
+        module.path,
+        output,
+        module.base,
+        image.len(),
+        sections,
+        unreadable_pages
+    );
+
+    Ok(ModuleDump {
+        module,
+        file_size: image.len() as u64,
+        sections,
+        header_restored,
+        unreadable_pages,
+    })
+}
@@ -0,0 +1,62 @@
+//! 线程枚举与寄存器读取
+//!
+//! 线程列表和寄存器都由驱动从绑定进程中取得，不依赖 ptrace。
+//! 与观察点命中记录中的 tid 对照，可以知道是哪个线程访问了目标地址。
+
+use crate::core::driver_manager::DriverManager;
+use crate::wuwa::BindProc;
+use anyhow::{anyhow, Result};
+
+/// 绑定进程中的线程
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: i32,
+    pub name: String,
+    /// 调度状态，与 /proc/<pid>/task/<tid>/stat 中的字母一致
+    pub state: char,
+}
+
+/// 线程的用户态寄存器
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadRegisters {
+    pub tid: i32,
+    /// x0..x30
+    pub regs: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+fn bound_process(manager: &DriverManager) -> Result<&BindProc> {
+    manager.get_bound_process().filter(|_| manager.is_process_bound()).ok_or_else(|| anyhow!("No process bound"))
+}
+
+/// 列出绑定进程的线程，按 tid 排序
+pub fn list_threads(manager: &DriverManager) -> Result<Vec<ThreadInfo>> {
+    let mut threads: Vec<ThreadInfo> = bound_process(manager)?
+        .list_threads()?
+        .iter()
+        .map(|thread| {
+            let end = thread.comm.iter().position(|&b| b == 0).unwrap_or(thread.comm.len());
+            ThreadInfo {
+                tid: thread.tid,
+                name: String::from_utf8_lossy(&thread.comm[..end]).into_owned(),
+                state: thread.state as char,
+            }
+        })
+        .collect();
+    threads.sort_by_key(|thread| thread.tid);
+    Ok(threads)
+}
+
+/// 读取线程 `tid` 的寄存器
+pub fn read_registers(manager: &DriverManager, tid: i32) -> Result<ThreadRegisters> {
+    let cmd = bound_process(manager)?.get_thread_regs(tid)?;
+    Ok(ThreadRegisters {
+        tid,
+        regs: cmd.regs,
+        sp: cmd.sp,
+        pc: cmd.pc,
+        pstate: cmd.pstate,
+    })
+}
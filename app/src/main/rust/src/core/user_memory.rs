@@ -0,0 +1,176 @@
+//! 用户态内存访问
+//!
+//! 内核模块无法加载时的退路：通过 `process_vm_readv`/`process_vm_writev` 或 `/proc/<pid>/mem` 读写目标进程，
+//! 内存区域改为解析 `/proc/<pid>/maps`。两者都需要 root（或对目标进程的 ptrace 权限），
+//! 访问会出现在目标进程可观察的痕迹中，速度也比驱动慢。
+//! `process_vm_writev` 无法写入只读页，`/proc/<pid>/mem` 的写入带 FOLL_FORCE，可以改写只读的私有映射。
+
+use crate::core::error::MamuError;
+use crate::core::globals::PAGE_SIZE;
+use crate::core::region_type::MemRegion;
+use crate::wuwa::{PageStatusBitmap, MEM_EXECUTABLE, MEM_READABLE, MEM_SHARED, MEM_WRITABLE};
+use anyhow::{anyhow, Result};
+use nix::libc;
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+
+/// 用户态访问方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserMemoryKind {
+    ProcessVm,
+    ProcMem,
+}
+
+/// 绑定到一个进程的用户态读写器
+pub struct UserMemory {
+    pid: i32,
+    kind: UserMemoryKind,
+    /// `/proc/<pid>/mem`，仅 [`UserMemoryKind::ProcMem`] 打开
+    mem: Option<File>,
+}
+
+impl UserMemory {
+    pub fn open(pid: i32, kind: UserMemoryKind) -> Result<Self> {
+        if std::fs::metadata(format!("/proc/{}", pid)).is_err() {
+            return Err(anyhow!("Process {} does not exist", pid));
+        }
+        let mem = match kind {
+            UserMemoryKind::ProcessVm => None,
+            UserMemoryKind::ProcMem => Some(
+                OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(format!("/proc/{}/mem", pid))
+                    .map_err(|e| anyhow!("Failed to open /proc/{}/mem: {}", pid, e))?,
+            ),
+        };
+        Ok(Self { pid, kind, mem })
+    }
+
+    pub fn kind(&self) -> UserMemoryKind {
+        self.kind
+    }
+
+    /// 尽量读取 `buf.len()` 字节，返回实际读到的长度（遇到未映射页即停止）
+    fn read_some(&self, addr: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &self.mem {
+            Some(mem) => mem.read_at(buf, addr),
+            None => {
+                let local = libc::iovec {
+                    iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+                    iov_len: buf.len(),
+                };
+                let remote = libc::iovec {
+                    iov_base: addr as *mut libc::c_void,
+                    iov_len: buf.len(),
+                };
+                let n = unsafe { libc::process_vm_readv(self.pid, &local, 1, &remote, 1, 0) };
+                if n < 0 { Err(std::io::Error::last_os_error()) } else { Ok(n as usize) }
+            },
+        }
+    }
+
+    fn write_some(&self, addr: u64, buf: &[u8]) -> std::io::Result<usize> {
+        match &self.mem {
+            Some(mem) => mem.write_at(buf, addr),
+            None => {
+                let local = libc::iovec {
+                    iov_base: buf.as_ptr() as *mut libc::c_void,
+                    iov_len: buf.len(),
+                };
+                let remote = libc::iovec {
+                    iov_base: addr as *mut libc::c_void,
+                    iov_len: buf.len(),
+                };
+                let n = unsafe { libc::process_vm_writev(self.pid, &local, 1, &remote, 1, 0) };
+                if n < 0 { Err(std::io::Error::last_os_error()) } else { Ok(n as usize) }
+            },
+        }
+    }
+
+    /// 读取目标进程内存
+    ///
+    /// 整段读取失败时按页重试，未映射的页填 0。
+    /// 传入 `page_status` 时只要有一页读到就返回成功，由调用方按页检查；否则要求整段读到。
+    pub fn read(&self, addr: u64, buf: &mut [u8], page_status: Option<&mut PageStatusBitmap>) -> Result<()> {
+        if matches!(self.read_some(addr, buf), Ok(n) if n == buf.len()) {
+            if let Some(status) = page_status {
+                status.mark_all_success();
+            }
+            return Ok(());
+        }
+        let Some(status) = page_status else {
+            return Err(MamuError::read_fault(addr, format!("Userspace read failed: va=0x{:x} size={}", addr, buf.len())).into());
+        };
+
+        let page_size = *PAGE_SIZE as u64;
+        let first_page = addr & !(page_size - 1);
+        let end = addr + buf.len() as u64;
+        let mut any = false;
+        let mut page = first_page;
+        while page < end {
+            let start = page.max(addr);
+            let stop = (page + page_size).min(end);
+            let chunk = &mut buf[(start - addr) as usize..(stop - addr) as usize];
+            if matches!(self.read_some(start, chunk), Ok(n) if n == chunk.len()) {
+                status.mark_success(((page - first_page) / page_size) as usize);
+                any = true;
+            } else {
+                chunk.fill(0);
+            }
+            page += page_size;
+        }
+
+        if any {
+            Ok(())
+        } else {
+            Err(MamuError::read_fault(addr, format!("Userspace read failed: va=0x{:x} size={}", addr, buf.len())).into())
+        }
+    }
+
+    /// 写入目标进程内存，必须整段写入才算成功
+    pub fn write(&self, addr: u64, buf: &[u8]) -> Result<()> {
+        match self.write_some(addr, buf) {
+            Ok(n) if n == buf.len() => Ok(()),
+            Ok(n) => Err(MamuError::write_fault(addr, format!("Userspace write incomplete: va=0x{:x} {}/{} bytes", addr, n, buf.len())).into()),
+            Err(e) => Err(MamuError::write_fault(addr, format!("Userspace write failed: va=0x{:x} size={}: {}", addr, buf.len(), e)).into()),
+        }
+    }
+}
+
+/// 解析 `/proc/<pid>/maps`，权限位与驱动返回的一致
+pub fn read_proc_maps(pid: i32) -> Result<Vec<MemRegion>> {
+    let maps = std::fs::read_to_string(format!("/proc/{}/maps", pid)).map_err(|e| anyhow!("Unable to read /proc/{}/maps: {}", pid, e))?;
+
+    let regions = maps
+        .lines()
+        .filter_map(|line| {
+            // start-end perms offset dev inode [name]
+            let mut fields = line.splitn(6, ' ');
+            let (start, end) = fields.next()?.split_once('-')?;
+            let perms = fields.next()?.as_bytes();
+            let name = fields.nth(3).unwrap_or("").trim_start();
+
+            let mut flags = 0;
+            for (i, (ch, flag)) in [(b'r', MEM_READABLE), (b'w', MEM_WRITABLE), (b'x', MEM_EXECUTABLE), (b's', MEM_SHARED)].into_iter().enumerate() {
+                if perms.get(i) == Some(&ch) {
+                    flags |= flag;
+                }
+            }
+            Some(MemRegion {
+                start: u64::from_str_radix(start, 16).ok()?,
+                end: u64::from_str_radix(end, 16).ok()?,
+                flags,
+                name: name.to_string(),
+            })
+        })
+        .collect();
+    Ok(regions)
+}
+
+/// 从 `/proc/<pid>/cmdline` 取进程名
+pub fn proc_name(pid: i32) -> Option<String> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let end = cmdline.iter().position(|&b| b == 0).unwrap_or(cmdline.len());
+    (end > 0).then(|| String::from_utf8_lossy(&cmdline[..end]).into_owned())
+}
@@ -0,0 +1,343 @@
+//! 值历史记录
+//!
+//! 按设定的频率轮询登记的地址，把带时间戳的值写入每个地址的环形缓冲区，供 Java 侧画出值随时间变化的曲线，
+//! 从中看出经过异或、缩放或混淆的编码。
+//!
+//! 全部地址由一个后台线程采样，登记第一个地址时启动，全部移除后退出；同一轮到期的地址合并为一次批量读取。
+//! 环形缓冲区满后，设置了落盘目录时最旧的样本转存到 `MmapQueue`，长时间记录不占用内存，否则直接丢弃。
+//! 时间戳为毫秒级 Unix 时间，由单调时钟推算，系统时间调整不会打乱顺序。
+
+use crate::core::globals::{clear_mutex_poison, DRIVER_MANAGER, OP_QUEUE};
+use crate::pointer_scan::storage::MmapQueue;
+use anyhow::{Result, anyhow};
+use log::{info, warn};
+use rkyv::{Archive, Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 单个值的最大字节数
+pub const MAX_SAMPLE_SIZE: usize = 8;
+
+/// 最小采样间隔
+pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// 内存中每个地址的默认与最大样本数
+pub const DEFAULT_CAPACITY: usize = 4096;
+pub const MAX_CAPACITY: usize = 1 << 20;
+
+/// 同时记录的最大地址数
+pub const MAX_TRACKS: usize = 64;
+
+/// 没有地址到期时线程最长休眠时间
+const MAX_IDLE: Duration = Duration::from_millis(50);
+
+/// 一个样本，`value` 为小端原始值零扩展到 8 字节
+#[derive(Archive, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub timestamp_ms: u64,
+    pub value: u64,
+}
+
+/// 记录配置
+#[derive(Debug, Clone)]
+pub struct TrackConfig {
+    pub address: u64,
+    pub size: usize,
+    pub interval: Duration,
+    /// 内存中保留的样本数
+    pub capacity: usize,
+}
+
+struct Track {
+    id: u64,
+    config: TrackConfig,
+    recent: VecDeque<Sample>,
+    /// 从环形缓冲区转存出的更早样本
+    spilled: Option<MmapQueue<Sample>>,
+    /// 没有落盘目录时丢弃的样本数
+    dropped: u64,
+    next_due: Instant,
+}
+
+impl Track {
+    fn push(&mut self, sample: Sample, spill_dir: Option<&Path>) {
+        if self.recent.len() >= self.config.capacity
+            && let Some(oldest) = self.recent.pop_front()
+        {
+            match spill_dir {
+                Some(dir) => {
+                    if let Err(e) = self.spill(&oldest, dir) {
+                        warn!("ValueHistory: 记录 {} 转存失败，丢弃样本: {:#}", self.id, e);
+                        self.dropped += 1;
+                    }
+                },
+                None => self.dropped += 1,
+            }
+        }
+        self.recent.push_back(sample);
+    }
+
+    fn spill(&mut self, sample: &Sample, dir: &Path) -> Result<()> {
+        if self.spilled.is_none() {
+            self.spilled = Some(MmapQueue::new_fixed(dir, &format!("value_history_{}", self.id))?);
+        }
+        self.spilled.as_mut().unwrap().push(sample)
+    }
+
+    fn spilled_len(&self) -> usize {
+        self.spilled.as_ref().map_or(0, |queue| queue.len())
+    }
+
+    fn spilled_at(&self, index: usize) -> Option<Sample> {
+        let record = self.spilled.as_ref()?.get(index)?;
+        Some(Sample {
+            timestamp_ms: record.timestamp_ms.to_native(),
+            value: record.value.to_native(),
+        })
+    }
+
+    /// 第一个时间戳不小于 `timestamp_ms` 的样本在完整历史中的位置
+    fn lower_bound(&self, timestamp_ms: u64) -> usize {
+        let spilled = self.spilled_len();
+        if self.recent.front().is_some_and(|first| first.timestamp_ms < timestamp_ms) || spilled == 0 {
+            return spilled + self.recent.partition_point(|sample| sample.timestamp_ms < timestamp_ms);
+        }
+        let (mut low, mut high) = (0, spilled);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.spilled_at(mid).is_some_and(|sample| sample.timestamp_ms < timestamp_ms) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    fn at(&self, index: usize) -> Option<Sample> {
+        let spilled = self.spilled_len();
+        if index < spilled {
+            self.spilled_at(index)
+        } else {
+            self.recent.get(index - spilled).copied()
+        }
+    }
+
+    /// `[from_ms, to_ms]` 内的样本，超过 `max` 个时等间隔抽取
+    fn query(&self, from_ms: u64, to_ms: u64, max: usize) -> Vec<Sample> {
+        if from_ms > to_ms || max == 0 {
+            return Vec::new();
+        }
+        let start = self.lower_bound(from_ms);
+        let end = match to_ms.checked_add(1) {
+            Some(next) => self.lower_bound(next),
+            None => self.spilled_len() + self.recent.len(),
+        };
+        let count = end.saturating_sub(start);
+        if count <= max {
+            return (start..end).filter_map(|index| self.at(index)).collect();
+        }
+        (0..max).filter_map(|i| self.at(start + i * count / max)).collect()
+    }
+}
+
+#[derive(Default)]
+struct RecorderState {
+    tracks: Vec<Track>,
+    spill_dir: Option<PathBuf>,
+    thread_running: bool,
+}
+
+/// 值历史记录器
+pub struct ValueRecorder {
+    state: Arc<Mutex<RecorderState>>,
+    next_id: AtomicU64,
+    /// 创建时的单调时钟与 Unix 毫秒时间，用于推算时间戳
+    epoch: (Instant, u64),
+}
+
+impl Default for ValueRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValueRecorder {
+    pub fn new() -> Self {
+        let unix_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        Self {
+            state: Arc::new(Mutex::new(RecorderState::default())),
+            next_id: AtomicU64::new(1),
+            epoch: (Instant::now(), unix_ms),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, RecorderState>> {
+        self.state.lock().map_err(|_| anyhow!("Failed to acquire value recorder lock"))
+    }
+
+    /// 清除锁的中毒状态，返回中毒的锁数
+    pub fn clear_poison(&self) -> usize {
+        clear_mutex_poison(&self.state)
+    }
+
+    /// 设置转存目录，None 时环形缓冲区满后丢弃最旧的样本。已转存的样本不受影响
+    pub fn set_spill_dir(&self, dir: Option<PathBuf>) -> Result<()> {
+        self.lock()?.spill_dir = dir;
+        Ok(())
+    }
+
+    /// 开始记录一个地址并在需要时启动采样线程，返回记录 id
+    pub fn track(&self, config: TrackConfig) -> Result<u64> {
+        if config.size == 0 || config.size > MAX_SAMPLE_SIZE {
+            return Err(anyhow!("Sample size must be between 1 and {} bytes", MAX_SAMPLE_SIZE));
+        }
+        if config.capacity == 0 || config.capacity > MAX_CAPACITY {
+            return Err(anyhow!("Capacity must be between 1 and {}", MAX_CAPACITY));
+        }
+        let mut state = self.lock()?;
+        if state.tracks.len() >= MAX_TRACKS {
+            return Err(anyhow!("At most {} addresses can be tracked", MAX_TRACKS));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        state.tracks.push(Track {
+            id,
+            recent: VecDeque::with_capacity(config.capacity.min(DEFAULT_CAPACITY)),
+            config,
+            spilled: None,
+            dropped: 0,
+            next_due: Instant::now(),
+        });
+        if !state.thread_running {
+            let thread_state = self.state.clone();
+            let epoch = self.epoch;
+            std::thread::Builder::new()
+                .name("mamu-history".to_string())
+                .spawn(move || run(thread_state, epoch))
+                .map_err(|e| anyhow!("Failed to spawn history thread: {}", e))?;
+            state.thread_running = true;
+            info!("ValueHistory: 采样线程已启动");
+        }
+        Ok(id)
+    }
+
+    /// 停止记录并丢弃历史，记录不存在时返回 false
+    pub fn untrack(&self, id: u64) -> Result<bool> {
+        let mut state = self.lock()?;
+        let len = state.tracks.len();
+        state.tracks.retain(|track| track.id != id);
+        Ok(state.tracks.len() != len)
+    }
+
+    /// 移除全部记录，采样线程随后退出
+    pub fn clear(&self) -> Result<()> {
+        self.lock()?.tracks.clear();
+        Ok(())
+    }
+
+    pub fn track_ids(&self) -> Vec<u64> {
+        self.state
+            .lock()
+            .map(|state| state.tracks.iter().map(|track| track.id).collect())
+            .unwrap_or_default()
+    }
+
+    /// 已保留的样本数（内存和转存），记录不存在时返回 None
+    pub fn sample_count(&self, id: u64) -> Option<u64> {
+        let state = self.state.lock().ok()?;
+        let track = state.tracks.iter().find(|track| track.id == id)?;
+        Some((track.spilled_len() + track.recent.len()) as u64)
+    }
+
+    /// 因没有转存目录或转存失败而丢弃的样本数，记录不存在时返回 None
+    pub fn dropped_count(&self, id: u64) -> Option<u64> {
+        let state = self.state.lock().ok()?;
+        state.tracks.iter().find(|track| track.id == id).map(|track| track.dropped)
+    }
+
+    /// 时间范围 `[from_ms, to_ms]` 内的样本，按时间排序，超过 `max` 个时等间隔抽取。记录不存在时返回 None
+    pub fn history(&self, id: u64, from_ms: u64, to_ms: u64, max: usize) -> Option<Vec<Sample>> {
+        let state = self.state.lock().ok()?;
+        let track = state.tracks.iter().find(|track| track.id == id)?;
+        Some(track.query(from_ms, to_ms, max))
+    }
+
+    /// 最新的样本
+    pub fn latest(&self, id: u64) -> Option<Sample> {
+        let state = self.state.lock().ok()?;
+        state.tracks.iter().find(|track| track.id == id)?.recent.back().copied()
+    }
+}
+
+fn timestamp_ms(epoch: (Instant, u64), now: Instant) -> u64 {
+    epoch.1 + now.duration_since(epoch.0).as_millis() as u64
+}
+
+/// 采样线程，记录清空后退出
+fn run(state: Arc<Mutex<RecorderState>>, epoch: (Instant, u64)) {
+    loop {
+        // 在锁外读取，采样期间查询不被阻塞
+        let now = Instant::now();
+        let (due, sleep) = {
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            if state.tracks.is_empty() {
+                state.thread_running = false;
+                info!("ValueHistory: 没有记录，采样线程退出");
+                return;
+            }
+            let mut due = Vec::new();
+            let mut next_due = now + MAX_IDLE;
+            for track in state.tracks.iter_mut() {
+                if track.next_due <= now {
+                    track.next_due = now + track.config.interval.max(MIN_SAMPLE_INTERVAL);
+                    due.push((track.id, track.config.address, track.config.size));
+                }
+                next_due = next_due.min(track.next_due);
+            }
+            (due, next_due)
+        };
+
+        if !due.is_empty() {
+            let values = {
+                let Ok(manager) = DRIVER_MANAGER.read() else {
+                    return;
+                };
+                let requests: Vec<(u64, usize)> = due.iter().map(|&(_, address, size)| (address, size)).collect();
+                let _op = OP_QUEUE.interactive();
+                manager.read_memory_batch(&requests)
+            };
+            let timestamp_ms = timestamp_ms(epoch, now);
+
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            let state = &mut *state;
+            for (&(id, _, _), value) in due.iter().zip(values) {
+                // 不可读时不记录，曲线上留下空档
+                let Ok(bytes) = value else {
+                    continue;
+                };
+                let Some(track) = state.tracks.iter_mut().find(|track| track.id == id) else {
+                    continue;
+                };
+                let mut raw = [0u8; 8];
+                raw[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+                let sample = Sample {
+                    timestamp_ms,
+                    value: u64::from_le_bytes(raw),
+                };
+                track.push(sample, state.spill_dir.as_deref());
+            }
+        }
+
+        let sleep = sleep.saturating_duration_since(Instant::now());
+        if !sleep.is_zero() {
+            std::thread::sleep(sleep);
+        }
+    }
+}
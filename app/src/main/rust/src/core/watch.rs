@@ -0,0 +1,399 @@
+//! WatchManager - 硬件观察点
+//!
+//! "是什么写了这个地址"：通过驱动在绑定进程的所有线程上布置 ARM64 硬件观察点，
+//! 命中时驱动记录访问指令地址、线程、寄存器和时间戳，这里取回后按观察点归档。
+//!
+//! 观察点数量受 CPU 限制（通常 4 个），单个观察点最多覆盖不跨 8 字节边界的 8 字节。
+//! 页保护 + 信号跳板的方案需要在目标进程内安装信号处理器，而驱动只提供内存读写，
+//! 因此不做回退；驱动不支持观察点时布置直接返回错误。
+//!
+//! 读访问追踪（"是什么访问了这个地址"）用读或读写观察点覆盖一段地址，
+//! 命中只保留指令地址和线程，写入固定容量的环形缓冲区供 Java 层轮询，
+//! 适合每帧都会读取、却很少写入的结构。
+//!
+//! 观察点属于绑定进程的 fd，切换或解绑进程后由驱动释放，这里的记录随之丢弃。
+
+use crate::core::driver_manager::DriverManager;
+use crate::wuwa::{BindProc, BpWatchHit, BP_WATCH_ACCESS, BP_WATCH_MAX_LEN, BP_WATCH_READ, BP_WATCH_WRITE};
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// 每个观察点保留的最大命中记录数，超出后丢弃最旧的
+pub const MAX_HITS_PER_WATCH: usize = 4096;
+
+/// 追踪环形缓冲区的默认与最大容量
+pub const DEFAULT_TRACE_CAPACITY: usize = 4096;
+pub const MAX_TRACE_CAPACITY: usize = 1 << 16;
+
+/// 单个追踪最多占用的观察点数，即最多覆盖 8 个对齐的 8 字节块
+pub const MAX_TRACE_WATCHPOINTS: usize = 8;
+
+/// 单次从驱动取回的命中数
+const COLLECT_BATCH: usize = 256;
+
+/// 观察点触发的访问类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    Read,
+    /// 读或写
+    Access,
+}
+
+impl WatchKind {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(WatchKind::Write),
+            1 => Some(WatchKind::Read),
+            2 => Some(WatchKind::Access),
+            _ => None,
+        }
+    }
+
+    fn driver_kind(self) -> u32 {
+        match self {
+            WatchKind::Write => BP_WATCH_WRITE,
+            WatchKind::Read => BP_WATCH_READ,
+            WatchKind::Access => BP_WATCH_ACCESS,
+        }
+    }
+}
+
+/// 已布置的观察点
+#[derive(Debug)]
+struct Watch {
+    /// 布置时绑定的进程
+    pid: i32,
+    address: u64,
+    size: usize,
+    kind: WatchKind,
+    /// 尚未取走的命中记录
+    hits: VecDeque<BpWatchHit>,
+    /// 累计命中次数，包括已取走和被丢弃的
+    hit_count: u64,
+}
+
+/// 追踪记录的一次访问
+#[derive(Debug, Clone, Copy)]
+pub struct AccessRecord {
+    pub pc: u64,
+    pub tid: i32,
+    pub address: u64,
+    pub timestamp_ns: u64,
+}
+
+/// 一段地址上的访问追踪
+#[derive(Debug)]
+struct AccessTrace {
+    pid: i32,
+    address: u64,
+    len: usize,
+    kind: WatchKind,
+    /// 覆盖该地址段的驱动观察点 id
+    watch_ids: Vec<i32>,
+    ring: VecDeque<AccessRecord>,
+    capacity: usize,
+    /// 累计访问次数，包括被环形缓冲区覆盖的
+    total: u64,
+}
+
+impl AccessTrace {
+    fn push(&mut self, record: AccessRecord) {
+        if self.ring.len() >= self.capacity {
+            self.ring.pop_front();
+        }
+        self.ring.push_back(record);
+        self.total += 1;
+    }
+}
+
+/// 把 [address, address + len) 切成不跨 8 字节边界的块
+fn split_aligned(address: u64, len: usize) -> Vec<(u64, usize)> {
+    let block = BP_WATCH_MAX_LEN as u64;
+    let end = address + len as u64;
+    let mut chunks = Vec::new();
+    let mut start = address;
+    while start < end {
+        let chunk_end = ((start / block + 1) * block).min(end);
+        chunks.push((start, (chunk_end - start) as usize));
+        start = chunk_end;
+    }
+    chunks
+}
+
+/// 硬件观察点管理器
+#[derive(Default)]
+pub struct WatchManager {
+    /// 驱动分配的观察点 id -> 观察点
+    watches: BTreeMap<i32, Watch>,
+    /// 追踪 id -> 追踪
+    traces: BTreeMap<i32, AccessTrace>,
+    /// 追踪占用的驱动观察点 id -> 追踪 id
+    trace_watches: HashMap<i32, i32>,
+    next_trace_id: i32,
+    /// 驱动环形缓冲区溢出丢失的命中数
+    dropped: u64,
+}
+
+fn bound_process(driver: &DriverManager) -> Result<&BindProc> {
+    driver.get_bound_process().ok_or_else(|| anyhow!("No process bound"))
+}
+
+impl WatchManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 丢弃不属于当前绑定进程的观察点，驱动在关闭 fd 时已释放它们
+    fn forget_stale(&mut self, driver: &DriverManager) {
+        let pid = if driver.is_process_bound() { driver.get_bound_pid() } else { 0 };
+        self.watches.retain(|_, watch| watch.pid == pid);
+        self.traces.retain(|_, trace| trace.pid == pid);
+        let traces = &self.traces;
+        self.trace_watches.retain(|_, trace_id| traces.contains_key(trace_id));
+    }
+
+    /// 在 [address, address + size) 上布置观察点，返回观察点 id
+    pub fn arm(&mut self, driver: &DriverManager, address: u64, size: usize, kind: WatchKind) -> Result<i32> {
+        if size == 0 || size > BP_WATCH_MAX_LEN {
+            return Err(anyhow!("Invalid watch size: {}", size));
+        }
+        if (address as usize % BP_WATCH_MAX_LEN) + size > BP_WATCH_MAX_LEN {
+            return Err(anyhow!("Watch range 0x{:X}+{} crosses an 8-byte boundary", address, size));
+        }
+
+        self.forget_stale(driver);
+        let id = bound_process(driver)?.set_watchpoint(address as usize, size, kind.driver_kind())?;
+        debug!("WatchManager: 观察点 {} 布置于 0x{:X}+{} ({:?})", id, address, size, kind);
+
+        self.watches.insert(
+            id,
+            Watch {
+                pid: driver.get_bound_pid(),
+                address,
+                size,
+                kind,
+                hits: VecDeque::new(),
+                hit_count: 0,
+            },
+        );
+        Ok(id)
+    }
+
+    /// 解除观察点，未找到时返回 false
+    pub fn disarm(&mut self, driver: &DriverManager, id: i32) -> Result<bool> {
+        self.forget_stale(driver);
+        if !self.watches.contains_key(&id) {
+            return Ok(false);
+        }
+
+        bound_process(driver)?.clear_watchpoint(id)?;
+        self.watches.remove(&id);
+        Ok(true)
+    }
+
+    /// 解除全部观察点和追踪，返回成功解除的数量
+    pub fn disarm_all(&mut self, driver: &DriverManager) -> usize {
+        self.forget_stale(driver);
+        let ids: Vec<i32> = self.watches.keys().copied().collect();
+        let mut disarmed = 0;
+        for id in ids {
+            match self.disarm(driver, id) {
+                Ok(_) => disarmed += 1,
+                Err(e) => warn!("WatchManager: 解除观察点 {} 失败: {}", id, e),
+            }
+        }
+
+        let trace_ids: Vec<i32> = self.traces.keys().copied().collect();
+        for trace_id in trace_ids {
+            match self.stop_trace(driver, trace_id) {
+                Ok(_) => disarmed += 1,
+                Err(e) => warn!("WatchManager: 停止追踪 {} 失败: {}", trace_id, e),
+            }
+        }
+        disarmed
+    }
+
+    /// 从驱动取回命中记录并归档，返回新记录数
+    pub fn poll(&mut self, driver: &DriverManager) -> Result<usize> {
+        self.forget_stale(driver);
+        if self.watches.is_empty() && self.traces.is_empty() {
+            return Ok(0);
+        }
+
+        let bind_proc = bound_process(driver)?;
+        let mut collected = 0;
+        loop {
+            let (hits, dropped) = bind_proc.collect_watch_hits(COLLECT_BATCH)?;
+            self.dropped += dropped as u64;
+
+            let batch_len = hits.len();
+            for hit in hits {
+                if let Some(trace) = self.trace_watches.get(&hit.id).and_then(|trace_id| self.traces.get_mut(trace_id)) {
+                    trace.push(AccessRecord {
+                        pc: hit.pc,
+                        tid: hit.tid,
+                        address: hit.addr,
+                        timestamp_ns: hit.timestamp_ns,
+                    });
+                    collected += 1;
+                    continue;
+                }
+
+                // 刚解除的观察点可能还有残留记录
+                let Some(watch) = self.watches.get_mut(&hit.id) else {
+                    continue;
+                };
+                if watch.hits.len() >= MAX_HITS_PER_WATCH {
+                    watch.hits.pop_front();
+                }
+                watch.hits.push_back(hit);
+                watch.hit_count += 1;
+                collected += 1;
+            }
+
+            if batch_len < COLLECT_BATCH {
+                break;
+            }
+        }
+        Ok(collected)
+    }
+
+    /// 取走观察点的全部命中记录，按时间顺序
+    pub fn take_hits(&mut self, driver: &DriverManager, id: i32) -> Result<Vec<BpWatchHit>> {
+        self.poll(driver)?;
+        let watch = self.watches.get_mut(&id).ok_or_else(|| anyhow!("No watch with id {}", id))?;
+        Ok(watch.hits.drain(..).collect())
+    }
+
+    /// 观察点的全部命中记录，按时间顺序，不取走
+    pub fn hits(&mut self, driver: &DriverManager, id: i32) -> Result<Vec<BpWatchHit>> {
+        self.poll(driver)?;
+        let watch = self.watches.get(&id).ok_or_else(|| anyhow!("No watch with id {}", id))?;
+        Ok(watch.hits.iter().copied().collect())
+    }
+
+    /// 开始追踪 [address, address + len) 上的访问，返回追踪 id
+    ///
+    /// 地址段按 8 字节对齐块拆分，每块占用一个观察点；任一块布置失败时已布置的全部回滚。
+    pub fn start_trace(&mut self, driver: &DriverManager, address: u64, len: usize, kind: WatchKind, capacity: usize) -> Result<i32> {
+        if len == 0 {
+            return Err(anyhow!("Empty trace range"));
+        }
+        if capacity == 0 || capacity > MAX_TRACE_CAPACITY {
+            return Err(anyhow!("Invalid trace capacity: {}", capacity));
+        }
+        let chunks = split_aligned(address, len);
+        if chunks.len() > MAX_TRACE_WATCHPOINTS {
+            return Err(anyhow!("Trace range 0x{:X}+{} needs {} watchpoints, at most {}", address, len, chunks.len(), MAX_TRACE_WATCHPOINTS));
+        }
+
+        self.forget_stale(driver);
+        let bind_proc = bound_process(driver)?;
+        let mut watch_ids = Vec::with_capacity(chunks.len());
+        for (start, size) in chunks {
+            match bind_proc.set_watchpoint(start as usize, size, kind.driver_kind()) {
+                Ok(id) => watch_ids.push(id),
+                Err(e) => {
+                    for id in watch_ids {
+                        if let Err(e) = bind_proc.clear_watchpoint(id) {
+                            warn!("WatchManager: 回滚观察点 {} 失败: {}", id, e);
+                        }
+                    }
+                    return Err(e);
+                },
+            }
+        }
+
+        let trace_id = self.next_trace_id;
+        self.next_trace_id += 1;
+        debug!("WatchManager: 追踪 {} 覆盖 0x{:X}+{}，观察点 {:?}", trace_id, address, len, watch_ids);
+
+        for &id in &watch_ids {
+            self.trace_watches.insert(id, trace_id);
+        }
+        self.traces.insert(
+            trace_id,
+            AccessTrace {
+                pid: driver.get_bound_pid(),
+                address,
+                len,
+                kind,
+                watch_ids,
+                ring: VecDeque::with_capacity(capacity.min(DEFAULT_TRACE_CAPACITY)),
+                capacity,
+                total: 0,
+            },
+        );
+        Ok(trace_id)
+    }
+
+    /// 取走追踪缓冲区中最多 `max` 条记录，按时间顺序
+    pub fn poll_trace(&mut self, driver: &DriverManager, trace_id: i32, max: usize) -> Result<Vec<AccessRecord>> {
+        self.poll(driver)?;
+        let trace = self.traces.get_mut(&trace_id).ok_or_else(|| anyhow!("No trace with id {}", trace_id))?;
+        let count = max.min(trace.ring.len());
+        Ok(trace.ring.drain(..count).collect())
+    }
+
+    /// 停止追踪并释放观察点，未找到时返回 false
+    pub fn stop_trace(&mut self, driver: &DriverManager, trace_id: i32) -> Result<bool> {
+        self.forget_stale(driver);
+        let Some(trace) = self.traces.remove(&trace_id) else {
+            return Ok(false);
+        };
+
+        for id in &trace.watch_ids {
+            self.trace_watches.remove(id);
+        }
+        let bind_proc = bound_process(driver)?;
+        for &id in &trace.watch_ids {
+            if let Err(e) = bind_proc.clear_watchpoint(id) {
+                warn!("WatchManager: 释放追踪 {} 的观察点 {} 失败: {}", trace_id, id, e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 追踪的地址、长度和类型
+    pub fn get_trace(&self, trace_id: i32) -> Option<(u64, usize, WatchKind)> {
+        self.traces.get(&trace_id).map(|trace| (trace.address, trace.len, trace.kind))
+    }
+
+    /// 追踪累计记录的访问次数
+    pub fn trace_count(&self, trace_id: i32) -> Option<u64> {
+        self.traces.get(&trace_id).map(|trace| trace.total)
+    }
+
+    /// 累计命中次数
+    pub fn hit_count(&self, id: i32) -> Option<u64> {
+        self.watches.get(&id).map(|watch| watch.hit_count)
+    }
+
+    /// 观察点的地址、大小和类型
+    pub fn get(&self, id: i32) -> Option<(u64, usize, WatchKind)> {
+        self.watches.get(&id).map(|watch| (watch.address, watch.size, watch.kind))
+    }
+
+    /// 驱动缓冲区溢出丢失的命中数
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// 进程解绑后调用，丢弃记录，驱动已随 fd 释放观察点
+    pub fn forget_all(&mut self) {
+        self.watches.clear();
+        self.traces.clear();
+        self.trace_watches.clear();
+        self.dropped = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+}
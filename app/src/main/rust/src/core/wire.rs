@@ -0,0 +1,280 @@
+//! 跨 JNI 边界的 protobuf 消息
+//!
+//! 进程列表、内存区域、搜索结果、指针链和扫描事件以 protobuf 编码为 byte[] 返回，
+//! Java 侧用 protobuf-javalite 生成的类一次解析，省去逐个创建 `CProcInfo` / `MemRegionEntry` 等对象的开销。
+//! 消息定义与 `app/src/main/proto/mamu.proto` 一一对应，修改时两边同步。
+
+use crate::core::region_type::MemRegion;
+use crate::core::scan_events::{ScanKind, ScanListener};
+use crate::pointer_scan::types::PointerChain;
+use crate::wuwa::WuwaGetProcInfoCmd;
+use prost::Message;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProcInfo {
+    #[prost(int32, tag = "1")]
+    pub pid: i32,
+    #[prost(int32, tag = "2")]
+    pub tgid: i32,
+    #[prost(string, tag = "3")]
+    pub name: String,
+    #[prost(uint32, tag = "4")]
+    pub uid: u32,
+    #[prost(int32, tag = "5")]
+    pub ppid: i32,
+    #[prost(int32, tag = "6")]
+    pub prio: i32,
+    #[prost(uint64, tag = "7")]
+    pub rss: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProcessList {
+    #[prost(message, repeated, tag = "1")]
+    pub processes: Vec<ProcInfo>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Region {
+    #[prost(uint64, tag = "1")]
+    pub start: u64,
+    #[prost(uint64, tag = "2")]
+    pub end: u64,
+    #[prost(uint32, tag = "3")]
+    pub flags: u32,
+    #[prost(string, tag = "4")]
+    pub name: String,
+    /// RegionType 序号，无法分类时为 -1
+    #[prost(int32, tag = "5")]
+    pub region_type: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RegionList {
+    #[prost(message, repeated, tag = "1")]
+    pub regions: Vec<Region>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SearchResult {
+    #[prost(uint64, tag = "1")]
+    pub address: u64,
+    #[prost(int32, tag = "2")]
+    pub value_type: i32,
+    /// 小端原始值，按类型宽度截断
+    #[prost(int64, tag = "3")]
+    pub value: i64,
+    #[prost(bool, tag = "4")]
+    pub valid: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SearchResultPage {
+    #[prost(uint32, tag = "1")]
+    pub offset: u32,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+    #[prost(message, repeated, tag = "3")]
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ChainStep {
+    #[prost(string, tag = "1")]
+    pub module_name: String,
+    #[prost(uint32, tag = "2")]
+    pub module_index: u32,
+    #[prost(int64, tag = "3")]
+    pub offset: i64,
+    #[prost(bool, tag = "4")]
+    pub is_static: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Chain {
+    /// `libil2cpp.so[0]+0x1A2B3C0->+0x18` 形式
+    #[prost(string, tag = "1")]
+    pub text: String,
+    #[prost(message, repeated, tag = "2")]
+    pub steps: Vec<ChainStep>,
+    #[prost(uint64, tag = "3")]
+    pub target_address: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ChainList {
+    #[prost(uint64, tag = "1")]
+    pub total: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub chains: Vec<Chain>,
+}
+
+/// 事件类型
+pub const EVENT_PROGRESS: i32 = 0;
+pub const EVENT_FINISHED: i32 = 1;
+pub const EVENT_ERROR: i32 = 2;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ScanEvent {
+    /// ScanKind
+    #[prost(int32, tag = "1")]
+    pub kind: i32,
+    /// `EVENT_*`
+    #[prost(int32, tag = "2")]
+    pub event: i32,
+    #[prost(int32, tag = "3")]
+    pub progress: i32,
+    #[prost(int64, tag = "4")]
+    pub found: i64,
+    #[prost(bool, tag = "5")]
+    pub cancelled: bool,
+    #[prost(int32, tag = "6")]
+    pub error_code: i32,
+    #[prost(uint64, tag = "7")]
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ScanEventBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub events: Vec<ScanEvent>,
+    /// 队列满时丢弃的最早事件数
+    #[prost(uint32, tag = "2")]
+    pub dropped: u32,
+}
+
+impl From<&WuwaGetProcInfoCmd> for ProcInfo {
+    fn from(info: &WuwaGetProcInfoCmd) -> Self {
+        let end = info.name.iter().position(|&c| c == 0).unwrap_or(info.name.len());
+        Self {
+            pid: info.pid,
+            tgid: info.tgid,
+            name: String::from_utf8_lossy(&info.name[..end]).into_owned(),
+            uid: info.uid,
+            ppid: info.ppid,
+            prio: info.prio,
+            rss: info.rss as u64,
+        }
+    }
+}
+
+impl Region {
+    pub fn new(region: &MemRegion, proc_name: &str) -> Self {
+        Self {
+            start: region.start,
+            end: region.end,
+            flags: region.flags,
+            name: region.name.clone(),
+            region_type: region.classify(proc_name).map_or(-1, |typ| typ as i32),
+        }
+    }
+}
+
+impl From<&PointerChain> for Chain {
+    fn from(chain: &PointerChain) -> Self {
+        Self {
+            text: chain.format(),
+            steps: chain
+                .steps
+                .iter()
+                .map(|step| ChainStep {
+                    module_name: step.module_name.clone().unwrap_or_default(),
+                    module_index: step.module_index,
+                    offset: step.offset,
+                    is_static: step.is_static,
+                })
+                .collect(),
+            target_address: chain.target_address,
+        }
+    }
+}
+
+/// 编码为 protobuf 字节
+pub fn encode(message: &impl Message) -> Vec<u8> {
+    message.encode_to_vec()
+}
+
+/// 缓存扫描事件的监听器，由 Java 定时取出，代替逐个事件的 JNI 回调
+pub struct ScanEventQueue {
+    inner: Mutex<(VecDeque<ScanEvent>, u32)>,
+    capacity: usize,
+}
+
+impl ScanEventQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new((VecDeque::with_capacity(capacity.min(1024)), 0)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn push(&self, kind: ScanKind, event: ScanEvent) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let (events, dropped) = &mut *inner;
+        if events.len() >= self.capacity {
+            events.pop_front();
+            *dropped = dropped.saturating_add(1);
+        }
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        events.push_back(ScanEvent {
+            kind: kind as i32,
+            timestamp_ms,
+            ..event
+        });
+    }
+
+    /// 取出全部事件和丢弃数
+    pub fn drain(&self) -> ScanEventBatch {
+        let Ok(mut inner) = self.inner.lock() else {
+            return ScanEventBatch::default();
+        };
+        let (events, dropped) = &mut *inner;
+        ScanEventBatch {
+            events: events.drain(..).collect(),
+            dropped: std::mem::take(dropped),
+        }
+    }
+}
+
+impl ScanListener for ScanEventQueue {
+    fn on_progress(&self, kind: ScanKind, progress: i32, found: i64) {
+        self.push(
+            kind,
+            ScanEvent {
+                event: EVENT_PROGRESS,
+                progress,
+                found,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn on_finished(&self, kind: ScanKind, found: i64, cancelled: bool) {
+        self.push(
+            kind,
+            ScanEvent {
+                event: EVENT_FINISHED,
+                progress: 100,
+                found,
+                cancelled,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn on_error(&self, kind: ScanKind, error_code: i32) {
+        self.push(
+            kind,
+            ScanEvent {
+                event: EVENT_ERROR,
+                error_code,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Minimal AArch64 assembler for code patches.
+//!
+//! Covers the instructions patches are usually made of, so users can type
+//! `mov w0, #1; ret` instead of hand-encoding bytes:
+//! - `nop`, `ret`, `br`, `blr`, `svc`
+//! - `b`, `bl`, `b.<cond>`, `cbz`, `cbnz` to a label or an absolute `#address`
+//! - `mov` (register or immediate), `movz`, `movn`, `movk`
+//! - `add`, `adds`, `sub`, `subs`, `cmp`, `cmn` with an immediate or a register
+//! - `ldr`, `str`, `ldrb`, `strb`, `ldrh`, `strh` with `[xn]` or `[xn, #imm]`
+//!
+//! Statements are separated by `;` or newlines, `//` starts a comment and
+//! `name:` defines a label. Branch targets are absolute, matching what the
+//! disassembler prints.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+const ZR: u32 = 31;
+
+#[derive(Debug, Clone, Copy)]
+struct Reg {
+    num: u32,
+    is64: bool,
+    /// `sp`/`wsp` rather than the zero register, both are encoded as 31
+    is_sp: bool,
+}
+
+fn parse_reg(token: &str) -> Result<Reg> {
+    let token = token.trim().to_ascii_lowercase();
+    let reg = |num, is64, is_sp| Ok(Reg { num, is64, is_sp });
+    match token.as_str() {
+        "sp" => return reg(ZR, true, true),
+        "wsp" => return reg(ZR, false, true),
+        "xzr" => return reg(ZR, true, false),
+        "wzr" => return reg(ZR, false, false),
+        "lr" => return reg(30, true, false),
+        "fp" => return reg(29, true, false),
+        _ => {},
+    }
+
+    let (is64, digits) = match token.split_at_checked(1) {
+        Some(("x", digits)) => (true, digits),
+        Some(("w", digits)) => (false, digits),
+        _ => return Err(anyhow!("Invalid register: {}", token)),
+    };
+    match digits.parse::<u32>() {
+        Ok(num) if num <= 30 => reg(num, is64, false),
+        _ => Err(anyhow!("Invalid register: {}", token)),
+    }
+}
+
+fn parse_imm(token: &str) -> Result<i64> {
+    let token = token.trim();
+    let token = token.strip_prefix('#').unwrap_or(token).trim();
+    let (negative, digits) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse::<u64>(),
+    }
+    .map_err(|_| anyhow!("Invalid immediate: {}", token))? as i64;
+    Ok(if negative { value.wrapping_neg() } else { value })
+}
+
+fn is_imm(token: &str) -> bool {
+    let token = token.trim();
+    token.starts_with('#') || token.starts_with('-') || token.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn sf(reg: Reg) -> u32 {
+    (reg.is64 as u32) << 31
+}
+
+fn condition_code(cond: &str) -> Result<u32> {
+    Ok(match cond {
+        "eq" => 0,
+        "ne" => 1,
+        "cs" | "hs" => 2,
+        "cc" | "lo" => 3,
+        "mi" => 4,
+        "pl" => 5,
+        "vs" => 6,
+        "vc" => 7,
+        "hi" => 8,
+        "ls" => 9,
+        "ge" => 10,
+        "lt" => 11,
+        "gt" => 12,
+        "le" => 13,
+        "al" => 14,
+        _ => return Err(anyhow!("Invalid condition: {}", cond)),
+    })
+}
+
+/// Split operands on commas that are not inside `[...]`.
+fn split_operands(operands: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in operands.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            },
+            _ => {},
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+struct Assembler<'a> {
+    labels: &'a HashMap<String, u64>,
+}
+
+impl Assembler<'_> {
+    fn target(&self, token: &str) -> Result<u64> {
+        if is_imm(token) {
+            return Ok(parse_imm(token)? as u64);
+        }
+        self.labels.get(token.trim()).copied().ok_or_else(|| anyhow!("Unknown label: {}", token))
+    }
+
+    /// Word offset from `pc` to `target`, checked to fit a signed `bits`-bit field
+    fn branch_offset(&self, pc: u64, token: &str, bits: u32) -> Result<u32> {
+        let target = self.target(token)?;
+        if target % 4 != 0 {
+            return Err(anyhow!("Branch target 0x{:X} is not 4-byte aligned", target));
+        }
+        let words = (target.wrapping_sub(pc) as i64) >> 2;
+        let limit = 1i64 << (bits - 1);
+        if !(-limit..limit).contains(&words) {
+            return Err(anyhow!("Branch target 0x{:X} is out of range", target));
+        }
+        Ok(words as u32 & ((1 << bits) - 1))
+    }
+
+    fn assemble(&self, mnemonic: &str, ops: &[String], pc: u64) -> Result<u32> {
+        let expect = |count: usize| {
+            if ops.len() == count {
+                Ok(())
+            } else {
+                Err(anyhow!("{} expects {} operands, got {}", mnemonic, count, ops.len()))
+            }
+        };
+
+        if let Some(cond) = mnemonic.strip_prefix("b.") {
+            expect(1)?;
+            return Ok(0x5400_0000 | (self.branch_offset(pc, &ops[0], 19)? << 5) | condition_code(cond)?);
+        }
+
+        match mnemonic {
+            "nop" => {
+                expect(0)?;
+                Ok(0xD503_201F)
+            },
+            "ret" => {
+                let rn = if ops.is_empty() { 30 } else { parse_reg(&ops[0])?.num };
+                Ok(0xD65F_0000 | (rn << 5))
+            },
+            "br" | "blr" => {
+                expect(1)?;
+                let base = if mnemonic == "br" { 0xD61F_0000 } else { 0xD63F_0000 };
+                Ok(base | (parse_reg(&ops[0])?.num << 5))
+            },
+            "svc" => {
+                expect(1)?;
+                let imm = parse_imm(&ops[0])?;
+                if !(0..=0xFFFF).contains(&imm) {
+                    return Err(anyhow!("svc immediate out of range: {}", imm));
+                }
+                Ok(0xD400_0001 | ((imm as u32) << 5))
+            },
+            "b" | "bl" => {
+                expect(1)?;
+                let base = if mnemonic == "b" { 0x1400_0000 } else { 0x9400_0000 };
+                Ok(base | self.branch_offset(pc, &ops[0], 26)?)
+            },
+            "cbz" | "cbnz" => {
+                expect(2)?;
+                let rt = parse_reg(&ops[0])?;
+                let base = if mnemonic == "cbz" { 0x3400_0000 } else { 0x3500_0000 };
+                Ok(sf(rt) | base | (self.branch_offset(pc, &ops[1], 19)? << 5) | rt.num)
+            },
+            "movz" | "movn" | "movk" => {
+                if ops.len() != 2 && ops.len() != 3 {
+                    return Err(anyhow!("{} expects 2 or 3 operands", mnemonic));
+                }
+                let rd = parse_reg(&ops[0])?;
+                let imm = parse_imm(&ops[1])?;
+                let shift = match ops.get(2) {
+                    Some(op) => parse_imm(op.trim().strip_prefix("lsl").ok_or_else(|| anyhow!("Expected lsl: {}", op))?)?,
+                    None => 0,
+                };
+                let opc = match mnemonic {
+                    "movn" => 0b00,
+                    "movz" => 0b10,
+                    _ => 0b11,
+                };
+                encode_move_wide(rd, opc, imm, shift)
+            },
+            "mov" => {
+                expect(2)?;
+                let rd = parse_reg(&ops[0])?;
+                if is_imm(&ops[1]) {
+                    return encode_mov_imm(rd, parse_imm(&ops[1])?);
+                }
+                let rm = parse_reg(&ops[1])?;
+                if rd.is_sp || rm.is_sp {
+                    // mov to/from sp is an alias of add #0
+                    return Ok(sf(rd) | 0x1100_0000 | (rm.num << 5) | rd.num);
+                }
+                Ok(sf(rd) | 0x2A00_03E0 | (rm.num << 16) | rd.num)
+            },
+            "add" | "adds" | "sub" | "subs" => {
+                if ops.len() != 3 && ops.len() != 4 {
+                    return Err(anyhow!("{} expects 3 or 4 operands", mnemonic));
+                }
+                let rd = parse_reg(&ops[0])?;
+                let rn = parse_reg(&ops[1])?;
+                encode_add_sub(mnemonic.starts_with("sub"), mnemonic.ends_with('s'), rd, rn, &ops[2..])
+            },
+            "cmp" | "cmn" => {
+                if ops.len() != 2 && ops.len() != 3 {
+                    return Err(anyhow!("{} expects 2 or 3 operands", mnemonic));
+                }
+                let rn = parse_reg(&ops[0])?;
+                let zr = Reg { num: ZR, is64: rn.is64, is_sp: false };
+                encode_add_sub(mnemonic == "cmp", true, zr, rn, &ops[1..])
+            },
+            "ldr" | "str" | "ldrb" | "strb" | "ldrh" | "strh" => {
+                expect(2)?;
+                encode_load_store(mnemonic, parse_reg(&ops[0])?, &ops[1])
+            },
+            _ => Err(anyhow!("Unsupported instruction: {}", mnemonic)),
+        }
+    }
+}
+
+fn encode_move_wide(rd: Reg, opc: u32, imm: i64, shift: i64) -> Result<u32> {
+    let max_shift = if rd.is64 { 48 } else { 16 };
+    if shift % 16 != 0 || !(0..=max_shift).contains(&shift) {
+        return Err(anyhow!("Invalid shift: {}", shift));
+    }
+    if !(0..=0xFFFF).contains(&imm) {
+        return Err(anyhow!("Immediate does not fit in 16 bits: {}", imm));
+    }
+    Ok(sf(rd) | (opc << 29) | 0x1280_0000 | (((shift / 16) as u32) << 21) | ((imm as u32) << 5) | rd.num)
+}
+
+/// `mov rd, #imm` as a single movz or movn; wider constants need movz + movk.
+fn encode_mov_imm(rd: Reg, imm: i64) -> Result<u32> {
+    let (value, width) = if rd.is64 { (imm as u64, 64) } else { (imm as u32 as u64, 32) };
+    let mask = if width == 64 { u64::MAX } else { 0xFFFF_FFFF };
+    for shift in (0..width).step_by(16) {
+        if value & !(0xFFFF << shift) == 0 {
+            return encode_move_wide(rd, 0b10, ((value >> shift) & 0xFFFF) as i64, shift);
+        }
+        let inverted = !value & mask;
+        if inverted & !(0xFFFF << shift) == 0 {
+            return encode_move_wide(rd, 0b00, ((inverted >> shift) & 0xFFFF) as i64, shift);
+        }
+    }
+    Err(anyhow!("Immediate 0x{:X} needs more than one instruction, use movz and movk", value))
+}
+
+fn encode_add_sub(sub: bool, set_flags: bool, rd: Reg, rn: Reg, rest: &[String]) -> Result<u32> {
+    let flags = sf(rd) | ((set_flags as u32) << 29);
+    if !is_imm(&rest[0]) {
+        if rest.len() != 1 {
+            return Err(anyhow!("Shifted register operands are not supported"));
+        }
+        let rm = parse_reg(&rest[0])?;
+        if rm.is_sp {
+            return Err(anyhow!("sp cannot be the second source register"));
+        }
+        // The shifted register form reads register 31 as the zero register; sp needs the
+        // extended register form with a zero-shift UXTX (UXTW for 32-bit)
+        if rd.is_sp || rn.is_sp {
+            if set_flags && rd.is_sp {
+                return Err(anyhow!("Flag-setting instructions cannot write sp"));
+            }
+            let option = if rd.is64 { 0b011 } else { 0b010 };
+            return Ok(flags | ((sub as u32) << 30) | 0x0B20_0000 | (rm.num << 16) | (option << 13) | (rn.num << 5) | rd.num);
+        }
+        return Ok(flags | ((sub as u32) << 30) | 0x0B00_0000 | (rm.num << 16) | (rn.num << 5) | rd.num);
+    }
+
+    let mut imm = parse_imm(&rest[0])?;
+    let mut sub = sub;
+    if imm < 0 {
+        imm = -imm;
+        sub = !sub;
+    }
+    let shifted = match rest.get(1) {
+        Some(op) if op.trim() == "lsl #12" || op.trim() == "lsl 12" => true,
+        Some(op) => return Err(anyhow!("Invalid shift: {}", op)),
+        None if imm > 0xFFF && imm & 0xFFF == 0 && imm >> 12 <= 0xFFF => {
+            imm >>= 12;
+            true
+        },
+        None => false,
+    };
+    if imm > 0xFFF {
+        return Err(anyhow!("Immediate does not fit in 12 bits: {}", imm));
+    }
+    Ok(flags | ((sub as u32) << 30) | 0x1100_0000 | ((shifted as u32) << 22) | ((imm as u32) << 10) | (rn.num << 5) | rd.num)
+}
+
+fn encode_load_store(mnemonic: &str, rt: Reg, address: &str) -> Result<u32> {
+    let inner = address
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| anyhow!("Expected [xn] or [xn, #imm]: {}", address))?;
+    let parts = split_operands(inner);
+    let rn = parse_reg(&parts[0])?;
+    if !rn.is64 {
+        return Err(anyhow!("Base register must be 64-bit: {}", parts[0]));
+    }
+    let offset = match parts.get(1) {
+        Some(op) => parse_imm(op)?,
+        None => 0,
+    };
+
+    let load = mnemonic.starts_with("ldr");
+    // (size field, scale) per access width
+    let (size, scale) = match mnemonic {
+        "ldrb" | "strb" => (0b00, 1),
+        "ldrh" | "strh" => (0b01, 2),
+        _ if rt.is64 => (0b11, 8),
+        _ => (0b10, 4),
+    };
+    let opc = (load as u32) << 22;
+
+    if offset >= 0 && offset % scale == 0 && offset / scale <= 0xFFF {
+        return Ok((size << 30) | 0x3900_0000 | opc | (((offset / scale) as u32) << 10) | (rn.num << 5) | rt.num);
+    }
+    if (-256..256).contains(&offset) {
+        // Unscaled ldur/stur
+        return Ok((size << 30) | 0x3800_0000 | opc | (((offset as u32) & 0x1FF) << 12) | (rn.num << 5) | rt.num);
+    }
+    Err(anyhow!("Offset {} is out of range for {}", offset, mnemonic))
+}
+
+/// Strip the comment and split a source line into statements.
+fn statements(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .flat_map(|line| line.split("//").next().unwrap_or("").split(';'))
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+}
+
+/// Split off a leading `label:` from a statement.
+fn take_label(statement: &str) -> (Option<&str>, &str) {
+    match statement.split_once(':') {
+        Some((label, rest)) if !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') => {
+            (Some(label), rest.trim())
+        },
+        _ => (None, statement),
+    }
+}
+
+/// Assemble `source` as if placed at `address`.
+///
+/// Returns the little-endian machine code, four bytes per instruction.
+pub fn assemble_arm64(source: &str, address: u64) -> Result<Vec<u8>> {
+    // First pass: every instruction is one word, so labels resolve by counting
+    let mut labels = HashMap::new();
+    let mut instructions = Vec::new();
+    for statement in statements(source) {
+        let (label, rest) = take_label(statement);
+        if let Some(label) = label {
+            let label_address = address + instructions.len() as u64 * 4;
+            if labels.insert(label.to_string(), label_address).is_some() {
+                return Err(anyhow!("Duplicate label: {}", label));
+            }
+        }
+        if !rest.is_empty() {
+            instructions.push(rest);
+        }
+    }
+
+    let assembler = Assembler { labels: &labels };
+    let mut code = Vec::with_capacity(instructions.len() * 4);
+    for (index, instruction) in instructions.iter().enumerate() {
+        let (mnemonic, operands) = instruction.split_once(char::is_whitespace).unwrap_or((instruction, ""));
+        let mnemonic = mnemonic.to_ascii_lowercase();
+        let ops = split_operands(operands);
+        let pc = address + index as u64 * 4;
+        let word = assembler
+            .assemble(&mnemonic, &ops, pc)
+            .map_err(|e| anyhow!("`{}`: {}", instruction, e))?;
+        code.extend_from_slice(&word.to_le_bytes());
+    }
+
+    if code.is_empty() {
+        return Err(anyhow!("No instructions"));
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm::{disassemble, Architecture};
+
+    fn roundtrip(source: &str, address: u64) -> Vec<String> {
+        let code = assemble_arm64(source, address).unwrap();
+        disassemble(Architecture::ARM64, &code, address, 0)
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{} {}", r.mnemonic, r.operands).trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_assemble_roundtrip() {
+        let text = roundtrip(
+            "mov w0, #1; movk x1, #0x1234, lsl #16; add x2, sp, #0x10; sub w3, w4, w5; cmp x0, #-1\n\
+             ldr x6, [x7, #8]; strb w8, [x9]; ldr w10, [x11, #-4]; mov x12, x13; nop; ret",
+            0x1000,
+        );
+        assert_eq!(
+            text,
+            [
+                "mov w0, #1",
+                "movk x1, #0x1234, lsl #16",
+                "add x2, sp, #0x10",
+                "sub w3, w4, w5",
+                "cmn x0, #1",
+                "ldr x6, [x7, #8]",
+                "strb w8, [x9]",
+                "ldur w10, [x11, #-4]",
+                "mov x12, x13",
+                "nop",
+                "ret",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_add_sub_sp_register() {
+        let text = roundtrip("add x0, sp, x1; sub sp, sp, x2; add wsp, w3, w4; adds x5, sp, x6", 0x1000);
+        assert_eq!(text, ["add x0, sp, x1", "sub sp, sp, x2", "add wsp, w3, w4", "adds x5, sp, x6"]);
+        assert!(assemble_arm64("add x0, x1, sp", 0x1000).is_err());
+        assert!(assemble_arm64("adds sp, x1, x2", 0x1000).is_err());
+    }
+
+    #[test]
+    fn test_assemble_branches() {
+        let text = roundtrip("loop: cbz w0, done; sub w0, w0, #1; b.ne loop; done: bl #0x2000", 0x1000);
+        assert_eq!(text, ["cbz w0, #0x100c", "sub w0, w0, #1", "b.ne #0x1000", "bl #0x2000"]);
+    }
+}
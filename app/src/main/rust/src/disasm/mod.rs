@@ -1,10 +1,14 @@
 //! ARM instruction disassembler using Capstone engine.
 
+mod asm;
 mod pseudo;
+mod xref;
 
 use anyhow::{anyhow, Result};
 use capstone::prelude::*;
+pub use asm::assemble_arm64;
 pub use pseudo::generate_pseudo_code;
+pub use xref::{find_code_xrefs, scan_regions_for_xrefs, CodeXref, XrefKind, XrefScanner};
 
 /// Architecture modes for disassembly.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -119,6 +123,47 @@ pub fn disassemble_with_pseudo(
     Ok(results)
 }
 
+/// Disassembles fixed-width ARM64 code without stopping at undecodable words.
+///
+/// Code segments read straight from memory contain literal pools and padding,
+/// which Capstone refuses to decode. Such words are emitted as `.inst` records
+/// and disassembly resumes at the next word. Trailing bytes that don't make up
+/// a whole instruction are ignored.
+pub fn disassemble_arm64_lenient(bytes: &[u8], address: u64) -> Result<Vec<DisassemblyResult>> {
+    let cs = create_capstone(Architecture::ARM64)?;
+    let usable = bytes.len() & !3;
+    let mut results = Vec::with_capacity(usable / 4);
+
+    let mut offset = 0;
+    while offset < usable {
+        let instructions = cs.disasm_all(&bytes[offset..usable], address + offset as u64)?;
+        for insn in instructions.iter() {
+            results.push(DisassemblyResult {
+                address: insn.address(),
+                bytes: insn.bytes().to_vec(),
+                mnemonic: insn.mnemonic().unwrap_or("???").to_string(),
+                operands: insn.op_str().unwrap_or("").to_string(),
+                pseudo_code: None,
+            });
+        }
+        offset += instructions.len() * 4;
+
+        if offset < usable {
+            let word = &bytes[offset..offset + 4];
+            results.push(DisassemblyResult {
+                address: address + offset as u64,
+                bytes: word.to_vec(),
+                mnemonic: ".inst".to_string(),
+                operands: format!("0x{:08x}", u32::from_le_bytes(word.try_into().unwrap())),
+                pseudo_code: None,
+            });
+            offset += 4;
+        }
+    }
+
+    Ok(results)
+}
+
 /// Creates a Capstone instance for the specified architecture.
 fn create_capstone(arch: Architecture) -> Result<Capstone> {
     let cs = match arch {
@@ -159,6 +204,18 @@ mod tests {
         assert_eq!(results[0].mnemonic, "mov");
     }
 
+    #[test]
+    fn test_arm64_lenient_skips_invalid_words() {
+        // mov x0, #0x1234; <invalid>; ret
+        let bytes = vec![0x80, 0x46, 0x82, 0xd2, 0xff, 0xff, 0xff, 0xff, 0xc0, 0x03, 0x5f, 0xd6];
+        let results = disassemble_arm64_lenient(&bytes, 0x1000).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].mnemonic, ".inst");
+        assert_eq!(results[1].address, 0x1004);
+        assert_eq!(results[2].mnemonic, "ret");
+    }
+
     #[test]
     fn test_thumb_disassemble() {
         // movs r0, #42
@@ -0,0 +1,235 @@
+//! Static code cross-reference scanner for ARM64.
+//!
+//! Finds instructions in executable segments that compute or access a given
+//! data address through PC-relative addressing:
+//! - `ADR Xd, label`
+//! - `ADRP Xd, page` followed by `ADD Xd, Xd, #lo12`
+//! - `ADRP Xd, page` followed by `LDR/STR Xt, [Xd, #imm]`
+//! - `LDR Xt, label` (literal)
+//!
+//! Instructions are decoded by bit pattern rather than through Capstone, since
+//! a single `libil2cpp.so` text segment easily holds tens of millions of them.
+
+/// How an instruction references the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum XrefKind {
+    Adr = 0,
+    AdrpAdd = 1,
+    AdrpLoad = 2,
+    AdrpStore = 3,
+    LiteralLoad = 4,
+}
+
+/// A code location referencing the target range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeXref {
+    /// Address of the instruction completing the reference
+    pub insn_address: u64,
+    /// Address the instruction resolves to
+    pub ref_address: u64,
+    pub kind: XrefKind,
+}
+
+/// Number of instructions an ADRP result is trusted for. Beyond that the
+/// register has most likely been reused, since there is no data-flow tracking.
+const ADRP_WINDOW: u64 = 64;
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// Stateful ARM64 xref scanner.
+///
+/// Feed consecutive chunks of the same region through [`XrefScanner::feed`] so
+/// ADRP pairs spanning a chunk boundary are still matched; call
+/// [`XrefScanner::reset`] before switching to an unrelated region.
+pub struct XrefScanner {
+    target_start: u64,
+    target_end: u64,
+    /// Per-register (value, instruction index) of the last ADRP/ADD result
+    regs: [Option<(u64, u64)>; 32],
+    insn_index: u64,
+}
+
+impl XrefScanner {
+    /// Create a scanner matching references into `[target_start, target_end)`.
+    pub fn new(target_start: u64, target_end: u64) -> Self {
+        Self {
+            target_start,
+            target_end: target_end.max(target_start + 1),
+            regs: [None; 32],
+            insn_index: 0,
+        }
+    }
+
+    /// Forget tracked register values.
+    pub fn reset(&mut self) {
+        self.regs = [None; 32];
+    }
+
+    fn in_target(&self, addr: u64) -> bool {
+        addr >= self.target_start && addr < self.target_end
+    }
+
+    fn tracked(&self, reg: usize) -> Option<u64> {
+        match self.regs[reg] {
+            Some((value, index)) if self.insn_index - index <= ADRP_WINDOW => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Scan `code` located at `base_addr` and append matches to `out`.
+    pub fn feed(&mut self, code: &[u8], base_addr: u64, out: &mut Vec<CodeXref>) {
+        for (i, word) in code.chunks_exact(4).enumerate() {
+            let insn = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+            let pc = base_addr + (i as u64) * 4;
+            self.insn_index += 1;
+            self.step(insn, pc, out);
+        }
+    }
+
+    fn step(&mut self, insn: u32, pc: u64, out: &mut Vec<CodeXref>) {
+        let rd = (insn & 0x1F) as usize;
+        let rn = ((insn >> 5) & 0x1F) as usize;
+
+        // ADR / ADRP
+        if insn & 0x1F000000 == 0x10000000 {
+            let imm = (((insn >> 5) & 0x7FFFF) << 2 | ((insn >> 29) & 0x3)) as u64;
+            let imm = sign_extend(imm, 21);
+            if insn & 0x80000000 != 0 {
+                let page = (pc & !0xFFF).wrapping_add_signed(imm << 12);
+                self.regs[rd] = Some((page, self.insn_index));
+            } else {
+                let addr = pc.wrapping_add_signed(imm);
+                if self.in_target(addr) {
+                    out.push(CodeXref { insn_address: pc, ref_address: addr, kind: XrefKind::Adr });
+                }
+                self.regs[rd] = Some((addr, self.insn_index));
+            }
+            return;
+        }
+
+        // ADD Xd, Xn, #imm{, lsl #12}
+        if insn & 0xFF800000 == 0x91000000 {
+            if let Some(base) = self.tracked(rn) {
+                let mut imm = ((insn >> 10) & 0xFFF) as u64;
+                if insn & (1 << 22) != 0 {
+                    imm <<= 12;
+                }
+                let addr = base.wrapping_add(imm);
+                if self.in_target(addr) {
+                    out.push(CodeXref { insn_address: pc, ref_address: addr, kind: XrefKind::AdrpAdd });
+                }
+                self.regs[rd] = Some((addr, self.insn_index));
+            } else {
+                self.regs[rd] = None;
+            }
+            return;
+        }
+
+        // LDR/STR (unsigned immediate), integer and SIMD&FP
+        if insn & 0x3B000000 == 0x39000000 {
+            let size = insn >> 30;
+            let is_simd = insn & (1 << 26) != 0;
+            let opc = (insn >> 22) & 0x3;
+            let scale = if is_simd && opc & 0x2 != 0 { 4 } else { size };
+
+            if let Some(base) = self.tracked(rn) {
+                let addr = base.wrapping_add((((insn >> 10) & 0xFFF) as u64) << scale);
+                if self.in_target(addr) {
+                    let kind = if opc == 0 { XrefKind::AdrpStore } else { XrefKind::AdrpLoad };
+                    out.push(CodeXref { insn_address: pc, ref_address: addr, kind });
+                }
+            }
+            if !is_simd && opc != 0 {
+                // Loaded value overwrites Rt
+                self.regs[rd] = None;
+            }
+            return;
+        }
+
+        // LDR (literal)
+        if insn & 0x3B000000 == 0x18000000 {
+            let offset = sign_extend(((insn >> 5) & 0x7FFFF) as u64, 19) << 2;
+            let addr = pc.wrapping_add_signed(offset);
+            if self.in_target(addr) {
+                out.push(CodeXref { insn_address: pc, ref_address: addr, kind: XrefKind::LiteralLoad });
+            }
+            if insn & (1 << 26) == 0 {
+                self.regs[rd] = None;
+            }
+        }
+    }
+}
+
+/// Scan a single code buffer for references into `[target_start, target_end)`.
+pub fn find_code_xrefs(code: &[u8], base_addr: u64, target_start: u64, target_end: u64) -> Vec<CodeXref> {
+    let mut scanner = XrefScanner::new(target_start, target_end);
+    let mut out = Vec::new();
+    scanner.feed(code, base_addr, &mut out);
+    out
+}
+
+/// Scan executable regions `(start, end)` in chunks using `read_memory`.
+///
+/// Chunks that fail to read are skipped and break ADRP tracking, so a pair
+/// straddling an unreadable page is missed rather than mismatched.
+pub fn scan_regions_for_xrefs<R>(regions: &[(u64, u64)], target_start: u64, target_end: u64, mut read_memory: R) -> Vec<CodeXref>
+where
+    R: FnMut(u64, &mut [u8]) -> anyhow::Result<()>,
+{
+    const CHUNK_SIZE: u64 = 1024 * 1024;
+
+    let mut scanner = XrefScanner::new(target_start, target_end);
+    let mut out = Vec::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE as usize];
+
+    for &(start, end) in regions {
+        scanner.reset();
+        let mut addr = start & !0x3;
+        while addr < end {
+            let len = (end - addr).min(CHUNK_SIZE) as usize;
+            let chunk = &mut buffer[..len];
+            if read_memory(addr, chunk).is_ok() {
+                scanner.feed(chunk, addr, &mut out);
+            } else {
+                scanner.reset();
+            }
+            addr += len as u64;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(insns: &[u32]) -> Vec<u8> {
+        insns.iter().flat_map(|i| i.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_adrp_add_ldr() {
+        // adrp x0, #0x1000 ; add x0, x0, #0x20 ; ldr x1, [x0, #8]
+        let code = encode(&[0xB0000000, 0x91008000, 0xF9400401]);
+
+        let xrefs = find_code_xrefs(&code, 0x10000, 0x11020, 0x11030);
+        assert_eq!(xrefs.len(), 2);
+        assert_eq!(xrefs[0], CodeXref { insn_address: 0x10004, ref_address: 0x11020, kind: XrefKind::AdrpAdd });
+        assert_eq!(xrefs[1], CodeXref { insn_address: 0x10008, ref_address: 0x11028, kind: XrefKind::AdrpLoad });
+    }
+
+    #[test]
+    fn test_ldr_literal() {
+        // ldr x0, #0x40
+        let code = encode(&[0x58000200]);
+
+        let xrefs = find_code_xrefs(&code, 0x2000, 0x2040, 0x2041);
+        assert_eq!(xrefs.len(), 1);
+        assert_eq!(xrefs[0].kind, XrefKind::LiteralLoad);
+    }
+}
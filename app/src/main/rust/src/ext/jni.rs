@@ -1,6 +1,9 @@
+use crate::core::error::{error_code, find_mamu_error};
 use anyhow::Context;
 use jni::JNIEnv;
-use jni::objects::{JClass, JString};
+use jni::objects::{JClass, JString, JThrowable, JValue};
+
+const MAMU_EXCEPTION_CLASS: &str = "moe/fuqiuluo/mamu/driver/MamuException";
 
 /// 抛出 RuntimeException 异常，支持格式化字符串
 #[macro_export] 
@@ -24,12 +27,46 @@ pub trait JniResultExt<T> {
 impl<T: Default> JniResultExt<T> for JniResult<T> {
     fn or_throw(self, env: &mut JNIEnv) -> T {
         self.unwrap_or_else(|e| {
-            let _ = env.throw(format!("{:#}", e));
+            // 已有挂起的 Java 异常（如 JNI 调用失败留下的）时保留它，不能再抛出新的异常
+            if env.exception_check().unwrap_or(true) {
+                return T::default();
+            }
+            if throw_mamu_exception(env, &e).is_err() {
+                let _ = env.throw(format!("{:#}", e));
+            }
             T::default()
         })
     }
 }
 
+/// 抛出带错误码的 MamuException，没有地址或 ioctl 时对应参数为 -1
+fn throw_mamu_exception(env: &mut JNIEnv, err: &anyhow::Error) -> JniResult<()> {
+    let mamu = find_mamu_error(err);
+    let address = mamu.and_then(|mamu| mamu.address).map_or(-1, |address| address as i64);
+    let ioctl = mamu.and_then(|mamu| mamu.ioctl).map_or(-1, |ioctl| ioctl as i64);
+    let message = env.new_string(format!("{:#}", err))?;
+    let exception = env.new_object(
+        MAMU_EXCEPTION_CLASS,
+        "(ILjava/lang/String;JJ)V",
+        &[
+            JValue::Int(error_code(err) as i32),
+            JValue::Object(&message),
+            JValue::Long(address),
+            JValue::Long(ioctl),
+        ],
+    );
+    let exception = match exception {
+        Ok(exception) => exception,
+        Err(e) => {
+            // 找不到类等情况会留下 Java 异常
+            let _ = env.exception_clear();
+            return Err(e.into());
+        },
+    };
+    env.throw(JThrowable::from(exception))?;
+    Ok(())
+}
+
 pub trait JNIEnvExt<'l> {
     fn find_class_safe(&mut self, name: &str) -> JniResult<JClass<'l>>;
     fn new_string_safe(&mut self, s: String) -> JniResult<JString<'l>>;
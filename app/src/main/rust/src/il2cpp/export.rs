@@ -0,0 +1,148 @@
+//! C# 伪头文件导出
+//!
+//! 格式与常见的 dump.cs 相近：每个类列出字段偏移和方法的 RVA/VA，
+//! 方法体为空，只用于阅读和搜索，不能编译。
+
+use super::{Il2CppClass, Il2CppDump, Il2CppField, Il2CppMethod};
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const TYPE_ATTRIBUTE_INTERFACE: u32 = 0x20;
+const TYPE_ATTRIBUTE_ABSTRACT: u32 = 0x80;
+const TYPE_ATTRIBUTE_SEALED: u32 = 0x100;
+const FIELD_ATTRIBUTE_INIT_ONLY: u16 = 0x20;
+const METHOD_ATTRIBUTE_VIRTUAL: u16 = 0x40;
+const METHOD_ATTRIBUTE_ABSTRACT: u16 = 0x400;
+
+/// 成员访问级别（FieldAttributes / MethodAttributes 的低 3 位）
+fn member_access(flags: u16) -> &'static str {
+    match flags & 0x7 {
+        1 => "private",
+        2 => "private protected",
+        3 => "internal",
+        4 => "protected",
+        5 => "protected internal",
+        6 => "public",
+        _ => "",
+    }
+}
+
+/// 类型访问级别（TypeAttributes 的低 3 位）
+fn type_access(flags: u32) -> &'static str {
+    match flags & 0x7 {
+        1 | 2 => "public",
+        3 => "private",
+        4 => "protected",
+        6 => "private protected",
+        7 => "protected internal",
+        _ => "internal",
+    }
+}
+
+fn class_keyword(class: &Il2CppClass) -> &'static str {
+    if class.flags & TYPE_ATTRIBUTE_INTERFACE != 0 {
+        return "interface";
+    }
+    match class.parent.as_deref() {
+        Some("Enum") => "enum",
+        Some("ValueType") => "struct",
+        _ => "class",
+    }
+}
+
+fn class_declaration(class: &Il2CppClass) -> String {
+    let keyword = class_keyword(class);
+    let mut declaration = type_access(class.flags).to_string();
+    if keyword == "class" {
+        let abstract_ = class.flags & TYPE_ATTRIBUTE_ABSTRACT != 0;
+        let sealed = class.flags & TYPE_ATTRIBUTE_SEALED != 0;
+        match (abstract_, sealed) {
+            (true, true) => declaration.push_str(" static"),
+            (true, false) => declaration.push_str(" abstract"),
+            (false, true) => declaration.push_str(" sealed"),
+            _ => {},
+        }
+    }
+    declaration.push_str(&format!(" {} {}", keyword, class.name));
+    // 值类型和枚举的基类是隐式的
+    if let Some(parent) = class.parent.as_deref()
+        && keyword == "class"
+        && parent != "object"
+    {
+        declaration.push_str(&format!(" : {}", parent));
+    }
+    declaration
+}
+
+fn field_line(field: &Il2CppField) -> String {
+    let mut line = format!("\t{}", member_access(field.attrs));
+    if field.is_const() {
+        line.push_str(" const");
+    } else {
+        if field.is_static() {
+            line.push_str(" static");
+        }
+        if field.attrs & FIELD_ATTRIBUTE_INIT_ONLY != 0 {
+            line.push_str(" readonly");
+        }
+    }
+    line.push_str(&format!(" {} {};", field.type_name, field.name));
+    if let Some(offset) = field.offset
+        && !field.is_const()
+    {
+        line.push_str(&format!(" // 0x{:X}", offset));
+    }
+    line
+}
+
+fn method_lines(method: &Il2CppMethod) -> String {
+    let location = match (method.rva, method.address) {
+        (Some(rva), Some(address)) => format!("\t// RVA: 0x{:X} VA: 0x{:X}\n", rva, address),
+        _ => "\t// RVA: -1\n".to_string(),
+    };
+    let mut signature = format!("\t{}", member_access(method.flags));
+    if method.is_static() {
+        signature.push_str(" static");
+    }
+    if method.flags & METHOD_ATTRIBUTE_ABSTRACT != 0 {
+        signature.push_str(" abstract");
+    } else if method.flags & METHOD_ATTRIBUTE_VIRTUAL != 0 {
+        signature.push_str(" virtual");
+    }
+    format!("{}{} {} {}({}) {{ }}\n", location, signature, method.return_type, method.name, method.parameter_list())
+}
+
+pub fn write_csharp(dump: &Il2CppDump, path: &str) -> Result<usize> {
+    let mut writer = BufWriter::new(File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?);
+
+    writeln!(writer, "// Il2Cpp metadata v{}, {} @ 0x{:X}", dump.metadata_version, dump.module_path, dump.module_base)?;
+    for (index, image) in dump.images.iter().enumerate() {
+        writeln!(writer, "// Image {}: {}", index, image)?;
+    }
+
+    for class in &dump.classes {
+        writeln!(writer)?;
+        writeln!(writer, "// Namespace: {}", class.namespace)?;
+        writeln!(writer, "{}", class_declaration(class))?;
+        writeln!(writer, "{{")?;
+        if !class.fields.is_empty() {
+            writeln!(writer, "\t// Fields")?;
+            for field in &class.fields {
+                writeln!(writer, "{}", field_line(field))?;
+            }
+        }
+        if !class.methods.is_empty() {
+            if !class.fields.is_empty() {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "\t// Methods")?;
+            for method in &class.methods {
+                write!(writer, "{}", method_lines(method))?;
+            }
+        }
+        writeln!(writer, "}}")?;
+    }
+    writer.flush()?;
+    Ok(dump.classes.len())
+}
@@ -0,0 +1,443 @@
+//! global-metadata.dat 解析
+//!
+//! 元数据在运行时整体映射进进程，优先按映射名查找；加密的元数据通常被解密到匿名内存，
+//! 此时按文件头的魔数逐个区域探测。读到的是运行时真正使用的那份，
+//! 版本 27 起 Il2CppType 中的句柄直接指向这份映射，需要记录它的地址。
+//!
+//! 支持的版本为 24.2～24.5、27、29 和 31。24.x 的文件头版本号都是 24，
+//! 通过类型定义表的大小与镜像中登记的类型数之比区分布局。
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::OP_QUEUE;
+use crate::core::region_type::query_mem_regions;
+use crate::wuwa::MEM_READABLE;
+use anyhow::{anyhow, Result};
+use log::debug;
+
+pub const METADATA_SANITY: u32 = 0xFAB1_1BAF;
+const SUPPORTED_VERSIONS: [u32; 4] = [24, 27, 29, 31];
+
+/// 元数据上限，超过时认为文件头损坏
+const MAX_METADATA_SIZE: usize = 256 << 20;
+const READ_CHUNK: usize = 1024 * 1024;
+
+// 文件头中各表的 (offset, size) 位置
+const HEADER_STRINGS: usize = 24;
+const HEADER_METHODS: usize = 48;
+const HEADER_PARAMETERS: usize = 88;
+const HEADER_FIELDS: usize = 96;
+const HEADER_GENERIC_PARAMETERS: usize = 104;
+const HEADER_GENERIC_CONTAINERS: usize = 120;
+const HEADER_TYPE_DEFINITIONS: usize = 160;
+const HEADER_IMAGES: usize = 168;
+/// 文件头至少要包含到镜像表为止
+const HEADER_SIZE: usize = HEADER_IMAGES + 8;
+/// 解析时检查范围的表
+const HEADER_TABLES: [usize; 8] = [
+    HEADER_STRINGS,
+    HEADER_METHODS,
+    HEADER_PARAMETERS,
+    HEADER_FIELDS,
+    HEADER_GENERIC_PARAMETERS,
+    HEADER_GENERIC_CONTAINERS,
+    HEADER_TYPE_DEFINITIONS,
+    HEADER_IMAGES,
+];
+
+const IMAGE_DEFINITION_SIZE: usize = 40;
+const FIELD_DEFINITION_SIZE: usize = 12;
+const PARAMETER_DEFINITION_SIZE: usize = 12;
+const GENERIC_PARAMETER_SIZE: usize = 16;
+const GENERIC_CONTAINER_SIZE: usize = 16;
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_i32(buf: &[u8], offset: usize) -> i32 {
+    read_u32(buf, offset) as i32
+}
+
+/// 随版本变化的结构布局
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    type_size: usize,
+    type_declaring: usize,
+    type_parent: usize,
+    type_generic_container: usize,
+    type_flags: usize,
+    type_field_start: usize,
+    type_method_start: usize,
+    type_method_count: usize,
+    type_field_count: usize,
+    method_size: usize,
+    method_return: usize,
+    method_parameter_start: usize,
+    method_token: usize,
+    method_flags: usize,
+    method_parameter_count: usize,
+}
+
+impl Layout {
+    fn new(version: u32, type_size: usize) -> Result<Self> {
+        // 24.2～24.5 多一个 byrefTypeIndex
+        let shift = match type_size {
+            92 if version == 24 => 4,
+            88 if version >= 27 => 0,
+            _ => return Err(anyhow!("Unsupported metadata v{} with {}-byte type definitions", version, type_size)),
+        };
+        // 31 在 returnType 之后多一个 returnParameterToken
+        let extra = if version >= 31 { 4 } else { 0 };
+        Ok(Self {
+            type_size,
+            type_declaring: 12 + shift,
+            type_parent: 16 + shift,
+            type_generic_container: 24 + shift,
+            type_flags: 28 + shift,
+            type_field_start: 32 + shift,
+            type_method_start: 36 + shift,
+            type_method_count: 64 + shift,
+            type_field_count: 68 + shift,
+            method_size: 32 + extra,
+            method_return: 8,
+            method_parameter_start: 12 + extra,
+            method_token: 20 + extra,
+            method_flags: 24 + extra,
+            method_parameter_count: 30 + extra,
+        })
+    }
+}
+
+/// 镜像定义（一个程序集）
+#[derive(Debug, Clone)]
+pub struct ImageDefinition {
+    pub name: String,
+    pub type_start: usize,
+    pub type_count: usize,
+}
+
+/// 类型定义
+#[derive(Debug, Clone, Copy)]
+pub struct TypeDefinition {
+    pub name_index: u32,
+    pub namespace_index: u32,
+    pub declaring_type: i32,
+    pub parent_type: i32,
+    pub generic_container: i32,
+    pub flags: u32,
+    pub field_start: i32,
+    pub method_start: i32,
+    pub method_count: u16,
+    pub field_count: u16,
+}
+
+/// 方法定义
+#[derive(Debug, Clone, Copy)]
+pub struct MethodDefinition {
+    pub name_index: u32,
+    pub return_type: i32,
+    pub parameter_start: i32,
+    pub token: u32,
+    pub flags: u16,
+    pub parameter_count: u16,
+}
+
+/// 字段或参数定义
+#[derive(Debug, Clone, Copy)]
+pub struct MemberDefinition {
+    pub name_index: u32,
+    pub type_index: i32,
+}
+
+/// 读入内存的元数据
+pub struct Metadata {
+    bytes: Vec<u8>,
+    pub version: u32,
+    /// 元数据在目标进程中的地址
+    pub address: u64,
+    layout: Layout,
+    pub images: Vec<ImageDefinition>,
+}
+
+fn table(bytes: &[u8], header: usize) -> (usize, usize) {
+    (read_u32(bytes, header) as usize, read_u32(bytes, header + 4) as usize)
+}
+
+fn string_at(bytes: &[u8], offset: usize) -> String {
+    let Some(bytes) = bytes.get(offset..) else {
+        return String::new();
+    };
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+impl Metadata {
+    fn table(&self, header: usize) -> (usize, usize) {
+        table(&self.bytes, header)
+    }
+
+    /// 解析读到的元数据
+    ///
+    /// 各表须完整落在 `bytes` 内，之后按表大小检查序号即可安全读取。
+    fn parse(bytes: Vec<u8>, address: u64) -> Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(anyhow!("Metadata is smaller than its header"));
+        }
+        for header in HEADER_TABLES {
+            let (offset, size) = table(&bytes, header);
+            if offset.checked_add(size).is_none_or(|end| end > bytes.len()) {
+                return Err(anyhow!("Metadata table at header offset {} is out of range", header));
+            }
+        }
+
+        let version = read_u32(&bytes, 4);
+        let (strings, _) = table(&bytes, HEADER_STRINGS);
+        let (images_offset, images_size) = table(&bytes, HEADER_IMAGES);
+        let mut images: Vec<ImageDefinition> = bytes[images_offset..images_offset + images_size]
+            .chunks_exact(IMAGE_DEFINITION_SIZE)
+            .map(|image| ImageDefinition {
+                name: string_at(&bytes, strings + read_u32(image, 0) as usize),
+                type_start: read_i32(image, 8).max(0) as usize,
+                type_count: read_u32(image, 12) as usize,
+            })
+            .collect();
+
+        let type_count = images.iter().try_fold(0usize, |sum, image| sum.checked_add(image.type_count));
+        let (_, types_size) = table(&bytes, HEADER_TYPE_DEFINITIONS);
+        let Some(type_count) = type_count.filter(|&count| count != 0 && types_size.is_multiple_of(count)) else {
+            return Err(anyhow!("Type definition table does not match the image table"));
+        };
+
+        // 镜像登记的类型范围不能超出类型定义表
+        for image in &mut images {
+            image.type_start = image.type_start.min(type_count);
+            image.type_count = image.type_count.min(type_count - image.type_start);
+        }
+
+        Ok(Self {
+            layout: Layout::new(version, types_size / type_count)?,
+            bytes,
+            version,
+            address,
+            images,
+        })
+    }
+
+    /// 字符串表中的字符串
+    pub fn string(&self, index: u32) -> String {
+        string_at(&self.bytes, self.table(HEADER_STRINGS).0 + index as usize)
+    }
+
+    pub fn type_count(&self) -> usize {
+        self.table(HEADER_TYPE_DEFINITIONS).1 / self.layout.type_size
+    }
+
+    pub fn type_definition(&self, index: usize) -> TypeDefinition {
+        let layout = &self.layout;
+        let def = &self.bytes[self.table(HEADER_TYPE_DEFINITIONS).0 + index * layout.type_size..];
+        TypeDefinition {
+            name_index: read_u32(def, 0),
+            namespace_index: read_u32(def, 4),
+            declaring_type: read_i32(def, layout.type_declaring),
+            parent_type: read_i32(def, layout.type_parent),
+            generic_container: read_i32(def, layout.type_generic_container),
+            flags: read_u32(def, layout.type_flags),
+            field_start: read_i32(def, layout.type_field_start),
+            method_start: read_i32(def, layout.type_method_start),
+            method_count: read_u16(def, layout.type_method_count),
+            field_count: read_u16(def, layout.type_field_count),
+        }
+    }
+
+    /// 运行时类型定义句柄（版本 27 起）对应的类型序号
+    pub fn type_index_of_handle(&self, handle: u64) -> Option<usize> {
+        let (offset, size) = self.table(HEADER_TYPE_DEFINITIONS);
+        let start = self.address + offset as u64;
+        let relative = handle.checked_sub(start)? as usize;
+        (relative < size && relative.is_multiple_of(self.layout.type_size)).then_some(relative / self.layout.type_size)
+    }
+
+    pub fn method_definition(&self, index: usize) -> Option<MethodDefinition> {
+        let layout = &self.layout;
+        let (offset, size) = self.table(HEADER_METHODS);
+        if (index + 1) * layout.method_size > size {
+            return None;
+        }
+        let def = &self.bytes[offset + index * layout.method_size..];
+        Some(MethodDefinition {
+            name_index: read_u32(def, 0),
+            return_type: read_i32(def, layout.method_return),
+            parameter_start: read_i32(def, layout.method_parameter_start),
+            token: read_u32(def, layout.method_token),
+            flags: read_u16(def, layout.method_flags),
+            parameter_count: read_u16(def, layout.method_parameter_count),
+        })
+    }
+
+    pub fn field_definition(&self, index: usize) -> Option<MemberDefinition> {
+        let (offset, size) = self.table(HEADER_FIELDS);
+        if (index + 1) * FIELD_DEFINITION_SIZE > size {
+            return None;
+        }
+        let def = &self.bytes[offset + index * FIELD_DEFINITION_SIZE..];
+        Some(MemberDefinition {
+            name_index: read_u32(def, 0),
+            type_index: read_i32(def, 4),
+        })
+    }
+
+    pub fn parameter_definition(&self, index: usize) -> Option<MemberDefinition> {
+        let (offset, size) = self.table(HEADER_PARAMETERS);
+        if (index + 1) * PARAMETER_DEFINITION_SIZE > size {
+            return None;
+        }
+        let def = &self.bytes[offset + index * PARAMETER_DEFINITION_SIZE..];
+        Some(MemberDefinition {
+            name_index: read_u32(def, 0),
+            type_index: read_i32(def, 8),
+        })
+    }
+
+    /// 泛型参数名
+    pub fn generic_parameter_name(&self, index: usize) -> Option<String> {
+        let (offset, size) = self.table(HEADER_GENERIC_PARAMETERS);
+        if (index + 1) * GENERIC_PARAMETER_SIZE > size {
+            return None;
+        }
+        Some(self.string(read_u32(&self.bytes, offset + index * GENERIC_PARAMETER_SIZE + 4)))
+    }
+
+    /// 运行时泛型参数句柄（版本 27 起）对应的参数名
+    pub fn generic_parameter_name_of_handle(&self, handle: u64) -> Option<String> {
+        let (offset, _) = self.table(HEADER_GENERIC_PARAMETERS);
+        let relative = handle.checked_sub(self.address + offset as u64)? as usize;
+        if !relative.is_multiple_of(GENERIC_PARAMETER_SIZE) {
+            return None;
+        }
+        self.generic_parameter_name(relative / GENERIC_PARAMETER_SIZE)
+    }
+
+    /// 泛型容器的参数名列表
+    pub fn generic_container_parameters(&self, index: i32) -> Vec<String> {
+        let (offset, size) = self.table(HEADER_GENERIC_CONTAINERS);
+        if index < 0 || (index as usize + 1) * GENERIC_CONTAINER_SIZE > size {
+            return Vec::new();
+        }
+        let container = &self.bytes[offset + index as usize * GENERIC_CONTAINER_SIZE..];
+        let count = read_i32(container, 4).max(0) as usize;
+        let start = read_i32(container, 12).max(0) as usize;
+        (start..start + count).filter_map(|param| self.generic_parameter_name(param)).collect()
+    }
+}
+
+/// 文件头中各表的最远结束位置，即元数据的总长度
+fn metadata_size(header: &[u8]) -> Option<usize> {
+    let header_size = read_u32(header, 8) as usize;
+    if header_size < HEADER_SIZE || header_size > header.len() {
+        return None;
+    }
+    (8..header_size)
+        .step_by(8)
+        .map(|offset| read_u32(header, offset) as usize + read_u32(header, offset + 4) as usize)
+        .max()
+        .filter(|&size| size <= MAX_METADATA_SIZE)
+}
+
+fn is_metadata_header(header: &[u8]) -> bool {
+    read_u32(header, 0) == METADATA_SANITY && SUPPORTED_VERSIONS.contains(&read_u32(header, 4))
+}
+
+/// 读取 `address` 处的元数据，文件头无效时返回 None
+fn read_metadata(manager: &DriverManager, address: u64) -> Option<Metadata> {
+    let mut header = [0u8; 512];
+    manager.read_memory_unified(address, &mut header, None).ok()?;
+    if !is_metadata_header(&header) {
+        return None;
+    }
+
+    let size = metadata_size(&header)?;
+    let mut bytes = vec![0u8; size];
+    for (index, chunk) in bytes.chunks_mut(READ_CHUNK).enumerate() {
+        let _op = OP_QUEUE.bulk();
+        manager.read_memory_unified(address + (index * READ_CHUNK) as u64, chunk, None).ok()?;
+    }
+
+    match Metadata::parse(bytes, address) {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            debug!("Il2Cpp: 0x{:X} 处的元数据无法解析: {}", address, e);
+            None
+        },
+    }
+}
+
+/// 在绑定进程中查找并读取元数据
+pub fn locate_metadata(manager: &DriverManager) -> Result<Metadata> {
+    let mut regions = query_mem_regions(manager, manager.get_bound_pid())?;
+    regions.sort_by_key(|region| region.start);
+
+    if let Some(region) = regions.iter().find(|region| region.name.ends_with("global-metadata.dat"))
+        && let Some(metadata) = read_metadata(manager, region.start)
+    {
+        debug!("Il2Cpp: 元数据映射于 0x{:X}，版本 {}", region.start, metadata.version);
+        return Ok(metadata);
+    }
+
+    // 解密后的元数据位于匿名内存，逐个区域检查文件头
+    for region in regions.iter().filter(|region| region.flags & MEM_READABLE != 0 && !region.name.starts_with('/')) {
+        let mut magic = [0u8; 8];
+        if manager.read_memory_unified(region.start, &mut magic, None).is_err() || !is_metadata_header(&magic) {
+            continue;
+        }
+        if let Some(metadata) = read_metadata(manager, region.start) {
+            debug!("Il2Cpp: 在匿名内存 0x{:X} 找到元数据，版本 {}", region.start, metadata.version);
+            return Ok(metadata);
+        }
+    }
+
+    Err(anyhow!("global-metadata.dat was not found in the bound process"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_metadata() {
+        assert!(Metadata::parse(vec![0; 16], 0).is_err());
+
+        let mut bytes = vec![0u8; HEADER_SIZE];
+        put(&mut bytes, 4, 29);
+        put(&mut bytes, HEADER_IMAGES, HEADER_SIZE as u32);
+        put(&mut bytes, HEADER_IMAGES + 4, IMAGE_DEFINITION_SIZE as u32);
+        assert!(Metadata::parse(bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_clamps_image_type_ranges() {
+        let images = HEADER_SIZE;
+        let types = images + IMAGE_DEFINITION_SIZE;
+        let mut bytes = vec![0u8; types + 88];
+        put(&mut bytes, 4, 29);
+        put(&mut bytes, HEADER_IMAGES, images as u32);
+        put(&mut bytes, HEADER_IMAGES + 4, IMAGE_DEFINITION_SIZE as u32);
+        put(&mut bytes, HEADER_TYPE_DEFINITIONS, types as u32);
+        put(&mut bytes, HEADER_TYPE_DEFINITIONS + 4, 88);
+        // 登记 1 个类型，但起始序号越界
+        put(&mut bytes, images + 8, 1000);
+        put(&mut bytes, images + 12, 1);
+
+        let metadata = Metadata::parse(bytes, 0).unwrap();
+        assert_eq!(metadata.type_count(), 1);
+        assert_eq!(metadata.images[0].type_start, 1);
+        assert_eq!(metadata.images[0].type_count, 0);
+    }
+}
@@ -0,0 +1,218 @@
+//! Il2Cpp 分析
+//!
+//! 从绑定进程中读取 global-metadata.dat 和 libil2cpp.so 的注册结构，
+//! 得到全部类、字段（类型与偏移）和方法（签名与地址），供界面浏览，
+//! 也可以导出为 C# 伪头文件。所有数据都取自运行中的进程，加密的元数据只要已被游戏解密即可分析。
+
+mod export;
+mod metadata;
+mod runtime;
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::RemoteElf;
+use crate::core::image_memory::ImageMemory;
+use crate::core::modules::find_module;
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use metadata::locate_metadata;
+use runtime::{find_code_gen_modules, find_metadata_registration, CodeGenModule, TypeResolver};
+use std::collections::HashMap;
+
+pub const FIELD_ATTRIBUTE_STATIC: u16 = 0x10;
+pub const FIELD_ATTRIBUTE_LITERAL: u16 = 0x40;
+pub const METHOD_ATTRIBUTE_STATIC: u16 = 0x10;
+
+/// 字段
+#[derive(Debug, Clone)]
+pub struct Il2CppField {
+    pub name: String,
+    pub type_name: String,
+    /// 实例字段相对对象起始的偏移，静态字段相对静态数据区；未知时为 None
+    pub offset: Option<i32>,
+    /// FieldAttributes
+    pub attrs: u16,
+}
+
+impl Il2CppField {
+    pub fn is_static(&self) -> bool {
+        self.attrs & FIELD_ATTRIBUTE_STATIC != 0
+    }
+
+    pub fn is_const(&self) -> bool {
+        self.attrs & FIELD_ATTRIBUTE_LITERAL != 0
+    }
+}
+
+/// 方法
+#[derive(Debug, Clone)]
+pub struct Il2CppMethod {
+    pub name: String,
+    pub return_type: String,
+    /// (类型, 名称)
+    pub parameters: Vec<(String, String)>,
+    /// MethodAttributes
+    pub flags: u16,
+    /// 运行时地址，抽象方法和未实例化的泛型方法没有
+    pub address: Option<u64>,
+    /// 相对 libil2cpp.so 的偏移
+    pub rva: Option<u64>,
+}
+
+impl Il2CppMethod {
+    pub fn is_static(&self) -> bool {
+        self.flags & METHOD_ATTRIBUTE_STATIC != 0
+    }
+
+    /// 参数列表，如 `int a, float b`
+    pub fn parameter_list(&self) -> String {
+        self.parameters.iter().map(|(ty, name)| format!("{} {}", ty, name)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// 类
+#[derive(Debug, Clone)]
+pub struct Il2CppClass {
+    /// 所属程序集
+    pub image: String,
+    pub namespace: String,
+    /// 含泛型参数，嵌套类型以外层类型名为前缀，如 `Outer.Inner`、`List<T>`
+    pub name: String,
+    pub parent: Option<String>,
+    /// TypeAttributes
+    pub flags: u32,
+    pub fields: Vec<Il2CppField>,
+    pub methods: Vec<Il2CppMethod>,
+}
+
+impl Il2CppClass {
+    pub fn full_name(&self) -> String {
+        if self.namespace.is_empty() { self.name.clone() } else { format!("{}.{}", self.namespace, self.name) }
+    }
+}
+
+/// 一次完整的分析结果
+pub struct Il2CppDump {
+    /// 分析时绑定的进程
+    pub pid: i32,
+    pub metadata_version: u32,
+    pub module_path: String,
+    pub module_base: u64,
+    pub images: Vec<String>,
+    pub classes: Vec<Il2CppClass>,
+}
+
+impl Il2CppDump {
+    /// 分析绑定进程，`module` 为 libil2cpp.so 的文件名或路径（部分游戏会改名）
+    pub fn analyze(manager: &DriverManager, module: &str) -> Result<Self> {
+        if !manager.is_process_bound() {
+            return Err(anyhow!("No process bound"));
+        }
+
+        let metadata = locate_metadata(manager)?;
+        let module = find_module(manager, module)?;
+        let image = RemoteElf::read(manager, module.base).ok_or_else(|| anyhow!("{} is not a valid ELF image", module.path))?;
+        let memory = ImageMemory::read(manager, &image);
+
+        let registration = find_metadata_registration(&memory, metadata.type_count())?;
+        let code_modules = find_code_gen_modules(&memory, metadata.images.len()).unwrap_or_else(|e| {
+            warn!("Il2Cpp: {}，方法地址不可用", e);
+            Vec::new()
+        });
+        let modules_by_name: HashMap<&str, &CodeGenModule> = code_modules.iter().map(|module| (module.name.as_str(), module)).collect();
+
+        let mut resolver = TypeResolver::new(&memory, &metadata, &registration);
+        let mut classes = Vec::with_capacity(metadata.type_count());
+        for image_def in &metadata.images {
+            let code_module = modules_by_name.get(image_def.name.as_str()).copied();
+            for type_index in image_def.type_start..image_def.type_start + image_def.type_count {
+                let def = metadata.type_definition(type_index);
+
+                let mut name = metadata.string(def.name_index);
+                if def.generic_container >= 0 {
+                    let params = metadata.generic_container_parameters(def.generic_container);
+                    let base = name.split('`').next().unwrap_or_default().to_string();
+                    name = format!("{}<{}>", base, params.join(", "));
+                }
+                if def.declaring_type >= 0 {
+                    name = format!("{}.{}", resolver.type_name_at(def.declaring_type), name);
+                }
+
+                let field_count = def.field_count as usize;
+                let offsets = resolver.field_offsets(type_index, field_count);
+                let fields = (0..field_count)
+                    .filter_map(|i| {
+                        let field = metadata.field_definition(def.field_start.max(0) as usize + i)?;
+                        Some(Il2CppField {
+                            name: metadata.string(field.name_index),
+                            type_name: resolver.type_name_at(field.type_index),
+                            offset: offsets[i],
+                            attrs: resolver.type_at(field.type_index).map_or(0, |ty| ty.attrs),
+                        })
+                    })
+                    .collect();
+
+                let methods = (0..def.method_count as usize)
+                    .filter_map(|i| {
+                        let method = metadata.method_definition(def.method_start.max(0) as usize + i)?;
+                        let parameters = (0..method.parameter_count as usize)
+                            .filter_map(|p| metadata.parameter_definition(method.parameter_start.max(0) as usize + p))
+                            .map(|param| (resolver.type_name_at(param.type_index), metadata.string(param.name_index)))
+                            .collect();
+                        let address = code_module.and_then(|module| module.method_pointer(method.token));
+                        Some(Il2CppMethod {
+                            name: metadata.string(method.name_index),
+                            return_type: resolver.type_name_at(method.return_type),
+                            parameters,
+                            flags: method.flags,
+                            address,
+                            rva: address.map(|address| address.wrapping_sub(module.load_bias)),
+                        })
+                    })
+                    .collect();
+
+                classes.push(Il2CppClass {
+                    image: image_def.name.clone(),
+                    namespace: metadata.string(def.namespace_index),
+                    name,
+                    parent: (def.parent_type >= 0).then(|| resolver.type_name_at(def.parent_type)),
+                    flags: def.flags,
+                    fields,
+                    methods,
+                });
+            }
+        }
+
+        info!(
+            "Il2Cpp: 元数据 v{}，{} 个程序集，{} 个类，{} 个代码模块",
+            metadata.version,
+            metadata.images.len(),
+            classes.len(),
+            code_modules.len()
+        );
+        Ok(Self {
+            pid: manager.get_bound_pid(),
+            metadata_version: metadata.version,
+            module_path: module.path,
+            module_base: module.base,
+            images: metadata.images.iter().map(|image| image.name.clone()).collect(),
+            classes,
+        })
+    }
+
+    /// 按完整类名查找（不区分大小写的子串匹配），返回类序号，最多 `max` 个
+    pub fn find_classes(&self, query: &str, max: usize) -> Vec<usize> {
+        let query = query.to_lowercase();
+        self.classes
+            .iter()
+            .enumerate()
+            .filter(|(_, class)| query.is_empty() || class.full_name().to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .take(max)
+            .collect()
+    }
+
+    /// 导出为 C# 伪头文件，返回写出的类数
+    pub fn export_csharp(&self, path: &str) -> Result<usize> {
+        export::write_csharp(self, path)
+    }
+}
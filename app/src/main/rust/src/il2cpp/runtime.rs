@@ -0,0 +1,284 @@
+//! libil2cpp.so 中的注册结构与运行时类型
+//!
+//! 方法地址和类型信息不在元数据里，而在 libil2cpp.so 的数据段中：
+//! - `Il2CppCodeRegistration.codeGenModules`：每个程序集一个模块，按方法 token 索引方法指针
+//! - `Il2CppMetadataRegistration`：`types` 为全部 Il2CppType，`fieldOffsets` 为各类型的字段偏移
+//!
+//! 这两个结构没有导出符号，按结构特征在可写段中搜索：
+//! 模块数组前是等于镜像数的计数；元数据注册中 fieldOffsetsCount 与
+//! typeDefinitionsSizesCount 都等于类型定义数，二者隔一个指针相邻。
+//! 数据段整体读入本地，指针追踪优先在本地完成，其余地址再经驱动读取。
+
+use super::metadata::Metadata;
+use crate::core::image_memory::ImageMemory;
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::collections::HashMap;
+
+// Il2CppTypeEnum
+const TYPE_VOID: u8 = 0x01;
+const TYPE_PTR: u8 = 0x0f;
+const TYPE_BYREF: u8 = 0x10;
+const TYPE_VALUETYPE: u8 = 0x11;
+const TYPE_CLASS: u8 = 0x12;
+const TYPE_VAR: u8 = 0x13;
+const TYPE_ARRAY: u8 = 0x14;
+const TYPE_GENERICINST: u8 = 0x15;
+const TYPE_SZARRAY: u8 = 0x1d;
+const TYPE_MVAR: u8 = 0x1e;
+
+/// 元数据注册中 fieldOffsetsCount 的偏移
+const FIELD_OFFSETS_COUNT_OFFSET: u64 = 80;
+/// 类型名递归深度上限，防止读到错误数据时无限展开
+const MAX_TYPE_DEPTH: usize = 8;
+/// 泛型参数个数上限
+const MAX_GENERIC_ARGS: u32 = 32;
+
+fn primitive_name(kind: u8) -> Option<&'static str> {
+    Some(match kind {
+        TYPE_VOID => "void",
+        0x02 => "bool",
+        0x03 => "char",
+        0x04 => "sbyte",
+        0x05 => "byte",
+        0x06 => "short",
+        0x07 => "ushort",
+        0x08 => "int",
+        0x09 => "uint",
+        0x0a => "long",
+        0x0b => "ulong",
+        0x0c => "float",
+        0x0d => "double",
+        0x0e => "string",
+        0x16 => "TypedReference",
+        0x18 => "IntPtr",
+        0x19 => "UIntPtr",
+        0x1c => "object",
+        _ => return None,
+    })
+}
+
+/// 一个程序集的代码模块
+#[derive(Debug, Clone)]
+pub struct CodeGenModule {
+    pub name: String,
+    /// 按方法 token 的行号排列的方法地址，0 表示没有实现
+    pub method_pointers: Vec<u64>,
+}
+
+impl CodeGenModule {
+    /// 方法 token 对应的地址
+    pub fn method_pointer(&self, token: u32) -> Option<u64> {
+        let row = (token & 0x00FF_FFFF) as usize;
+        self.method_pointers.get(row.checked_sub(1)?).copied().filter(|&pointer| pointer != 0)
+    }
+}
+
+/// 读取 `address` 处的 Il2CppCodeGenModule
+fn read_code_gen_module(memory: &ImageMemory, address: u64) -> Option<CodeGenModule> {
+    let name = memory.read_cstring(memory.read_u64(address)?, 256)?;
+    if !name.ends_with(".dll") {
+        return None;
+    }
+    let count = memory.read_u32(address + 8)? as usize;
+    let pointers = memory.read_u64(address + 16)?;
+    let method_pointers = if count == 0 {
+        Vec::new()
+    } else {
+        memory
+            .read_bytes(pointers, count * 8)?
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+            .collect()
+    };
+    Some(CodeGenModule { name, method_pointers })
+}
+
+/// 搜索 codeGenModules 并读取全部模块
+pub fn find_code_gen_modules(memory: &ImageMemory, image_count: usize) -> Result<Vec<CodeGenModule>> {
+    memory
+        .scan(|_, [count, array]| {
+            if count != image_count as u64 || !memory.is_data_pointer(array) {
+                return None;
+            }
+            // 先验证第一个模块，再读取全部
+            let first = memory.read_u64(array).filter(|&module| memory.is_data_pointer(module))?;
+            read_code_gen_module(memory, first)?;
+            let modules = (0..image_count as u64)
+                .map(|index| memory.read_u64(array + index * 8).and_then(|module| read_code_gen_module(memory, module)))
+                .collect::<Option<Vec<CodeGenModule>>>()?;
+            debug!("Il2Cpp: codeGenModules 位于 0x{:X}", array);
+            Some(modules)
+        })
+        .ok_or_else(|| anyhow!("Il2CppCodeRegistration.codeGenModules was not found"))
+}
+
+/// Il2CppMetadataRegistration 中用到的部分
+pub struct MetadataRegistration {
+    types: Vec<u64>,
+    field_offsets: u64,
+}
+
+/// 搜索 Il2CppMetadataRegistration
+pub fn find_metadata_registration(memory: &ImageMemory, type_count: usize) -> Result<MetadataRegistration> {
+    memory
+        .scan(|address, [offsets_count, offsets, sizes_count, sizes]| {
+            if offsets_count != type_count as u64 || sizes_count != type_count as u64 {
+                return None;
+            }
+            if !memory.is_data_pointer(offsets) || !memory.is_data_pointer(sizes) {
+                return None;
+            }
+
+            let registration = address - FIELD_OFFSETS_COUNT_OFFSET;
+            let types_count = memory.read_u64(registration + 48).filter(|&count| count > 0 && count < 1 << 24)?;
+            let types = memory.read_u64(registration + 56).and_then(|types| memory.read_bytes(types, types_count as usize * 8))?;
+            debug!("Il2Cpp: Il2CppMetadataRegistration 位于 0x{:X}，{} 个类型", registration, types_count);
+            Some(MetadataRegistration {
+                types: types.chunks_exact(8).map(|word| u64::from_le_bytes(word.try_into().unwrap())).collect(),
+                field_offsets: offsets,
+            })
+        })
+        .ok_or_else(|| anyhow!("Il2CppMetadataRegistration was not found"))
+}
+
+/// 解析后的 Il2CppType
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeType {
+    pub data: u64,
+    pub attrs: u16,
+    pub kind: u8,
+}
+
+/// 类型名解析
+pub struct TypeResolver<'a> {
+    memory: &'a ImageMemory<'a>,
+    metadata: &'a Metadata,
+    registration: &'a MetadataRegistration,
+    /// 类型定义的短名，不含命名空间
+    class_names: Vec<String>,
+    cache: HashMap<u64, String>,
+}
+
+impl<'a> TypeResolver<'a> {
+    pub fn new(memory: &'a ImageMemory<'a>, metadata: &'a Metadata, registration: &'a MetadataRegistration) -> Self {
+        let class_names = (0..metadata.type_count())
+            .map(|index| {
+                let name = metadata.string(metadata.type_definition(index).name_index);
+                // List`1 -> List
+                name.split('`').next().unwrap_or_default().to_string()
+            })
+            .collect();
+        Self {
+            memory,
+            metadata,
+            registration,
+            class_names,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn read_type(&self, address: u64) -> Option<RuntimeType> {
+        let bytes = self.memory.read_bytes(address, 16)?;
+        let data = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let bits = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        Some(RuntimeType {
+            data,
+            attrs: bits as u16,
+            kind: (bits >> 16) as u8,
+        })
+    }
+
+    /// 类型表中第 `index` 个类型
+    pub fn type_at(&self, index: i32) -> Option<RuntimeType> {
+        let address = *self.registration.types.get(usize::try_from(index).ok()?)?;
+        self.read_type(address)
+    }
+
+    /// 类型表中第 `index` 个类型的名字
+    pub fn type_name_at(&mut self, index: i32) -> String {
+        match usize::try_from(index).ok().and_then(|index| self.registration.types.get(index)).copied() {
+            Some(address) => self.type_name(address, 0),
+            None => "?".to_string(),
+        }
+    }
+
+    /// CLASS/VALUETYPE 数据对应的类型定义序号
+    fn class_index(&self, data: u64) -> Option<usize> {
+        if self.metadata.version >= 27 {
+            self.metadata.type_index_of_handle(data)
+        } else {
+            Some(data as u32 as usize).filter(|&index| index < self.class_names.len())
+        }
+    }
+
+    fn type_name(&mut self, address: u64, depth: usize) -> String {
+        if let Some(name) = self.cache.get(&address) {
+            return name.clone();
+        }
+        let name = self.resolve(address, depth).unwrap_or_else(|| "?".to_string());
+        self.cache.insert(address, name.clone());
+        name
+    }
+
+    fn resolve(&mut self, address: u64, depth: usize) -> Option<String> {
+        if depth > MAX_TYPE_DEPTH {
+            return None;
+        }
+        let ty = self.read_type(address)?;
+        if let Some(name) = primitive_name(ty.kind) {
+            return Some(name.to_string());
+        }
+
+        Some(match ty.kind {
+            TYPE_CLASS | TYPE_VALUETYPE => self.class_names.get(self.class_index(ty.data)?)?.clone(),
+            TYPE_PTR => format!("{}*", self.type_name(ty.data, depth + 1)),
+            TYPE_BYREF => format!("ref {}", self.type_name(ty.data, depth + 1)),
+            TYPE_SZARRAY => format!("{}[]", self.type_name(ty.data, depth + 1)),
+            TYPE_ARRAY => {
+                // Il2CppArrayType { etype, rank, ... }
+                let element = self.memory.read_u64(ty.data)?;
+                let rank = self.memory.read_bytes(ty.data + 8, 1)?[0].max(1) as usize;
+                format!("{}[{}]", self.type_name(element, depth + 1), ",".repeat(rank - 1))
+            },
+            TYPE_VAR | TYPE_MVAR => {
+                if self.metadata.version >= 27 {
+                    self.metadata.generic_parameter_name_of_handle(ty.data)?
+                } else {
+                    self.metadata.generic_parameter_name(ty.data as u32 as usize)?
+                }
+            },
+            TYPE_GENERICINST => {
+                // Il2CppGenericClass { typeDefinitionIndex / type, context { class_inst, method_inst }, cached_class }
+                let head = self.memory.read_u64(ty.data)?;
+                let base = if self.metadata.version >= 27 {
+                    self.type_name(head, depth + 1)
+                } else {
+                    self.class_names.get(head as u32 as usize)?.clone()
+                };
+                let inst = self.memory.read_u64(ty.data + 8)?;
+                let argc = self.memory.read_u32(inst)?.min(MAX_GENERIC_ARGS);
+                let argv = self.memory.read_u64(inst + 8)?;
+                let args: Vec<String> = (0..argc as u64)
+                    .filter_map(|index| self.memory.read_u64(argv + index * 8))
+                    .map(|arg| self.type_name(arg, depth + 1))
+                    .collect();
+                format!("{}<{}>", base, args.join(", "))
+            },
+            _ => return None,
+        })
+    }
+
+    /// 类型定义 `index` 的字段偏移表
+    pub fn field_offsets(&self, type_index: usize, field_count: usize) -> Vec<Option<i32>> {
+        let offsets = self
+            .memory
+            .read_u64(self.registration.field_offsets + type_index as u64 * 8)
+            .filter(|&pointer| pointer != 0)
+            .and_then(|pointer| self.memory.read_bytes(pointer, field_count * 4));
+        match offsets {
+            Some(bytes) => bytes.chunks_exact(4).map(|offset| Some(i32::from_le_bytes(offset.try_into().unwrap()))).collect(),
+            None => vec![None; field_count],
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! 代码注入
+//!
+//! 在绑定进程内分配内存并写入代码，是 hook 和自定义 code cave 的基础；
+//! 在此之上可以让目标进程加载任意共享库。
+//! 所有操作都经由驱动完成，不需要 ptrace 目标进程。
+
+mod remote_alloc;
+mod solib;
+
+pub use remote_alloc::{RemoteAllocation, RemoteAllocator, RemoteProt};
+pub use solib::{InjectedLibrary, LibraryInjector};
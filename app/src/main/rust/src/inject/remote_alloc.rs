@@ -0,0 +1,226 @@
+//! 远程内存分配
+//!
+//! 通过驱动在绑定进程内映射匿名页。可执行载荷默认按 RW → 写入 → RX 的顺序加载，
+//! 不出现同时可写可执行的页；自修改代码之类需要 RWX 时可显式要求。
+//!
+//! 分配属于绑定进程，解绑前先恢复跳入分配的补丁和 hook，再调用 [`RemoteAllocator::free_all`]，
+//! 否则映射会一直留在目标进程中直到其退出；无法恢复时宁可保留映射，不让目标跳进已解除的页。
+
+use crate::core::driver_manager::DriverManager;
+use crate::wuwa::BindProc;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use nix::libc::{c_int, PROT_EXEC, PROT_READ, PROT_WRITE};
+use std::collections::BTreeMap;
+
+/// 单次写入的最大字节数，BindProc 写入上限为 64KB
+const WRITE_CHUNK: usize = 64 * 1024;
+
+/// 远程页的访问权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteProt {
+    ReadWrite,
+    ReadExec,
+    ReadWriteExec,
+}
+
+impl RemoteProt {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(RemoteProt::ReadWrite),
+            1 => Some(RemoteProt::ReadExec),
+            2 => Some(RemoteProt::ReadWriteExec),
+            _ => None,
+        }
+    }
+
+    pub fn id(self) -> i32 {
+        match self {
+            RemoteProt::ReadWrite => 0,
+            RemoteProt::ReadExec => 1,
+            RemoteProt::ReadWriteExec => 2,
+        }
+    }
+
+    fn flags(self) -> c_int {
+        match self {
+            RemoteProt::ReadWrite => PROT_READ | PROT_WRITE,
+            RemoteProt::ReadExec => PROT_READ | PROT_EXEC,
+            RemoteProt::ReadWriteExec => PROT_READ | PROT_WRITE | PROT_EXEC,
+        }
+    }
+
+    fn writable(self) -> bool {
+        self != RemoteProt::ReadExec
+    }
+}
+
+/// 绑定进程内的一块分配
+#[derive(Debug, Clone)]
+pub struct RemoteAllocation {
+    pub address: u64,
+    /// 按页对齐后的大小
+    pub size: usize,
+    pub prot: RemoteProt,
+}
+
+/// 远程内存分配记录
+#[derive(Default)]
+pub struct RemoteAllocator {
+    /// 分配所属的进程
+    pid: i32,
+    /// 起始地址 -> 分配
+    allocations: BTreeMap<u64, RemoteAllocation>,
+}
+
+fn page_align(size: usize) -> usize {
+    let page_size = *crate::core::globals::PAGE_SIZE;
+    size.div_ceil(page_size) * page_size
+}
+
+fn write_chunked(driver: &DriverManager, address: u64, bytes: &[u8]) -> Result<()> {
+    for (index, chunk) in bytes.chunks(WRITE_CHUNK).enumerate() {
+        driver.write_memory_unified(address + (index * WRITE_CHUNK) as u64, chunk)?;
+    }
+    Ok(())
+}
+
+impl RemoteAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 检查绑定状态；进程已切换时旧记录作废（映射随旧进程留存，已无法释放）
+    fn bound<'a>(&mut self, driver: &'a DriverManager) -> Result<&'a BindProc> {
+        let bind_proc = driver.get_bound_process().filter(|_| driver.is_process_bound()).ok_or_else(|| anyhow!("No process bound"))?;
+        let pid = driver.get_bound_pid();
+        if self.pid != pid {
+            if !self.allocations.is_empty() {
+                warn!("RemoteAllocator: 进程已切换，丢弃 {} 个分配记录", self.allocations.len());
+            }
+            self.allocations.clear();
+            self.pid = pid;
+        }
+        Ok(bind_proc)
+    }
+
+    /// 包含 [address, address + len) 的分配
+    fn containing(&self, address: u64, len: usize) -> Option<&RemoteAllocation> {
+        let (_, allocation) = self.allocations.range(..=address).next_back()?;
+        let end = address.checked_add(len as u64)?;
+        (end <= allocation.address + allocation.size as u64).then_some(allocation)
+    }
+
+    /// 分配至少 `size` 字节，返回起始地址
+    pub fn allocate(&mut self, driver: &DriverManager, size: usize, prot: RemoteProt) -> Result<u64> {
+        if size == 0 {
+            return Err(anyhow!("Empty allocation"));
+        }
+
+        let size = page_align(size);
+        let address = self.bound(driver)?.remote_mmap(0, size, prot.flags())? as u64;
+        debug!("RemoteAllocator: 分配 0x{:X}+0x{:X} ({:?})", address, size, prot);
+        self.allocations.insert(address, RemoteAllocation { address, size, prot });
+        Ok(address)
+    }
+
+    /// 分配可执行内存并写入 `payload`，返回起始地址
+    ///
+    /// `rwx` 为 false 时先以 RW 映射写入，再改为 RX。任一步失败都会释放已分配的内存。
+    pub fn load_payload(&mut self, driver: &DriverManager, payload: &[u8], rwx: bool) -> Result<u64> {
+        let prot = if rwx { RemoteProt::ReadWriteExec } else { RemoteProt::ReadWrite };
+        let address = self.allocate(driver, payload.len(), prot)?;
+
+        let loaded = write_chunked(driver, address, payload).and_then(|_| if rwx { Ok(()) } else { self.protect(driver, address, RemoteProt::ReadExec) });
+        if let Err(e) = loaded {
+            if let Err(free_err) = self.free(driver, address) {
+                warn!("RemoteAllocator: 释放 0x{:X} 失败: {}", address, free_err);
+            }
+            return Err(e);
+        }
+        Ok(address)
+    }
+
+    /// 向分配内写入数据，只读可执行的页会临时改为可写
+    pub fn write(&mut self, driver: &DriverManager, address: u64, bytes: &[u8]) -> Result<()> {
+        let bind_proc = self.bound(driver)?;
+        let allocation = self
+            .containing(address, bytes.len())
+            .ok_or_else(|| anyhow!("0x{:X}+{} is not inside a remote allocation", address, bytes.len()))?
+            .clone();
+
+        if allocation.prot.writable() {
+            return write_chunked(driver, address, bytes);
+        }
+
+        bind_proc.remote_mprotect(allocation.address as usize, allocation.size, RemoteProt::ReadWrite.flags())?;
+        let written = write_chunked(driver, address, bytes);
+        bind_proc.remote_mprotect(allocation.address as usize, allocation.size, allocation.prot.flags())?;
+        written
+    }
+
+    /// 修改整块分配的权限
+    pub fn protect(&mut self, driver: &DriverManager, address: u64, prot: RemoteProt) -> Result<()> {
+        let bind_proc = self.bound(driver)?;
+        let allocation = self.allocations.get_mut(&address).ok_or_else(|| anyhow!("No remote allocation at 0x{:X}", address))?;
+        bind_proc.remote_mprotect(address as usize, allocation.size, prot.flags())?;
+        allocation.prot = prot;
+        Ok(())
+    }
+
+    /// 释放分配，未找到时返回 false
+    pub fn free(&mut self, driver: &DriverManager, address: u64) -> Result<bool> {
+        let bind_proc = self.bound(driver)?;
+        let Some(allocation) = self.allocations.get(&address) else {
+            return Ok(false);
+        };
+
+        bind_proc.remote_munmap(address as usize, allocation.size)?;
+        self.allocations.remove(&address);
+        Ok(true)
+    }
+
+    /// 释放全部分配，返回成功释放的数量
+    ///
+    /// 须在解绑或切换进程之前调用，此时 BindProc 仍指向分配所属的进程。
+    pub fn free_all(&mut self, driver: &DriverManager) -> usize {
+        if self.allocations.is_empty() {
+            return 0;
+        }
+
+        let addresses: Vec<u64> = self.allocations.keys().copied().collect();
+        let mut freed = 0;
+        for address in addresses {
+            match self.free(driver, address) {
+                Ok(_) => freed += 1,
+                Err(e) => warn!("RemoteAllocator: 释放 0x{:X} 失败: {}", address, e),
+            }
+        }
+        // 释放失败的映射无法再追踪
+        self.allocations.clear();
+        freed
+    }
+
+    /// 丢弃全部记录但不释放映射，用于进程已不可访问或仍有代码跳入分配时
+    pub fn forget_all(&mut self) {
+        self.allocations.clear();
+    }
+
+    /// 全部分配的 [起始, 结束) 范围
+    pub fn ranges(&self) -> Vec<(u64, u64)> {
+        self.allocations.values().map(|allocation| (allocation.address, allocation.address + allocation.size as u64)).collect()
+    }
+
+    /// 全部分配，按地址排序
+    pub fn list(&self) -> Vec<RemoteAllocation> {
+        self.allocations.values().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.allocations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allocations.is_empty()
+    }
+}
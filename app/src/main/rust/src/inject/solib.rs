@@ -0,0 +1,193 @@
+//! 共享库注入
+//!
+//! 借助驱动的远程调用让目标进程自己执行 `dlopen`：库路径写入一块临时远程分配，
+//! 取得句柄后从内存映射中找出库的基址。卸载同样通过远程 `dlclose` 完成。
+//!
+//! `dlopen`/`dlclose`/`dlerror` 的远程地址由本进程中同一系统库的地址换算得到：
+//! 两个进程都由 zygote 派生，映射的是同一个 libdl.so。
+//!
+//! 库文件须能被目标进程读取（SELinux 上下文和文件权限），否则 `dlopen` 失败，
+//! 错误信息取自目标进程的 `dlerror()`。
+
+use super::remote_alloc::{RemoteAllocator, RemoteProt};
+use crate::core::driver_manager::DriverManager;
+use crate::core::region_type::query_mem_regions;
+use crate::wuwa::BindProc;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use nix::libc::{self, c_int, RTLD_NOW};
+use std::ffi::{CStr, CString};
+
+/// 远程调用等待时间，`dlopen` 会执行库的构造函数，可能较慢
+const REMOTE_CALL_TIMEOUT_MS: u32 = 5000;
+
+/// `dlerror` 信息的最大读取长度
+const MAX_ERROR_LEN: usize = 512;
+
+/// 已注入的库
+#[derive(Debug, Clone)]
+pub struct InjectedLibrary {
+    pub path: String,
+    /// `dlopen` 返回的句柄
+    pub handle: u64,
+    /// 最低映射地址，找不到映射时为 0
+    pub base: u64,
+}
+
+/// 共享库注入记录
+#[derive(Default)]
+pub struct LibraryInjector {
+    /// 注入所属的进程
+    pid: i32,
+    libraries: Vec<InjectedLibrary>,
+}
+
+/// 本进程中 `symbol` 的地址及其所在库的路径和基址
+fn local_symbol(symbol: &str) -> Result<(u64, String, u64)> {
+    let name = CString::new(symbol)?;
+    let address = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+    if address.is_null() {
+        return Err(anyhow!("Symbol {} not found locally", symbol));
+    }
+
+    let mut info: libc::Dl_info = unsafe { std::mem::zeroed() };
+    if unsafe { libc::dladdr(address, &mut info) } == 0 || info.dli_fname.is_null() {
+        return Err(anyhow!("Unable to locate the library containing {}", symbol));
+    }
+    let path = unsafe { CStr::from_ptr(info.dli_fname) }.to_string_lossy().into_owned();
+    Ok((address as u64, path, info.dli_fbase as u64))
+}
+
+/// 绑定进程中名为 `path` 的映射的最低地址
+///
+/// 映射名是内核看到的真实路径，与传入路径不一致（如经过符号链接）时按文件名匹配。
+fn remote_base(driver: &DriverManager, path: &str) -> Result<Option<u64>> {
+    let regions = query_mem_regions(driver, driver.get_bound_pid())?;
+    let exact = regions.iter().filter(|region| region.name == path).map(|region| region.start).min();
+    if exact.is_some() {
+        return Ok(exact);
+    }
+
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    Ok(regions
+        .iter()
+        .filter(|region| region.name.rsplit('/').next() == Some(file_name))
+        .map(|region| region.start)
+        .min())
+}
+
+/// 把本进程中系统库符号的地址换算到绑定进程
+fn remote_symbol(driver: &DriverManager, symbol: &str) -> Result<u64> {
+    let (local, path, local_base) = local_symbol(symbol)?;
+    let base = remote_base(driver, &path)?.ok_or_else(|| anyhow!("{} is not mapped in the target", path))?;
+    Ok(base + (local - local_base))
+}
+
+/// 读取绑定进程中以 NUL 结尾的字符串
+fn read_remote_string(driver: &DriverManager, address: u64) -> Option<String> {
+    let mut buf = vec![0u8; MAX_ERROR_LEN];
+    // 字符串可能紧挨着页尾，读取失败时缩短再试
+    let mut len = MAX_ERROR_LEN;
+    while driver.read_memory_unified(address, &mut buf[..len], None).is_err() {
+        len /= 2;
+        if len < 16 {
+            return None;
+        }
+    }
+    let end = buf[..len].iter().position(|&b| b == 0).unwrap_or(len);
+    Some(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+fn bound_process(driver: &DriverManager) -> Result<&BindProc> {
+    driver.get_bound_process().filter(|_| driver.is_process_bound()).ok_or_else(|| anyhow!("No process bound"))
+}
+
+impl LibraryInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 进程已切换时丢弃旧记录
+    fn sync_pid(&mut self, driver: &DriverManager) {
+        let pid = driver.get_bound_pid();
+        if self.pid != pid {
+            self.libraries.clear();
+            self.pid = pid;
+        }
+    }
+
+    /// 在绑定进程中加载 `path`，返回注入记录
+    ///
+    /// 同一个库重复注入时 `dlopen` 返回同一句柄，只增加引用计数，不重复登记。
+    pub fn inject(&mut self, driver: &DriverManager, allocator: &mut RemoteAllocator, path: &str) -> Result<InjectedLibrary> {
+        if !path.starts_with('/') {
+            return Err(anyhow!("Library path must be absolute: {}", path));
+        }
+        let c_path = CString::new(path)?;
+        let bind_proc = bound_process(driver)?;
+        self.sync_pid(driver);
+
+        let dlopen = remote_symbol(driver, "dlopen")?;
+        let path_buf = allocator.allocate(driver, c_path.as_bytes_with_nul().len(), RemoteProt::ReadWrite)?;
+        let handle = allocator
+            .write(driver, path_buf, c_path.as_bytes_with_nul())
+            .and_then(|_| bind_proc.remote_call(dlopen as usize, &[path_buf, RTLD_NOW as u64], REMOTE_CALL_TIMEOUT_MS));
+        if let Err(e) = allocator.free(driver, path_buf) {
+            warn!("LibraryInjector: 释放路径缓冲区失败: {}", e);
+        }
+        let handle = handle?;
+
+        if handle == 0 {
+            let reason = remote_symbol(driver, "dlerror")
+                .and_then(|dlerror| bind_proc.remote_call(dlerror as usize, &[], REMOTE_CALL_TIMEOUT_MS))
+                .ok()
+                .filter(|&message| message != 0)
+                .and_then(|message| read_remote_string(driver, message))
+                .unwrap_or_else(|| "unknown error".to_string());
+            return Err(anyhow!("dlopen {} failed: {}", path, reason));
+        }
+
+        if let Some(library) = self.libraries.iter().find(|library| library.handle == handle) {
+            return Ok(library.clone());
+        }
+
+        let base = remote_base(driver, path)?.unwrap_or(0);
+        debug!("LibraryInjector: {} 已加载，句柄 0x{:X}，基址 0x{:X}", path, handle, base);
+        let library = InjectedLibrary {
+            path: path.to_string(),
+            handle,
+            base,
+        };
+        self.libraries.push(library.clone());
+        Ok(library)
+    }
+
+    /// 通过 `dlclose` 卸载，未找到时返回 false
+    ///
+    /// 库被其他代码引用或标记为 NODELETE 时仍会留在内存中，记录照常移除。
+    pub fn unload(&mut self, driver: &DriverManager, handle: u64) -> Result<bool> {
+        let bind_proc = bound_process(driver)?;
+        self.sync_pid(driver);
+        let Some(index) = self.libraries.iter().position(|library| library.handle == handle) else {
+            return Ok(false);
+        };
+
+        let dlclose = remote_symbol(driver, "dlclose")?;
+        let result = bind_proc.remote_call(dlclose as usize, &[handle], REMOTE_CALL_TIMEOUT_MS)? as c_int;
+        if result != 0 {
+            return Err(anyhow!("dlclose 0x{:X} failed: {}", handle, result));
+        }
+        self.libraries.remove(index);
+        Ok(true)
+    }
+
+    /// 已注入的库，按注入顺序
+    pub fn list(&self) -> Vec<InjectedLibrary> {
+        self.libraries.clone()
+    }
+
+    /// 进程解绑后调用，已加载的库留在目标进程中
+    pub fn forget_all(&mut self) {
+        self.libraries.clear();
+    }
+}
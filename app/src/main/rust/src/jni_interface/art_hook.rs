@@ -0,0 +1,83 @@
+//! JNI methods for ArtHook
+
+use crate::core::art_hook::{inspect_art_method, write_branch_stub, ArtMethodLayout};
+use crate::core::globals::ART_HOOK_MANAGER;
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::JObject;
+use jni::sys::{jboolean, jint, jlong, jlongArray, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 读取 ArtMethod 字段，返回 [accessFlags, data, entryPoint]
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeInspectMethod", "(JI)[J")]
+pub fn jni_art_inspect_method(mut env: JNIEnv, _obj: JObject, method: jlong, sdk_int: jint) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let layout = ArtMethodLayout::for_sdk(sdk_int)?;
+        let info = inspect_art_method(method as u64, &layout)?;
+
+        let values = [info.access_flags as jlong, info.data as jlong, info.entry_point as jlong];
+        let array = env.new_long_array(values.len() as i32)?;
+        env.set_long_array_region(&array, 0, &values)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 在 cave 写入跳转到 target 的跳板，返回跳板地址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeWriteTrampoline", "(JJ)J")]
+pub fn jni_art_write_trampoline(mut env: JNIEnv, _obj: JObject, cave: jlong, target: jlong) -> jlong {
+    (|| -> JniResult<jlong> { Ok(write_branch_stub(cave as u64, target as u64)? as jlong) })().or_throw(&mut env)
+}
+
+/// 替换方法入口，返回原始入口点
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeInstallHook", "(JJI)J")]
+pub fn jni_art_install_hook(mut env: JNIEnv, _obj: JObject, method: jlong, trampoline: jlong, sdk_int: jint) -> jlong {
+    (|| -> JniResult<jlong> {
+        let mut manager = ART_HOOK_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ArtHookManager write lock"))?;
+        Ok(manager.install(method as u64, trampoline as u64, sdk_int)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 恢复方法入口
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeUninstallHook", "(J)Z")]
+pub fn jni_art_uninstall_hook(mut env: JNIEnv, _obj: JObject, method: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = ART_HOOK_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ArtHookManager write lock"))?;
+        manager.uninstall(method as u64)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 恢复所有 hook，返回恢复数量
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeUninstallAll", "()I")]
+pub fn jni_art_uninstall_all(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let mut manager = ART_HOOK_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ArtHookManager write lock"))?;
+        Ok(manager.uninstall_all() as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已 hook 的 ArtMethod 地址列表
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ArtHook", "nativeGetHookedMethods", "()[J")]
+pub fn jni_art_get_hooked_methods(mut env: JNIEnv, _obj: JObject) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let manager = ART_HOOK_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ArtHookManager read lock"))?;
+        let methods: Vec<jlong> = manager.hooked_methods().into_iter().map(|m| m as jlong).collect();
+        let array = env.new_long_array(methods.len() as i32)?;
+        env.set_long_array_region(&array, 0, &methods)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
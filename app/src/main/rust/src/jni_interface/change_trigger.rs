@@ -0,0 +1,127 @@
+//! JNI methods for ChangeTrigger
+
+use crate::core::change_trigger::{TriggerAction, TriggerConfig};
+use crate::core::globals::CHANGE_TRIGGER;
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::search::parser::parse_search_query;
+use crate::search::types::{FuzzyCondition, ValueType};
+use anyhow::anyhow;
+use jni::objects::{JObject, JString};
+use jni::sys::{jboolean, jint, jlong, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+use std::time::Duration;
+
+fn arm(address: jlong, size: jint, interval_us: jlong, action: TriggerAction, one_shot: jboolean) -> JniResult<jboolean> {
+    if size <= 0 || interval_us < 0 {
+        return Err(anyhow!("Invalid size/interval: {}/{}", size, interval_us));
+    }
+
+    let mut trigger = CHANGE_TRIGGER
+        .write()
+        .map_err(|_| anyhow!("Failed to acquire ChangeTrigger write lock"))?;
+    trigger.arm(TriggerConfig {
+        address: address as u64,
+        size: size as usize,
+        interval: Duration::from_micros(interval_us as u64),
+        action,
+        one_shot: one_shot != JNI_FALSE,
+    })?;
+    Ok(JNI_TRUE)
+}
+
+/// 值变化时发起精确改善搜索
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeArmRefine", "(JIJLjava/lang/String;IZ)Z")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_trigger_arm_refine(
+    mut env: JNIEnv,
+    _obj: JObject,
+    address: jlong,
+    size: jint,
+    interval_us: jlong,
+    query_str: JString,
+    default_type: jint,
+    one_shot: jboolean,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let query: String = env.get_string(&query_str)?.into();
+        let value_type = ValueType::from_id(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
+        let search_query = parse_search_query(&query, value_type).map_err(|e| anyhow!("Parse error: {}", e))?;
+
+        arm(address, size, interval_us, TriggerAction::Refine(search_query), one_shot)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 值变化时发起模糊改善搜索，条件参数同 `nativeStartFuzzyRefineAsync`
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeArmFuzzyRefine", "(JIJIJJZ)Z")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_trigger_arm_fuzzy_refine(
+    mut env: JNIEnv,
+    _obj: JObject,
+    address: jlong,
+    size: jint,
+    interval_us: jlong,
+    condition_id: jint,
+    param1: jlong,
+    param2: jlong,
+    one_shot: jboolean,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let condition = FuzzyCondition::from_id(condition_id, param1, param2).ok_or_else(|| anyhow!("Invalid fuzzy condition id: {}", condition_id))?;
+        if condition.is_initial() {
+            return Err(anyhow!("Cannot use Initial condition for refine search"));
+        }
+
+        arm(address, size, interval_us, TriggerAction::FuzzyRefine(condition), one_shot)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 解除触发器
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeDisarm", "()V")]
+pub fn jni_trigger_disarm(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        let mut trigger = CHANGE_TRIGGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ChangeTrigger write lock"))?;
+        trigger.disarm();
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeIsArmed", "()Z")]
+pub fn jni_trigger_is_armed(mut env: JNIEnv, _obj: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let trigger = CHANGE_TRIGGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ChangeTrigger read lock"))?;
+        Ok(if trigger.is_armed() { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已发起的搜索次数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeGetFireCount", "()J")]
+pub fn jni_trigger_get_fire_count(mut env: JNIEnv, _obj: JObject) -> jlong {
+    (|| -> JniResult<jlong> {
+        let trigger = CHANGE_TRIGGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ChangeTrigger read lock"))?;
+        Ok(trigger.fire_count() as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 因搜索进行中被跳过的变化次数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ChangeTrigger", "nativeGetSkippedCount", "()J")]
+pub fn jni_trigger_get_skipped_count(mut env: JNIEnv, _obj: JObject) -> jlong {
+    (|| -> JniResult<jlong> {
+        let trigger = CHANGE_TRIGGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ChangeTrigger read lock"))?;
+        Ok(trigger.skipped_count() as jlong)
+    })()
+    .or_throw(&mut env)
+}
@@ -0,0 +1,76 @@
+//! JNI methods for CompareSlots
+
+use crate::core::globals::COMPARE_SLOTS;
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::search::types::ValueType;
+use anyhow::anyhow;
+use jni::objects::JObject;
+use jni::sys::{jboolean, jint, jlong, jlongArray, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 每个槽位在刷新结果中占用的 long 数：[slot, address, valueType, value, state]
+const SNAPSHOT_STRIDE: usize = 5;
+
+/// 钉住地址，返回槽位
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/CompareSlots", "nativePin", "(JI)I")]
+pub fn jni_compare_pin(mut env: JNIEnv, _obj: JObject, address: jlong, value_type: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let value_type = ValueType::from_id(value_type).ok_or_else(|| anyhow!("Invalid value type: {}", value_type))?;
+        let mut slots = COMPARE_SLOTS
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire CompareSlots write lock"))?;
+        Ok(slots.pin(address as u64, value_type)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 释放槽位
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/CompareSlots", "nativeUnpin", "(I)Z")]
+pub fn jni_compare_unpin(mut env: JNIEnv, _obj: JObject, slot: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut slots = COMPARE_SLOTS
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire CompareSlots write lock"))?;
+        Ok(if slots.unpin(slot as usize) { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 清空所有槽位
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/CompareSlots", "nativeClear", "()V")]
+pub fn jni_compare_clear(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        let mut slots = COMPARE_SLOTS
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire CompareSlots write lock"))?;
+        slots.clear();
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 刷新所有槽位并与参考槽比较，每个槽位依次为 [slot, address, valueType, value, state]
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/CompareSlots", "nativeRefresh", "(I)[J")]
+pub fn jni_compare_refresh(mut env: JNIEnv, _obj: JObject, reference: jint) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let slots = COMPARE_SLOTS
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire CompareSlots read lock"))?;
+        let snapshots = slots.refresh(reference as usize)?;
+
+        let mut values = Vec::with_capacity(snapshots.len() * SNAPSHOT_STRIDE);
+        for snapshot in snapshots {
+            values.push(snapshot.slot as jlong);
+            values.push(snapshot.address as jlong);
+            values.push(snapshot.value_type.to_id() as jlong);
+            values.push(snapshot.value.map(i64::from_le_bytes).unwrap_or(0));
+            values.push(snapshot.state as jlong);
+        }
+
+        let array = env.new_long_array(values.len() as i32)?;
+        env.set_long_array_region(&array, 0, &values)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
@@ -1,13 +1,16 @@
 //! JNI methods for Disassembler
 
 use anyhow::anyhow;
-use crate::disasm::{Architecture, disassemble, disassemble_with_pseudo};
+use crate::core::error::MamuError;
+use crate::core::DRIVER_MANAGER;
+use crate::disasm::{Architecture, assemble_arm64, disassemble, disassemble_arm64_lenient, disassemble_with_pseudo, scan_regions_for_xrefs};
 use crate::ext::jni::{JniResult, JniResultExt};
 use jni::JNIEnv;
-use jni::objects::{JByteArray, JClass, JObject, JObjectArray, JString};
-use jni::sys::{jint, jlong, jobjectArray, jsize};
+use jni::objects::{JByteArray, JClass, JLongArray, JObject, JObjectArray, JString};
+use jni::sys::{jbyteArray, jint, jlong, jobjectArray, jsize};
 use jni_macro::jni_method;
 use log::{debug, error};
+use std::collections::HashMap;
 
 /// Converts DisassemblyResult to Java object
 fn disasm_result_to_jobject<'l>(
@@ -93,6 +96,83 @@ pub fn jni_disassemble(
     .or_throw(&mut env)
 }
 
+/// Upper bound on instructions per remote disassembly request
+const MAX_REMOTE_INSTRUCTIONS: usize = 4096;
+
+/// Reads `size` bytes of code at `address` from the bound process.
+///
+/// If the whole range can't be read, reading continues page by page and stops
+/// at the first unreadable page, so a request running off the end of a mapping
+/// still returns the readable prefix.
+fn read_remote_code(address: u64, size: usize) -> JniResult<Vec<u8>> {
+    let manager = DRIVER_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() {
+        return Err(anyhow!("No process bound"));
+    }
+
+    let mut buffer = vec![0u8; size];
+    if manager.read_memory_unified(address, &mut buffer, None).is_ok() {
+        return Ok(buffer);
+    }
+
+    let page_size = *crate::core::globals::PAGE_SIZE as u64;
+    let mut read = 0usize;
+    while read < size {
+        let current = address + read as u64;
+        let chunk = ((page_size - current % page_size) as usize).min(size - read);
+        if manager.read_memory_unified(current, &mut buffer[read..read + chunk], None).is_err() {
+            break;
+        }
+        read += chunk;
+    }
+
+    if read == 0 {
+        return Err(anyhow!("Failed to read code at 0x{:x}", address));
+    }
+    buffer.truncate(read);
+    Ok(buffer)
+}
+
+/// Disassembles `count` ARM64 instructions read from the bound process.
+#[jni_method(
+    85,
+    "moe/fuqiuluo/mamu/driver/Disassembler",
+    "nativeDisassemble",
+    "(JI)[Lmoe/fuqiuluo/mamu/driver/DisassemblyResult;"
+)]
+pub fn jni_disassemble_remote(
+    mut env: JNIEnv,
+    _obj: JObject,
+    address: jlong,
+    count: jint,
+) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        if count <= 0 {
+            return Err(anyhow!("Invalid instruction count: {}", count));
+        }
+        // Instructions are 4-byte aligned
+        let address = address as u64 & !3;
+        let count = (count as usize).min(MAX_REMOTE_INSTRUCTIONS);
+        debug!("Disassemble remote: address=0x{:x}, count={}", address, count);
+
+        let code = read_remote_code(address, count * 4)?;
+        let results = disassemble_arm64_lenient(&code, address)
+            .map_err(|e| anyhow!("Disassembly failed: {}", e))?;
+
+        let result_class = env.find_class("moe/fuqiuluo/mamu/driver/DisassemblyResult")?;
+        let array = env.new_object_array(results.len() as jsize, result_class, JObject::null())?;
+        for (i, result) in results.iter().enumerate() {
+            let obj = disasm_result_to_jobject(&mut env, result)?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(
     85,
     "moe/fuqiuluo/mamu/driver/Disassembler",
@@ -140,3 +220,108 @@ pub fn jni_generate_pseudo_code(
     })()
     .or_throw(&mut env)
 }
+
+/// Scans executable regions of the bound process for ARM64 code referencing
+/// `[target_address, target_address + target_size)`.
+///
+/// Module offsets are relative to the lowest start address among the regions
+/// sharing the same name, so pass all segments of a module to get file-relative offsets.
+#[jni_method(
+    85,
+    "moe/fuqiuluo/mamu/driver/Disassembler",
+    "nativeFindCodeXrefs",
+    "([J[Ljava/lang/String;JI)[Lmoe/fuqiuluo/mamu/driver/CodeXrefResult;"
+)]
+pub fn jni_find_code_xrefs(
+    mut env: JNIEnv,
+    _obj: JObject,
+    regions: JLongArray,
+    region_names: JObjectArray,
+    target_address: jlong,
+    target_size: jint,
+) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let regions_len = env.get_array_length(&regions)? as usize;
+        let mut region_data = vec![0i64; regions_len];
+        env.get_long_array_region(&regions, 0, &mut region_data)?;
+
+        let region_count = regions_len / 2;
+        if env.get_array_length(&region_names)? as usize != region_count {
+            return Err(anyhow!("Region count mismatch"));
+        }
+
+        let mut ranges = Vec::with_capacity(region_count);
+        let mut names = Vec::with_capacity(region_count);
+        for i in 0..region_count {
+            ranges.push((region_data[i * 2] as u64, region_data[i * 2 + 1] as u64));
+            let name_obj = env.get_object_array_element(&region_names, i as jsize)?;
+            let name: String = env.get_string(&JString::from(name_obj))?.into();
+            names.push(name);
+        }
+
+        let mut module_bases: HashMap<&str, u64> = HashMap::new();
+        for (name, &(start, _)) in names.iter().zip(ranges.iter()) {
+            let base = module_bases.entry(name.as_str()).or_insert(start);
+            *base = (*base).min(start);
+        }
+
+        let target_start = target_address as u64;
+        let target_end = target_start
+            .checked_add(target_size.max(1) as u64)
+            .ok_or_else(|| anyhow!("Target range 0x{:x}+{} overflows", target_start, target_size))?;
+
+        let xrefs = {
+            let manager = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+            if !manager.is_process_bound() {
+                return Err(MamuError::not_bound().into());
+            }
+
+            scan_regions_for_xrefs(&ranges, target_start, target_end, |addr, buf| manager.read_memory_unified(addr, buf, None))
+        };
+
+        debug!("Found {} code xrefs to 0x{:x}", xrefs.len(), target_start);
+
+        let result_class = env.find_class("moe/fuqiuluo/mamu/driver/CodeXrefResult")?;
+        let array = env.new_object_array(xrefs.len() as jsize, &result_class, JObject::null())?;
+
+        for (i, xref) in xrefs.iter().enumerate() {
+            let region_idx = ranges.iter().position(|&(start, end)| xref.insn_address >= start && xref.insn_address < end);
+            let (name, offset) = match region_idx {
+                Some(idx) => (names[idx].as_str(), xref.insn_address - module_bases[names[idx].as_str()]),
+                None => ("", xref.insn_address),
+            };
+            let name_str = env.new_string(name)?;
+
+            // CodeXrefResult(address: Long, moduleName: String, moduleOffset: Long, kind: Int, refAddress: Long)
+            let obj = env.new_object(
+                &result_class,
+                "(JLjava/lang/String;JIJ)V",
+                &[
+                    (xref.insn_address as jlong).into(),
+                    (&name_str).into(),
+                    (offset as jlong).into(),
+                    (xref.kind as jint).into(),
+                    (xref.ref_address as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Assembles ARM64 source placed at `address` into machine code.
+#[jni_method(85, "moe/fuqiuluo/mamu/driver/Disassembler", "nativeAssemble", "(Ljava/lang/String;J)[B")]
+pub fn jni_assemble(mut env: JNIEnv, _obj: JObject, source: JString, address: jlong) -> jbyteArray {
+    (|| -> JniResult<jbyteArray> {
+        let source: String = env.get_string(&source)?.into();
+        let code = assemble_arm64(&source, address as u64)?;
+        Ok(env.byte_array_from_slice(&code)?.into_raw())
+    })()
+    .or_throw(&mut env)
+}
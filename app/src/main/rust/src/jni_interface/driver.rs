@@ -1,20 +1,37 @@
 //! JNI methods for WuwaDriver
 
-use crate::core::{MemoryAccessMode, DRIVER_MANAGER};
+use crate::core::globals::{ART_HOOK_MANAGER, CHANGE_TRIGGER, COMPARE_SLOTS, FREEZE_MANAGER, IL2CPP_DUMP, LIBRARY_INJECTOR, OP_QUEUE, PATCH_MANAGER, PROCESS_STATES, PROCESS_WATCHER, REGION_GROWTH, REMOTE_ALLOCATOR, SCAN_PROFILES, UNREAL_SESSION, VALUE_HISTORY, WATCH_MANAGER};
+use crate::core::error::MamuError;
+use crate::core::access_benchmark::benchmark_access_modes;
+use crate::core::app_verify::verify_host_app;
+use crate::core::backend::WUWA_BACKEND;
+use crate::core::layout_analyzer::analyze_layout;
+use crate::core::process_state;
+use crate::core::process_watcher::DEFAULT_POLL_INTERVAL;
+use crate::core::process_pause::{pause_process, paused_pid, resume_process};
+use crate::core::processes::{filter_processes, find_process_by_name, foreground_app_pid, ProcessFilter};
+use crate::core::dissect::dissect_structure;
+use crate::core::freeze_manager::FrozenState;
+use crate::core::dump::{dump_ranges, DumpSummary};
+use crate::core::modules::{enumerate_modules, resolve_symbol};
+use crate::core::so_dump::dump_module;
+use crate::core::wire::{self, ProcInfo, ProcessList, Region, RegionList};
+use crate::core::threads::{list_threads, read_registers};
+use crate::core::region_type::{process_name, query_mem_regions, MemRegion};
+use crate::core::globals::{clear_poisoned_locks, clear_rwlock_poison};
+use crate::core::{DriverManager, MemoryAccessMode, DRIVER_MANAGER};
 use crate::ext::jni::{JniResult, JniResultExt};
-use crate::wuwa::{WuWaDriver, WuwaMemRegionEntry};
+use crate::wuwa::WuWaDriver;
 use anyhow::anyhow;
-use jni::JNIEnv;
-use jni::objects::{JByteArray, JClass, JIntArray, JLongArray, JObject, JObjectArray, JString};
-use jni::sys::{JNI_FALSE, JNI_TRUE, jboolean, jint, jlong, jsize, jlongArray, jintArray, jobjectArray};
+use jni::{JNIEnv, JavaVM};
+use jni::objects::{GlobalRef, JByteArray, JClass, JIntArray, JLongArray, JObject, JObjectArray, JString, JValue};
+use jni::sys::{JNI_FALSE, JNI_TRUE, jboolean, jbyte, jdouble, jfloat, jint, jlong, jshort, jsize, jobjectArray, jstring};
 use jni_macro::jni_method;
-use log::{debug, error, info, log_enabled, Level};
-use nix::libc::close;
-use nix::sys::mman::{MapFlags, ProtFlags, mmap, munmap};
+use log::{debug, error, log_enabled, warn, Level};
 use obfstr::obfstr as s;
 use obfstr::obfstring as ss;
-use std::num::NonZeroUsize;
-use std::os::fd::BorrowedFd;
+use std::sync::RwLock;
+use std::time::Duration;
 
 mod conversions {
     use super::*;
@@ -51,14 +68,13 @@ mod conversions {
         )?)
     }
 
-    /// 将MemRegionEntry转换为JObject
+    /// 将MemRegion转换为JObject
     pub fn mem_region_to_jobject<'l>(
         env: &mut JNIEnv<'l>,
-        entry: &WuwaMemRegionEntry,
+        entry: &MemRegion,
         mem_region_class: &JClass<'l>,
     ) -> JniResult<JObject<'l>> {
-        let name = extract_cstring(&entry.name);
-        let jname = env.new_string(&name)?;
+        let jname = env.new_string(&entry.name)?;
 
         Ok(env.new_object(
             mem_region_class,
@@ -66,7 +82,7 @@ mod conversions {
             &[
                 (entry.start as jlong).into(),
                 (entry.end as jlong).into(),
-                (entry.type_ as jint).into(),
+                (entry.flags as jint).into(),
                 (&jname).into(),
             ],
         )?)
@@ -79,7 +95,7 @@ mod conversions {
 pub fn jni_set_driver_fd(mut env: JNIEnv, _obj: JObject, fd: i32) -> jboolean {
     (|| -> JniResult<jboolean> {
         if DRIVER_MANAGER.is_poisoned() {
-            return Err(anyhow!("DriverManager is poisoned"));
+            return Err(anyhow!("DriverManager is poisoned, reset the driver state first"));
         }
 
         let mut manager = DRIVER_MANAGER
@@ -91,21 +107,8 @@ pub fn jni_set_driver_fd(mut env: JNIEnv, _obj: JObject, fd: i32) -> jboolean {
             debug!("{}: {}, {}", s!("设置驱动文件描述符"), fd, s!("驱动已初始化"));
         }
 
-        if let Some(driver) = manager.get_driver() {
-            let Ok(proc_info) = (unsafe { driver.get_process_info(nix::libc::getpid()) }) else {
-                return Err(anyhow!("Failed to get process info"));
-            };
-            let split_index = proc_info
-                .name
-                .iter()
-                .position(|&c| c == 0)
-                .unwrap_or(proc_info.name.len());
-            let cmdline = String::from_utf8(proc_info.name[0..split_index].to_vec()).unwrap_or_default();
-            if !cmdline.contains(s!("fuqiuluo")) {
-                return Err(anyhow!("Current process name verification failed"));
-            }
-
-            debug!("{}: {}", s!("驱动初始化成功，当前进程名称"), cmdline);
+        if let Some(driver) = manager.find_backend(WUWA_BACKEND).and_then(|backend| backend.as_wuwa()) {
+            verify_host_app(driver)?;
         } else {
             return Err(anyhow!("Failed to initialize driver"));
         }
@@ -132,7 +135,7 @@ pub fn jni_is_loaded(_env: JNIEnv, _obj: JObject) -> jboolean {
 pub fn jni_set_memory_access_mode(mut env: JNIEnv, _obj: JObject, mode_id: i32) {
     (|| -> JniResult<()> {
         if DRIVER_MANAGER.is_poisoned() {
-            return Err(anyhow!("DriverManager is poisoned"));
+            return Err(anyhow!("DriverManager is poisoned, reset the driver state first"));
         }
         let mut manager = DRIVER_MANAGER
             .write()
@@ -146,6 +149,138 @@ pub fn jni_set_memory_access_mode(mut env: JNIEnv, _obj: JObject, mode_id: i32)
     .or_throw(&mut env)
 }
 
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeSetReadCoalesceWindow", "(I)V")]
+pub fn jni_set_read_coalesce_window(mut env: JNIEnv, _obj: JObject, window_us: jint) {
+    (|| -> JniResult<()> {
+        if window_us < 0 {
+            return Err(anyhow!("Invalid read coalesce window: {}us", window_us));
+        }
+        let mut manager = DRIVER_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        manager.set_read_coalesce_window(Duration::from_micros(window_us as u64));
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetBackends", "()[Ljava/lang/String;")]
+pub fn jni_get_backends<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let names = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?
+            .backend_names();
+
+        let array = env.new_object_array(names.len() as jsize, "java/lang/String", JObject::null())?;
+        for (i, name) in names.iter().enumerate() {
+            let name = env.new_string(name)?;
+            env.set_object_array_element(&array, i as jsize, name)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetCurrentBackend", "()Ljava/lang/String;")]
+pub fn jni_get_current_backend(mut env: JNIEnv, _obj: JObject) -> jstring {
+    (|| -> JniResult<jstring> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        Ok(env.new_string(manager.backend_name())?.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeSelectBackend", "(Ljava/lang/String;)V")]
+pub fn jni_select_backend(mut env: JNIEnv, _obj: JObject, name: JString) {
+    (|| -> JniResult<()> {
+        let name: String = env.get_string(&name)?.into();
+        let mut manager = DRIVER_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        manager.select_backend(&name)?;
+        debug!("{}: {}", s!("切换内存后端"), name);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 以 `name` 登记一个兼容 WuWa 协议的驱动 fd，不会自动选中
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeRegisterDriverBackend", "(Ljava/lang/String;I)V")]
+pub fn jni_register_driver_backend(mut env: JNIEnv, _obj: JObject, name: JString, fd: jint) {
+    (|| -> JniResult<()> {
+        let name: String = env.get_string(&name)?.into();
+        if name.is_empty() {
+            return Err(anyhow!("Backend name must not be empty"));
+        }
+        let mut manager = DRIVER_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        manager.register_backend(&name, Box::new(WuWaDriver::from_fd(fd)))?;
+        debug!("{}: {}, fd={}", s!("登记内存后端"), name, fd);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 当前后端的版本和功能位，`backend` 为后端名
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetDriverCapabilities", "()Lmoe/fuqiuluo/mamu/driver/DriverCapabilities;")]
+pub fn jni_get_driver_capabilities<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let capabilities = manager.backend().capabilities();
+
+        let backend = env.new_string(manager.backend_name())?;
+        let version_name = env.new_string(capabilities.version_name())?;
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/DriverCapabilities",
+            "(Ljava/lang/String;ILjava/lang/String;J)V",
+            &[
+                (&backend).into(),
+                (capabilities.version as jint).into(),
+                (&version_name).into(),
+                (capabilities.features as jlong).into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 测试各访问模式的速度，`flags` 含 1 时切换到最快的模式
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeBenchmarkAccessModes", "(I)[Lmoe/fuqiuluo/mamu/driver/AccessModeBenchmark;")]
+pub fn jni_benchmark_access_modes<'l>(mut env: JNIEnv<'l>, _obj: JObject, flags: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let results = {
+            let mut manager = DRIVER_MANAGER
+                .write()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+            benchmark_access_modes(&mut manager, flags & 1 != 0)?
+        };
+
+        let result_class = env.find_class("moe/fuqiuluo/mamu/driver/AccessModeBenchmark")?;
+        let array = env.new_object_array(results.len() as jsize, &result_class, JObject::null())?;
+        for (i, result) in results.iter().enumerate() {
+            let obj = env.new_object(
+                &result_class,
+                "(IZDJ)V",
+                &[
+                    result.mode.id().into(),
+                    (result.available as jboolean).into(),
+                    result.throughput.into(),
+                    (result.latency_ns as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
 // Process management JNI methods
 
 #[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeIsProcessAlive", "(I)Z")]
@@ -154,12 +289,8 @@ pub fn jni_is_proc_alive(mut env: JNIEnv, _obj: JObject, pid: jint) -> jboolean
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
-        if let Some(driver) = manager.get_driver() {
-            if let Ok(alive) = driver.is_process_alive(pid) {
-                if alive {
-                    return Ok(JNI_TRUE);
-                }
-            }
+        if manager.backend().is_process_alive(pid) {
+            return Ok(JNI_TRUE);
         }
         Ok(JNI_FALSE)
     })()
@@ -172,10 +303,7 @@ pub fn jni_get_proc_list<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JIntArray<'l
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
-        let driver = manager.get_driver()
-            .ok_or_else(|| anyhow!("Driver is not initialized"))?;
-
-        let proc_list = driver.list_processes();
+        let proc_list = manager.backend().list_processes();
         let result = env.new_int_array(proc_list.len() as jsize)
             .map_err(|_| anyhow!("Cannot create process list result array"))?;
         env.set_int_array_region(&result, 0, &proc_list)?;
@@ -189,11 +317,9 @@ pub fn jni_get_proc_info<'l>(mut env: JNIEnv<'l>, _obj: JObject, pid: jint) -> J
     (|| -> JniResult<JObject<'l>> {
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
-        let driver = manager.get_driver()
-            .ok_or_else(|| anyhow!("Driver is not initialized"))?;
-
-        let proc_info = driver
-            .get_process_info(pid)
+        let proc_info = manager
+            .backend()
+            .process_info(pid)
             .map_err(|_| anyhow!("Unable to get process info for pid {}", pid))?;
 
         conversions::proc_info_to_jobject(&mut env, &proc_info)
@@ -206,16 +332,15 @@ pub fn jni_get_proc_list_with_info<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JO
     (|| -> JniResult<JObjectArray<'l>> {
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
-        let driver = manager.get_driver()
-            .ok_or_else(|| anyhow!("Driver is not initialized"))?;
+        let backend = manager.backend();
 
-        let proc_list = driver.list_processes();
+        let proc_list = backend.list_processes();
         let process_info_class = env.find_class("moe/fuqiuluo/mamu/driver/CProcInfo")?;
         let result_array = env.new_object_array(proc_list.len() as jsize, &process_info_class, JObject::null())?;
 
         for (i, &pid) in proc_list.iter().enumerate() {
-            let proc_info = driver
-                .get_process_info(pid)
+            let proc_info = backend
+                .process_info(pid)
                 .map_err(|_| anyhow!("Unable to get process info for pid {}", pid))?;
 
             let proc_info_obj = conversions::proc_info_to_jobject(&mut env, &proc_info)?;
@@ -227,23 +352,123 @@ pub fn jni_get_proc_list_with_info<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JO
     .or_throw(&mut env)
 }
 
+/// 进程列表的 protobuf 编码（`ProcessList`），取不到信息的进程被跳过
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetProcessListProto", "()[B")]
+pub fn jni_get_proc_list_proto<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        let list = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            let backend = manager.backend();
+            ProcessList {
+                processes: backend
+                    .list_processes()
+                    .into_iter()
+                    .filter_map(|pid| backend.process_info(pid).ok())
+                    .map(|info| ProcInfo::from(&info))
+                    .collect(),
+            }
+        };
+        Ok(env.byte_array_from_slice(&wire::encode(&list))?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 只返回符合条件的进程：名字包含 `name`（不区分大小写）、uid 不低于 `min_uid`，`apps_only` 时只要应用进程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetProcessListFiltered", "(Ljava/lang/String;IZ)[Lmoe/fuqiuluo/mamu/driver/CProcInfo;")]
+pub fn jni_get_proc_list_filtered<'l>(mut env: JNIEnv<'l>, _obj: JObject, name: JString, min_uid: jint, apps_only: jboolean) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let filter = ProcessFilter {
+            name: env.get_string(&name)?.into(),
+            min_uid: min_uid.max(0) as u32,
+            apps_only: apps_only != JNI_FALSE,
+        };
+        let processes = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            filter_processes(manager.backend(), &filter)
+        };
+
+        let process_info_class = env.find_class("moe/fuqiuluo/mamu/driver/CProcInfo")?;
+        let result_array = env.new_object_array(processes.len() as jsize, &process_info_class, JObject::null())?;
+        for (i, proc_info) in processes.iter().enumerate() {
+            let proc_info_obj = conversions::proc_info_to_jobject(&mut env, proc_info)?;
+            env.set_object_array_element(&result_array, i as jsize, proc_info_obj)?;
+        }
+        Ok(result_array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按进程名或包名查找进程，未找到返回 0
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeFindProcessByName", "(Ljava/lang/String;)I")]
+pub fn jni_find_proc_by_name(mut env: JNIEnv, _obj: JObject, name: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let name: String = env.get_string(&name)?.into();
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        Ok(find_process_by_name(manager.backend(), &name).unwrap_or(0))
+    })()
+    .or_throw(&mut env)
+}
+
+/// 当前前台应用的主进程，判断不出时返回 0
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetForegroundAppPid", "()I")]
+pub fn jni_get_foreground_app_pid(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        Ok(foreground_app_pid(manager.backend()).unwrap_or(0))
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeBindProcess", "(I)Z")]
 pub fn jni_bind_proc(mut env: JNIEnv, _obj: JObject, pid: jint) -> jboolean {
     (|| -> JniResult<jboolean> {
         let manager_read = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
-        let driver = manager_read.get_driver()
-            .ok_or_else(|| anyhow!("Driver is not initialized"))?;
-
-        let Ok(bind_proc) = driver.bind_process(pid) else {
+        // 用户态后端没有 BindProc，按用户态模式读写
+        let Ok(bind_proc) = manager_read.backend().bind_process(pid) else {
             return Ok(JNI_FALSE);
         };
+        let target_changed = manager_read.is_process_bound() && manager_read.get_bound_pid() != pid;
         drop(manager_read);
 
+        // 换绑其他进程时旧进程的冻结、补丁、hook 等状态都不再适用，按解绑处理；
+        // 轮询线程会读取 DriverManager，需在获取写锁前停止
+        if target_changed && let Ok(mut trigger) = CHANGE_TRIGGER.write() {
+            trigger.disarm();
+        }
+        // 旧进程的远程分配须在替换 BindProc 前释放
+        free_remote_allocations();
         let mut manager_write = DRIVER_MANAGER.write()
             .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
-        manager_write.bind_process(bind_proc, pid)?;
+        if target_changed && manager_write.get_bound_pid() != pid {
+            release_binding(&mut manager_write);
+        } else if let Ok(mut allocator) = REMOTE_ALLOCATOR.write() {
+            allocator.forget_all();
+        }
+        let bound = match bind_proc {
+            Some(bind_proc) => manager_write.bind_process(bind_proc, pid),
+            None => manager_write.bind_process_userspace(pid),
+        };
+        if let Err(e) = bound {
+            error!("{}: {}", s!("绑定进程失败"), e);
+            return Ok(JNI_FALSE);
+        }
+        // 之前附加时暂存的状态不再换入
+        if let Ok(mut states) = PROCESS_STATES.write() {
+            states.discard(pid);
+        }
 
+        // 自动加载该包的扫描配置
+        let name = process_name(&manager_write, pid);
+        if let Ok(mut profiles) = SCAN_PROFILES.write() {
+            profiles.activate(&name);
+        }
+
+        ensure_process_watcher();
         debug!("{}: {}", s!("绑定进程成功，PID"), pid);
         Ok(JNI_TRUE)
     })()
@@ -272,119 +497,567 @@ pub fn jni_is_proc_bound(_env: JNIEnv, _obj: JObject) -> jboolean {
     }
 }
 
+/// 暂停绑定进程的全部线程，返回暂停方式（0 cgroup 冻结器，1 SIGSTOP）
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativePauseProcess", "()I")]
+pub fn jni_pause_process(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let pid = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            if !manager.is_process_bound() {
+                return Err(MamuError::not_bound().into());
+            }
+            manager.get_bound_pid()
+        };
+        Ok(pause_process(pid)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 恢复暂停的进程，没有暂停的进程时返回 false
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeResumeProcess", "()Z")]
+pub fn jni_resume_process(mut env: JNIEnv, _obj: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> { Ok(resume_process()? as jboolean) })().or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeIsProcessPaused", "()Z")]
+pub fn jni_is_process_paused(_env: JNIEnv, _obj: JObject) -> jboolean {
+    paused_pid().is_some() as jboolean
+}
+
+/// 恢复跳入远程分配的补丁和 hook，再释放当前进程的全部远程分配
+///
+/// 有补丁或 hook 恢复失败时保留全部分配，目标进程仍可能跳进去。
+/// 恢复时会获取 DriverManager 读锁，须在获取写锁之前调用。
+fn free_remote_allocations() {
+    let ranges = match REMOTE_ALLOCATOR.read() {
+        Ok(allocator) => allocator.ranges(),
+        Err(_) => return,
+    };
+    if ranges.is_empty() {
+        return;
+    }
+
+    let mut restored = true;
+    match PATCH_MANAGER.write() {
+        Ok(mut patches) => {
+            for address in patches.branching_into(&ranges) {
+                if let Err(e) = patches.restore(address) {
+                    warn!("Failed to restore patch at 0x{:X} before freeing remote memory: {:#}", address, e);
+                    restored = false;
+                }
+            }
+        },
+        Err(_) => restored = false,
+    }
+    match ART_HOOK_MANAGER.write() {
+        Ok(mut hooks) => {
+            for method in hooks.hooks_into(&ranges) {
+                if let Err(e) = hooks.uninstall(method) {
+                    warn!("Failed to uninstall hook 0x{:X} before freeing remote memory: {:#}", method, e);
+                    restored = false;
+                }
+            }
+        },
+        Err(_) => restored = false,
+    }
+    if !restored {
+        warn!("{}: {}", s!("仍有代码跳入远程分配，保留映射"), ranges.len());
+        return;
+    }
+
+    let Ok(manager) = DRIVER_MANAGER.read() else {
+        return;
+    };
+    if let Ok(mut allocator) = REMOTE_ALLOCATOR.write() {
+        allocator.free_all(&manager);
+    }
+}
+
+/// 解绑进程并清掉与之相关的全部状态，调用方需已停止变化触发器的轮询
+///
+/// 能安全释放的远程分配应已由 [`free_remote_allocations`] 释放，剩下的留在目标进程中。
+fn release_binding(manager: &mut DriverManager) {
+    if let Ok(mut allocator) = REMOTE_ALLOCATOR.write() {
+        allocator.forget_all();
+    }
+    // 不留下一直暂停的进程
+    if paused_pid() == Some(manager.get_bound_pid())
+        && let Err(e) = resume_process()
+    {
+        warn!("Failed to resume paused process on unbind: {:#}", e);
+    }
+    manager.unbind_process();
+    // 冻结条目和补丁范围属于旧进程，不能留给之后绑定的进程
+    if let Ok(freeze) = FREEZE_MANAGER.read() {
+        freeze.swap_state(FrozenState::default());
+    }
+    // 附加的进程随之解除，暂存的状态一并丢弃
+    if let Ok(mut states) = PROCESS_STATES.write() {
+        states.clear();
+    }
+    // 进程已不可写，hook 记录失效
+    if let Ok(mut hooks) = ART_HOOK_MANAGER.write() {
+        hooks.forget_all();
+    }
+    if let Ok(mut patches) = PATCH_MANAGER.write() {
+        patches.forget_all();
+    }
+    if let Ok(mut watches) = WATCH_MANAGER.write() {
+        watches.forget_all();
+    }
+    if let Ok(mut injector) = LIBRARY_INJECTOR.write() {
+        injector.forget_all();
+    }
+    if let Ok(mut dump) = IL2CPP_DUMP.write() {
+        *dump = None;
+    }
+    if let Ok(mut session) = UNREAL_SESSION.write() {
+        *session = None;
+    }
+    if let Ok(mut slots) = COMPARE_SLOTS.write() {
+        slots.clear();
+    }
+    if let Ok(mut growth) = REGION_GROWTH.write() {
+        growth.reset();
+    }
+    if let Ok(mut profiles) = SCAN_PROFILES.write() {
+        profiles.deactivate();
+    }
+    // 记录的地址属于旧进程
+    let _ = VALUE_HISTORY.clear();
+}
+
+/// Java 注册的进程退出回调
+struct ProcessExitCallback {
+    vm: JavaVM,
+    callback: GlobalRef,
+}
+
+static PROCESS_EXIT_CALLBACK: RwLock<Option<ProcessExitCallback>> = RwLock::new(None);
+
+/// 绑定的进程退出时由 [`PROCESS_WATCHER`] 调用：当前进程释放全部绑定状态，附加的进程直接解除，再通知 Java
+fn handle_process_exit(pid: i32) {
+    let is_current = DRIVER_MANAGER
+        .read()
+        .is_ok_and(|manager| manager.is_process_bound() && manager.get_bound_pid() == pid);
+    if is_current && let Ok(mut trigger) = CHANGE_TRIGGER.write() {
+        trigger.disarm();
+    }
+    {
+        let Ok(mut manager) = DRIVER_MANAGER.write() else {
+            return;
+        };
+        if is_current && manager.get_bound_pid() == pid {
+            release_binding(&mut manager);
+        } else {
+            if let Ok(mut states) = PROCESS_STATES.write() {
+                states.discard(pid);
+            }
+            manager.detach_process(pid);
+        }
+    }
+    warn!("{}: {}", s!("绑定的进程已退出，已释放绑定"), pid);
+
+    let Ok(callback) = PROCESS_EXIT_CALLBACK.read() else {
+        return;
+    };
+    if let Some(callback) = callback.as_ref()
+        && let Ok(mut env) = callback.vm.attach_current_thread()
+    {
+        let result = env.call_method(
+            &callback.callback,
+            "onProcessExit",
+            "(IZ)V",
+            &[JValue::Int(pid), JValue::Bool(is_current as jboolean)],
+        );
+        if let Err(e) = result {
+            error!("Failed to call onProcessExit: {:?}", e);
+        }
+    }
+}
+
+/// 绑定成功后确保退出检测在运行
+fn ensure_process_watcher() {
+    if let Ok(mut watcher) = PROCESS_WATCHER.write()
+        && let Err(e) = watcher.start(DEFAULT_POLL_INTERVAL, handle_process_exit)
+    {
+        error!("{}: {}", s!("启动进程退出检测失败"), e);
+    }
+}
+
+/// 设置进程退出回调，传 null 取消。回调在后台线程调用，此时绑定已释放
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeSetProcessExitCallback", "(Lmoe/fuqiuluo/mamu/driver/ProcessExitCallback;)V")]
+pub fn jni_set_process_exit_callback(mut env: JNIEnv, _obj: JObject, callback: JObject) {
+    (|| -> JniResult<()> {
+        let callback = if callback.is_null() {
+            None
+        } else {
+            Some(ProcessExitCallback {
+                vm: env.get_java_vm()?,
+                callback: env.new_global_ref(callback)?,
+            })
+        };
+        *PROCESS_EXIT_CALLBACK
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire process exit callback lock"))? = callback;
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeUnbindProcess", "()Z")]
 pub fn jni_unbind_proc(mut env: JNIEnv, _obj: JObject) -> jboolean {
     (|| -> JniResult<jboolean> {
+        // 轮询线程会读取 DriverManager，需在获取写锁前停止
+        if let Ok(mut trigger) = CHANGE_TRIGGER.write() {
+            trigger.disarm();
+        }
+        free_remote_allocations();
         let mut manager = DRIVER_MANAGER.write()
             .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
-        manager.unbind_process();
+        release_binding(&mut manager);
         debug!("{}", s!("释放进程绑定成功"));
         Ok(JNI_TRUE)
     })()
     .or_throw(&mut env)
 }
 
-#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeQueryMemRegions", "(I)[Lmoe/fuqiuluo/mamu/driver/MemRegionEntry;")]
-pub fn jni_query_mem_regions<'l>(
-    mut env: JNIEnv<'l>,
-    _obj: JObject,
+/// 在当前进程之外再绑定一个进程，可按 pid 读写或切换为当前进程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeAttachProcess", "(I)Z")]
+pub fn jni_attach_proc(mut env: JNIEnv, _obj: JObject, pid: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager_read = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let Ok(bind_proc) = manager_read.backend().bind_process(pid) else {
+            return Ok(JNI_FALSE);
+        };
+        drop(manager_read);
+
+        let mut manager = DRIVER_MANAGER.write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        if let Err(e) = manager.attach_process(pid, bind_proc) {
+            error!("{}: {}", s!("附加进程失败"), e);
+            return Ok(JNI_FALSE);
+        }
+        ensure_process_watcher();
+        debug!("{}: {}", s!("附加进程成功，PID"), pid);
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeDetachProcess", "(I)Z")]
+pub fn jni_detach_proc(mut env: JNIEnv, _obj: JObject, pid: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = DRIVER_MANAGER.write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        if let Ok(mut states) = PROCESS_STATES.write() {
+            states.discard(pid);
+        }
+        Ok(if manager.detach_process(pid) { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 把附加的进程切换为当前进程，原当前进程转为附加，两者的冻结、补丁、搜索结果等状态各自保留
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeSelectProcess", "(I)V")]
+pub fn jni_select_proc(mut env: JNIEnv, _obj: JObject, pid: jint) {
+    (|| -> JniResult<()> {
+        if let Ok(mut trigger) = CHANGE_TRIGGER.write() {
+            trigger.disarm();
+        }
+        // 冻结、补丁、搜索结果等状态随进程存下和换入
+        process_state::select_process(pid)?;
+
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let name = process_name(&manager, pid);
+        if let Ok(mut profiles) = SCAN_PROFILES.write() {
+            profiles.activate(&name);
+        }
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 全部已绑定的进程，当前进程在前
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetAttachedProcesses", "()[I")]
+pub fn jni_get_attached_procs<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JIntArray<'l> {
+    (|| -> JniResult<JIntArray<'l>> {
+        let pids = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?
+            .attached_pids();
+        let result = env.new_int_array(pids.len() as jsize)?;
+        env.set_int_array_region(&result, 0, &pids)?;
+        Ok(result)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 查询内存区域并转换为 MemRegionEntry 数组，`filter` 返回 false 的区域被跳过
+fn query_mem_regions_filtered<'l>(
+    env: &mut JNIEnv<'l>,
     pid: jint,
-) -> JObjectArray<'l> {
-    (|| -> JniResult<JObjectArray<'l>> {
+    filter: impl Fn(&MemRegion, &str) -> bool,
+) -> JniResult<JObjectArray<'l>> {
+    let (regions, proc_name) = {
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
         if !manager.is_process_bound() {
-            return Err(anyhow!("No process is bound. Please bind a process before querying memory regions."));
+            return Err(MamuError::not_bound().into());
         }
 
-        let driver = manager.get_driver()
-            .ok_or_else(|| anyhow!("Driver is not initialized"))?;
+        (query_mem_regions(&manager, pid)?, process_name(&manager, pid))
+    };
 
-        let result = driver
-            .query_mem_regions(pid, 0, 0)
-            .map_err(|e| anyhow!("Unable to get memory regions for pid {}: {}", pid, e))?;
+    let total = regions.len();
+    let filtered_entries: Vec<MemRegion> = regions.into_iter().filter(|r| filter(r, &proc_name)).collect();
 
-        info!(
-            "Query memory regions: fd={}, buffer_size={}, entry_count={}",
-            result.fd, result.buffer_size, result.entry_count
-        );
-
-        let borrowed_fd = unsafe { BorrowedFd::borrow_raw(result.fd) };
-
-        let mapped = unsafe {
-            mmap(
-                None,
-                NonZeroUsize::new(result.buffer_size).ok_or_else(|| anyhow!("Invalid buffer size"))?,
-                ProtFlags::PROT_READ,
-                MapFlags::MAP_PRIVATE,
-                borrowed_fd,
-                0,
-            )
-        };
+    let mem_region_class = env.find_class("moe/fuqiuluo/mamu/driver/MemRegionEntry")?;
+    let result_array = env.new_object_array(filtered_entries.len() as jsize, &mem_region_class, JObject::null())
+        .map_err(|e| anyhow!("Failed to create MemRegionEntry array: {}", e))?;
 
-        let mapped_ptr = match mapped {
-            Ok(ptr) => ptr,
+    for (i, entry) in filtered_entries.iter().enumerate() {
+        match conversions::mem_region_to_jobject(env, entry, &mem_region_class) {
+            Ok(entry_obj) => {
+                if let Err(e) = env.set_object_array_element(&result_array, i as jsize, entry_obj) {
+                    error!("Failed to set array element at index {}: {}", i, e);
+                }
+            },
             Err(e) => {
-                unsafe { close(result.fd) };
-                return Err(anyhow!("Failed to mmap memory regions buffer: {}", e));
+                error!("Failed to create MemRegionEntry object at index {}: {}", i, e);
             },
+        }
+    }
+
+    debug!("Successfully returned {} memory regions (filtered from {})", filtered_entries.len(), total);
+
+    Ok(result_array)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeQueryMemRegions", "(I)[Lmoe/fuqiuluo/mamu/driver/MemRegionEntry;")]
+pub fn jni_query_mem_regions<'l>(
+    mut env: JNIEnv<'l>,
+    _obj: JObject,
+    pid: jint,
+) -> JObjectArray<'l> {
+    query_mem_regions_filtered(&mut env, pid, |_, _| true).or_throw(&mut env)
+}
+
+/// 只返回类型在 `range_mask` 中的内存区域，第 n 位对应 ordinal 为 n 的 MemoryRange
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeQueryMemRegionsFiltered", "(IJ)[Lmoe/fuqiuluo/mamu/driver/MemRegionEntry;")]
+pub fn jni_query_mem_regions_filtered<'l>(
+    mut env: JNIEnv<'l>,
+    _obj: JObject,
+    pid: jint,
+    range_mask: jlong,
+) -> JObjectArray<'l> {
+    let mask = range_mask as u64;
+    query_mem_regions_filtered(&mut env, pid, |region, proc_name| {
+        region.classify(proc_name).is_some_and(|typ| typ.matches(mask))
+    })
+    .or_throw(&mut env)
+}
+
+/// 类型在 `range_mask` 中的内存区域的 protobuf 编码（`RegionList`），`range_mask` 为 -1 时包括无法分类的区域
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeQueryMemRegionsProto", "(IJ)[B")]
+pub fn jni_query_mem_regions_proto<'l>(mut env: JNIEnv<'l>, _obj: JObject, pid: jint, range_mask: jlong) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        let mask = range_mask as u64;
+        let list = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            if !manager.is_process_bound() {
+                return Err(MamuError::not_bound().into());
+            }
+            let proc_name = process_name(&manager, pid);
+            RegionList {
+                regions: query_mem_regions(&manager, pid)?
+                    .iter()
+                    .map(|region| Region::new(region, &proc_name))
+                    .filter(|region| mask == u64::MAX || (region.region_type >= 0 && mask & (1 << region.region_type) != 0))
+                    .collect(),
+            }
         };
+        Ok(env.byte_array_from_slice(&wire::encode(&list))?)
+    })()
+    .or_throw(&mut env)
+}
 
-        let entries = mapped_ptr.as_ptr() as *const WuwaMemRegionEntry;
+/// 已加载的 ELF 模块，需读取镜像头，只支持当前绑定的进程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetModules", "(I)[Lmoe/fuqiuluo/mamu/driver/ModuleInfo;")]
+pub fn jni_get_modules<'l>(mut env: JNIEnv<'l>, _obj: JObject, pid: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let modules = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            if manager.get_bound_pid() != pid {
+                return Err(anyhow!("Modules can only be listed for the bound process, pid {} is not bound", pid));
+            }
+            enumerate_modules(&manager)?
+        };
 
-        // 收集过滤后的内存区域
-        let mut filtered_entries = Vec::new();
-        for i in 0..result.entry_count {
-            let entry = unsafe { &*entries.add(i) };
-            filtered_entries.push(entry);
+        let module_class = env.find_class("moe/fuqiuluo/mamu/driver/ModuleInfo")?;
+        let array = env.new_object_array(modules.len() as jsize, &module_class, JObject::null())?;
+        for (i, module) in modules.iter().enumerate() {
+            let path = env.new_string(&module.path)?;
+            let obj = env.new_object(
+                &module_class,
+                "(Ljava/lang/String;JJJ)V",
+                &[
+                    (&path).into(),
+                    (module.base as jlong).into(),
+                    (module.size as jlong).into(),
+                    (module.load_bias as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
         }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
 
-        let mem_region_class = env.find_class("moe/fuqiuluo/mamu/driver/MemRegionEntry")?;
-        let result_array = env.new_object_array(filtered_entries.len() as jsize, &mem_region_class, JObject::null());
+/// 解析模块导出的符号，`module` 可以是完整路径或文件名，未找到符号时返回 0
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeResolveSymbol", "(Ljava/lang/String;Ljava/lang/String;)J")]
+pub fn jni_resolve_symbol(mut env: JNIEnv, _obj: JObject, module: JString, symbol: JString) -> jlong {
+    (|| -> JniResult<jlong> {
+        let module: String = env.get_string(&module)?.into();
+        let symbol: String = env.get_string(&symbol)?.into();
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        Ok(resolve_symbol(&manager, &module, &symbol)?.unwrap_or(0) as jlong)
+    })()
+    .or_throw(&mut env)
+}
 
-        let result_array = match result_array {
-            Ok(arr) => arr,
-            Err(e) => {
-                unsafe {
-                    let _ = munmap(mapped_ptr, result.buffer_size);
-                    close(result.fd);
-                };
-                return Err(anyhow!("Failed to create MemRegionEntry array: {}", e));
-            },
+/// 从内存转储模块，并按磁盘文件重建 ELF 头和节头
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeDumpModule", "(Ljava/lang/String;Ljava/lang/String;)Lmoe/fuqiuluo/mamu/driver/ModuleDumpResult;")]
+pub fn jni_dump_module<'l>(mut env: JNIEnv<'l>, _obj: JObject, module: JString, output: JString) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let module: String = env.get_string(&module)?.into();
+        let output: String = env.get_string(&output)?.into();
+        let dump = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            dump_module(&manager, &module, &output)?
         };
 
-        for (i, entry) in filtered_entries.iter().enumerate() {
-            match conversions::mem_region_to_jobject(&mut env, entry, &mem_region_class) {
-                Ok(entry_obj) => {
-                    if let Err(e) = env.set_object_array_element(&result_array, i as jsize, entry_obj) {
-                        error!("Failed to set array element at index {}: {}", i, e);
-                    }
-                },
-                Err(e) => {
-                    error!("Failed to create MemRegionEntry object at index {}: {}", i, e);
-                },
-            }
+        let path = env.new_string(&dump.module.path)?;
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/ModuleDumpResult",
+            "(Ljava/lang/String;JJIZI)V",
+            &[
+                (&path).into(),
+                (dump.module.base as jlong).into(),
+                (dump.file_size as jlong).into(),
+                (dump.sections as jint).into(),
+                dump.header_restored.into(),
+                (dump.unreadable_pages as jint).into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 列出绑定进程的线程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetThreads", "()[Lmoe/fuqiuluo/mamu/driver/ThreadInfo;")]
+pub fn jni_get_threads<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let threads = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            list_threads(&manager)?
+        };
+
+        let thread_class = env.find_class("moe/fuqiuluo/mamu/driver/ThreadInfo")?;
+        let array = env.new_object_array(threads.len() as jsize, &thread_class, JObject::null())?;
+        for (i, thread) in threads.iter().enumerate() {
+            let name = env.new_string(&thread.name)?;
+            let obj = env.new_object(
+                &thread_class,
+                "(ILjava/lang/String;C)V",
+                &[thread.tid.into(), (&name).into(), (thread.state as u16).into()],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 读取线程寄存器
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetThreadRegisters", "(I)Lmoe/fuqiuluo/mamu/driver/ThreadRegisters;")]
+pub fn jni_get_thread_registers<'l>(mut env: JNIEnv<'l>, _obj: JObject, tid: jint) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let registers = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            read_registers(&manager, tid)?
+        };
+
+        let regs: Vec<jlong> = registers.regs.iter().map(|&r| r as jlong).collect();
+        let regs_array = env.new_long_array(regs.len() as jsize)?;
+        env.set_long_array_region(&regs_array, 0, &regs)?;
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/ThreadRegisters",
+            "(I[JJJJ)V",
+            &[
+                registers.tid.into(),
+                (&regs_array).into(),
+                (registers.sp as jlong).into(),
+                (registers.pc as jlong).into(),
+                (registers.pstate as jlong).into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
+
+// Memory operations JNI methods
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadMemory", "(JI)[B")]
+pub fn jni_read_memory<'l>(
+    mut env: JNIEnv<'l>,
+    _obj: JObject,
+    addr: jlong,
+    size: jint,
+) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        if size <= 0 {
+            return Err(anyhow!("Invalid size: {}", size));
         }
 
-        unsafe {
-            let _ = munmap(mapped_ptr, result.buffer_size);
-            close(result.fd);
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        if !manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
         }
 
-        debug!("Successfully returned {} memory regions (filtered from {})", filtered_entries.len(), result.entry_count);
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
 
-        Ok(result_array)
+        let mut buffer = vec![0u8; size as usize];
+        manager.read_memory_unified(addr as u64, &mut buffer, None)
+            .map_err(|e| anyhow!("Failed to read memory at 0x{:x}: {}", addr, e))?;
+
+        let result = env.byte_array_from_slice(&buffer)
+            .map_err(|e| anyhow!("Failed to create byte array: {}", e))?;
+
+        Ok(result.into())
     })()
     .or_throw(&mut env)
 }
 
-// Memory operations JNI methods
-
-#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadMemory", "(JI)[B")]
-pub fn jni_read_memory<'l>(
+/// 按 pid 读取当前进程或附加的进程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadMemory", "(IJI)[B")]
+pub fn jni_read_process_memory<'l>(
     mut env: JNIEnv<'l>,
     _obj: JObject,
+    pid: jint,
     addr: jlong,
     size: jint,
 ) -> JObject<'l> {
@@ -396,13 +1069,12 @@ pub fn jni_read_memory<'l>(
         let manager = DRIVER_MANAGER.read()
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
-        if !manager.is_process_bound() {
-            return Err(anyhow!("No process is bound. Please bind a process first."));
-        }
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
 
         let mut buffer = vec![0u8; size as usize];
-        manager.read_memory_unified(addr as u64, &mut buffer, None)
-            .map_err(|e| anyhow!("Failed to read memory at 0x{:x}: {}", addr, e))?;
+        manager.read_memory_of(pid, addr as u64, &mut buffer, None)
+            .map_err(|e| anyhow!("Failed to read memory of {} at 0x{:x}: {}", pid, addr, e))?;
 
         let result = env.byte_array_from_slice(&buffer)
             .map_err(|e| anyhow!("Failed to create byte array: {}", e))?;
@@ -412,6 +1084,49 @@ pub fn jni_read_memory<'l>(
     .or_throw(&mut env)
 }
 
+/// 直接读取到 Java DirectByteBuffer 的 `[offset, offset + size)`，避免中间拷贝
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadMemoryInto", "(JLjava/nio/ByteBuffer;II)I")]
+pub fn jni_read_memory_into(
+    mut env: JNIEnv,
+    _obj: JObject,
+    addr: jlong,
+    buffer: JObject,
+    offset: jint,
+    size: jint,
+) -> jint {
+    (|| -> JniResult<jint> {
+        if offset < 0 || size <= 0 {
+            return Err(anyhow!("Invalid offset/size: {}/{}", offset, size));
+        }
+
+        let buffer = (&buffer).into();
+        let ptr = env.get_direct_buffer_address(buffer)?;
+        let capacity = env.get_direct_buffer_capacity(buffer)?;
+
+        let (offset, size) = (offset as usize, size as usize);
+        if offset + size > capacity {
+            return Err(anyhow!("Buffer too small: offset={}, size={}, capacity={}", offset, size, capacity));
+        }
+
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        if !manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
+        let target = unsafe { std::slice::from_raw_parts_mut(ptr.add(offset), size) };
+        manager.read_memory_unified(addr as u64, target, None)
+            .map_err(|e| anyhow!("Failed to read memory at 0x{:x}: {}", addr, e))?;
+
+        Ok(size as jint)
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeBatchReadMemory", "([J[I)[[B")]
 pub fn jni_batch_read_memory<'l>(
     mut env: JNIEnv<'l>,
@@ -449,26 +1164,32 @@ pub fn jni_batch_read_memory<'l>(
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
         if !manager.is_process_bound() {
-            return Err(anyhow!("No process is bound. Please bind a process first."));
+            return Err(MamuError::not_bound().into());
         }
 
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
+        // Adjacent reads are coalesced by the driver manager
+        let requests: Vec<(u64, usize)> = addresses
+            .iter()
+            .zip(&read_sizes)
+            .map(|(&addr, &size)| (addr as u64, size.max(0) as usize))
+            .collect();
+        let results = manager.read_memory_batch(&requests);
+
         // Create result 2D byte array
         let byte_array_class = env.find_class("[B")?;
         let result_array = env.new_object_array(addr_len as jsize, byte_array_class, JObject::null())?;
 
-        // Read memory for each address
-        for i in 0..addr_len {
-            let addr = addresses[i] as u64;
-            let size = read_sizes[i] as usize;
-
-            if size == 0 {
+        for (i, result) in results.into_iter().enumerate() {
+            if requests[i].1 == 0 {
                 // Set null for zero-size reads
                 continue;
             }
 
-            let mut buffer = vec![0u8; size];
-            match manager.read_memory_unified(addr, &mut buffer, None) {
-                Ok(_) => {
+            match result {
+                Ok(buffer) => {
                     let byte_array = env.byte_array_from_slice(&buffer)
                         .map_err(|e| anyhow!("Failed to create byte array for index {}: {}", i, e))?;
                     env.set_object_array_element(&result_array, i as jsize, byte_array)
@@ -476,7 +1197,7 @@ pub fn jni_batch_read_memory<'l>(
                 }
                 Err(e) => {
                     // On read failure, leave the element as null
-                    debug!("Failed to read memory at 0x{:x} (index {}): {}", addr, i, e);
+                    debug!("Failed to read memory at 0x{:x} (index {}): {}", requests[i].0, i, e);
                 }
             }
         }
@@ -505,9 +1226,12 @@ pub fn jni_write_memory(
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
         if !manager.is_process_bound() {
-            return Err(anyhow!("No process is bound. Please bind a process first."));
+            return Err(MamuError::not_bound().into());
         }
 
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
         let mut buffer = vec![0i8; len];
         env.get_byte_array_region(&data, 0, &mut buffer)
             .map_err(|e| anyhow!("Failed to get byte array region: {}", e))?;
@@ -525,6 +1249,276 @@ pub fn jni_write_memory(
     .or_throw(&mut env)
 }
 
+/// 按 pid 写入当前进程或附加的进程
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteMemory", "(IJ[B)Z")]
+pub fn jni_write_process_memory(
+    mut env: JNIEnv,
+    _obj: JObject,
+    pid: jint,
+    addr: jlong,
+    data: JByteArray,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let bytes = env.convert_byte_array(&data)
+            .map_err(|e| anyhow!("Failed to get byte array: {}", e))?;
+
+        if bytes.is_empty() {
+            return Err(anyhow!("Cannot write zero bytes"));
+        }
+
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
+        manager.write_memory_of(pid, addr as u64, &bytes)
+            .map_err(|e| anyhow!("Failed to write memory of {} at 0x{:x}: {}", pid, addr, e))?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按类型读取 N 字节，按小端解释，地址无需对齐
+fn read_typed<const N: usize>(addr: jlong) -> JniResult<[u8; N]> {
+    let addr = addr as u64;
+    if addr.checked_add(N as u64).is_none() {
+        return Err(anyhow!("Address out of range: 0x{:x}", addr));
+    }
+
+    let manager = DRIVER_MANAGER.read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+
+    // UI 读写优先于后台扫描
+    let _op = OP_QUEUE.interactive();
+
+    let mut buf = [0u8; N];
+    manager.read_memory_unified(addr, &mut buf, None)
+        .map_err(|e| anyhow!("Failed to read memory at 0x{:x}: {}", addr, e))?;
+    Ok(buf)
+}
+
+/// 按类型写入（小端字节序）
+fn write_typed(addr: jlong, bytes: &[u8]) -> JniResult<jboolean> {
+    let addr = addr as u64;
+    if addr.checked_add(bytes.len() as u64).is_none() {
+        return Err(anyhow!("Address out of range: 0x{:x}", addr));
+    }
+
+    let manager = DRIVER_MANAGER.read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+
+    // UI 读写优先于后台扫描
+    let _op = OP_QUEUE.interactive();
+
+    manager.write_memory_unified(addr, bytes)
+        .map_err(|e| anyhow!("Failed to write memory at 0x{:x}: {}", addr, e))?;
+    Ok(JNI_TRUE)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadByte", "(J)B")]
+pub fn jni_read_byte(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jbyte {
+    read_typed(addr).map(i8::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteByte", "(JB)Z")]
+pub fn jni_write_byte(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jbyte) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadShort", "(J)S")]
+pub fn jni_read_short(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jshort {
+    read_typed(addr).map(i16::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteShort", "(JS)Z")]
+pub fn jni_write_short(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jshort) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadInt", "(J)I")]
+pub fn jni_read_int(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jint {
+    read_typed(addr).map(i32::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteInt", "(JI)Z")]
+pub fn jni_write_int(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jint) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadLong", "(J)J")]
+pub fn jni_read_long(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jlong {
+    read_typed(addr).map(i64::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteLong", "(JJ)Z")]
+pub fn jni_write_long(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jlong) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadFloat", "(J)F")]
+pub fn jni_read_float(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jfloat {
+    read_typed(addr).map(f32::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteFloat", "(JF)Z")]
+pub fn jni_write_float(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jfloat) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadDouble", "(J)D")]
+pub fn jni_read_double(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jdouble {
+    read_typed(addr).map(f64::from_le_bytes).or_throw(&mut env)
+}
+
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteDouble", "(JD)Z")]
+pub fn jni_write_double(mut env: JNIEnv, _obj: JObject, addr: jlong, value: jdouble) -> jboolean {
+    write_typed(addr, &value.to_le_bytes()).or_throw(&mut env)
+}
+
+/// 强制写入只读页（.rodata 等），返回被覆盖的原始字节
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteMemoryForce", "(J[B)[B")]
+pub fn jni_write_memory_force<'l>(
+    mut env: JNIEnv<'l>,
+    _obj: JObject,
+    addr: jlong,
+    data: JByteArray,
+) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let bytes = env.convert_byte_array(&data)
+            .map_err(|e| anyhow!("Failed to get byte array: {}", e))?;
+
+        if bytes.is_empty() {
+            return Err(anyhow!("Cannot write zero bytes"));
+        }
+
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        if !manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
+        let original = manager.write_memory_force(addr as u64, &bytes)
+            .map_err(|e| anyhow!("Failed to force write memory at 0x{:x}: {}", addr, e))?;
+
+        if log_enabled!(Level::Debug) {
+            debug!("{}: 0x{:x}, size={}", s!("强制写入内存成功"), addr, bytes.len());
+        }
+
+        let result = env.byte_array_from_slice(&original)
+            .map_err(|e| anyhow!("Failed to create byte array: {}", e))?;
+        Ok(result.into())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按标志选择写入方式，见 `WRITE_FLAG_*`
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeWriteMemoryEx", "(J[BI)Z")]
+pub fn jni_write_memory_ex(
+    mut env: JNIEnv,
+    _obj: JObject,
+    addr: jlong,
+    data: JByteArray,
+    flags: jint,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let bytes = env.convert_byte_array(&data)
+            .map_err(|e| anyhow!("Failed to get byte array: {}", e))?;
+
+        if bytes.is_empty() {
+            return Err(anyhow!("Cannot write zero bytes"));
+        }
+
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        if !manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
+        manager.write_memory_ex(addr as u64, &bytes, flags as u32)
+            .map_err(|e| anyhow!("Failed to write memory at 0x{:x}: {}", addr, e))?;
+
+        if log_enabled!(Level::Debug) {
+            debug!("{}: 0x{:x}, size={}, flags={}", s!("写入内存成功"), addr, bytes.len(), flags);
+        }
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 查询地址所在映射的权限（PROT_*）
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeGetMemoryProtection", "(J)I")]
+pub fn jni_get_memory_protection(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        manager.memory_protection(addr as u64)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 绑定进程中虚拟地址对应的物理地址
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeVirtToPhys", "(J)J")]
+pub fn jni_virt_to_phys(mut env: JNIEnv, _obj: JObject, addr: jlong) -> jlong {
+    (|| -> JniResult<jlong> {
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        Ok(manager.virt_to_phys(addr as u64)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按物理地址读取，不能跨页
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeReadPhysical", "(JI)[B")]
+pub fn jni_read_physical<'l>(mut env: JNIEnv<'l>, _obj: JObject, phys_addr: jlong, size: jint) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        let data = {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            manager.read_physical(phys_addr as u64, size.max(0) as usize)?
+        };
+
+        Ok(env.byte_array_from_slice(&data)?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 修改远程页的权限（PROT_*），范围扩展到页边界
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeSetMemoryProtection", "(JJI)Z")]
+pub fn jni_set_memory_protection(mut env: JNIEnv, _obj: JObject, addr: jlong, size: jlong, prot: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = DRIVER_MANAGER.read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+
+        let previous = manager.set_memory_protection(addr as u64, size.max(0) as u64, prot)
+            .map_err(|e| anyhow!("Failed to change protection at 0x{:x}: {}", addr, e))?;
+
+        if log_enabled!(Level::Debug) {
+            debug!("{}: 0x{:x}, size={}, prot={} -> {}", s!("修改内存权限成功"), addr, size, previous, prot);
+        }
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeBatchWriteMemory", "([J[[B)[Z")]
 pub fn jni_batch_write_memory<'l>(
     mut env: JNIEnv<'l>,
@@ -557,9 +1551,12 @@ pub fn jni_batch_write_memory<'l>(
             .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
 
         if !manager.is_process_bound() {
-            return Err(anyhow!("No process is bound. Please bind a process first."));
+            return Err(MamuError::not_bound().into());
         }
 
+        // UI 读写优先于后台扫描
+        let _op = OP_QUEUE.interactive();
+
         // Create result boolean array
         let mut results = vec![0u8; addr_len];
 
@@ -615,6 +1612,139 @@ pub fn jni_batch_write_memory<'l>(
         .or_throw(&mut env)
 }
 
+fn dump_to_file(ranges: &[(u64, u64)], path: &str) -> JniResult<DumpSummary> {
+    let manager = DRIVER_MANAGER.read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+    dump_ranges(&manager, ranges, path, None)
+}
+
+/// 将一段地址范围转储到文件，没有任何可读页时返回 false
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeDumpMemory", "(JJLjava/lang/String;)Z")]
+pub fn jni_dump_memory(mut env: JNIEnv, _obj: JObject, addr: jlong, size: jlong, path: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let path: String = env.get_string(&path)?.into();
+        if size <= 0 {
+            return Err(anyhow!("Invalid dump size: {}", size));
+        }
+
+        let start = addr as u64;
+        let end = start.checked_add(size as u64).ok_or_else(|| anyhow!("Dump range overflows: 0x{:x}+{}", addr, size))?;
+        let summary = dump_to_file(&[(start, end)], &path)?;
+        Ok(if summary.readable_pages > 0 { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 将多个区域转储到同一个文件，`ranges` 为 [start0, end0, start1, end1, ...]
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeDumpRegions", "([JLjava/lang/String;)Z")]
+pub fn jni_dump_regions(mut env: JNIEnv, _obj: JObject, ranges: JLongArray, path: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let path: String = env.get_string(&path)?.into();
+        let len = env.get_array_length(&ranges)? as usize;
+        if len == 0 || !len.is_multiple_of(2) {
+            return Err(anyhow!("Region array must hold start/end pairs, got {} values", len));
+        }
+
+        let mut values = vec![0i64; len];
+        env.get_long_array_region(&ranges, 0, &mut values)?;
+        let ranges: Vec<(u64, u64)> = values.chunks_exact(2).map(|pair| (pair[0] as u64, pair[1] as u64)).collect();
+        let summary = dump_to_file(&ranges, &path)?;
+        Ok(if summary.readable_pages > 0 { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 分析内存结构，供结构热力图使用
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeAnalyzeMemoryLayout", "(JIIZ)Lmoe/fuqiuluo/mamu/driver/MemoryLayoutAnalysis;")]
+pub fn jni_analyze_memory_layout<'l>(
+    mut env: JNIEnv<'l>,
+    _obj: JObject,
+    addr: jlong,
+    size: jint,
+    max_stride: jint,
+    big_endian: jboolean,
+) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        if size <= 0 {
+            return Err(anyhow!("Invalid size: {}", size));
+        }
+
+        {
+            let manager = DRIVER_MANAGER.read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            if !manager.is_process_bound() {
+                return Err(MamuError::not_bound().into());
+            }
+        }
+
+        let analysis = analyze_layout(addr as u64, size as usize, max_stride.max(0) as usize, big_endian != JNI_FALSE)
+            .map_err(|e| anyhow!("Failed to analyze memory at 0x{:x}: {}", addr, e))?;
+
+        let cells: Vec<u8> = analysis.cells.iter().map(|c| *c as u8).collect();
+        let strides: Vec<jint> = analysis.strides.iter().map(|c| c.stride as jint).collect();
+        let scores: Vec<f32> = analysis.strides.iter().map(|c| c.score).collect();
+        let fields: Vec<u8> = analysis.field_types.iter().map(|c| *c as u8).collect();
+
+        let cells_array = env.byte_array_from_slice(&cells)?;
+        let strides_array = env.new_int_array(strides.len() as jsize)?;
+        env.set_int_array_region(&strides_array, 0, &strides)?;
+        let scores_array = env.new_float_array(scores.len() as jsize)?;
+        env.set_float_array_region(&scores_array, 0, &scores)?;
+        let fields_array = env.byte_array_from_slice(&fields)?;
+
+        let analysis_class = env.find_class("moe/fuqiuluo/mamu/driver/MemoryLayoutAnalysis")?;
+        Ok(env.new_object(
+            analysis_class,
+            "(J[B[I[F[B)V",
+            &[
+                (analysis.address as jlong).into(),
+                (&cells_array).into(),
+                (&strides_array).into(),
+                (&scores_array).into(),
+                (&fields_array).into(),
+            ],
+        )?)
+    })()
+        .or_throw(&mut env)
+}
+
+/// 逐个槽位解析一段内存的结构
+#[jni_method(80, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeDissectStructure", "(JI)[Lmoe/fuqiuluo/mamu/driver/DissectedSlot;")]
+pub fn jni_dissect_structure<'l>(mut env: JNIEnv<'l>, _obj: JObject, addr: jlong, size: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let slots = {
+            let manager = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            dissect_structure(&manager, addr as u64, size.max(0) as usize)?
+        };
+
+        let slot_class = env.find_class("moe/fuqiuluo/mamu/driver/DissectedSlot")?;
+        let array = env.new_object_array(slots.len() as jsize, &slot_class, JObject::null())?;
+        for (i, slot) in slots.iter().enumerate() {
+            let annotation = env.new_string(&slot.annotation)?;
+            let obj = env.new_object(
+                &slot_class,
+                "(IIIJJLjava/lang/String;)V",
+                &[
+                    (slot.offset as jint).into(),
+                    (slot.size as jint).into(),
+                    (slot.kind as jint).into(),
+                    (slot.value as jlong).into(),
+                    (slot.target as jlong).into(),
+                    (&annotation).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(
     90,
     "moe/fuqiuluo/mamu/driver/WuwaDriver",
@@ -644,4 +1774,29 @@ pub fn jni_allow_bind_proc<'l>(mut env: JNIEnv<'l>, _obj: JObject, package: JStr
         Ok(JNI_TRUE)
     })()
         .or_throw(&mut env)
-}
\ No newline at end of file
+}
+
+/// 清除全局锁的中毒状态并解绑进程，某次调用 panic 后不必重启应用；返回中毒的锁数
+///
+/// 只在 debug 等会展开 panic 的构建中有用，release 构建 panic 时直接 abort。
+#[jni_method(90, "moe/fuqiuluo/mamu/driver/WuwaDriver", "nativeResetDriverState", "()I")]
+pub fn jni_reset_driver_state(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let cleared = clear_poisoned_locks()
+            + clear_rwlock_poison(&PROCESS_EXIT_CALLBACK)
+            + crate::jni_interface::scan_events::clear_poisoned_lock();
+        if let Ok(mut trigger) = CHANGE_TRIGGER.write() {
+            trigger.disarm();
+        }
+        free_remote_allocations();
+        let mut manager = DRIVER_MANAGER.write()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager write lock"))?;
+        // 中毒时持锁的调用可能只改了一半，从未绑定的状态重新开始
+        release_binding(&mut manager);
+        if cleared > 0 {
+            warn!("{}: {}", s!("已清除中毒的锁"), cleared);
+        }
+        Ok(cleared as jint)
+    })()
+    .or_throw(&mut env)
+}
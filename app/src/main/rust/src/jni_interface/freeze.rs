@@ -1,7 +1,7 @@
 //! JNI methods for FreezeManager
 
 use jni::objects::{JByteArray, JObject};
-use jni::sys::{jboolean, jint, jlong, JNI_FALSE, JNI_TRUE};
+use jni::sys::{jboolean, jint, jlong, jlongArray, jsize, JNI_FALSE, JNI_TRUE};
 use jni::JNIEnv;
 use jni_macro::jni_method;
 use log::error;
@@ -36,36 +36,68 @@ pub fn jni_freeze_stop(_env: JNIEnv, _obj: JObject) {
     }
 }
 
+/// 将地址列表转换为 long[]，失败时返回 null
+fn addresses_to_jarray(env: &mut JNIEnv, addresses: &[u64]) -> jlongArray {
+    let values: Vec<jlong> = addresses.iter().map(|&a| a as jlong).collect();
+    let array = match env.new_long_array(values.len() as jsize) {
+        Ok(a) => a,
+        Err(e) => {
+            error!("FreezeManager JNI: 创建数组失败: {}", e);
+            return std::ptr::null_mut();
+        },
+    };
+    if let Err(e) = env.set_long_array_region(&array, 0, &values) {
+        error!("FreezeManager JNI: 写入数组失败: {}", e);
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
 /// 添加冻结地址
-#[jni_method(70, "moe/fuqiuluo/mamu/driver/FreezeManager", "nativeAddFrozen", "(J[BI)Z")]
-pub fn jni_freeze_add(mut env: JNIEnv, _obj: JObject, address: jlong, value: JByteArray, value_type: jint) -> jboolean {
+///
+/// 返回冲突条目的地址：空数组表示添加成功，非空表示与已有条目重叠而被拒绝，null 表示失败
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/FreezeManager", "nativeAddFrozen", "(J[BI)[J")]
+pub fn jni_freeze_add(mut env: JNIEnv, _obj: JObject, address: jlong, value: JByteArray, value_type: jint) -> jlongArray {
     let len = match env.get_array_length(&value) {
         Ok(l) => l as usize,
         Err(e) => {
             error!("FreezeManager JNI: 获取数组长度失败: {}", e);
-            return JNI_FALSE;
+            return std::ptr::null_mut();
         },
     };
 
     let mut buffer = vec![0i8; len];
     if let Err(e) = env.get_byte_array_region(&value, 0, &mut buffer) {
         error!("FreezeManager JNI: 读取字节数组失败: {}", e);
-        return JNI_FALSE;
+        return std::ptr::null_mut();
     }
 
     // 转换为 u8
     let value_bytes: Vec<u8> = buffer.iter().map(|&b| b as u8).collect();
 
-    match FREEZE_MANAGER.read() {
-        Ok(manager) => {
-            manager.add_frozen(address as u64, value_bytes, value_type);
-            JNI_TRUE
+    let conflicts = match FREEZE_MANAGER.read() {
+        Ok(manager) => manager.add_frozen(address as u64, value_bytes, value_type).err().unwrap_or_default(),
+        Err(e) => {
+            error!("FreezeManager JNI: 无法获取读锁: {}", e);
+            return std::ptr::null_mut();
         },
+    };
+
+    addresses_to_jarray(&mut env, &conflicts)
+}
+
+/// 查找与 `[address, address + size)` 重叠的已冻结条目和补丁
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/FreezeManager", "nativeFindConflicts", "(JI)[J")]
+pub fn jni_freeze_find_conflicts(mut env: JNIEnv, _obj: JObject, address: jlong, size: jint) -> jlongArray {
+    let conflicts = match FREEZE_MANAGER.read() {
+        Ok(manager) => manager.find_conflicts(address as u64, size.max(0) as usize),
         Err(e) => {
             error!("FreezeManager JNI: 无法获取读锁: {}", e);
-            JNI_FALSE
+            return std::ptr::null_mut();
         },
-    }
+    };
+
+    addresses_to_jarray(&mut env, &conflicts)
 }
 
 /// 移除冻结地址
@@ -0,0 +1,177 @@
+//! JNI methods for Il2Cpp
+
+use crate::core::globals::{DRIVER_MANAGER, IL2CPP_DUMP};
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::il2cpp::{Il2CppClass, Il2CppDump};
+use anyhow::anyhow;
+use jni::objects::{JObject, JObjectArray, JString};
+use jni::sys::{jint, jlong, jsize};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 在当前进程的分析结果上执行 `f`，未分析或进程已切换时报错
+fn with_dump<T>(f: impl FnOnce(&Il2CppDump) -> JniResult<T>) -> JniResult<T> {
+    let bound_pid = DRIVER_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?
+        .get_bound_pid();
+    let dump = IL2CPP_DUMP
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire Il2Cpp dump read lock"))?;
+    let dump = dump
+        .as_ref()
+        .filter(|dump| dump.pid == bound_pid)
+        .ok_or_else(|| anyhow!("Il2Cpp has not been analyzed for the bound process"))?;
+    f(dump)
+}
+
+fn class_at(dump: &Il2CppDump, index: jint) -> JniResult<&Il2CppClass> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| dump.classes.get(index))
+        .ok_or_else(|| anyhow!("Invalid class index: {}", index))
+}
+
+/// 分析绑定进程，返回类数量
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeAnalyze", "(Ljava/lang/String;)I")]
+pub fn jni_il2cpp_analyze(mut env: JNIEnv, _obj: JObject, module: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let module: String = env.get_string(&module)?.into();
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let dump = Il2CppDump::analyze(&manager, &module)?;
+        let count = dump.classes.len() as jint;
+        *IL2CPP_DUMP
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire Il2Cpp dump write lock"))? = Some(dump);
+        Ok(count)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按类名查找，`query` 为空时按顺序返回前 `max` 个
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeFindClasses", "(Ljava/lang/String;I)[Lmoe/fuqiuluo/mamu/driver/Il2CppClass;")]
+pub fn jni_il2cpp_find_classes<'l>(mut env: JNIEnv<'l>, _obj: JObject, query: JString, max: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let query: String = env.get_string(&query)?.into();
+        let class_class = env.find_class("moe/fuqiuluo/mamu/driver/Il2CppClass")?;
+
+        with_dump(|dump| {
+            let indices = dump.find_classes(&query, max.max(0) as usize);
+            let array = env.new_object_array(indices.len() as jsize, &class_class, JObject::null())?;
+            for (i, &index) in indices.iter().enumerate() {
+                let class = &dump.classes[index];
+                let image = env.new_string(&class.image)?;
+                let namespace = env.new_string(&class.namespace)?;
+                let name = env.new_string(&class.name)?;
+                let parent = match &class.parent {
+                    Some(parent) => JObject::from(env.new_string(parent)?),
+                    None => JObject::null(),
+                };
+                let obj = env.new_object(
+                    &class_class,
+                    "(ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;III)V",
+                    &[
+                        (index as jint).into(),
+                        (&image).into(),
+                        (&namespace).into(),
+                        (&name).into(),
+                        (&parent).into(),
+                        (class.flags as jint).into(),
+                        (class.fields.len() as jint).into(),
+                        (class.methods.len() as jint).into(),
+                    ],
+                )?;
+                env.set_object_array_element(&array, i as jsize, obj)?;
+            }
+            Ok(array)
+        })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 类的字段，偏移未知时为 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeGetFields", "(I)[Lmoe/fuqiuluo/mamu/driver/Il2CppField;")]
+pub fn jni_il2cpp_get_fields<'l>(mut env: JNIEnv<'l>, _obj: JObject, class_index: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let field_class = env.find_class("moe/fuqiuluo/mamu/driver/Il2CppField")?;
+
+        with_dump(|dump| {
+            let class = class_at(dump, class_index)?;
+            let array = env.new_object_array(class.fields.len() as jsize, &field_class, JObject::null())?;
+            for (i, field) in class.fields.iter().enumerate() {
+                let name = env.new_string(&field.name)?;
+                let type_name = env.new_string(&field.type_name)?;
+                let obj = env.new_object(
+                    &field_class,
+                    "(Ljava/lang/String;Ljava/lang/String;II)V",
+                    &[
+                        (&name).into(),
+                        (&type_name).into(),
+                        field.offset.unwrap_or(-1).into(),
+                        (field.attrs as jint).into(),
+                    ],
+                )?;
+                env.set_object_array_element(&array, i as jsize, obj)?;
+            }
+            Ok(array)
+        })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 类的方法，没有实现的方法地址和 RVA 为 0
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeGetMethods", "(I)[Lmoe/fuqiuluo/mamu/driver/Il2CppMethod;")]
+pub fn jni_il2cpp_get_methods<'l>(mut env: JNIEnv<'l>, _obj: JObject, class_index: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let method_class = env.find_class("moe/fuqiuluo/mamu/driver/Il2CppMethod")?;
+
+        with_dump(|dump| {
+            let class = class_at(dump, class_index)?;
+            let array = env.new_object_array(class.methods.len() as jsize, &method_class, JObject::null())?;
+            for (i, method) in class.methods.iter().enumerate() {
+                let name = env.new_string(&method.name)?;
+                let return_type = env.new_string(&method.return_type)?;
+                let parameters = env.new_string(method.parameter_list())?;
+                let obj = env.new_object(
+                    &method_class,
+                    "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;IJJ)V",
+                    &[
+                        (&name).into(),
+                        (&return_type).into(),
+                        (&parameters).into(),
+                        (method.flags as jint).into(),
+                        (method.address.unwrap_or(0) as jlong).into(),
+                        (method.rva.unwrap_or(0) as jlong).into(),
+                    ],
+                )?;
+                env.set_object_array_element(&array, i as jsize, obj)?;
+            }
+            Ok(array)
+        })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 导出 C# 伪头文件，返回写出的类数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeExportHeaders", "(Ljava/lang/String;)I")]
+pub fn jni_il2cpp_export_headers(mut env: JNIEnv, _obj: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        with_dump(|dump| Ok(dump.export_csharp(&path)? as jint))
+    })()
+    .or_throw(&mut env)
+}
+
+/// 释放分析结果
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Il2Cpp", "nativeRelease", "()V")]
+pub fn jni_il2cpp_release(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        *IL2CPP_DUMP
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire Il2Cpp dump write lock"))? = None;
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
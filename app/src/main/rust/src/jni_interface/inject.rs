@@ -0,0 +1,197 @@
+//! JNI methods for Injector
+
+use crate::core::globals::{DRIVER_MANAGER, LIBRARY_INJECTOR, REMOTE_ALLOCATOR};
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::inject::RemoteProt;
+use anyhow::anyhow;
+use jni::objects::{JByteArray, JObject, JObjectArray, JString};
+use jni::sys::{jboolean, jint, jlong, jlongArray, jsize, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+fn parse_prot(prot: jint) -> JniResult<RemoteProt> {
+    RemoteProt::from_id(prot).ok_or_else(|| anyhow!("Invalid protection: {}", prot))
+}
+
+/// 在绑定进程内分配内存，返回起始地址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeAllocate", "(JI)J")]
+pub fn jni_inject_allocate(mut env: JNIEnv, _obj: JObject, size: jlong, prot: jint) -> jlong {
+    (|| -> JniResult<jlong> {
+        if size <= 0 {
+            return Err(anyhow!("Invalid allocation size: {}", size));
+        }
+        let prot = parse_prot(prot)?;
+
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        Ok(allocator.allocate(&manager, size as usize, prot)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 分配可执行内存并写入载荷，返回起始地址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeLoadPayload", "([BZ)J")]
+pub fn jni_inject_load_payload(mut env: JNIEnv, _obj: JObject, payload: JByteArray, rwx: jboolean) -> jlong {
+    (|| -> JniResult<jlong> {
+        let payload = env.convert_byte_array(&payload)?;
+        if payload.is_empty() {
+            return Err(anyhow!("Empty payload"));
+        }
+
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        Ok(allocator.load_payload(&manager, &payload, rwx != 0)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 向分配内写入数据
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeWrite", "(J[B)Z")]
+pub fn jni_inject_write(mut env: JNIEnv, _obj: JObject, address: jlong, bytes: JByteArray) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let bytes = env.convert_byte_array(&bytes)?;
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        allocator.write(&manager, address as u64, &bytes)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 修改分配的权限
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeProtect", "(JI)Z")]
+pub fn jni_inject_protect(mut env: JNIEnv, _obj: JObject, address: jlong, prot: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let prot = parse_prot(prot)?;
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        allocator.protect(&manager, address as u64, prot)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 释放分配，未找到时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeFree", "(J)Z")]
+pub fn jni_inject_free(mut env: JNIEnv, _obj: JObject, address: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        Ok(if allocator.free(&manager, address as u64)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 释放全部分配，返回释放数量
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeFreeAll", "()I")]
+pub fn jni_inject_free_all(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        Ok(allocator.free_all(&manager) as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 全部分配，按 [地址, 大小, 权限] 平铺
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeListAllocations", "()[J")]
+pub fn jni_inject_list_allocations(mut env: JNIEnv, _obj: JObject) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let allocations = REMOTE_ALLOCATOR
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator read lock"))?
+            .list();
+
+        let values: Vec<jlong> = allocations
+            .iter()
+            .flat_map(|allocation| [allocation.address as jlong, allocation.size as jlong, allocation.prot.id() as jlong])
+            .collect();
+        let array = env.new_long_array(values.len() as jsize)?;
+        env.set_long_array_region(&array, 0, &values)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 在绑定进程中加载共享库，返回库的基址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeInjectLibrary", "(Ljava/lang/String;)J")]
+pub fn jni_inject_library(mut env: JNIEnv, _obj: JObject, path: JString) -> jlong {
+    (|| -> JniResult<jlong> {
+        let path: String = env.get_string(&path)?.into();
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut allocator = REMOTE_ALLOCATOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RemoteAllocator write lock"))?;
+        let mut injector = LIBRARY_INJECTOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire LibraryInjector write lock"))?;
+        Ok(injector.inject(&manager, &mut allocator, &path)?.base as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 卸载注入的库，未找到时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeUnloadLibrary", "(J)Z")]
+pub fn jni_inject_unload_library(mut env: JNIEnv, _obj: JObject, handle: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut injector = LIBRARY_INJECTOR
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire LibraryInjector write lock"))?;
+        Ok(if injector.unload(&manager, handle as u64)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已注入的库
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Injector", "nativeListInjectedLibraries", "()[Lmoe/fuqiuluo/mamu/driver/InjectedLibrary;")]
+pub fn jni_inject_list_libraries<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let libraries = LIBRARY_INJECTOR
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire LibraryInjector read lock"))?
+            .list();
+
+        let library_class = env.find_class("moe/fuqiuluo/mamu/driver/InjectedLibrary")?;
+        let array = env.new_object_array(libraries.len() as jsize, &library_class, JObject::null())?;
+        for (i, library) in libraries.iter().enumerate() {
+            let path = env.new_string(&library.path)?;
+            let obj = env.new_object(
+                &library_class,
+                "(Ljava/lang/String;JJ)V",
+                &[(&path).into(), (library.handle as jlong).into(), (library.base as jlong).into()],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
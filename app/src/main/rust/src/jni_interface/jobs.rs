@@ -0,0 +1,65 @@
+//! JNI methods for Jobs
+
+use crate::core::dump::start_dump_job;
+use crate::core::globals::JOB_REGISTRY;
+use crate::core::jobs::JobKind;
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JLongArray, JObject, JString};
+use jni::sys::{jboolean, jint, jlong, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+use std::time::Duration;
+
+/// 请求取消任务，任务不存在或已结束时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeCancelJob", "(I)Z")]
+pub fn jni_cancel_job(_env: JNIEnv, _obj: JObject, job: jint) -> jboolean {
+    if JOB_REGISTRY.cancel(job) { JNI_TRUE } else { JNI_FALSE }
+}
+
+/// 任务状态，任务不存在（或已被清理）时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeGetJobStatus", "(I)I")]
+pub fn jni_get_job_status(_env: JNIEnv, _obj: JObject, job: jint) -> jint {
+    JOB_REGISTRY.status(job).map_or(-1, |status| status as jint)
+}
+
+/// 任务类型，任务不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeGetJobKind", "(I)I")]
+pub fn jni_get_job_kind(_env: JNIEnv, _obj: JObject, job: jint) -> jint {
+    JOB_REGISTRY.kind(job).map_or(-1, |kind| kind as jint)
+}
+
+/// 等待任务结束，`timeout_ms` 小于 0 时一直等待，返回值同 nativeGetJobStatus（超时为运行中）
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeAwaitJob", "(IJ)I")]
+pub fn jni_await_job(_env: JNIEnv, _obj: JObject, job: jint, timeout_ms: jlong) -> jint {
+    let timeout = (timeout_ms >= 0).then(|| Duration::from_millis(timeout_ms as u64));
+    JOB_REGISTRY.wait(job, timeout).map_or(-1, |status| status as jint)
+}
+
+/// 某类型中正在运行的任务，没有时返回 0
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeGetActiveJob", "(I)I")]
+pub fn jni_get_active_job(mut env: JNIEnv, _obj: JObject, kind: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let kind = JobKind::from_i32(kind)?;
+        Ok(JOB_REGISTRY.active(kind).unwrap_or(0))
+    })()
+    .or_throw(&mut env)
+}
+
+/// 在后台转储多个区域，`ranges` 为 [start0, end0, start1, end1, ...]，返回任务 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Jobs", "nativeStartDump", "([JLjava/lang/String;)I")]
+pub fn jni_start_dump(mut env: JNIEnv, _obj: JObject, ranges: JLongArray, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let len = env.get_array_length(&ranges)? as usize;
+        if len == 0 || !len.is_multiple_of(2) {
+            return Err(anyhow!("Region array must hold start/end pairs, got {} values", len));
+        }
+
+        let mut values = vec![0i64; len];
+        env.get_long_array_region(&ranges, 0, &mut values)?;
+        let ranges: Vec<(u64, u64)> = values.chunks_exact(2).map(|pair| (pair[0] as u64, pair[1] as u64)).collect();
+        start_dump_job(ranges, path)
+    })()
+    .or_throw(&mut env)
+}
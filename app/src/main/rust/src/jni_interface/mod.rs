@@ -9,4 +9,22 @@ pub mod mem_ops;
 pub mod disassembler;
 pub mod driver_installer;
 pub mod pointer_scan;
-pub mod freeze;
\ No newline at end of file
+pub mod freeze;
+pub mod art_hook;
+pub mod compare_slots;
+pub mod change_trigger;
+pub mod region_growth;
+pub mod patch;
+pub mod scan_profile;
+pub mod watch;
+pub mod inject;
+pub mod snapshot;
+pub mod il2cpp;
+pub mod unreal;
+pub mod scan_events;
+pub mod jobs;
+pub mod saved_list;
+pub mod script;
+pub mod remote;
+pub mod rules;
+pub mod value_history;
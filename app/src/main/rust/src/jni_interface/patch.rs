@@ -0,0 +1,202 @@
+//! JNI methods for PatchManager
+
+use crate::core::globals::PATCH_MANAGER;
+use crate::core::patch_manager::{encode_branch, encode_immediate, PatchWrite};
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JByteArray, JObject, JObjectArray, JString};
+use jni::sys::{jboolean, jbyteArray, jint, jlong, jlongArray, jsize, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 替换所有特征码匹配处，返回已修改的地址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeAobReplace", "(Ljava/lang/String;Ljava/lang/String;JIZ)[J")]
+pub fn jni_patch_aob_replace(
+    mut env: JNIEnv,
+    _obj: JObject,
+    pattern: JString,
+    replacement: JString,
+    region_mask: jlong,
+    limit: jint,
+    force: jboolean,
+) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let pattern: String = env.get_string(&pattern)?.into();
+        let replacement: String = env.get_string(&replacement)?.into();
+
+        let addresses = {
+            let mut manager = PATCH_MANAGER
+                .write()
+                .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+            manager.aob_replace(&pattern, &replacement, region_mask as u64, limit.max(0) as usize, force != 0)?
+        };
+
+        let values: Vec<jlong> = addresses.into_iter().map(|a| a as jlong).collect();
+        let array = env.new_long_array(values.len() as i32)?;
+        env.set_long_array_region(&array, 0, &values)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 写入补丁并记录原始字节
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeApply", "(J[BZ)Z")]
+pub fn jni_patch_apply(mut env: JNIEnv, _obj: JObject, address: jlong, bytes: JByteArray, force: jboolean) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let bytes = env.convert_byte_array(&bytes)?;
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        manager.apply(address as u64, &bytes, force != 0)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 恢复单个补丁
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeRestore", "(J)Z")]
+pub fn jni_patch_restore(mut env: JNIEnv, _obj: JObject, address: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        manager.restore(address as u64)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 恢复所有补丁，返回恢复数量
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeRestoreAll", "()I")]
+pub fn jni_patch_restore_all(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        Ok(manager.restore_all() as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已打补丁的地址列表
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeGetPatchedAddresses", "()[J")]
+pub fn jni_patch_get_patched_addresses(mut env: JNIEnv, _obj: JObject) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let manager = PATCH_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager read lock"))?;
+        let addresses: Vec<jlong> = manager.patched_addresses().into_iter().map(|a| a as jlong).collect();
+        let array = env.new_long_array(addresses.len() as i32)?;
+        env.set_long_array_region(&array, 0, &addresses)?;
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 写入代码补丁，返回补丁 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeApplyPatch", "(J[B)I")]
+pub fn jni_patch_apply_code(mut env: JNIEnv, _obj: JObject, address: jlong, bytes: JByteArray) -> jint {
+    (|| -> JniResult<jint> {
+        let bytes = env.convert_byte_array(&bytes)?;
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        manager.apply_code(address as u64, &bytes)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 汇编并写入代码补丁，返回补丁 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeApplyAsm", "(JLjava/lang/String;)I")]
+pub fn jni_patch_apply_asm(mut env: JNIEnv, _obj: JObject, address: jlong, source: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let source: String = env.get_string(&source)?.into();
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        manager.apply_asm(address as u64, &source)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按 id 恢复补丁，未找到时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeRevertPatch", "(I)Z")]
+pub fn jni_patch_revert(mut env: JNIEnv, _obj: JObject, id: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        Ok(if manager.revert(id)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 切换补丁字节与原始字节
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeSetPatchEnabled", "(IZ)Z")]
+pub fn jni_patch_set_enabled(mut env: JNIEnv, _obj: JObject, id: jint, enabled: jboolean) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = PATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PatchManager write lock"))?;
+        manager.set_enabled(id, enabled != 0)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 全部补丁，按地址排序
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeListPatches", "()[Lmoe/fuqiuluo/mamu/driver/PatchInfo;")]
+pub fn jni_patch_list<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let patches: Vec<_> = {
+            let manager = PATCH_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire PatchManager read lock"))?;
+            manager.list().into_iter().map(|(addr, entry)| (addr, entry.clone())).collect()
+        };
+
+        let info_class = env.find_class("moe/fuqiuluo/mamu/driver/PatchInfo")?;
+        let array = env.new_object_array(patches.len() as jsize, &info_class, JObject::null())?;
+        for (i, (address, entry)) in patches.iter().enumerate() {
+            let original = env.byte_array_from_slice(&entry.original)?;
+            let patched = env.byte_array_from_slice(&entry.patched)?;
+            let obj = env.new_object(
+                &info_class,
+                "(IJ[B[BZZ)V",
+                &[
+                    entry.id.into(),
+                    (*address as jlong).into(),
+                    (&original).into(),
+                    (&patched).into(),
+                    ((entry.write == PatchWrite::Force) as jboolean).into(),
+                    (entry.enabled as jboolean).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 生成位于 `from` 处跳转到 `to` 的 `b` 指令
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeEncodeBranch", "(JJ)[B")]
+pub fn jni_patch_encode_branch(mut env: JNIEnv, _obj: JObject, from: jlong, to: jlong) -> jbyteArray {
+    (|| -> JniResult<jbyteArray> {
+        let insn = encode_branch(from as u64, to as u64)?;
+        Ok(env.byte_array_from_slice(&insn.to_le_bytes())?.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 替换指令中的立即数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PatchManager", "nativeEncodeImmediate", "(II)I")]
+pub fn jni_patch_encode_immediate(mut env: JNIEnv, _obj: JObject, insn: jint, value: jint) -> jint {
+    (|| -> JniResult<jint> {
+        if value < 0 {
+            return Err(anyhow!("Invalid immediate: {}", value));
+        }
+        Ok(encode_immediate(insn as u32, value as u32)? as jint)
+    })()
+    .or_throw(&mut env)
+}
@@ -1,18 +1,85 @@
 //! JNI methods for PointerScanner.
 
-use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::core::DRIVER_MANAGER;
+use crate::core::wire::{self, Chain, ChainList};
 use crate::ext::jni::{JniResult, JniResultExt};
+use crate::pointer_scan::graph::GraphFormat;
+use crate::pointer_scan::maintenance;
 use crate::pointer_scan::manager::POINTER_SCAN_MANAGER;
 use crate::pointer_scan::scanner::ScanRegion;
 use crate::pointer_scan::shared_buffer::SHARED_BUFFER_SIZE;
+use crate::pointer_scan::static_modules::{self, assign_module_indices};
 use crate::pointer_scan::types::{ScanPhase, VmStaticData};
+use crate::search::engine::SEARCH_ENGINE_MANAGER;
 use anyhow::anyhow;
-use jni::objects::{JLongArray, JObject, JObjectArray, JString};
-use jni::sys::{jboolean, jint, jlong, jobjectArray, JNI_FALSE, JNI_TRUE};
+use jni::objects::{JByteArray, JIntArray, JLongArray, JObject, JObjectArray, JString};
+use jni::sys::{jboolean, jint, jlong, jobjectArray, jstring, JNI_FALSE, JNI_TRUE};
 use jni::JNIEnv;
 use jni_macro::jni_method;
 use log::{error, info, log_enabled, Level};
 
+/// Parse static modules passed as [start1, end1, ...] plus names.
+fn parse_static_modules(env: &mut JNIEnv, modules: &JLongArray, module_names: &JObjectArray) -> JniResult<Vec<VmStaticData>> {
+    let modules_len = env.get_array_length(modules)? as usize;
+    let module_count = modules_len / 2;
+
+    let names_count = env.get_array_length(module_names)? as usize;
+    if names_count != module_count {
+        return Err(anyhow!("Module count mismatch: {} modules but {} names", module_count, names_count));
+    }
+
+    let mut module_data = vec![0i64; modules_len];
+    env.get_long_array_region(modules, 0, &mut module_data)?;
+
+    let mut static_modules = Vec::with_capacity(module_count);
+    for i in 0..module_count {
+        let name_obj = env.get_object_array_element(module_names, i as i32)?;
+        let name: String = env.get_string(&JString::from(name_obj))?.into();
+        static_modules.push(VmStaticData::new(name, module_data[i * 2] as u64, module_data[i * 2 + 1] as u64, true));
+    }
+
+    assign_module_indices(&mut static_modules);
+    Ok(static_modules)
+}
+
+/// Find the static data segments of the images loaded in the bound process.
+#[jni_method(
+    70,
+    "moe/fuqiuluo/mamu/driver/PointerScanner",
+    "nativeDiscoverStaticModules",
+    "()[Lmoe/fuqiuluo/mamu/driver/MemoryRegionInfo;"
+)]
+pub fn jni_discover_static_modules(mut env: JNIEnv, _class: JObject) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let modules = {
+            let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+            static_modules::discover_static_modules(&driver_manager)?
+        };
+
+        let region_class = env.find_class("moe/fuqiuluo/mamu/driver/MemoryRegionInfo")?;
+        let result_array = env.new_object_array(modules.len() as i32, &region_class, JObject::null())?;
+
+        for (i, module) in modules.iter().enumerate() {
+            let name = env.new_string(&module.name)?;
+            let region = env.new_object(
+                &region_class,
+                "(JJLjava/lang/String;Z)V",
+                &[
+                    (module.base_address as jlong).into(),
+                    (module.end_address as jlong).into(),
+                    (&name).into(),
+                    JNI_TRUE.into(),
+                ],
+            )?;
+            env.set_object_array_element(&result_array, i as i32, region)?;
+        }
+
+        Ok(result_array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
 /// Initialize the pointer scanner with a cache directory.
 #[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeInit", "(Ljava/lang/String;)Z")]
 pub fn jni_init_pointer_scanner(mut env: JNIEnv, _class: JObject, cache_dir: JString) -> jboolean {
@@ -66,7 +133,8 @@ pub fn jni_set_pointer_scan_buffer(mut env: JNIEnv, _class: JObject, buffer: JOb
 /// * `regions` - Memory regions as [start1, end1, start2, end2, ...]
 /// * `region_names` - Names of the regions
 /// * `static_flags` - Boolean flags indicating if each region is static
-#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeStartScan", "(JIII[J[Ljava/lang/String;[ZZ)Z")]
+/// * `background` - Run the scan at low priority, see [`crate::core::background`]
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeStartScan", "(JIII[J[Ljava/lang/String;[ZZZ)Z")]
 pub fn jni_start_pointer_scan(
     mut env: JNIEnv,
     _class: JObject,
@@ -77,7 +145,8 @@ pub fn jni_start_pointer_scan(
     regions: JLongArray,
     region_names: JObjectArray,
     static_flags: JObject, // jbooleanArray
-    is_layer_bfs: jboolean
+    is_layer_bfs: jboolean,
+    background: jboolean,
 ) -> jboolean {
     (|| -> JniResult<jboolean> {
         // Parse regions
@@ -124,21 +193,7 @@ pub fn jni_start_pointer_scan(
             }
         }
 
-        // Assign indices and first_module_base_addr to static modules with duplicate names
-        // 同名模块共享第一个段的基址，用于计算统一的偏移
-        let mut name_counts: HashMap<String, u32> = HashMap::new();
-        let mut first_base_addrs: HashMap<String, u64> = HashMap::new();
-        for module in &mut static_modules {
-            let count = name_counts.entry(module.name.clone()).or_insert(0);
-            module.index = *count;
-            if *count == 0 {
-                // 记录该名称第一个模块的基址
-                first_base_addrs.insert(module.name.clone(), module.base_address);
-            }
-            // 所有同名模块共享第一个段的基址
-            module.first_module_base_addr = *first_base_addrs.get(&module.name).unwrap();
-            *count += 1;
-        }
+        assign_module_indices(&mut static_modules);
 
         if log_enabled!(Level::Debug) {
             info!("Static modules:");
@@ -167,7 +222,8 @@ pub fn jni_start_pointer_scan(
             align as u32,
             scan_regions,
             static_modules,
-            is_layer_bfs == 1u8
+            is_layer_bfs == 1u8,
+            background != JNI_FALSE,
         )?;
 
         Ok(JNI_TRUE)
@@ -278,6 +334,27 @@ pub fn jni_get_chains(mut env: JNIEnv, _class: JObject, start: jint, count: jint
     .or_throw(&mut env)
 }
 
+/// Get a range of chain results encoded as a `ChainList` protobuf message.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeGetChainsProto", "(II)[B")]
+pub fn jni_get_chains_proto<'l>(mut env: JNIEnv<'l>, _class: JObject, start: jint, count: jint) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        if start < 0 || count < 0 {
+            return Err(anyhow!("Invalid chain range: start={}, count={}", start, count));
+        }
+        let list = {
+            let manager = POINTER_SCAN_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+            ChainList {
+                total: manager.get_chain_count() as u64,
+                chains: manager.get_chain_results(start as usize, count as usize).iter().map(Chain::from).collect(),
+            }
+        };
+        Ok(env.byte_array_from_slice(&wire::encode(&list))?)
+    })()
+    .or_throw(&mut env)
+}
+
 /// Clear all scan results.
 #[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeClear", "()V")]
 pub fn jni_clear_pointer_scan(_env: JNIEnv, _class: JObject) {
@@ -294,3 +371,625 @@ pub fn jni_get_phase(_env: JNIEnv, _class: JObject) -> jint {
         Err(_) => ScanPhase::Idle as jint,
     }
 }
+
+/// Search result spill files that must survive pruning while results are held.
+fn search_files_in_use(cache_dir: &std::path::Path) -> Vec<PathBuf> {
+    match SEARCH_ENGINE_MANAGER.read() {
+        Ok(manager) => {
+            let mut files = manager.history_files();
+            if manager.get_total_count().unwrap_or(0) > 0 {
+                files.push(cache_dir.join("mamu_search_results.bin"));
+                files.push(cache_dir.join("mamu_fuzzy_results.bin"));
+            }
+            if manager.is_searching() {
+                files.push(cache_dir.join("mamu_ps_fuzzy_spill.bin"));
+            }
+            files
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// List mamu-owned files in the cache directory.
+#[jni_method(
+    70,
+    "moe/fuqiuluo/mamu/driver/PointerScanner",
+    "nativeListCacheFiles",
+    "()[Lmoe/fuqiuluo/mamu/driver/CacheFileEntry;"
+)]
+pub fn jni_list_cache_files(mut env: JNIEnv, _class: JObject) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let cache_dir = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?
+            .cache_dir()
+            .clone();
+
+        let files = maintenance::list_cache_files(&cache_dir)?;
+
+        let entry_class = env.find_class("moe/fuqiuluo/mamu/driver/CacheFileEntry")?;
+        let result_array = env.new_object_array(files.len() as i32, &entry_class, JObject::null())?;
+
+        for (i, file) in files.iter().enumerate() {
+            let name_jstring = env.new_string(&file.name)?;
+            let entry_obj = env.new_object(
+                &entry_class,
+                "(Ljava/lang/String;IJJ)V",
+                &[
+                    (&name_jstring).into(),
+                    (file.kind as jint).into(),
+                    (file.size as jlong).into(),
+                    (file.modified_secs as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&result_array, i as i32, entry_obj)?;
+        }
+
+        Ok(result_array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Delete the named cache files. Returns the number of bytes freed.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeDeleteCacheFiles", "([Ljava/lang/String;)J")]
+pub fn jni_delete_cache_files(mut env: JNIEnv, _class: JObject, names: JObjectArray) -> jlong {
+    (|| -> JniResult<jlong> {
+        let count = env.get_array_length(&names)?;
+        let mut file_names = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let name_obj = env.get_object_array_element(&names, i)?;
+            let name: String = env.get_string(&JString::from(name_obj))?.into();
+            file_names.push(name);
+        }
+
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        let extra_in_use = search_files_in_use(manager.cache_dir());
+        let (_, freed) = manager.delete_cache_files(&file_names, &extra_in_use)?;
+
+        Ok(freed as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Delete cache files older than `max_age_secs`. Returns the number of bytes freed.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativePruneCacheFiles", "(J)J")]
+pub fn jni_prune_cache_files(mut env: JNIEnv, _class: JObject, max_age_secs: jlong) -> jlong {
+    (|| -> JniResult<jlong> {
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        let extra_in_use = search_files_in_use(manager.cache_dir());
+        let (_, freed) = manager.prune_cache_files(max_age_secs.max(0) as u64, &extra_in_use)?;
+
+        Ok(freed as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Shrink the pointer library backing file. Returns the number of bytes released.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeCompactStorage", "()J")]
+pub fn jni_compact_storage(mut env: JNIEnv, _class: JObject) -> jlong {
+    (|| -> JniResult<jlong> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.compact_storage()? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Set Phase 1 chunk size, worker thread limit and batch threshold for later scans.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeSetPerformanceConfig", "(III)Z")]
+pub fn jni_set_performance_config(
+    mut env: JNIEnv,
+    _class: JObject,
+    chunk_size: jint,
+    max_threads: jint,
+    batch_threshold: jint,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        if chunk_size <= 0 || max_threads < 0 || batch_threshold <= 0 {
+            return Err(anyhow!(
+                "Invalid performance config: chunk_size={}, max_threads={}, batch_threshold={}",
+                chunk_size, max_threads, batch_threshold
+            ));
+        }
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        manager.set_performance_config(chunk_size as usize, max_threads as usize, batch_threshold as usize)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Set per-level max offsets, the structure offset whitelist and the max negative offset used by Phase 2.
+/// Empty arrays restore the single max offset.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeSetOffsetRules", "([I[II)Z")]
+pub fn jni_set_offset_rules(
+    mut env: JNIEnv,
+    _class: JObject,
+    level_max_offsets: JIntArray,
+    offset_whitelist: JIntArray,
+    max_backward_offset: jint,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let read_offsets = |array: &JIntArray| -> JniResult<Vec<u32>> {
+            let mut values = vec![0i32; env.get_array_length(array)? as usize];
+            env.get_int_array_region(array, 0, &mut values)?;
+            if let Some(negative) = values.iter().find(|&&v| v < 0) {
+                return Err(anyhow!("Invalid offset: {}", negative));
+            }
+            Ok(values.into_iter().map(|v| v as u32).collect())
+        };
+        let level_max_offsets = read_offsets(&level_max_offsets)?;
+        let offset_whitelist = read_offsets(&offset_whitelist)?;
+        if max_backward_offset < 0 {
+            return Err(anyhow!("Invalid max backward offset: {}", max_backward_offset));
+        }
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        manager.set_offset_rules(level_max_offsets, offset_whitelist, max_backward_offset as u32)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Get the Phase 1 writer statistics of the last scan as JSON, or null if no scan has finished Phase 1.
+/// Includes the batch threshold chosen from available memory and storage speed.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeGetLastScanReport", "()Ljava/lang/String;")]
+pub fn jni_get_last_scan_report(mut env: JNIEnv, _class: JObject) -> jstring {
+    (|| -> JniResult<jstring> {
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        match manager.last_scan_report() {
+            Some(report) => Ok(env.new_string(serde_json::to_string(report)?)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// Get the interrupted scan checkpoint as JSON, or null if there is none to resume.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeGetScanCheckpoint", "()Ljava/lang/String;")]
+pub fn jni_get_scan_checkpoint(mut env: JNIEnv, _class: JObject) -> jstring {
+    (|| -> JniResult<jstring> {
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        match manager.scan_checkpoint() {
+            Some(summary) => Ok(env.new_string(serde_json::to_string(&summary)?)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// Resume an interrupted scan from its checkpoint, then build chains as the original scan would.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeResumeScan", "()Z")]
+pub fn jni_resume_scan(mut env: JNIEnv, _class: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        manager.resume_scan_async()?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Delete the interrupted scan checkpoint and its temp files. Returns whether there was one.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeDiscardScanCheckpoint", "()Z")]
+pub fn jni_discard_scan_checkpoint(mut env: JNIEnv, _class: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        Ok(if manager.discard_scan_checkpoint()? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// Save the current chain results to a file. Returns the number of chains written.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeSaveChains", "(Ljava/lang/String;)I")]
+pub fn jni_save_chains(mut env: JNIEnv, _class: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        Ok(manager.save_chains(&PathBuf::from(path))? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Load chain results from a file, replacing the current ones. Returns the number of chains loaded.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeLoadChains", "(Ljava/lang/String;)I")]
+pub fn jni_load_chains(mut env: JNIEnv, _class: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.load_chains(&PathBuf::from(path))? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Save the Phase 1 pointer library to a file. Returns the number of pointers saved.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeSavePointerLibrary", "(Ljava/lang/String;)J")]
+pub fn jni_save_pointer_library(mut env: JNIEnv, _class: JObject, path: JString) -> jlong {
+    (|| -> JniResult<jlong> {
+        let path: String = env.get_string(&path)?.into();
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.save_pointer_library(&PathBuf::from(path))? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Export the pointers whose address or value lies in `ranges` ([start1, end1, start2, end2, ...])
+/// to a standalone library file. Returns the number of pointers exported.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeExportPointerLibrarySubset", "(Ljava/lang/String;[J)J")]
+pub fn jni_export_pointer_library_subset(mut env: JNIEnv, _class: JObject, path: JString, ranges: JLongArray) -> jlong {
+    (|| -> JniResult<jlong> {
+        let path: String = env.get_string(&path)?.into();
+
+        let ranges_len = env.get_array_length(&ranges)? as usize;
+        if !ranges_len.is_multiple_of(2) {
+            return Err(anyhow!("Ranges array length must be even"));
+        }
+        let mut ranges_buf = vec![0i64; ranges_len];
+        env.get_long_array_region(&ranges, 0, &mut ranges_buf)?;
+        let ranges: Vec<(u64, u64)> = ranges_buf.chunks(2).map(|chunk| (chunk[0] as u64, chunk[1] as u64)).collect();
+
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        Ok(manager.export_pointer_library_subset(&PathBuf::from(path), &ranges)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Export the pointer graph around a target address. Returns the number of nodes written.
+///
+/// # Arguments
+/// * `format` - 0 = DOT, 1 = JSON
+/// * `modules` - Static module regions as [start1, end1, start2, end2, ...]
+/// * `module_names` - Names of the modules
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeExportPointerGraph", "(Ljava/lang/String;JIIII[J[Ljava/lang/String;)I")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_export_pointer_graph(
+    mut env: JNIEnv,
+    _class: JObject,
+    path: JString,
+    target_address: jlong,
+    max_depth: jint,
+    max_offset: jint,
+    max_nodes: jint,
+    format: jint,
+    modules: JLongArray,
+    module_names: JObjectArray,
+) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let format = GraphFormat::from_id(format).ok_or_else(|| anyhow!("Invalid graph format: {}", format))?;
+        let static_modules = parse_static_modules(&mut env, &modules, &module_names)?;
+
+        let manager = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+
+        Ok(manager.export_pointer_graph(
+            &PathBuf::from(path),
+            target_address as u64,
+            max_depth.max(0) as u32,
+            max_offset.max(0) as u32,
+            max_nodes.max(1) as usize,
+            format,
+            &static_modules,
+        )? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Flatten `(address, value)` pairs into a Java long array.
+fn pointer_pairs_to_array<'l>(env: &mut JNIEnv<'l>, pairs: &[(u64, u64)]) -> JniResult<JLongArray<'l>> {
+    let flat: Vec<jlong> = pairs.iter().flat_map(|&(address, value)| [address as jlong, value as jlong]).collect();
+    let array = env.new_long_array(flat.len() as i32)?;
+    env.set_long_array_region(&array, 0, &flat)?;
+    Ok(array)
+}
+
+/// Query the pointer library for slots pointing into
+/// `[target - max_offset, target + max_backward_offset]`.
+/// Returns `[address1, value1, address2, value2, ...]` in value order.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeFindReferencesTo", "(JIII)[J")]
+pub fn jni_find_references_to<'l>(mut env: JNIEnv<'l>, _class: JObject, target: jlong, max_offset: jint, max_backward_offset: jint, limit: jint) -> JLongArray<'l> {
+    (|| -> JniResult<JLongArray<'l>> {
+        let references = {
+            let manager = POINTER_SCAN_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+            manager.find_references_to(target as u64, max_offset.max(0) as u32, max_backward_offset.max(0) as u32, limit.max(0) as usize)?
+        };
+        pointer_pairs_to_array(&mut env, &references)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Query the pointer library for pointers stored in `[address, address + span)`.
+/// Returns `[address1, value1, address2, value2, ...]` in address order.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeFindPointersIn", "(JJI)[J")]
+pub fn jni_find_pointers_in<'l>(mut env: JNIEnv<'l>, _class: JObject, address: jlong, span: jlong, limit: jint) -> JLongArray<'l> {
+    (|| -> JniResult<JLongArray<'l>> {
+        let pointers = {
+            let manager = POINTER_SCAN_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?;
+            manager.find_pointers_in(address as u64, span.max(0) as u64, limit.max(0) as usize)?
+        };
+        pointer_pairs_to_array(&mut env, &pointers)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Load a saved pointer library, replacing the current one. Returns the number of pointers loaded.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeLoadPointerLibrary", "(Ljava/lang/String;)J")]
+pub fn jni_load_pointer_library(mut env: JNIEnv, _class: JObject, path: JString) -> jlong {
+    (|| -> JniResult<jlong> {
+        let path: String = env.get_string(&path)?.into();
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.load_pointer_library(&PathBuf::from(path))? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Build chains from the current pointer library without rescanning memory.
+///
+/// # Arguments
+/// * `modules` - Static module regions as [start1, end1, start2, end2, ...]
+/// * `module_names` - Names of the modules
+/// * `background` - Build at low priority, see [`crate::core::background`]
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeStartChainBuild", "(JII[J[Ljava/lang/String;ZZ)Z")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_start_chain_build(
+    mut env: JNIEnv,
+    _class: JObject,
+    target_address: jlong,
+    max_depth: jint,
+    max_offset: jint,
+    modules: JLongArray,
+    module_names: JObjectArray,
+    is_layer_bfs: jboolean,
+    background: jboolean,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let static_modules = parse_static_modules(&mut env, &modules, &module_names)?;
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        manager.start_chain_build_async(
+            target_address as u64,
+            max_depth as u32,
+            max_offset as u32,
+            static_modules,
+            is_layer_bfs == 1u8,
+            background != JNI_FALSE,
+        )?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Drop the first levels of a chain and rebuild its prefix from the current pointer library.
+/// Returns the address of the new root slot.
+///
+/// # Arguments
+/// * `modules` - Static module regions as [start1, end1, start2, end2, ...]
+/// * `module_names` - Names of the modules
+/// * `background` - Build at low priority, see [`crate::core::background`]
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeStartReroot", "(IIII[J[Ljava/lang/String;ZZ)J")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_start_reroot(
+    mut env: JNIEnv,
+    _class: JObject,
+    chain_index: jint,
+    drop_levels: jint,
+    max_depth: jint,
+    max_offset: jint,
+    modules: JLongArray,
+    module_names: JObjectArray,
+    is_layer_bfs: jboolean,
+    background: jboolean,
+) -> jlong {
+    (|| -> JniResult<jlong> {
+        let static_modules = parse_static_modules(&mut env, &modules, &module_names)?;
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        let new_root = manager.start_reroot_async(
+            chain_index.max(0) as usize,
+            drop_levels.max(0) as usize,
+            max_depth as u32,
+            max_offset as u32,
+            static_modules,
+            is_layer_bfs == 1u8,
+            background != JNI_FALSE,
+        )?;
+
+        Ok(new_root as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Seed chain building with the hits of a watchpoint on the target.
+///
+/// # Returns
+/// Number of distinct last-level offsets
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeSeedFromWatch", "(JI)I")]
+pub fn jni_seed_from_watch(mut env: JNIEnv, _class: JObject, target_address: jlong, watch_id: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.seed_from_watch(target_address as u64, watch_id)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Drop the watchpoint seed.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeClearAccessSeed", "()V")]
+pub fn jni_clear_access_seed(mut env: JNIEnv, _class: JObject) {
+    (|| -> JniResult<()> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        manager.clear_access_seed();
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Filter chain results with the watchpoint seed of the current target.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeFilterChainsByAccess", "()I")]
+pub fn jni_filter_chains_by_access(mut env: JNIEnv, _class: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.filter_chains_by_access()? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Re-resolve a range of chains against fresh module bases in the bound process.
+///
+/// # Arguments
+/// * `modules` - Static module regions as [start1, end1, start2, end2, ...]
+/// * `module_names` - Names of the modules
+/// * `start` - Index of the first chain
+/// * `count` - Number of chains to validate
+#[jni_method(
+    70,
+    "moe/fuqiuluo/mamu/driver/PointerScanner",
+    "nativeValidateChains",
+    "([J[Ljava/lang/String;II)[Lmoe/fuqiuluo/mamu/driver/ChainValidationResult;"
+)]
+pub fn jni_validate_chains(
+    mut env: JNIEnv,
+    _class: JObject,
+    modules: JLongArray,
+    module_names: JObjectArray,
+    start: jint,
+    count: jint,
+) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let static_modules = parse_static_modules(&mut env, &modules, &module_names)?;
+
+        let validations = POINTER_SCAN_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager read lock"))?
+            .validate_chains(&static_modules, start.max(0) as usize, count.max(0) as usize)?;
+
+        let result_class = env.find_class("moe/fuqiuluo/mamu/driver/ChainValidationResult")?;
+        let result_array = env.new_object_array(validations.len() as i32, &result_class, JObject::null())?;
+
+        for (i, v) in validations.iter().enumerate() {
+            // ChainValidationResult(index: Int, isValid: Boolean, resolvedAddress: Long, value: Long, failedStep: Int)
+            let obj = env.new_object(
+                &result_class,
+                "(IZJJI)V",
+                &[
+                    (start + i as jint).into(),
+                    v.is_valid().into(),
+                    (v.resolved_address.unwrap_or(0) as jlong).into(),
+                    (v.value.unwrap_or(0) as jlong).into(),
+                    (v.failed_step.map(|s| s as jint).unwrap_or(-1)).into(),
+                ],
+            )?;
+            env.set_object_array_element(&result_array, i as i32, obj)?;
+        }
+
+        Ok(result_array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Drop every chain that no longer resolves in the bound process. Returns the number of chains left.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeRemoveInvalidChains", "([J[Ljava/lang/String;)I")]
+pub fn jni_remove_invalid_chains(mut env: JNIEnv, _class: JObject, modules: JLongArray, module_names: JObjectArray) -> jint {
+    (|| -> JniResult<jint> {
+        let static_modules = parse_static_modules(&mut env, &modules, &module_names)?;
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.remove_invalid_chains(&static_modules)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Keep only chains that also appear (structurally) in a saved chain file. Returns the number of chains left.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeIntersectWithFile", "(Ljava/lang/String;)I")]
+pub fn jni_intersect_with_file(mut env: JNIEnv, _class: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.intersect_with_file(&PathBuf::from(path))? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Drop chains with a repeated module+offset path. Returns the number of chains left.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/PointerScanner", "nativeDeduplicateChains", "()I")]
+pub fn jni_deduplicate_chains(mut env: JNIEnv, _class: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let mut manager = POINTER_SCAN_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire PointerScanManager write lock"))?;
+
+        Ok(manager.deduplicate_chains()? as jint)
+    })()
+    .or_throw(&mut env)
+}
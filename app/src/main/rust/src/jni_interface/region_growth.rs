@@ -0,0 +1,83 @@
+//! JNI methods for RegionGrowthMonitor
+
+use crate::core::globals::{DRIVER_MANAGER, REGION_GROWTH};
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JObject, JObjectArray};
+use jni::sys::{jint, jlong, jsize};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 采集一次内存映射，返回当前区域数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RegionGrowthMonitor", "nativeSnapshot", "()I")]
+pub fn jni_region_growth_snapshot(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut tracker = REGION_GROWTH
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RegionGrowth write lock"))?;
+        Ok(tracker.snapshot(&manager)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 清空记录
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RegionGrowthMonitor", "nativeReset", "()V")]
+pub fn jni_region_growth_reset(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        let mut tracker = REGION_GROWTH
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire RegionGrowth write lock"))?;
+        tracker.reset();
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RegionGrowthMonitor", "nativeGetGeneration", "()I")]
+pub fn jni_region_growth_get_generation(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let tracker = REGION_GROWTH
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire RegionGrowth read lock"))?;
+        Ok(tracker.generation() as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 增长最快的区域，`range_mask` 为 0 时不过滤
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RegionGrowthMonitor", "nativeGetFastestGrowing", "(IJ)[Lmoe/fuqiuluo/mamu/driver/RegionGrowthEntry;")]
+pub fn jni_region_growth_get_fastest<'l>(mut env: JNIEnv<'l>, _obj: JObject, limit: jint, range_mask: jlong) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let growths = {
+            let tracker = REGION_GROWTH
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire RegionGrowth read lock"))?;
+            tracker.fastest_growing(limit.max(0) as usize, range_mask as u64)
+        };
+
+        let entry_class = env.find_class("moe/fuqiuluo/mamu/driver/RegionGrowthEntry")?;
+        let array = env.new_object_array(growths.len() as jsize, &entry_class, JObject::null())?;
+        for (i, growth) in growths.iter().enumerate() {
+            let name = env.new_string(&growth.name)?;
+            let obj = env.new_object(
+                &entry_class,
+                "(JJLjava/lang/String;IJJD)V",
+                &[
+                    (growth.start as jlong).into(),
+                    (growth.end as jlong).into(),
+                    (&name).into(),
+                    growth.region_type.map(|t| t as jint).unwrap_or(-1).into(),
+                    (growth.first_size as jlong).into(),
+                    (growth.growth as jlong).into(),
+                    growth.bytes_per_sec.into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
@@ -0,0 +1,54 @@
+//! JNI methods for RemoteServer
+
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::remote::{is_running, start_tcp, start_unix, stop_server};
+use anyhow::anyhow;
+use jni::JNIEnv;
+use jni::objects::{JObject, JString};
+use jni::sys::{jboolean, jint};
+use jni_macro::jni_method;
+
+/// 读取令牌，null 或空串直接报错，服务不允许无认证运行
+fn get_token(env: &mut JNIEnv, token: &JString) -> JniResult<String> {
+    if token.is_null() {
+        return Err(anyhow!("Remote server requires a non-empty token"));
+    }
+    let token: String = env.get_string(token)?.into();
+    if token.is_empty() {
+        return Err(anyhow!("Remote server requires a non-empty token"));
+    }
+    Ok(token)
+}
+
+/// 在 127.0.0.1:port 上启动远程控制服务
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RemoteServer", "nativeStartTcp", "(ILjava/lang/String;)V")]
+pub fn jni_start_tcp(mut env: JNIEnv, _obj: JObject, port: jint, token: JString) {
+    (|| -> JniResult<()> {
+        let port = u16::try_from(port).map_err(|_| anyhow!("Invalid port: {}", port))?;
+        let token = get_token(&mut env, &token)?;
+        start_tcp(port, token)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 在 Unix 套接字上启动远程控制服务
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RemoteServer", "nativeStartUnix", "(Ljava/lang/String;Ljava/lang/String;)V")]
+pub fn jni_start_unix(mut env: JNIEnv, _obj: JObject, path: JString, token: JString) {
+    (|| -> JniResult<()> {
+        let path: String = env.get_string(&path)?.into();
+        let token = get_token(&mut env, &token)?;
+        start_unix(&path, token)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 停止服务，没有运行中的服务时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RemoteServer", "nativeStop", "()Z")]
+pub fn jni_stop(_env: JNIEnv, _obj: JObject) -> jboolean {
+    stop_server() as jboolean
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RemoteServer", "nativeIsRunning", "()Z")]
+pub fn jni_is_running(_env: JNIEnv, _obj: JObject) -> jboolean {
+    is_running() as jboolean
+}
@@ -0,0 +1,195 @@
+//! JNI methods for RuleEngine
+
+use crate::core::globals::RULE_ENGINE;
+use crate::core::rules::{FreezeMode, RuleAction, RuleCondition, RuleConfig, RuleListener};
+use crate::core::saved_list::SavedLocation;
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::pointer_scan::types::PointerChain;
+use crate::search::types::ValueType;
+use anyhow::anyhow;
+use jni::objects::{GlobalRef, JByteArray, JLongArray, JObject, JString, JValue};
+use jni::sys::{jboolean, jdouble, jint, jlong, jsize, JNI_FALSE};
+use jni::{JNIEnv, JavaVM};
+use jni_macro::jni_method;
+use log::error;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 转发到 Java 的 RuleListener
+struct JniRuleListener {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+impl RuleListener for JniRuleListener {
+    fn on_rule_fired(&self, rule_id: u64, value: &[u8]) {
+        let Ok(mut env) = self.vm.attach_current_thread() else {
+            return;
+        };
+        let result = env.byte_array_from_slice(value).and_then(|value| {
+            env.call_method(&self.listener, "onRuleFired", "(J[B)V", &[JValue::Long(rule_id as jlong), JValue::Object(&value)])
+        });
+        if let Err(e) = result {
+            error!("Failed to call onRuleFired: {:?}", e);
+            // 回调抛出的异常不能留给规则线程
+            let _ = env.exception_clear();
+        }
+    }
+}
+
+/// `chain` 非 null 时为指针链，否则为固定地址
+fn location(env: &mut JNIEnv, address: jlong, chain: &JString, data_start: jboolean) -> JniResult<SavedLocation> {
+    if chain.is_null() {
+        return Ok(SavedLocation::Address { address: address as u64 });
+    }
+    let text: String = env.get_string(chain)?.into();
+    let chain = PointerChain::parse(&text, 0).ok_or_else(|| anyhow!("Invalid pointer chain: {}", text))?;
+    Ok(SavedLocation::Pointer {
+        chain,
+        data_start: data_start != JNI_FALSE,
+    })
+}
+
+/// 添加规则，返回规则 id。`operand` 用于相等条件，`threshold` 用于阈值条件
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeAddRule", "(JLjava/lang/String;ZII[BDJJZ)J")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_add_rule(
+    mut env: JNIEnv,
+    _obj: JObject,
+    address: jlong,
+    chain: JString,
+    data_start: jboolean,
+    value_type: jint,
+    condition: jint,
+    operand: JByteArray,
+    threshold: jdouble,
+    interval_ms: jlong,
+    cooldown_ms: jlong,
+    one_shot: jboolean,
+) -> jlong {
+    (|| -> JniResult<jlong> {
+        if interval_ms < 0 || cooldown_ms < 0 {
+            return Err(anyhow!("Invalid interval/cooldown: {}/{}", interval_ms, cooldown_ms));
+        }
+        let location = location(&mut env, address, &chain, data_start)?;
+        let value_type = ValueType::from_id(value_type).ok_or_else(|| anyhow!("Invalid value type: {}", value_type))?;
+        let operand = if operand.is_null() { Vec::new() } else { env.convert_byte_array(&operand)? };
+        let id = RULE_ENGINE.add_rule(RuleConfig {
+            location,
+            value_type,
+            condition: RuleCondition::from_id(condition, operand, threshold)?,
+            interval: Duration::from_millis(interval_ms as u64),
+            cooldown: Duration::from_millis(cooldown_ms as u64),
+            one_shot: one_shot != JNI_FALSE,
+        })?;
+        Ok(id as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 触发时写入 `value`，`address` 为 0 且 `chain` 为 null 时写入规则监视的地址
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeAddWriteAction", "(JJLjava/lang/String;Z[B)Z")]
+pub fn jni_add_write_action(
+    mut env: JNIEnv,
+    _obj: JObject,
+    rule_id: jlong,
+    address: jlong,
+    chain: JString,
+    data_start: jboolean,
+    value: JByteArray,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let location = if address == 0 && chain.is_null() { None } else { Some(location(&mut env, address, &chain, data_start)?) };
+        let value = env.convert_byte_array(&value)?;
+        Ok(RULE_ENGINE.add_action(rule_id as u64, RuleAction::Write { location, value })? as jboolean)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 触发时修改保存列表条目的冻结状态，`mode` 为 0 冻结、1 解冻、2 切换
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeAddFreezeAction", "(JJI)Z")]
+pub fn jni_add_freeze_action(mut env: JNIEnv, _obj: JObject, rule_id: jlong, entry_id: jlong, mode: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mode = match mode {
+            0 => FreezeMode::Freeze,
+            1 => FreezeMode::Unfreeze,
+            2 => FreezeMode::Toggle,
+            _ => return Err(anyhow!("Invalid freeze mode: {}", mode)),
+        };
+        let action = RuleAction::Freeze {
+            entry_id: entry_id as u64,
+            mode,
+        };
+        Ok(RULE_ENGINE.add_action(rule_id as u64, action)? as jboolean)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 触发时运行 Lua 脚本，已有脚本在运行时跳过
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeAddScriptAction", "(JLjava/lang/String;Ljava/lang/String;)Z")]
+pub fn jni_add_script_action(mut env: JNIEnv, _obj: JObject, rule_id: jlong, source: JString, name: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let source: String = env.get_string(&source)?.into();
+        let name: String = env.get_string(&name)?.into();
+        Ok(RULE_ENGINE.add_action(rule_id as u64, RuleAction::RunScript { source, name })? as jboolean)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 触发时通知 RuleListener
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeAddNotifyAction", "(J)Z")]
+pub fn jni_add_notify_action(mut env: JNIEnv, _obj: JObject, rule_id: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> { Ok(RULE_ENGINE.add_action(rule_id as u64, RuleAction::Notify)? as jboolean) })().or_throw(&mut env)
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeRemoveRule", "(J)Z")]
+pub fn jni_remove_rule(mut env: JNIEnv, _obj: JObject, rule_id: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> { Ok(RULE_ENGINE.remove_rule(rule_id as u64)? as jboolean) })().or_throw(&mut env)
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeClearRules", "()V")]
+pub fn jni_clear_rules(mut env: JNIEnv, _obj: JObject) {
+    RULE_ENGINE.clear().or_throw(&mut env)
+}
+
+/// 启用或停用规则，规则不存在时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeSetRuleEnabled", "(JZ)Z")]
+pub fn jni_set_rule_enabled(mut env: JNIEnv, _obj: JObject, rule_id: jlong, enabled: jboolean) -> jboolean {
+    (|| -> JniResult<jboolean> { Ok(RULE_ENGINE.set_enabled(rule_id as u64, enabled != JNI_FALSE)? as jboolean) })().or_throw(&mut env)
+}
+
+/// 规则已触发的次数，规则不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeGetFireCount", "(J)J")]
+pub fn jni_get_fire_count(_env: JNIEnv, _obj: JObject, rule_id: jlong) -> jlong {
+    RULE_ENGINE.fire_count(rule_id as u64).map_or(-1, |count| count as jlong)
+}
+
+/// 全部规则的 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeGetRuleIds", "()[J")]
+pub fn jni_get_rule_ids<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JLongArray<'l> {
+    (|| -> JniResult<JLongArray<'l>> {
+        let ids: Vec<jlong> = RULE_ENGINE.rule_ids().into_iter().map(|id| id as jlong).collect();
+        let array = env.new_long_array(ids.len() as jsize)?;
+        env.set_long_array_region(&array, 0, &ids)?;
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 设置通知监听器，传 null 取消
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/RuleEngine", "nativeSetListener", "(Lmoe/fuqiuluo/mamu/driver/RuleListener;)V")]
+pub fn jni_set_rule_listener(mut env: JNIEnv, _obj: JObject, listener: JObject) {
+    (|| -> JniResult<()> {
+        let listener: Option<Arc<dyn RuleListener>> = if listener.is_null() {
+            None
+        } else {
+            Some(Arc::new(JniRuleListener {
+                vm: env.get_java_vm()?,
+                listener: env.new_global_ref(listener)?,
+            }))
+        };
+        RULE_ENGINE.set_listener(listener);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
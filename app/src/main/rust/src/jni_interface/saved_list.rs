@@ -0,0 +1,279 @@
+//! JNI methods for SavedList
+
+use crate::core::cheat_table::{export_cheat_table, import_cheat_table};
+use crate::core::globals::{FREEZE_MANAGER, SAVED_LIST};
+use crate::core::saved_list::{Resolver, SavedEntry, SavedList, SavedLocation};
+use crate::core::DRIVER_MANAGER;
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::pointer_scan::static_modules::discover_static_modules;
+use crate::pointer_scan::types::PointerChain;
+use anyhow::anyhow;
+use jni::objects::{JByteArray, JObject, JString, JValue};
+use jni::sys::{jboolean, jint, jlong, jobjectArray, jsize, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+use log::warn;
+use std::path::Path;
+
+const SAVED_ENTRY_CLASS: &str = "moe/fuqiuluo/mamu/driver/SavedEntry";
+
+/// 按当前进程重新同步冻结条目
+fn sync_freezes(list: &mut SavedList) -> JniResult<usize> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let freeze = FREEZE_MANAGER.read().map_err(|_| anyhow!("Failed to acquire FreezeManager read lock"))?;
+    Ok(list.sync_freezes(&manager, &freeze))
+}
+
+/// 加入条目，冻结的条目立即生效，返回条目 id
+fn add_entry(entry: SavedEntry) -> JniResult<jlong> {
+    let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+    let frozen = entry.frozen;
+    let id = list.add(entry);
+    if frozen {
+        sync_freezes(&mut list)?;
+    }
+    Ok(id as jlong)
+}
+
+/// 添加一个固定地址的条目
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeAddAddress", "(Ljava/lang/String;JI[BZI)J")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_saved_list_add_address(
+    mut env: JNIEnv,
+    _obj: JObject,
+    label: JString,
+    address: jlong,
+    value_type: jint,
+    value: JByteArray,
+    frozen: jboolean,
+    hotkey: jint,
+) -> jlong {
+    (|| -> JniResult<jlong> {
+        add_entry(SavedEntry {
+            id: 0,
+            label: env.get_string(&label)?.into(),
+            location: SavedLocation::Address { address: address as u64 },
+            value_type,
+            value: env.convert_byte_array(&value)?,
+            frozen: frozen != JNI_FALSE,
+            hotkey,
+        })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 添加一个指针链条目，`chain` 为 `libfoo.so[0]+0x1234->+0x18` 形式
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeAddPointer", "(Ljava/lang/String;Ljava/lang/String;ZI[BZI)J")]
+#[allow(clippy::too_many_arguments)]
+pub fn jni_saved_list_add_pointer(
+    mut env: JNIEnv,
+    _obj: JObject,
+    label: JString,
+    chain: JString,
+    data_start: jboolean,
+    value_type: jint,
+    value: JByteArray,
+    frozen: jboolean,
+    hotkey: jint,
+) -> jlong {
+    (|| -> JniResult<jlong> {
+        let chain_text: String = env.get_string(&chain)?.into();
+        let chain = PointerChain::parse(&chain_text, 0).ok_or_else(|| anyhow!("Invalid pointer chain: {}", chain_text))?;
+        add_entry(SavedEntry {
+            id: 0,
+            label: env.get_string(&label)?.into(),
+            location: SavedLocation::Pointer {
+                chain,
+                data_start: data_start != JNI_FALSE,
+            },
+            value_type,
+            value: env.convert_byte_array(&value)?,
+            frozen: frozen != JNI_FALSE,
+            hotkey,
+        })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 删除条目，它的冻结一并撤下
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeRemove", "(J)Z")]
+pub fn jni_saved_list_remove(mut env: JNIEnv, _obj: JObject, id: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        if !list.remove(id as u64) {
+            return Ok(JNI_FALSE);
+        }
+        sync_freezes(&mut list)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 清空列表
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeClear", "()V")]
+pub fn jni_saved_list_clear(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        list.clear();
+        sync_freezes(&mut list)?;
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 修改名称和热键
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeUpdate", "(JLjava/lang/String;I)Z")]
+pub fn jni_saved_list_update(mut env: JNIEnv, _obj: JObject, id: jlong, label: JString, hotkey: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let label: String = env.get_string(&label)?.into();
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        let Some(entry) = list.get_mut(id as u64) else {
+            return Ok(JNI_FALSE);
+        };
+        entry.label = label;
+        entry.hotkey = hotkey;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 冻结或解冻条目，`value` 为 null 时沿用条目原有的值
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeSetFrozen", "(JZ[B)Z")]
+pub fn jni_saved_list_set_frozen(mut env: JNIEnv, _obj: JObject, id: jlong, frozen: jboolean, value: JByteArray) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let value = if value.is_null() { None } else { Some(env.convert_byte_array(&value)?) };
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        let Some(entry) = list.get_mut(id as u64) else {
+            return Ok(JNI_FALSE);
+        };
+        entry.frozen = frozen != JNI_FALSE;
+        if let Some(value) = value {
+            entry.value = value;
+        }
+        sync_freezes(&mut list)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 全部条目，指针链按当前进程解析，无法解析时地址为 0
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeGetEntries", "()[Lmoe/fuqiuluo/mamu/driver/SavedEntry;")]
+pub fn jni_saved_list_get_entries(mut env: JNIEnv, _obj: JObject) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let list = SAVED_LIST.read().map_err(|_| anyhow!("Failed to acquire SavedList read lock"))?;
+        let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut resolver = Resolver::new(&manager);
+
+        let array = env.new_object_array(list.entries().len() as jsize, SAVED_ENTRY_CLASS, JObject::null())?;
+        for (i, entry) in list.entries().iter().enumerate() {
+            let address = resolver.resolve(&entry.location).unwrap_or(0);
+            let (pointer, data_start) = match &entry.location {
+                SavedLocation::Address { .. } => (JObject::null(), false),
+                SavedLocation::Pointer { chain, data_start } => (JObject::from(env.new_string(chain.format())?), *data_start),
+            };
+            let label = env.new_string(&entry.label)?;
+            let value = env.byte_array_from_slice(&entry.value)?;
+
+            // data class SavedEntry(id, label, address, pointer, dataStart, valueType, value, frozen, hotkey)
+            let obj = env.new_object(
+                SAVED_ENTRY_CLASS,
+                "(JLjava/lang/String;JLjava/lang/String;ZI[BZI)V",
+                &[
+                    JValue::Long(entry.id as jlong),
+                    JValue::Object(&label),
+                    JValue::Long(address as jlong),
+                    JValue::Object(&pointer),
+                    JValue::Bool(data_start as jboolean),
+                    JValue::Int(entry.value_type),
+                    JValue::Object(&value),
+                    JValue::Bool(entry.frozen as jboolean),
+                    JValue::Int(entry.hotkey),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 保存为 JSON 文件
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeSave", "(Ljava/lang/String;)V")]
+pub fn jni_saved_list_save(mut env: JNIEnv, _obj: JObject, path: JString) {
+    (|| -> JniResult<()> {
+        let path: String = env.get_string(&path)?.into();
+        let list = SAVED_LIST.read().map_err(|_| anyhow!("Failed to acquire SavedList read lock"))?;
+        list.save(Path::new(&path))
+    })()
+    .or_throw(&mut env)
+}
+
+/// 从 JSON 文件加载并替换当前列表，返回条目数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeLoad", "(Ljava/lang/String;)I")]
+pub fn jni_saved_list_load(mut env: JNIEnv, _obj: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        let count = list.load(Path::new(&path))?;
+        sync_freezes(&mut list)?;
+        Ok(count as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按当前进程重新解析冻结条目（如重新绑定后），返回生效的冻结数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeSyncFrozen", "()I")]
+pub fn jni_saved_list_sync_frozen(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        Ok(sync_freezes(&mut list)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 导入 Cheat Engine 表（.CT），`replace` 为 true 时替换当前列表，否则追加。
+/// 无法对应的条目（脚本、字符串、符号地址等）被跳过，返回导入的条目数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeImportCheatTable", "(Ljava/lang/String;Z)I")]
+pub fn jni_saved_list_import_cheat_table(mut env: JNIEnv, _obj: JObject, path: JString, replace: jboolean) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let import = import_cheat_table(&std::fs::read_to_string(&path)?)?;
+        if !import.skipped.is_empty() {
+            warn!("SavedList: {} 跳过 {} 个无法导入的条目", path, import.skipped.len());
+        }
+
+        let count = import.entries.len();
+        let mut list = SAVED_LIST.write().map_err(|_| anyhow!("Failed to acquire SavedList write lock"))?;
+        if replace != JNI_FALSE {
+            list.replace(import.entries);
+        } else {
+            for entry in import.entries {
+                list.add(entry);
+            }
+        }
+        sync_freezes(&mut list)?;
+        Ok(count as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 导出为 Cheat Engine 表（.CT），返回导出的条目数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SavedList", "nativeExportCheatTable", "(Ljava/lang/String;)I")]
+pub fn jni_saved_list_export_cheat_table(mut env: JNIEnv, _obj: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let list = SAVED_LIST.read().map_err(|_| anyhow!("Failed to acquire SavedList read lock"))?;
+        let static_modules = {
+            let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            if manager.is_process_bound() { discover_static_modules(&manager)? } else { Vec::new() }
+        };
+
+        let (text, skipped) = export_cheat_table(list.entries(), &static_modules);
+        std::fs::write(&path, text)?;
+        if skipped > 0 {
+            warn!("SavedList: {} 个条目无法导出为 CT", skipped);
+        }
+        Ok((list.entries().len() - skipped) as jint)
+    })()
+    .or_throw(&mut env)
+}
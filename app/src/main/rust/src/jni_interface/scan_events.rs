@@ -0,0 +1,105 @@
+//! JNI methods for ScanEvents
+
+use crate::core::globals::clear_rwlock_poison;
+use crate::core::scan_events::{set_scan_listener, ScanKind, ScanListener};
+use crate::core::wire::{self, ScanEventQueue};
+use crate::ext::jni::{JniResult, JniResultExt};
+use jni::objects::{GlobalRef, JByteArray, JObject, JValue};
+use jni::sys::{jboolean, jint, jlong};
+use jni::{JNIEnv, JavaVM};
+use jni_macro::jni_method;
+use log::error;
+use std::sync::{Arc, RwLock};
+
+/// 事件队列模式下的队列
+static EVENT_QUEUE: RwLock<Option<Arc<ScanEventQueue>>> = RwLock::new(None);
+
+/// 清除事件队列锁的中毒状态，返回中毒的锁数
+pub(crate) fn clear_poisoned_lock() -> usize {
+    clear_rwlock_poison(&EVENT_QUEUE)
+}
+
+/// 转发到 Java 的 ScanListener
+struct JniScanListener {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+impl JniScanListener {
+    fn call(&self, name: &str, sig: &str, args: &[JValue]) {
+        if let Ok(mut env) = self.vm.attach_current_thread()
+            && let Err(e) = env.call_method(&self.listener, name, sig, args)
+        {
+            error!("Failed to call {}: {:?}", name, e);
+            // 回调抛出的异常不能留给分发线程
+            let _ = env.exception_clear();
+        }
+    }
+}
+
+impl ScanListener for JniScanListener {
+    fn on_progress(&self, kind: ScanKind, progress: i32, found: i64) {
+        self.call(
+            "onProgress",
+            "(IIJ)V",
+            &[JValue::Int(kind as jint), JValue::Int(progress), JValue::Long(found as jlong)],
+        );
+    }
+
+    fn on_finished(&self, kind: ScanKind, found: i64, cancelled: bool) {
+        self.call(
+            "onFinished",
+            "(IJZ)V",
+            &[JValue::Int(kind as jint), JValue::Long(found as jlong), JValue::Bool(cancelled as jboolean)],
+        );
+    }
+
+    fn on_error(&self, kind: ScanKind, error_code: i32) {
+        self.call("onError", "(II)V", &[JValue::Int(kind as jint), JValue::Int(error_code)]);
+    }
+}
+
+/// 设置扫描事件监听器，传 null 取消
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanEvents", "nativeSetScanListener", "(Lmoe/fuqiuluo/mamu/driver/ScanListener;)V")]
+pub fn jni_set_scan_listener(mut env: JNIEnv, _obj: JObject, listener: JObject) {
+    (|| -> JniResult<()> {
+        let listener: Option<Arc<dyn ScanListener>> = if listener.is_null() {
+            None
+        } else {
+            Some(Arc::new(JniScanListener {
+                vm: env.get_java_vm()?,
+                listener: env.new_global_ref(listener)?,
+            }))
+        };
+        if let Ok(mut queue) = EVENT_QUEUE.write() {
+            *queue = None;
+        }
+        set_scan_listener(listener);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 改为把事件缓存在队列中，由 nativeDrainEvents 取出，替换已设置的监听器。`capacity` 不大于 0 时关闭队列
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanEvents", "nativeSetEventQueue", "(I)V")]
+pub fn jni_set_event_queue(_env: JNIEnv, _obj: JObject, capacity: jint) {
+    let queue = (capacity > 0).then(|| Arc::new(ScanEventQueue::new(capacity as usize)));
+    if let Ok(mut current) = EVENT_QUEUE.write() {
+        *current = queue.clone();
+    }
+    set_scan_listener(queue.map(|queue| queue as Arc<dyn ScanListener>));
+}
+
+/// 取出队列中的全部事件，编码为 `ScanEventBatch`，未开启队列时为空批次
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanEvents", "nativeDrainEvents", "()[B")]
+pub fn jni_drain_events<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        let batch = EVENT_QUEUE
+            .read()
+            .ok()
+            .and_then(|queue| queue.as_ref().map(|queue| queue.drain()))
+            .unwrap_or_default();
+        Ok(env.byte_array_from_slice(&wire::encode(&batch))?)
+    })()
+    .or_throw(&mut env)
+}
@@ -0,0 +1,106 @@
+//! JNI methods for per-package scan profiles
+
+use crate::core::globals::SCAN_PROFILES;
+use crate::core::scan_profile::ScanProfile;
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JObject, JString};
+use jni::sys::{jboolean, jobjectArray, jsize, jstring, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 设置配置目录，不存在时创建
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeInit", "(Ljava/lang/String;)Z")]
+pub fn jni_scan_profile_init(mut env: JNIEnv, _obj: JObject, dir: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let dir: String = env.get_string(&dir)?.into();
+        let mut profiles = SCAN_PROFILES
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore write lock"))?;
+        profiles.set_dir(dir)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 保存 JSON 格式的配置
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeSave", "(Ljava/lang/String;)Z")]
+pub fn jni_scan_profile_save(mut env: JNIEnv, _obj: JObject, json: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let json: String = env.get_string(&json)?.into();
+        let profile: ScanProfile = serde_json::from_str(&json)?;
+        let mut profiles = SCAN_PROFILES
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore write lock"))?;
+        profiles.save(&profile)?;
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 读取配置，不存在时返回 null
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeLoad", "(Ljava/lang/String;)Ljava/lang/String;")]
+pub fn jni_scan_profile_load(mut env: JNIEnv, _obj: JObject, package: JString) -> jstring {
+    (|| -> JniResult<jstring> {
+        let package: String = env.get_string(&package)?.into();
+        let profile = SCAN_PROFILES
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore read lock"))?
+            .load(&package)?;
+
+        match profile {
+            Some(profile) => Ok(env.new_string(serde_json::to_string(&profile)?)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// 删除配置
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeDelete", "(Ljava/lang/String;)Z")]
+pub fn jni_scan_profile_delete(mut env: JNIEnv, _obj: JObject, package: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let package: String = env.get_string(&package)?.into();
+        let mut profiles = SCAN_PROFILES
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore write lock"))?;
+        Ok(if profiles.delete(&package)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已保存配置的包名
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeList", "()[Ljava/lang/String;")]
+pub fn jni_scan_profile_list(mut env: JNIEnv, _obj: JObject) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let packages = SCAN_PROFILES
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore read lock"))?
+            .list()?;
+
+        let string_class = env.find_class("java/lang/String")?;
+        let array = env.new_object_array(packages.len() as jsize, &string_class, JObject::null())?;
+        for (i, package) in packages.iter().enumerate() {
+            let value = env.new_string(package)?;
+            env.set_object_array_element(&array, i as jsize, value)?;
+        }
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 当前绑定进程的配置，未绑定或没有配置时返回 null
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScanProfiles", "nativeGetActive", "()Ljava/lang/String;")]
+pub fn jni_scan_profile_get_active(mut env: JNIEnv, _obj: JObject) -> jstring {
+    (|| -> JniResult<jstring> {
+        let profiles = SCAN_PROFILES
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire ScanProfileStore read lock"))?;
+
+        match profiles.active() {
+            Some(profile) => Ok(env.new_string(serde_json::to_string(profile)?)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
@@ -0,0 +1,108 @@
+//! JNI methods for ScriptEngine
+
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::script::{is_running, run_script, set_script_output, stop_script, ScriptOutput};
+use jni::objects::{GlobalRef, JObject, JString, JValue};
+use jni::sys::{jboolean, jint};
+use jni::{JNIEnv, JavaVM};
+use jni_macro::jni_method;
+use log::error;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 转发到 Java 的 ScriptOutputListener
+struct JniScriptOutput {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+/// 回调失败时记录并清除异常，回调抛出的异常不能留给脚本线程
+fn check_call<T>(env: &mut JNIEnv, name: &str, result: jni::errors::Result<T>) {
+    if let Err(e) = result {
+        error!("Failed to call {}: {:?}", name, e);
+        let _ = env.exception_clear();
+    }
+}
+
+impl ScriptOutput for JniScriptOutput {
+    fn on_print(&self, line: &str) {
+        let Ok(mut env) = self.vm.attach_current_thread() else {
+            return;
+        };
+        let result = env
+            .new_string(line)
+            .and_then(|line| env.call_method(&self.listener, "onPrint", "(Ljava/lang/String;)V", &[JValue::Object(&line)]));
+        check_call(&mut env, "onPrint", result);
+    }
+
+    fn on_finished(&self, error: Option<&str>, stopped: bool) {
+        let Ok(mut env) = self.vm.attach_current_thread() else {
+            return;
+        };
+        let result = match error {
+            Some(error) => env.new_string(error).map(JObject::from),
+            None => Ok(JObject::null()),
+        }
+        .and_then(|error| {
+            env.call_method(
+                &self.listener,
+                "onFinished",
+                "(Ljava/lang/String;Z)V",
+                &[JValue::Object(&error), JValue::Bool(stopped as jboolean)],
+            )
+        });
+        check_call(&mut env, "onFinished", result);
+    }
+}
+
+/// 在后台运行脚本，返回任务 id，已有脚本在运行时抛出异常
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScriptEngine", "nativeRunScript", "(Ljava/lang/String;Ljava/lang/String;)I")]
+pub fn jni_run_script(mut env: JNIEnv, _obj: JObject, source: JString, name: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let source: String = env.get_string(&source)?.into();
+        let name: String = env.get_string(&name)?.into();
+        run_script(source, name)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 加载并运行脚本文件，返回任务 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScriptEngine", "nativeRunScriptFile", "(Ljava/lang/String;)I")]
+pub fn jni_run_script_file(mut env: JNIEnv, _obj: JObject, path: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let path: String = env.get_string(&path)?.into();
+        let source = std::fs::read_to_string(&path)?;
+        let name = Path::new(&path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or(path);
+        run_script(source, name)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 请求停止当前脚本
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScriptEngine", "nativeStopScript", "()Z")]
+pub fn jni_stop_script(_env: JNIEnv, _obj: JObject) -> jboolean {
+    stop_script() as jboolean
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScriptEngine", "nativeIsScriptRunning", "()Z")]
+pub fn jni_is_script_running(_env: JNIEnv, _obj: JObject) -> jboolean {
+    is_running() as jboolean
+}
+
+/// 设置输出监听器，传 null 取消
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ScriptEngine", "nativeSetOutputListener", "(Lmoe/fuqiuluo/mamu/driver/ScriptOutputListener;)V")]
+pub fn jni_set_script_output_listener(mut env: JNIEnv, _obj: JObject, listener: JObject) {
+    (|| -> JniResult<()> {
+        let output: Option<Arc<dyn ScriptOutput>> = if listener.is_null() {
+            None
+        } else {
+            Some(Arc::new(JniScriptOutput {
+                vm: env.get_java_vm()?,
+                listener: env.new_global_ref(listener)?,
+            }))
+        };
+        set_script_output(output);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
@@ -1,19 +1,23 @@
 //! JNI methods for SearchEngine.
 
+use crate::core::globals::OP_QUEUE;
 use crate::core::DRIVER_MANAGER;
+use crate::core::error::MamuError;
+use crate::core::region_type::query_ranges_by_mask;
+use crate::core::wire;
 use crate::ext::jni::{JniResult, JniResultExt};
 use crate::search::SearchResultItem;
+use crate::search::engine::obfuscation;
 use crate::search::engine::{SEARCH_ENGINE_MANAGER, SHARED_BUFFER_SIZE, SearchProgressCallback};
 use crate::search::parser::parse_search_query;
 use crate::search::result_manager::SearchResultMode;
-use crate::search::types::ValueType;
+use crate::search::types::{FloatCompare, ValueType};
 use anyhow::anyhow;
-use jni::objects::{GlobalRef, JIntArray, JLongArray, JObject, JString, JValue};
-use jni::sys::{JNI_FALSE, JNI_TRUE, jboolean, jint, jlong, jobjectArray};
+use jni::objects::{GlobalRef, JBooleanArray, JByteArray, JIntArray, JLongArray, JObject, JObjectArray, JString, JValue};
+use jni::sys::{JNI_FALSE, JNI_TRUE, jboolean, jdouble, jint, jlong, jobjectArray, jsize, jstring};
 use jni::{JNIEnv, JavaVM};
 use jni_macro::jni_method;
 use log::{Level, error, log_enabled, warn};
-use std::ops::Not;
 use std::sync::Arc;
 
 struct JniCallback {
@@ -52,10 +56,15 @@ fn jint_to_value_type(value: jint) -> Option<ValueType> {
         5 => Some(ValueType::Double),
         6 => Some(ValueType::Auto),
         7 => Some(ValueType::Xor),
+        8 => Some(ValueType::Vec3),
         _ => None,
     }
 }
 
+fn jint_to_float_compare(mode: jint, epsilon: jdouble) -> JniResult<FloatCompare> {
+    FloatCompare::from_id(mode, epsilon).ok_or_else(|| anyhow!("Invalid float compare mode {} with epsilon {}", mode, epsilon))
+}
+
 fn format_value(bytes: &[u8], typ: ValueType) -> String {
     match typ {
         ValueType::Byte => {
@@ -105,6 +114,14 @@ fn format_value(bytes: &[u8], typ: ValueType) -> String {
                 "N/A".to_string()
             }
         },
+        ValueType::Vec3 => {
+            if bytes.len() >= 12 {
+                let component = |i: usize| f32::from_le_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]);
+                format!("{}|{}|{}", component(0), component(1), component(2))
+            } else {
+                "N/A".to_string()
+            }
+        },
     }
 }
 
@@ -164,7 +181,8 @@ pub fn jni_clear_shared_buffer(mut env: JNIEnv, _class: JObject) {
 }
 
 /// Starts an async search. Returns immediately. Progress is communicated via the shared buffer.
-#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartSearchAsync", "(Ljava/lang/String;I[JZZ)Z")]
+/// `float_compare`/`float_epsilon` select how fixed float values are compared, see `FloatCompare::from_id`.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartSearchAsync", "(Ljava/lang/String;I[JZZID)Z")]
 pub fn jni_start_search_async(
     mut env: JNIEnv,
     _class: JObject,
@@ -173,13 +191,17 @@ pub fn jni_start_search_async(
     regions: JLongArray,
     use_deep_search: jboolean,
     keep_results: jboolean,
+    float_compare: jint,
+    float_epsilon: jdouble,
 ) -> jboolean {
     (|| -> JniResult<jboolean> {
         let query: String = env.get_string(&query_str)?.into();
 
         let value_type = jint_to_value_type(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
 
-        let search_query = parse_search_query(&query, value_type).map_err(|e| anyhow!("Parse error: {}", e))?;
+        let search_query = parse_search_query(&query, value_type)
+            .map_err(|e| anyhow!("Parse error: {}", e))?
+            .with_float_compare(jint_to_float_compare(float_compare, float_epsilon)?);
 
         let regions_len = env.get_array_length(&regions)? as usize;
         if regions_len % 2 != 0 {
@@ -202,15 +224,98 @@ pub fn jni_start_search_async(
     .or_throw(&mut env)
 }
 
+/// Resolves a region type mask (bit n = MemoryRange ordinal n) to the bound process's matching ranges.
+fn resolve_region_mask(region_mask: jlong) -> JniResult<Vec<(u64, u64)>> {
+    let manager = DRIVER_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    query_ranges_by_mask(&manager, region_mask as u64)
+}
+
+/// Starts an async search over every region whose type is in `region_mask`.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartSearchInRangesAsync", "(Ljava/lang/String;IJZZID)Z")]
+pub fn jni_start_search_in_ranges_async(
+    mut env: JNIEnv,
+    _class: JObject,
+    query_str: JString,
+    default_type: jint,
+    region_mask: jlong,
+    use_deep_search: jboolean,
+    keep_results: jboolean,
+    float_compare: jint,
+    float_epsilon: jdouble,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let query: String = env.get_string(&query_str)?.into();
+
+        let value_type = jint_to_value_type(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
+
+        let search_query = parse_search_query(&query, value_type)
+            .map_err(|e| anyhow!("Parse error: {}", e))?
+            .with_float_compare(jint_to_float_compare(float_compare, float_epsilon)?);
+
+        let memory_regions = resolve_region_mask(region_mask)?;
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_search_async(search_query, memory_regions, use_deep_search != JNI_FALSE, keep_results != JNI_FALSE)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Starts an async search over the pages that hold at least one current result.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartSearchInResultPagesAsync", "(Ljava/lang/String;IZID)Z")]
+pub fn jni_start_search_in_result_pages_async(
+    mut env: JNIEnv,
+    _class: JObject,
+    query_str: JString,
+    default_type: jint,
+    use_deep_search: jboolean,
+    float_compare: jint,
+    float_epsilon: jdouble,
+) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let query: String = env.get_string(&query_str)?.into();
+
+        let value_type = jint_to_value_type(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
+
+        let search_query = parse_search_query(&query, value_type)
+            .map_err(|e| anyhow!("Parse error: {}", e))?
+            .with_float_compare(jint_to_float_compare(float_compare, float_epsilon)?);
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_search_in_result_pages_async(search_query, use_deep_search != JNI_FALSE)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
 /// Starts an async refine search. Returns immediately.
-#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartRefineAsync", "(Ljava/lang/String;I)Z")]
-pub fn jni_start_refine_async(mut env: JNIEnv, _class: JObject, query_str: JString, default_type: jint) -> jboolean {
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartRefineAsync", "(Ljava/lang/String;IID)Z")]
+pub fn jni_start_refine_async(
+    mut env: JNIEnv,
+    _class: JObject,
+    query_str: JString,
+    default_type: jint,
+    float_compare: jint,
+    float_epsilon: jdouble,
+) -> jboolean {
     (|| -> JniResult<jboolean> {
         let query: String = env.get_string(&query_str)?.into();
 
         let value_type = jint_to_value_type(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
 
-        let search_query = parse_search_query(&query, value_type).map_err(|e| anyhow!("Parse error: {}", e))?;
+        let search_query = parse_search_query(&query, value_type)
+            .map_err(|e| anyhow!("Parse error: {}", e))?
+            .with_float_compare(jint_to_float_compare(float_compare, float_epsilon)?);
 
         let mut manager = SEARCH_ENGINE_MANAGER
             .write()
@@ -223,6 +328,147 @@ pub fn jni_start_refine_async(mut env: JNIEnv, _class: JObject, query_str: JStri
     .or_throw(&mut env)
 }
 
+/// Starts an async near-by search around the current results. Returns immediately.
+/// Keeps the results that have a value matching `query` within `distance` bytes.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartNearbyRefineAsync", "(Ljava/lang/String;IJ)Z")]
+pub fn jni_start_nearby_refine_async(mut env: JNIEnv, _class: JObject, query_str: JString, default_type: jint, distance: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let query: String = env.get_string(&query_str)?.into();
+
+        let value_type = jint_to_value_type(default_type).ok_or_else(|| anyhow!("Invalid value type: {}", default_type))?;
+
+        let search_query = parse_search_query(&query, value_type).map_err(|e| anyhow!("Parse error: {}", e))?;
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_nearby_refine_async(search_query, distance.max(0) as u64)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Starts an async obfuscated value search over the current results. Returns immediately.
+/// Keeps the results that decode to `value` under one of the `transforms` bits.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartObfuscatedRefineAsync", "(II)Z")]
+pub fn jni_start_obfuscated_refine_async(mut env: JNIEnv, _class: JObject, value: jint, transforms: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_obfuscated_refine_async(value, transforms as u32)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets the transforms still matching each result of a page, 0 for results no obfuscated search ran on.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetObfuscationMasks", "(II)[I")]
+pub fn jni_get_obfuscation_masks<'l>(mut env: JNIEnv<'l>, _class: JObject, offset: jint, count: jint) -> JIntArray<'l> {
+    (|| -> JniResult<JIntArray<'l>> {
+        if offset < 0 || count < 0 {
+            return Err(anyhow!("Invalid result page: offset={}, count={}", offset, count));
+        }
+        let masks: Vec<jint> = {
+            let manager = SEARCH_ENGINE_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+            let items = manager.get_results(offset as usize, count as usize)?;
+            result_entries(&items)
+                .iter()
+                .map(|&(address, _)| manager.obfuscation_mask(address) as jint)
+                .collect()
+        };
+        let array = env.new_int_array(masks.len() as jsize)?;
+        env.set_int_array_region(&array, 0, &masks)?;
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Reads the value at `address` and decodes it with a single transform bit.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeDecodeObfuscated", "(JI)I")]
+pub fn jni_decode_obfuscated(mut env: JNIEnv, _class: JObject, address: jlong, transform: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        obfuscation::decode_at(&manager, address as u64, transform as u32)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Takes a named snapshot of the current result values. Returns the number of values recorded.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeTakeSnapshot", "(Ljava/lang/String;)I")]
+pub fn jni_take_snapshot(mut env: JNIEnv, _class: JObject, name: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let name: String = env.get_string(&name)?.into();
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        Ok(manager.take_snapshot(&name)? as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Deletes a named snapshot.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeRemoveSnapshot", "(Ljava/lang/String;)Z")]
+pub fn jni_remove_snapshot(mut env: JNIEnv, _class: JObject, name: JString) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let name: String = env.get_string(&name)?.into();
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        Ok(if manager.remove_snapshot(&name)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets the names of all snapshots, sorted.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetSnapshotNames", "()[Ljava/lang/String;")]
+pub fn jni_get_snapshot_names<'l>(mut env: JNIEnv<'l>, _class: JObject) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let names = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?
+            .snapshot_names()?;
+
+        let array = env.new_object_array(names.len() as jsize, "java/lang/String", JObject::null())?;
+        for (i, (name, _)) in names.iter().enumerate() {
+            let name = env.new_string(name)?;
+            env.set_object_array_element(&array, i as jsize, name)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Starts an async refine against a named snapshot. Returns immediately.
+/// Keeps the results whose value changed since the snapshot when `changed` is true, otherwise the unchanged ones.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartSnapshotRefineAsync", "(Ljava/lang/String;Z)Z")]
+pub fn jni_start_snapshot_refine_async(mut env: JNIEnv, _class: JObject, name: JString, changed: jboolean) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let name: String = env.get_string(&name)?.into();
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_snapshot_refine_async(name, changed != JNI_FALSE)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
 /// Checks if a search is currently running.
 #[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeIsSearching", "()Z")]
 pub fn jni_is_searching(mut env: JNIEnv, _class: JObject) -> jboolean {
@@ -339,28 +585,9 @@ pub fn jni_get_results(mut env: JNIEnv, _class: JObject, start: jint, size: jint
         if filter.is_active() {
             results = results
                 .into_iter()
-                .filter(|(_idx, item)| {
-                    if filter.enable_address_filter {
-                        let addr = match item {
-                            SearchResultItem::Exact(exact) => exact.address,
-                            SearchResultItem::Fuzzy(fuzzy) => fuzzy.address,
-                        };
-                        if addr < filter.address_start || addr > filter.address_end {
-                            return false;
-                        }
-                    }
-
-                    if filter.enable_type_filter && filter.type_ids.is_empty().not() {
-                        let typ = match item {
-                            SearchResultItem::Exact(exact) => exact.typ,
-                            SearchResultItem::Fuzzy(fuzzy) => fuzzy.value_type,
-                        };
-                        if !filter.type_ids.contains(&typ) {
-                            return false;
-                        }
-                    }
-
-                    true
+                .filter(|(_idx, item)| match item {
+                    SearchResultItem::Exact(exact) => filter.matches(exact.address, exact.typ),
+                    SearchResultItem::Fuzzy(fuzzy) => filter.matches(fuzzy.address, fuzzy.value_type),
                 })
                 .collect::<Vec<(usize, SearchResultItem)>>();
         }
@@ -378,19 +605,25 @@ pub fn jni_get_results(mut env: JNIEnv, _class: JObject, start: jint, size: jint
         let array = env.new_object_array(results.len() as jint, &class, JObject::null())?;
 
         let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let _op = OP_QUEUE.interactive();
+
+        // 精确结果的当前值一次批量读取（fuzzy 结果使用已存储的值）
+        let requests: Vec<(u64, usize)> = results
+            .iter()
+            .map(|(_, item)| match item {
+                SearchResultItem::Exact(exact) => (exact.address, exact.typ.size()),
+                SearchResultItem::Fuzzy(_) => (0, 0),
+            })
+            .collect();
+        let mut current_values = driver_manager.read_memory_batch(&requests).into_iter();
 
         for (i, (native_position, item)) in results.into_iter().enumerate() {
+            let current_value = current_values.next();
             let obj = match item {
                 SearchResultItem::Exact(exact) => {
-                    let value_str = {
-                        let size = exact.typ.size();
-                        let mut buffer = vec![0u8; size];
-
-                        if driver_manager.read_memory_unified(exact.address, &mut buffer, None).is_ok() {
-                            format_value(&buffer, exact.typ)
-                        } else {
-                            "N/A".to_string()
-                        }
+                    let value_str = match current_value {
+                        Some(Ok(buffer)) => format_value(&buffer, exact.typ),
+                        _ => "N/A".to_string(),
                     };
 
                     let value_jstring = env.new_string(&value_str)?;
@@ -454,6 +687,222 @@ pub fn jni_get_total_result_count(mut env: JNIEnv, _class: JObject) -> jlong {
     .or_throw(&mut env)
 }
 
+/// Gets the result count addressable by nativeGetSearchResults, clamped to Int.MAX_VALUE.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetSearchResultCount", "()I")]
+pub fn jni_get_search_result_count(mut env: JNIEnv, _class: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        Ok(manager.get_total_count()?.min(jint::MAX as usize) as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Little-endian value bytes zero-extended to a jlong.
+fn raw_value(bytes: &[u8]) -> jlong {
+    let mut raw = [0u8; 8];
+    let len = bytes.len().min(8);
+    raw[..len].copy_from_slice(&bytes[..len]);
+    i64::from_le_bytes(raw)
+}
+
+/// Address and type of each result.
+fn result_entries(items: &[SearchResultItem]) -> Vec<(u64, ValueType)> {
+    items
+        .iter()
+        .map(|item| match item {
+            SearchResultItem::Exact(exact) => (exact.address, exact.typ),
+            SearchResultItem::Fuzzy(fuzzy) => (fuzzy.address, fuzzy.value_type),
+        })
+        .collect()
+}
+
+/// Reads the current values of `entries` in one coalesced batch, returns raw values and whether each read succeeded.
+fn read_result_values(entries: &[(u64, ValueType)]) -> JniResult<(Vec<jlong>, Vec<jboolean>)> {
+    let requests: Vec<(u64, usize)> = entries.iter().map(|&(address, typ)| (address, typ.size())).collect();
+    let mut values = vec![0 as jlong; entries.len()];
+    let mut valid = vec![JNI_FALSE; entries.len()];
+
+    let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let _op = OP_QUEUE.interactive();
+    for (i, result) in driver_manager.read_memory_batch(&requests).into_iter().enumerate() {
+        if let Ok(bytes) = result {
+            values[i] = raw_value(&bytes);
+            valid[i] = JNI_TRUE;
+        }
+    }
+    Ok((values, valid))
+}
+
+/// One page of results: total count, address and type of each result, raw values and whether each value is known.
+type ResultPage = (usize, Vec<(u64, ValueType)>, Vec<jlong>, Vec<jboolean>);
+
+fn load_result_page(offset: jint, count: jint, reread: bool) -> JniResult<ResultPage> {
+    if offset < 0 || count < 0 {
+        return Err(anyhow!("Invalid result page: offset={}, count={}", offset, count));
+    }
+
+    let (total, items) = {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+        (manager.get_total_count()?, manager.get_results(offset as usize, count as usize)?)
+    };
+
+    let entries = result_entries(&items);
+    let (values, valid) = if reread {
+        read_result_values(&entries)?
+    } else {
+        let mut values = vec![0 as jlong; entries.len()];
+        let mut valid = vec![JNI_FALSE; entries.len()];
+        for (i, item) in items.iter().enumerate() {
+            if let SearchResultItem::Fuzzy(fuzzy) = item {
+                let value = fuzzy.value;
+                values[i] = raw_value(&value[..fuzzy.value_type.size()]);
+                valid[i] = JNI_TRUE;
+            }
+        }
+        (values, valid)
+    };
+    Ok((total, entries, values, valid))
+}
+
+/// Gets one page of the current result set as primitive arrays instead of one object per result.
+/// With `reread` the values are read fresh from memory in one batch; otherwise fuzzy results carry
+/// their stored value and exact results have none. The filter is not applied, indices match nativeRemoveResult.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetSearchResults", "(IIZ)Lmoe/fuqiuluo/mamu/driver/SearchResultPage;")]
+pub fn jni_get_search_results<'l>(mut env: JNIEnv<'l>, _class: JObject, offset: jint, count: jint, reread: jboolean) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let (total, entries, values, valid) = load_result_page(offset, count, reread != JNI_FALSE)?;
+
+        let addresses: Vec<jlong> = entries.iter().map(|&(address, _)| address as jlong).collect();
+        let types: Vec<jint> = entries.iter().map(|&(_, typ)| typ.to_id()).collect();
+
+        let address_array = env.new_long_array(addresses.len() as jsize)?;
+        env.set_long_array_region(&address_array, 0, &addresses)?;
+        let type_array = env.new_int_array(types.len() as jsize)?;
+        env.set_int_array_region(&type_array, 0, &types)?;
+        let value_array = env.new_long_array(values.len() as jsize)?;
+        env.set_long_array_region(&value_array, 0, &values)?;
+        let valid_array = env.new_boolean_array(valid.len() as jsize)?;
+        env.set_boolean_array_region(&valid_array, 0, &valid)?;
+
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/SearchResultPage",
+            "(IJ[J[I[J[Z)V",
+            &[
+                JValue::Int(offset),
+                JValue::Long(total as jlong),
+                (&address_array).into(),
+                (&type_array).into(),
+                (&value_array).into(),
+                (&valid_array).into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Same page as nativeGetSearchResults, encoded as a `SearchResultPage` protobuf message.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetSearchResultsProto", "(IIZ)[B")]
+pub fn jni_get_search_results_proto<'l>(mut env: JNIEnv<'l>, _class: JObject, offset: jint, count: jint, reread: jboolean) -> JByteArray<'l> {
+    (|| -> JniResult<JByteArray<'l>> {
+        let (total, entries, values, valid) = load_result_page(offset, count, reread != JNI_FALSE)?;
+        let page = wire::SearchResultPage {
+            offset: offset as u32,
+            total: total as u64,
+            results: entries
+                .iter()
+                .zip(values.iter().zip(valid.iter()))
+                .map(|(&(address, typ), (&value, &valid))| wire::SearchResult {
+                    address,
+                    value_type: typ.to_id(),
+                    value,
+                    valid: valid != JNI_FALSE,
+                })
+                .collect(),
+        };
+        Ok(env.byte_array_from_slice(&wire::encode(&page))?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Reads fresh values of the results starting at `offset` into `values` / `valid`, one per array slot,
+/// so the visible rows of the result list can be refreshed periodically without a refine.
+/// Returns the number of results read, at most the length of `values`.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeRefreshResultValues", "(I[J[Z)I")]
+pub fn jni_refresh_result_values(mut env: JNIEnv, _class: JObject, offset: jint, values: JLongArray, valid: JBooleanArray) -> jint {
+    (|| -> JniResult<jint> {
+        let count = env.get_array_length(&values)?;
+        if offset < 0 || env.get_array_length(&valid)? < count {
+            return Err(anyhow!("Invalid refresh window: offset={}, count={}", offset, count));
+        }
+
+        let items = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?
+            .get_results(offset as usize, count as usize)?;
+        let (fresh_values, fresh_valid) = read_result_values(&result_entries(&items))?;
+
+        env.set_long_array_region(&values, 0, &fresh_values)?;
+        env.set_boolean_array_region(&valid, 0, &fresh_valid)?;
+        Ok(fresh_values.len() as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Results written per driver batch by nativeWriteToResults.
+const WRITE_RESULTS_BATCH: usize = 4096;
+
+/// Writes the same value bytes to every result in [offset, offset + count), `count` < 0 meaning up to the end.
+/// Results excluded by the active filter are skipped. Writes are submitted in coalesced batches.
+/// Returns the number of successful writes.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeWriteToResults", "(II[B)I")]
+pub fn jni_write_to_results(mut env: JNIEnv, _class: JObject, offset: jint, count: jint, value: JByteArray) -> jint {
+    (|| -> JniResult<jint> {
+        let value = env.convert_byte_array(&value)?;
+        if offset < 0 || value.is_empty() {
+            return Err(anyhow!("Invalid result write: offset={}, {} bytes", offset, value.len()));
+        }
+
+        let search_manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+        let total = search_manager.get_total_count()?;
+        let start = offset as usize;
+        let end = if count < 0 { total } else { start.saturating_add(count as usize).min(total) };
+        let filter = search_manager.get_filter();
+
+        let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        if !driver_manager.is_process_bound() {
+            return Err(MamuError::not_bound().into());
+        }
+
+        let mut written = 0usize;
+        let mut batch_start = start;
+        while batch_start < end {
+            let items = search_manager.get_results(batch_start, WRITE_RESULTS_BATCH.min(end - batch_start))?;
+            if items.is_empty() {
+                break;
+            }
+            batch_start += items.len();
+
+            let writes: Vec<(u64, &[u8])> = result_entries(&items)
+                .into_iter()
+                .filter(|&(address, typ)| filter.matches(address, typ))
+                .map(|(address, _)| (address, value.as_slice()))
+                .collect();
+            let _op = OP_QUEUE.interactive();
+            written += driver_manager.write_memory_batch(&writes).into_iter().filter(|&ok| ok).count();
+        }
+
+        Ok(written.min(jint::MAX as usize) as jint)
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeClearSearchResults", "()V")]
 pub fn jni_clear_result(mut env: JNIEnv, _class: JObject) {
     (|| -> JniResult<()> {
@@ -472,6 +921,61 @@ pub fn jni_clear_result(mut env: JNIEnv, _class: JObject) {
     .or_throw(&mut env);
 }
 
+/// Restores the result set from before the last refine step.
+/// Returns the restored result count, or -1 if there is nothing to undo.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeUndoSearch", "()J")]
+pub fn jni_undo_search(mut env: JNIEnv, _class: JObject) -> jlong {
+    (|| -> JniResult<jlong> {
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        Ok(manager.undo_search()?.map_or(-1, |count| count as jlong))
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets the undo steps of the current search session, oldest first.
+#[jni_method(
+    70,
+    "moe/fuqiuluo/mamu/driver/SearchEngine",
+    "nativeGetSearchHistory",
+    "()[Lmoe/fuqiuluo/mamu/driver/SearchStepEntry;"
+)]
+pub fn jni_get_search_history(mut env: JNIEnv, _class: JObject) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let entries = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?
+            .search_history();
+
+        let class = env.find_class("moe/fuqiuluo/mamu/driver/SearchStepEntry")?;
+        let array = env.new_object_array(entries.len() as jint, &class, JObject::null())?;
+
+        for (i, entry) in entries.iter().enumerate() {
+            let label = env.new_string(&entry.label)?;
+            let mode = match entry.mode {
+                SearchResultMode::Exact => 0,
+                SearchResultMode::Fuzzy => 1,
+            };
+            let obj = env.new_object(
+                &class,
+                "(Ljava/lang/String;IJZ)V",
+                &[
+                    JValue::Object(&label),
+                    JValue::Int(mode),
+                    JValue::Long(entry.count as jlong),
+                    JValue::Bool(if entry.spilled { JNI_TRUE } else { JNI_FALSE }),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jint, obj)?;
+        }
+
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
 #[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeRemoveResult", "(I)Z")]
 pub fn jni_remove_result(mut env: JNIEnv, _class: JObject, index: jint) -> jboolean {
     (|| -> JniResult<jboolean> {
@@ -619,6 +1123,185 @@ pub fn jni_get_compatibility_mode(mut env: JNIEnv, _class: JObject) -> jboolean
     .or_throw(&mut env)
 }
 
+/// Sets unaligned search.
+/// When enabled, new searches check every byte offset instead of stepping by the value alignment.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeSetUnalignedSearch", "(Z)V")]
+pub fn jni_set_unaligned_search(mut env: JNIEnv, _class: JObject, enabled: jboolean) {
+    (|| -> JniResult<()> {
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.set_unaligned_search(enabled != JNI_FALSE);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets unaligned search.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetUnalignedSearch", "()Z")]
+pub fn jni_get_unaligned_search(mut env: JNIEnv, _class: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        Ok(if manager.get_unaligned_search() { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// Sets background mode.
+/// When enabled, new searches and refines run on low-priority workers with throttled reads.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeSetBackgroundMode", "(Z)V")]
+pub fn jni_set_background_mode(mut env: JNIEnv, _class: JObject, enabled: jboolean) {
+    (|| -> JniResult<()> {
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.set_background_mode(enabled != JNI_FALSE);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets background mode.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetBackgroundMode", "()Z")]
+pub fn jni_get_background_mode(mut env: JNIEnv, _class: JObject) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        Ok(if manager.get_background_mode() { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// Sets the in-memory budget of fuzzy initial scan results in bytes.
+/// Results beyond it are spilled to the cache dir during the scan.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeSetFuzzyMemoryBudget", "(J)V")]
+pub fn jni_set_fuzzy_memory_budget(mut env: JNIEnv, _class: JObject, budget: jlong) {
+    (|| -> JniResult<()> {
+        if budget < 0 {
+            return Err(anyhow!("Invalid fuzzy memory budget: {}", budget));
+        }
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.set_fuzzy_memory_budget(budget as usize);
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets the in-memory budget of fuzzy initial scan results in bytes.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetFuzzyMemoryBudget", "()J")]
+pub fn jni_get_fuzzy_memory_budget(mut env: JNIEnv, _class: JObject) -> jlong {
+    (|| -> JniResult<jlong> {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        Ok(manager.get_fuzzy_memory_budget() as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Gets the path of the report written by the last finished search operation, or null.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeGetLastReportPath", "()Ljava/lang/String;")]
+pub fn jni_get_last_report_path(mut env: JNIEnv, _class: JObject) -> jstring {
+    (|| -> JniResult<jstring> {
+        let manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        match manager.last_report_path() {
+            Some(path) => Ok(env.new_string(path.to_string_lossy())?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// Samples up to `count` results across the whole result set, with values read fresh from memory.
+#[jni_method(
+    70,
+    "moe/fuqiuluo/mamu/driver/SearchEngine",
+    "nativeSampleResults",
+    "(IZ)[Lmoe/fuqiuluo/mamu/driver/SearchResultItem;"
+)]
+pub fn jni_sample_results(mut env: JNIEnv, _class: JObject, count: jint, random: jboolean) -> jobjectArray {
+    (|| -> JniResult<jobjectArray> {
+        let search_manager = SEARCH_ENGINE_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+
+        let current_mode = search_manager.get_current_mode()?;
+        let samples = search_manager.sample_results(count.max(0) as usize, random != JNI_FALSE)?;
+
+        let class = match current_mode {
+            SearchResultMode::Exact => env.find_class("moe/fuqiuluo/mamu/driver/ExactSearchResultItem")?,
+            SearchResultMode::Fuzzy => env.find_class("moe/fuqiuluo/mamu/driver/FuzzySearchResultItem")?,
+        };
+
+        let array = env.new_object_array(samples.len() as jint, &class, JObject::null())?;
+
+        let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let _op = OP_QUEUE.interactive();
+        let requests: Vec<(u64, usize)> = samples
+            .iter()
+            .map(|(_, item)| match item {
+                SearchResultItem::Exact(exact) => (exact.address, exact.typ.size()),
+                SearchResultItem::Fuzzy(fuzzy) => (fuzzy.address, fuzzy.value_type.size()),
+            })
+            .collect();
+        let mut current_values = driver_manager.read_memory_batch(&requests).into_iter();
+        let mut read_value = |typ: ValueType| match current_values.next() {
+            Some(Ok(buffer)) => format_value(&buffer, typ),
+            _ => "N/A".to_string(),
+        };
+
+        for (i, (native_position, item)) in samples.into_iter().enumerate() {
+            let obj = match item {
+                SearchResultItem::Exact(exact) => {
+                    let value_jstring = env.new_string(read_value(exact.typ))?;
+                    env.new_object(
+                        &class,
+                        "(JJILjava/lang/String;)V",
+                        &[
+                            JValue::Long(native_position as i64),
+                            JValue::Long(exact.address as i64),
+                            JValue::Int(exact.typ.to_id()),
+                            JValue::Object(&value_jstring),
+                        ],
+                    )?
+                },
+                SearchResultItem::Fuzzy(fuzzy) => {
+                    let value_jstring = env.new_string(read_value(fuzzy.value_type))?;
+                    env.new_object(
+                        &class,
+                        "(JJLjava/lang/String;I)V",
+                        &[
+                            JValue::Long(native_position as i64),
+                            JValue::Long(fuzzy.address as i64),
+                            JValue::Object(&value_jstring),
+                            JValue::Int(fuzzy.value_type.to_id()),
+                        ],
+                    )?
+                },
+            };
+            env.set_object_array_element(&array, i as jint, obj)?;
+        }
+
+        Ok(array.into_raw())
+    })()
+    .or_throw(&mut env)
+}
+
 /// Legacy synchronous refine search method.
 #[jni_method(
     70,
@@ -744,6 +1427,42 @@ pub fn jni_start_fuzzy_search_async(mut env: JNIEnv, _class: JObject, value_type
     .or_throw(&mut env)
 }
 
+/// Starts async fuzzy initial search over every region whose type is in `region_mask`.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartFuzzySearchInRangesAsync", "(IJZ)Z")]
+pub fn jni_start_fuzzy_search_in_ranges_async(mut env: JNIEnv, _class: JObject, value_type_id: jint, region_mask: jlong, keep_results: jboolean) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let value_type = jint_to_value_type(value_type_id).ok_or_else(|| anyhow!("Invalid value type: {}", value_type_id))?;
+
+        let memory_regions = resolve_region_mask(region_mask)?;
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_fuzzy_search_async(value_type, memory_regions, keep_results != JNI_FALSE)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
+/// Starts async fuzzy initial search over the pages that hold at least one current result.
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/SearchEngine", "nativeStartFuzzySearchInResultPagesAsync", "(I)Z")]
+pub fn jni_start_fuzzy_search_in_result_pages_async(mut env: JNIEnv, _class: JObject, value_type_id: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let value_type = jint_to_value_type(value_type_id).ok_or_else(|| anyhow!("Invalid value type: {}", value_type_id))?;
+
+        let mut manager = SEARCH_ENGINE_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+
+        manager.start_fuzzy_search_in_result_pages_async(value_type)?;
+
+        Ok(JNI_TRUE)
+    })()
+    .or_throw(&mut env)
+}
+
 /// Starts async fuzzy refine search with a condition.
 ///
 /// Parameters:
@@ -0,0 +1,73 @@
+//! JNI methods for MemorySnapshot
+
+use crate::core::globals::DRIVER_MANAGER;
+use crate::core::snapshot::{diff_snapshots, take_snapshot};
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JObject, JString};
+use jni::sys::{jint, jlong, jsize};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 拍摄类型在 `region_mask` 内的区域，写入 `path`
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/MemorySnapshot", "nativeTake", "(JLjava/lang/String;)Lmoe/fuqiuluo/mamu/driver/SnapshotSummary;")]
+pub fn jni_snapshot_take<'l>(mut env: JNIEnv<'l>, _obj: JObject, region_mask: jlong, path: JString) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let path: String = env.get_string(&path)?.into();
+        let summary = {
+            let manager = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            take_snapshot(&manager, region_mask as u64, &path)?
+        };
+
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/SnapshotSummary",
+            "(IJJJJJ)V",
+            &[
+                (summary.regions as jint).into(),
+                (summary.pages as jlong).into(),
+                (summary.unique_pages as jlong).into(),
+                (summary.zero_pages as jlong).into(),
+                (summary.unreadable_pages as jlong).into(),
+                (summary.file_size as jlong).into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 对比两份快照，值按 `value_size` 字节对齐比较，最多返回 `max_changes` 条值变化
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/MemorySnapshot", "nativeDiff", "(Ljava/lang/String;Ljava/lang/String;II)Lmoe/fuqiuluo/mamu/driver/SnapshotDiff;")]
+pub fn jni_snapshot_diff<'l>(mut env: JNIEnv<'l>, _obj: JObject, old_path: JString, new_path: JString, value_size: jint, max_changes: jint) -> JObject<'l> {
+    (|| -> JniResult<JObject<'l>> {
+        let old_path: String = env.get_string(&old_path)?.into();
+        let new_path: String = env.get_string(&new_path)?.into();
+        let diff = diff_snapshots(&old_path, &new_path, value_size.max(0) as usize, max_changes.max(0) as usize)?;
+
+        let pages: Vec<jlong> = diff.changed_pages.iter().map(|&page| page as jlong).collect();
+        let addresses: Vec<jlong> = diff.changes.iter().map(|change| change.address as jlong).collect();
+        let old_values: Vec<jlong> = diff.changes.iter().map(|change| change.old_value as jlong).collect();
+        let new_values: Vec<jlong> = diff.changes.iter().map(|change| change.new_value as jlong).collect();
+
+        let mut arrays = Vec::with_capacity(4);
+        for values in [&pages, &addresses, &old_values, &new_values] {
+            let array = env.new_long_array(values.len() as jsize)?;
+            env.set_long_array_region(&array, 0, values)?;
+            arrays.push(array);
+        }
+
+        Ok(env.new_object(
+            "moe/fuqiuluo/mamu/driver/SnapshotDiff",
+            "([J[J[J[JZ)V",
+            &[
+                (&arrays[0]).into(),
+                (&arrays[1]).into(),
+                (&arrays[2]).into(),
+                (&arrays[3]).into(),
+                diff.truncated.into(),
+            ],
+        )?)
+    })()
+    .or_throw(&mut env)
+}
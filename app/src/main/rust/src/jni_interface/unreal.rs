@@ -0,0 +1,150 @@
+//! JNI methods for Unreal
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{DRIVER_MANAGER, UNREAL_SESSION};
+use crate::ext::jni::{JniResult, JniResultExt};
+use crate::unreal::UnrealSession;
+use anyhow::anyhow;
+use jni::objects::{JObject, JObjectArray, JString};
+use jni::sys::{jint, jlong, jsize, jstring};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 在当前进程的会话上执行 `f`，未附加或进程已切换时报错
+fn with_session<T>(f: impl FnOnce(&DriverManager, &mut UnrealSession) -> JniResult<T>) -> JniResult<T> {
+    let manager = DRIVER_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let mut session = UNREAL_SESSION
+        .write()
+        .map_err(|_| anyhow!("Failed to acquire Unreal session write lock"))?;
+    let session = session
+        .as_mut()
+        .filter(|session| session.pid == manager.get_bound_pid())
+        .ok_or_else(|| anyhow!("Unreal has not been attached to the bound process"))?;
+    f(&manager, session)
+}
+
+/// 附加到绑定进程，返回对象槽位数
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeAttach", "(Ljava/lang/String;)I")]
+pub fn jni_unreal_attach(mut env: JNIEnv, _obj: JObject, module: JString) -> jint {
+    (|| -> JniResult<jint> {
+        let module: String = env.get_string(&module)?.into();
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let session = UnrealSession::attach(&manager, &module)?;
+        let count = session.object_count(&manager) as jint;
+        *UNREAL_SESSION
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire Unreal session write lock"))? = Some(session);
+        Ok(count)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按对象名查找，`query` 为空时按槽位顺序返回前 `max` 个
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeFindObjects", "(Ljava/lang/String;I)[Lmoe/fuqiuluo/mamu/driver/UObjectInfo;")]
+pub fn jni_unreal_find_objects<'l>(mut env: JNIEnv<'l>, _obj: JObject, query: JString, max: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let query: String = env.get_string(&query)?.into();
+        let objects = with_session(|manager, session| Ok(session.find_objects(manager, &query, max.max(0) as usize)))?;
+
+        let object_class = env.find_class("moe/fuqiuluo/mamu/driver/UObjectInfo")?;
+        let array = env.new_object_array(objects.len() as jsize, &object_class, JObject::null())?;
+        for (i, object) in objects.iter().enumerate() {
+            let name = env.new_string(&object.name)?;
+            let class_name = env.new_string(&object.class_name)?;
+            let outer = match &object.outer {
+                Some(outer) => JObject::from(env.new_string(outer)?),
+                None => JObject::null(),
+            };
+            let obj = env.new_object(
+                &object_class,
+                "(IJLjava/lang/String;Ljava/lang/String;JLjava/lang/String;)V",
+                &[
+                    (object.index as jint).into(),
+                    (object.address as jlong).into(),
+                    (&name).into(),
+                    (&class_name).into(),
+                    (object.class_address as jlong).into(),
+                    (&outer).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 对象所属类及其基类，从派生到基类排列
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeGetClassHierarchy", "(J)[Ljava/lang/String;")]
+pub fn jni_unreal_get_class_hierarchy<'l>(mut env: JNIEnv<'l>, _obj: JObject, object: jlong) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let hierarchy = with_session(|manager, session| Ok(session.class_hierarchy(manager, object as u64)))?;
+
+        let array = env.new_object_array(hierarchy.len() as jsize, "java/lang/String", JObject::null())?;
+        for (i, name) in hierarchy.iter().enumerate() {
+            let name = env.new_string(name)?;
+            env.set_object_array_element(&array, i as jsize, name)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// UClass/UScriptStruct 的属性，包括继承的
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeGetProperties", "(J)[Lmoe/fuqiuluo/mamu/driver/UPropertyInfo;")]
+pub fn jni_unreal_get_properties<'l>(mut env: JNIEnv<'l>, _obj: JObject, structure: jlong) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let properties = with_session(|manager, session| Ok(session.properties(manager, structure as u64)))?;
+
+        let property_class = env.find_class("moe/fuqiuluo/mamu/driver/UPropertyInfo")?;
+        let array = env.new_object_array(properties.len() as jsize, &property_class, JObject::null())?;
+        for (i, property) in properties.iter().enumerate() {
+            let owner = env.new_string(&property.owner)?;
+            let name = env.new_string(&property.name)?;
+            let type_name = env.new_string(&property.type_name)?;
+            let obj = env.new_object(
+                &property_class,
+                "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;II)V",
+                &[
+                    (&owner).into(),
+                    (&name).into(),
+                    (&type_name).into(),
+                    property.offset.into(),
+                    property.array_dim.into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 按 ComparisonIndex 取名字，无法解析时返回 null
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeGetName", "(I)Ljava/lang/String;")]
+pub fn jni_unreal_get_name(mut env: JNIEnv, _obj: JObject, index: jint) -> jstring {
+    (|| -> JniResult<jstring> {
+        let name = with_session(|manager, session| Ok(session.name(manager, index as u32)))?;
+        match name {
+            Some(name) => Ok(env.new_string(name)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// 释放会话
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/Unreal", "nativeRelease", "()V")]
+pub fn jni_unreal_release(mut env: JNIEnv, _obj: JObject) {
+    (|| -> JniResult<()> {
+        *UNREAL_SESSION
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire Unreal session write lock"))? = None;
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
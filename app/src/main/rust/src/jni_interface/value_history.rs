@@ -0,0 +1,108 @@
+//! JNI methods for ValueHistory
+
+use crate::core::globals::VALUE_HISTORY;
+use crate::core::value_history::{Sample, TrackConfig};
+use crate::ext::jni::{JniResult, JniResultExt};
+use jni::JNIEnv;
+use jni::objects::{JLongArray, JObject, JString};
+use jni::sys::{jboolean, jint, jlong, jlongArray, jsize};
+use jni_macro::jni_method;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 样本展开为 `[时间戳, 值, 时间戳, 值, ...]`
+fn samples_to_array<'l>(env: &mut JNIEnv<'l>, samples: &[Sample]) -> JniResult<JLongArray<'l>> {
+    let flat: Vec<jlong> = samples
+        .iter()
+        .flat_map(|sample| [sample.timestamp_ms as jlong, sample.value as jlong])
+        .collect();
+    let array = env.new_long_array(flat.len() as jsize)?;
+    env.set_long_array_region(&array, 0, &flat)?;
+    Ok(array)
+}
+
+/// 开始记录 `address` 处 `size` 字节的值，返回记录 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeTrack", "(JIII)J")]
+pub fn jni_track(mut env: JNIEnv, _obj: JObject, address: jlong, size: jint, interval_ms: jint, capacity: jint) -> jlong {
+    (|| -> JniResult<jlong> {
+        let config = TrackConfig {
+            address: address as u64,
+            size: size.max(0) as usize,
+            interval: Duration::from_millis(interval_ms.max(0) as u64),
+            capacity: capacity.max(0) as usize,
+        };
+        Ok(VALUE_HISTORY.track(config)? as jlong)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 停止记录并丢弃历史，记录不存在时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeUntrack", "(J)Z")]
+pub fn jni_untrack(mut env: JNIEnv, _obj: JObject, id: jlong) -> jboolean {
+    (|| -> JniResult<jboolean> { Ok(VALUE_HISTORY.untrack(id as u64)? as jboolean) })().or_throw(&mut env)
+}
+
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeClear", "()V")]
+pub fn jni_clear_history(mut env: JNIEnv, _obj: JObject) {
+    VALUE_HISTORY.clear().or_throw(&mut env)
+}
+
+/// 设置转存目录，传 null 时缓冲区满后丢弃最旧的样本
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeSetSpillDir", "(Ljava/lang/String;)V")]
+pub fn jni_set_spill_dir(mut env: JNIEnv, _obj: JObject, dir: JString) {
+    (|| -> JniResult<()> {
+        let dir = if dir.is_null() {
+            None
+        } else {
+            Some(PathBuf::from(String::from(env.get_string(&dir)?)))
+        };
+        VALUE_HISTORY.set_spill_dir(dir)?;
+        Ok(())
+    })()
+    .or_throw(&mut env)
+}
+
+/// 全部记录的 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeGetTrackIds", "()[J")]
+pub fn jni_get_track_ids<'l>(mut env: JNIEnv<'l>, _obj: JObject) -> JLongArray<'l> {
+    (|| -> JniResult<JLongArray<'l>> {
+        let ids: Vec<jlong> = VALUE_HISTORY.track_ids().into_iter().map(|id| id as jlong).collect();
+        let array = env.new_long_array(ids.len() as jsize)?;
+        env.set_long_array_region(&array, 0, &ids)?;
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 已保留的样本数，记录不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeGetSampleCount", "(J)J")]
+pub fn jni_get_sample_count(_env: JNIEnv, _obj: JObject, id: jlong) -> jlong {
+    VALUE_HISTORY.sample_count(id as u64).map_or(-1, |count| count as jlong)
+}
+
+/// 丢弃的样本数，记录不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeGetDroppedCount", "(J)J")]
+pub fn jni_get_dropped_count(_env: JNIEnv, _obj: JObject, id: jlong) -> jlong {
+    VALUE_HISTORY.dropped_count(id as u64).map_or(-1, |count| count as jlong)
+}
+
+/// `[fromMs, toMs]` 内最多 `max` 个样本，`toMs` 为负数时不限结束时间，记录不存在时返回 null
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeGetHistory", "(JJJI)[J")]
+pub fn jni_get_history(mut env: JNIEnv, _obj: JObject, id: jlong, from_ms: jlong, to_ms: jlong, max: jint) -> jlongArray {
+    (|| -> JniResult<jlongArray> {
+        let from_ms = from_ms.max(0) as u64;
+        let to_ms = if to_ms < 0 { u64::MAX } else { to_ms as u64 };
+        match VALUE_HISTORY.history(id as u64, from_ms, to_ms, max.max(0) as usize) {
+            Some(samples) => Ok(samples_to_array(&mut env, &samples)?.into_raw()),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })()
+    .or_throw(&mut env)
+}
+
+/// 最新的样本 `[时间戳, 值]`，没有样本时返回空数组
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/ValueHistory", "nativeGetLatest", "(J)[J")]
+pub fn jni_get_latest<'l>(mut env: JNIEnv<'l>, _obj: JObject, id: jlong) -> JLongArray<'l> {
+    let latest: Vec<Sample> = VALUE_HISTORY.latest(id as u64).into_iter().collect();
+    samples_to_array(&mut env, &latest).or_throw(&mut env)
+}
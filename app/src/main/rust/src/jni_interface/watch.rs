@@ -0,0 +1,193 @@
+//! JNI methods for WatchManager
+
+use crate::core::globals::{DRIVER_MANAGER, WATCH_MANAGER};
+use crate::core::watch::WatchKind;
+use crate::ext::jni::{JniResult, JniResultExt};
+use anyhow::anyhow;
+use jni::objects::{JObject, JObjectArray};
+use jni::sys::{jboolean, jint, jlong, jsize, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_macro::jni_method;
+
+/// 布置硬件观察点，返回观察点 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeArm", "(JII)I")]
+pub fn jni_watch_arm(mut env: JNIEnv, _obj: JObject, address: jlong, size: jint, kind: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let kind = WatchKind::from_id(kind).ok_or_else(|| anyhow!("Invalid watch kind: {}", kind))?;
+        if size <= 0 {
+            return Err(anyhow!("Invalid watch size: {}", size));
+        }
+
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut watches = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+        watches.arm(&manager, address as u64, size as usize, kind)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 解除观察点，未找到时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeDisarm", "(I)Z")]
+pub fn jni_watch_disarm(mut env: JNIEnv, _obj: JObject, id: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut watches = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+        Ok(if watches.disarm(&manager, id)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 解除全部观察点，返回解除数量
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeDisarmAll", "()I")]
+pub fn jni_watch_disarm_all(mut env: JNIEnv, _obj: JObject) -> jint {
+    (|| -> JniResult<jint> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut watches = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+        Ok(watches.disarm_all(&manager) as jint)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 取走观察点的命中记录
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeTakeHits", "(I)[Lmoe/fuqiuluo/mamu/driver/WatchHit;")]
+pub fn jni_watch_take_hits<'l>(mut env: JNIEnv<'l>, _obj: JObject, id: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let hits = {
+            let manager = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            let mut watches = WATCH_MANAGER
+                .write()
+                .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+            watches.take_hits(&manager, id)?
+        };
+
+        let hit_class = env.find_class("moe/fuqiuluo/mamu/driver/WatchHit")?;
+        let array = env.new_object_array(hits.len() as jsize, &hit_class, JObject::null())?;
+        for (i, hit) in hits.iter().enumerate() {
+            let regs: Vec<jlong> = hit.regs.iter().map(|&r| r as jlong).collect();
+            let regs_array = env.new_long_array(regs.len() as jsize)?;
+            env.set_long_array_region(&regs_array, 0, &regs)?;
+
+            let obj = env.new_object(
+                &hit_class,
+                "(IJJ[JJJ)V",
+                &[
+                    hit.tid.into(),
+                    (hit.pc as jlong).into(),
+                    (hit.addr as jlong).into(),
+                    (&regs_array).into(),
+                    (hit.sp as jlong).into(),
+                    (hit.timestamp_ns as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 累计命中次数，观察点不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeGetHitCount", "(I)J")]
+pub fn jni_watch_get_hit_count(mut env: JNIEnv, _obj: JObject, id: jint) -> jlong {
+    (|| -> JniResult<jlong> {
+        let watches = WATCH_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager read lock"))?;
+        Ok(watches.hit_count(id).map_or(-1, |count| count as jlong))
+    })()
+    .or_throw(&mut env)
+}
+
+/// 开始追踪一段地址上的访问，返回追踪 id
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeStartTrace", "(JIII)I")]
+pub fn jni_watch_start_trace(mut env: JNIEnv, _obj: JObject, address: jlong, length: jint, kind: jint, capacity: jint) -> jint {
+    (|| -> JniResult<jint> {
+        let kind = WatchKind::from_id(kind).ok_or_else(|| anyhow!("Invalid watch kind: {}", kind))?;
+        if length <= 0 || capacity <= 0 {
+            return Err(anyhow!("Invalid trace length/capacity: {}/{}", length, capacity));
+        }
+
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut watches = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+        watches.start_trace(&manager, address as u64, length as usize, kind, capacity as usize)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 取走追踪缓冲区中最多 `max` 条记录
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativePollTrace", "(II)[Lmoe/fuqiuluo/mamu/driver/AccessRecord;")]
+pub fn jni_watch_poll_trace<'l>(mut env: JNIEnv<'l>, _obj: JObject, trace_id: jint, max: jint) -> JObjectArray<'l> {
+    (|| -> JniResult<JObjectArray<'l>> {
+        let records = {
+            let manager = DRIVER_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+            let mut watches = WATCH_MANAGER
+                .write()
+                .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+            watches.poll_trace(&manager, trace_id, max.max(0) as usize)?
+        };
+
+        let record_class = env.find_class("moe/fuqiuluo/mamu/driver/AccessRecord")?;
+        let array = env.new_object_array(records.len() as jsize, &record_class, JObject::null())?;
+        for (i, record) in records.iter().enumerate() {
+            let obj = env.new_object(
+                &record_class,
+                "(JIJJ)V",
+                &[
+                    (record.pc as jlong).into(),
+                    record.tid.into(),
+                    (record.address as jlong).into(),
+                    (record.timestamp_ns as jlong).into(),
+                ],
+            )?;
+            env.set_object_array_element(&array, i as jsize, obj)?;
+        }
+        Ok(array)
+    })()
+    .or_throw(&mut env)
+}
+
+/// 停止追踪，未找到时返回 false
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeStopTrace", "(I)Z")]
+pub fn jni_watch_stop_trace(mut env: JNIEnv, _obj: JObject, trace_id: jint) -> jboolean {
+    (|| -> JniResult<jboolean> {
+        let manager = DRIVER_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        let mut watches = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager write lock"))?;
+        Ok(if watches.stop_trace(&manager, trace_id)? { JNI_TRUE } else { JNI_FALSE })
+    })()
+    .or_throw(&mut env)
+}
+
+/// 追踪累计访问次数，追踪不存在时返回 -1
+#[jni_method(70, "moe/fuqiuluo/mamu/driver/WatchManager", "nativeGetTraceCount", "(I)J")]
+pub fn jni_watch_get_trace_count(mut env: JNIEnv, _obj: JObject, trace_id: jint) -> jlong {
+    (|| -> JniResult<jlong> {
+        let watches = WATCH_MANAGER
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager read lock"))?;
+        Ok(watches.trace_count(trace_id).map_or(-1, |count| count as jlong))
+    })()
+    .or_throw(&mut env)
+}
@@ -2,9 +2,14 @@
 pub mod core;
 pub mod disasm;
 pub mod ext;
+pub mod il2cpp;
+pub mod inject;
 pub mod jni_interface;
 pub mod pointer_scan;
+pub mod remote;
+pub mod script;
 pub mod search;
+pub mod unreal;
 pub mod wuwa;
 
 use android_logger::Config;
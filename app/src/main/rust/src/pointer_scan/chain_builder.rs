@@ -6,10 +6,17 @@
 //! ## 算法
 //! - `build_pointer_chains`: 主入口，调用分层BFS算法
 //! - `build_pointer_chains_layered_bfs`: **分层BFS + rayon并行**
+//! - `filter_chains_by_access`: 用观察点捕获的基址寄存器验证已有的链；
+//!   同样的偏移放进 `PointerScanConfig::access_offsets` 时直接作为第一层的种子
 
+mod access_hint;
 mod layer_bfs;
+mod pruning;
 mod recursive_dfs;
+#[cfg(test)]
+mod tests;
 
+pub use crate::pointer_scan::chain_builder::access_hint::{access_bases, access_offsets, filter_chains_by_access};
 use crate::pointer_scan::chain_builder::layer_bfs::build_pointer_chains_layered_bfs;
 use crate::pointer_scan::chain_builder::recursive_dfs::build_pointer_chains_dfs;
 use crate::pointer_scan::storage::MmapQueue;
@@ -17,7 +24,6 @@ use crate::pointer_scan::types::{PointerChain, PointerChainStep, PointerData, Po
 use anyhow::Result;
 use log::{debug, info, log_enabled, warn, Level};
 use rayon::prelude::*;
-use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
 
@@ -61,13 +67,14 @@ fn find_range_in_pointer_queue(queue: &MmapQueue<PointerData>, min_value: u64, m
     (start_idx, end_idx)
 }
 
-/// 查找所有指向 [target - max_offset, target] 范围的指针。
+/// 查找所有指向 [target - max_offset, target + max_backward_offset] 范围的指针。
 /// 返回 Vec<(指针地址, 有符号偏移)>，其中 有符号偏移 = target - 指针值。
 /// 正偏移：指针指向target下方
-/// 负偏移：指针指向target上方
-fn find_pointers_to_range(pointer_lib: &MmapQueue<PointerData>, target: u64, max_offset: u32) -> Vec<(u64, i64)> {
+/// 负偏移：指针指向target上方，`max_backward_offset` 为 0 时不查找
+pub(crate) fn find_pointers_to_range(pointer_lib: &MmapQueue<PointerData>, target: u64, max_offset: u32, max_backward_offset: u32) -> Vec<(u64, i64)> {
     let min_value = target.saturating_sub(max_offset as u64);
-    let max_value = target + 1; // 上界不包含，所以 target+1 表示搜索到 target
+    // 上界不包含，所以 +1 表示搜索到 target + max_backward_offset
+    let max_value = target.saturating_add(max_backward_offset as u64).saturating_add(1);
 
     let (start_idx, end_idx) = find_range_in_pointer_queue(pointer_lib, min_value, max_value);
 
@@ -80,23 +87,21 @@ fn find_pointers_to_range(pointer_lib: &MmapQueue<PointerData>, target: u64, max
 
     let mut results = Vec::with_capacity(end_idx - start_idx);
 
-    for i in start_idx..end_idx {
-        if let Some(archived) = pointer_lib.get(i) {
-            let ptr_address = archived.address.to_native();
-            let ptr_value = archived.value.to_native();
-            // 有符号偏移：正值表示指针指向target下方
-            let offset = (target as i64).wrapping_sub(ptr_value as i64);
-
-            // 验证偏移在范围内
-            if offset >= 0 && offset <= max_offset as i64 {
-                // ptr_address这个位置有个指针值，把它读出来然后加上offset得到target
-                results.push((ptr_address, offset));
-            } else if log_enabled!(Level::Debug) {
-                debug!(
-                    "跳过超出范围的指针: 地址=0x{:X}, 值=0x{:X}, 偏移={}, max_offset={}",
-                    ptr_address, ptr_value, offset, max_offset
-                );
-            }
+    for archived in pointer_lib.range(start_idx..end_idx) {
+        let ptr_address = archived.address.to_native();
+        let ptr_value = archived.value.to_native();
+        // 有符号偏移：正值表示指针指向target下方
+        let offset = (target as i64).wrapping_sub(ptr_value as i64);
+
+        // 验证偏移在范围内
+        if offset >= -(max_backward_offset as i64) && offset <= max_offset as i64 {
+            // ptr_address这个位置有个指针值，把它读出来然后加上offset得到target
+            results.push((ptr_address, offset));
+        } else if log_enabled!(Level::Debug) {
+            debug!(
+                "跳过超出范围的指针: 地址=0x{:X}, 值=0x{:X}, 偏移={}, max_offset={}, max_backward_offset={}",
+                ptr_address, ptr_value, offset, max_offset, max_backward_offset
+            );
         }
     }
 
@@ -107,43 +112,110 @@ fn find_pointers_to_range(pointer_lib: &MmapQueue<PointerData>, target: u64, max
     results
 }
 
+/// 按配置查找第 `depth` 层指向 `target` 的候选指针。
+/// 使用该层的最大偏移和配置的最大负偏移；配置了偏移白名单时只返回白名单内的偏移。
+/// 第一层配置了观察点偏移（`access_offsets`）时只沿这些偏移查找。
+pub(crate) fn find_candidate_pointers(pointer_lib: &MmapQueue<PointerData>, target: u64, depth: usize, config: &PointerScanConfig) -> Vec<(u64, i64)> {
+    let max_offset = config.max_offset_at(depth);
+    if depth == 0 && !config.access_offsets.is_empty() {
+        let min_offset = -(config.max_backward_offset as i64);
+        let offsets = config.access_offsets.iter().copied().filter(|&offset| offset >= min_offset && offset <= max_offset as i64);
+        return find_pointers_at_offsets(pointer_lib, target, offsets);
+    }
+    if config.offset_whitelist.is_empty() {
+        return find_pointers_to_range(pointer_lib, target, max_offset, config.max_backward_offset);
+    }
+
+    // 白名单通常只有几个到几十个偏移，逐个精确查找比扫描整个偏移范围快得多
+    let offsets = config.offset_whitelist.iter().take_while(|&&offset| offset <= max_offset).map(|&offset| offset as i64);
+    find_pointers_at_offsets(pointer_lib, target, offsets)
+}
+
+/// 逐个精确查找值为 target - offset 的指针
+fn find_pointers_at_offsets(pointer_lib: &MmapQueue<PointerData>, target: u64, offsets: impl Iterator<Item = i64>) -> Vec<(u64, i64)> {
+    let mut results = Vec::new();
+    for offset in offsets {
+        let Some(value) = target.checked_add_signed(-offset) else {
+            continue;
+        };
+        let (start_idx, end_idx) = find_range_in_pointer_queue(pointer_lib, value, value.saturating_add(1));
+        results.extend(pointer_lib.range(start_idx..end_idx).map(|archived| (archived.address.to_native(), offset)));
+    }
+    results
+}
+
+/// 按基址排序的静态模块索引，查找地址所在模块时二分而不是逐个比较。
+///
+/// 模块区间可能重叠，`max_end[i]` 记录排序后前 i+1 个模块的最大结束地址，
+/// 二分定位后向前回溯，直到前面的模块不可能再包含该地址。
+/// 多个模块都包含该地址时返回原列表中最靠前的一个，与逐个比较的结果一致。
+pub(crate) struct StaticModuleIndex<'a> {
+    /// (模块, 在原列表中的序号)，按基址排序
+    modules: Vec<(&'a VmStaticData, usize)>,
+    max_end: Vec<u64>,
+}
+
+impl<'a> StaticModuleIndex<'a> {
+    pub fn new(static_modules: &'a [VmStaticData]) -> Self {
+        let mut modules: Vec<(&VmStaticData, usize)> = static_modules.iter().enumerate().map(|(idx, module)| (module, idx)).collect();
+        modules.sort_by_key(|(module, _)| module.base_address);
+
+        let max_end = modules
+            .iter()
+            .scan(0u64, |max_end, (module, _)| {
+                *max_end = (*max_end).max(module.end_address);
+                Some(*max_end)
+            })
+            .collect();
+
+        Self { modules, max_end }
+    }
+
+    /// 查找包含 `address` 的模块
+    pub fn find(&self, address: u64) -> Option<&'a VmStaticData> {
+        let upper = self.modules.partition_point(|(module, _)| module.base_address <= address);
+
+        let mut found: Option<(&VmStaticData, usize)> = None;
+        for i in (0..upper).rev() {
+            if self.max_end[i] <= address {
+                break;
+            }
+            let (module, idx) = self.modules[i];
+            if module.contains(address) && found.is_none_or(|(_, best)| idx < best) {
+                found = Some((module, idx));
+            }
+        }
+        found.map(|(module, _)| module)
+    }
+}
+
 /// 检查地址是否属于静态模块。
 /// 如果找到，返回 (模块名, 模块索引, 基址偏移)。
 ///
 /// 注意：不对静态模块内部的指针位置做 max_offset 限制，
 /// 因为代码段可能很大（数MB），指针可以在任何位置。
 /// max_offset 只用于指针链的偏移检查，不用于静态根的位置检查。
-fn classify_pointer(
-    address: u64,
-    static_modules: &[VmStaticData],
-    data_start: bool,
-    _bss_start: bool,
-    _max_offset: u32, // 不使用，仅为保持接口兼容
-) -> Option<(String, u32, u64)> {
-    for module in static_modules {
-        if module.contains(address) {
-            let local_offset = module.offset_from_base(address);
-
-            // 计算返回的偏移：
-            // - 如果 data_start=true 且 index!=0，使用相对于第一个段的偏移（统一基址）
-            // - 否则使用相对于当前段的偏移
-            let display_offset = if data_start && module.index != 0 {
-                address.saturating_sub(module.first_module_base_addr)
-            } else {
-                local_offset
-            };
-
-            if log_enabled!(Level::Debug) {
-                debug!(
-                    "分类指针 0x{:X}: 模块={}, 索引={}, 偏移=0x{:X}",
-                    address, module.name, module.index, display_offset
-                );
-            }
+pub(crate) fn classify_pointer(address: u64, static_modules: &StaticModuleIndex, data_start: bool) -> Option<(String, u32, u64)> {
+    let module = static_modules.find(address)?;
+    let local_offset = module.offset_from_base(address);
 
-            return Some((module.name.clone(), module.index, display_offset));
-        }
+    // 计算返回的偏移：
+    // - 如果 data_start=true 且 index!=0，使用相对于第一个段的偏移（统一基址）
+    // - 否则使用相对于当前段的偏移
+    let display_offset = if data_start && module.index != 0 {
+        address.saturating_sub(module.first_module_base_addr)
+    } else {
+        local_offset
+    };
+
+    if log_enabled!(Level::Debug) {
+        debug!(
+            "分类指针 0x{:X}: 模块={}, 索引={}, 偏移=0x{:X}",
+            address, module.name, module.index, display_offset
+        );
     }
-    None
+
+    Some((module.name.clone(), module.index, display_offset))
 }
 
 /// 第二阶段：使用分层BFS从目标地址构建指针链。
@@ -155,25 +227,31 @@ fn classify_pointer(
 /// * `pointer_lib` - 第一阶段构建的已排序指针库
 /// * `static_modules` - 静态模块列表（代码段）
 /// * `config` - 扫描配置
-/// * `progress_callback` - 进度回调 (当前深度, 已找到链数)
+/// * `sink` - 接收每条找到的完整链，返回错误时中止构建
+/// * `progress_callback` - 进度回调 (当前深度, 最大深度, 已找到链数, 累计裁剪的候选数)
 /// * `check_cancelled` - 检查是否取消的函数
 ///
+/// 链不在内存中汇总，逐条交给 `sink`（通常写入 `ChainStore`）。
+/// 分层BFS按深度从短到长输出，同一深度内按模块名排序；DFS按发现顺序输出。
+///
 /// # 返回
-/// 完整指针链的向量
-pub fn build_pointer_chains<F, C>(
+/// 找到的链数
+pub fn build_pointer_chains<S, F, C>(
     pointer_lib: &MmapQueue<PointerData>,
     static_modules: &[VmStaticData],
     config: &PointerScanConfig,
+    sink: S,
     progress_callback: F,
     check_cancelled: C,
-) -> Result<Vec<PointerChain>>
+) -> Result<usize>
 where
-    F: Fn(u32, i32, i64) + Sync + Send + 'static,
+    S: FnMut(PointerChain) -> Result<()> + Send,
+    F: Fn(u32, i32, i64, u64) + Sync + Send + 'static,
     C: Fn() -> bool + Sync,
 {
     if config.is_layer_bfs {
-        build_pointer_chains_layered_bfs(pointer_lib, static_modules, config, progress_callback, check_cancelled)
+        build_pointer_chains_layered_bfs(pointer_lib, static_modules, config, sink, progress_callback, check_cancelled)
     } else {
-        build_pointer_chains_dfs(pointer_lib, static_modules, config, progress_callback, check_cancelled)
+        build_pointer_chains_dfs(pointer_lib, static_modules, config, sink, progress_callback, check_cancelled)
     }
 }
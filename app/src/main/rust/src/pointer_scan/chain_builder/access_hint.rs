@@ -0,0 +1,80 @@
+use super::*;
+use crate::disasm::disassemble_arm64_lenient;
+use crate::pointer_scan::chain_store::ChainStore;
+use crate::wuwa::BpWatchHit;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 从访存指令的操作数中取出基址寄存器：`w0, [x19, #0x48]` -> 19，`[sp]` -> 31
+fn base_register(operands: &str) -> Option<usize> {
+    let inner = &operands[operands.find('[')? + 1..];
+    let reg = inner.split([',', ']']).next()?.trim();
+    if reg == "sp" {
+        return Some(31);
+    }
+    reg.strip_prefix('x')?.parse().ok().filter(|&n| n < 31)
+}
+
+/// 观察点命中时访存指令的基址寄存器值，排序去重。
+///
+/// 例如 `ldr w0, [x19, #0x48]` 命中时取 x19。读不到或解不出指令时，
+/// 退而取所有与访问地址距离在 `max_offset` 以内（不超过访问地址）的寄存器值。
+pub fn access_bases<R>(hits: &[BpWatchHit], max_offset: u32, mut read_insn: R) -> Vec<u64>
+where
+    R: FnMut(u64) -> Option<[u8; 4]>,
+{
+    let mut registers: HashMap<u64, Option<usize>> = HashMap::new();
+    let mut bases = Vec::new();
+    for hit in hits {
+        let register = *registers.entry(hit.pc).or_insert_with(|| {
+            let insn = read_insn(hit.pc)?;
+            base_register(&disassemble_arm64_lenient(&insn, hit.pc).ok()?.first()?.operands)
+        });
+        match register {
+            Some(31) => bases.push(hit.sp),
+            Some(n) => bases.push(hit.regs[n]),
+            None => bases.extend(
+                hit.regs
+                    .iter()
+                    .chain([&hit.sp])
+                    .copied()
+                    .filter(|&value| value <= hit.addr && hit.addr - value <= max_offset as u64),
+            ),
+        }
+    }
+    bases.sort_unstable();
+    bases.dedup();
+    bases
+}
+
+/// 基址对应的最后一级偏移 offset = target - base，排序去重。
+/// 只保留 [-max_backward_offset, max_offset] 范围内的偏移。
+pub fn access_offsets(target: u64, bases: &[u64], max_offset: u32, max_backward_offset: u32) -> Vec<i64> {
+    let mut offsets: Vec<i64> = bases
+        .iter()
+        .map(|&base| (target as i64).wrapping_sub(base as i64))
+        .filter(|&offset| offset >= -(max_backward_offset as i64) && offset <= max_offset as i64)
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets
+}
+
+/// 用运行时基址验证已有的指针链，结果写入 `cache_dir` 中的新存储。
+///
+/// 链的最后一步偏移等于 target - 最后一级指针值，因此只有最后一步偏移在 `offsets`
+/// （见 [`access_offsets`]）中的链才会被保留，静态扫描产生的大量 "碰巧可达" 的链
+/// 在这里会被过滤掉。只有静态根、没有解引用的链无从验证，原样保留。
+pub fn filter_chains_by_access(chains: &ChainStore, cache_dir: &Path, offsets: &[i64]) -> Result<ChainStore> {
+    let mut unchecked = 0usize;
+    let filtered = chains.filter(cache_dir, |chain| {
+        if chain.steps.len() < 2 {
+            unchecked += 1;
+            return true;
+        }
+        chain.steps.last().is_some_and(|step| offsets.binary_search(&step.offset).is_ok())
+    })?;
+
+    info!("访问记录过滤: {} -> {} 条链（{} 条无解引用，未验证）, 允许的末级偏移 {:?}", chains.len(), filtered.len(), unchecked, offsets);
+    Ok(filtered)
+}
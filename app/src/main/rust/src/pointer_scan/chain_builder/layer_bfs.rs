@@ -1,4 +1,5 @@
 use super::*;
+use super::pruning::{prune_candidates, StaticRanges};
 
 /// BFS遍历的路径节点。
 /// 存储当前目标地址和从target到此节点的偏移历史。
@@ -88,30 +89,37 @@ const MAX_FANOUT_PER_NODE: usize = 10 * 10000;
 /// 内存优化：
 /// - 路径内循环检测：使用 PathNode.visited_addresses 防止 A→B→C→B 类型的循环
 /// - 扇出限制：每个节点最多产生 MAX_FANOUT_PER_NODE 个子节点
-/// - 层级限制：每层最多 MAX_CANDIDATES_PER_LAYER 个节点
-pub fn build_pointer_chains_layered_bfs<F, C>(
+/// - 层级限制：每层最多 MAX_CANDIDATES_PER_LAYER 个节点，超出时按优先级裁剪（见 `pruning`）
+pub fn build_pointer_chains_layered_bfs<S, F, C>(
     pointer_lib: &MmapQueue<PointerData>,
     static_modules: &[VmStaticData],
     config: &PointerScanConfig,
+    mut sink: S,
     progress_callback: F,
     check_cancelled: C,
-) -> Result<Vec<PointerChain>>
+) -> Result<usize>
 where
-    F: Fn(u32, i32, i64) + Sync,
+    S: FnMut(PointerChain) -> Result<()>,
+    F: Fn(u32, i32, i64, u64) + Sync,
     C: Fn() -> bool + Sync,
 {
     info!(
-        "构建指针链 (分层BFS) 目标=0x{:X}, 最大深度={}, 最大偏移=0x{:X}",
-        config.target_address, config.max_depth, config.max_offset
+        "构建指针链 (分层BFS) 目标=0x{:X}, 最大深度={}, 最大偏移=0x{:X}, 分层偏移={:X?}, 偏移白名单 {} 项",
+        config.target_address,
+        config.max_depth,
+        config.max_offset,
+        config.level_max_offsets,
+        config.offset_whitelist.len()
     );
 
-    let mut results: Vec<PointerChain> = Vec::new();
-
     // 用目标地址初始化
     let mut current_layer = vec![PathNode::new(config.target_address)];
 
     let cancelled = AtomicBool::new(false);
     let chains_found = AtomicUsize::new(0);
+    let static_ranges = StaticRanges::new(static_modules);
+    let static_index = StaticModuleIndex::new(static_modules);
+    let mut candidates_dropped = 0u64;
 
     for depth in 0..config.max_depth {
         if check_cancelled() {
@@ -136,7 +144,7 @@ where
                     return Vec::new();
                 }
 
-                let pointers = find_pointers_to_range(pointer_lib, node.current_target, config.max_offset);
+                let pointers = find_candidate_pointers(pointer_lib, node.current_target, depth as usize, config);
 
                 // 过滤掉循环引用的候选，并限制扇出数量
                 pointers
@@ -161,14 +169,16 @@ where
         // 遍历所有候选
         // 注意：循环引用检查已在散射阶段通过 is_visited 完成
         let mut next_layer: Vec<PathNode> = Vec::new();
+        // 本层找到的链深度相同，排序后整层输出
+        let mut layer_chains: Vec<PointerChain> = Vec::new();
 
         for candidate in candidates {
             let parent = &current_layer[candidate.parent_idx];
 
             // 检查此指针是否来自静态模块
-            if let Some((module_name, module_index, base_offset)) =
-                classify_pointer(candidate.ptr_address, static_modules, config.data_start, config.bss_start, config.max_offset)
-            {
+            let root = classify_pointer(candidate.ptr_address, &static_index, config.data_start);
+            let is_static = root.is_some();
+            if let Some((module_name, module_index, base_offset)) = root {
                 // 找到一条完整链！
                 let mut chain = PointerChain::with_capacity(config.target_address, parent.depth() + 2);
 
@@ -183,46 +193,50 @@ where
                     chain.push(PointerChainStep::dynamic_offset(offset));
                 }
 
-                results.push(chain);
+                layer_chains.push(chain);
                 chains_found.fetch_add(1, AtomicOrdering::Relaxed);
             }
 
             // 如果未达到最大深度，继续向上搜索
             if depth + 1 < config.max_depth {
                 // 只将非静态指针添加到下一层（或者如果不是scan_static_only则全部添加）
-                if classify_pointer(candidate.ptr_address, static_modules, config.data_start, config.bss_start, config.max_offset).is_none() {
+                if !is_static {
                     next_layer.push(parent.child(candidate.ptr_address, candidate.offset));
                 }
             }
         }
 
-        // 剪枝：如果候选过多，只保留一部分
+        // 同一深度内按模块名排序
+        layer_chains.par_sort_by(|a, b| {
+            let a_name = a.steps.first().and_then(|s| s.module_name.as_ref());
+            let b_name = b.steps.first().and_then(|s| s.module_name.as_ref());
+            a_name.cmp(&b_name)
+        });
+        for chain in layer_chains {
+            sink(chain)?;
+        }
+
+        // 剪枝：如果候选过多，按优先级只保留一部分
         if next_layer.len() > MAX_CANDIDATES_PER_LAYER {
-            warn!("[候选裁剪] 在深度 {} 将候选从 {} 剪枝到 {}", depth, next_layer.len(), MAX_CANDIDATES_PER_LAYER);
-            next_layer.truncate(MAX_CANDIDATES_PER_LAYER);
+            let before = next_layer.len();
+            let dropped = prune_candidates(&mut next_layer, MAX_CANDIDATES_PER_LAYER, |node| {
+                static_ranges.prune_key(node.current_target, &node.offset_history)
+            });
+            candidates_dropped += dropped as u64;
+            warn!("[候选裁剪] 在深度 {} 将候选从 {} 剪枝到 {}", depth, before, next_layer.len());
         }
 
         // 报告进度
-        progress_callback(depth + 1, config.max_depth as i32, chains_found.load(AtomicOrdering::Relaxed) as i64);
+        progress_callback(depth + 1, config.max_depth as i32, chains_found.load(AtomicOrdering::Relaxed) as i64, candidates_dropped);
 
         current_layer = next_layer;
     }
 
     // 最终进度报告
-    progress_callback(config.max_depth, config.max_depth as i32, results.len() as i64);
+    let found = chains_found.load(AtomicOrdering::Relaxed);
+    progress_callback(config.max_depth, config.max_depth as i32, found as i64, candidates_dropped);
 
-    info!("指针链构建 (分层BFS) 完成。找到 {} 条链", results.len());
-
-    // 按深度排序（短链优先），然后按模块名排序
-    results.par_sort_by(|a, b| {
-        let depth_cmp = a.depth().cmp(&b.depth());
-        if depth_cmp != Ordering::Equal {
-            return depth_cmp;
-        }
-        let a_name = a.steps.first().and_then(|s| s.module_name.as_ref());
-        let b_name = b.steps.first().and_then(|s| s.module_name.as_ref());
-        a_name.cmp(&b_name)
-    });
+    info!("指针链构建 (分层BFS) 完成。找到 {} 条链，裁剪候选 {} 个", found, candidates_dropped);
 
-    Ok(results)
+    Ok(found)
 }
@@ -0,0 +1,79 @@
+//! 候选裁剪
+//!
+//! 分层BFS某一层的候选超过上限时按优先级保留，而不是按扫描顺序截断
+//! （截断会偏向先扫描到的内存区域）：
+//! 1. 离静态模块越近越优先，按距离的数量级分档
+//! 2. 路径上各级偏移绝对值之和越小越优先
+//! 3. 以上相同时按地址哈希排序，结果与扫描顺序无关且可复现
+
+use super::*;
+
+/// 裁剪优先级，越小越优先：(静态模块距离档位, 偏移绝对值之和, 地址哈希)
+pub(super) type PruneKey = (u32, u64, u64);
+
+/// 静态模块的地址区间，按起始地址排序
+pub(super) struct StaticRanges(Vec<(u64, u64)>);
+
+impl StaticRanges {
+    pub fn new(static_modules: &[VmStaticData]) -> Self {
+        let mut ranges: Vec<(u64, u64)> = static_modules.iter().map(|m| (m.base_address, m.end_address)).collect();
+        ranges.sort_unstable();
+        Self(ranges)
+    }
+
+    /// 到最近静态模块的距离，位于模块内时为 0，没有静态模块时为 u64::MAX
+    pub fn distance(&self, addr: u64) -> u64 {
+        let idx = self.0.partition_point(|&(start, _)| start <= addr);
+        let before = idx.checked_sub(1).map(|i| self.0[i]).map_or(u64::MAX, |(_, end)| {
+            if addr < end { 0 } else { addr - end + 1 }
+        });
+        // 区间可能重叠，前一个之前的区间也可能更近，但只用于分档，误差可以接受
+        let after = self.0.get(idx).map_or(u64::MAX, |&(start, _)| start - addr);
+        before.min(after)
+    }
+
+    /// 计算节点的裁剪优先级
+    pub fn prune_key(&self, addr: u64, offsets: &[i64]) -> PruneKey {
+        let bucket = 64 - self.distance(addr).leading_zeros();
+        let offset_cost = offsets.iter().map(|o| o.unsigned_abs()).fold(0u64, u64::saturating_add);
+        (bucket, offset_cost, mix64(addr))
+    }
+}
+
+/// splitmix64，把地址打散为与扫描顺序无关的确定性排序键
+#[inline]
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// 按优先级保留最多 `limit` 个节点，保留的节点维持原有顺序。
+/// 返回被裁掉的数量。
+pub(super) fn prune_candidates<T, K>(nodes: &mut Vec<T>, limit: usize, key: K) -> usize
+where
+    T: Sync,
+    K: Fn(&T) -> PruneKey + Sync,
+{
+    if nodes.len() <= limit {
+        return 0;
+    }
+
+    // 下标参与比较，键完全相同时结果仍然确定
+    let mut keyed: Vec<(PruneKey, usize)> = nodes.par_iter().enumerate().map(|(idx, node)| (key(node), idx)).collect();
+    keyed.select_nth_unstable(limit);
+
+    let mut keep = vec![false; nodes.len()];
+    for &(_, idx) in &keyed[..limit] {
+        keep[idx] = true;
+    }
+
+    let dropped = nodes.len() - limit;
+    let mut idx = 0;
+    nodes.retain(|_| {
+        idx += 1;
+        keep[idx - 1]
+    });
+    dropped
+}
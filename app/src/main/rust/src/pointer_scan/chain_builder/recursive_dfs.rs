@@ -7,20 +7,22 @@ use std::time::{Duration, Instant};
 
 struct DfsContext<'a> {
     pointer_lib: &'a MmapQueue<PointerData>,
-    static_modules: &'a [VmStaticData],
+    static_modules: StaticModuleIndex<'a>,
     config: &'a PointerScanConfig,
     cancelled: &'a AtomicBool,
 }
 
-pub fn build_pointer_chains_dfs<F, C>(
+pub fn build_pointer_chains_dfs<S, F, C>(
     pointer_lib: &MmapQueue<PointerData>,
     static_modules: &[VmStaticData],
     config: &PointerScanConfig,
+    mut sink: S,
     progress_callback: F,
     check_cancelled: C,
-) -> Result<Vec<PointerChain>>
+) -> Result<usize>
 where
-    F: Fn(u32, i32, i64) + Sync + Send + 'static,
+    S: FnMut(PointerChain) -> Result<()> + Send,
+    F: Fn(u32, i32, i64, u64) + Sync + Send + 'static,
     C: Fn() -> bool + Sync,
 {
     if log_enabled!(Level::Debug) {
@@ -32,73 +34,82 @@ where
     let (tx, rx) = unbounded::<PointerChain>();
 
     let max_depth = config.max_depth as i32;
-    let consumer_handle = thread::spawn(move || {
-        let mut results = Vec::new();
-        let mut last_report = Instant::now();
-
-        // 不断接收直到所有 Sender 关闭
-        while let Ok(chain) = rx.recv() {
-            let depth = chain.depth() as u32;
-            results.push(chain);
-
-            // 限制回调频率，避免刷新太快拖慢速度 (例如每 100ms 刷新一次)
-            if last_report.elapsed() >= Duration::from_millis(100) {
-                progress_callback(depth, max_depth, results.len() as i64);
-                last_report = Instant::now();
-            }
-        }
-
-        // 最终报告
-        progress_callback(max_depth as u32, max_depth, results.len() as i64);
-        results
-    });
 
     // 准备搜索上下文
     let ctx = DfsContext {
         pointer_lib,
-        static_modules,
+        static_modules: StaticModuleIndex::new(static_modules),
         config,
         cancelled: &cancelled,
     };
 
-    // 获取第一层入口点 (反向搜索第一步)
-    let roots = find_pointers_to_range(pointer_lib, config.target_address, config.max_offset);
-    if log_enabled!(Level::Debug) {
-        info!("第一层入口点数量: {}", roots.len());
-    }
+    let found = thread::scope(|scope| {
+        let cancelled = &cancelled;
+        let consumer_handle = scope.spawn(move || -> Result<usize> {
+            let mut found = 0usize;
+            let mut last_report = Instant::now();
+
+            // 不断接收直到所有 Sender 关闭
+            while let Ok(chain) = rx.recv() {
+                let depth = chain.depth() as u32;
+                if let Err(e) = sink(chain) {
+                    // 写入失败时让生产者尽快停下
+                    cancelled.store(true, AtomicOrdering::Relaxed);
+                    return Err(e);
+                }
+                found += 1;
+
+                // 限制回调频率，避免刷新太快拖慢速度 (例如每 100ms 刷新一次)
+                if last_report.elapsed() >= Duration::from_millis(100) {
+                    progress_callback(depth, max_depth, found as i64, 0);
+                    last_report = Instant::now();
+                }
+            }
+
+            // 最终报告
+            progress_callback(max_depth as u32, max_depth, found as i64, 0);
+            Ok(found)
+        });
 
-    // 并行生产者 (Producers)
-    // Rayon 负责并行调度，每个任务持有一个 tx 的克隆
-    roots.par_iter().for_each_with(tx, |local_tx, (ptr_addr, offset)| {
-        if check_cancelled() {
-            cancelled.store(true, AtomicOrdering::Relaxed);
-            return;
+        // 获取第一层入口点 (反向搜索第一步)
+        let roots = find_candidate_pointers(pointer_lib, config.target_address, 0, config);
+        if log_enabled!(Level::Debug) {
+            info!("第一层入口点数量: {}", roots.len());
         }
 
-        // 初始化路径状态
-        let mut offset_history = Vec::with_capacity(config.max_depth as usize);
-        offset_history.push(*offset);
+        // 并行生产者 (Producers)
+        // Rayon 负责并行调度，每个任务持有一个 tx 的克隆
+        roots.par_iter().for_each_with(tx, |local_tx, (ptr_addr, offset)| {
+            if check_cancelled() {
+                cancelled.store(true, AtomicOrdering::Relaxed);
+                return;
+            }
+
+            // 初始化路径状态
+            let mut offset_history = Vec::with_capacity(config.max_depth as usize);
+            offset_history.push(*offset);
 
-        // 环路检测：记录路径上的地址
-        let mut visited_addrs = Vec::with_capacity(config.max_depth as usize);
-        visited_addrs.push(config.target_address); // 目标本身
-        visited_addrs.push(*ptr_addr);
+            // 环路检测：记录路径上的地址
+            let mut visited_addrs = Vec::with_capacity(config.max_depth as usize);
+            visited_addrs.push(config.target_address); // 目标本身
+            visited_addrs.push(*ptr_addr);
 
-        // 开始递归
-        dfs_recursive(&ctx, local_tx, *ptr_addr, 1, &mut offset_history, &mut visited_addrs);
-    });
+            // 开始递归
+            dfs_recursive(&ctx, local_tx, *ptr_addr, 1, &mut offset_history, &mut visited_addrs);
+        });
 
-    // 所有 Rayon 任务完成后，local_tx 会被自动 Drop。
-    // 当所有 Sender 都 Drop 后，rx.recv() 会返回 Err，消费者线程随之结束。
+        // 所有 Rayon 任务完成后，local_tx 会被自动 Drop。
+        // 当所有 Sender 都 Drop 后，rx.recv() 会返回 Err，消费者线程随之结束。
 
-    // 等待结果
-    let final_results = consumer_handle.join().map_err(|_| anyhow::anyhow!("消费者线程崩溃"))?;
+        // 等待结果
+        consumer_handle.join().map_err(|_| anyhow::anyhow!("消费者线程崩溃"))?
+    })?;
 
     if log_enabled!(Level::Debug) {
-        info!("DFS 扫描完成，共找到 {} 条链", final_results.len());
+        info!("DFS 扫描完成，共找到 {} 条链", found);
     }
 
-    Ok(final_results)
+    Ok(found)
 }
 
 /// 核心递归函数
@@ -111,8 +122,7 @@ fn dfs_recursive(ctx: &DfsContext, tx: &Sender<PointerChain>, current_address: u
 
     let config = ctx.config;
     // 检查是否到达静态基址
-    if let Some((mod_name, mod_idx, base_offset)) = classify_pointer(current_address, ctx.static_modules, config.data_start, config.bss_start, config.max_offset)
-    {
+    if let Some((mod_name, mod_idx, base_offset)) = classify_pointer(current_address, &ctx.static_modules, config.data_start) {
         // 构建链条
         let mut chain = PointerChain::with_capacity(ctx.config.target_address, offset_history.len() + 1);
         chain.push(PointerChainStep::static_root(mod_name, mod_idx, base_offset as i64));
@@ -135,7 +145,7 @@ fn dfs_recursive(ctx: &DfsContext, tx: &Sender<PointerChain>, current_address: u
 
     // 查找父节点
     // 这里是性能关键点：大量的随机 IO 读取
-    let parents = find_pointers_to_range(ctx.pointer_lib, current_address, ctx.config.max_offset);
+    let parents = find_candidate_pointers(ctx.pointer_lib, current_address, depth as usize, ctx.config);
 
     for (parent_addr, offset) in parents {
         // 环路检测
@@ -0,0 +1,138 @@
+//! Tests for static module lookup and access-hint seeding, plus a rough lookup benchmark
+
+use super::*;
+use crate::pointer_scan::chain_store::ChainStore;
+use crate::wuwa::BpWatchHit;
+
+/// Reference implementation: the linear scan `classify_pointer` used to do
+fn find_linear(static_modules: &[VmStaticData], address: u64) -> Option<&VmStaticData> {
+    static_modules.iter().find(|module| module.contains(address))
+}
+
+/// Modules laid out like a real process: `count` images with a few segments
+/// each, shuffled so the input isn't already sorted by base.
+fn make_modules(count: usize) -> Vec<VmStaticData> {
+    let mut modules = Vec::with_capacity(count * 3);
+    for i in 0..count as u64 {
+        let base = 0x7000_0000_0000 + i * 0x10_0000;
+        modules.push(VmStaticData::new(format!("lib{}.so", i), base, base + 0x4_0000, true));
+        modules.push(VmStaticData::new(format!("lib{}.so", i), base + 0x5_0000, base + 0x6_0000, true));
+        modules.push(VmStaticData::new(format!("lib{}.so", i), base + 0x6_0000, base + 0x6_8000, true));
+    }
+    // Deterministic shuffle
+    let len = modules.len();
+    for i in 0..len {
+        modules.swap(i, (i * 7919 + 13) % len);
+    }
+    modules
+}
+
+fn addresses(count: usize) -> Vec<u64> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            0x6FFF_FFF0_0000 + state % 0x1_0000_0000
+        })
+        .collect()
+}
+
+#[test]
+fn test_index_matches_linear_scan() {
+    let modules = make_modules(200);
+    let index = StaticModuleIndex::new(&modules);
+
+    for address in addresses(100_000) {
+        let expected = find_linear(&modules, address).map(|m| (m.base_address, m.end_address));
+        let actual = index.find(address).map(|m| (m.base_address, m.end_address));
+        assert_eq!(expected, actual, "lookup of 0x{:X}", address);
+    }
+
+    // Boundaries: base is inside, end is not
+    let module = &modules[0];
+    assert!(index.find(module.base_address).is_some());
+    assert_eq!(
+        index.find(module.end_address - 1).map(|m| m.base_address),
+        Some(module.base_address)
+    );
+}
+
+#[test]
+fn test_index_overlapping_modules() {
+    // A large mapping containing a smaller one; the earlier entry wins, as with the linear scan
+    let modules = vec![
+        VmStaticData::new("outer".to_string(), 0x1000, 0x9000, true),
+        VmStaticData::new("inner".to_string(), 0x2000, 0x3000, true),
+        VmStaticData::new("after".to_string(), 0x9000, 0xA000, true),
+    ];
+    let index = StaticModuleIndex::new(&modules);
+
+    assert_eq!(index.find(0x2500).map(|m| m.name.as_str()), Some("outer"));
+    assert_eq!(index.find(0x5000).map(|m| m.name.as_str()), Some("outer"));
+    assert_eq!(index.find(0x9000).map(|m| m.name.as_str()), Some("after"));
+    assert!(index.find(0xA000).is_none());
+    assert!(index.find(0x0FFF).is_none());
+
+    let reversed: Vec<VmStaticData> = modules.iter().rev().cloned().collect();
+    let index = StaticModuleIndex::new(&reversed);
+    assert_eq!(index.find(0x2500).map(|m| m.name.as_str()), Some("inner"));
+}
+
+fn watch_hit(pc: u64, addr: u64, regs: &[(usize, u64)]) -> BpWatchHit {
+    let mut hit = BpWatchHit { pc, addr, ..Default::default() };
+    for &(n, value) in regs {
+        hit.regs[n] = value;
+    }
+    hit
+}
+
+#[test]
+fn test_access_bases_uses_instruction_base_register() {
+    let pc = 0x7000_1000;
+    let insn: [u8; 4] = crate::disasm::assemble_arm64("ldr w0, [x19, #0x48]", pc).unwrap().try_into().unwrap();
+    // x2 is also within max_offset, but the instruction only uses x19
+    let hit = watch_hit(pc, 0x5048, &[(19, 0x5000), (2, 0x5040)]);
+
+    let bases = access_bases(&[hit, hit], 0x1000, |address| (address == pc).then_some(insn));
+    assert_eq!(bases, vec![0x5000]);
+}
+
+#[test]
+fn test_access_bases_falls_back_to_nearby_registers() {
+    let hit = watch_hit(0x7000_1000, 0x5048, &[(1, 0x5000), (2, 0x5048), (3, 0x6000), (4, 0x1000)]);
+
+    let bases = access_bases(&[hit], 0x100, |_| None);
+    assert_eq!(bases, vec![0x5000, 0x5048]);
+}
+
+#[test]
+fn test_access_offsets_respects_bounds() {
+    let bases = [0x5000, 0x5040, 0x5048, 0x5050, 0x4000];
+    assert_eq!(access_offsets(0x5048, &bases, 0x100, 0x10), vec![-8, 0, 8, 0x48]);
+    assert_eq!(access_offsets(0x5048, &bases, 0x100, 0), vec![0, 8, 0x48]);
+}
+
+#[test]
+fn test_filter_chains_by_access_keeps_matching_and_unchecked() {
+    let dir = std::env::temp_dir().join(format!("mamu_access_filter_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let chain = |offsets: &[i64]| {
+        let mut chain = PointerChain::new(0x5048);
+        chain.push(PointerChainStep::static_root("libgame.so".to_string(), 0, 0x100));
+        for &offset in offsets {
+            chain.push(PointerChainStep::dynamic_offset(offset));
+        }
+        chain
+    };
+    let chains = ChainStore::from_chains(&dir, [chain(&[0x10, 0x48]), chain(&[0x48, 0x8]), chain(&[])]).unwrap();
+
+    let filtered = filter_chains_by_access(&chains, &dir, &[0x48]).unwrap();
+    let kept: Vec<usize> = filtered.iter().map(|chain| chain.steps.len()).collect();
+    assert_eq!(kept, vec![3, 1]);
+
+    drop((chains, filtered));
+    let _ = std::fs::remove_dir_all(&dir);
+}
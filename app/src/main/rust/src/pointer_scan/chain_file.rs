@@ -0,0 +1,377 @@
+//! On-disk format for pointer chain results.
+//!
+//! Chains built in Phase 2 live in a disk-backed `ChainStore`. This module
+//! persists them so results survive app restarts and can be shared between
+//! devices. Chains are written and read one at a time, so saving or loading
+//! never holds the whole result set in memory.
+//!
+//! # File layout (version 2)
+//!
+//! ```text
+//! [0-7]   magic          "MAMUPTRC"
+//! [8-11]  version        u32 LE
+//! [12-15] reserved       u32 LE (0)
+//! [16-23] target_address u64 LE
+//! [24-27] max_depth      u32 LE
+//! [28-31] max_offset     u32 LE
+//! [32-39] created_at     u64 LE
+//! [40-47] chain_count    u64 LE
+//! [48-..] chain_count records: u32 LE length + rkyv archive of one `PointerChain`
+//! ```
+//!
+//! Version 1 files (one rkyv archive of `PointerChainFile` after a 24 byte
+//! header with the payload length at [16-23]) can still be read.
+//!
+//! Every record is validated on load, so files from untrusted sources are safe to open.
+
+use crate::pointer_scan::types::PointerChain;
+use anyhow::{anyhow, Result};
+use rkyv::rancor::Error;
+use rkyv::util::AlignedVec;
+use rkyv::{Archive, Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const CHAIN_FILE_MAGIC: &[u8; 8] = b"MAMUPTRC";
+const CHAIN_FILE_VERSION: u32 = 2;
+const LEGACY_VERSION: u32 = 1;
+const LEGACY_HEADER_SIZE: usize = 24;
+const HEADER_SIZE: usize = 48;
+const COUNT_OFFSET: u64 = 40;
+/// Upper bound for one archived chain; real chains are a few hundred bytes
+const MAX_RECORD_LEN: usize = 1024 * 1024;
+
+/// Parameters of the scan that produced a chain file.
+#[derive(Debug, Clone, Default)]
+pub struct ChainFileInfo {
+    /// Target address at the time of the scan
+    pub target_address: u64,
+    /// Maximum chain depth used for the scan
+    pub max_depth: u32,
+    /// Maximum offset per level used for the scan
+    pub max_offset: u32,
+    /// Creation time, seconds since the unix epoch
+    pub created_at: u64,
+}
+
+/// Version 1 payload: the whole result set as a single archive.
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+pub struct PointerChainFile {
+    pub target_address: u64,
+    pub max_depth: u32,
+    pub max_offset: u32,
+    pub created_at: u64,
+    pub chains: Vec<PointerChain>,
+}
+
+/// Streams chains into a new chain file.
+///
+/// The file is written next to the destination first and renamed into place
+/// by [`ChainFileWriter::finish`], so an interrupted save never leaves a
+/// truncated file behind.
+pub struct ChainFileWriter {
+    file: BufWriter<File>,
+    path: PathBuf,
+    tmp_path: PathBuf,
+    count: u64,
+}
+
+impl ChainFileWriter {
+    pub fn create(path: &Path, info: &ChainFileInfo) -> Result<Self> {
+        let tmp_path = path.with_extension("tmp");
+        let mut file = BufWriter::new(File::create(&tmp_path)?);
+        file.write_all(CHAIN_FILE_MAGIC)?;
+        file.write_all(&CHAIN_FILE_VERSION.to_le_bytes())?;
+        file.write_all(&0u32.to_le_bytes())?;
+        file.write_all(&info.target_address.to_le_bytes())?;
+        file.write_all(&info.max_depth.to_le_bytes())?;
+        file.write_all(&info.max_offset.to_le_bytes())?;
+        file.write_all(&info.created_at.to_le_bytes())?;
+        // Chain count, filled in by finish()
+        file.write_all(&0u64.to_le_bytes())?;
+
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            tmp_path,
+            count: 0,
+        })
+    }
+
+    pub fn push(&mut self, chain: &PointerChain) -> Result<()> {
+        let record = rkyv::to_bytes::<Error>(chain)?;
+        if record.len() > MAX_RECORD_LEN {
+            return Err(anyhow!("Chain with {} steps is too large to save", chain.steps.len()));
+        }
+        self.file.write_all(&(record.len() as u32).to_le_bytes())?;
+        self.file.write_all(&record)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Write the chain count, sync and move the file into place.
+    ///
+    /// Returns the number of chains written.
+    pub fn finish(self) -> Result<u64> {
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(COUNT_OFFSET))?;
+        file.write_all(&self.count.to_le_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&self.tmp_path, &self.path)?;
+        Ok(self.count)
+    }
+}
+
+enum ChainSource {
+    Stream(BufReader<File>),
+    Legacy(std::vec::IntoIter<PointerChain>),
+}
+
+/// Reads the chains of a file written by [`ChainFileWriter`] one at a time.
+///
+/// Yields an error and stops at the first corrupted or missing record.
+pub struct ChainFileReader {
+    info: ChainFileInfo,
+    source: ChainSource,
+    remaining: u64,
+    record: AlignedVec<16>,
+}
+
+impl ChainFileReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut header = [0u8; HEADER_SIZE];
+        file.read_exact(&mut header[..LEGACY_HEADER_SIZE]).map_err(|_| anyhow!("Chain file too small"))?;
+
+        if &header[0..8] != CHAIN_FILE_MAGIC {
+            return Err(anyhow!("Not a pointer chain file"));
+        }
+
+        let version = u32::from_le_bytes(header[8..12].try_into()?);
+        match version {
+            LEGACY_VERSION => Self::open_legacy(file, &header, file_len),
+            CHAIN_FILE_VERSION => {
+                file.read_exact(&mut header[LEGACY_HEADER_SIZE..]).map_err(|_| anyhow!("Chain file too small"))?;
+                let info = ChainFileInfo {
+                    target_address: u64::from_le_bytes(header[16..24].try_into()?),
+                    max_depth: u32::from_le_bytes(header[24..28].try_into()?),
+                    max_offset: u32::from_le_bytes(header[28..32].try_into()?),
+                    created_at: u64::from_le_bytes(header[32..40].try_into()?),
+                };
+                let count = u64::from_le_bytes(header[40..48].try_into()?);
+                // Every record takes at least its length prefix
+                if count > (file_len - HEADER_SIZE as u64) / 4 {
+                    return Err(anyhow!("Corrupted chain file: {} chains do not fit in {} bytes", count, file_len));
+                }
+
+                Ok(Self {
+                    info,
+                    source: ChainSource::Stream(BufReader::new(file)),
+                    remaining: count,
+                    record: AlignedVec::new(),
+                })
+            },
+            _ => Err(anyhow!("Unsupported chain file version: {}", version)),
+        }
+    }
+
+    fn open_legacy(mut file: File, header: &[u8], file_len: u64) -> Result<Self> {
+        let payload_len = u64::from_le_bytes(header[16..24].try_into()?) as usize;
+        let expected_len = LEGACY_HEADER_SIZE
+            .checked_add(payload_len)
+            .ok_or_else(|| anyhow!("Corrupted chain file: payload length {} overflows", payload_len))?;
+        if expected_len as u64 > file_len {
+            return Err(anyhow!("Chain file truncated: expected {} bytes, got {}", expected_len, file_len));
+        }
+
+        let mut payload = AlignedVec::<16>::with_capacity(payload_len);
+        payload.resize(payload_len, 0);
+        file.read_exact(&mut payload)?;
+
+        let data = rkyv::from_bytes::<PointerChainFile, Error>(&payload).map_err(|e| anyhow!("Corrupted chain file: {}", e))?;
+        Ok(Self {
+            info: ChainFileInfo {
+                target_address: data.target_address,
+                max_depth: data.max_depth,
+                max_offset: data.max_offset,
+                created_at: data.created_at,
+            },
+            remaining: data.chains.len() as u64,
+            source: ChainSource::Legacy(data.chains.into_iter()),
+            record: AlignedVec::new(),
+        })
+    }
+
+    pub fn info(&self) -> &ChainFileInfo {
+        &self.info
+    }
+
+    /// Number of chains not read yet.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    fn read_record(&mut self) -> Result<PointerChain> {
+        let stream = match &mut self.source {
+            ChainSource::Stream(stream) => stream,
+            ChainSource::Legacy(chains) => return chains.next().ok_or_else(|| anyhow!("Chain file truncated")),
+        };
+
+        let mut len = [0u8; 4];
+        stream.read_exact(&mut len).map_err(|_| anyhow!("Chain file truncated"))?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_RECORD_LEN {
+            return Err(anyhow!("Corrupted chain file: record of {} bytes", len));
+        }
+
+        self.record.clear();
+        self.record.resize(len, 0);
+        stream.read_exact(&mut self.record).map_err(|_| anyhow!("Chain file truncated"))?;
+        rkyv::from_bytes::<PointerChain, Error>(&self.record).map_err(|e| anyhow!("Corrupted chain file: {}", e))
+    }
+}
+
+impl Iterator for ChainFileReader {
+    type Item = Result<PointerChain>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let chain = self.read_record();
+        // Stop after the first error instead of reading garbage
+        self.remaining = if chain.is_ok() { self.remaining - 1 } else { 0 };
+        Some(chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pointer_scan::types::PointerChainStep;
+
+    fn test_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("mamu_chain_file_{}_{}.mptr", name, std::process::id()))
+    }
+
+    fn sample_info() -> ChainFileInfo {
+        ChainFileInfo {
+            target_address: 0x7000_1234,
+            max_depth: 5,
+            max_offset: 0x1000,
+            created_at: 1_700_000_000,
+        }
+    }
+
+    fn sample_chains() -> Vec<PointerChain> {
+        let mut chain = PointerChain::new(0x7000_1234);
+        chain.push(PointerChainStep::static_root("libgame.so".to_string(), 1, 0x1A2B0));
+        chain.push(PointerChainStep::dynamic_offset(0x18));
+        chain.push(PointerChainStep::dynamic_offset(-0x20));
+        vec![chain.clone(), PointerChain::new(0x7000_1234), chain]
+    }
+
+    fn paths(chains: &[PointerChain]) -> Vec<(Vec<PointerChainStep>, u64)> {
+        chains.iter().map(|c| (c.steps.clone(), c.target_address)).collect()
+    }
+
+    fn save(path: &Path) {
+        let mut writer = ChainFileWriter::create(path, &sample_info()).unwrap();
+        for chain in sample_chains() {
+            writer.push(&chain).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), 3);
+    }
+
+    fn load(path: &Path) -> Result<Vec<PointerChain>> {
+        ChainFileReader::open(path)?.collect()
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let path = test_path("roundtrip");
+        save(&path);
+
+        let reader = ChainFileReader::open(&path).unwrap();
+        let info = reader.info().clone();
+        assert_eq!(reader.remaining(), 3);
+        assert_eq!(info.target_address, 0x7000_1234);
+        assert_eq!(info.max_depth, 5);
+        assert_eq!(info.max_offset, 0x1000);
+        assert_eq!(info.created_at, 1_700_000_000);
+        assert_eq!(paths(&reader.collect::<Result<Vec<_>>>().unwrap()), paths(&sample_chains()));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_legacy_file() {
+        let path = test_path("legacy");
+        let info = sample_info();
+        let data = PointerChainFile {
+            target_address: info.target_address,
+            max_depth: info.max_depth,
+            max_offset: info.max_offset,
+            created_at: info.created_at,
+            chains: sample_chains(),
+        };
+        let payload = rkyv::to_bytes::<Error>(&data).unwrap();
+        let mut bytes = CHAIN_FILE_MAGIC.to_vec();
+        bytes.extend_from_slice(&LEGACY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        std::fs::write(&path, &bytes).unwrap();
+
+        assert_eq!(paths(&load(&path).unwrap()), paths(&sample_chains()));
+
+        // A payload length that overflows the header offset is rejected
+        bytes[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(ChainFileReader::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_bad_count() {
+        let path = test_path("bad_count");
+        save(&path);
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(ChainFileReader::open(&path).is_err());
+
+        // More chains announced than stored
+        bytes[40..48].copy_from_slice(&4u64.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(load(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_corrupted_record() {
+        let path = test_path("corrupt");
+        save(&path);
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        // Steps pointer of the last chain's root, the target address follows it
+        let len = bytes.len();
+        bytes[len - 16..len - 8].fill(0xFF);
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(load(&path).is_err());
+
+        // Oversized record length
+        bytes[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(load(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,29 @@
+//! Operations over collections of pointer chains.
+
+use crate::pointer_scan::chain_store::ChainStore;
+use crate::pointer_scan::chain_trie::ChainTrie;
+use crate::pointer_scan::types::{PointerChain, PointerChainStep};
+use anyhow::Result;
+use log::info;
+use std::path::Path;
+
+/// Keep only chains of `chains` whose module+offset path also appears in `other`.
+///
+/// Chains are compared by their steps, not by the address they resolve to, so
+/// two scans taken before and after a game restart can be intersected: chains
+/// that only existed by coincidence in one run drop out. The result is streamed
+/// into a new store in `cache_dir`.
+pub fn intersect_chains(chains: &ChainStore, other: &ChainTrie, cache_dir: &Path) -> Result<ChainStore> {
+    let result = chains.filter(cache_dir, |chain| other.contains(chain))?;
+    info!("Chain intersection: {} & {} -> {}", chains.len(), other.len(), result.len());
+    Ok(result)
+}
+
+/// Append `tail` to `chain` and point it at `target_address`.
+///
+/// Used after re-rooting: each chain found for the intermediate slot becomes
+/// a new prefix for the levels that were kept from the original chain.
+pub fn graft_tail(chain: &mut PointerChain, tail: &[PointerChainStep], target_address: u64) {
+    chain.steps.extend_from_slice(tail);
+    chain.target_address = target_address;
+}
@@ -0,0 +1,87 @@
+//! Disk-backed Phase 2 results.
+//!
+//! Large games can yield tens of millions of chains, far more than fit in
+//! RAM as `PointerChain` values. Phase 2 streams every chain it finds into a
+//! `ChainStore`, an indexed `MmapQueue<PointerChain>`, and the UI pages
+//! through it with [`ChainStore::range`]. Only the `(offset, len)` index of
+//! each chain stays in memory.
+
+use crate::pointer_scan::storage::MmapQueue;
+use crate::pointer_scan::types::PointerChain;
+use anyhow::{anyhow, Result};
+use rkyv::rancor::Error;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Every store gets its own backing file: a filtered store is written while
+/// the one it is derived from is still open.
+static NEXT_STORE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Chain results of the last build, empty until a build or load produced some.
+#[derive(Default)]
+pub struct ChainStore {
+    queue: Option<MmapQueue<PointerChain>>,
+}
+
+impl ChainStore {
+    /// Create an empty store backed by a new file in `cache_dir`.
+    pub fn create(cache_dir: &Path) -> Result<Self> {
+        let name = format!("chains_{}_{}", std::process::id(), NEXT_STORE_ID.fetch_add(1, Ordering::Relaxed));
+        let queue = MmapQueue::new(&cache_dir.to_path_buf(), &name)?;
+        Ok(Self { queue: Some(queue) })
+    }
+
+    /// Create a store holding `chains`.
+    pub fn from_chains(cache_dir: &Path, chains: impl IntoIterator<Item = PointerChain>) -> Result<Self> {
+        let mut store = Self::create(cache_dir)?;
+        for chain in chains {
+            store.push(&chain)?;
+        }
+        Ok(store)
+    }
+
+    pub fn push(&mut self, chain: &PointerChain) -> Result<()> {
+        self.queue.as_mut().ok_or_else(|| anyhow!("Chain store has no backing file"))?.push(chain)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.as_ref().map_or(0, |queue| queue.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<PointerChain> {
+        let archived = self.queue.as_ref()?.get(index)?;
+        rkyv::deserialize::<PointerChain, Error>(archived).ok()
+    }
+
+    /// Chains in `range`, clamped to the store length.
+    pub fn range(&self, range: Range<usize>) -> Vec<PointerChain> {
+        let end = range.end.min(self.len());
+        (range.start.min(end)..end).filter_map(|index| self.get(index)).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = PointerChain> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Write the chains accepted by `keep` into a new store.
+    pub fn filter<P>(&self, cache_dir: &Path, mut keep: P) -> Result<Self>
+    where
+        P: FnMut(&PointerChain) -> bool,
+    {
+        Self::from_chains(cache_dir, self.iter().filter(|chain| keep(chain)))
+    }
+
+    /// Drop all chains and delete the backing file.
+    pub fn clear(&mut self) {
+        self.queue = None;
+    }
+
+    pub fn file_path(&self) -> Option<&PathBuf> {
+        self.queue.as_ref().map(|queue| queue.file_path())
+    }
+}
@@ -0,0 +1,138 @@
+//! Prefix-compressed chain set.
+//!
+//! Chains found for one target share long prefixes: the same static root and
+//! the first few offsets show up in thousands of results. `ChainTrie` stores
+//! every distinct step once and every chain as a path of node indices, so a
+//! chain costs one node per level it doesn't share with an earlier chain.
+//! Inserting a chain whose module+offset path is already present is a no-op,
+//! which makes the trie a deduplication pass as well.
+
+use crate::pointer_scan::types::{PointerChain, PointerChainStep};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// Index of the virtual root node every chain starts from.
+const ROOT: u32 = 0;
+
+#[derive(Default)]
+pub struct ChainTrie {
+    /// Distinct steps, referenced by index from the nodes
+    steps: Vec<PointerChainStep>,
+    step_ids: HashMap<PointerChainStep, u32>,
+    /// Parent and step of each node; index 0 is the root and has neither
+    parents: Vec<u32>,
+    node_steps: Vec<u32>,
+    children: HashMap<(u32, u32), u32>,
+    /// Last node of each stored chain, in insertion order
+    leaves: Vec<u32>,
+    targets: Vec<u64>,
+    is_leaf: Vec<bool>,
+}
+
+impl ChainTrie {
+    pub fn new() -> Self {
+        Self {
+            parents: vec![ROOT],
+            node_steps: vec![u32::MAX],
+            is_leaf: vec![false],
+            ..Default::default()
+        }
+    }
+
+    fn step_id(&mut self, step: &PointerChainStep) -> u32 {
+        if let Some(&id) = self.step_ids.get(step) {
+            return id;
+        }
+        let id = self.steps.len() as u32;
+        self.steps.push(step.clone());
+        self.step_ids.insert(step.clone(), id);
+        id
+    }
+
+    /// Add `chain`. Returns false if a chain with the same path is already stored.
+    pub fn insert(&mut self, chain: &PointerChain) -> bool {
+        let mut node = ROOT;
+        for step in &chain.steps {
+            let step_id = self.step_id(step);
+            node = match self.children.entry((node, step_id)) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let child = self.parents.len() as u32;
+                    self.parents.push(node);
+                    self.node_steps.push(step_id);
+                    self.is_leaf.push(false);
+                    *entry.insert(child)
+                },
+            };
+        }
+
+        if node == ROOT || self.is_leaf[node as usize] {
+            return false;
+        }
+        self.is_leaf[node as usize] = true;
+        self.leaves.push(node);
+        self.targets.push(chain.target_address);
+        true
+    }
+
+    /// Check whether a chain with the same module+offset path as `chain` is stored,
+    /// regardless of the address either chain resolves to.
+    pub fn contains(&self, chain: &PointerChain) -> bool {
+        let mut node = ROOT;
+        for step in &chain.steps {
+            let Some(&step_id) = self.step_ids.get(step) else {
+                return false;
+            };
+            match self.children.get(&(node, step_id)) {
+                Some(&child) => node = child,
+                None => return false,
+            }
+        }
+        self.is_leaf[node as usize]
+    }
+
+    /// Number of distinct chains.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Number of stored steps, i.e. trie nodes without the root.
+    pub fn node_count(&self) -> usize {
+        self.parents.len() - 1
+    }
+
+    /// Rebuild chain `index`, counted in insertion order.
+    pub fn get(&self, index: usize) -> Option<PointerChain> {
+        let mut node = *self.leaves.get(index)?;
+        let mut steps = Vec::new();
+        while node != ROOT {
+            steps.push(self.steps[self.node_steps[node as usize] as usize].clone());
+            node = self.parents[node as usize];
+        }
+        steps.reverse();
+
+        Some(PointerChain {
+            steps,
+            target_address: self.targets[index],
+        })
+    }
+
+    /// Distinct chains in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = PointerChain> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+impl FromIterator<PointerChain> for ChainTrie {
+    fn from_iter<I: IntoIterator<Item = PointerChain>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for chain in iter {
+            trie.insert(&chain);
+        }
+        trie
+    }
+}
@@ -0,0 +1,175 @@
+//! Phase 1 checkpoints.
+//!
+//! Android may kill the app while a long pointer scan runs in the background.
+//! The Phase 1 writer records its progress after every temp file flush: the
+//! regions whose pointers are all on disk, the sorted temp chunks holding
+//! them and the running pointer count. A later run loads the checkpoint and
+//! scans only the remaining regions before the usual k-way merge.
+//!
+//! Regions still buffered in memory when the app dies are not recorded and
+//! are simply scanned again, so a checkpoint never refers to partial data.
+
+use crate::pointer_scan::scanner::ScanRegion;
+use crate::pointer_scan::types::{PointerScanConfig, VmStaticData};
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the checkpoint file inside the cache directory.
+pub const CHECKPOINT_FILE_NAME: &str = "mamu_ps_checkpoint.json";
+
+/// Progress of an interrupted Phase 1 scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// Process the scan was started against, a resumed scan must target the same one
+    pub pid: i32,
+    pub config: PointerScanConfig,
+    pub regions: Vec<ScanRegion>,
+    /// Static modules handed to Phase 2 once the scan completes
+    pub static_modules: Vec<VmStaticData>,
+    /// Indices into `regions` whose pointers are all stored in `temp_files`
+    pub completed: Vec<usize>,
+    /// Sorted temp chunks written so far
+    pub temp_files: Vec<PathBuf>,
+    /// Pointers stored in `temp_files`
+    pub pointers_found: usize,
+    /// Last save time, seconds since the unix epoch
+    pub updated_at: u64,
+}
+
+/// Short description of a checkpoint for the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointSummary {
+    pub pid: i32,
+    pub target_address: u64,
+    pub background: bool,
+    pub total_regions: usize,
+    pub completed_regions: usize,
+    pub pointers_found: usize,
+    pub temp_files: usize,
+    pub updated_at: u64,
+}
+
+impl ScanCheckpoint {
+    pub fn new(pid: i32, config: PointerScanConfig, regions: Vec<ScanRegion>, static_modules: Vec<VmStaticData>) -> Self {
+        Self {
+            pid,
+            config,
+            regions,
+            static_modules,
+            completed: Vec::new(),
+            temp_files: Vec::new(),
+            pointers_found: 0,
+            updated_at: 0,
+        }
+    }
+
+    pub fn path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Indices of the regions that still have to be scanned.
+    pub fn remaining_regions(&self) -> Vec<usize> {
+        let mut done = vec![false; self.regions.len()];
+        for &index in &self.completed {
+            if let Some(flag) = done.get_mut(index) {
+                *flag = true;
+            }
+        }
+        (0..self.regions.len()).filter(|&index| !done[index]).collect()
+    }
+
+    /// Record a flushed temp file and the regions it completes, then save.
+    ///
+    /// A failed save is logged and otherwise ignored: the scan itself can
+    /// still finish, it just cannot be resumed from this point.
+    pub fn commit(&mut self, cache_dir: &Path, temp_file: Option<PathBuf>, regions: &mut Vec<usize>, pointers: usize) {
+        self.temp_files.extend(temp_file);
+        self.completed.append(regions);
+        self.pointers_found += pointers;
+
+        if let Err(e) = self.save(cache_dir) {
+            warn!("Failed to save scan checkpoint: {}", e);
+        }
+    }
+
+    /// Write the checkpoint atomically, replacing the previous one.
+    pub fn save(&mut self, cache_dir: &Path) -> Result<()> {
+        self.updated_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        let path = Self::path(cache_dir);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Load the checkpoint in `cache_dir`, if any.
+    ///
+    /// A checkpoint whose temp files are gone (merged, or pruned by cache
+    /// maintenance) can't be resumed and is reported as an error.
+    pub fn load(cache_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(cache_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let checkpoint: Self = serde_json::from_slice(&std::fs::read(&path)?)?;
+        if let Some(missing) = checkpoint.temp_files.iter().find(|file| !file.exists()) {
+            return Err(anyhow!("Scan checkpoint is stale, temp file {:?} is missing", missing));
+        }
+        if checkpoint.completed.iter().any(|&index| index >= checkpoint.regions.len()) {
+            return Err(anyhow!("Scan checkpoint refers to unknown regions"));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Remove the checkpoint file, keeping its temp files.
+    ///
+    /// Used once the temp files have been merged into the pointer library.
+    pub fn remove(cache_dir: &Path) {
+        let path = Self::path(cache_dir);
+        if path.exists()
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            warn!("Failed to remove scan checkpoint: {}", e);
+        }
+    }
+
+    /// Remove the checkpoint file together with its temp files.
+    ///
+    /// Returns whether a checkpoint existed.
+    pub fn discard(cache_dir: &Path) -> bool {
+        let path = Self::path(cache_dir);
+        if !path.exists() {
+            return false;
+        }
+
+        // A stale or unreadable checkpoint is dropped all the same
+        if let Ok(data) = std::fs::read(&path)
+            && let Ok(checkpoint) = serde_json::from_slice::<Self>(&data)
+        {
+            for file in &checkpoint.temp_files {
+                let _ = std::fs::remove_file(file);
+            }
+            info!("Discarded scan checkpoint with {} temp files", checkpoint.temp_files.len());
+        }
+        Self::remove(cache_dir);
+        true
+    }
+
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            pid: self.pid,
+            target_address: self.config.target_address,
+            background: self.config.background,
+            total_regions: self.regions.len(),
+            completed_regions: self.completed.len(),
+            pointers_found: self.pointers_found,
+            temp_files: self.temp_files.len(),
+            updated_at: self.updated_at,
+        }
+    }
+}
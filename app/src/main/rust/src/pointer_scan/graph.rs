@@ -0,0 +1,163 @@
+//! Pointer graph export.
+//!
+//! Walks the pointer library backwards from a target address, the same way
+//! Phase 2 does, but keeps every slot and reference it visits instead of only
+//! complete chains. The result is a bounded graph that external tools
+//! (Graphviz, Gephi, a browser) can lay out to show how objects reference
+//! each other around the target.
+
+use crate::pointer_scan::chain_builder::{classify_pointer, find_pointers_to_range, StaticModuleIndex};
+use crate::pointer_scan::storage::MmapQueue;
+use crate::pointer_scan::types::{PointerData, VmStaticData};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+/// Output format of an exported graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+impl GraphFormat {
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::Dot),
+            1 => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A pointer slot, or the target itself at depth 0.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub address: u64,
+    /// `module[index]+0xOFFSET` for static slots, the hex address otherwise
+    pub label: String,
+    pub is_static: bool,
+    /// Number of references between this node and the target
+    pub depth: u32,
+}
+
+/// `from` holds a pointer that reaches `to` after adding `offset`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct GraphEdge {
+    pub from: u64,
+    pub to: u64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PointerGraph {
+    pub target: u64,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// True when `max_nodes` stopped the walk before `max_depth`
+    pub truncated: bool,
+}
+
+/// Build the reference graph around `target`.
+///
+/// Static slots are roots and are not expanded further. References to nodes
+/// beyond `max_nodes` are dropped and mark the graph as truncated.
+pub fn build_pointer_graph(
+    pointer_lib: &MmapQueue<PointerData>,
+    static_modules: &[VmStaticData],
+    data_start: bool,
+    target: u64,
+    max_depth: u32,
+    max_offset: u32,
+    max_nodes: usize,
+) -> PointerGraph {
+    let mut nodes = vec![GraphNode {
+        address: target,
+        label: format!("0x{:X}", target),
+        is_static: false,
+        depth: 0,
+    }];
+    let mut index: HashMap<u64, usize> = HashMap::from([(target, 0)]);
+    let mut edges = Vec::new();
+    let mut truncated = false;
+    let static_index = StaticModuleIndex::new(static_modules);
+
+    let mut queue = VecDeque::from([(target, 0u32)]);
+    while let Some((address, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+
+        for (slot, offset) in find_pointers_to_range(pointer_lib, address, max_offset, 0) {
+            if let Entry::Vacant(entry) = index.entry(slot) {
+                if nodes.len() >= max_nodes {
+                    truncated = true;
+                    continue;
+                }
+
+                let root = classify_pointer(slot, &static_index, data_start);
+                let label = match root {
+                    Some((ref name, module_index, offset)) => format!("{}[{}]+0x{:X}", name, module_index, offset),
+                    None => format!("0x{:X}", slot),
+                };
+                entry.insert(nodes.len());
+                nodes.push(GraphNode {
+                    address: slot,
+                    label,
+                    is_static: root.is_some(),
+                    depth: depth + 1,
+                });
+                if root.is_none() {
+                    queue.push_back((slot, depth + 1));
+                }
+            }
+
+            edges.push(GraphEdge { from: slot, to: address, offset });
+        }
+    }
+
+    PointerGraph {
+        target,
+        nodes,
+        edges,
+        truncated,
+    }
+}
+
+impl PointerGraph {
+    pub fn to_dot(&self) -> String {
+        let mut out = String::with_capacity(64 * (self.nodes.len() + self.edges.len()));
+        out.push_str("digraph pointers {\n    rankdir=LR;\n    node [shape=box, fontname=monospace];\n");
+
+        for node in &self.nodes {
+            let style = if node.address == self.target {
+                ", style=filled, fillcolor=salmon"
+            } else if node.is_static {
+                ", style=filled, fillcolor=lightblue"
+            } else {
+                ""
+            };
+            let _ = writeln!(out, "    \"0x{:X}\" [label=\"{}\\n(depth {})\"{}];", node.address, node.label, node.depth, style);
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(out, "    \"0x{:X}\" -> \"0x{:X}\" [label=\"+0x{:X}\"];", edge.from, edge.to, edge.offset);
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| anyhow!("Failed to serialize pointer graph: {}", e))
+    }
+
+    pub fn render(&self, format: GraphFormat) -> Result<String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Json => self.to_json(),
+        }
+    }
+}
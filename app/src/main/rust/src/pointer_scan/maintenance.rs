@@ -0,0 +1,157 @@
+//! Cache directory maintenance.
+//!
+//! Pointer libraries, Phase 1 temp chunks and search result spill files all
+//! live in the app cache directory. Most of them are cleaned up on drop, but
+//! files left behind by a crash or a killed process are never reclaimed.
+//! This module lists those files with their sizes and ages and deletes the
+//! ones the user selects (or the ones older than a given age).
+
+use anyhow::Result;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Kind of a file owned by mamu inside the cache directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum CacheFileKind {
+    /// `MmapQueue` backing file (`mamu_ps_*.bin`), e.g. the Phase 1 pointer library
+    PointerLibrary = 0,
+    /// Sorted Phase 1 chunk waiting for the k-way merge (`scan_chunk_*.tmp`)
+    ScanChunk = 1,
+    /// Exact search results spilled to disk
+    SearchResults = 2,
+    /// Fuzzy search results spilled to disk
+    FuzzyResults = 3,
+    /// JSON report of the last search operation
+    RunReport = 4,
+    /// Progress of an interrupted pointer scan
+    ScanCheckpoint = 5,
+}
+
+impl CacheFileKind {
+    /// Classify a file by its name. Returns `None` for files mamu didn't create.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        if name.starts_with("mamu_ps_") && name.ends_with(".bin") {
+            Some(CacheFileKind::PointerLibrary)
+        } else if name.starts_with("scan_chunk_") && name.ends_with(".tmp") {
+            Some(CacheFileKind::ScanChunk)
+        } else if name == "mamu_search_results.bin" {
+            Some(CacheFileKind::SearchResults)
+        } else if name == "mamu_fuzzy_results.bin" {
+            Some(CacheFileKind::FuzzyResults)
+        } else if name == crate::search::engine::report::REPORT_FILE_NAME {
+            Some(CacheFileKind::RunReport)
+        } else if name == crate::pointer_scan::checkpoint::CHECKPOINT_FILE_NAME {
+            Some(CacheFileKind::ScanCheckpoint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A single file found in the cache directory.
+#[derive(Debug, Clone)]
+pub struct CacheFileInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: CacheFileKind,
+    /// File size in bytes (allocated length, including unused mmap tail)
+    pub size: u64,
+    /// Last modification time, seconds since the unix epoch
+    pub modified_secs: u64,
+}
+
+impl CacheFileInfo {
+    /// Age in seconds relative to `now_secs`.
+    pub fn age_secs(&self, now_secs: u64) -> u64 {
+        now_secs.saturating_sub(self.modified_secs)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// List all mamu-owned files in `cache_dir`, largest first.
+pub fn list_cache_files(cache_dir: &Path) -> Result<Vec<CacheFileInfo>> {
+    let mut files = Vec::new();
+
+    if !cache_dir.exists() {
+        return Ok(files);
+    }
+
+    for entry in std::fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(kind) = CacheFileKind::from_file_name(&name) else {
+            continue;
+        };
+
+        let metadata = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+
+        let modified_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        files.push(CacheFileInfo {
+            name,
+            path: entry.path(),
+            kind,
+            size: metadata.len(),
+            modified_secs,
+        });
+    }
+
+    files.sort_by_key(|f| std::cmp::Reverse(f.size));
+    Ok(files)
+}
+
+/// Delete the named files from `cache_dir`.
+///
+/// Only plain file names of mamu-owned files are accepted; anything in
+/// `in_use` is skipped. Returns `(deleted_count, freed_bytes)`.
+pub fn delete_cache_files(cache_dir: &Path, names: &[String], in_use: &[PathBuf]) -> Result<(usize, u64)> {
+    let files = list_cache_files(cache_dir)?;
+    let selected = files.into_iter().filter(|f| names.iter().any(|n| n == &f.name)).collect::<Vec<_>>();
+    Ok(remove_files(selected, in_use))
+}
+
+/// Delete every mamu-owned file in `cache_dir` older than `max_age_secs`.
+///
+/// Returns `(deleted_count, freed_bytes)`.
+pub fn prune_cache_files(cache_dir: &Path, max_age_secs: u64, in_use: &[PathBuf]) -> Result<(usize, u64)> {
+    let now = now_secs();
+    let files = list_cache_files(cache_dir)?;
+    let selected = files.into_iter().filter(|f| f.age_secs(now) >= max_age_secs).collect::<Vec<_>>();
+    Ok(remove_files(selected, in_use))
+}
+
+fn remove_files(files: Vec<CacheFileInfo>, in_use: &[PathBuf]) -> (usize, u64) {
+    let mut deleted = 0usize;
+    let mut freed = 0u64;
+
+    for file in files {
+        if in_use.iter().any(|p| p == &file.path) {
+            warn!("Skipping cache file in use: {}", file.name);
+            continue;
+        }
+
+        match std::fs::remove_file(&file.path) {
+            Ok(()) => {
+                deleted += 1;
+                freed += file.size;
+            },
+            Err(e) => warn!("Failed to remove cache file {}: {}", file.name, e),
+        }
+    }
+
+    info!("Cache maintenance removed {} files, freed {} bytes", deleted, freed);
+    (deleted, freed)
+}
@@ -4,20 +4,37 @@
 //! It coordinates Phase 1 (pointer scanning) and Phase 2 (chain building),
 //! manages async execution, and provides JNI-accessible state.
 
-use crate::core::globals::TOKIO_RUNTIME;
+use crate::core::globals::{PAGE_SIZE, TOKIO_RUNTIME, WATCH_MANAGER};
+use crate::core::DRIVER_MANAGER;
 use crate::pointer_scan::chain_builder;
+use crate::pointer_scan::chain_file::{ChainFileInfo, ChainFileReader, ChainFileWriter};
+use crate::pointer_scan::chain_ops;
+use crate::pointer_scan::chain_store::ChainStore;
+use crate::pointer_scan::chain_trie::ChainTrie;
+use crate::pointer_scan::checkpoint::{CheckpointSummary, ScanCheckpoint};
+use crate::pointer_scan::graph::{self, GraphFormat};
+use crate::pointer_scan::maintenance;
 use crate::pointer_scan::scanner::{self, ScanRegion};
 use crate::pointer_scan::shared_buffer::PointerScanSharedBuffer;
 use crate::pointer_scan::storage::MmapQueue;
-use crate::pointer_scan::types::{PointerChain, PointerData, PointerScanConfig, ScanErrorCode, ScanPhase, VmStaticData};
+use crate::pointer_scan::types::{PointerChain, PointerChainStep, PointerData, PointerScanConfig, ScanErrorCode, ScanPhase, ScanReport, VmStaticData};
+use crate::pointer_scan::validator::{self, ChainValidation};
 use anyhow::{anyhow, Result};
 use lazy_static::lazy_static;
 use log::{error, info, log_enabled, warn, Level};
-use std::path::PathBuf;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
+/// Levels kept from a re-rooted chain, appended to every chain found for the new root.
+struct RerootTail {
+    steps: Vec<PointerChainStep>,
+    target_address: u64,
+}
+
 lazy_static! {
     pub static ref POINTER_SCAN_MANAGER: RwLock<PointerScanManager> = RwLock::new(PointerScanManager::new());
 }
@@ -27,7 +44,7 @@ pub struct PointerScanManager {
     /// Pointer library built in Phase 1
     pointer_library: Option<MmapQueue<PointerData>>,
     /// Pointer chain results from Phase 2
-    chain_results: Vec<PointerChain>,
+    chain_results: ChainStore,
     /// Current scan configuration
     config: PointerScanConfig,
     /// Shared buffer for progress communication
@@ -42,6 +59,10 @@ pub struct PointerScanManager {
     current_phase: ScanPhase,
     /// Last error code
     last_error: ScanErrorCode,
+    /// Phase 1 writer statistics of the last scan
+    last_scan_report: Option<ScanReport>,
+    /// Last-level offsets a watchpoint saw on a target, as (target, offsets)
+    access_seed: Option<(u64, Vec<i64>)>,
 }
 
 impl PointerScanManager {
@@ -49,7 +70,7 @@ impl PointerScanManager {
     pub fn new() -> Self {
         Self {
             pointer_library: None,
-            chain_results: Vec::new(),
+            chain_results: ChainStore::default(),
             config: PointerScanConfig::default(),
             shared_buffer: PointerScanSharedBuffer::new(),
             cancel_token: None,
@@ -57,6 +78,8 @@ impl PointerScanManager {
             cache_dir: PathBuf::from("/data/data/moe.fuqiuluo.mamu/cache"),
             current_phase: ScanPhase::Idle,
             last_error: ScanErrorCode::None,
+            last_scan_report: None,
+            access_seed: None,
         }
     }
 
@@ -109,17 +132,13 @@ impl PointerScanManager {
         self.chain_results.len()
     }
 
-    /// Get a slice of chain results.
+    /// Get a page of chain results, read from the chain store.
     pub fn get_chain_results(&self, start: usize, count: usize) -> Vec<PointerChain> {
         if log_enabled!(Level::Debug) {
             info!("PointerScanManager get_chain results(start = {}, count = {})", start, count);
         }
 
-        let end = std::cmp::min(start + count, self.chain_results.len());
-        if start >= self.chain_results.len() {
-            return Vec::new();
-        }
-        let rrt = self.chain_results[start..end].to_vec();
+        let rrt = self.chain_results.range(start..start.saturating_add(count));
 
         if log_enabled!(Level::Debug) {
             for x in &rrt {
@@ -130,6 +149,624 @@ impl PointerScanManager {
         rrt
     }
 
+    /// Seed chain building with the accesses watchpoint `watch_id` caught on `target_address`.
+    ///
+    /// The base register of each accessing instruction (x19 for `ldr w0, [x19, #0x48]`)
+    /// gives one last-level offset. Scans and chain builds for the same target only
+    /// follow those offsets at the first level, and [`Self::filter_chains_by_access`]
+    /// checks existing chains against them.
+    ///
+    /// Returns the number of distinct offsets.
+    pub fn seed_from_watch(&mut self, target_address: u64, watch_id: i32) -> Result<usize> {
+        let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+        let hits = WATCH_MANAGER
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire WatchManager lock"))?
+            .hits(&driver_manager, watch_id)?;
+        if hits.is_empty() {
+            return Err(anyhow!("Watch {} has not been hit yet", watch_id));
+        }
+
+        let read_insn = |pc: u64| {
+            let mut insn = [0u8; 4];
+            driver_manager.read_memory_unified(pc, &mut insn, None).ok().map(|_| insn)
+        };
+        let bases = chain_builder::access_bases(&hits, self.config.max_offset_at(0), read_insn);
+        // Bounded by the level limits when used, which may still change before the next scan
+        let offsets = chain_builder::access_offsets(target_address, &bases, u32::MAX, u32::MAX);
+        if offsets.is_empty() {
+            return Err(anyhow!("No usable base register in {} hits of watch {}", hits.len(), watch_id));
+        }
+
+        info!("Access seed for 0x{:X}: {} hits -> offsets {:X?}", target_address, hits.len(), offsets);
+        let count = offsets.len();
+        self.access_seed = Some((target_address, offsets));
+        Ok(count)
+    }
+
+    /// Drop the offsets set by [`Self::seed_from_watch`].
+    pub fn clear_access_seed(&mut self) {
+        self.access_seed = None;
+    }
+
+    /// Seeded offsets for `target_address`, empty when the seed belongs to another target.
+    fn access_offsets_for(&self, target_address: u64) -> Vec<i64> {
+        match &self.access_seed {
+            Some((target, offsets)) if *target == target_address => offsets.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Keep only chains whose last pointer value matches a base register seen by
+    /// [`Self::seed_from_watch`] for the current target.
+    ///
+    /// Returns the number of chains left.
+    pub fn filter_chains_by_access(&mut self) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot filter chains while scanning"));
+        }
+
+        let offsets = self.access_offsets_for(self.config.target_address);
+        if offsets.is_empty() {
+            return Err(anyhow!("No access seed for target 0x{:X}", self.config.target_address));
+        }
+        self.chain_results = chain_builder::filter_chains_by_access(&self.chain_results, &self.cache_dir, &offsets)?;
+        self.shared_buffer.write_chains_found(self.chain_results.len() as i64);
+
+        Ok(self.chain_results.len())
+    }
+
+    /// Re-resolve `count` chains starting at `start` against fresh module bases
+    /// in the currently bound process.
+    pub fn validate_chains(&self, static_modules: &[VmStaticData], start: usize, count: usize) -> Result<Vec<ChainValidation>> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot validate chains while scanning"));
+        }
+
+        let end = std::cmp::min(start.saturating_add(count), self.chain_results.len());
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+        if !driver_manager.is_process_bound() {
+            return Err(anyhow!("No process bound"));
+        }
+
+        let read_u64 = |addr: u64| {
+            let mut buf = [0u8; 8];
+            driver_manager.read_memory_unified(addr, &mut buf, None).ok().map(|_| u64::from_le_bytes(buf))
+        };
+
+        Ok(validator::validate_pointer_chains(&self.chain_results.range(start..end), static_modules, self.config.data_start, read_u64))
+    }
+
+    /// Drop every chain that no longer resolves in the currently bound process.
+    ///
+    /// Returns the number of chains left.
+    pub fn remove_invalid_chains(&mut self, static_modules: &[VmStaticData]) -> Result<usize> {
+        const PAGE: usize = 4096;
+
+        // Validate page by page so the whole store is never held in memory
+        let before = self.chain_results.len();
+        let mut valid = Vec::with_capacity(before);
+        for start in (0..before).step_by(PAGE) {
+            valid.extend(self.validate_chains(static_modules, start, PAGE)?.iter().map(|v| v.is_valid()));
+        }
+
+        let mut valid = valid.into_iter();
+        self.chain_results = self.chain_results.filter(&self.cache_dir, |_| valid.next().unwrap_or(false))?;
+        self.shared_buffer.write_chains_found(self.chain_results.len() as i64);
+
+        info!("Removed {} invalid chains, {} left", before - self.chain_results.len(), self.chain_results.len());
+        Ok(self.chain_results.len())
+    }
+
+    /// Save the current chain results to `path`.
+    ///
+    /// Returns the number of chains written.
+    pub fn save_chains(&self, path: &Path) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot save chains while scanning"));
+        }
+
+        let info = ChainFileInfo {
+            target_address: self.config.target_address,
+            max_depth: self.config.max_depth,
+            max_offset: self.config.max_offset,
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        };
+        let mut writer = ChainFileWriter::create(path, &info)?;
+        for chain in self.chain_results.iter() {
+            writer.push(&chain)?;
+        }
+        let count = writer.finish()? as usize;
+
+        info!("Saved {} chains to {:?}", count, path);
+        Ok(count)
+    }
+
+    /// Replace the current chain results with the ones stored in `path`.
+    ///
+    /// Returns the number of chains loaded.
+    pub fn load_chains(&mut self, path: &Path) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot load chains while scanning"));
+        }
+
+        // Read into a fresh store first so a corrupted file leaves the current results alone
+        let mut reader = ChainFileReader::open(path)?;
+        let info = reader.info().clone();
+        let mut chains = ChainStore::create(&self.cache_dir)?;
+        for chain in &mut reader {
+            chains.push(&chain?)?;
+        }
+
+        self.clear();
+        self.config.target_address = info.target_address;
+        self.config.max_depth = info.max_depth;
+        self.config.max_offset = info.max_offset;
+        self.chain_results = chains;
+        self.current_phase = ScanPhase::Completed;
+        self.shared_buffer.write_chains_found(self.chain_results.len() as i64);
+        self.shared_buffer.write_phase(ScanPhase::Completed);
+
+        info!("Loaded {} chains from {:?}", self.chain_results.len(), path);
+        Ok(self.chain_results.len())
+    }
+
+    /// Intersect the current chain results with the chains saved in `path`.
+    ///
+    /// Intended for scans of the same target taken across a game restart: only
+    /// chains with the same module+offset path in both runs are kept.
+    ///
+    /// Returns the number of chains left.
+    pub fn intersect_with_file(&mut self, path: &Path) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot intersect chains while scanning"));
+        }
+
+        // Only the other file's distinct paths are kept in memory, as a prefix trie
+        let mut other = ChainTrie::new();
+        for chain in ChainFileReader::open(path)? {
+            other.insert(&chain?);
+        }
+        self.chain_results = chain_ops::intersect_chains(&self.chain_results, &other, &self.cache_dir)?;
+        self.shared_buffer.write_chains_found(self.chain_results.len() as i64);
+
+        Ok(self.chain_results.len())
+    }
+
+    /// Drop chains whose module+offset path repeats an earlier one.
+    ///
+    /// Chains pass through a prefix trie (see `chain_trie`), so shared prefixes
+    /// are held in memory only once. The first occurrence of each path is kept,
+    /// in the original order.
+    ///
+    /// Returns the number of chains left.
+    pub fn deduplicate_chains(&mut self) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot deduplicate chains while scanning"));
+        }
+
+        let before = self.chain_results.len();
+        let trie: ChainTrie = self.chain_results.iter().collect();
+        info!("Chain dedup: {} -> {} chains, {} trie nodes", before, trie.len(), trie.node_count());
+
+        self.chain_results = ChainStore::from_chains(&self.cache_dir, trie.iter())?;
+        self.shared_buffer.write_chains_found(self.chain_results.len() as i64);
+
+        Ok(self.chain_results.len())
+    }
+
+    /// Get the cache directory used for mmap storage and temp files.
+    pub fn cache_dir(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+
+    /// Backing files currently owned by this manager, which must not be deleted.
+    pub fn cache_files_in_use(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.pointer_library.iter().map(|lib| lib.file_path().clone()).collect();
+        files.extend(self.chain_results.file_path().cloned());
+        // Temp chunks of an interrupted scan are kept for resuming it
+        if let Ok(Some(checkpoint)) = ScanCheckpoint::load(&self.cache_dir) {
+            files.push(ScanCheckpoint::path(&self.cache_dir));
+            files.extend(checkpoint.temp_files);
+        }
+        files
+    }
+
+    /// Delete the named cache files, skipping the ones in use.
+    ///
+    /// Refused while a scan is running, since Phase 1 temp chunks are not
+    /// tracked individually.
+    pub fn delete_cache_files(&self, names: &[String], extra_in_use: &[PathBuf]) -> Result<(usize, u64)> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot delete cache files while scanning"));
+        }
+        let mut in_use = self.cache_files_in_use();
+        in_use.extend_from_slice(extra_in_use);
+        maintenance::delete_cache_files(&self.cache_dir, names, &in_use)
+    }
+
+    /// Delete all cache files older than `max_age_secs`, skipping the ones in use.
+    pub fn prune_cache_files(&self, max_age_secs: u64, extra_in_use: &[PathBuf]) -> Result<(usize, u64)> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot prune cache files while scanning"));
+        }
+        let mut in_use = self.cache_files_in_use();
+        in_use.extend_from_slice(extra_in_use);
+        maintenance::prune_cache_files(&self.cache_dir, max_age_secs, &in_use)
+    }
+
+    /// Shrink the pointer library backing file to its used size.
+    ///
+    /// Returns the number of bytes released.
+    pub fn compact_storage(&mut self) -> Result<u64> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot compact storage while scanning"));
+        }
+        match self.pointer_library.as_mut() {
+            Some(lib) => lib.compact(),
+            None => Ok(0),
+        }
+    }
+
+    /// Persist the Phase 1 pointer library to `path` for reuse in a later run.
+    ///
+    /// Returns the number of pointers saved.
+    pub fn save_pointer_library(&mut self, path: &Path) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot save the pointer library while scanning"));
+        }
+        let lib = self.pointer_library.as_mut().ok_or_else(|| anyhow!("No pointer library to save"))?;
+
+        lib.persist()?;
+        if lib.file_path() != path {
+            std::fs::copy(lib.file_path(), path)?;
+        }
+        info!("Saved pointer library ({} pointers) to {:?}", lib.len(), path);
+        Ok(lib.len())
+    }
+
+    /// Export the pointers whose address or value lies in one of `ranges`
+    /// (e.g. the segments of one module) to a standalone library file.
+    ///
+    /// The file has the same format as `save_pointer_library` and keeps the
+    /// value ordering, so it can be loaded with `load_pointer_library`.
+    /// Returns the number of pointers exported.
+    pub fn export_pointer_library_subset(&self, path: &Path, ranges: &[(u64, u64)]) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot export the pointer library while scanning"));
+        }
+        if ranges.is_empty() {
+            return Err(anyhow!("No address ranges to export"));
+        }
+        let lib = self.pointer_library.as_ref().ok_or_else(|| anyhow!("No pointer library to export"))?;
+
+        let in_ranges = |addr: u64| ranges.iter().any(|&(start, end)| addr >= start && addr < end);
+
+        let mut subset = MmapQueue::<PointerData>::new_fixed(&self.cache_dir, "export")?;
+        for archived in lib.iter() {
+            let address = archived.address.to_native();
+            let value = archived.value.to_native();
+            if in_ranges(address) || in_ranges(value) {
+                subset.push(&PointerData::new(address, value))?;
+            }
+        }
+        subset.persist()?;
+
+        // The temporary file is removed when `subset` is dropped
+        std::fs::copy(subset.file_path(), path)?;
+        info!("Exported {} of {} pointers to {:?}", subset.len(), lib.len(), path);
+        Ok(subset.len())
+    }
+
+    /// Write the pointer graph around `target_address` to `path` as DOT or JSON.
+    ///
+    /// Nodes are the slots reached walking the pointer library backwards from
+    /// the target, at most `max_depth` references away; edges are the pointers
+    /// between them. Returns the number of nodes written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn export_pointer_graph(
+        &self,
+        path: &Path,
+        target_address: u64,
+        max_depth: u32,
+        max_offset: u32,
+        max_nodes: usize,
+        format: GraphFormat,
+        static_modules: &[VmStaticData],
+    ) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot export the pointer graph while scanning"));
+        }
+        let lib = self.pointer_library.as_ref().ok_or_else(|| anyhow!("No pointer library, run a full scan first"))?;
+
+        let graph = graph::build_pointer_graph(lib, static_modules, self.config.data_start, target_address, max_depth, max_offset, max_nodes);
+        std::fs::write(path, graph.render(format)?)?;
+
+        info!(
+            "Exported pointer graph around 0x{:X}: {} nodes, {} edges{} to {:?}",
+            target_address,
+            graph.nodes.len(),
+            graph.edges.len(),
+            if graph.truncated { " (truncated)" } else { "" },
+            path
+        );
+        Ok(graph.nodes.len())
+    }
+
+    /// Load a pointer library saved by `save_pointer_library`, replacing the
+    /// current one. Chain results are cleared.
+    ///
+    /// Returns the number of pointers loaded.
+    pub fn load_pointer_library(&mut self, path: &Path) -> Result<usize> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot load a pointer library while scanning"));
+        }
+        let lib = MmapQueue::<PointerData>::open(path)?;
+        if !lib.is_fixed() {
+            return Err(anyhow!("{:?} is not a pointer library", path));
+        }
+
+        let count = lib.len();
+        self.clear();
+        self.pointer_library = Some(lib);
+        info!("Loaded pointer library ({} pointers) from {:?}", count, path);
+        Ok(count)
+    }
+
+    /// Number of pointers in the current pointer library, if any.
+    pub fn pointer_library_len(&self) -> Option<usize> {
+        self.pointer_library.as_ref().map(|lib| lib.len())
+    }
+
+    /// Slots in the pointer library that point into
+    /// `[target - max_offset, target + max_backward_offset]`.
+    ///
+    /// Binary search over the value-sorted library. Returns at most `limit`
+    /// `(address, value)` pairs in value order.
+    pub fn find_references_to(&self, target: u64, max_offset: u32, max_backward_offset: u32, limit: usize) -> Result<Vec<(u64, u64)>> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot query the pointer library while scanning"));
+        }
+        let lib = self.pointer_library.as_ref().ok_or_else(|| anyhow!("No pointer library, run a full scan first"))?;
+
+        Ok(chain_builder::find_pointers_to_range(lib, target, max_offset, max_backward_offset)
+            .into_iter()
+            .take(limit)
+            .map(|(address, offset)| (address, (target as i64).wrapping_sub(offset) as u64))
+            .collect())
+    }
+
+    /// Pointers the library recorded at addresses in `[address, address + span)`,
+    /// i.e. what that block points to as of the scan.
+    ///
+    /// The library is ordered by value, so this is a parallel linear pass.
+    /// Returns at most `limit` `(address, value)` pairs in address order.
+    pub fn find_pointers_in(&self, address: u64, span: u64, limit: usize) -> Result<Vec<(u64, u64)>> {
+        const SLICE: usize = 1 << 20;
+
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot query the pointer library while scanning"));
+        }
+        let lib = self.pointer_library.as_ref().ok_or_else(|| anyhow!("No pointer library, run a full scan first"))?;
+
+        let end = address.saturating_add(span);
+        let mut found: Vec<(u64, u64)> = (0..lib.len().div_ceil(SLICE))
+            .into_par_iter()
+            .flat_map_iter(|slice| {
+                lib.range(slice * SLICE..(slice + 1) * SLICE)
+                    .map(|archived| (archived.address.to_native(), archived.value.to_native()))
+                    .filter(|&(slot, _)| slot >= address && slot < end)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        found.sort_unstable();
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    /// Run Phase 2 only, reusing the current pointer library.
+    ///
+    /// The library comes from the last scan or from `load_pointer_library`,
+    /// and must belong to the currently bound process instance. Alignment and
+    /// performance settings are kept from the current config.
+    pub fn start_chain_build_async(
+        &mut self,
+        target_address: u64,
+        max_depth: u32,
+        max_offset: u32,
+        static_modules: Vec<VmStaticData>,
+        is_layer_bfs: bool,
+        background: bool,
+    ) -> Result<()> {
+        if self.is_scanning() {
+            self.last_error = ScanErrorCode::AlreadyScanning;
+            return Err(anyhow!("Scan already in progress"));
+        }
+        let pointer_lib = self.pointer_library.take().ok_or_else(|| anyhow!("No pointer library, run a full scan first"))?;
+
+        self.config.target_address = target_address;
+        self.config.max_depth = max_depth;
+        self.config.max_offset = max_offset;
+        self.config.is_layer_bfs = is_layer_bfs;
+        self.config.background = background;
+        self.config.access_offsets = self.access_offsets_for(target_address);
+
+        self.chain_results.clear();
+        self.last_error = ScanErrorCode::None;
+        self.shared_buffer.reset();
+        self.current_phase = ScanPhase::BuildingChains;
+        self.shared_buffer.write_phase(ScanPhase::BuildingChains);
+
+        let cancel_token = CancellationToken::new();
+        self.cancel_token = Some(cancel_token.clone());
+        let config = self.config.clone();
+
+        info!(
+            "Building chains from pointer library: target=0x{:X}, depth={}, offset=0x{:X}, pointers={}",
+            target_address,
+            max_depth,
+            max_offset,
+            pointer_lib.len()
+        );
+
+        let handle = TOKIO_RUNTIME.spawn(async move {
+            Self::run_chain_phase(pointer_lib, static_modules, config, None, cancel_token);
+        });
+
+        self.scan_handle = Some(handle);
+        Ok(())
+    }
+
+    /// Drop the first `drop_levels` levels of chain `chain_index` and build new
+    /// static paths to the slot they reached, reusing the current pointer library.
+    ///
+    /// The slot is resolved in the live process, so the chain must still resolve
+    /// up to that level. Each chain found gets the kept levels appended and
+    /// replaces the current results; `max_depth` limits only the new prefix.
+    /// Returns the address of the new root slot.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_reroot_async(
+        &mut self,
+        chain_index: usize,
+        drop_levels: usize,
+        max_depth: u32,
+        max_offset: u32,
+        static_modules: Vec<VmStaticData>,
+        is_layer_bfs: bool,
+        background: bool,
+    ) -> Result<u64> {
+        if self.is_scanning() {
+            self.last_error = ScanErrorCode::AlreadyScanning;
+            return Err(anyhow!("Scan already in progress"));
+        }
+        if self.pointer_library.is_none() {
+            return Err(anyhow!("No pointer library, run a full scan first"));
+        }
+
+        let chain = self.chain_results.get(chain_index).ok_or_else(|| anyhow!("Chain index {} out of range", chain_index))?;
+        if drop_levels == 0 || drop_levels >= chain.depth() {
+            return Err(anyhow!("Can drop 1..{} levels of a {}-level chain, got {}", chain.depth(), chain.depth(), drop_levels));
+        }
+
+        let new_root = {
+            let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+            if !driver_manager.is_process_bound() {
+                return Err(anyhow!("No process bound"));
+            }
+            let read_u64 = |addr: u64| {
+                let mut buf = [0u8; 8];
+                driver_manager.read_memory_unified(addr, &mut buf, None).ok().map(|_| u64::from_le_bytes(buf))
+            };
+            validator::resolve_chain_prefix(&chain, drop_levels, &static_modules, self.config.data_start, &read_u64)
+                .ok_or_else(|| anyhow!("Chain no longer resolves to level {}", drop_levels))?
+        };
+
+        let tail = RerootTail {
+            steps: chain.steps[drop_levels..].to_vec(),
+            target_address: chain.target_address,
+        };
+        let pointer_lib = self.pointer_library.take().ok_or_else(|| anyhow!("No pointer library, run a full scan first"))?;
+
+        self.config.target_address = new_root;
+        self.config.max_depth = max_depth;
+        self.config.max_offset = max_offset;
+        self.config.is_layer_bfs = is_layer_bfs;
+        self.config.background = background;
+        self.config.access_offsets = self.access_offsets_for(new_root);
+
+        self.chain_results.clear();
+        self.last_error = ScanErrorCode::None;
+        self.shared_buffer.reset();
+        self.current_phase = ScanPhase::BuildingChains;
+        self.shared_buffer.write_phase(ScanPhase::BuildingChains);
+
+        let cancel_token = CancellationToken::new();
+        self.cancel_token = Some(cancel_token.clone());
+        // Per-level offsets count from the original target, the kept levels come first
+        let mut config = self.config.clone();
+        config.level_max_offsets = config.level_max_offsets.iter().skip(tail.steps.len()).copied().collect();
+
+        info!(
+            "Re-rooting chain {}: dropped {} levels, new root=0x{:X}, keeping {} levels to 0x{:X}",
+            chain_index,
+            drop_levels,
+            new_root,
+            tail.steps.len(),
+            tail.target_address
+        );
+
+        let handle = TOKIO_RUNTIME.spawn(async move {
+            Self::run_chain_phase(pointer_lib, static_modules, config, Some(tail), cancel_token);
+        });
+
+        self.scan_handle = Some(handle);
+        Ok(new_root)
+    }
+
+    /// Tune Phase 1 memory usage and parallelism for subsequent scans.
+    ///
+    /// `chunk_size` is rounded up to the page size. `max_threads` of 0 uses
+    /// one worker per CPU core. `batch_threshold` may not exceed
+    /// [`scanner::MAX_BATCH_THRESHOLD`]. Takes effect on the next `start_scan_async`.
+    pub fn set_performance_config(&mut self, chunk_size: usize, max_threads: usize, batch_threshold: usize) -> Result<()> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot change performance config while scanning"));
+        }
+        if chunk_size == 0 || batch_threshold == 0 {
+            return Err(anyhow!("Chunk size and batch threshold must be positive"));
+        }
+        if batch_threshold > scanner::MAX_BATCH_THRESHOLD {
+            return Err(anyhow!("Batch threshold {} exceeds the maximum of {}", batch_threshold, scanner::MAX_BATCH_THRESHOLD));
+        }
+
+        self.config.chunk_size = chunk_size.next_multiple_of(*PAGE_SIZE);
+        self.config.max_threads = max_threads;
+        self.config.batch_threshold = batch_threshold;
+        info!(
+            "Pointer scan performance config: chunk_size={}, max_threads={}, batch_threshold={}",
+            self.config.chunk_size, max_threads, batch_threshold
+        );
+        Ok(())
+    }
+
+    /// Narrow the offsets Phase 2 follows, for subsequent scans and chain builds.
+    ///
+    /// `level_max_offsets[i]` replaces `max_offset` for the level `i` steps
+    /// away from the target; deeper levels keep `max_offset`. A non-empty
+    /// `offset_whitelist` restricts every level to those structure offsets.
+    /// Pass empty lists to go back to a single `max_offset`.
+    ///
+    /// `max_backward_offset` allows negative offsets down to `-max_backward_offset`,
+    /// for targets that sit below the address their pointer holds. Keep it at 0
+    /// when the target never does: every byte of backward range adds candidates.
+    pub fn set_offset_rules(&mut self, level_max_offsets: Vec<u32>, offset_whitelist: Vec<u32>, max_backward_offset: u32) -> Result<()> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot change offset rules while scanning"));
+        }
+
+        let config = std::mem::take(&mut self.config);
+        self.config = config
+            .with_level_max_offsets(level_max_offsets)
+            .with_offset_whitelist(offset_whitelist)
+            .with_max_backward_offset(max_backward_offset);
+        info!(
+            "Pointer scan offset rules: level max offsets {:X?}, {} whitelisted offsets, max backward offset 0x{:X}",
+            self.config.level_max_offsets,
+            self.config.offset_whitelist.len(),
+            max_backward_offset
+        );
+        Ok(())
+    }
+
+    /// Phase 1 writer statistics of the last completed scan, including the chosen batch threshold.
+    pub fn last_scan_report(&self) -> Option<&ScanReport> {
+        self.last_scan_report.as_ref()
+    }
+
     /// Clear all results and reset state.
     pub fn clear(&mut self) {
         self.pointer_library = None;
@@ -139,6 +776,17 @@ impl PointerScanManager {
         self.shared_buffer.reset();
     }
 
+    /// Drop the results a panicking call may have left half-updated.
+    ///
+    /// A scan still running is cancelled and detached; the cache dir and
+    /// shared buffer are kept, so no re-init is needed.
+    pub fn recover_from_poison(&mut self) {
+        self.request_cancel();
+        self.cancel_token = None;
+        self.scan_handle = None;
+        self.clear();
+    }
+
     /// Start an async pointer scan.
     ///
     /// This function returns immediately. Progress can be monitored via the shared buffer.
@@ -151,6 +799,7 @@ impl PointerScanManager {
         regions: Vec<ScanRegion>,
         static_modules: Vec<VmStaticData>,
         is_layer_bfs: bool,
+        background: bool,
     ) -> Result<()> {
         if self.is_scanning() {
             self.last_error = ScanErrorCode::AlreadyScanning;
@@ -162,6 +811,11 @@ impl PointerScanManager {
             return Err(anyhow!("No memory regions provided"));
         }
 
+        if !matches!(align, 1 | 2 | 4 | 8) {
+            self.last_error = ScanErrorCode::InternalError;
+            return Err(anyhow!("Invalid pointer alignment: {}", align));
+        }
+
         // Update config
         self.config = PointerScanConfig {
             target_address,
@@ -171,10 +825,24 @@ impl PointerScanManager {
             is_layer_bfs,
             data_start: true,
             bss_start: false,
+            chunk_size: self.config.chunk_size,
+            max_threads: self.config.max_threads,
+            batch_threshold: self.config.batch_threshold,
+            level_max_offsets: self.config.level_max_offsets.clone(),
+            offset_whitelist: self.config.offset_whitelist.clone(),
+            max_backward_offset: self.config.max_backward_offset,
+            background,
+            access_offsets: self.access_offsets_for(target_address),
         };
 
+        let pid = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?.get_bound_pid();
+
+        // A new scan supersedes any interrupted one
+        ScanCheckpoint::discard(&self.cache_dir);
+
         // Reset state
         self.clear();
+        self.last_scan_report = None;
         self.current_phase = ScanPhase::ScanningPointers;
         self.shared_buffer.write_phase(ScanPhase::ScanningPointers);
 
@@ -183,7 +851,6 @@ impl PointerScanManager {
         self.cancel_token = Some(cancel_token.clone());
 
         // Clone data for the async task
-        let config = self.config.clone();
         let cache_dir = self.cache_dir.clone();
 
         if log_enabled!(Level::Debug) {
@@ -196,24 +863,87 @@ impl PointerScanManager {
             );
         }
 
+        let checkpoint = ScanCheckpoint::new(pid, self.config.clone(), regions, static_modules);
+
         // Spawn the scan task
         let handle = TOKIO_RUNTIME.spawn(async move {
-            Self::run_scan_task(config, regions, static_modules, cache_dir, cancel_token).await;
+            Self::run_scan_task(checkpoint, cache_dir, cancel_token).await;
         });
 
         self.scan_handle = Some(handle);
         Ok(())
     }
 
+    /// Summary of the interrupted scan in the cache directory, if one can be resumed.
+    pub fn scan_checkpoint(&self) -> Option<CheckpointSummary> {
+        match ScanCheckpoint::load(&self.cache_dir) {
+            Ok(checkpoint) => checkpoint.map(|cp| cp.summary()),
+            Err(e) => {
+                warn!("Ignoring scan checkpoint: {}", e);
+                None
+            },
+        }
+    }
+
+    /// Continue an interrupted scan from its checkpoint.
+    ///
+    /// Only the regions not flushed before the interruption are scanned again,
+    /// then Phase 2 runs with the settings the scan was started with. The
+    /// process the scan was started against must still be bound.
+    pub fn resume_scan_async(&mut self) -> Result<()> {
+        if self.is_scanning() {
+            self.last_error = ScanErrorCode::AlreadyScanning;
+            return Err(anyhow!("Scan already in progress"));
+        }
+
+        let checkpoint = ScanCheckpoint::load(&self.cache_dir)?.ok_or_else(|| anyhow!("No interrupted scan to resume"))?;
+        let pid = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?.get_bound_pid();
+        if pid != checkpoint.pid {
+            return Err(anyhow!("Interrupted scan belongs to process {}, bound process is {}", checkpoint.pid, pid));
+        }
+
+        self.config = checkpoint.config.clone();
+        self.clear();
+        self.last_scan_report = None;
+        self.current_phase = ScanPhase::ScanningPointers;
+        self.shared_buffer.write_phase(ScanPhase::ScanningPointers);
+
+        let cancel_token = CancellationToken::new();
+        self.cancel_token = Some(cancel_token.clone());
+        let cache_dir = self.cache_dir.clone();
+
+        info!(
+            "Resuming pointer scan: target=0x{:X}, {}/{} regions done",
+            checkpoint.config.target_address,
+            checkpoint.completed.len(),
+            checkpoint.regions.len()
+        );
+
+        let handle = TOKIO_RUNTIME.spawn(async move {
+            Self::run_scan_task(checkpoint, cache_dir, cancel_token).await;
+        });
+
+        self.scan_handle = Some(handle);
+        Ok(())
+    }
+
+    /// Delete the interrupted scan checkpoint and its temp files.
+    ///
+    /// Returns whether there was one.
+    pub fn discard_scan_checkpoint(&self) -> Result<bool> {
+        if self.is_scanning() {
+            return Err(anyhow!("Cannot discard the scan checkpoint while scanning"));
+        }
+        Ok(ScanCheckpoint::discard(&self.cache_dir))
+    }
+
     /// The async scan task that runs Phase 1 and Phase 2.
-    async fn run_scan_task(
-        config: PointerScanConfig,
-        regions: Vec<ScanRegion>, // 包含了static_modules
-        static_modules: Vec<VmStaticData>,
-        cache_dir: PathBuf,
-        cancel_token: CancellationToken,
-    ) {
+    ///
+    /// Phase 1 continues from `checkpoint`, which is fresh for a new scan.
+    async fn run_scan_task(checkpoint: ScanCheckpoint, cache_dir: PathBuf, cancel_token: CancellationToken) {
         let check_cancelled = || cancel_token.is_cancelled();
+        let config = checkpoint.config.clone();
+        let static_modules = checkpoint.static_modules.clone();
 
         // Phase 1: Scan for pointers
         if log_enabled!(Level::Debug) {
@@ -222,12 +952,10 @@ impl PointerScanManager {
 
         let cancel_token_clone = cancel_token.clone();
         let pointer_lib_result = tokio::task::spawn_blocking({
-            let config = config.clone();
             let cache_dir = cache_dir.clone();
             move || {
-                scanner::scan_all_pointers(
-                    &regions,
-                    &config,
+                scanner::scan_all_pointers_resume(
+                    checkpoint,
                     &cache_dir,
                     |done, total, found| {
                         if let Ok(manager) = POINTER_SCAN_MANAGER.read() {
@@ -245,6 +973,8 @@ impl PointerScanManager {
             if log_enabled!(Level::Debug) {
                 info!("Scan cancelled during Phase 1");
             }
+            // A cancelled scan is not meant to be resumed
+            ScanCheckpoint::discard(&cache_dir);
             if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
                 manager.current_phase = ScanPhase::Cancelled;
                 manager.shared_buffer.write_phase(ScanPhase::Cancelled);
@@ -254,7 +984,18 @@ impl PointerScanManager {
 
         // Process Phase 1 result
         let pointer_lib = match pointer_lib_result {
-            Ok(Ok(lib)) => lib,
+            Ok(Ok((lib, report))) => {
+                // Temp files are merged into the library now
+                ScanCheckpoint::remove(&cache_dir);
+                info!(
+                    "Phase 1 batch threshold: {} (configured {}, min {}), {} temp files",
+                    report.batch_threshold, report.configured_batch_threshold, report.min_batch_threshold, report.temp_files
+                );
+                if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
+                    manager.last_scan_report = Some(report);
+                }
+                lib
+            },
             Ok(Err(e)) => {
                 error!("Phase 1 failed: {}", e);
                 if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
@@ -281,30 +1022,69 @@ impl PointerScanManager {
             info!("Phase 1 complete. Found {} pointers", pointer_lib.len());
         }
 
-        // Update phase
-        if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
-            manager.current_phase = ScanPhase::BuildingChains;
-            manager.shared_buffer.write_phase(ScanPhase::BuildingChains);
+        Self::run_chain_phase(pointer_lib, static_modules, config, None, cancel_token);
+
+        if log_enabled!(Level::Debug) {
+            info!("Pointer scan task completed");
         }
+    }
+
+    /// Phase 2: build chains from `pointer_lib` and store the results.
+    ///
+    /// The pointer library is handed back to the manager whatever the outcome,
+    /// so further chain-building runs can reuse it. With a `tail`, the kept
+    /// levels of a re-rooted chain are appended to every chain found.
+    fn run_chain_phase(
+        pointer_lib: MmapQueue<PointerData>,
+        static_modules: Vec<VmStaticData>,
+        config: PointerScanConfig,
+        tail: Option<RerootTail>,
+        cancel_token: CancellationToken,
+    ) {
+        // Update phase
+        let cache_dir = match POINTER_SCAN_MANAGER.write() {
+            Ok(mut manager) => {
+                manager.current_phase = ScanPhase::BuildingChains;
+                manager.shared_buffer.write_phase(ScanPhase::BuildingChains);
+                manager.cache_dir.clone()
+            },
+            Err(_) => return,
+        };
 
         // Phase 2: Build chains
         if log_enabled!(Level::Debug) {
             info!("Phase 2: Building pointer chains...");
         }
 
-        let chains_result = chain_builder::build_pointer_chains(
-            &pointer_lib,
-            &static_modules,
-            &config,
-            |depth, max_depth, chains_found| {
-                if let Ok(manager) = POINTER_SCAN_MANAGER.read() {
-                    manager
-                        .shared_buffer
-                        .update_building_progress(depth as i32, max_depth, chains_found);
+        let check_cancelled = || cancel_token.is_cancelled();
+        let final_target = tail.as_ref().map(|tail| tail.target_address);
+        let chains_result = ChainStore::create(&cache_dir).and_then(|mut store| {
+            // Chains go straight to disk, re-rooted ones get the kept levels appended first
+            let sink = |mut chain: PointerChain| {
+                if let Some(ref tail) = tail {
+                    chain_ops::graft_tail(&mut chain, &tail.steps, tail.target_address);
                 }
-            },
-            || check_cancelled(),
-        );
+                store.push(&chain)
+            };
+            scanner::run_in_pool(config.max_threads, config.background, || {
+                chain_builder::build_pointer_chains(
+                    &pointer_lib,
+                    &static_modules,
+                    &config,
+                    sink,
+                    |depth, max_depth, chains_found, candidates_dropped| {
+                        if let Ok(manager) = POINTER_SCAN_MANAGER.read() {
+                            manager
+                                .shared_buffer
+                                .update_building_progress(depth as i32, max_depth, chains_found, candidates_dropped);
+                        }
+                    },
+                    check_cancelled,
+                )
+            })
+            .and_then(|r| r)
+            .map(|_| store)
+        });
 
         // Check cancellation
         if check_cancelled() {
@@ -313,6 +1093,7 @@ impl PointerScanManager {
             }
 
             if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
+                manager.pointer_library = Some(pointer_lib);
                 manager.current_phase = ScanPhase::Cancelled;
                 manager.shared_buffer.write_phase(ScanPhase::Cancelled);
             }
@@ -326,6 +1107,10 @@ impl PointerScanManager {
                     info!("Phase 2 complete. Found {} chains", chains.len());
                 }
                 if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
+                    // Grafted chains point at the original target again
+                    if let Some(target) = final_target {
+                        manager.config.target_address = target;
+                    }
                     manager.pointer_library = Some(pointer_lib);
                     manager.chain_results = chains;
                     manager.current_phase = ScanPhase::Completed;
@@ -337,6 +1122,7 @@ impl PointerScanManager {
             Err(e) => {
                 error!("Phase 2 failed: {}", e);
                 if let Ok(mut manager) = POINTER_SCAN_MANAGER.write() {
+                    manager.pointer_library = Some(pointer_lib);
                     manager.current_phase = ScanPhase::Error;
                     manager.last_error = ScanErrorCode::InternalError;
                     manager.shared_buffer.write_phase(ScanPhase::Error);
@@ -344,10 +1130,6 @@ impl PointerScanManager {
                 }
             },
         }
-
-        if log_enabled!(Level::Debug) {
-            info!("Pointer scan task completed");
-        }
     }
 }
 
@@ -12,8 +12,15 @@
 //! - `storage`: Memory-mapped storage for large pointer datasets
 //! - `shared_buffer`: Progress communication with Kotlin via shared memory
 //! - `scanner`: Phase 1 - Scan all memory for valid pointers
+//! - `checkpoint`: Resume an interrupted Phase 1 scan
+//! - `static_modules`: Find static data segments of loaded images from the process maps
 //! - `chain_builder`: Phase 2 - Build pointer chains from target address
+//! - `chain_store`: Disk-backed Phase 2 results, paged by the UI
+//! - `chain_trie`: Prefix-compressed chain set used to deduplicate results
+//! - `validator`: Re-resolve chains in the live process after a restart
+//! - `graph`: Export the reference graph around a target for external tools
 //! - `manager`: Async task management and coordination
+//! - `maintenance`: Cache directory listing, pruning and compaction
 //!
 //! # Usage
 //!
@@ -35,14 +42,23 @@
 //! ```
 
 pub mod chain_builder;
+pub mod chain_file;
+pub mod chain_ops;
+pub mod chain_store;
+pub mod chain_trie;
+pub mod checkpoint;
+pub mod graph;
+pub mod maintenance;
 pub mod manager;
 pub mod scanner;
 pub mod shared_buffer;
+pub mod static_modules;
 pub mod storage;
 pub mod types;
+pub mod validator;
 
 // Re-export commonly used types
 pub use manager::POINTER_SCAN_MANAGER;
 pub use shared_buffer::PointerScanSharedBuffer;
-pub use storage::MmapQueue;
+pub use storage::{MmapQueue, MmapQueueIter};
 pub use types::{*};
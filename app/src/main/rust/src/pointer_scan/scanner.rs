@@ -7,8 +7,10 @@
 use std::cmp::min;
 use std::path::PathBuf;
 use crate::core::DRIVER_MANAGER;
+use crate::core::background::{self, ReadThrottle, BACKGROUND_READ_BYTES_PER_SEC};
+use crate::pointer_scan::checkpoint::ScanCheckpoint;
 use crate::pointer_scan::storage::MmapQueue;
-use crate::pointer_scan::types::{PointerData, PointerScanConfig};
+use crate::pointer_scan::types::{PointerData, PointerScanConfig, ScanReport};
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, log_enabled, warn, Level};
 use rayon::prelude::*;
@@ -17,16 +19,16 @@ use std::sync::{mpsc, Arc};
 use std::{process, thread};
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use itertools::Itertools;
 use memmap2::Mmap;
 use nix::libc;
 use rkyv::rancor::Error as RkyvError;
-use crate::core::globals::PAGE_SIZE;
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
 use crate::wuwa::PageStatusBitmap;
 
 /// Memory region for scanning.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ScanRegion {
     pub start: u64,
     pub end: u64,
@@ -39,39 +41,181 @@ impl ScanRegion {
     }
 }
 
-/// Validates if a 64-bit value could be a valid pointer.
+/// Lowest batch threshold the writer adapts down to (~16MB).
+const MIN_BATCH_THRESHOLD: usize = 1_000_000;
+
+/// Highest accepted batch threshold (~1.6GB). The writer buffer is allocated
+/// up front, so an unbounded value from JNI or a checkpoint could abort the process.
+pub const MAX_BATCH_THRESHOLD: usize = 100_000_000;
+
+/// The writer buffer may use at most 1/N of MemAvailable.
+const BATCH_MEMORY_SHARE: u64 = 4;
+
+/// Target duration of a single temp file flush. Scanners are blocked by the
+/// channel backpressure while the writer flushes, so slow storage gets smaller batches.
+const TARGET_FLUSH_SECS: f64 = 2.0;
+
+/// `MemAvailable` from /proc/meminfo, in bytes.
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Picks the writer's batch threshold from available memory and measured storage speed.
 ///
-/// On ARM64, only the lower 48 bits are used for addressing.
-/// The value must fall within a known memory region to be considered valid.
-#[inline]
-fn is_valid_pointer(value: u64, valid_ranges: &[(u64, u64)]) -> bool {
-    // Mask to 48-bit addressable space (ARM64)
-    let masked = value & 0x0000_FFFF_FFFF_FFFF;
-
-    // Quick range check
-    if valid_ranges.is_empty() {
-        return false;
+/// The configured threshold is an upper bound; the adaptive value never drops
+/// below `MIN_BATCH_THRESHOLD` unless the configured one is smaller.
+struct BatchSizer {
+    configured: usize,
+    current: usize,
+    min_used: usize,
+    initial: usize,
+    initial_available: Option<u64>,
+    written_bytes: u64,
+    write_time: Duration,
+}
+
+impl BatchSizer {
+    fn new(configured: usize) -> Self {
+        let initial_available = available_memory();
+        let mut sizer = Self {
+            configured,
+            current: configured,
+            min_used: configured,
+            initial: configured,
+            initial_available,
+            written_bytes: 0,
+            write_time: Duration::ZERO,
+        };
+        sizer.current = sizer.compute(initial_available);
+        sizer.min_used = sizer.current;
+        sizer.initial = sizer.current;
+        sizer
     }
 
-    let min_addr = valid_ranges.first().map(|r| r.0).unwrap_or(0);
-    let max_addr = valid_ranges.last().map(|r| r.1).unwrap_or(0);
+    fn compute(&self, available: Option<u64>) -> usize {
+        let item_size = size_of::<PointerData>() as u64;
+        let mut limit = usize::MAX;
+
+        if let Some(available) = available {
+            limit = limit.min((available / BATCH_MEMORY_SHARE / item_size) as usize);
+        }
 
-    if masked < min_addr || masked >= max_addr {
-        return false;
+        let secs = self.write_time.as_secs_f64();
+        if self.written_bytes > 0 && secs > 0.0 {
+            let bytes_per_sec = self.written_bytes as f64 / secs;
+            limit = limit.min((bytes_per_sec * TARGET_FLUSH_SECS / item_size as f64) as usize);
+        }
+
+        limit.max(MIN_BATCH_THRESHOLD).min(self.configured)
     }
 
-    // Binary search for containing region
-    valid_ranges
-        .binary_search_by(|(start, end)| {
-            if masked < *start {
-                std::cmp::Ordering::Greater
-            } else if masked >= *end {
-                std::cmp::Ordering::Less
-            } else {
-                std::cmp::Ordering::Equal
+    /// Record a finished flush and re-evaluate the threshold.
+    fn on_flush(&mut self, bytes: usize, elapsed: Duration) {
+        self.written_bytes += bytes as u64;
+        self.write_time += elapsed;
+
+        let next = self.compute(available_memory());
+        if next != self.current {
+            debug!("Batch threshold adjusted: {} -> {}", self.current, next);
+        }
+        self.current = next;
+        self.min_used = self.min_used.min(next);
+    }
+
+    fn write_bytes_per_sec(&self) -> Option<u64> {
+        let secs = self.write_time.as_secs_f64();
+        (self.written_bytes > 0 && secs > 0.0).then(|| (self.written_bytes as f64 / secs) as u64)
+    }
+}
+
+/// Address bits used on ARM64, the top byte may carry a TBI/MTE tag.
+const POINTER_ADDRESS_MASK: u64 = 0x0000_FFFF_FFFF_FFFF;
+
+/// One bit of the coarse index covers 64KB of address space.
+const GRANULE_SHIFT: u32 = 16;
+
+/// Each second-level bitmap covers 4GB (65536 granules, 8KB of bits).
+const LEAF_SHIFT: u32 = 32;
+const LEAF_WORDS: usize = 1 << (LEAF_SHIFT - GRANULE_SHIFT - 6);
+
+/// Two-level granule bitmap over the valid address ranges.
+///
+/// Most 8-byte candidates are not pointers, and for those the bitmap answers
+/// with one or two array lookups. Only values landing in a granule that
+/// overlaps a valid range fall back to binary search over the merged ranges.
+struct ValidRangeIndex {
+    /// Sorted, merged `[start, end)` ranges.
+    ranges: Vec<(u64, u64)>,
+    /// Indexed by `addr >> LEAF_SHIFT`: 0 = no valid range in that 4GB block, n = `leaves[n - 1]`.
+    roots: Vec<u32>,
+    leaves: Vec<Box<[u64; LEAF_WORDS]>>,
+}
+
+impl ValidRangeIndex {
+    /// `ranges` must be sorted and non-overlapping.
+    fn new(ranges: Vec<(u64, u64)>) -> Self {
+        let mut roots = vec![0u32; 1 << (48 - LEAF_SHIFT)];
+        let mut leaves: Vec<Box<[u64; LEAF_WORDS]>> = Vec::new();
+
+        for &(start, end) in &ranges {
+            let start = start & POINTER_ADDRESS_MASK;
+            let end = end.min(POINTER_ADDRESS_MASK + 1);
+            if start >= end {
+                continue;
             }
-        })
-        .is_ok()
+            for granule in (start >> GRANULE_SHIFT)..=((end - 1) >> GRANULE_SHIFT) {
+                let root = (granule >> (LEAF_SHIFT - GRANULE_SHIFT)) as usize;
+                if roots[root] == 0 {
+                    leaves.push(Box::new([0u64; LEAF_WORDS]));
+                    roots[root] = leaves.len() as u32;
+                }
+                let bit = granule as usize & ((1 << (LEAF_SHIFT - GRANULE_SHIFT)) - 1);
+                leaves[roots[root] as usize - 1][bit >> 6] |= 1 << (bit & 63);
+            }
+        }
+
+        debug!("Valid range index: {} ranges, {} leaf bitmaps ({} KB)", ranges.len(), leaves.len(), leaves.len() * LEAF_WORDS * 8 / 1024);
+        Self { ranges, roots, leaves }
+    }
+
+    #[inline(always)]
+    fn may_contain(&self, addr: u64) -> bool {
+        let leaf = self.roots[(addr >> LEAF_SHIFT) as usize];
+        if leaf == 0 {
+            return false;
+        }
+        let bit = ((addr >> GRANULE_SHIFT) as usize) & ((1 << (LEAF_SHIFT - GRANULE_SHIFT)) - 1);
+        // Safety: leaf ids are only ever assigned from `leaves.len()`.
+        let words = unsafe { self.leaves.get_unchecked(leaf as usize - 1) };
+        words[bit >> 6] & (1 << (bit & 63)) != 0
+    }
+
+    fn contains(&self, addr: u64) -> bool {
+        self.ranges
+            .binary_search_by(|(start, end)| {
+                if addr < *start {
+                    std::cmp::Ordering::Greater
+                } else if addr >= *end {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+}
+
+/// Validates if a 64-bit value could be a valid pointer.
+///
+/// On ARM64, only the lower 48 bits are used for addressing.
+/// The value must fall within a known memory region to be considered valid.
+#[inline(always)]
+fn is_valid_pointer(value: u64, valid_ranges: &ValidRangeIndex) -> bool {
+    let masked = value & POINTER_ADDRESS_MASK;
+    valid_ranges.may_contain(masked) && valid_ranges.contains(masked)
 }
 
 /// Scan a single memory chunk for valid pointers.
@@ -82,7 +226,7 @@ fn scan_chunk_for_pointers(
     buffer: &[u8],
     base_addr: u64,
     align: u32,
-    valid_ranges: &[(u64, u64)],
+    valid_ranges: &ValidRangeIndex,
     page_bitmap: &PageStatusBitmap,
 ) -> Vec<PointerData> {
     let mut results = Vec::with_capacity(1024);
@@ -111,33 +255,31 @@ fn scan_chunk_for_pointers(
             continue;
         }
 
-        // 实际可用的切片
-        let page_slice = &buffer[page_start_idx..page_end_idx];
-
-        // 只有当剩余数据足够放一个 u64 (8字节) 时才扫描
-        if page_slice.len() < 8 {
-            continue;
-        }
+        // 下一页也读取成功时，指针可以跨越页边界（align < 8 时会出现）
+        let next_page_ok = page_idx + 1 < num_pages && page_bitmap.is_page_success(page_idx + 1);
 
         // 限制扫描的终点，防止读取越界
-        // 例子：Slice 长度 4096。最大 offset 应该是 4088。4088..4096 是最后8字节。
-        let scan_limit = page_slice.len() - 8;
-
-        for offset in (0..=scan_limit).step_by(step) {
-            // Safety: 我们已经通过 scan_limit 保证了 offset+8 不会越界
-            // 使用 try_into 会被编译器优化掉，这里是零开销
-            let bytes = unsafe {
-                // 使用 unsafe get_unchecked 可以进一步减少边界检查，提升 extreme performance
-                // 但在标准安全代码中， slice索引就够了。这里演示最安全写法。
-                page_slice.get_unchecked(offset..offset + 8)
-            };
+        // 例子：页长度 4096，下一页不可用时最大起点是 4088；可用时可以一直扫到 4095（受 buffer 末尾限制）
+        let last_start = if next_page_ok {
+            min(page_end_idx - 1, buffer.len() - 8)
+        } else {
+            // 只有当剩余数据足够放一个 u64 (8字节) 时才扫描
+            if page_end_idx - page_start_idx < 8 {
+                continue;
+            }
+            page_end_idx - 8
+        };
+
+        for idx in (page_start_idx..=last_start).step_by(step) {
+            // Safety: last_start 保证了 idx+8 不会越过 buffer
+            let bytes = unsafe { buffer.get_unchecked(idx..idx + 8) };
 
             let value = u64::from_le_bytes(bytes.try_into().unwrap());
 
             // is_valid_pointer 最好是 #[inline] 的
             if is_valid_pointer(value, valid_ranges) {
-                // 计算实际内存地址：基址 + 页偏移 + 页内偏移
-                let ptr_address = base_addr + (page_start_idx + offset) as u64;
+                // 计算实际内存地址：基址 + buffer 内偏移
+                let ptr_address = base_addr + idx as u64;
                 results.push(PointerData::new(ptr_address, value));
             }
         }
@@ -146,14 +288,41 @@ fn scan_chunk_for_pointers(
     results
 }
 
-/// Scan a single memory region for valid pointers.
+/// 上一个 chunk 末尾的 7 字节，起点落在这里的指针要拼上下一个 chunk 的开头才能读完整。
+struct ChunkTail {
+    addr: u64,
+    bytes: [u8; 7],
+}
+
+/// 扫描横跨两个 chunk 的指针：`tail` 是上一个 chunk 的最后 7 字节，`head` 是当前 chunk 的开头。
+fn scan_chunk_boundary(tail: &ChunkTail, head: &[u8], align: u32, valid_ranges: &ValidRangeIndex, results: &mut Vec<PointerData>) {
+    let mut joined = [0u8; 14];
+    joined[..7].copy_from_slice(&tail.bytes);
+    let head_len = min(head.len(), 7);
+    joined[7..7 + head_len].copy_from_slice(&head[..head_len]);
+
+    for i in 0..7 {
+        let addr = tail.addr + i as u64;
+        if !addr.is_multiple_of(align as u64) || i + 8 > 7 + head_len {
+            continue;
+        }
+        let value = u64::from_le_bytes(joined[i..i + 8].try_into().unwrap());
+        if is_valid_pointer(value, valid_ranges) {
+            results.push(PointerData::new(addr, value));
+        }
+    }
+}
+
+/// Scan a single memory region for valid pointers, reading `chunk_size` bytes
+/// (page-aligned, from `PointerScanConfig::chunk_size`) at a time.
 /// Returns a vector of all pointers found in this region.
 fn scan_region_for_pointers(
     region: &ScanRegion,
-    chunk_size: usize, // todo: 当前大小写死了512kb
-    valid_ranges: &[(u64, u64)],
+    chunk_size: usize,
+    valid_ranges: &ValidRangeIndex,
     config: &PointerScanConfig,
     cancelled: &AtomicBool,
+    throttle: Option<&ReadThrottle>,
 ) -> Result<Vec<PointerData>> {
     assert_eq!(region.start & (*PAGE_SIZE as u64 - 1), 0);
     assert_eq!(region.end & (*PAGE_SIZE as u64 - 1), 0);
@@ -164,6 +333,7 @@ fn scan_region_for_pointers(
     let mut buffer = vec![0u8; chunk_size];
     let mut current_addr = region.start;
     let mut region_pointers = Vec::new();
+    let mut tail: Option<ChunkTail> = None;
 
     while current_addr < region.end {
         if cancelled.load(Ordering::Relaxed) {
@@ -175,13 +345,38 @@ fn scan_region_for_pointers(
         // 每次创建 bitmap 开销极小（只是几个整数计算），可以接受
         let mut page_bitmap = PageStatusBitmap::new(read_size, current_addr as usize);
 
-        match driver_manager.read_memory_unified(current_addr, &mut buffer[..read_size], Some(&mut page_bitmap)) {
+        let read_result = {
+            let _op = OP_QUEUE.bulk();
+            driver_manager.read_memory_unified(current_addr, &mut buffer[..read_size], Some(&mut page_bitmap))
+        };
+        // 后台模式：限速并让出 CPU
+        if let Some(throttle) = throttle {
+            throttle.consume(read_size);
+        }
+
+        match read_result {
             Ok(_) => {
-                // todo：Chunk 边界的指针遗漏，在 scan_region_for_pointers 中，你按 chunk_size (512KB) 逐块读取内存
-                // 在 scan_chunk_for_pointers 中，扫描循环限制为 scan_limit = page_slice.len() - 8
-                // 这意味着如果一个指针横跨了两个 Chunk（例如：指针起始地址在 Chunk A 的最后 4 个字节，结束地址在 Chunk B 的前 4 个字节），这个指针会被彻底漏掉。它在 Chunk A 中因为长度不足 8 被截断，在 Chunk B 中因为起始偏移是 0 而被跳过。
+                // 横跨上一个 chunk 末尾和本 chunk 开头的指针
+                if let Some(ref t) = tail
+                    && page_bitmap.is_page_success(0)
+                {
+                    scan_chunk_boundary(t, &buffer[..read_size], config.align, valid_ranges, &mut region_pointers);
+                }
+
                 let chunk_results = scan_chunk_for_pointers(&buffer[..read_size], current_addr, config.align, valid_ranges, &page_bitmap);
 
+                // 保存末尾 7 字节，留给下一个 chunk 拼接
+                tail = if read_size >= 7 && page_bitmap.is_page_success((read_size - 1) / *PAGE_SIZE) {
+                    let mut bytes = [0u8; 7];
+                    bytes.copy_from_slice(&buffer[read_size - 7..read_size]);
+                    Some(ChunkTail {
+                        addr: current_addr + (read_size - 7) as u64,
+                        bytes,
+                    })
+                } else {
+                    None
+                };
+
                 if !chunk_results.is_empty() {
                     if log_enabled!(Level::Debug) {
                         debug!("Chunk scan success: addr = 0x{:X}, found {} pointers", current_addr, chunk_results.len());
@@ -191,6 +386,7 @@ fn scan_region_for_pointers(
             },
             Err(e) => {
                 debug!("Failed to read memory at 0x{:X}-0x{:X}: {}", current_addr, current_addr + read_size as u64, e);
+                tail = None;
                 // Continue with next chunk
             },
         }
@@ -214,27 +410,90 @@ fn scan_region_for_pointers(
 /// * `check_cancelled` - Function to check if scan should be cancelled
 ///
 /// # Returns
-/// A sorted MmapQueue containing all found pointers
+/// A sorted MmapQueue containing all found pointers, and the writer statistics
 pub fn scan_all_pointers<F, C>(
     regions: &[ScanRegion],
     config: &PointerScanConfig,
     cache_dir: &PathBuf,
     progress_callback: F,
     check_cancelled: C,
-) -> Result<MmapQueue<PointerData>>
+) -> Result<(MmapQueue<PointerData>, ScanReport)>
+where
+    F: Fn(usize, usize, i64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let remaining: Vec<usize> = (0..regions.len()).collect();
+    scan_pointers(regions, &remaining, config, cache_dir, None, progress_callback, check_cancelled)
+}
+
+/// Phase 1 with a checkpoint: scan the regions `checkpoint` has not completed yet.
+///
+/// The checkpoint is saved to `cache_dir` after every temp file flush, so if
+/// the app is killed the scan can be resumed from the last flush by calling
+/// this again with the loaded checkpoint. A fresh `ScanCheckpoint` starts a
+/// full scan. The checkpoint file is left in place; the caller removes it
+/// once the returned library is in use.
+pub fn scan_all_pointers_resume<F, C>(
+    checkpoint: ScanCheckpoint,
+    cache_dir: &PathBuf,
+    progress_callback: F,
+    check_cancelled: C,
+) -> Result<(MmapQueue<PointerData>, ScanReport)>
+where
+    F: Fn(usize, usize, i64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let regions = checkpoint.regions.clone();
+    let config = checkpoint.config.clone();
+    let remaining = checkpoint.remaining_regions();
+
+    if !checkpoint.completed.is_empty() {
+        info!(
+            "Resuming pointer scan: {}/{} regions done, {} pointers in {} temp files",
+            checkpoint.completed.len(),
+            regions.len(),
+            checkpoint.pointers_found,
+            checkpoint.temp_files.len()
+        );
+    }
+
+    scan_pointers(&regions, &remaining, &config, cache_dir, Some(checkpoint), progress_callback, check_cancelled)
+}
+
+/// Scan `regions[i]` for every `i` in `remaining`, continuing from `checkpoint` if given.
+fn scan_pointers<F, C>(
+    regions: &[ScanRegion],
+    remaining: &[usize],
+    config: &PointerScanConfig,
+    cache_dir: &PathBuf,
+    checkpoint: Option<ScanCheckpoint>,
+    progress_callback: F,
+    check_cancelled: C,
+) -> Result<(MmapQueue<PointerData>, ScanReport)>
 where
     F: Fn(usize, usize, i64) + Send + Sync,
     C: Fn() -> bool + Send + Sync,
 {
     let start_time = Instant::now();
 
-    // 内存阈值：每积累 1000 万个指针 (约160MB) 就进行一次排序落盘
-    // 加上 buffer 双倍缓冲，总额外内存开销约 320MB，非常安全
-    const BATCH_SIZE_THRESHOLD: usize = 10_000_000;
-    const CHUNK_SIZE: usize = 512 * 1024; // 512KB 读取分块
+    // 内存阈值：默认每积累 1000 万个指针 (约160MB) 就进行一次排序落盘
+    // 配置值为上限，写入线程按可用内存和存储速度动态下调，避免低内存设备被杀
+    let sizer = BatchSizer::new(config.batch_threshold.clamp(1, MAX_BATCH_THRESHOLD));
+    let batch_threshold = sizer.current;
+    // 读取分块必须按页对齐，scan_chunk_for_pointers 按页索引 buffer
+    let chunk_size = config.chunk_size.max(*PAGE_SIZE).next_multiple_of(*PAGE_SIZE);
 
     if log_enabled!(Level::Debug) {
-        info!("Starting pointer scan: {} regions, Batch Threshold: {}", regions.len(), BATCH_SIZE_THRESHOLD);
+        info!(
+            "Starting pointer scan: {} regions ({} remaining), Batch Threshold: {} (configured {}, MemAvailable {:?}), Chunk Size: {}, Threads: {}",
+            regions.len(),
+            remaining.len(),
+            batch_threshold,
+            config.batch_threshold,
+            sizer.initial_available,
+            chunk_size,
+            config.max_threads
+        );
     }
 
     // Build sorted valid address ranges for binary search
@@ -255,88 +514,122 @@ where
         valid_ranges = merged;
     }
     debug!("Optimized valid ranges count: {}", valid_ranges.len());
+    let valid_ranges = ValidRangeIndex::new(valid_ranges);
 
     let total_regions = regions.len();
-    let completed_regions = Arc::new(AtomicUsize::new(0));
-    let total_found = Arc::new(AtomicUsize::new(0));
+    let (done_before, found_before) = checkpoint.as_ref().map_or((0, 0), |cp| (cp.completed.len(), cp.pointers_found));
+    let completed_regions = Arc::new(AtomicUsize::new(done_before));
+    let total_found = Arc::new(AtomicUsize::new(found_before));
     let cancelled = Arc::new(AtomicBool::new(false));
 
     // 创建通道：扫描线程(Producers) -> 排序写入线程(Consumer)
     // sync_channel(4) 提供背压，防止扫描太快内存爆掉
-    let (tx, rx) = mpsc::sync_channel::<Vec<PointerData>>(4);
+    // 空区域也会发送，写入线程据此记录已完成的区域
+    let (tx, rx) = mpsc::sync_channel::<(usize, Vec<PointerData>)>(4);
 
     let writer_handle = thread::spawn({
         let cache_dir = cache_dir.clone();
         let cancelled = cancelled.clone();
 
-        move || -> Result<Vec<PathBuf>> {
-            let mut temp_files = Vec::new();
-            let mut buffer: Vec<PointerData> = Vec::with_capacity(BATCH_SIZE_THRESHOLD);
+        let mut sizer = sizer;
+        let mut checkpoint = checkpoint;
+        let background = config.background;
+        move || -> Result<(Vec<PathBuf>, BatchSizer)> {
+            if background {
+                background::lower_current_thread_priority();
+            }
+            let mut temp_files = checkpoint.as_ref().map(|cp| cp.temp_files.clone()).unwrap_or_default();
+            let mut buffer: Vec<PointerData> = Vec::with_capacity(sizer.current);
+            // 指针还在 buffer 中、尚未落盘的区域
+            let mut pending_regions = Vec::new();
 
-            for mut chunk in rx {
+            for (region_index, mut chunk) in rx {
                 if cancelled.load(Ordering::Relaxed) { break; }
 
                 buffer.append(&mut chunk);
+                pending_regions.push(region_index);
 
-                if buffer.len() >= BATCH_SIZE_THRESHOLD {
+                if buffer.len() >= sizer.current {
+                    let count = buffer.len();
+                    let bytes = count * size_of::<PointerData>();
+                    let flush_start = Instant::now();
                     let path = sort_and_write_temp_file(&mut buffer, &cache_dir)?;
-                    temp_files.push(path);
+                    temp_files.push(path.clone());
+
+                    // 先落盘再记录，被杀时检查点只会引用完整的临时文件
+                    match checkpoint.as_mut() {
+                        Some(cp) => cp.commit(&cache_dir, Some(path), &mut pending_regions, count),
+                        None => pending_regions.clear(),
+                    }
+
+                    sizer.on_flush(bytes, flush_start.elapsed());
+                    // 阈值下调后释放多余容量
+                    buffer.shrink_to(sizer.current);
                 }
             }
 
             // 处理剩余数据
-            if !buffer.is_empty() && !cancelled.load(Ordering::Relaxed) {
-                let path = sort_and_write_temp_file(&mut buffer, &cache_dir)?;
-                temp_files.push(path);
+            if !cancelled.load(Ordering::Relaxed) {
+                let count = buffer.len();
+                let path = if count > 0 {
+                    let path = sort_and_write_temp_file(&mut buffer, &cache_dir)?;
+                    temp_files.push(path.clone());
+                    Some(path)
+                } else {
+                    None
+                };
+                if let Some(cp) = checkpoint.as_mut() {
+                    cp.commit(&cache_dir, path, &mut pending_regions, count);
+                }
             }
 
-            Ok(temp_files)
+            Ok((temp_files, sizer))
         }
     });
 
-    let scan_result = regions.par_iter().try_for_each(|region| -> Result<()> {
-        if cancelled.load(Ordering::Relaxed) || check_cancelled() {
-            cancelled.store(true, Ordering::Relaxed);
-            return Err(anyhow!("Scan cancelled"));
-        }
-
-        // 调用扫描函数
-        let chunk_res = scan_region_for_pointers(
-            region,
-            CHUNK_SIZE,
-            &valid_ranges,
-            config,
-            &cancelled,
-        );
+    let throttle = config.background.then(|| ReadThrottle::new(BACKGROUND_READ_BYTES_PER_SEC));
+    let scan_result = run_in_pool(config.max_threads, config.background, || {
+        remaining.par_iter().try_for_each(|&region_index| -> Result<()> {
+            if cancelled.load(Ordering::Relaxed) || check_cancelled() {
+                cancelled.store(true, Ordering::Relaxed);
+                return Err(anyhow!("Scan cancelled"));
+            }
 
-        match chunk_res {
-            Ok(pointers) => {
-                let count = pointers.len();
-                if count > 0 {
+            let region = &regions[region_index];
+
+            // 调用扫描函数
+            let chunk_res = scan_region_for_pointers(
+                region,
+                chunk_size,
+                &valid_ranges,
+                config,
+                &cancelled,
+                throttle.as_ref(),
+            );
+
+            match chunk_res {
+                Ok(pointers) => {
+                    let count = pointers.len();
                     // 发送给写入线程，如果队列满会阻塞当前线程
-                    if tx.send(pointers).is_err() {
+                    if tx.send((region_index, pointers)).is_err() {
                         return Err(anyhow!("Writer thread disconnected"));
                     }
 
                     let found = total_found.fetch_add(count, Ordering::Relaxed) + count;
                     let done = completed_regions.fetch_add(1, Ordering::Relaxed) + 1;
 
-                    if done % 50 == 0 {
+                    if done.is_multiple_of(50) {
                         progress_callback(done, total_regions, found as i64);
                     }
-                } else {
-                    let done = completed_regions.fetch_add(1, Ordering::Relaxed) + 1;
-                    if done % 50 == 0 {
-                        progress_callback(done, total_regions, total_found.load(Ordering::Relaxed) as i64);
-                    }
+                },
+                Err(e) => {
+                    warn!("Failed scan region {}: {}", region.start, e);
                 }
-            },
-            Err(e) => {
-                warn!("Failed scan region {}: {}", region.start, e);
             }
-        }
-        Ok(())
-    });
+            Ok(())
+        })
+    })
+    .and_then(|r| r);
 
     // 关闭发送端
     drop(tx);
@@ -349,7 +642,7 @@ where
     }
 
     // 等待所有临时文件写入完成
-    let temp_files = writer_handle.join().map_err(|_| anyhow!("Writer panicked"))??;
+    let (temp_files, sizer) = writer_handle.join().map_err(|_| anyhow!("Writer panicked"))??;
 
     if cancelled.load(Ordering::Relaxed) {
         return Err(anyhow!("Scan cancelled during flush"));
@@ -359,13 +652,53 @@ where
     info!("Scan phase done in {:.2}s. Found {} pointers. Merging {} temp files...",
         start_time.elapsed().as_secs_f64(), total_items, temp_files.len());
 
+    let mut report = ScanReport {
+        configured_batch_threshold: config.batch_threshold,
+        batch_threshold: sizer.initial,
+        min_batch_threshold: sizer.min_used,
+        available_memory: sizer.initial_available,
+        write_bytes_per_sec: sizer.write_bytes_per_sec(),
+        align: config.align,
+        relative_scan_cost: config.relative_scan_cost(),
+        background: config.background,
+        temp_files: temp_files.len(),
+        pointers: total_items,
+        scan_millis: start_time.elapsed().as_millis() as u64,
+        merge_millis: 0,
+    };
+
     if temp_files.is_empty() {
-        return MmapQueue::new(cache_dir, "pointer_lib");
+        return Ok((MmapQueue::new_fixed(cache_dir, "pointer_lib")?, report));
     }
+    let merge_start = Instant::now();
     let final_queue = merge_temp_files_kway(temp_files, cache_dir, "pointer_lib")?;
+    report.merge_millis = merge_start.elapsed().as_millis() as u64;
 
     info!("All done! Total time: {:.2}s", start_time.elapsed().as_secs_f64());
-    Ok(final_queue)
+    Ok((final_queue, report))
+}
+
+/// Run `op` on a dedicated rayon pool limited to `max_threads` workers,
+/// or on the global pool when `max_threads` is 0.
+/// A `background` pool always gets its own workers (one per core when
+/// `max_threads` is 0), lowered to SCHED_IDLE / nice 19 as they start.
+pub fn run_in_pool<R, OP>(max_threads: usize, background: bool, op: OP) -> Result<R>
+where
+    R: Send,
+    OP: FnOnce() -> R + Send,
+{
+    if max_threads == 0 && !background {
+        return Ok(op());
+    }
+
+    let mut builder = rayon::ThreadPoolBuilder::new()
+        .num_threads(max_threads)
+        .thread_name(|i| format!("ptr-scan-{}", i));
+    if background {
+        builder = builder.start_handler(|_| background::lower_current_thread_priority());
+    }
+    let pool = builder.build()?;
+    Ok(pool.install(op))
 }
 
 fn sort_and_write_temp_file(buffer: &mut Vec<PointerData>, dir: &PathBuf) -> Result<PathBuf> {
@@ -421,8 +754,8 @@ fn merge_temp_files_kway(files: Vec<PathBuf>, out_dir: &PathBuf, out_name: &str)
         a.value < b.value
     });
 
-    // 初始化输出队列
-    let mut queue = MmapQueue::<PointerData>::new(out_dir, out_name)?;
+    // 初始化输出队列：PointerData 定长，不需要逐条索引
+    let mut queue = MmapQueue::<PointerData>::new_fixed(out_dir, out_name)?;
 
     let mut batch_buffer = Vec::with_capacity(20_000);
     for ptr in merged_stream {
@@ -446,4 +779,110 @@ fn merge_temp_files_kway(files: Vec<PathBuf>, out_dir: &PathBuf, out_name: &str)
     }
 
     Ok(queue)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_range_index_granule_boundaries() {
+        let index = ValidRangeIndex::new(vec![(0x1_0000, 0x2_0000), (0x3_0000, 0x3_0008)]);
+        assert!(!is_valid_pointer(0xFFFF, &index));
+        assert!(is_valid_pointer(0x1_0000, &index));
+        assert!(is_valid_pointer(0x1_FFFF, &index));
+        assert!(!is_valid_pointer(0x2_0000, &index));
+        assert!(is_valid_pointer(0x3_0007, &index));
+        assert!(!is_valid_pointer(0x3_0008, &index));
+    }
+
+    #[test]
+    fn test_valid_range_index_unaligned_ends() {
+        let (start, end) = (0x7000_0000_1234, 0x7000_0003_0001);
+        let index = ValidRangeIndex::new(vec![(start, end)]);
+        // Same granules as the range, but outside it: only the binary search can tell
+        assert!(index.may_contain(start - 1));
+        assert!(!is_valid_pointer(start - 1, &index));
+        assert!(is_valid_pointer(start, &index));
+        assert!(is_valid_pointer(end - 1, &index));
+        assert!(index.may_contain(end));
+        assert!(!is_valid_pointer(end, &index));
+        assert!(!index.may_contain(end + 0x1_0000));
+    }
+
+    #[test]
+    fn test_valid_range_index_crosses_leaf() {
+        let index = ValidRangeIndex::new(vec![(0xFFFF_F000, 0x1_0000_1000)]);
+        assert!(is_valid_pointer(0xFFFF_FFFF, &index));
+        assert!(is_valid_pointer(0x1_0000_0000, &index));
+        assert!(!is_valid_pointer(0x1_0000_1000, &index));
+    }
+
+    #[test]
+    fn test_valid_range_index_masks_tag_and_clamps_end() {
+        let index = ValidRangeIndex::new(vec![(0x7000_0000_0000, 0x7000_0000_1000), (0xFFFF_FFFF_0000, u64::MAX)]);
+        assert!(is_valid_pointer(0xB400_7000_0000_0800, &index));
+        assert!(is_valid_pointer(0xFFFF_FFFF_FFFF, &index));
+        assert!(!is_valid_pointer(0, &index));
+
+        let empty = ValidRangeIndex::new(Vec::new());
+        assert!(!is_valid_pointer(0x7000_0000_0800, &empty));
+    }
+
+    #[test]
+    fn test_chunk_boundary_pointer() {
+        let index = ValidRangeIndex::new(vec![(0x7000_0000_0000, 0x7000_0001_0000)]);
+        let value = 0x7000_0000_0040u64.to_le_bytes();
+        // A pointer starting 3 bytes before the end of the previous chunk
+        let mut tail = ChunkTail { addr: 0x1000 - 7, bytes: [0; 7] };
+        tail.bytes[4..].copy_from_slice(&value[..3]);
+        let head = [&value[3..], &[0u8; 8][..]].concat();
+
+        let mut results = Vec::new();
+        scan_chunk_boundary(&tail, &head, 1, &index, &mut results);
+        assert_eq!(results.iter().map(|p| (p.address(), p.value())).collect::<Vec<_>>(), vec![(0xFFD, 0x7000_0000_0040)]);
+
+        results.clear();
+        scan_chunk_boundary(&tail, &head, 4, &index, &mut results);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_adjacent_chunks_report_each_pointer_once() {
+        let index = ValidRangeIndex::new(vec![(0x7000_0000_0000, 0x7000_0001_0000)]);
+        let page = *PAGE_SIZE;
+        let base = 0x10_0000u64;
+        let mut memory = vec![0u8; page * 2];
+        let mut put = |offset: usize, value: u64| memory[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        put(0x100, 0x7000_0000_0100);
+        put(page - 16, 0x7000_0000_0200);
+        // 4-byte aligned, straddles the two chunks
+        put(page - 4, 0x7000_0000_0300);
+        put(page + 8, 0x7000_0000_0400);
+        let (first, second) = memory.split_at(page);
+
+        let scan = |chunk: &[u8], addr: u64| {
+            let mut bitmap = PageStatusBitmap::new(chunk.len(), addr as usize);
+            bitmap.mark_all_success();
+            scan_chunk_for_pointers(chunk, addr, 4, &index, &bitmap)
+        };
+        let mut results = scan(first, base);
+        let tail = ChunkTail {
+            addr: base + (page - 7) as u64,
+            bytes: first[page - 7..].try_into().unwrap(),
+        };
+        scan_chunk_boundary(&tail, second, 4, &index, &mut results);
+        results.extend(scan(second, base + page as u64));
+
+        let mut found: Vec<(u64, u64)> = results.iter().map(|p| (p.address(), p.value())).collect();
+        found.sort_unstable();
+        assert_eq!(
+            found,
+            vec![
+                (base + 0x100, 0x7000_0000_0100),
+                (base + (page - 16) as u64, 0x7000_0000_0200),
+                (base + (page - 4) as u64, 0x7000_0000_0300),
+                (base + (page + 8) as u64, 0x7000_0000_0400),
+            ]
+        );
+    }
+}
@@ -5,6 +5,9 @@
 //! The buffer is a direct ByteBuffer allocated on the Kotlin side and passed
 //! to Rust via JNI.
 
+use crate::core::jobs::JobKind;
+use crate::core::scan_events::{self, ScanKind};
+use crate::pointer_scan::types::{ScanErrorCode, ScanPhase};
 use std::sync::atomic::{AtomicPtr, Ordering};
 
 /// Size of the shared buffer in bytes.
@@ -30,8 +33,8 @@ pub mod offsets {
     pub const CANCEL_FLAG: usize = 36;
     /// Error code (i32)
     pub const ERROR_CODE: usize = 40;
-    /// Reserved for future use
-    pub const RESERVED: usize = 44;
+    /// Chain-building candidates dropped by per-layer pruning (i32, saturating)
+    pub const CANDIDATES_DROPPED: usize = 44;
 }
 
 /// Shared buffer for communicating with Kotlin.
@@ -123,14 +126,25 @@ impl PointerScanSharedBuffer {
     }
 
     /// Write the current scan phase.
-    pub fn write_phase(&self, phase: crate::pointer_scan::types::ScanPhase) {
+    pub fn write_phase(&self, phase: ScanPhase) {
         self.write_i32(offsets::PHASE, phase as i32);
+        match phase {
+            ScanPhase::ScanningPointers => scan_events::report_started(ScanKind::PointerScan, JobKind::PointerScan),
+            // 只构建链时没有扫描阶段
+            ScanPhase::BuildingChains if !scan_events::is_running(ScanKind::PointerScan) => {
+                scan_events::report_started(ScanKind::PointerScan, JobKind::ChainBuild)
+            },
+            ScanPhase::Completed => scan_events::report_finished(ScanKind::PointerScan, false),
+            ScanPhase::Cancelled => scan_events::report_finished(ScanKind::PointerScan, true),
+            _ => {},
+        }
     }
 
     /// Write the progress percentage (0-100).
     pub fn write_progress(&self, progress: i32) {
         let clamped = progress.clamp(0, 100);
         self.write_i32(offsets::PROGRESS, clamped);
+        scan_events::report_progress(ScanKind::PointerScan, clamped);
     }
 
     /// Write the number of memory regions processed.
@@ -141,11 +155,13 @@ impl PointerScanSharedBuffer {
     /// Write the total number of pointers found.
     pub fn write_pointers_found(&self, count: i64) {
         self.write_i64(offsets::POINTERS_FOUND, count);
+        scan_events::report_found(ScanKind::PointerScan, count);
     }
 
     /// Write the total number of chains found.
     pub fn write_chains_found(&self, count: i64) {
         self.write_i64(offsets::CHAINS_FOUND, count);
+        scan_events::report_found(ScanKind::PointerScan, count);
     }
 
     /// Write the current search depth.
@@ -153,9 +169,19 @@ impl PointerScanSharedBuffer {
         self.write_i32(offsets::CURRENT_DEPTH, depth);
     }
 
+    /// Write the number of pruned chain-building candidates.
+    pub fn write_candidates_dropped(&self, count: u64) {
+        self.write_i32(offsets::CANDIDATES_DROPPED, count.min(i32::MAX as u64) as i32);
+    }
+
     /// Write the error code.
-    pub fn write_error_code(&self, code: crate::pointer_scan::types::ScanErrorCode) {
+    pub fn write_error_code(&self, code: ScanErrorCode) {
         self.write_i32(offsets::ERROR_CODE, code as i32);
+        match code {
+            ScanErrorCode::None => {},
+            ScanErrorCode::AlreadyScanning => scan_events::report_rejected(ScanKind::PointerScan, code as i32),
+            _ => scan_events::report_error(ScanKind::PointerScan, code as i32),
+        }
     }
 
     /// Update the heartbeat value.
@@ -182,23 +208,24 @@ impl PointerScanSharedBuffer {
         } else {
             0
         };
-        self.write_progress(progress);
-        self.write_regions_done(regions_done);
         self.write_pointers_found(pointers_found);
+        self.write_regions_done(regions_done);
+        self.write_progress(progress);
         self.update_heartbeat();
     }
 
     /// Update progress for Phase 2 (chain building).
-    pub fn update_building_progress(&self, current_depth: i32, max_depth: i32, chains_found: i64) {
+    pub fn update_building_progress(&self, current_depth: i32, max_depth: i32, chains_found: i64, candidates_dropped: u64) {
         // Phase 2 is 50-100% of total progress
         let progress = if max_depth > 0 {
             50 + (current_depth as f32 / max_depth as f32 * 50.0) as i32
         } else {
             50
         };
-        self.write_progress(progress);
-        self.write_current_depth(current_depth);
         self.write_chains_found(chains_found);
+        self.write_current_depth(current_depth);
+        self.write_candidates_dropped(candidates_dropped);
+        self.write_progress(progress);
         self.update_heartbeat();
     }
 }
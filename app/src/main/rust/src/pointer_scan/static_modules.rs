@@ -0,0 +1,94 @@
+//! Static module discovery.
+//!
+//! A pointer chain root is only stable if it lives in memory whose address
+//! is fixed relative to a module base: `.data`, `.bss`, `.got` and
+//! `.data.rel.ro` of a loaded image. Treating whole file mappings as static
+//! also roots chains in code and read-only data, which never hold pointers
+//! into the heap and only slow Phase 2 down.
+//!
+//! This walks the bound process's mappings, finds ELF images (`.so`, `.oat`,
+//! `.odex`, and libraries loaded straight from an APK) by their header, and
+//! reads their program headers to get the writable `PT_LOAD` segments,
+//! including the zero-filled `.bss` tail past the file contents. ART boot
+//! images (`.art`) are not ELF files; their writable mappings are used as is.
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::RemoteElf;
+use crate::core::region_type::{query_mem_regions, MemRegion};
+use crate::pointer_scan::types::VmStaticData;
+use crate::wuwa::MEM_WRITABLE;
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use std::collections::HashMap;
+
+fn is_image_name(name: &str) -> bool {
+    [".so", ".oat", ".odex", ".apk"].iter().any(|ext| name.ends_with(ext))
+}
+
+fn is_art_name(name: &str) -> bool {
+    name.ends_with(".art")
+}
+
+/// Find the static data segments of every image loaded in the bound process.
+///
+/// Segments of one image share the mapping name and get consecutive module
+/// indices, the same way the Java layer's static regions are numbered.
+pub fn discover_static_modules(manager: &DriverManager) -> Result<Vec<VmStaticData>> {
+    if !manager.is_process_bound() {
+        return Err(anyhow!("No process bound"));
+    }
+
+    let mut regions: Vec<MemRegion> = query_mem_regions(manager, manager.get_bound_pid())?;
+    regions.sort_by_key(|region| region.start);
+
+    let mut modules = Vec::new();
+    let mut covered_until = 0u64;
+    for region in &regions {
+        if region.start < covered_until {
+            continue;
+        }
+
+        if is_art_name(&region.name) {
+            if region.flags & MEM_WRITABLE != 0 {
+                modules.push(VmStaticData::new(region.name.clone(), region.start, region.end, true));
+            }
+            continue;
+        }
+
+        if !is_image_name(&region.name) {
+            continue;
+        }
+        let Some(image) = RemoteElf::read(manager, region.start) else {
+            continue;
+        };
+
+        let data_segments = image.data_segments();
+        debug!("Static image {} at 0x{:X}: {} data segments", region.name, region.start, data_segments.len());
+        covered_until = image.end();
+        for (start, end) in data_segments {
+            modules.push(VmStaticData::new(region.name.clone(), start, end, true));
+        }
+    }
+
+    assign_module_indices(&mut modules);
+    info!("Discovered {} static data segments in {} mappings", modules.len(), regions.len());
+    Ok(modules)
+}
+
+/// Assign indices and first_module_base_addr to static modules with duplicate names.
+pub fn assign_module_indices(static_modules: &mut [VmStaticData]) {
+    // 同名模块共享第一个段的基址，用于计算统一的偏移
+    let mut name_counts: HashMap<String, u32> = HashMap::new();
+    let mut first_base_addrs: HashMap<String, u64> = HashMap::new();
+    for module in static_modules {
+        let count = name_counts.entry(module.name.clone()).or_insert(0);
+        module.index = *count;
+        if *count == 0 {
+            // 记录该名称第一个模块的基址
+            first_base_addrs.insert(module.name.clone(), module.base_address);
+        }
+        // 所有同名模块共享第一个段的基址
+        module.first_module_base_addr = *first_base_addrs.get(&module.name).unwrap();
+        *count += 1;
+    }
+}
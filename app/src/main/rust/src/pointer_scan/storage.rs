@@ -4,6 +4,15 @@
 //! pointer data on disk while providing fast random access. This allows
 //! handling very large datasets (millions of pointers) without running
 //! out of memory.
+//!
+//! Queues created with `MmapQueue::new_fixed` require every record to
+//! serialize to the same length. They skip the per-item index and locate
+//! items by stride from a small file header, which halves the memory
+//! footprint of the pointer library.
+//!
+//! A queue can be persisted with `MmapQueue::persist` and reopened later with
+//! `MmapQueue::open`, so a pointer library built once can be reused for
+//! several chain-building runs without rescanning the process.
 
 use anyhow::Result;
 use memmap2::MmapMut;
@@ -15,12 +24,25 @@ use rkyv::util::AlignedVec;
 use rkyv::{access_unchecked, rancor, to_bytes, Archive, Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::marker::PhantomData;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use rkyv::api::high::HighSerializer;
 
 const ALIGNMENT: usize = 16;
 const RKYV_BUF_SIZE: usize = 4096;
 
+/// Header written at the start of fixed-stride files so they can be reopened
+/// without a per-item index: magic, archived record length, item count.
+const FIXED_MAGIC: [u8; 8] = *b"MAMUMQF1";
+const FIXED_HEADER_SIZE: usize = 32;
+const FIXED_RECORD_LEN_OFFSET: usize = 8;
+const FIXED_COUNT_OFFSET: usize = 16;
+
+/// Trailer written at the very end of persisted indexed files: magic, offset of
+/// the `(offset, len)` index table, item count.
+const INDEX_MAGIC: [u8; 8] = *b"MAMUMQI1";
+const INDEX_TRAILER_SIZE: usize = 24;
+
 pub struct MmapQueue<T> {
     file: File,
     file_path: PathBuf,
@@ -29,6 +51,17 @@ pub struct MmapQueue<T> {
     count: usize,                 // Number of items stored
     write_offset: usize,          // Current write position in bytes
     indices: Vec<(usize, usize)>, // (offset, length)
+    /// Archived length shared by every item so far, None once lengths differ.
+    /// While set, item `i` lives at `data_start + i * align_up(len)` and can be
+    /// located without `indices`.
+    uniform_len: Option<usize>,
+    /// Fixed-stride mode: every item must have the same archived length and
+    /// `indices` is never filled
+    fixed: bool,
+    /// Byte offset of the first item (after the header in fixed-stride mode)
+    data_start: usize,
+    /// Keep the backing file on drop (set for reopened queues)
+    keep_file: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -47,6 +80,21 @@ where
     /// * `cache_dir` - Directory to store the backing file
     /// * `name` - Name prefix for the backing file
     pub fn new(cache_dir: &PathBuf, name: &str) -> Result<Self> {
+        Self::create(cache_dir, name, false)
+    }
+
+    /// Create a fixed-stride MmapQueue for types with a constant archived size
+    /// (e.g. `PointerData`).
+    ///
+    /// Offsets are computed from the index, so no per-item `(offset, len)` pair
+    /// is kept in memory. The record length and item count are stored in a small
+    /// header at the start of the file. Pushing an item with a different archived
+    /// length fails.
+    pub fn new_fixed(cache_dir: &Path, name: &str) -> Result<Self> {
+        Self::create(cache_dir, name, true)
+    }
+
+    fn create(cache_dir: &Path, name: &str, fixed: bool) -> Result<Self> {
         let file_path = cache_dir.join(format!("mamu_ps_{}.bin", name));
 
         // Create parent directory if it doesn't exist
@@ -59,17 +107,175 @@ where
         file.set_len(Self::INITIAL_SIZE as u64)?;
 
         let mmap = unsafe { MmapMut::map_mut(&file)? };
+        let data_start = if fixed { FIXED_HEADER_SIZE } else { 0 };
 
-        Ok(Self {
+        let mut queue = Self {
             file,
             file_path,
             mmap: Some(mmap),
             capacity: Self::INITIAL_SIZE,
             count: 0,
-            write_offset: 0,
+            write_offset: data_start,
             indices: Vec::new(),
+            uniform_len: None,
+            fixed,
+            data_start,
+            keep_file: false,
             _phantom: PhantomData,
-        })
+        };
+        queue.write_header();
+        Ok(queue)
+    }
+
+    /// Reopen a queue file written by [`MmapQueue::persist`].
+    ///
+    /// Fixed-stride files are restored from their header, indexed files from
+    /// the index table at the end of the file. The caller must open the file
+    /// with the same `T` it was written with; files whose records are shorter
+    /// than `T::Archived` or misaligned are rejected as corrupt, since these
+    /// files may come from other devices. Reopened queues keep their backing
+    /// file on drop.
+    pub fn open(path: &Path) -> Result<Self> {
+        let archived_len = size_of::<T::Archived>();
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let file_len = file.metadata()?.len() as usize;
+        if file_len == 0 {
+            return Err(anyhow::anyhow!("Empty MmapQueue file: {}", path.display()));
+        }
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        let read_u64 = |offset: usize| u64::from_le_bytes(mmap[offset..offset + 8].try_into().unwrap()) as usize;
+
+        let mut queue = if file_len >= FIXED_HEADER_SIZE && mmap[..8] == FIXED_MAGIC {
+            let record_len = read_u64(FIXED_RECORD_LEN_OFFSET);
+            let count = read_u64(FIXED_COUNT_OFFSET);
+            let data_end = record_len
+                .checked_next_multiple_of(ALIGNMENT)
+                .and_then(|stride| count.checked_mul(stride))
+                .and_then(|len| len.checked_add(FIXED_HEADER_SIZE))
+                .filter(|&end| end <= file_len && (count == 0 || record_len == archived_len))
+                .ok_or_else(|| anyhow::anyhow!("Corrupt MmapQueue header in {}", path.display()))?;
+
+            Self {
+                file,
+                file_path: path.to_path_buf(),
+                mmap: None,
+                capacity: file_len,
+                count,
+                write_offset: data_end,
+                indices: Vec::new(),
+                uniform_len: if count > 0 { Some(record_len) } else { None },
+                fixed: true,
+                data_start: FIXED_HEADER_SIZE,
+                keep_file: true,
+                _phantom: PhantomData,
+            }
+        } else if file_len >= INDEX_TRAILER_SIZE && mmap[file_len - INDEX_TRAILER_SIZE..file_len - 16] == INDEX_MAGIC {
+            let table_offset = read_u64(file_len - 16);
+            let count = read_u64(file_len - 8);
+            if count.checked_mul(16).and_then(|len| len.checked_add(table_offset)) != Some(file_len - INDEX_TRAILER_SIZE) {
+                return Err(anyhow::anyhow!("Corrupt MmapQueue index in {}", path.display()));
+            }
+
+            let indices: Vec<(usize, usize)> = (0..count)
+                .map(|i| (read_u64(table_offset + i * 16), read_u64(table_offset + i * 16 + 8)))
+                .collect();
+            if indices
+                .iter()
+                .any(|&(offset, len)| offset.saturating_add(len) > table_offset || len < archived_len || offset % ALIGNMENT != 0)
+            {
+                return Err(anyhow::anyhow!("Corrupt MmapQueue index in {}", path.display()));
+            }
+
+            // Only keep the stride shortcut if items really are laid out back to back
+            let uniform_len = indices.first().map(|&(_, len)| len).filter(|&len| {
+                let stride = len.next_multiple_of(ALIGNMENT);
+                indices.iter().enumerate().all(|(i, &entry)| Some(entry) == i.checked_mul(stride).map(|offset| (offset, len)))
+            });
+
+            Self {
+                file,
+                file_path: path.to_path_buf(),
+                mmap: None,
+                capacity: file_len,
+                count,
+                write_offset: table_offset,
+                indices,
+                uniform_len,
+                fixed: false,
+                data_start: 0,
+                keep_file: true,
+                _phantom: PhantomData,
+            }
+        } else {
+            return Err(anyhow::anyhow!("Not a persisted MmapQueue file: {}", path.display()));
+        };
+
+        queue.mmap = Some(mmap);
+        Ok(queue)
+    }
+
+    /// Make the backing file reopenable with [`MmapQueue::open`].
+    ///
+    /// Fixed-stride queues only need their header; indexed queues get their
+    /// `(offset, len)` table appended after the data. The file is truncated to
+    /// the persisted size and flushed. Pushing more items afterwards overwrites
+    /// the table, so persist again before reopening.
+    pub fn persist(&mut self) -> Result<()> {
+        if self.fixed {
+            self.write_header();
+            self.resize(self.write_offset)?;
+        } else {
+            let table_offset = self.write_offset.next_multiple_of(8);
+            let end = table_offset + self.indices.len() * 16 + INDEX_TRAILER_SIZE;
+            self.resize(end)?;
+
+            if let Some(ref mut mmap) = self.mmap {
+                for (i, &(offset, len)) in self.indices.iter().enumerate() {
+                    let pos = table_offset + i * 16;
+                    mmap[pos..pos + 8].copy_from_slice(&(offset as u64).to_le_bytes());
+                    mmap[pos + 8..pos + 16].copy_from_slice(&(len as u64).to_le_bytes());
+                }
+                let trailer = end - INDEX_TRAILER_SIZE;
+                mmap[trailer..trailer + 8].copy_from_slice(&INDEX_MAGIC);
+                mmap[trailer + 8..trailer + 16].copy_from_slice(&(table_offset as u64).to_le_bytes());
+                mmap[trailer + 16..end].copy_from_slice(&(self.count as u64).to_le_bytes());
+            }
+        }
+        self.flush()
+    }
+
+    /// Keep (or delete) the backing file when the queue is dropped.
+    pub fn set_keep_file(&mut self, keep: bool) {
+        self.keep_file = keep;
+    }
+
+    /// Update the fixed-stride header (no-op for indexed queues).
+    fn write_header(&mut self) {
+        if !self.fixed {
+            return;
+        }
+        let record_len = self.uniform_len.unwrap_or(0) as u64;
+        let count = self.count as u64;
+        if let Some(ref mut mmap) = self.mmap {
+            mmap[..8].copy_from_slice(&FIXED_MAGIC);
+            mmap[FIXED_RECORD_LEN_OFFSET..FIXED_RECORD_LEN_OFFSET + 8].copy_from_slice(&record_len.to_le_bytes());
+            mmap[FIXED_COUNT_OFFSET..FIXED_COUNT_OFFSET + 8].copy_from_slice(&count.to_le_bytes());
+        }
+    }
+
+    /// True if this queue was created with [`MmapQueue::new_fixed`].
+    pub fn is_fixed(&self) -> bool {
+        self.fixed
+    }
+
+    /// Locate item `index` as (byte offset, archived length). Caller checks `index < count`.
+    #[inline]
+    fn locate(&self, index: usize) -> (usize, usize) {
+        match self.uniform_len {
+            Some(len) if self.fixed => (self.data_start + index * len.next_multiple_of(ALIGNMENT), len),
+            _ => self.indices[index],
+        }
     }
 
     /// Push an item to the end of the queue.
@@ -77,6 +283,14 @@ where
         let bytes = to_bytes::<Error>(item)?;
         let size = bytes.len();
 
+        if self.fixed && self.count > 0 && self.uniform_len != Some(size) {
+            return Err(anyhow::anyhow!(
+                "Fixed-stride MmapQueue expects {} byte records, got {}",
+                self.uniform_len.unwrap_or(0),
+                size
+            ));
+        }
+
         let padding = (ALIGNMENT - (self.write_offset % ALIGNMENT)) % ALIGNMENT;
         let required_space = size + padding;
 
@@ -95,10 +309,26 @@ where
         }
 
         let data_offset = self.write_offset + padding;
-        self.indices.push((data_offset, size));
+        if self.count == 0 {
+            self.uniform_len = Some(size);
+        } else if self.uniform_len != Some(size) {
+            self.uniform_len = None;
+        }
+        if !self.fixed {
+            self.indices.push((data_offset, size));
+        }
         self.write_offset += required_space;
         self.count += 1;
 
+        if self.fixed {
+            if let Some(ref mut mmap) = self.mmap {
+                mmap[FIXED_COUNT_OFFSET..FIXED_COUNT_OFFSET + 8].copy_from_slice(&(self.count as u64).to_le_bytes());
+            }
+            if self.count == 1 {
+                self.write_header();
+            }
+        }
+
         Ok(())
     }
 
@@ -111,7 +341,10 @@ where
     }
 
     pub fn get(&self, index: usize) -> Option<&T::Archived> {
-        let (offset, length) = *self.indices.get(index)?;
+        if index >= self.count {
+            return None;
+        }
+        let (offset, length) = self.locate(index);
 
         self.mmap.as_ref().map(|mmap| unsafe {
             let ptr = mmap.as_ptr().add(offset);
@@ -120,6 +353,35 @@ where
         })
     }
 
+    /// Iterate over all archived items in order.
+    pub fn iter(&self) -> MmapQueueIter<'_, T> {
+        self.range(0..self.count)
+    }
+
+    /// Iterate over the archived items in `range`, clamped to the queue length.
+    ///
+    /// When every item has the same archived size (e.g. `PointerData`), the
+    /// iterator steps through the mmap by a fixed stride instead of looking up
+    /// `indices` for each element.
+    pub fn range(&self, range: Range<usize>) -> MmapQueueIter<'_, T> {
+        let end = range.end.min(self.count);
+        let start = range.start.min(end);
+        // Without a mapping there is nothing to read, so the iterator is empty
+        let (base, end) = match self.mmap.as_ref() {
+            Some(mmap) => (mmap.as_ptr(), end),
+            None => (std::ptr::null(), start),
+        };
+
+        MmapQueueIter {
+            queue: self,
+            base,
+            stride: self.uniform_len.map(|len| (len, len.next_multiple_of(ALIGNMENT))),
+            data_start: self.data_start,
+            pos: start,
+            end,
+        }
+    }
+
     pub fn get_deserialized(&self, index: usize) -> Option<T>
     where
         T::Archived: Deserialize<T, T::Archived>,
@@ -148,8 +410,24 @@ where
     /// Clear all items from the queue.
     pub fn clear(&mut self) {
         self.count = 0;
-        self.write_offset = 0;
+        self.write_offset = self.data_start;
         self.indices.clear();
+        self.uniform_len = None;
+        self.write_header();
+    }
+
+    /// Set the backing file to exactly `new_size` bytes and remap.
+    fn resize(&mut self, new_size: usize) -> Result<()> {
+        if let Some(ref mmap) = self.mmap {
+            mmap.flush()?;
+        }
+        self.mmap = None;
+
+        self.file.set_len(new_size as u64)?;
+        self.mmap = Some(unsafe { MmapMut::map_mut(&self.file)? });
+        self.capacity = new_size;
+
+        Ok(())
     }
 
     /// Grow the backing file and remap.
@@ -168,6 +446,34 @@ where
         Ok(())
     }
 
+    /// Shrink the backing file to the bytes actually written.
+    ///
+    /// The file is preallocated in `INITIAL_SIZE`/`GROW_SIZE` steps, so a
+    /// finished (or cleared) queue usually carries a large unused tail.
+    /// Further pushes simply grow the file again.
+    ///
+    /// Returns the number of bytes released.
+    pub fn compact(&mut self) -> Result<u64> {
+        let page_size = *crate::core::globals::PAGE_SIZE;
+        let new_size = self.write_offset.div_ceil(page_size).max(1) * page_size;
+        if new_size >= self.capacity {
+            return Ok(0);
+        }
+
+        if let Some(ref mmap) = self.mmap {
+            mmap.flush()?;
+        }
+        self.mmap = None;
+
+        self.file.set_len(new_size as u64)?;
+        self.mmap = Some(unsafe { MmapMut::map_mut(&self.file)? });
+
+        let released = (self.capacity - new_size) as u64;
+        self.capacity = new_size;
+
+        Ok(released)
+    }
+
     /// Flush changes to disk.
     pub fn flush(&self) -> Result<()> {
         if let Some(ref mmap) = self.mmap {
@@ -182,11 +488,201 @@ where
     }
 }
 
+/// Sequential iterator over a range of [`MmapQueue`] items, see [`MmapQueue::range`].
+pub struct MmapQueueIter<'a, T> {
+    queue: &'a MmapQueue<T>,
+    base: *const u8,
+    /// (archived length, distance between items) for uniformly sized queues
+    stride: Option<(usize, usize)>,
+    data_start: usize,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a, T> MmapQueueIter<'a, T>
+where
+    T: Archive,
+    T::Archived: 'static,
+{
+    #[inline]
+    fn item(&self, index: usize) -> &'a T::Archived {
+        let (offset, length) = match self.stride {
+            Some((length, stride)) => (self.data_start + index * stride, length),
+            None => self.queue.indices[index],
+        };
+        // Safety: `index < queue.count`, so the item was fully written at `offset`,
+        // and the shared borrow of the queue keeps the mmap alive and unmoved.
+        unsafe {
+            let slice = std::slice::from_raw_parts(self.base.add(offset), length);
+            access_unchecked::<T::Archived>(slice)
+        }
+    }
+}
+
+impl<'a, T> Iterator for MmapQueueIter<'a, T>
+where
+    T: Archive,
+    T::Archived: 'static,
+{
+    type Item = &'a T::Archived;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let item = self.item(self.pos);
+        self.pos += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for MmapQueueIter<'_, T>
+where
+    T: Archive,
+    T::Archived: 'static,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.item(self.end))
+    }
+}
+
+impl<T> ExactSizeIterator for MmapQueueIter<'_, T>
+where
+    T: Archive,
+    T::Archived: 'static,
+{
+}
+
 impl<T> Drop for MmapQueue<T> {
     fn drop(&mut self) {
         // Explicitly drop mmap before file
         self.mmap = None;
         // Try to remove the backing file
-        let _ = std::fs::remove_file(&self.file_path);
+        if !self.keep_file {
+            let _ = std::fs::remove_file(&self.file_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pointer_scan::types::{ArchivedPointerData, PointerData};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mamu_storage_test_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn persisted_fixed(dir: &Path, count: u64) -> PathBuf {
+        let mut queue = MmapQueue::<PointerData>::new_fixed(dir, "lib").unwrap();
+        for i in 0..count {
+            queue.push(&PointerData::new(0x1000 + i * 8, 0x7000_0000 + i)).unwrap();
+        }
+        queue.persist().unwrap();
+        queue.set_keep_file(true);
+        queue.file_path().clone()
+    }
+
+    fn write_u64(path: &Path, offset: usize, value: u64) {
+        let mut bytes = std::fs::read(path).unwrap();
+        bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_fixed_persist_open_roundtrip() {
+        let dir = test_dir("fixed_roundtrip");
+        let path = persisted_fixed(&dir, 100);
+
+        let queue = MmapQueue::<PointerData>::open(&path).unwrap();
+        assert!(queue.is_fixed());
+        assert_eq!(queue.len(), 100);
+        assert_eq!(queue.get(42).unwrap().address, 0x1000 + 42 * 8);
+        assert_eq!(queue.iter().map(|p| p.value.to_native()).collect::<Vec<_>>(), (0..100).map(|i| 0x7000_0000 + i).collect::<Vec<_>>());
+        assert_eq!(queue.range(10..20).len(), 10);
+        assert_eq!(queue.range(90..200).rev().next().unwrap().address, 0x1000 + 99 * 8);
+
+        drop(queue);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_indexed_persist_open_roundtrip() {
+        let dir = test_dir("indexed_roundtrip");
+        let mut queue = MmapQueue::<Vec<u64>>::new(&dir, "indexed").unwrap();
+        for i in 0..10u64 {
+            queue.push(&(0..i).collect()).unwrap();
+        }
+        queue.persist().unwrap();
+        queue.set_keep_file(true);
+        let path = queue.file_path().clone();
+        drop(queue);
+
+        let queue = MmapQueue::<Vec<u64>>::open(&path).unwrap();
+        assert!(!queue.is_fixed());
+        assert_eq!(queue.len(), 10);
+        assert_eq!(queue.get(3).unwrap().as_slice(), &[0, 1, 2]);
+
+        drop(queue);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_iter_without_mmap_is_empty() {
+        let dir = test_dir("no_mmap");
+        let mut queue = MmapQueue::<PointerData>::new_fixed(&dir, "lib").unwrap();
+        queue.push(&PointerData::new(0x1000, 0x2000)).unwrap();
+        queue.mmap = None;
+
+        let iter = queue.iter();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.count(), 0);
+
+        drop(queue);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_short_record_len() {
+        let dir = test_dir("short_record");
+        let path = persisted_fixed(&dir, 4);
+        write_u64(&path, FIXED_RECORD_LEN_OFFSET, 1);
+        assert!(MmapQueue::<PointerData>::open(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_overflowing_header() {
+        let dir = test_dir("overflow_header");
+        let path = persisted_fixed(&dir, 4);
+        write_u64(&path, FIXED_RECORD_LEN_OFFSET, u64::MAX);
+        assert!(MmapQueue::<PointerData>::open(&path).is_err());
+        write_u64(&path, FIXED_RECORD_LEN_OFFSET, size_of::<ArchivedPointerData>() as u64);
+        write_u64(&path, FIXED_COUNT_OFFSET, u64::MAX);
+        assert!(MmapQueue::<PointerData>::open(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_truncated_file() {
+        let dir = test_dir("truncated");
+        let path = persisted_fixed(&dir, 4);
+        let len = std::fs::metadata(&path).unwrap().len();
+        File::options().write(true).open(&path).unwrap().set_len(len - 1).unwrap();
+        assert!(MmapQueue::<PointerData>::open(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
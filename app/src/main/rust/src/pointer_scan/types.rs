@@ -30,7 +30,7 @@ impl PointerData {
 }
 
 /// Memory region metadata for static module identification.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct VmStaticData {
     /// Module name (e.g., "libil2cpp.so")
     pub name: String,
@@ -68,7 +68,7 @@ impl VmStaticData {
 }
 
 /// A single step in a pointer chain.
-#[derive(Debug, Clone)]
+#[derive(Archive, Deserialize, Serialize, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PointerChainStep {
     /// Module name if this is a static pointer, None if dynamic
     pub module_name: Option<String>,
@@ -101,7 +101,7 @@ impl PointerChainStep {
 }
 
 /// Complete pointer chain from a static module to the target address.
-#[derive(Debug, Clone)]
+#[derive(Archive, Deserialize, Serialize, serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct PointerChain {
     /// Chain steps from root to target
     pub steps: Vec<PointerChainStep>,
@@ -162,10 +162,43 @@ impl PointerChain {
 
         result
     }
+
+    /// Parse a chain written by [`Self::format`]. The module index may be left out
+    /// (`libil2cpp.so+0x1A2B3C0->+0x18`), it then defaults to 0.
+    pub fn parse(text: &str, target_address: u64) -> Option<Self> {
+        let mut parts = text.trim().split("->");
+        let (module, root_offset) = parts.next()?.rsplit_once('+')?;
+        let (name, index) = match module.strip_suffix(']').and_then(|module| module.rsplit_once('[')) {
+            Some((name, index)) => (name, index.parse().ok()?),
+            None => (module, 0),
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut chain = Self::new(target_address);
+        chain.push(PointerChainStep::static_root(name.to_string(), index, parse_signed_hex(root_offset)?));
+        for offset in parts {
+            chain.push(PointerChainStep::dynamic_offset(parse_signed_hex(offset)?));
+        }
+        Some(chain)
+    }
+}
+
+/// Parse `0x18`, `+0x18` or `-0x20`.
+pub(crate) fn parse_signed_hex(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let digits = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")).unwrap_or(digits);
+    let value = u64::from_str_radix(digits, 16).ok()? as i64;
+    Some(if negative { value.wrapping_neg() } else { value })
 }
 
 /// Configuration for pointer scanning.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PointerScanConfig {
     /// Target address to find pointers to
     pub target_address: u64,
@@ -173,7 +206,9 @@ pub struct PointerScanConfig {
     pub max_depth: u32,
     /// Maximum offset per level in bytes (default: 0x1000)
     pub max_offset: u32,
-    /// Pointer alignment in bytes (default: 4)
+    /// Pointer alignment in bytes, one of 1, 2, 4 or 8 (default: 4).
+    /// 1 finds pointers at any offset inside packed structures, but Phase 1 checks
+    /// 4x the positions of the default and takes correspondingly longer.
     pub align: u32,
     /// Use Layer-BFS to build pointer chain
     pub is_layer_bfs: bool,
@@ -181,6 +216,34 @@ pub struct PointerScanConfig {
     pub data_start: bool,
     /// lookup Base Addr from start of .bss
     pub bss_start: bool,
+    /// Phase 1 read chunk size in bytes, page aligned (default: 512KB)
+    pub chunk_size: usize,
+    /// Maximum rayon worker threads, 0 = one per CPU core
+    pub max_threads: usize,
+    /// Upper bound of pointers buffered before a sorted temp file is flushed (default: 10M, ~160MB).
+    /// The writer lowers it at runtime when memory is short or storage is slow.
+    pub batch_threshold: usize,
+    /// Max offset per level, indexed by distance from the target (0 = pointers
+    /// to the target itself). Levels past the end use `max_offset`.
+    #[serde(default)]
+    pub level_max_offsets: Vec<u32>,
+    /// Known structure offsets, sorted. When not empty, only these offsets are
+    /// followed (still bounded by the level max offset).
+    #[serde(default)]
+    pub offset_whitelist: Vec<u32>,
+    /// How far below a pointer's value the next level may sit, i.e. the largest
+    /// negative offset followed (default: 0, `max_offset` only bounds forward offsets)
+    #[serde(default)]
+    pub max_backward_offset: u32,
+    /// Low-priority scan: workers run at SCHED_IDLE / nice 19, Phase 1 reads are
+    /// throttled and yield between chunks, so the game keeps its frame rate (default: false)
+    #[serde(default)]
+    pub background: bool,
+    /// Last-level offsets (target - base register) seen by a watchpoint on the
+    /// target, sorted. When not empty, the first level only follows these, so
+    /// every chain ends in an access the game really made.
+    #[serde(default)]
+    pub access_offsets: Vec<i64>,
 }
 
 impl Default for PointerScanConfig {
@@ -193,6 +256,14 @@ impl Default for PointerScanConfig {
             is_layer_bfs: false,
             data_start: true,
             bss_start: false,
+            chunk_size: 512 * 1024,
+            max_threads: 0,
+            batch_threshold: 10_000_000,
+            level_max_offsets: Vec::new(),
+            offset_whitelist: Vec::new(),
+            max_backward_offset: 0,
+            background: false,
+            access_offsets: Vec::new(),
         }
     }
 }
@@ -219,6 +290,85 @@ impl PointerScanConfig {
         self.align = align;
         self
     }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    pub fn with_max_threads(mut self, max_threads: usize) -> Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    pub fn with_batch_threshold(mut self, batch_threshold: usize) -> Self {
+        self.batch_threshold = batch_threshold;
+        self
+    }
+
+    pub fn with_background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_level_max_offsets(mut self, level_max_offsets: Vec<u32>) -> Self {
+        self.level_max_offsets = level_max_offsets;
+        self
+    }
+
+    pub fn with_offset_whitelist(mut self, mut offset_whitelist: Vec<u32>) -> Self {
+        offset_whitelist.sort_unstable();
+        offset_whitelist.dedup();
+        self.offset_whitelist = offset_whitelist;
+        self
+    }
+
+    pub fn with_max_backward_offset(mut self, max_backward_offset: u32) -> Self {
+        self.max_backward_offset = max_backward_offset;
+        self
+    }
+
+    pub fn with_access_offsets(mut self, mut access_offsets: Vec<i64>) -> Self {
+        access_offsets.sort_unstable();
+        access_offsets.dedup();
+        self.access_offsets = access_offsets;
+        self
+    }
+
+    /// Phase 1 positions checked relative to the default alignment of 4.
+    pub fn relative_scan_cost(&self) -> f64 {
+        4.0 / self.align.max(1) as f64
+    }
+
+    /// Max offset for the level `depth` steps away from the target.
+    pub fn max_offset_at(&self, depth: usize) -> u32 {
+        self.level_max_offsets.get(depth).copied().unwrap_or(self.max_offset)
+    }
+}
+
+/// Phase 1 writer statistics of the last pointer scan.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ScanReport {
+    /// `PointerScanConfig::batch_threshold`, the upper bound
+    pub configured_batch_threshold: usize,
+    /// Threshold chosen at start from available memory
+    pub batch_threshold: usize,
+    /// Smallest threshold used after adapting to memory and storage speed
+    pub min_batch_threshold: usize,
+    /// MemAvailable at scan start, in bytes
+    pub available_memory: Option<u64>,
+    /// Average temp file write speed, in bytes per second
+    pub write_bytes_per_sec: Option<u64>,
+    /// `PointerScanConfig::align` of the scan
+    pub align: u32,
+    /// `PointerScanConfig::relative_scan_cost`, Phase 1 work relative to the default alignment
+    pub relative_scan_cost: f64,
+    /// `PointerScanConfig::background` of the scan
+    pub background: bool,
+    pub temp_files: usize,
+    pub pointers: usize,
+    pub scan_millis: u64,
+    pub merge_millis: u64,
 }
 
 /// Scan phase enumeration for progress tracking.
@@ -0,0 +1,110 @@
+//! Pointer chain revalidation.
+//!
+//! Chains are stored as module-relative offsets, so after a process restart
+//! they can be re-resolved against fresh module bases. This module walks each
+//! chain in the live process and reports whether it still resolves and what
+//! it points to, which is how thousands of candidates get filtered down to
+//! the stable ones.
+
+use crate::pointer_scan::types::{PointerChain, VmStaticData};
+
+/// ARM64 virtual addresses use the lower 48 bits; the top byte may carry a tag.
+const ADDRESS_MASK: u64 = 0x0000_FFFF_FFFF_FFFF;
+
+/// Outcome of resolving a single chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainValidation {
+    /// Final address the chain resolves to, if every dereference succeeded
+    pub resolved_address: Option<u64>,
+    /// 8 bytes read at the resolved address
+    pub value: Option<u64>,
+    /// Index of the step that failed (root module missing or unreadable pointer)
+    pub failed_step: Option<usize>,
+}
+
+impl ChainValidation {
+    fn failed(step: usize) -> Self {
+        Self {
+            resolved_address: None,
+            value: None,
+            failed_step: Some(step),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.resolved_address.is_some()
+    }
+}
+
+/// Find the base address a chain root offset is relative to.
+///
+/// Mirrors `classify_pointer`: with `data_start`, every segment of a module
+/// shares the base of its first segment.
+fn resolve_root_base(name: &str, index: u32, static_modules: &[VmStaticData], data_start: bool) -> Option<u64> {
+    let module = static_modules.iter().find(|m| m.name == name && m.index == index)?;
+    if data_start && module.index != 0 {
+        Some(module.first_module_base_addr)
+    } else {
+        Some(module.base_address)
+    }
+}
+
+/// Walk the first `levels` steps of a chain and return the address they reach.
+///
+/// `levels` of 1 is the root slot itself; `chain.depth()` is the full chain.
+/// On failure returns the index of the step that could not be resolved.
+fn resolve_steps<R>(chain: &PointerChain, levels: usize, static_modules: &[VmStaticData], data_start: bool, read_u64: &R) -> Result<u64, usize>
+where
+    R: Fn(u64) -> Option<u64>,
+{
+    let root = chain.steps.first().ok_or(0usize)?;
+    let name = root.module_name.as_deref().ok_or(0usize)?;
+    let base = resolve_root_base(name, root.module_index, static_modules, data_start).ok_or(0usize)?;
+
+    let mut address = base.wrapping_add_signed(root.offset);
+    for (i, step) in chain.steps.iter().enumerate().take(levels).skip(1) {
+        match read_u64(address) {
+            Some(ptr) if ptr & ADDRESS_MASK != 0 => {
+                address = (ptr & ADDRESS_MASK).wrapping_add_signed(step.offset);
+            },
+            _ => return Err(i),
+        }
+    }
+    Ok(address)
+}
+
+/// Resolve a single chain.
+///
+/// `read_u64` reads 8 bytes at the given address in the target process.
+pub fn validate_chain<R>(chain: &PointerChain, static_modules: &[VmStaticData], data_start: bool, read_u64: &R) -> ChainValidation
+where
+    R: Fn(u64) -> Option<u64>,
+{
+    match resolve_steps(chain, chain.depth(), static_modules, data_start, read_u64) {
+        Ok(address) => ChainValidation {
+            resolved_address: Some(address),
+            value: read_u64(address),
+            failed_step: None,
+        },
+        Err(step) => ChainValidation::failed(step),
+    }
+}
+
+/// Resolve the slot a chain reaches after its first `levels` steps.
+///
+/// Dropping those steps and finding new static paths to this slot keeps the
+/// rest of the chain valid, which is how a chain gets re-rooted.
+pub fn resolve_chain_prefix<R>(chain: &PointerChain, levels: usize, static_modules: &[VmStaticData], data_start: bool, read_u64: &R) -> Option<u64>
+where
+    R: Fn(u64) -> Option<u64>,
+{
+    resolve_steps(chain, levels, static_modules, data_start, read_u64).ok()
+}
+
+/// Resolve every chain against fresh module bases.
+pub fn validate_pointer_chains<R>(chains: &[PointerChain], static_modules: &[VmStaticData], data_start: bool, read_u64: R) -> Vec<ChainValidation>
+where
+    R: Fn(u64) -> Option<u64>,
+{
+    chains.iter().map(|chain| validate_chain(chain, static_modules, data_start, &read_u64)).collect()
+}
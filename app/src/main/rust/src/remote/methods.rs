@@ -0,0 +1,264 @@
+//! 远程调用的方法
+//!
+//! | 方法 | 参数 | 结果 |
+//! | --- | --- | --- |
+//! | `ping` | | `"pong"` |
+//! | `status` | | 驱动、绑定进程和搜索状态 |
+//! | `process.list` | | `[{pid, name}]` |
+//! | `regions` | `mask?` | `[{start, end, flags, name, type}]` |
+//! | `mem.read` | `address, size` | 十六进制字符串 |
+//! | `mem.write` | `address, data`（十六进制） | `true` |
+//! | `search.start` | `query, type, mask?, keep?` | `true`，异步执行 |
+//! | `search.refine` | `query, type` | `true`，异步执行 |
+//! | `search.cancel` / `search.clear` | | `null` |
+//! | `search.status` | | `{searching, count}` |
+//! | `search.results` | `offset, count` | `[{address, type}]` |
+//!
+//! `type` 为值类型 id，`mask` 为区域类型掩码，与 JNI 接口一致。
+
+use crate::core::DRIVER_MANAGER;
+use crate::core::error::MamuError;
+use crate::core::globals::OP_QUEUE;
+use crate::core::region_type::{process_name, query_mem_regions, query_ranges_by_mask};
+use crate::search::SearchResultItem;
+use crate::search::engine::SEARCH_ENGINE_MANAGER;
+use crate::search::parser::parse_search_query;
+use crate::search::types::ValueType;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+/// mem.read 单次上限
+const MAX_READ_SIZE: usize = 1024 * 1024;
+
+/// search.results 单次上限
+const MAX_RESULTS: usize = 10_000;
+
+#[derive(Deserialize)]
+struct MaskParams {
+    mask: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ReadParams {
+    address: u64,
+    size: usize,
+}
+
+#[derive(Deserialize)]
+struct WriteParams {
+    address: u64,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    query: String,
+    #[serde(rename = "type")]
+    value_type: i32,
+    mask: Option<u64>,
+    #[serde(default)]
+    keep: bool,
+}
+
+#[derive(Deserialize)]
+struct ResultsParams {
+    #[serde(default)]
+    offset: usize,
+    count: usize,
+}
+
+fn params<T: DeserializeOwned>(params: &Value) -> Result<T> {
+    let params = if params.is_null() { json!({}) } else { params.clone() };
+    serde_json::from_value(params).map_err(|e| anyhow!("Invalid params: {}", e))
+}
+
+fn value_type(id: i32) -> Result<ValueType> {
+    ValueType::from_id(id).ok_or_else(|| anyhow!("Invalid value type: {}", id))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    // 先整体校验，非 ASCII 字符不会被当作两个十六进制位切开
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return Err(anyhow!("Invalid hex data"));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err(anyhow!("Hex data has odd length"));
+    }
+    let nibble = |digit: u8| (digit as char).to_digit(16).unwrap_or_default() as u8;
+    Ok(digits.chunks_exact(2).map(|pair| (nibble(pair[0]) << 4) | nibble(pair[1])).collect())
+}
+
+/// 执行一个方法
+pub fn dispatch(method: &str, raw: &Value) -> Result<Value> {
+    match method {
+        "ping" => Ok(json!("pong")),
+        "status" => status(),
+        "process.list" => process_list(),
+        "regions" => regions(params(raw)?),
+        "mem.read" => mem_read(params(raw)?),
+        "mem.write" => mem_write(params(raw)?),
+        "search.start" => search_start(params(raw)?),
+        "search.refine" => search_refine(params(raw)?),
+        "search.cancel" => {
+            SEARCH_ENGINE_MANAGER
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?
+                .request_cancel();
+            Ok(Value::Null)
+        },
+        "search.clear" => {
+            let mut manager = SEARCH_ENGINE_MANAGER
+                .write()
+                .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+            manager.clear_results()?;
+            Ok(Value::Null)
+        },
+        "search.status" => search_status(),
+        "search.results" => search_results(params(raw)?),
+        _ => Err(anyhow!("Unknown method: {}", method)),
+    }
+}
+
+fn status() -> Result<Value> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let (searching, count) = match SEARCH_ENGINE_MANAGER.read() {
+        Ok(search) => (search.is_searching(), search.get_total_count().unwrap_or(0)),
+        Err(_) => (false, 0),
+    };
+    Ok(json!({
+        "driverLoaded": manager.is_driver_loaded(),
+        "backend": manager.backend_name(),
+        "bound": manager.is_process_bound(),
+        "pid": manager.get_bound_pid(),
+        "searching": searching,
+        "resultCount": count,
+    }))
+}
+
+fn process_list() -> Result<Value> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let backend = manager.backend();
+    let list: Vec<Value> = backend
+        .list_processes()
+        .into_iter()
+        .filter_map(|pid| {
+            let info = backend.process_info(pid).ok()?;
+            let end = info.name.iter().position(|&c| c == 0).unwrap_or(info.name.len());
+            Some(json!({ "pid": pid, "name": String::from_utf8_lossy(&info.name[..end]) }))
+        })
+        .collect();
+    Ok(Value::Array(list))
+}
+
+fn regions(params: MaskParams) -> Result<Value> {
+    let mask = params.mask.unwrap_or(u64::MAX);
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into());
+    }
+    let pid = manager.get_bound_pid();
+    let proc_name = process_name(&manager, pid);
+    let list: Vec<Value> = query_mem_regions(&manager, pid)?
+        .into_iter()
+        .filter_map(|region| {
+            let typ = region.classify(&proc_name).filter(|typ| typ.matches(mask))?;
+            Some(json!({
+                "start": region.start,
+                "end": region.end,
+                "flags": region.flags,
+                "name": region.name,
+                "type": typ as u8,
+            }))
+        })
+        .collect();
+    Ok(Value::Array(list))
+}
+
+fn mem_read(params: ReadParams) -> Result<Value> {
+    if params.size > MAX_READ_SIZE {
+        return Err(anyhow!("Read size {} exceeds {}", params.size, MAX_READ_SIZE));
+    }
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let _op = OP_QUEUE.interactive();
+    let mut buf = vec![0u8; params.size];
+    manager.read_memory_unified(params.address, &mut buf, None)?;
+    Ok(json!(to_hex(&buf)))
+}
+
+fn mem_write(params: WriteParams) -> Result<Value> {
+    let data = from_hex(&params.data)?;
+    let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+    let _op = OP_QUEUE.interactive();
+    manager.write_memory_unified(params.address, &data)?;
+    Ok(json!(true))
+}
+
+fn search_start(params: SearchParams) -> Result<Value> {
+    let query = parse_search_query(&params.query, value_type(params.value_type)?).map_err(|e| anyhow!("Parse error: {}", e))?;
+    let ranges = {
+        let manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager read lock"))?;
+        query_ranges_by_mask(&manager, params.mask.unwrap_or(u64::MAX))?
+    };
+    let mut manager = SEARCH_ENGINE_MANAGER
+        .write()
+        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+    manager.start_search_async(query, ranges, false, params.keep)?;
+    Ok(json!(true))
+}
+
+fn search_refine(params: SearchParams) -> Result<Value> {
+    let query = parse_search_query(&params.query, value_type(params.value_type)?).map_err(|e| anyhow!("Parse error: {}", e))?;
+    let mut manager = SEARCH_ENGINE_MANAGER
+        .write()
+        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager write lock"))?;
+    manager.start_refine_async(query)?;
+    Ok(json!(true))
+}
+
+fn search_status() -> Result<Value> {
+    let manager = SEARCH_ENGINE_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+    let searching = manager.is_searching();
+    // 搜索进行中结果集还在写入，只报告状态
+    let count = if searching { 0 } else { manager.get_total_count()? };
+    Ok(json!({ "searching": searching, "count": count }))
+}
+
+fn search_results(params: ResultsParams) -> Result<Value> {
+    let manager = SEARCH_ENGINE_MANAGER
+        .read()
+        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))?;
+    let items = manager.get_results(params.offset, params.count.min(MAX_RESULTS))?;
+    let list: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            let (address, typ) = match item {
+                SearchResultItem::Exact(exact) => (exact.address, exact.typ),
+                SearchResultItem::Fuzzy(fuzzy) => (fuzzy.address, fuzzy.value_type),
+            };
+            json!({ "address": address, "type": typ.to_id() })
+        })
+        .collect();
+    Ok(Value::Array(list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(from_hex("de ad\nBE ef").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("aé00").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+}
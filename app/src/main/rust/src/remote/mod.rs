@@ -0,0 +1,398 @@
+//! 远程控制服务
+//!
+//! 在本机 TCP 端口（只绑定 127.0.0.1，可经 `adb forward` 转发到电脑）或 Unix 套接字上
+//! 提供驱动管理器和搜索引擎的调用接口，供桌面端工具驱动扫描和内存修改。
+//! 帧格式见 [`protocol`]，可用方法见 [`methods`]。
+//!
+//! 同一时间只运行一个服务，每个连接一个线程（最多 [`MAX_CONNECTIONS`] 个），请求按顺序处理。
+//! 本机任何应用都能连上监听端口，所以服务必须设置令牌：连接的第一个请求必须是 `auth`
+//! （参数 `{"token": "..."}`），否则断开。认证前单帧不超过 [`PRE_AUTH_MAX_FRAME_SIZE`]，
+//! 收到无法解析的帧立即断开，且须在 [`AUTH_TIMEOUT`] 内完成认证，避免空闲连接占满名额。
+
+pub mod methods;
+pub mod protocol;
+
+use crate::core::globals::clear_mutex_poison;
+use anyhow::{Result, anyhow};
+use log::{error, info, warn};
+use protocol::{MAX_FRAME_SIZE, PRE_AUTH_MAX_FRAME_SIZE, Request, error_response, ok_response, read_frame, write_frame};
+use serde_json::{Value, json};
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// accept 轮询间隔
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 连接读超时，超时后检查停止标志
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 连接建立后完成认证的期限
+pub const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 同时处理的连接上限，超出的连接直接关闭
+pub const MAX_CONNECTIONS: usize = 8;
+
+struct ServerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    /// Unix 套接字文件，停止时删除
+    socket_path: Option<PathBuf>,
+}
+
+static SERVER: Mutex<Option<ServerHandle>> = Mutex::new(None);
+
+/// 可以接受连接的监听器
+trait Listener: Send + 'static {
+    type Stream: Read + Write + Send + 'static;
+
+    fn accept_stream(&self) -> std::io::Result<Self::Stream>;
+
+    /// 连接默认继承非阻塞，改回阻塞并设置读超时
+    fn prepare(stream: &Self::Stream) -> std::io::Result<()>;
+}
+
+impl Listener for TcpListener {
+    type Stream = std::net::TcpStream;
+
+    fn accept_stream(&self) -> std::io::Result<Self::Stream> {
+        self.accept().map(|(stream, _)| stream)
+    }
+
+    fn prepare(stream: &Self::Stream) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))
+    }
+}
+
+impl Listener for UnixListener {
+    type Stream = std::os::unix::net::UnixStream;
+
+    fn accept_stream(&self) -> std::io::Result<Self::Stream> {
+        self.accept().map(|(stream, _)| stream)
+    }
+
+    fn prepare(stream: &Self::Stream) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))
+    }
+}
+
+/// 在 127.0.0.1:`port` 上启动服务，`token` 不能为空
+pub fn start_tcp(port: u16, token: String) -> Result<()> {
+    check_token(&token)?;
+    let mut server = SERVER.lock().map_err(|_| anyhow!("Failed to acquire remote server lock"))?;
+    if server.is_some() {
+        return Err(anyhow!("Remote server is already running"));
+    }
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    info!("Remote: 监听 {}", listener.local_addr()?);
+    *server = Some(spawn(listener, token, None)?);
+    Ok(())
+}
+
+/// 在 Unix 套接字 `path` 上启动服务，已存在的文件会被替换，`token` 不能为空
+pub fn start_unix(path: &str, token: String) -> Result<()> {
+    check_token(&token)?;
+    let mut server = SERVER.lock().map_err(|_| anyhow!("Failed to acquire remote server lock"))?;
+    if server.is_some() {
+        return Err(anyhow!("Remote server is already running"));
+    }
+    let path = PathBuf::from(path);
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    // 只允许本进程所属用户连接
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    listener.set_nonblocking(true)?;
+    info!("Remote: 监听 {}", path.display());
+    *server = Some(spawn(listener, token, Some(path))?);
+    Ok(())
+}
+
+/// 停止服务并等待监听线程退出，没有运行中的服务时返回 false
+///
+/// 已建立的连接在下一次读超时时关闭
+pub fn stop_server() -> bool {
+    let Some(mut handle) = SERVER.lock().ok().and_then(|mut server| server.take()) else {
+        return false;
+    };
+    handle.stop.store(true, Ordering::Release);
+    if let Some(thread) = handle.thread.take() {
+        let _ = thread.join();
+    }
+    if let Some(path) = &handle.socket_path {
+        let _ = std::fs::remove_file(path);
+    }
+    info!("Remote: 已停止");
+    true
+}
+
+pub fn is_running() -> bool {
+    SERVER.lock().is_ok_and(|server| server.is_some())
+}
+
+/// 清除服务锁的中毒状态，返回中毒的锁数
+pub(crate) fn clear_poisoned_lock() -> usize {
+    clear_mutex_poison(&SERVER)
+}
+
+fn check_token(token: &str) -> Result<()> {
+    if token.is_empty() {
+        return Err(anyhow!("Remote server requires a non-empty token"));
+    }
+    Ok(())
+}
+
+/// 比较令牌，耗时与第一个不同字节的位置无关
+fn token_eq(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len() && given.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// 占用一个连接名额，drop 时归还
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < MAX_CONNECTIONS).then_some(n + 1))
+            .ok()
+            .map(|_| ConnectionSlot(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn spawn<L: Listener>(listener: L, token: String, socket_path: Option<PathBuf>) -> Result<ServerHandle> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let token = Arc::new(token);
+    let thread = std::thread::Builder::new()
+        .name("mamu-remote".to_string())
+        .spawn(move || accept_loop(listener, token, thread_stop))?;
+    Ok(ServerHandle {
+        stop,
+        thread: Some(thread),
+        socket_path,
+    })
+}
+
+fn accept_loop<L: Listener>(listener: L, token: Arc<String>, stop: Arc<AtomicBool>) {
+    let active = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::Acquire) {
+        match listener.accept_stream() {
+            Ok(stream) => {
+                let Some(slot) = ConnectionSlot::acquire(&active) else {
+                    warn!("Remote: 连接数已达上限 {}，拒绝新连接", MAX_CONNECTIONS);
+                    continue;
+                };
+                if let Err(e) = L::prepare(&stream) {
+                    warn!("Remote: 设置连接失败: {}", e);
+                    continue;
+                }
+                let token = token.clone();
+                let stop = stop.clone();
+                let spawned = std::thread::Builder::new().name("mamu-remote-conn".to_string()).spawn(move || {
+                    let _slot = slot;
+                    if let Err(e) = serve(stream, &token, &stop) {
+                        warn!("Remote: 连接断开: {:#}", e);
+                    }
+                });
+                if let Err(e) = spawned {
+                    error!("Remote: 创建连接线程失败: {}", e);
+                }
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_INTERVAL),
+            Err(e) => {
+                error!("Remote: accept 失败: {}", e);
+                std::thread::sleep(ACCEPT_INTERVAL);
+            },
+        }
+    }
+}
+
+/// 处理一个连接直到对端关闭或服务停止
+fn serve(mut stream: impl Read + Write, token: &str, stop: &AtomicBool) -> Result<()> {
+    let auth_deadline = Instant::now() + AUTH_TIMEOUT;
+    let mut authenticated = false;
+    loop {
+        let (max_len, deadline) = if authenticated { (MAX_FRAME_SIZE, None) } else { (PRE_AUTH_MAX_FRAME_SIZE, Some(auth_deadline)) };
+        let Some(frame) = read_frame(&mut stream, stop, max_len, deadline)? else {
+            break;
+        };
+        let request: Request = match serde_json::from_slice(&frame) {
+            Ok(request) => request,
+            Err(e) => {
+                write_frame(&mut stream, &error_response(0, &anyhow!("Malformed request: {}", e)))?;
+                // 认证前的坏帧直接断开
+                if !authenticated {
+                    return Err(anyhow!("Malformed request before authentication"));
+                }
+                continue;
+            },
+        };
+
+        if !authenticated {
+            let given = request.params.get("token").and_then(Value::as_str).unwrap_or_default();
+            if request.method != "auth" || !token_eq(given.as_bytes(), token.as_bytes()) {
+                write_frame(&mut stream, &error_response(request.id, &anyhow!("Authentication required")))?;
+                return Err(anyhow!("Authentication failed"));
+            }
+            authenticated = true;
+            write_frame(&mut stream, &ok_response(request.id, json!(true)))?;
+            continue;
+        }
+
+        let response = match request.method.as_str() {
+            "auth" => ok_response(request.id, json!(true)),
+            method => match methods::dispatch(method, &request.params) {
+                Ok(result) => ok_response(request.id, result),
+                Err(e) => error_response(request.id, &e),
+            },
+        };
+        write_frame(&mut stream, &response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// 输入来自预先写好的帧，输出写入缓冲区
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Duplex {
+        fn new(frames: &[&[u8]]) -> Self {
+            let mut input = Vec::new();
+            for frame in frames {
+                input.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+                input.extend_from_slice(frame);
+            }
+            Self {
+                input: Cursor::new(input),
+                output: Vec::new(),
+            }
+        }
+
+        fn responses(&self) -> Vec<Value> {
+            let stop = AtomicBool::new(false);
+            let mut output = Cursor::new(self.output.as_slice());
+            std::iter::from_fn(|| read_frame(&mut output, &stop, MAX_FRAME_SIZE, None).unwrap())
+                .map(|frame| serde_json::from_slice(&frame).unwrap())
+                .collect()
+        }
+    }
+
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_token_eq() {
+        assert!(token_eq(b"secret", b"secret"));
+        assert!(!token_eq(b"secreT", b"secret"));
+        assert!(!token_eq(b"secret1", b"secret"));
+        assert!(!token_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn test_empty_token_rejected() {
+        assert!(check_token("").is_err());
+        assert!(start_tcp(0, String::new()).is_err());
+    }
+
+    #[test]
+    fn test_wrong_token_disconnects() {
+        let stop = AtomicBool::new(false);
+        let mut stream = Duplex::new(&[br#"{"id":1,"method":"auth","params":{"token":"nope"}}"#]);
+        assert!(serve(&mut stream, "secret", &stop).is_err());
+        assert!(stream.responses()[0].get("error").is_some());
+    }
+
+    #[test]
+    fn test_request_before_auth_disconnects() {
+        let stop = AtomicBool::new(false);
+        let mut stream = Duplex::new(&[br#"{"id":1,"method":"mem.read","params":{}}"#]);
+        assert!(serve(&mut stream, "secret", &stop).is_err());
+    }
+
+    #[test]
+    fn test_large_frame_before_auth_rejected() {
+        let stop = AtomicBool::new(false);
+        let mut stream = Duplex::new(&[&vec![b' '; PRE_AUTH_MAX_FRAME_SIZE + 1]]);
+        assert!(serve(&mut stream, "secret", &stop).is_err());
+        assert!(stream.output.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_frame_before_auth_disconnects() {
+        let stop = AtomicBool::new(false);
+        let mut stream = Duplex::new(&[b"not json", br#"{"id":1,"method":"auth","params":{"token":"secret"}}"#]);
+        assert!(serve(&mut stream, "secret", &stop).is_err());
+        assert_eq!(stream.responses().len(), 1);
+    }
+
+    #[test]
+    fn test_read_deadline() {
+        /// 始终读超时的连接
+        struct Idle;
+
+        impl Read for Idle {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(ErrorKind::WouldBlock.into())
+            }
+        }
+
+        let stop = AtomicBool::new(false);
+        assert!(read_frame(&mut Idle, &stop, PRE_AUTH_MAX_FRAME_SIZE, Some(Instant::now())).is_err());
+    }
+
+    #[test]
+    fn test_auth_then_ping() {
+        let stop = AtomicBool::new(false);
+        let mut stream = Duplex::new(&[br#"{"id":1,"method":"auth","params":{"token":"secret"}}"#, br#"{"id":2,"method":"auth"}"#]);
+        serve(&mut stream, "secret", &stop).unwrap();
+        let responses = stream.responses();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[1]["result"], json!(true));
+    }
+
+    #[test]
+    fn test_connection_slots_capped() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS).map(|_| ConnectionSlot::acquire(&active).unwrap()).collect();
+        assert!(ConnectionSlot::acquire(&active).is_none());
+        drop(slots);
+        assert_eq!(active.load(Ordering::Acquire), 0);
+    }
+}
@@ -0,0 +1,91 @@
+//! 帧格式
+//!
+//! 每帧为 4 字节小端长度加 UTF-8 JSON：
+//! - 请求 `{"id": 1, "method": "mem.read", "params": {...}}`
+//! - 成功 `{"id": 1, "result": ...}`
+//! - 失败 `{"id": 1, "error": {"code": 3, "message": "..."}}`，`code` 同 `ErrorCode`
+
+use crate::core::error::error_code;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// 单帧上限
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// 认证前的单帧上限，`auth` 请求用不了更多
+pub const PRE_AUTH_MAX_FRAME_SIZE: usize = 4 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: u64,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+pub fn ok_response(id: u64, result: Value) -> Value {
+    json!({ "id": id, "result": result })
+}
+
+pub fn error_response(id: u64, err: &anyhow::Error) -> Value {
+    json!({
+        "id": id,
+        "error": { "code": error_code(err) as i32, "message": format!("{:#}", err) },
+    })
+}
+
+/// 读满 `buf`，读超时时检查 `stop` 和 `deadline`。读到一半断开视为错误，开头就断开返回 false
+fn read_full(stream: &mut impl Read, buf: &mut [u8], stop: &AtomicBool, deadline: Option<Instant>) -> Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        if stop.load(Ordering::Acquire) {
+            return Err(anyhow!("Server stopped"));
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(anyhow!("Read deadline exceeded"));
+        }
+        match stream.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(anyhow!("Connection closed mid-frame")),
+            Ok(n) => filled += n,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(true)
+}
+
+/// 读取一帧，长度超过 `max_len` 时不分配直接报错，对端关闭时返回 None
+///
+/// 给出 `deadline` 时，到期仍未读完整帧则报错
+pub fn read_frame(stream: &mut impl Read, stop: &AtomicBool, max_len: usize, deadline: Option<Instant>) -> Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    if !read_full(stream, &mut len, stop, deadline)? {
+        return Ok(None);
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    if len > max_len {
+        return Err(anyhow!("Frame too large: {} bytes", len));
+    }
+    let mut frame = vec![0u8; len];
+    if !read_full(stream, &mut frame, stop, deadline)? && len > 0 {
+        return Err(anyhow!("Connection closed mid-frame"));
+    }
+    Ok(Some(frame))
+}
+
+pub fn write_frame(stream: &mut impl Write, value: &Value) -> Result<()> {
+    let payload = serde_json::to_vec(value)?;
+    if payload.len() > MAX_FRAME_SIZE {
+        return Err(anyhow!("Response too large: {} bytes", payload.len()));
+    }
+    stream.write_all(&(payload.len() as u32).to_le_bytes())?;
+    stream.write_all(&payload)?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,399 @@
+//! 脚本可用的 `mamu` 接口
+//!
+//! 类型参数使用值类型 id（`mamu.TYPE_*`，与 `ValueType::to_id` 一致），
+//! 区域掩码中第 n 位对应 [`RegionType`] 的第 n 个类型（`mamu.REGION_*`）。
+//!
+//! | 函数 | 说明 |
+//! | --- | --- |
+//! | `read(addr, type)` | 读取一个值，失败返回 nil |
+//! | `write(addr, type, value)` | 写入一个值，返回是否成功 |
+//! | `readBytes(addr, len)` / `writeBytes(addr, bytes)` | 按字节串读写 |
+//! | `search(query, type[, mask])` | 新搜索，等待完成后返回结果数 |
+//! | `refine(query, type)` | 在当前结果中改善搜索，返回结果数 |
+//! | `getResults(offset, count)` | 结果列表，每项为 `{address, type}` |
+//! | `resultCount()` / `clearResults()` | 结果数 / 清空结果 |
+//! | `regions([mask])` | 内存区域，每项为 `{start, end, flags, name, type}` |
+//! | `resolvePointer(chain[, dataStart])` | 解析 `libfoo.so[0]+0x10->+0x8` 形式的指针链 |
+//! | `freeze(addr, type, value)` / `unfreeze(addr)` | 冻结 / 解冻，冻结循环需已启动 |
+//! | `sleep(ms)` | 休眠，期间响应停止请求 |
+//! | `isBound()` / `pid()` | 绑定状态 / 目标 pid |
+
+use crate::core::DRIVER_MANAGER;
+use crate::core::error::MamuError;
+use crate::core::globals::{FREEZE_MANAGER, OP_QUEUE};
+use crate::core::region_type::{MemRegion, RegionType, process_name, query_mem_regions, query_ranges_by_mask};
+use crate::core::saved_list::{Resolver, SavedLocation};
+use crate::pointer_scan::types::PointerChain;
+use crate::script::{ScriptContext, emit};
+use crate::search::SearchResultItem;
+use crate::search::engine::SEARCH_ENGINE_MANAGER;
+use crate::search::parser::parse_search_query;
+use crate::search::types::ValueType;
+use mlua::{ExternalError, ExternalResult, Lua, Table, Value, Variadic};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 等待搜索和休眠时检查停止请求的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const REGION_TYPES: [(&str, RegionType); 21] = [
+    ("JH", RegionType::Jh),
+    ("CH", RegionType::Ch),
+    ("CA", RegionType::Ca),
+    ("CD", RegionType::Cd),
+    ("CB", RegionType::Cb),
+    ("PS", RegionType::Ps),
+    ("AN", RegionType::An),
+    ("J", RegionType::J),
+    ("S", RegionType::S),
+    ("AS", RegionType::As),
+    ("V", RegionType::V),
+    ("O", RegionType::O),
+    ("B", RegionType::B),
+    ("XA", RegionType::Xa),
+    ("XS", RegionType::Xs),
+    ("DX", RegionType::Dx),
+    ("JC", RegionType::Jc),
+    ("OA", RegionType::Oa),
+    ("VX", RegionType::Vx),
+    ("TS", RegionType::Ts),
+    ("XX", RegionType::Xx),
+];
+
+fn lock_error(name: &str) -> mlua::Error {
+    mlua::Error::runtime(format!("Failed to acquire {} lock", name))
+}
+
+pub(crate) fn value_type(id: i32) -> mlua::Result<ValueType> {
+    match ValueType::from_id(id) {
+        Some(ValueType::Vec3) => Err(mlua::Error::runtime("Vec3 is not supported in scripts")),
+        Some(typ) => Ok(typ),
+        None => Err(mlua::Error::runtime(format!("Invalid value type: {}", id))),
+    }
+}
+
+/// 把 Lua 数值编码为 `typ` 的小端字节
+pub(crate) fn encode_value(typ: ValueType, value: &Value) -> mlua::Result<Vec<u8>> {
+    let (int, float) = match value {
+        Value::Integer(i) => (*i, *i as f64),
+        Value::Number(n) => (*n as i64, *n),
+        Value::String(s) => {
+            let text = s.to_str()?.trim();
+            match text.parse::<i64>() {
+                Ok(i) => (i, i as f64),
+                Err(_) => {
+                    let n = text.parse::<f64>().map_err(|_| mlua::Error::runtime(format!("Invalid number: {}", text)))?;
+                    (n as i64, n)
+                },
+            }
+        },
+        other => return Err(mlua::Error::runtime(format!("Expected a number, got {}", other.type_name()))),
+    };
+    Ok(match typ {
+        ValueType::Float => (float as f32).to_le_bytes().to_vec(),
+        ValueType::Double => float.to_le_bytes().to_vec(),
+        ValueType::Vec3 => return Err(mlua::Error::runtime("Vec3 is not supported in scripts")),
+        _ => int.to_le_bytes()[..typ.size()].to_vec(),
+    })
+}
+
+/// 把小端字节解码为 Lua 数值，整数按有符号解释，Vec3 无法表示为单个数值，返回 nil
+pub(crate) fn decode_value(typ: ValueType, bytes: &[u8]) -> Value<'static> {
+    let mut raw = [0u8; 8];
+    let len = bytes.len().min(8);
+    raw[..len].copy_from_slice(&bytes[..len]);
+    match typ {
+        ValueType::Byte => Value::Integer(raw[0] as i8 as i64),
+        ValueType::Word => Value::Integer(i16::from_le_bytes([raw[0], raw[1]]) as i64),
+        ValueType::Dword | ValueType::Auto | ValueType::Xor => Value::Integer(i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as i64),
+        ValueType::Qword => Value::Integer(i64::from_le_bytes(raw)),
+        ValueType::Float => Value::Number(f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64),
+        ValueType::Double => Value::Number(f64::from_le_bytes(raw)),
+        ValueType::Vec3 => Value::Nil,
+    }
+}
+
+pub(crate) fn read_bytes(address: u64, len: usize) -> mlua::Result<Option<Vec<u8>>> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    let _op = OP_QUEUE.interactive();
+    let mut buf = vec![0u8; len];
+    Ok(manager.read_memory_unified(address, &mut buf, None).ok().map(|_| buf))
+}
+
+pub(crate) fn write_bytes(address: u64, bytes: &[u8]) -> mlua::Result<bool> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    let _op = OP_QUEUE.interactive();
+    Ok(manager.write_memory_unified(address, bytes).is_ok())
+}
+
+/// 一次读取多个值，失败的为 None
+pub(crate) fn read_many(requests: &[(u64, usize)]) -> mlua::Result<Vec<Option<Vec<u8>>>> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    let _op = OP_QUEUE.interactive();
+    Ok(manager.read_memory_batch(requests).into_iter().map(Result::ok).collect())
+}
+
+/// 一次写入多个值，返回成功数
+pub(crate) fn write_many(writes: &[(u64, &[u8])]) -> mlua::Result<usize> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    let _op = OP_QUEUE.interactive();
+    Ok(manager.write_memory_batch(writes).into_iter().filter(|ok| *ok).count())
+}
+
+/// 休眠 `duration`，期间响应停止请求
+pub(crate) fn sleep(context: &ScriptContext, duration: Duration) -> mlua::Result<()> {
+    let deadline = std::time::Instant::now() + duration;
+    loop {
+        context.check_stopped()?;
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            return Ok(());
+        }
+        std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+/// 等待异步搜索结束并返回结果数，脚本被停止时取消搜索
+pub(crate) fn wait_search(context: &ScriptContext) -> mlua::Result<usize> {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let manager = SEARCH_ENGINE_MANAGER.read().map_err(|_| lock_error("SearchEngineManager"))?;
+        if !manager.is_searching() {
+            context.check_stopped()?;
+            return manager.get_total_count().into_lua_err();
+        }
+        if context.is_stopped() {
+            manager.request_cancel();
+        }
+    }
+}
+
+/// 在 `ranges` 中开始新搜索并等待完成
+pub(crate) fn search(context: &ScriptContext, query: &str, typ: ValueType, ranges: Vec<(u64, u64)>) -> mlua::Result<usize> {
+    let query = parse_search_query(query, typ).map_err(|e| mlua::Error::runtime(format!("Parse error: {}", e)))?;
+    SEARCH_ENGINE_MANAGER
+        .write()
+        .map_err(|_| lock_error("SearchEngineManager"))?
+        .start_search_async(query, ranges, false, false)
+        .into_lua_err()?;
+    wait_search(context)
+}
+
+/// 在当前结果中改善搜索并等待完成
+pub(crate) fn refine(context: &ScriptContext, query: &str, typ: ValueType) -> mlua::Result<usize> {
+    let query = parse_search_query(query, typ).map_err(|e| mlua::Error::runtime(format!("Parse error: {}", e)))?;
+    SEARCH_ENGINE_MANAGER
+        .write()
+        .map_err(|_| lock_error("SearchEngineManager"))?
+        .start_refine_async(query)
+        .into_lua_err()?;
+    wait_search(context)
+}
+
+/// 绑定进程中类型在 `mask` 内的区域及其类型
+pub(crate) fn regions(mask: u64) -> mlua::Result<Vec<(MemRegion, RegionType)>> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    if !manager.is_process_bound() {
+        return Err(MamuError::not_bound().into_lua_err());
+    }
+    let pid = manager.get_bound_pid();
+    let proc_name = process_name(&manager, pid);
+    Ok(query_mem_regions(&manager, pid)
+        .into_lua_err()?
+        .into_iter()
+        .filter_map(|region| {
+            let typ = region.classify(&proc_name).filter(|typ| typ.matches(mask))?;
+            Some((region, typ))
+        })
+        .collect())
+}
+
+pub(crate) fn ranges_by_mask(mask: u64) -> mlua::Result<Vec<(u64, u64)>> {
+    let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+    query_ranges_by_mask(&manager, mask).into_lua_err()
+}
+
+/// 当前结果中 `offset` 开始的至多 `count` 个，返回地址和类型
+pub(crate) fn results(offset: usize, count: usize) -> mlua::Result<Vec<(u64, ValueType)>> {
+    let manager = SEARCH_ENGINE_MANAGER.read().map_err(|_| lock_error("SearchEngineManager"))?;
+    let items = manager.get_results(offset, count).into_lua_err()?;
+    Ok(items
+        .iter()
+        .map(|item| match item {
+            SearchResultItem::Exact(exact) => (exact.address, exact.typ),
+            SearchResultItem::Fuzzy(fuzzy) => (fuzzy.address, fuzzy.value_type),
+        })
+        .collect())
+}
+
+pub(crate) fn result_count() -> mlua::Result<usize> {
+    let manager = SEARCH_ENGINE_MANAGER.read().map_err(|_| lock_error("SearchEngineManager"))?;
+    manager.get_total_count().into_lua_err()
+}
+
+pub(crate) fn clear_results() -> mlua::Result<()> {
+    let mut manager = SEARCH_ENGINE_MANAGER.write().map_err(|_| lock_error("SearchEngineManager"))?;
+    manager.clear_results().into_lua_err()
+}
+
+/// 用 tostring 转换参数，以制表符连接
+pub(crate) fn join_args(lua: &Lua, args: Variadic<Value>) -> mlua::Result<String> {
+    let tostring: mlua::Function = lua.globals().get("tostring")?;
+    let mut parts = Vec::with_capacity(args.len());
+    for arg in args {
+        parts.push(tostring.call::<_, String>(arg)?);
+    }
+    Ok(parts.join("\t"))
+}
+
+/// 在 `lua` 中注册 `mamu` 表并替换 `print`
+pub fn install(lua: &Lua, context: Arc<ScriptContext>) -> mlua::Result<()> {
+    let mamu = lua.create_table()?;
+
+    for typ in [
+        ValueType::Byte,
+        ValueType::Word,
+        ValueType::Dword,
+        ValueType::Qword,
+        ValueType::Float,
+        ValueType::Double,
+    ] {
+        let name = match typ {
+            ValueType::Byte => "TYPE_BYTE",
+            ValueType::Word => "TYPE_WORD",
+            ValueType::Dword => "TYPE_DWORD",
+            ValueType::Qword => "TYPE_QWORD",
+            ValueType::Float => "TYPE_FLOAT",
+            _ => "TYPE_DOUBLE",
+        };
+        mamu.set(name, typ.to_id())?;
+    }
+    for (name, typ) in REGION_TYPES {
+        mamu.set(format!("REGION_{}", name), typ.mask())?;
+    }
+    mamu.set("REGION_ALL", REGION_TYPES.iter().fold(0u64, |mask, (_, typ)| mask | typ.mask()))?;
+
+    mamu.set(
+        "read",
+        lua.create_function(|_, (address, typ): (u64, i32)| {
+            let typ = value_type(typ)?;
+            Ok(read_bytes(address, typ.size())?.map(|bytes| decode_value(typ, &bytes)))
+        })?,
+    )?;
+    mamu.set(
+        "write",
+        lua.create_function(|_, (address, typ, value): (u64, i32, Value)| write_bytes(address, &encode_value(value_type(typ)?, &value)?))?,
+    )?;
+    mamu.set(
+        "readBytes",
+        lua.create_function(|lua, (address, len): (u64, usize)| match read_bytes(address, len)? {
+            Some(bytes) => Ok(Some(lua.create_string(&bytes)?)),
+            None => Ok(None),
+        })?,
+    )?;
+    mamu.set(
+        "writeBytes",
+        lua.create_function(|_, (address, bytes): (u64, mlua::String)| write_bytes(address, bytes.as_bytes()))?,
+    )?;
+
+    let search_context = context.clone();
+    mamu.set(
+        "search",
+        lua.create_function(move |_, (query, typ, mask): (String, i32, Option<u64>)| {
+            let ranges = ranges_by_mask(mask.unwrap_or(u64::MAX))?;
+            search(&search_context, &query, value_type(typ)?, ranges)
+        })?,
+    )?;
+    let refine_context = context.clone();
+    mamu.set(
+        "refine",
+        lua.create_function(move |_, (query, typ): (String, i32)| refine(&refine_context, &query, value_type(typ)?))?,
+    )?;
+    mamu.set(
+        "getResults",
+        lua.create_function(|lua, (offset, count): (usize, usize)| {
+            let list = lua.create_table()?;
+            for (address, typ) in results(offset, count)? {
+                let item = lua.create_table()?;
+                item.set("address", address)?;
+                item.set("type", typ.to_id())?;
+                list.push(item)?;
+            }
+            Ok(list)
+        })?,
+    )?;
+    mamu.set("resultCount", lua.create_function(|_, ()| result_count())?)?;
+    mamu.set("clearResults", lua.create_function(|_, ()| clear_results())?)?;
+
+    mamu.set(
+        "regions",
+        lua.create_function(|lua, mask: Option<u64>| {
+            let list: Table = lua.create_table()?;
+            for (region, typ) in regions(mask.unwrap_or(u64::MAX))? {
+                let item = lua.create_table()?;
+                item.set("start", region.start)?;
+                item.set("end", region.end)?;
+                item.set("flags", region.flags)?;
+                item.set("name", region.name)?;
+                item.set("type", typ as u8)?;
+                list.push(item)?;
+            }
+            Ok(list)
+        })?,
+    )?;
+
+    mamu.set(
+        "resolvePointer",
+        lua.create_function(|_, (text, data_start): (String, Option<bool>)| {
+            let chain = PointerChain::parse(&text, 0).ok_or_else(|| mlua::Error::runtime(format!("Invalid pointer chain: {}", text)))?;
+            let manager = DRIVER_MANAGER.read().map_err(|_| lock_error("DriverManager"))?;
+            let location = SavedLocation::Pointer {
+                chain,
+                data_start: data_start.unwrap_or(false),
+            };
+            Ok(Resolver::new(&manager).resolve(&location))
+        })?,
+    )?;
+
+    mamu.set(
+        "freeze",
+        lua.create_function(|_, (address, typ, value): (u64, i32, Value)| {
+            let bytes = encode_value(value_type(typ)?, &value)?;
+            let freeze = FREEZE_MANAGER.read().map_err(|_| lock_error("FreezeManager"))?;
+            Ok(freeze.add_frozen(address, bytes, typ).is_ok())
+        })?,
+    )?;
+    mamu.set(
+        "unfreeze",
+        lua.create_function(|_, address: u64| {
+            let freeze = FREEZE_MANAGER.read().map_err(|_| lock_error("FreezeManager"))?;
+            Ok(freeze.remove_frozen(address))
+        })?,
+    )?;
+
+    let sleep_context = context.clone();
+    mamu.set(
+        "sleep",
+        lua.create_function(move |_, ms: u64| sleep(&sleep_context, Duration::from_millis(ms)))?,
+    )?;
+    mamu.set(
+        "isBound",
+        lua.create_function(|_, ()| Ok(DRIVER_MANAGER.read().is_ok_and(|manager| manager.is_process_bound())))?,
+    )?;
+    mamu.set(
+        "pid",
+        lua.create_function(|_, ()| Ok(DRIVER_MANAGER.read().map(|manager| manager.get_bound_pid()).unwrap_or(0)))?,
+    )?;
+
+    lua.globals().set("mamu", mamu)?;
+    lua.globals().set(
+        "print",
+        lua.create_function(|lua, args: Variadic<Value>| {
+            for line in join_args(lua, args)?.split('\n') {
+                emit(line.trim_end_matches('\r'));
+            }
+            Ok(())
+        })?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,371 @@
+//! GameGuardian 脚本兼容层
+//!
+//! 在全局表 `gg` 中提供 GameGuardian 常用的接口，映射到搜索引擎和驱动管理器，
+//! 现有的 GG 脚本改动很少即可运行。常量取值与 GG 一致：
+//! - `gg.TYPE_*` 为 GG 的类型标志位，与 [`ValueType`] 互相转换
+//! - `gg.REGION_*` 为 GG 的区域标志位，搜索时转成 [`RegionType`] 掩码
+//!
+//! 支持的接口：`setRanges` / `getRanges`、`searchNumber`、`refineNumber`、`getResults`、
+//! `getResultsCount`、`clearResults`、`editAll`、`getValues`、`setValues`、`getRangesList`、
+//! `processPause` / `processResume` / `isProcessPaused`、`sleep`、`toast`、`alert`。
+//! 与 GG 相同，已有结果时 `searchNumber` 在结果中继续搜索，`editAll` 只修改最近一次
+//! `getResults` 载入的结果。`choice`、`prompt` 等界面接口没有对应实现。
+
+use crate::core::DRIVER_MANAGER;
+use crate::core::process_pause::{pause_process, paused_pid, resume_process};
+use crate::core::region_type::RegionType;
+use crate::script::api::{
+    clear_results, decode_value, encode_value, join_args, ranges_by_mask, read_many, refine, regions, result_count, results, search, sleep, write_many,
+};
+use crate::script::{ScriptContext, emit};
+use crate::search::types::ValueType;
+use crate::wuwa::{MEM_EXECUTABLE, MEM_READABLE, MEM_SHARED, MEM_WRITABLE};
+use mlua::{Lua, Table, Value, Variadic};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TYPE_BYTE: i64 = 1;
+const TYPE_WORD: i64 = 2;
+const TYPE_DWORD: i64 = 4;
+const TYPE_XOR: i64 = 8;
+const TYPE_FLOAT: i64 = 16;
+const TYPE_QWORD: i64 = 32;
+const TYPE_DOUBLE: i64 = 64;
+const TYPE_AUTO: i64 = 127;
+
+const SIGN_EQUAL: i64 = 536870912;
+
+/// 不属于其他区域标志的所有位
+const REGION_OTHER: i64 = -2080896;
+
+/// GG 区域标志位和对应的区域类型
+const REGIONS: [(&str, i64, &[RegionType]); 14] = [
+    ("REGION_C_HEAP", 1, &[RegionType::Ch]),
+    ("REGION_JAVA_HEAP", 2, &[RegionType::Jh]),
+    ("REGION_C_ALLOC", 4, &[RegionType::Ca]),
+    ("REGION_C_DATA", 8, &[RegionType::Cd]),
+    ("REGION_C_BSS", 16, &[RegionType::Cb]),
+    ("REGION_ANONYMOUS", 32, &[RegionType::An]),
+    ("REGION_STACK", 64, &[RegionType::S, RegionType::Ts]),
+    ("REGION_CODE_APP", 16384, &[RegionType::Xa]),
+    ("REGION_CODE_SYS", 32768, &[RegionType::Xs]),
+    ("REGION_JAVA", 65536, &[RegionType::J]),
+    ("REGION_BAD", 131072, &[RegionType::B]),
+    ("REGION_PPSSPP", 262144, &[RegionType::Ps]),
+    ("REGION_ASHMEM", 524288, &[RegionType::As]),
+    ("REGION_VIDEO", 1048576, &[RegionType::V]),
+];
+
+/// GG 中没有单独标志的类型归入 REGION_OTHER
+const OTHER_TYPES: [RegionType; 6] = [RegionType::O, RegionType::Dx, RegionType::Jc, RegionType::Oa, RegionType::Vx, RegionType::Xx];
+
+fn type_from_flags(flags: i64) -> mlua::Result<ValueType> {
+    Ok(match flags {
+        TYPE_BYTE => ValueType::Byte,
+        TYPE_WORD => ValueType::Word,
+        TYPE_DWORD => ValueType::Dword,
+        TYPE_XOR => ValueType::Xor,
+        TYPE_FLOAT => ValueType::Float,
+        TYPE_QWORD => ValueType::Qword,
+        TYPE_DOUBLE => ValueType::Double,
+        TYPE_AUTO => ValueType::Auto,
+        _ => return Err(mlua::Error::runtime(format!("Unsupported type flags: {}", flags))),
+    })
+}
+
+fn type_to_flags(typ: ValueType) -> i64 {
+    match typ {
+        ValueType::Byte => TYPE_BYTE,
+        ValueType::Word => TYPE_WORD,
+        ValueType::Dword => TYPE_DWORD,
+        ValueType::Xor => TYPE_XOR,
+        ValueType::Float => TYPE_FLOAT,
+        ValueType::Qword => TYPE_QWORD,
+        ValueType::Double => TYPE_DOUBLE,
+        ValueType::Auto => TYPE_AUTO,
+        // GG 没有向量类型，按第一个分量报告
+        ValueType::Vec3 => TYPE_FLOAT,
+    }
+}
+
+/// GG 区域标志转为区域类型掩码
+fn region_mask(ranges: i64) -> u64 {
+    let mut mask = 0u64;
+    for (_, flag, types) in REGIONS {
+        if ranges & flag != 0 {
+            mask |= types.iter().fold(0, |mask, typ| mask | typ.mask());
+        }
+    }
+    if ranges & REGION_OTHER != 0 {
+        mask |= OTHER_TYPES.iter().fold(0, |mask, typ| mask | typ.mask());
+    }
+    mask
+}
+
+/// 区域类型对应 GG 显示的简称
+fn region_state(typ: RegionType) -> &'static str {
+    match typ {
+        RegionType::Jh => "Jh",
+        RegionType::Ch => "Ch",
+        RegionType::Ca => "Ca",
+        RegionType::Cd => "Cd",
+        RegionType::Cb => "Cb",
+        RegionType::Ps => "PS",
+        RegionType::An => "A",
+        RegionType::J => "J",
+        RegionType::S | RegionType::Ts => "S",
+        RegionType::As => "As",
+        RegionType::V => "V",
+        RegionType::B => "B",
+        RegionType::Xa => "Xa",
+        RegionType::Xs => "Xs",
+        _ => "O",
+    }
+}
+
+fn perms(flags: u32) -> String {
+    let bit = |mask: u32, c: char| if flags & mask != 0 { c } else { '-' };
+    [
+        bit(MEM_READABLE, 'r'),
+        bit(MEM_WRITABLE, 'w'),
+        bit(MEM_EXECUTABLE, 'x'),
+        if flags & MEM_SHARED != 0 { 's' } else { 'p' },
+    ]
+    .iter()
+    .collect()
+}
+
+/// searchNumber(text, type, encrypted, sign, memoryFrom, memoryTo)
+type SearchNumberArgs = (String, Option<i64>, Option<bool>, Option<i64>, Option<u64>, Option<i64>);
+
+/// getResults(maxCount, skip, addressMin, addressMax, valueMin, valueMax, type)，数值过滤未实现
+type GetResultsArgs<'lua> = (usize, Option<usize>, Option<u64>, Option<u64>, Value<'lua>, Value<'lua>, Option<i64>);
+
+/// 一次脚本运行中 `gg` 的状态
+struct GgState {
+    ranges: AtomicI64,
+    /// 最近一次 getResults 载入的结果，editAll 作用于这些结果
+    loaded: Mutex<Vec<(u64, ValueType)>>,
+}
+
+/// 在 `lua` 中注册 `gg` 表
+pub fn install(lua: &Lua, context: Arc<ScriptContext>) -> mlua::Result<()> {
+    let gg = lua.create_table()?;
+    let state = Arc::new(GgState {
+        ranges: AtomicI64::new(-1),
+        loaded: Mutex::new(Vec::new()),
+    });
+
+    for (name, value) in [
+        ("TYPE_BYTE", TYPE_BYTE),
+        ("TYPE_WORD", TYPE_WORD),
+        ("TYPE_DWORD", TYPE_DWORD),
+        ("TYPE_XOR", TYPE_XOR),
+        ("TYPE_FLOAT", TYPE_FLOAT),
+        ("TYPE_QWORD", TYPE_QWORD),
+        ("TYPE_DOUBLE", TYPE_DOUBLE),
+        ("TYPE_AUTO", TYPE_AUTO),
+        ("SIGN_EQUAL", SIGN_EQUAL),
+        ("REGION_OTHER", REGION_OTHER),
+    ] {
+        gg.set(name, value)?;
+    }
+    for (name, flag, _) in REGIONS {
+        gg.set(name, flag)?;
+    }
+
+    let s = state.clone();
+    gg.set(
+        "setRanges",
+        lua.create_function(move |_, ranges: i64| {
+            s.ranges.store(ranges, Ordering::Relaxed);
+            Ok(())
+        })?,
+    )?;
+    let s = state.clone();
+    gg.set("getRanges", lua.create_function(move |_, ()| Ok(s.ranges.load(Ordering::Relaxed)))?)?;
+
+    let s = state.clone();
+    let c = context.clone();
+    gg.set(
+        "searchNumber",
+        lua.create_function(move |_, (text, flags, _encrypted, sign, from, to): SearchNumberArgs| {
+            check_sign(sign)?;
+            let typ = type_from_flags(flags.unwrap_or(TYPE_AUTO))?;
+            s.loaded.lock().map(|mut loaded| loaded.clear()).ok();
+            // 与 GG 相同，已有结果时在结果中搜索
+            if result_count()? > 0 {
+                refine(&c, &text, typ)?;
+                return Ok(true);
+            }
+            let from = from.unwrap_or(0);
+            let to = to.filter(|&to| to > 0).map_or(u64::MAX, |to| to as u64);
+            let ranges: Vec<(u64, u64)> = ranges_by_mask(region_mask(s.ranges.load(Ordering::Relaxed)))?
+                .into_iter()
+                .filter_map(|(start, end)| {
+                    let (start, end) = (start.max(from), end.min(to));
+                    (start < end).then_some((start, end))
+                })
+                .collect();
+            search(&c, &text, typ, ranges)?;
+            Ok(true)
+        })?,
+    )?;
+
+    let s = state.clone();
+    let c = context.clone();
+    gg.set(
+        "refineNumber",
+        lua.create_function(move |_, (text, flags, _encrypted, sign): (String, Option<i64>, Option<bool>, Option<i64>)| {
+            check_sign(sign)?;
+            s.loaded.lock().map(|mut loaded| loaded.clear()).ok();
+            refine(&c, &text, type_from_flags(flags.unwrap_or(TYPE_AUTO))?)?;
+            Ok(true)
+        })?,
+    )?;
+
+    gg.set("getResultsCount", lua.create_function(|_, ()| result_count())?)?;
+    let s = state.clone();
+    gg.set(
+        "clearResults",
+        lua.create_function(move |_, ()| {
+            s.loaded.lock().map(|mut loaded| loaded.clear()).ok();
+            clear_results()
+        })?,
+    )?;
+
+    let s = state.clone();
+    gg.set(
+        "getResults",
+        lua.create_function(
+            move |lua, (max_count, skip, address_min, address_max, _value_min, _value_max, flags): GetResultsArgs| {
+                let type_filter = flags.filter(|&flags| flags != TYPE_AUTO && flags != 0);
+                let items: Vec<(u64, ValueType)> = results(skip.unwrap_or(0), max_count)?
+                    .into_iter()
+                    .filter(|(address, typ)| {
+                        address_min.is_none_or(|min| *address >= min)
+                            && address_max.is_none_or(|max| *address <= max)
+                            && type_filter.is_none_or(|flags| flags & type_to_flags(*typ) != 0)
+                    })
+                    .collect();
+                let list = values_table(lua, &items)?;
+                if let Ok(mut loaded) = s.loaded.lock() {
+                    *loaded = items;
+                }
+                Ok(list)
+            },
+        )?,
+    )?;
+
+    let s = state.clone();
+    gg.set(
+        "editAll",
+        lua.create_function(move |_, (value, flags): (Value, i64)| {
+            let typ = type_from_flags(flags)?;
+            let bytes = encode_value(typ, &value)?;
+            let loaded = s.loaded.lock().map(|loaded| loaded.clone()).unwrap_or_default();
+            let writes: Vec<(u64, &[u8])> = loaded
+                .iter()
+                .filter(|(_, loaded_type)| type_to_flags(*loaded_type) & flags != 0)
+                .map(|(address, _)| (*address, &bytes[..]))
+                .collect();
+            write_many(&writes)
+        })?,
+    )?;
+
+    gg.set(
+        "getValues",
+        lua.create_function(|lua, items: Table| {
+            let mut list = Vec::new();
+            for item in items.sequence_values::<Table>() {
+                let item = item?;
+                list.push((item.get::<_, u64>("address")?, type_from_flags(item.get("flags")?)?));
+            }
+            values_table(lua, &list)
+        })?,
+    )?;
+
+    gg.set(
+        "setValues",
+        lua.create_function(|_, items: Table| {
+            let mut encoded = Vec::new();
+            for item in items.sequence_values::<Table>() {
+                let item = item?;
+                let typ = type_from_flags(item.get("flags")?)?;
+                encoded.push((item.get::<_, u64>("address")?, encode_value(typ, &item.get::<_, Value>("value")?)?));
+            }
+            let writes: Vec<(u64, &[u8])> = encoded.iter().map(|(address, bytes)| (*address, &bytes[..])).collect();
+            Ok(write_many(&writes)? == writes.len())
+        })?,
+    )?;
+
+    gg.set(
+        "getRangesList",
+        lua.create_function(|lua, filter: Option<String>| {
+            let filter = filter.unwrap_or_default();
+            let list = lua.create_table()?;
+            for (region, typ) in regions(u64::MAX)? {
+                if !filter.is_empty() && !region.name.contains(&filter) {
+                    continue;
+                }
+                let item = lua.create_table()?;
+                item.set("start", region.start)?;
+                item.set("end", region.end)?;
+                item.set("type", perms(region.flags))?;
+                item.set("state", region_state(typ))?;
+                item.set("name", region.name.clone())?;
+                item.set("internalName", region.name)?;
+                list.push(item)?;
+            }
+            Ok(list)
+        })?,
+    )?;
+
+    gg.set(
+        "processPause",
+        lua.create_function(|_, ()| {
+            let pid = DRIVER_MANAGER.read().map(|manager| manager.get_bound_pid()).unwrap_or(0);
+            Ok(pid > 0 && pause_process(pid).is_ok())
+        })?,
+    )?;
+    gg.set("processResume", lua.create_function(|_, ()| Ok(resume_process().unwrap_or(false)))?)?;
+    gg.set("isProcessPaused", lua.create_function(|_, ()| Ok(paused_pid().is_some()))?)?;
+
+    let c = context.clone();
+    gg.set("sleep", lua.create_function(move |_, ms: u64| sleep(&c, Duration::from_millis(ms)))?)?;
+    for name in ["toast", "alert"] {
+        gg.set(
+            name,
+            lua.create_function(|lua, args: Variadic<Value>| {
+                emit(&join_args(lua, args)?);
+                Ok(())
+            })?,
+        )?;
+    }
+
+    lua.globals().set("gg", gg)?;
+    Ok(())
+}
+
+fn check_sign(sign: Option<i64>) -> mlua::Result<()> {
+    match sign {
+        None | Some(SIGN_EQUAL) => Ok(()),
+        Some(sign) => Err(mlua::Error::runtime(format!("Unsupported search sign: {}", sign))),
+    }
+}
+
+/// 读取地址当前的值，返回 GG 格式的 `{address, flags, value}` 列表，读取失败的值为 nil
+fn values_table<'lua>(lua: &'lua Lua, items: &[(u64, ValueType)]) -> mlua::Result<Table<'lua>> {
+    let requests: Vec<(u64, usize)> = items.iter().map(|(address, typ)| (*address, typ.size())).collect();
+    let values = read_many(&requests)?;
+    let list = lua.create_table_with_capacity(items.len(), 0)?;
+    for ((address, typ), value) in items.iter().zip(values) {
+        let item = lua.create_table()?;
+        item.set("address", *address)?;
+        item.set("flags", type_to_flags(*typ))?;
+        item.set("value", value.map(|bytes| decode_value(*typ, &bytes)))?;
+        list.push(item)?;
+    }
+    Ok(list)
+}
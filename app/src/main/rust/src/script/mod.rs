@@ -0,0 +1,200 @@
+//! Lua 脚本引擎
+//!
+//! 在原生层内嵌 Lua 5.4（mlua），脚本通过全局表 `mamu` 读写内存、搜索、查询区域、
+//! 解析指针链和冻结，接口见 [`api`]；全局表 `gg` 提供 GameGuardian 脚本的兼容接口，见 [`gg`]。
+//!
+//! 同一时间只运行一个脚本，在独立线程中执行并登记为 [`JobKind::Script`] 任务。
+//! 停止请求由指令计数钩子和 `mamu.sleep` / 搜索等待检查，脚本在下一个检查点抛错退出。
+//! `print` 的输出逐行交给 [`ScriptOutput`]，没有设置时写入日志。
+//!
+//! 脚本只加载 table / string / math / utf8 / coroutine 标准库，不能访问文件、执行命令或加载其他代码文件。
+
+pub mod api;
+pub mod gg;
+
+use crate::core::globals::{clear_mutex_poison, clear_rwlock_poison, JOB_REGISTRY};
+use crate::core::jobs::{JobKind, JobStatus};
+use anyhow::{Result, anyhow};
+use log::{error, info};
+use mlua::{HookTriggers, Lua, LuaOptions, StdLib, Value};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 每执行这么多条指令检查一次停止请求
+const STOP_CHECK_INSTRUCTIONS: u32 = 10_000;
+
+/// 脚本输出接收者，在脚本线程中调用
+pub trait ScriptOutput: Send + Sync {
+    /// `print` 输出的一行
+    fn on_print(&self, line: &str);
+
+    /// 脚本结束，`error` 为出错信息，正常结束或被停止时为 None
+    fn on_finished(&self, error: Option<&str>, stopped: bool);
+}
+
+static OUTPUT: RwLock<Option<Arc<dyn ScriptOutput>>> = RwLock::new(None);
+
+/// 正在运行的脚本
+static CURRENT: Mutex<Option<Arc<ScriptContext>>> = Mutex::new(None);
+
+/// 一次脚本运行的状态
+pub struct ScriptContext {
+    name: String,
+    stop: AtomicBool,
+    job: AtomicI32,
+}
+
+impl ScriptContext {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            stop: AtomicBool::new(false),
+            job: AtomicI32::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Acquire)
+    }
+
+    /// 已请求停止时返回让脚本退出的错误
+    pub fn check_stopped(&self) -> mlua::Result<()> {
+        if self.is_stopped() {
+            Err(mlua::Error::runtime("script stopped"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn request_stop(&self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+/// 设置输出接收者，None 取消
+pub fn set_script_output(output: Option<Arc<dyn ScriptOutput>>) {
+    if let Ok(mut current) = OUTPUT.write() {
+        *current = output;
+    }
+}
+
+fn output() -> Option<Arc<dyn ScriptOutput>> {
+    OUTPUT.read().ok()?.clone()
+}
+
+/// 转发一行输出
+pub(crate) fn emit(line: &str) {
+    match output() {
+        Some(output) => output.on_print(line),
+        None => info!("Script: {}", line),
+    }
+}
+
+pub fn is_running() -> bool {
+    CURRENT.lock().is_ok_and(|current| current.is_some())
+}
+
+/// 清除当前脚本和输出接收者锁的中毒状态，返回中毒的锁数
+pub(crate) fn clear_poisoned_locks() -> usize {
+    clear_mutex_poison(&CURRENT) + clear_rwlock_poison(&OUTPUT)
+}
+
+/// 请求停止当前脚本，没有运行中的脚本时返回 false
+pub fn stop_script() -> bool {
+    match CURRENT.lock().ok().and_then(|current| current.clone()) {
+        Some(context) => {
+            context.request_stop();
+            true
+        },
+        None => false,
+    }
+}
+
+/// 在后台线程中运行脚本，返回任务 id。已有脚本在运行时返回错误
+pub fn run_script(source: String, name: String) -> Result<i32> {
+    let context = {
+        let mut current = CURRENT.lock().map_err(|_| anyhow!("Failed to acquire script lock"))?;
+        if current.is_some() {
+            return Err(anyhow!("A script is already running"));
+        }
+        let context = Arc::new(ScriptContext::new(name));
+        *current = Some(context.clone());
+        context
+    };
+
+    let stop_context = context.clone();
+    let job = JOB_REGISTRY.start(JobKind::Script, move || stop_context.request_stop());
+    context.job.store(job, Ordering::Release);
+
+    let thread_context = context.clone();
+    let spawned = std::thread::Builder::new()
+        .name("mamu-script".to_string())
+        .spawn(move || run_in_thread(thread_context, source));
+    if let Err(e) = spawned {
+        finish(&context, JobStatus::Failed);
+        return Err(e.into());
+    }
+    Ok(job)
+}
+
+fn run_in_thread(context: Arc<ScriptContext>, source: String) {
+    info!("Script: 开始运行 {}", context.name);
+    let result = execute(&context, &source);
+    let stopped = context.is_stopped();
+
+    let status = match &result {
+        Ok(()) => JobStatus::Completed,
+        Err(_) if stopped => JobStatus::Cancelled,
+        Err(e) => {
+            error!("Script: {} 出错: {}", context.name, e);
+            JobStatus::Failed
+        },
+    };
+    finish(&context, status);
+
+    let message = match &result {
+        Err(e) if !stopped => Some(e.to_string()),
+        _ => None,
+    };
+    match output() {
+        Some(output) => output.on_finished(message.as_deref(), stopped),
+        None => info!("Script: {} 结束", context.name),
+    }
+}
+
+fn execute(context: &Arc<ScriptContext>, source: &str) -> mlua::Result<()> {
+    let lua = Lua::new_with(StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8 | StdLib::COROUTINE, LuaOptions::default())?;
+    // 基础库中能读取文件的函数
+    let globals = lua.globals();
+    for name in ["require", "dofile", "loadfile"] {
+        globals.set(name, Value::Nil)?;
+    }
+    api::install(&lua, context.clone())?;
+    gg::install(&lua, context.clone())?;
+
+    let hook_context = context.clone();
+    lua.set_hook(HookTriggers::new().every_nth_instruction(STOP_CHECK_INSTRUCTIONS), move |_lua, _debug| {
+        hook_context.check_stopped()
+    });
+
+    lua.load(source).set_name(context.name.as_str()).exec()
+}
+
+/// 结束任务并清除当前脚本
+///
+/// 先清除当前脚本再结束任务，等待任务结束的一方可以立即运行下一个脚本
+fn finish(context: &Arc<ScriptContext>, status: JobStatus) {
+    if let Ok(mut current) = CURRENT.lock()
+        && current.as_ref().is_some_and(|c| Arc::ptr_eq(c, context))
+    {
+        *current = None;
+    }
+    let job = context.job.swap(0, Ordering::AcqRel);
+    if job != 0 {
+        JOB_REGISTRY.finish(job, status);
+    }
+}
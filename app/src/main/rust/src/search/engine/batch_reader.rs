@@ -1,3 +1,4 @@
+use crate::core::globals::OP_QUEUE;
 use crate::core::DRIVER_MANAGER;
 use crate::search::result_manager::FuzzySearchResultItem;
 use anyhow::{anyhow, Result};
@@ -159,6 +160,7 @@ where
             || Vec::new(), // 线程本地累加器
             |mut acc, (batch_idx, batch)| -> Result<Vec<(FuzzySearchResultItem, Vec<u8>)>> {
                 let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+                let _op = OP_QUEUE.bulk();
 
                 // 分配批次缓冲区
                 let mut buffer = vec![0u8; batch.total_size];
@@ -197,6 +199,7 @@ where
                     },
                 }
 
+                drop(_op);
                 drop(driver_manager); // 显式释放读锁
 
                 if batch_idx % PROGRESS_UPDATE_BATCH_SIZE == 0 {
@@ -28,6 +28,14 @@ impl SearchFilter {
         self.enable_address_filter || self.enable_type_filter || !self.type_ids.is_empty()
     }
 
+    /// 结果是否通过过滤（地址范围为闭区间）
+    pub fn matches(&self, address: u64, typ: ValueType) -> bool {
+        if self.enable_address_filter && (address < self.address_start || address > self.address_end) {
+            return false;
+        }
+        !(self.enable_type_filter && !self.type_ids.is_empty() && !self.type_ids.contains(&typ))
+    }
+
     #[inline]
     pub fn clear(&mut self) {
         *self = Self::default();
@@ -1,134 +1,198 @@
 use super::super::result_manager::FuzzySearchResultItem;
 use super::super::types::{FuzzyCondition, ValueType};
 use super::manager::{BPLUS_TREE_ORDER};
-use crate::core::DRIVER_MANAGER;
+use crate::core::globals::OP_QUEUE;
+use crate::core::{DriverManager, DRIVER_MANAGER};
 use crate::wuwa::PageStatusBitmap;
 use anyhow::{anyhow, Result};
 use bplustree::BPlusTreeSet;
 use log::{debug, log_enabled, warn, Level};
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread;
 use crate::search::engine::batch_reader::{cluster_addresses, parallel_batch_read};
+use crate::search::engine::fuzzy_spill::FuzzySpillStore;
 use crate::search::PAGE_SIZE;
+use std::path::Path;
+
+/// 合并通道容量（分块数），扫描快于落盘时扫描线程在此阻塞
+const MERGE_CHANNEL_CAPACITY: usize = 16;
+
+/// 首次扫描的一个分块
+struct ScanChunk {
+    region: usize,
+    start: u64,
+    end: u64,
+}
+
+/// 模糊搜索初始扫描的统计
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FuzzyInitialStats {
+    pub found: usize,
+    /// 所有分块都读取出错的区域数
+    pub failed_regions: usize,
+}
 
 /// 模糊搜索初始扫描
 /// 记录指定内存区域内所有地址的当前值
-/// 使用 BPlusTreeSet 存储结果，保持有序且支持高效删除
+///
+/// 所有区域按 `chunk_size` 切分后由 rayon 线程池并行读取，区域之间、区域内的分块之间都可并行。
+/// 各分块的结果经有界通道交给单独的合并线程写入 `store`，超出内存预算的部分落盘到 `cache_dir`。
 ///
 /// # 参数
 /// * `value_type` - 要搜索的值类型
-/// * `start` - 区域起始地址
-/// * `end` - 区域结束地址
+/// * `regions` - 要扫描的区域 (start, end)
 /// * `chunk_size` - 每次读取的块大小
-/// * `processed_counter` - 已处理计数器（可选）
-/// * `total_found_counter` - 找到总数计数器（可选）
-/// * `check_cancelled` - 取消检查闭包（可选）
+/// * `store` - 结果暂存
+/// * `cache_dir` - 落盘目录
+/// * `check_cancelled` - 取消检查闭包，取消后返回已找到的部分
+/// * `on_region_done` - 某个区域的全部分块完成时回调 (已完成区域数, 当前找到总数)
 ///
 /// # 返回
-/// 返回所有成功读取的地址及其值（有序）
-pub(crate) fn fuzzy_initial_scan<F>(
+/// 返回成功读取的地址数和失败区域数
+pub(crate) fn fuzzy_initial_scan<F, P>(
     value_type: ValueType,
-    start: u64,
-    end: u64,
+    stride: usize,
+    regions: &[(u64, u64)],
     chunk_size: usize,
-    processed_counter: Option<&Arc<AtomicUsize>>,
-    total_found_counter: Option<&Arc<AtomicUsize>>,
-    check_cancelled: Option<&F>,
-) -> Result<BPlusTreeSet<FuzzySearchResultItem>>
+    store: &FuzzySpillStore,
+    cache_dir: &Path,
+    check_cancelled: &F,
+    on_region_done: &P,
+) -> Result<FuzzyInitialStats>
 where
-    F: Fn() -> bool,
+    F: Fn() -> bool + Sync,
+    P: Fn(usize, usize) + Sync,
 {
-    let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+    let guard = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+    let driver_manager: &DriverManager = &guard;
 
     let element_size = value_type.size();
     let page_size = *PAGE_SIZE;
 
-    let mut results = BPlusTreeSet::new(BPLUS_TREE_ORDER);
+    // 切分分块，区域起点页对齐
+    let mut chunks = Vec::new();
+    let mut pending_chunks = Vec::with_capacity(regions.len());
+    for (region, &(start, end)) in regions.iter().enumerate() {
+        let mut current = start & !(page_size as u64 - 1);
+        let mut count = 0usize;
+        while current < end {
+            let chunk_end = (current + chunk_size as u64).min(end);
+            chunks.push(ScanChunk { region, start: current, end: chunk_end });
+            current = chunk_end;
+            count += 1;
+        }
+        pending_chunks.push(AtomicUsize::new(count));
+    }
+    let region_readable: Vec<AtomicBool> = regions.iter().map(|_| AtomicBool::new(false)).collect();
 
-    let mut read_success = 0usize;
-    let mut read_failed = 0usize;
+    let empty_regions = pending_chunks.iter().filter(|c| c.load(Ordering::Relaxed) == 0).count();
+    let completed_regions = AtomicUsize::new(empty_regions);
+    let found = AtomicUsize::new(0);
+    let read_failed = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
 
-    let mut current = start & !(*PAGE_SIZE as u64 - 1); // 页对齐
-    let mut chunk_buffer = vec![0u8; chunk_size];
+    let (tx, rx) = mpsc::sync_channel::<Vec<FuzzySearchResultItem>>(MERGE_CHANNEL_CAPACITY);
 
-    while current < end {
-        // Check cancellation at each chunk
-        if let Some(check_fn) = check_cancelled {
-            if check_fn() {
-                if log_enabled!(Level::Debug) {
-                    debug!("Fuzzy initial scan cancelled, returning {} results", results.len());
-                }
-                return Ok(results);
+    thread::scope(|scope| -> Result<()> {
+        // 合并线程：唯一写入 store 的线程，落盘 IO 不占用扫描线程
+        let merger = scope.spawn(move || -> Result<()> {
+            for items in rx {
+                store.push(items, cache_dir)?;
             }
-        }
+            Ok(())
+        });
+
+        chunks.par_iter().for_each_init(
+            || vec![0u8; chunk_size],
+            |buffer, chunk| {
+                if stopped.load(Ordering::Relaxed) {
+                    return;
+                }
+                if check_cancelled() {
+                    stopped.store(true, Ordering::Relaxed);
+                    return;
+                }
 
-        let chunk_end = (current + chunk_size as u64).min(end);
-        let chunk_len = (chunk_end - current) as usize;
-
-        let mut page_status = PageStatusBitmap::new(chunk_len, current as usize);
-
-        let read_result = driver_manager.read_memory_unified(current, &mut chunk_buffer[..chunk_len], Some(&mut page_status));
-
-        match read_result {
-            Ok(_) => {
-                let success_pages = page_status.success_count();
-                if success_pages > 0 {
-                    read_success += 1;
-
-                    // 使用 rayon 并行处理 buffer，收集到临时 Vec
-                    let chunk_results = scan_buffer_parallel(
-                        &chunk_buffer[..chunk_len],
-                        current,
-                        start,
-                        end,
-                        element_size,
-                        value_type,
-                        page_size,
-                        &page_status,
-                    );
-
-                    // 批量插入到 BPlusTreeSet
-                    for item in chunk_results {
-                        results.insert(item);
-                    }
-                } else {
-                    read_failed += 1;
+                let chunk_len = (chunk.end - chunk.start) as usize;
+                let mut page_status = PageStatusBitmap::new(chunk_len, chunk.start as usize);
+
+                let read_result = {
+                    let _op = OP_QUEUE.bulk();
+                    driver_manager.read_memory_unified(chunk.start, &mut buffer[..chunk_len], Some(&mut page_status))
+                };
+
+                match read_result {
+                    Ok(_) => {
+                        region_readable[chunk.region].store(true, Ordering::Relaxed);
+                        if page_status.success_count() > 0 {
+                            let (start, end) = regions[chunk.region];
+                            let items = scan_buffer_parallel(
+                                &buffer[..chunk_len],
+                                chunk.start,
+                                start,
+                                end,
+                                element_size,
+                                stride,
+                                value_type,
+                                page_size,
+                                &page_status,
+                            );
+
+                            if !items.is_empty() {
+                                found.fetch_add(items.len(), Ordering::Relaxed);
+                                // 合并线程出错退出时发送失败，停止扫描，错误由 join 返回
+                                if tx.send(items).is_err() {
+                                    stopped.store(true, Ordering::Relaxed);
+                                    return;
+                                }
+                            }
+                        } else {
+                            read_failed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    },
+                    Err(error) => {
+                        if log_enabled!(Level::Debug) {
+                            warn!("Failed to read memory at 0x{:X} - 0x{:X}, err: {:?}", chunk.start, chunk.end, error);
+                        }
+                        read_failed.fetch_add(1, Ordering::Relaxed);
+                    },
                 }
-            },
-            Err(error) => {
-                if log_enabled!(Level::Debug) {
-                    warn!("Failed to read memory at 0x{:X} - 0x{:X}, err: {:?}", current, chunk_end, error);
+
+                if pending_chunks[chunk.region].fetch_sub(1, Ordering::AcqRel) == 1 {
+                    let done = completed_regions.fetch_add(1, Ordering::Relaxed) + 1;
+                    on_region_done(done, found.load(Ordering::Relaxed));
                 }
-                read_failed += 1;
             },
-        }
-
-        // 更新计数器
-        if let Some(counter) = processed_counter {
-            counter.fetch_add(chunk_len, Ordering::Relaxed);
-        }
+        );
 
-        current = chunk_end;
-    }
+        // 关闭发送端，合并线程处理完剩余结果后退出
+        drop(tx);
+        merger.join().map_err(|_| anyhow!("Fuzzy merge thread panicked"))?
+    })?;
+
+    let stats = FuzzyInitialStats {
+        found: found.load(Ordering::Relaxed),
+        failed_regions: regions
+            .iter()
+            .zip(&region_readable)
+            .filter(|((start, end), readable)| start < end && !readable.load(Ordering::Relaxed))
+            .count(),
+    };
 
     if log_enabled!(Level::Debug) {
-        let region_size = end - start;
         debug!(
-            "Fuzzy initial scan: size={}MB, reads={} success + {} failed, found={}",
-            region_size / 1024 / 1024,
-            read_success,
-            read_failed,
-            results.len()
+            "Fuzzy initial scan: regions={}, chunks={} ({} unreadable), found={}, failed_regions={}",
+            regions.len(),
+            chunks.len(),
+            read_failed.load(Ordering::Relaxed),
+            stats.found,
+            stats.failed_regions
         );
     }
 
-    // 更新总找到数
-    if let Some(counter) = total_found_counter {
-        counter.store(results.len(), Ordering::Relaxed);
-    }
-
-    Ok(results)
+    Ok(stats)
 }
 
 /// 使用 rayon 并行处理缓冲区，按页分割任务
@@ -140,6 +204,7 @@ fn scan_buffer_parallel(
     region_start: u64,
     region_end: u64,
     element_size: usize,
+    stride: usize,
     value_type: ValueType,
     page_size: usize,
     page_status: &PageStatusBitmap,
@@ -164,7 +229,7 @@ fn scan_buffer_parallel(
     // 使用 rayon 并行处理每个成功的页
     success_pages
         .par_iter()
-        .flat_map(|&page_idx| scan_single_page(buffer, buffer_addr, search_start, search_end, element_size, value_type, page_size, page_idx))
+        .flat_map(|&page_idx| scan_single_page(buffer, buffer_addr, search_start, search_end, element_size, stride, value_type, page_size, page_idx))
         .collect()
 }
 
@@ -176,6 +241,7 @@ fn scan_single_page(
     search_start: u64,
     search_end: u64,
     element_size: usize,
+    stride: usize,
     value_type: ValueType,
     page_size: usize,
     page_idx: usize,
@@ -191,12 +257,12 @@ fn scan_single_page(
         return Vec::new();
     }
 
-    // 对齐到元素边界
-    let rem = effective_start % element_size as u64;
+    // 对齐到步长边界
+    let rem = effective_start % stride as u64;
     let first_addr = if rem == 0 {
         effective_start
     } else {
-        effective_start + element_size as u64 - rem
+        effective_start + stride as u64 - rem
     };
 
     if first_addr >= effective_end {
@@ -204,7 +270,7 @@ fn scan_single_page(
     }
 
     // 预计算元素数量，一次性分配
-    let elements_count = ((effective_end - first_addr) as usize) / element_size;
+    let elements_count = ((effective_end - first_addr) as usize) / stride;
     let mut results = Vec::with_capacity(elements_count);
 
     // 批量处理：直接遍历字节切片，无需逐元素检查页状态
@@ -222,8 +288,8 @@ fn scan_single_page(
         let item = FuzzySearchResultItem::from_bytes(addr, &buffer[offset..offset + element_size], value_type);
         results.push(item);
 
-        offset += element_size;
-        addr += element_size as u64;
+        offset += stride;
+        addr += stride as u64;
     }
 
     results
@@ -0,0 +1,152 @@
+//! 模糊搜索首次扫描的结果暂存
+//!
+//! 首次扫描会记录区域内每个地址的值，几 GB 的匿名内存按 i32 扫描就有上亿条结果，
+//! 全部留在内存里很容易被系统杀掉。各区域扫描线程把每个分块的结果交给 `FuzzySpillStore`，
+//! 内存中的结果超过预算后，后续分块写入 cache 目录下的 `MmapQueue`，
+//! 扫描结束后再按地址顺序交给结果管理器。
+
+use super::super::result_manager::{ExactSearchResultItem, FuzzySearchResultItem};
+use super::super::types::ValueType;
+use crate::pointer_scan::storage::MmapQueue;
+use anyhow::{anyhow, Result};
+use log::debug;
+use rkyv::{Archive, Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 默认内存预算：256MB
+pub const DEFAULT_FUZZY_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+
+/// 落盘的结果记录，精确结果的 `value` 为 0
+#[derive(Archive, Deserialize, Serialize, Debug, Clone, Copy)]
+pub(crate) struct SpillRecord {
+    address: u64,
+    value: [u8; 12],
+    value_type: i32,
+}
+
+impl From<&FuzzySearchResultItem> for SpillRecord {
+    fn from(item: &FuzzySearchResultItem) -> Self {
+        Self {
+            address: item.address,
+            value: item.value,
+            value_type: item.value_type.to_id(),
+        }
+    }
+}
+
+impl From<&ExactSearchResultItem> for SpillRecord {
+    fn from(item: &ExactSearchResultItem) -> Self {
+        Self {
+            address: item.address,
+            value: [0; 12],
+            value_type: item.typ.to_id(),
+        }
+    }
+}
+
+impl ArchivedSpillRecord {
+    /// 排序键：地址和值类型 id
+    pub(crate) fn key(&self) -> (u64, i32) {
+        (self.address.to_native(), self.value_type.to_native())
+    }
+
+    fn value_type(&self) -> Result<ValueType> {
+        let id = self.value_type.to_native();
+        ValueType::from_id(id).ok_or_else(|| anyhow!("Invalid value type in spill record: {}", id))
+    }
+
+    pub(crate) fn to_fuzzy(&self) -> Result<FuzzySearchResultItem> {
+        Ok(FuzzySearchResultItem::new(self.address.to_native(), self.value, self.value_type()?))
+    }
+
+    pub(crate) fn to_exact(&self) -> Result<ExactSearchResultItem> {
+        Ok(ExactSearchResultItem::new(self.address.to_native(), self.value_type()?))
+    }
+}
+
+/// 一个分块的结果，按首地址排序
+enum Segment {
+    Memory(Vec<FuzzySearchResultItem>),
+    /// 在落盘队列中的 [start, start + count)
+    Disk { start: usize, count: usize },
+}
+
+struct SpillState {
+    segments: Vec<(u64, Segment)>,
+    memory_bytes: usize,
+    queue: Option<MmapQueue<SpillRecord>>,
+}
+
+/// 带内存预算的结果暂存，可被多个扫描线程共享
+pub(crate) struct FuzzySpillStore {
+    budget: usize,
+    state: Mutex<SpillState>,
+}
+
+impl FuzzySpillStore {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            state: Mutex::new(SpillState {
+                segments: Vec::new(),
+                memory_bytes: 0,
+                queue: None,
+            }),
+        }
+    }
+
+    /// 添加一个分块的结果，同一分块内须按地址有序
+    pub fn push(&self, items: Vec<FuzzySearchResultItem>, cache_dir: &Path) -> Result<()> {
+        let Some(first) = items.first() else {
+            return Ok(());
+        };
+        let key = first.address;
+        let bytes = items.len() * size_of::<FuzzySearchResultItem>();
+
+        let mut state = self.state.lock().map_err(|_| anyhow!("Failed to acquire FuzzySpillStore lock"))?;
+        if state.memory_bytes + bytes <= self.budget {
+            state.memory_bytes += bytes;
+            state.segments.push((key, Segment::Memory(items)));
+            return Ok(());
+        }
+
+        if state.queue.is_none() {
+            debug!("Fuzzy initial scan exceeded memory budget ({} MB), spilling to disk", self.budget / 1024 / 1024);
+            state.queue = Some(MmapQueue::new_fixed(cache_dir, "fuzzy_spill")?);
+        }
+        let queue = state.queue.as_mut().unwrap();
+        let start = queue.len();
+        for item in &items {
+            queue.push(&SpillRecord::from(item))?;
+        }
+        state.segments.push((key, Segment::Disk { start, count: items.len() }));
+        Ok(())
+    }
+
+    /// 已落盘的结果数
+    pub fn spilled_count(&self) -> usize {
+        self.state.lock().ok().and_then(|state| state.queue.as_ref().map(|queue| queue.len())).unwrap_or(0)
+    }
+
+    /// 按地址顺序逐段取出全部结果，结束后删除落盘文件
+    pub fn drain<F>(self, mut consume: F) -> Result<()>
+    where
+        F: FnMut(Vec<FuzzySearchResultItem>) -> Result<()>,
+    {
+        let mut state = self.state.into_inner().map_err(|_| anyhow!("FuzzySpillStore lock poisoned"))?;
+        state.segments.sort_unstable_by_key(|(key, _)| *key);
+
+        for (_, segment) in state.segments {
+            match segment {
+                Segment::Memory(items) => consume(items)?,
+                Segment::Disk { start, count } => {
+                    let queue = state.queue.as_ref().ok_or_else(|| anyhow!("Spill queue missing"))?;
+                    let items = queue.range(start..start + count).map(|record| record.to_fuzzy()).collect::<Result<Vec<_>>>()?;
+                    consume(items)?;
+                },
+            }
+        }
+        Ok(())
+    }
+}
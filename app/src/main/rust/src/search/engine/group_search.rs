@@ -1,5 +1,6 @@
-use super::super::types::{SearchMode, SearchQuery, SearchValue, ValueType};
+use super::super::types::{FloatCompare, SearchMode, SearchQuery, SearchValue, ValueType};
 use super::manager::{ValuePair, BPLUS_TREE_ORDER};
+use crate::core::globals::OP_QUEUE;
 use crate::core::DRIVER_MANAGER;
 use crate::search::{PAGE_MASK, PAGE_SIZE};
 use crate::wuwa::PageStatusBitmap;
@@ -20,7 +21,7 @@ pub(crate) fn search_region_group(query: &SearchQuery, start: u64, end: u64, per
     let mut read_failed = 0usize;
     let mut matches_checked = 0usize;
 
-    let min_element_size = query.values.iter().map(|v| v.value_type().size()).min().unwrap_or(1);
+    let min_element_size = query.values.iter().map(|v| query.stride(v.value_type())).min().unwrap_or(1);
     let search_range = query.range as usize;
 
     let mut current = start & *PAGE_MASK as u64;
@@ -35,7 +36,10 @@ pub(crate) fn search_region_group(query: &SearchQuery, start: u64, end: u64, per
         let mut page_status = PageStatusBitmap::new(chunk_len, current as usize);
 
         // 读取数据到滑动窗口的后半部分
-        let read_result = driver_manager.read_memory_unified(current, &mut sliding_buffer[per_chunk_size..per_chunk_size + chunk_len], Some(&mut page_status));
+        let read_result = {
+            let _op = OP_QUEUE.bulk();
+            driver_manager.read_memory_unified(current, &mut sliding_buffer[per_chunk_size..per_chunk_size + chunk_len], Some(&mut page_status))
+        };
 
         match read_result {
             Ok(_) => {
@@ -186,7 +190,7 @@ where
     let mut read_failed = 0usize;
     let mut matches_checked = 0usize;
 
-    let min_element_size = query.values.iter().map(|v| v.value_type().size()).min().unwrap_or(1);
+    let min_element_size = query.values.iter().map(|v| query.stride(v.value_type())).min().unwrap_or(1);
     let search_range = query.range as usize;
 
     let mut current = start & *PAGE_MASK as u64;
@@ -205,7 +209,10 @@ where
 
         let mut page_status = PageStatusBitmap::new(chunk_len, current as usize);
 
-        let read_result = driver_manager.read_memory_unified(current, &mut sliding_buffer[per_chunk_size..per_chunk_size + chunk_len], Some(&mut page_status));
+        let read_result = {
+            let _op = OP_QUEUE.bulk();
+            driver_manager.read_memory_unified(current, &mut sliding_buffer[per_chunk_size..per_chunk_size + chunk_len], Some(&mut page_status))
+        };
 
         match read_result {
             Ok(_) => {
@@ -346,7 +353,8 @@ pub(crate) fn search_in_buffer_group(
                 anchor_index = Some(idx);
                 break;
             },
-            SearchValue::FixedFloat { value, value_type } => {
+            // 带容差的浮点值不能按字节匹配
+            SearchValue::FixedFloat { value, value_type, compare: FloatCompare::Exact } => {
                 match value_type {
                     ValueType::Float => {
                         let f32_val = *value as f32;
@@ -409,7 +417,7 @@ pub(crate) fn search_in_buffer_group(
     }
 
     let buffer_page_start = buffer_addr & !(*PAGE_SIZE as u64 - 1);
-    let anchor_alignment = anchor_bytes_len;
+    let anchor_alignment = if query.unaligned { 1 } else { anchor_bytes_len };
 
     // SIMD 快速扫描找到所有 anchor 候选位置
     while pos < buffer.len() {
@@ -613,7 +621,7 @@ pub(crate) fn try_match_ordered(buffer: &[u8], _start_addr: u64, query: &SearchQ
                 break;
             }
 
-            let alignment = target_value.value_type().size();
+            let alignment = query.stride(target_value.value_type());
             current_offset += alignment;
         }
 
@@ -635,7 +643,7 @@ pub(crate) fn try_match_unordered(buffer: &[u8], _start_addr: u64, query: &Searc
         }
 
         let value_size = target_value.value_type().size();
-        let alignment = value_size;
+        let alignment = query.stride(target_value.value_type());
         let mut offset = 0usize;
 
         while offset + value_size <= buffer.len() {
@@ -859,7 +867,7 @@ fn dfs_ordered(
 
     let target_value = &query.values[query_idx];
     let value_size = target_value.value_type().size();
-    let alignment = value_size;
+    let alignment = query.stride(target_value.value_type());
 
     let mut offset = search_offset;
     while offset + value_size <= buffer.len() {
@@ -1002,7 +1010,7 @@ fn dfs_unordered(
 
     let target_value = &query.values[query_idx];
     let value_size = target_value.value_type().size();
-    let alignment = value_size;
+    let alignment = query.stride(target_value.value_type());
 
     let mut offset = search_offset;
     while offset + value_size <= buffer.len() {
@@ -1176,7 +1184,7 @@ fn dfs_ordered_with_cancel<F>(
 
     let target_value = &query.values[query_idx];
     let value_size = target_value.value_type().size();
-    let alignment = value_size;
+    let alignment = query.stride(target_value.value_type());
 
     let mut offset = search_offset;
     let mut iteration_count = 0u64;
@@ -1365,7 +1373,7 @@ fn dfs_unordered_with_cancel<F>(
 
     let target_value = &query.values[query_idx];
     let value_size = target_value.value_type().size();
-    let alignment = value_size;
+    let alignment = query.stride(target_value.value_type());
 
     let mut offset = search_offset;
     let mut iteration_count = 0u64;
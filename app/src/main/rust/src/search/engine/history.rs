@@ -0,0 +1,198 @@
+//! Search session history.
+//!
+//! A snapshot of the result set is taken before each refine step so that a
+//! mistyped refine value can be undone without re-scanning the whole process.
+//! Small snapshots stay in memory, large ones are spilled to an `MmapQueue`
+//! in the cache directory.
+
+use super::super::result_manager::{ExactSearchResultItem, FuzzySearchResultItem, SearchResultMode};
+use super::fuzzy_spill::SpillRecord;
+use crate::pointer_scan::storage::MmapQueue;
+use anyhow::Result;
+use log::{debug, warn};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Snapshots with more items than this are spilled to disk.
+pub const SPILL_THRESHOLD: usize = 256 * 1024;
+
+/// Maximum number of undo steps kept. The oldest step is dropped first.
+pub const MAX_HISTORY_STEPS: usize = 16;
+
+enum SnapshotData {
+    Exact(Vec<ExactSearchResultItem>),
+    Fuzzy(Vec<FuzzySearchResultItem>),
+    Spilled(MmapQueue<SpillRecord>),
+}
+
+/// Result set restored by [`SearchHistory::pop`].
+pub enum RestoredResults {
+    Exact(Vec<ExactSearchResultItem>),
+    Fuzzy(Vec<FuzzySearchResultItem>),
+}
+
+impl RestoredResults {
+    pub fn mode(&self) -> SearchResultMode {
+        match self {
+            RestoredResults::Exact(_) => SearchResultMode::Exact,
+            RestoredResults::Fuzzy(_) => SearchResultMode::Fuzzy,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            RestoredResults::Exact(items) => items.len(),
+            RestoredResults::Fuzzy(items) => items.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+struct HistoryStep {
+    label: String,
+    mode: SearchResultMode,
+    count: usize,
+    data: SnapshotData,
+}
+
+/// Summary of one undo step, oldest first.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    /// Refine step that replaced this result set.
+    pub label: String,
+    pub mode: SearchResultMode,
+    /// Number of results before the step.
+    pub count: usize,
+    pub spilled: bool,
+}
+
+pub struct SearchHistory {
+    steps: VecDeque<HistoryStep>,
+    cache_dir: PathBuf,
+    next_id: u64,
+}
+
+impl SearchHistory {
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            steps: VecDeque::new(),
+            cache_dir,
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Drops all steps and their spill files.
+    pub fn clear(&mut self) {
+        if !self.steps.is_empty() {
+            debug!("Search history cleared ({} steps)", self.steps.len());
+        }
+        self.steps.clear();
+    }
+
+    /// Records the exact result set before a refine step.
+    pub fn push_exact(&mut self, label: String, items: &[ExactSearchResultItem]) {
+        let data = if items.len() > SPILL_THRESHOLD {
+            self.spill(items.iter().map(SpillRecord::from))
+        } else {
+            Ok(SnapshotData::Exact(items.to_vec()))
+        };
+        self.push_step(label, SearchResultMode::Exact, items.len(), data);
+    }
+
+    /// Records the fuzzy result set before a refine step.
+    pub fn push_fuzzy(&mut self, label: String, items: &[FuzzySearchResultItem]) {
+        let data = if items.len() > SPILL_THRESHOLD {
+            self.spill(items.iter().map(SpillRecord::from))
+        } else {
+            Ok(SnapshotData::Fuzzy(items.to_vec()))
+        };
+        self.push_step(label, SearchResultMode::Fuzzy, items.len(), data);
+    }
+
+    fn push_step(&mut self, label: String, mode: SearchResultMode, count: usize, data: Result<SnapshotData>) {
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                // Without a snapshot this step cannot be undone, and neither can anything before it.
+                warn!("Failed to snapshot results for undo, history reset: {:?}", e);
+                self.steps.clear();
+                return;
+            },
+        };
+
+        if self.steps.len() >= MAX_HISTORY_STEPS {
+            self.steps.pop_front();
+        }
+        debug!("Search history: pushed '{}' ({} results, {:?})", label, count, mode);
+        self.steps.push_back(HistoryStep { label, mode, count, data });
+    }
+
+    fn spill(&mut self, records: impl Iterator<Item = SpillRecord>) -> Result<SnapshotData> {
+        let name = format!("search_history_{}", self.next_id);
+        self.next_id += 1;
+
+        let mut queue = MmapQueue::new_fixed(Path::new(&self.cache_dir), &name)?;
+        for record in records {
+            queue.push(&record)?;
+        }
+        Ok(SnapshotData::Spilled(queue))
+    }
+
+    /// Drops the most recent step without restoring it, used when a refine did not change the results.
+    pub fn discard_last(&mut self) {
+        self.steps.pop_back();
+    }
+
+    /// Removes the most recent step and returns the result set it recorded.
+    pub fn pop(&mut self) -> Result<Option<RestoredResults>> {
+        let Some(step) = self.steps.pop_back() else {
+            return Ok(None);
+        };
+
+        let restored = match step.data {
+            SnapshotData::Exact(items) => RestoredResults::Exact(items),
+            SnapshotData::Fuzzy(items) => RestoredResults::Fuzzy(items),
+            SnapshotData::Spilled(queue) => match step.mode {
+                SearchResultMode::Exact => RestoredResults::Exact(queue.iter().map(|record| record.to_exact()).collect::<Result<_>>()?),
+                SearchResultMode::Fuzzy => RestoredResults::Fuzzy(queue.iter().map(|record| record.to_fuzzy()).collect::<Result<_>>()?),
+            },
+        };
+
+        debug!("Search history: undo '{}' -> {} results", step.label, restored.len());
+        Ok(Some(restored))
+    }
+
+    /// Backing files of spilled snapshots.
+    pub fn spill_files(&self) -> Vec<PathBuf> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step.data {
+                SnapshotData::Spilled(ref queue) => Some(queue.file_path().clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.steps
+            .iter()
+            .map(|step| HistoryEntry {
+                label: step.label.clone(),
+                mode: step.mode,
+                count: step.count,
+                spilled: matches!(step.data, SnapshotData::Spilled(_)),
+            })
+            .collect()
+    }
+}
@@ -1,11 +1,19 @@
 use super::super::result_manager::{FuzzySearchResultItem, SearchResultManager, SearchResultMode};
 use super::super::types::{FuzzyCondition, SearchQuery, ValueType};
 use super::super::SearchResultItem;
+use super::batch_reader::{cluster_addresses, parallel_batch_read};
 use super::filter::SearchFilter;
+use super::history::{HistoryEntry, RestoredResults, SearchHistory};
+use super::report::{RunOutcome, RunReport};
 use super::fuzzy_search;
+use super::fuzzy_spill::{FuzzySpillStore, DEFAULT_FUZZY_MEMORY_BUDGET};
 use super::group_search;
+use super::nearby_search::{self, MAX_NEARBY_DISTANCE};
+use super::obfuscation::{self, ALL_TRANSFORMS};
 use super::shared_buffer::{SearchErrorCode, SearchStatus, SharedBuffer};
 use super::single_search;
+use super::{PAGE_MASK, PAGE_SIZE};
+use crate::core::background;
 use crate::core::globals::TOKIO_RUNTIME;
 use crate::core::DRIVER_MANAGER;
 use anyhow::{anyhow, Result};
@@ -14,7 +22,8 @@ use lazy_static::lazy_static;
 use log::{debug, error, info, log_enabled, warn, Level};
 use rayon::prelude::*;
 use std::cmp::Ordering as CmpOrdering;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
@@ -55,14 +64,73 @@ impl From<(u64, ValueType)> for ValuePair {
 /// B+ tree order for search results. Large value to avoid splits.
 pub const BPLUS_TREE_ORDER: u16 = 256;
 
+/// What a refine step tests on each existing result.
+enum RefineOp {
+    /// Value or group query
+    Query(SearchQuery),
+    /// Single value within the given distance, see [`nearby_search`]
+    Nearby(SearchQuery, u64),
+    /// Value under a set of transforms, see [`obfuscation`]
+    Obfuscated { value: i32, transforms: u32 },
+    /// Value changed (or unchanged) since the named snapshot was taken
+    Snapshot { name: String, changed: bool },
+}
+
+impl RefineOp {
+    /// Label of the undo snapshot taken before the step
+    fn label(&self) -> String {
+        match self {
+            RefineOp::Query(query) => format!("refine: {} values, {:?}", query.values.len(), query.mode),
+            RefineOp::Nearby(_, distance) => format!("nearby: ±{} bytes", distance),
+            RefineOp::Obfuscated { value, .. } => format!("obfuscated: {}", value),
+            RefineOp::Snapshot { name, changed: true } => format!("snapshot: changed since '{}'", name),
+            RefineOp::Snapshot { name, changed: false } => format!("snapshot: same as '{}'", name),
+        }
+    }
+
+    fn report(&self) -> RunReport {
+        let mut report = match self {
+            RefineOp::Query(_) => RunReport::new("refine"),
+            RefineOp::Nearby(_, distance) => {
+                let mut report = RunReport::new("nearby");
+                report.param("distance", distance);
+                report
+            },
+            RefineOp::Obfuscated { value, transforms } => {
+                let mut report = RunReport::new("obfuscated");
+                report.param("value", value).param("transforms", format!("{:#x}", transforms));
+                report
+            },
+            RefineOp::Snapshot { name, changed } => {
+                let mut report = RunReport::new("snapshot");
+                report.param("snapshot", name).param("changed", changed);
+                report
+            },
+        };
+        if let RefineOp::Query(query) | RefineOp::Nearby(query, _) = self {
+            report.param("values", query.values.len()).param("mode", format!("{:?}", query.mode));
+        }
+        report
+    }
+}
+
 /// Legacy callback interface for search progress. Kept for backward compatibility.
 pub trait SearchProgressCallback: Send + Sync {
     fn on_search_complete(&self, total_found: usize, total_regions: usize, elapsed_millis: u64);
 }
 
 /// Search engine manager with async support.
+/// Results, undo history and obfuscation masks of a process that is not the current one.
+pub struct ParkedSearch {
+    result_manager: Option<SearchResultManager>,
+    history: SearchHistory,
+    obfuscation_masks: HashMap<u64, u32>,
+}
+
 pub struct SearchEngineManager {
     result_manager: Option<SearchResultManager>,
+    /// In-memory result buffer size passed to `init`, reused for the results of other processes
+    memory_buffer_size: usize,
     chunk_size: usize,
     filter: SearchFilter,
     shared_buffer: SharedBuffer,
@@ -70,18 +138,40 @@ pub struct SearchEngineManager {
     search_handle: Option<JoinHandle<()>>,
     /// 兼容模式：所有搜索结果都以模糊搜索格式存储，支持精确搜索和模糊搜索互相切换
     compatibility_mode: bool,
+    /// Scan every byte offset instead of stepping by the value alignment
+    unaligned_search: bool,
+    /// Cache directory, also where run reports are written
+    cache_dir: PathBuf,
+    /// Report of the last finished async operation
+    last_report_path: Option<PathBuf>,
+    /// Snapshots taken before each refine step, for undo
+    history: SearchHistory,
+    /// In-memory budget of fuzzy initial scan results in bytes, the rest is spilled to disk
+    fuzzy_memory_budget: usize,
+    /// Transforms still matching each address after obfuscated refines
+    obfuscation_masks: HashMap<u64, u32>,
+    /// Run searches and refines at background priority, see [`crate::core::background`]
+    background: bool,
 }
 
 impl SearchEngineManager {
     pub fn new() -> Self {
         Self {
             result_manager: None,
+            memory_buffer_size: 0,
             chunk_size: 512 * 1024,
             filter: SearchFilter::new(),
             shared_buffer: SharedBuffer::new(),
             cancel_token: None,
             search_handle: None,
             compatibility_mode: false,
+            unaligned_search: false,
+            cache_dir: PathBuf::new(),
+            last_report_path: None,
+            history: SearchHistory::new(PathBuf::new()),
+            fuzzy_memory_budget: DEFAULT_FUZZY_MEMORY_BUDGET,
+            obfuscation_masks: HashMap::new(),
+            background: false,
         }
     }
 
@@ -97,6 +187,40 @@ impl SearchEngineManager {
         self.compatibility_mode
     }
 
+    /// Set unaligned search
+    /// When enabled, new exact and fuzzy searches check every byte offset, which finds values
+    /// inside packed structures but checks `alignment` times as many positions.
+    pub fn set_unaligned_search(&mut self, enabled: bool) {
+        self.unaligned_search = enabled;
+    }
+
+    /// Get unaligned search
+    pub fn get_unaligned_search(&self) -> bool {
+        self.unaligned_search
+    }
+
+    /// Set background mode
+    /// When enabled, subsequent searches and refines run on low-priority workers with
+    /// throttled reads, so a long scan does not cost the game frames.
+    pub fn set_background_mode(&mut self, enabled: bool) {
+        self.background = enabled;
+    }
+
+    /// Get background mode
+    pub fn get_background_mode(&self) -> bool {
+        self.background
+    }
+
+    /// Sets the in-memory budget of fuzzy initial scan results.
+    /// Results beyond it are spilled to the cache dir while the scan runs.
+    pub fn set_fuzzy_memory_budget(&mut self, budget: usize) {
+        self.fuzzy_memory_budget = budget;
+    }
+
+    pub fn get_fuzzy_memory_budget(&self) -> usize {
+        self.fuzzy_memory_budget
+    }
+
     /// Sets the shared buffer for progress communication.
     pub fn set_shared_buffer(&mut self, ptr: *mut u8, len: usize) -> bool {
         self.shared_buffer.set(ptr, len)
@@ -129,7 +253,10 @@ impl SearchEngineManager {
         }
 
         let cache_path = PathBuf::from(cache_dir);
+        self.cache_dir = cache_path.clone();
+        self.history = SearchHistory::new(cache_path.clone());
         self.result_manager = Some(SearchResultManager::new(memory_buffer_size, cache_path));
+        self.memory_buffer_size = memory_buffer_size;
         self.chunk_size = if chunk_size == 0 { 512 * 1024 } else { chunk_size };
 
         Ok(())
@@ -139,6 +266,45 @@ impl SearchEngineManager {
         self.result_manager.is_some()
     }
 
+    /// Path of the report written by the last finished async operation.
+    pub fn last_report_path(&self) -> Option<&PathBuf> {
+        self.last_report_path.as_ref()
+    }
+
+    /// Write `report` to the cache dir and remember its path.
+    /// Must be called before the final status is published so Kotlin sees the new path.
+    fn publish_report(report: &RunReport, cache_dir: &Path) {
+        match report.write(cache_dir) {
+            Ok(path) => {
+                if let Ok(mut manager) = SEARCH_ENGINE_MANAGER.write() {
+                    manager.last_report_path = Some(path);
+                }
+            },
+            Err(e) => warn!("Failed to write run report: {:?}", e),
+        }
+    }
+
+    /// Finish and publish the report of an operation that was not cancelled.
+    fn publish_final_report(mut report: RunReport, success: bool, cache_dir: &Path) {
+        if success {
+            let final_count = SEARCH_ENGINE_MANAGER
+                .read()
+                .ok()
+                .and_then(|manager| manager.get_total_count().ok())
+                .unwrap_or(0);
+            report.finish(RunOutcome::Completed, final_count);
+        } else {
+            report.warn("Failed to store results").finish(RunOutcome::Error, 0);
+        }
+        Self::publish_report(&report, cache_dir);
+    }
+
+    /// Publish the report of a cancelled operation.
+    fn publish_cancelled_report(mut report: RunReport, found: usize, cache_dir: &Path) {
+        report.finish(RunOutcome::Cancelled, found);
+        Self::publish_report(&report, cache_dir);
+    }
+
     /// Starts an async memory search. Returns immediately.
     /// Progress and status are communicated via the shared buffer.
     ///
@@ -157,6 +323,9 @@ impl SearchEngineManager {
             return Err(anyhow!("Search already in progress"));
         }
 
+        self.history.clear();
+        self.obfuscation_masks.clear();
+
         // Prepare result manager.
         let result_mgr = self
             .result_manager
@@ -198,10 +367,13 @@ impl SearchEngineManager {
 
         let chunk_size = self.chunk_size;
         let compatibility_mode = self.compatibility_mode;
+        let background = self.background;
+        let cache_dir = self.cache_dir.clone();
+        let query = query.with_unaligned(self.unaligned_search);
 
         // Spawn async search task.
         let handle = TOKIO_RUNTIME.spawn(async move {
-            Self::run_search_task(query, regions, use_deep_search, chunk_size, compatibility_mode, cancel_token).await;
+            Self::run_search_task(query, regions, use_deep_search, chunk_size, compatibility_mode, background, cache_dir, cancel_token).await;
         });
 
         self.search_handle = Some(handle);
@@ -209,18 +381,39 @@ impl SearchEngineManager {
     }
 
     /// Internal async search task that runs in tokio runtime.
+    #[allow(clippy::too_many_arguments)]
     async fn run_search_task(
         query: SearchQuery,
         regions: Vec<(u64, u64)>,
         use_deep_search: bool,
         chunk_size: usize,
         compatibility_mode: bool,
+        background: bool,
+        cache_dir: PathBuf,
         cancel_token: CancellationToken,
     ) {
         let start_time = Instant::now();
         let total_regions = regions.len();
         let is_group_search = query.values.len() > 1;
 
+        let mut report = RunReport::new("exact_search");
+        report
+            .param("values", query.values.len())
+            .param("mode", format!("{:?}", query.mode))
+            .param("range", query.range)
+            .param("chunk_size", chunk_size)
+            .param("deep_search", use_deep_search)
+            .param("compatibility_mode", compatibility_mode)
+            .param("background", background)
+            .param("unaligned", query.unaligned)
+            .regions(&regions);
+        if query.unaligned {
+            let cost = query.values.iter().map(|v| v.value_type().alignment()).max().unwrap_or(1);
+            if cost > 1 {
+                report.warn(format!("Unaligned scan checks up to {}x the positions of an aligned scan", cost));
+            }
+        }
+
         if log_enabled!(Level::Debug) {
             debug!(
                 "Starting async search: {} values, mode={:?}, range={}, regions={}, chunk_size={} KB, deep_search={}, compat_mode={}",
@@ -237,16 +430,18 @@ impl SearchEngineManager {
         // Shared state for progress tracking.
         let completed_regions = Arc::new(AtomicUsize::new(0));
         let total_found_count = Arc::new(AtomicI64::new(0));
+        let failed_regions = Arc::new(AtomicUsize::new(0));
         let cancelled = Arc::new(AtomicBool::new(false));
 
         // Clone for the blocking task.
         let completed_regions_clone = Arc::clone(&completed_regions);
         let total_found_clone = Arc::clone(&total_found_count);
+        let failed_regions_clone = Arc::clone(&failed_regions);
         let cancelled_clone = Arc::clone(&cancelled);
         let cancel_token_clone = cancel_token.clone();
 
         // Run the CPU-intensive search in a blocking task with rayon.
-        let search_result = tokio::task::spawn_blocking(move || {
+        let search_result = tokio::task::spawn_blocking(move || background::install(background, move || {
             let mut all_results: Vec<_> = regions
                 .par_iter()
                 .enumerate()
@@ -293,13 +488,14 @@ impl SearchEngineManager {
                             group_search::search_region_group(&query, *start, *end, chunk_size)
                         }
                     } else {
-                        single_search::search_region_single(&query.values[0], *start, *end, chunk_size)
+                        single_search::search_region_single(&query.values[0], *start, *end, chunk_size, query.stride(query.values[0].value_type()))
                     };
 
                     let region_results = match result {
                         Ok(results) => results,
                         Err(e) => {
                             error!("Failed to search region {}: {:?}", idx, e);
+                            failed_regions_clone.fetch_add(1, AtomicOrdering::Relaxed);
                             Vec::new()
                         },
                     };
@@ -336,11 +532,15 @@ impl SearchEngineManager {
             }
 
             all_results
-        })
+        }))
         .await;
 
+        report.mark_phase("scan").failed_regions(failed_regions.load(AtomicOrdering::Relaxed));
+
         // Check if cancelled.
         if cancel_token.is_cancelled() || cancelled.load(AtomicOrdering::Relaxed) {
+            Self::publish_cancelled_report(report, total_found_count.load(AtomicOrdering::Relaxed) as usize, &cache_dir);
+
             // Update shared buffer via the global manager.
             if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
                 manager.shared_buffer.write_status(SearchStatus::Cancelled);
@@ -423,6 +623,9 @@ impl SearchEngineManager {
             },
         };
 
+        report.mark_phase("store");
+        Self::publish_final_report(report, success, &cache_dir);
+
         // Now set status AFTER the write lock is released.
         // This ensures Kotlin can immediately acquire read lock when it sees COMPLETED.
         if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
@@ -435,9 +638,149 @@ impl SearchEngineManager {
         }
     }
 
+    /// Memory ranges made of the pages that hold at least one current result, sorted and merged.
+    /// A value crossing a page boundary keeps both pages.
+    pub fn result_page_ranges(&self) -> Result<Vec<(u64, u64)>> {
+        let result_mgr = self.result_manager.as_ref().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+
+        let mut items: Vec<(u64, usize)> = match result_mgr.get_mode() {
+            SearchResultMode::Exact => result_mgr
+                .get_all_exact_results()?
+                .into_iter()
+                .map(|result| (result.address, result.typ.size()))
+                .collect(),
+            SearchResultMode::Fuzzy => result_mgr
+                .get_all_fuzzy_results()?
+                .into_iter()
+                .map(|fuzzy| (fuzzy.address, fuzzy.value_size()))
+                .collect(),
+        };
+        items.sort_unstable();
+
+        let page_mask = *PAGE_MASK as u64;
+        let page_size = *PAGE_SIZE as u64;
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for (addr, size) in items {
+            let start = addr & page_mask;
+            let end = (addr.saturating_add(size.max(1) as u64 - 1) & page_mask).saturating_add(page_size);
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => ranges.push((start, end)),
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Starts an async search over the pages of the current results only. Returns immediately.
+    /// Much faster than a full search for second-stage searches (e.g. a group search next to a found value).
+    pub fn start_search_in_result_pages_async(&mut self, query: SearchQuery, use_deep_search: bool) -> Result<()> {
+        let regions = self.result_page_ranges()?;
+        if regions.is_empty() {
+            return Err(anyhow!("No previous results to search in"));
+        }
+        info!("Searching {} page ranges of the previous results", regions.len());
+        self.start_search_async(query, regions, use_deep_search, false)
+    }
+
+    /// Starts an async fuzzy initial search over the pages of the current results only. Returns immediately.
+    pub fn start_fuzzy_search_in_result_pages_async(&mut self, value_type: ValueType) -> Result<()> {
+        let regions = self.result_page_ranges()?;
+        if regions.is_empty() {
+            return Err(anyhow!("No previous results to search in"));
+        }
+        info!("Fuzzy searching {} page ranges of the previous results", regions.len());
+        self.start_fuzzy_search_async(value_type, regions, false)
+    }
+
     /// Starts async refine search. Returns immediately.
     /// Supports both Exact and Fuzzy modes. When in Fuzzy mode, results will be converted back to Fuzzy after refinement.
     pub fn start_refine_async(&mut self, query: SearchQuery) -> Result<()> {
+        self.start_refine_inner(RefineOp::Query(query))
+    }
+
+    /// Starts async near-by search. Returns immediately.
+    /// Keeps only the results that have a value matching `query` within `distance` bytes before or after them.
+    /// `query` must hold a single value. Undo restores the results from before the search, like a refine.
+    pub fn start_nearby_refine_async(&mut self, query: SearchQuery, distance: u64) -> Result<()> {
+        if query.values.len() != 1 {
+            return Err(anyhow!("Near-by search needs a single value, got {}", query.values.len()));
+        }
+        if distance == 0 || distance > MAX_NEARBY_DISTANCE {
+            return Err(anyhow!("Near-by distance must be in 1..={}", MAX_NEARBY_DISTANCE));
+        }
+        self.start_refine_inner(RefineOp::Nearby(query, distance))
+    }
+
+    /// Starts async obfuscated value search. Returns immediately.
+    /// Keeps only the results that decode to `value` under one of `transforms` (`obfuscation::*` bits),
+    /// addresses left by a previous obfuscated search only test the transforms that matched then.
+    pub fn start_obfuscated_refine_async(&mut self, value: i32, transforms: u32) -> Result<()> {
+        if transforms & ALL_TRANSFORMS == 0 {
+            return Err(anyhow!("No valid transform in {:#x}", transforms));
+        }
+        self.start_refine_inner(RefineOp::Obfuscated { value, transforms })
+    }
+
+    /// Transforms still matching `address` after obfuscated searches, 0 when none was run on it.
+    pub fn obfuscation_mask(&self, address: u64) -> u32 {
+        self.obfuscation_masks.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Takes a named snapshot of the current values of all results, replacing a snapshot with the same name.
+    /// Reads every result once on the calling thread. For a snapshot of whole regions, run a fuzzy initial search first.
+    /// Returns the number of values recorded, results that could not be read are left out.
+    pub fn take_snapshot(&mut self, name: &str) -> Result<usize> {
+        if self.is_searching() {
+            return Err(anyhow!("Search already in progress"));
+        }
+
+        let result_mgr = self.result_manager.as_mut().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+
+        let mut items = match result_mgr.get_mode() {
+            SearchResultMode::Exact => result_mgr
+                .get_all_exact_results()?
+                .into_iter()
+                .map(|result| FuzzySearchResultItem::new(result.address, [0; 12], result.typ))
+                .collect(),
+            SearchResultMode::Fuzzy => result_mgr.get_all_fuzzy_results()?,
+        };
+        items.sort_unstable_by_key(|item| item.address);
+
+        let batches = cluster_addresses(&items);
+        let values = parallel_batch_read(&batches, &items, None, None, &|_, _| {}, None::<&fn() -> bool>)?;
+        let snapshot: Vec<_> = values
+            .into_iter()
+            .map(|(item, value)| FuzzySearchResultItem::from_bytes(item.address, &value, item.value_type))
+            .collect();
+
+        result_mgr.save_snapshot(name, snapshot)
+    }
+
+    /// Deletes a named snapshot. Returns false if there was none.
+    pub fn remove_snapshot(&mut self, name: &str) -> Result<bool> {
+        let result_mgr = self.result_manager.as_mut().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+        Ok(result_mgr.remove_snapshot(name))
+    }
+
+    /// Names and value counts of all snapshots, sorted by name.
+    pub fn snapshot_names(&self) -> Result<Vec<(String, usize)>> {
+        let result_mgr = self.result_manager.as_ref().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+        Ok(result_mgr.snapshot_names())
+    }
+
+    /// Starts async snapshot-compare refine. Returns immediately.
+    /// Keeps the results whose value changed since snapshot `name` was taken (`changed`), or is still the same.
+    /// Results missing from the snapshot are dropped. Undo restores the results from before the step.
+    pub fn start_snapshot_refine_async(&mut self, name: String, changed: bool) -> Result<()> {
+        let result_mgr = self.result_manager.as_ref().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+        if result_mgr.snapshot(&name).is_none() {
+            return Err(anyhow!("No snapshot named '{}'", name));
+        }
+        self.start_refine_inner(RefineOp::Snapshot { name, changed })
+    }
+
+    /// Shared setup of every refine step.
+    fn start_refine_inner(&mut self, op: RefineOp) -> Result<()> {
         if !self.is_initialized() {
             self.shared_buffer.write_status(SearchStatus::Error);
             self.shared_buffer.write_error_code(SearchErrorCode::NotInitialized);
@@ -453,23 +796,29 @@ impl SearchEngineManager {
         let result_mgr = self.result_manager.as_ref().unwrap();
         let original_mode = result_mgr.get_mode();
 
+        let label = op.label();
+
         let current_results: Vec<ValuePair> = match original_mode {
-            SearchResultMode::Exact => result_mgr
-                .get_all_exact_results()?
-                .into_iter()
-                .map(|result| ValuePair::new(result.address, result.typ))
-                .collect(),
-            SearchResultMode::Fuzzy => result_mgr
-                .get_all_fuzzy_results()?
-                .into_iter()
-                .map(|fuzzy| ValuePair::new(fuzzy.address, fuzzy.value_type))
-                .collect(),
+            SearchResultMode::Exact => {
+                let items = result_mgr.get_all_exact_results()?;
+                if !items.is_empty() {
+                    self.history.push_exact(label, &items);
+                }
+                items.into_iter().map(|result| ValuePair::new(result.address, result.typ)).collect()
+            },
+            SearchResultMode::Fuzzy => {
+                let items = result_mgr.get_all_fuzzy_results()?;
+                if !items.is_empty() {
+                    self.history.push_fuzzy(label, &items);
+                }
+                items.into_iter().map(|fuzzy| ValuePair::new(fuzzy.address, fuzzy.value_type)).collect()
+            },
         };
 
         if current_results.is_empty() {
             warn!("No results to refine");
-            self.shared_buffer.write_status(SearchStatus::Completed);
             self.shared_buffer.write_found_count(0);
+            self.shared_buffer.write_status(SearchStatus::Completed);
             return Ok(());
         }
 
@@ -481,8 +830,11 @@ impl SearchEngineManager {
         let cancel_token = CancellationToken::new();
         self.cancel_token = Some(cancel_token.clone());
 
+        let cache_dir = self.cache_dir.clone();
+        let background = self.background;
+
         let handle = TOKIO_RUNTIME.spawn(async move {
-            Self::run_refine_task(query, current_results, original_mode, cancel_token).await;
+            Self::run_refine_task(op, current_results, original_mode, background, cache_dir, cancel_token).await;
         });
 
         self.search_handle = Some(handle);
@@ -490,16 +842,22 @@ impl SearchEngineManager {
     }
 
     /// Internal async refine task.
-    async fn run_refine_task(query: SearchQuery, current_results: Vec<ValuePair>, original_mode: SearchResultMode, cancel_token: CancellationToken) {
+    async fn run_refine_task(
+        op: RefineOp,
+        current_results: Vec<ValuePair>,
+        original_mode: SearchResultMode,
+        background: bool,
+        cache_dir: PathBuf,
+        cancel_token: CancellationToken,
+    ) {
         let start_time = Instant::now();
         let total_addresses = current_results.len();
 
-        debug!(
-            "Starting async refine search: {} values, mode={:?}, existing results={}",
-            query.values.len(),
-            query.mode,
-            total_addresses
-        );
+        let mut report = op.report();
+        report.param("result_mode", format!("{:?}", original_mode)).param("background", background);
+        report.input_count = Some(total_addresses);
+
+        debug!("Starting async refine search: {}, existing results={}", op.label(), total_addresses);
 
         let processed_counter = Arc::new(AtomicUsize::new(0));
         let total_found_counter = Arc::new(AtomicUsize::new(0));
@@ -510,7 +868,7 @@ impl SearchEngineManager {
         let cancelled_clone = Arc::clone(&cancelled);
         let cancel_token_clone = cancel_token.clone();
 
-        let refine_result = tokio::task::spawn_blocking(move || {
+        let refine_result = tokio::task::spawn_blocking(move || background::install(background, move || {
             // Check cancellation from both CancellationToken and shared buffer.
             let check_cancelled = || -> bool {
                 if cancel_token_clone.is_cancelled() || cancelled_clone.load(AtomicOrdering::Relaxed) {
@@ -526,7 +884,7 @@ impl SearchEngineManager {
             };
 
             if check_cancelled() {
-                return Vec::new();
+                return (Vec::new(), None);
             }
 
             // Progress update callback for refine search.
@@ -538,53 +896,135 @@ impl SearchEngineManager {
                 }
             };
 
-            let refined_results = if query.values.len() == 1 {
-                single_search::refine_single_search_with_cancel(
-                    &current_results,
-                    &query.values[0],
-                    Some(&processed_clone),
-                    Some(&found_clone),
-                    &check_cancelled,
-                    &update_progress,
-                )
-                .unwrap_or_else(|e| {
-                    error!("Refine search failed: {:?}", e);
-                    Vec::new()
-                })
-            } else {
-                match group_search::refine_search_group_with_dfs_and_cancel(
-                    &current_results,
-                    &query,
-                    Some(&processed_clone),
-                    Some(&found_clone),
-                    &check_cancelled,
-                    &update_progress,
-                ) {
-                    Ok(results) => results.into_iter().cloned().collect(),
-                    Err(e) => {
-                        error!("Group refine search failed: {:?}", e);
+            match op {
+                RefineOp::Nearby(query, distance) => {
+                    let results = nearby_search::refine_nearby_with_cancel(
+                        &current_results,
+                        &query.values[0],
+                        distance,
+                        Some(&processed_clone),
+                        Some(&found_clone),
+                        &check_cancelled,
+                        &update_progress,
+                    )
+                    .unwrap_or_else(|e| {
+                        error!("Near-by search failed: {:?}", e);
                         Vec::new()
-                    },
-                }
-            };
-
-            refined_results
-        })
+                    });
+                    (results, None)
+                },
+                RefineOp::Obfuscated { value, transforms } => {
+                    let masks = SEARCH_ENGINE_MANAGER
+                        .read()
+                        .map(|manager| manager.obfuscation_masks.clone())
+                        .unwrap_or_default();
+                    match obfuscation::refine_obfuscated_with_cancel(
+                        &current_results,
+                        &masks,
+                        value,
+                        transforms,
+                        Some(&processed_clone),
+                        Some(&found_clone),
+                        &check_cancelled,
+                        &update_progress,
+                    ) {
+                        Ok(results) => {
+                            let masks = results.iter().map(|(pair, mask)| (pair.addr, *mask)).collect();
+                            (results.into_iter().map(|(pair, _)| pair).collect(), Some(masks))
+                        },
+                        Err(e) => {
+                            error!("Obfuscated search failed: {:?}", e);
+                            (Vec::new(), None)
+                        },
+                    }
+                },
+                RefineOp::Snapshot { name, changed } => {
+                    let old_values = SEARCH_ENGINE_MANAGER
+                        .read()
+                        .map_err(|_| anyhow!("Failed to acquire SearchEngineManager read lock"))
+                        .and_then(|manager| {
+                            let snapshot = manager
+                                .result_manager
+                                .as_ref()
+                                .and_then(|result_mgr| result_mgr.snapshot(&name))
+                                .ok_or_else(|| anyhow!("No snapshot named '{}'", name))?;
+                            snapshot.values_for(&current_results)
+                        });
+                    let condition = if changed { FuzzyCondition::Changed } else { FuzzyCondition::Unchanged };
+                    let results = old_values.and_then(|mut old_values| {
+                        old_values.sort_unstable_by_key(|item| item.address);
+                        fuzzy_search::fuzzy_refine_search(
+                            &old_values,
+                            condition,
+                            Some(&processed_clone),
+                            Some(&found_clone),
+                            &update_progress,
+                            Some(&check_cancelled),
+                        )
+                    });
+                    match results {
+                        Ok(tree) => (tree.iter().map(|item| ValuePair::new(item.address, item.value_type)).collect(), None),
+                        Err(e) => {
+                            error!("Snapshot refine failed: {:?}", e);
+                            (Vec::new(), None)
+                        },
+                    }
+                },
+                RefineOp::Query(query) if query.values.len() == 1 => {
+                    let results = single_search::refine_single_search_with_cancel(
+                        &current_results,
+                        &query.values[0],
+                        Some(&processed_clone),
+                        Some(&found_clone),
+                        &check_cancelled,
+                        &update_progress,
+                    )
+                    .unwrap_or_else(|e| {
+                        error!("Refine search failed: {:?}", e);
+                        Vec::new()
+                    });
+                    (results, None)
+                },
+                RefineOp::Query(query) => {
+                    match group_search::refine_search_group_with_dfs_and_cancel(
+                        &current_results,
+                        &query,
+                        Some(&processed_clone),
+                        Some(&found_clone),
+                        &check_cancelled,
+                        &update_progress,
+                    ) {
+                        Ok(results) => (results.into_iter().cloned().collect(), None),
+                        Err(e) => {
+                            error!("Group refine search failed: {:?}", e);
+                            (Vec::new(), None)
+                        },
+                    }
+                },
+            }
+        }))
         .await;
 
+        report.mark_phase("refine");
+
         if cancel_token.is_cancelled() || cancelled.load(AtomicOrdering::Relaxed) {
+            Self::publish_cancelled_report(report, total_found_counter.load(AtomicOrdering::Relaxed), &cache_dir);
             if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
                 manager.shared_buffer.write_status(SearchStatus::Cancelled);
             }
+            Self::discard_last_snapshot();
             info!("Refine search cancelled");
             return;
         }
 
         // IMPORTANT: Release write lock BEFORE setting status to COMPLETED.
         let success = match refine_result {
-            Ok(refined_results) => {
+            Ok((refined_results, masks)) => {
                 match SEARCH_ENGINE_MANAGER.write() {
                     Ok(mut manager) => {
+                        if let Some(masks) = masks {
+                            manager.obfuscation_masks = masks;
+                        }
                         if let Some(ref mut result_mgr) = manager.result_manager {
                             // Clear and update results.
                             let _ = result_mgr.clear();
@@ -651,6 +1091,9 @@ impl SearchEngineManager {
             },
         };
 
+        report.mark_phase("store");
+        Self::publish_final_report(report, success, &cache_dir);
+
         // Set status AFTER write lock is released.
         if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
             if success {
@@ -679,6 +1122,9 @@ impl SearchEngineManager {
             return Err(anyhow!("Search already in progress"));
         }
 
+        self.history.clear();
+        self.obfuscation_masks.clear();
+
         // Prepare result manager for fuzzy mode.
         let result_mgr = self
             .result_manager
@@ -713,9 +1159,9 @@ impl SearchEngineManager {
 
                 // Since we already have results, just complete immediately
                 self.shared_buffer.reset();
-                self.shared_buffer.write_status(SearchStatus::Completed);
                 self.shared_buffer.write_found_count(result_mgr.total_count() as i64);
                 self.shared_buffer.write_progress(100);
+                self.shared_buffer.write_status(SearchStatus::Completed);
                 return Ok(());
             } else {
                 result_mgr.clear()?;
@@ -735,9 +1181,13 @@ impl SearchEngineManager {
         self.cancel_token = Some(cancel_token.clone());
 
         let chunk_size = self.chunk_size;
+        let cache_dir = self.cache_dir.clone();
+        let memory_budget = self.fuzzy_memory_budget;
+        let stride = if self.unaligned_search { 1 } else { value_type.alignment() };
+        let background = self.background;
 
         let handle = TOKIO_RUNTIME.spawn(async move {
-            Self::run_fuzzy_initial_task(value_type, regions, chunk_size, cancel_token).await;
+            Self::run_fuzzy_initial_task(value_type, stride, regions, chunk_size, memory_budget, background, cache_dir, cancel_token).await;
         });
 
         self.search_handle = Some(handle);
@@ -745,10 +1195,32 @@ impl SearchEngineManager {
     }
 
     /// Internal async fuzzy initial scan task.
-    async fn run_fuzzy_initial_task(value_type: ValueType, regions: Vec<(u64, u64)>, chunk_size: usize, cancel_token: CancellationToken) {
+    #[allow(clippy::too_many_arguments)]
+    async fn run_fuzzy_initial_task(
+        value_type: ValueType,
+        stride: usize,
+        regions: Vec<(u64, u64)>,
+        chunk_size: usize,
+        memory_budget: usize,
+        background: bool,
+        cache_dir: PathBuf,
+        cancel_token: CancellationToken,
+    ) {
         let start_time = Instant::now();
         let total_regions = regions.len();
 
+        let mut report = RunReport::new("fuzzy_initial");
+        report
+            .param("value_type", format!("{:?}", value_type))
+            .param("chunk_size", chunk_size)
+            .param("memory_budget", memory_budget)
+            .param("stride", stride)
+            .param("background", background)
+            .regions(&regions);
+        if stride < value_type.alignment() {
+            report.warn(format!("Unaligned scan checks {}x the positions of an aligned scan", value_type.alignment() / stride));
+        }
+
         if log_enabled!(Level::Debug) {
             debug!(
                 "Starting fuzzy initial scan: value_type={:?}, regions={}, chunk_size={} KB",
@@ -758,79 +1230,50 @@ impl SearchEngineManager {
             );
         }
 
-        let completed_regions = Arc::new(AtomicUsize::new(0));
-        let total_found_count = Arc::new(AtomicI64::new(0));
         let cancelled = Arc::new(AtomicBool::new(false));
 
-        let completed_regions_clone = Arc::clone(&completed_regions);
-        let total_found_clone = Arc::clone(&total_found_count);
         let cancelled_clone = Arc::clone(&cancelled);
         let cancel_token_clone = cancel_token.clone();
+        let spill_dir = cache_dir.clone();
 
-        // Run fuzzy scan in blocking task with rayon.
-        let scan_result = tokio::task::spawn_blocking(move || {
-            let all_results: Vec<BPlusTreeSet<FuzzySearchResultItem>> = regions
-                .par_iter()
-                .enumerate()
-                .filter_map(|(idx, (start, end))| {
-                    // Check cancellation.
-                    if cancel_token_clone.is_cancelled() || cancelled_clone.load(AtomicOrdering::Relaxed) {
-                        cancelled_clone.store(true, AtomicOrdering::Relaxed);
-                        return None;
-                    }
-
-                    if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
-                        if manager.shared_buffer.is_cancel_requested() {
-                            cancelled_clone.store(true, AtomicOrdering::Relaxed);
-                            return None;
-                        }
-                    }
+        // Regions and the chunks within them are scanned on the rayon pool, a merge thread feeds the store.
+        let scan_result = tokio::task::spawn_blocking(move || background::install(background, move || {
+            let store = FuzzySpillStore::new(memory_budget);
 
-                    // Create check_cancelled closure for this region
-                    let check_cancelled_for_region = || -> bool {
-                        if cancel_token_clone.is_cancelled() || cancelled_clone.load(AtomicOrdering::Relaxed) {
-                            return true;
-                        }
-                        if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
-                            if manager.shared_buffer.is_cancel_requested() {
-                                cancelled_clone.store(true, AtomicOrdering::Relaxed);
-                                return true;
-                            }
-                        }
-                        false
-                    };
-
-                    let result = fuzzy_search::fuzzy_initial_scan(value_type, *start, *end, chunk_size, None, None, Some(&check_cancelled_for_region));
-
-                    let region_results = match result {
-                        Ok(results) => results,
-                        Err(e) => {
-                            error!("Failed to fuzzy scan region {}: {:?}", idx, e);
-                            BPlusTreeSet::new(BPLUS_TREE_ORDER)
-                        },
-                    };
+            let check_cancelled = || -> bool {
+                if cancel_token_clone.is_cancelled() || cancelled_clone.load(AtomicOrdering::Relaxed) {
+                    return true;
+                }
+                if let Ok(manager) = SEARCH_ENGINE_MANAGER.read()
+                    && manager.shared_buffer.is_cancel_requested()
+                {
+                    cancelled_clone.store(true, AtomicOrdering::Relaxed);
+                    return true;
+                }
+                false
+            };
 
-                    // Update progress.
-                    let completed = completed_regions_clone.fetch_add(1, AtomicOrdering::Relaxed) + 1;
-                    let found_in_region = region_results.len() as i64;
-                    let total_found = total_found_clone.fetch_add(found_in_region, AtomicOrdering::Relaxed) + found_in_region;
+            let on_region_done = |completed: usize, total_found: usize| {
+                if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
+                    let progress = ((completed as f64 / total_regions as f64) * 100.0) as i32;
+                    manager.shared_buffer.update_progress(progress, completed as i32, total_found as i64);
+                    manager.shared_buffer.tick_heartbeat();
+                }
+            };
 
-                    if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
-                        let progress = ((completed as f64 / total_regions as f64) * 100.0) as i32;
-                        manager.shared_buffer.update_progress(progress, completed as i32, total_found);
-                        manager.shared_buffer.tick_heartbeat();
-                    }
+            fuzzy_search::fuzzy_initial_scan(value_type, stride, &regions, chunk_size, &store, &spill_dir, &check_cancelled, &on_region_done)
+                .map(|stats| (store, stats))
+        }))
+        .await;
 
-                    Some(region_results)
-                })
-                .collect();
+        let scan_result = scan_result.map_err(|e| anyhow!("Fuzzy scan task panicked: {:?}", e)).and_then(|r| r);
+        let stats = scan_result.as_ref().map(|(_, stats)| *stats).unwrap_or_default();
 
-            all_results
-        })
-        .await;
+        report.mark_phase("scan").failed_regions(stats.failed_regions);
 
         // Check if cancelled.
         if cancel_token.is_cancelled() || cancelled.load(AtomicOrdering::Relaxed) {
+            Self::publish_cancelled_report(report, stats.found, &cache_dir);
             if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
                 manager.shared_buffer.write_status(SearchStatus::Cancelled);
             }
@@ -840,18 +1283,18 @@ impl SearchEngineManager {
 
         // Process results.
         let success = match scan_result {
-            Ok(all_results) => {
+            Ok((store, _)) => {
+                let spilled = store.spilled_count();
+                if spilled > 0 {
+                    report.warn(format!("{} results exceeded the memory budget and were spilled to disk", spilled));
+                }
+
                 match SEARCH_ENGINE_MANAGER.write() {
                     Ok(mut manager) => {
                         if let Some(ref mut result_mgr) = manager.result_manager {
-                            for region_results in all_results {
-                                if !region_results.is_empty() {
-                                    // Convert BPlusTreeSet to Vec for storage
-                                    let items: Vec<_> = region_results.iter().cloned().collect();
-                                    if let Err(e) = result_mgr.add_fuzzy_results_batch(items) {
-                                        error!("Failed to add fuzzy results: {:?}", e);
-                                    }
-                                }
+                            // Segments come out in address order, the result manager spills beyond its own buffer.
+                            if let Err(e) = store.drain(|items| result_mgr.add_fuzzy_results_batch(items)) {
+                                error!("Failed to add fuzzy results: {:?}", e);
                             }
 
                             let elapsed = start_time.elapsed().as_millis() as u64;
@@ -881,6 +1324,9 @@ impl SearchEngineManager {
             },
         };
 
+        report.mark_phase("store");
+        Self::publish_final_report(report, success, &cache_dir);
+
         // Set status after releasing write lock.
         if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
             if success {
@@ -914,10 +1360,11 @@ impl SearchEngineManager {
         let current_results = result_mgr.get_all_fuzzy_results()?;
         if current_results.is_empty() {
             warn!("No fuzzy results to refine");
-            self.shared_buffer.write_status(SearchStatus::Completed);
             self.shared_buffer.write_found_count(0);
+            self.shared_buffer.write_status(SearchStatus::Completed);
             return Ok(());
         }
+        self.history.push_fuzzy(format!("fuzzy_refine: {:?}", condition), &current_results);
 
         // Reset shared buffer.
         self.shared_buffer.reset();
@@ -927,8 +1374,11 @@ impl SearchEngineManager {
         let cancel_token = CancellationToken::new();
         self.cancel_token = Some(cancel_token.clone());
 
+        let cache_dir = self.cache_dir.clone();
+        let background = self.background;
+
         let handle = TOKIO_RUNTIME.spawn(async move {
-            Self::run_fuzzy_refine_task(current_results, condition, cancel_token).await;
+            Self::run_fuzzy_refine_task(current_results, condition, background, cache_dir, cancel_token).await;
         });
 
         self.search_handle = Some(handle);
@@ -936,10 +1386,20 @@ impl SearchEngineManager {
     }
 
     /// Internal async fuzzy refine task.
-    async fn run_fuzzy_refine_task(current_results: Vec<FuzzySearchResultItem>, condition: FuzzyCondition, cancel_token: CancellationToken) {
+    async fn run_fuzzy_refine_task(
+        current_results: Vec<FuzzySearchResultItem>,
+        condition: FuzzyCondition,
+        background: bool,
+        cache_dir: PathBuf,
+        cancel_token: CancellationToken,
+    ) {
         let start_time = Instant::now();
         let total_items = current_results.len();
 
+        let mut report = RunReport::new("fuzzy_refine");
+        report.param("condition", format!("{:?}", condition)).param("background", background);
+        report.input_count = Some(total_items);
+
         debug!("Starting fuzzy refine: condition={:?}, existing results={}", condition, total_items);
 
         let processed_counter = Arc::new(AtomicUsize::new(0));
@@ -951,7 +1411,7 @@ impl SearchEngineManager {
         let cancelled_clone = Arc::clone(&cancelled);
         let cancel_token_clone = cancel_token.clone();
 
-        let refine_result = tokio::task::spawn_blocking(move || {
+        let refine_result = tokio::task::spawn_blocking(move || background::install(background, move || {
             // Check cancellation.
             if cancel_token_clone.is_cancelled() || cancelled_clone.load(AtomicOrdering::Relaxed) {
                 return BPlusTreeSet::new(BPLUS_TREE_ORDER);
@@ -999,13 +1459,17 @@ impl SearchEngineManager {
                 error!("Fuzzy refine failed: {:?}", e);
                 BPlusTreeSet::new(BPLUS_TREE_ORDER)
             })
-        })
+        }))
         .await;
 
+        report.mark_phase("refine");
+
         if cancel_token.is_cancelled() || cancelled.load(AtomicOrdering::Relaxed) {
+            Self::publish_cancelled_report(report, total_found_counter.load(AtomicOrdering::Relaxed), &cache_dir);
             if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
                 manager.shared_buffer.write_status(SearchStatus::Cancelled);
             }
+            Self::discard_last_snapshot();
             info!("Fuzzy refine cancelled");
             return;
         }
@@ -1050,6 +1514,9 @@ impl SearchEngineManager {
             },
         };
 
+        report.mark_phase("store");
+        Self::publish_final_report(report, success, &cache_dir);
+
         // Set status after releasing write lock.
         if let Ok(manager) = SEARCH_ENGINE_MANAGER.read() {
             if success {
@@ -1109,7 +1576,7 @@ impl SearchEngineManager {
                         group_search::search_region_group(query, *start, *end, chunk_size) // 废弃调用点
                     }
                 } else {
-                    single_search::search_region_single(&query.values[0], *start, *end, chunk_size) // 废弃调用点
+                    single_search::search_region_single(&query.values[0], *start, *end, chunk_size, query.stride(query.values[0].value_type())) // 废弃调用点
                 };
 
                 let region_results = match result {
@@ -1167,18 +1634,147 @@ impl SearchEngineManager {
         result_mgr.get_results(start, size)
     }
 
+    /// Pick up to `count` results spread over the whole result set, for previews.
+    ///
+    /// Evenly spaced picks start at the first result; random picks are drawn
+    /// without replacement. Returns `(index, item)` pairs in index order.
+    pub fn sample_results(&self, count: usize, random: bool) -> Result<Vec<(usize, SearchResultItem)>> {
+        let result_mgr = self.result_manager.as_ref().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+
+        let total = result_mgr.total_count();
+        let count = count.min(total);
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let indices: Vec<usize> = if random {
+            let mut indices = rand::seq::index::sample(&mut rand::rng(), total, count).into_vec();
+            indices.sort_unstable();
+            indices
+        } else {
+            (0..count).map(|i| (i as u128 * total as u128 / count as u128) as usize).collect()
+        };
+
+        let mut samples = Vec::with_capacity(indices.len());
+        for index in indices {
+            if let Some(item) = result_mgr.get_results(index, 1)?.into_iter().next() {
+                samples.push((index, item));
+            }
+        }
+        Ok(samples)
+    }
+
     pub fn get_total_count(&self) -> Result<usize> {
         let result_mgr = self.result_manager.as_ref().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
 
         Ok(result_mgr.total_count())
     }
 
+    /// Restores the result set from before the last refine step.
+    /// Returns the restored result count, or `None` if there is nothing to undo.
+    pub fn undo_search(&mut self) -> Result<Option<usize>> {
+        if self.is_searching() {
+            return Err(anyhow!("Search already in progress"));
+        }
+
+        let result_mgr = self.result_manager.as_mut().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
+        let Some(restored) = self.history.pop()? else {
+            return Ok(None);
+        };
+
+        result_mgr.clear()?;
+        result_mgr.set_mode(restored.mode())?;
+        // Restored addresses test every transform again
+        self.obfuscation_masks.clear();
+        match restored {
+            RestoredResults::Exact(items) => result_mgr.add_results_batch(items.into_iter().map(SearchResultItem::Exact).collect())?,
+            RestoredResults::Fuzzy(items) => result_mgr.add_fuzzy_results_batch(items)?,
+        }
+
+        let count = result_mgr.total_count();
+        self.shared_buffer.write_found_count(count as i64);
+        info!("Undo search: restored {} results, {} steps left", count, self.history.len());
+        Ok(Some(count))
+    }
+
+    /// Undo steps of the current search session, oldest first.
+    pub fn search_history(&self) -> Vec<HistoryEntry> {
+        self.history.entries()
+    }
+
+    /// Spill files of undo snapshots, which must survive cache pruning.
+    pub fn history_files(&self) -> Vec<PathBuf> {
+        self.history.spill_files()
+    }
+
+    /// Drops the snapshot of a refine step that left the results untouched.
+    fn discard_last_snapshot() {
+        if let Ok(mut manager) = SEARCH_ENGINE_MANAGER.write() {
+            manager.history.discard_last();
+        }
+    }
+
     pub fn clear_results(&mut self) -> Result<()> {
         let result_mgr = self.result_manager.as_mut().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
 
+        self.history.clear();
+        self.obfuscation_masks.clear();
         result_mgr.clear()
     }
 
+    /// Parks the results of the current process and swaps in `parked`, the state parked for process `pid`.
+    ///
+    /// A process without parked state starts with empty results in its own cache subdirectory,
+    /// so the result files of the parked processes are not overwritten. Fails while a search is running.
+    pub fn swap_process_results(&mut self, parked: Option<ParkedSearch>, pid: i32) -> Result<ParkedSearch> {
+        if self.is_searching() {
+            return Err(anyhow!("Cannot switch process while a search is in progress"));
+        }
+
+        let incoming = match parked {
+            Some(parked) => parked,
+            None => {
+                let cache_dir = self.cache_dir.join(format!("process_{}", pid));
+                let result_manager = match self.result_manager {
+                    Some(_) => {
+                        std::fs::create_dir_all(&cache_dir)?;
+                        Some(SearchResultManager::new(self.memory_buffer_size, cache_dir.clone()))
+                    },
+                    None => None,
+                };
+                ParkedSearch {
+                    result_manager,
+                    history: SearchHistory::new(cache_dir),
+                    obfuscation_masks: HashMap::new(),
+                }
+            },
+        };
+
+        let outgoing = ParkedSearch {
+            result_manager: std::mem::replace(&mut self.result_manager, incoming.result_manager),
+            history: std::mem::replace(&mut self.history, incoming.history),
+            obfuscation_masks: std::mem::replace(&mut self.obfuscation_masks, incoming.obfuscation_masks),
+        };
+        self.shared_buffer.write_found_count(self.get_total_count().unwrap_or(0) as i64);
+        Ok(outgoing)
+    }
+
+    /// Drops the results and history a panicking call may have left half-updated.
+    /// Settings and the cache dir are kept, so the manager stays initialized.
+    pub fn recover_from_poison(&mut self) {
+        self.request_cancel();
+        self.cancel_token = None;
+        self.search_handle = None;
+        self.history.clear();
+        self.obfuscation_masks.clear();
+        if let Some(result_mgr) = self.result_manager.as_mut()
+            && let Err(e) = result_mgr.clear()
+        {
+            warn!("Failed to clear search results after a panic: {:?}", e);
+        }
+        self.shared_buffer.write_status(SearchStatus::Idle);
+    }
+
     pub fn remove_result(&mut self, index: usize) -> Result<()> {
         let result_mgr = self.result_manager.as_mut().ok_or_else(|| anyhow!("SearchEngineManager not initialized"))?;
 
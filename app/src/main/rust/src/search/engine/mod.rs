@@ -3,13 +3,20 @@
 mod batch_reader;
 pub mod filter;
 pub mod fuzzy_search;
+pub(crate) mod fuzzy_spill;
 pub mod group_search;
+pub mod history;
 pub mod manager;
 mod memchr_ext;
+pub mod nearby_search;
+pub mod obfuscation;
+pub mod report;
 pub mod shared_buffer;
+mod simd_compare;
 pub mod single_search;
 
 pub use crate::core::globals::{PAGE_MASK, PAGE_SIZE};
 pub use filter::SearchFilter;
 pub use manager::{SearchEngineManager, SearchProgressCallback, ValuePair, BPLUS_TREE_ORDER, SEARCH_ENGINE_MANAGER};
+pub use report::{RunOutcome, RunReport};
 pub use shared_buffer::{SearchErrorCode, SearchStatus, SharedBuffer, SHARED_BUFFER_SIZE};
@@ -0,0 +1,203 @@
+//! 附近搜索
+//!
+//! 在现有每个结果前后 `distance` 字节内查找第二个值，只保留附近存在该值的结果，对应 GG 的"附近搜索"。
+//! 结果本身所在的地址不算作邻居。
+//!
+//! 结果地址有序，相邻结果的窗口重叠或间隙不超过一页时合并为一次读取，单次读取不超过 [`WINDOW_MAX_SIZE`]。
+//! 每个窗口只扫描一次，得到命中地址后按结果二分查找。窗口整体读取失败时按页读取，跳过不可读的页。
+
+use super::super::types::SearchValue;
+use super::manager::ValuePair;
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::{OP_QUEUE, PAGE_SIZE};
+use crate::core::DRIVER_MANAGER;
+use anyhow::{anyhow, Result};
+use log::{debug, log_enabled, Level};
+use rayon::prelude::*;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 最大搜索距离
+pub const MAX_NEARBY_DISTANCE: u64 = 64 * 1024;
+
+/// 合并窗口的最大间隙
+const WINDOW_MAX_GAP: u64 = 4096;
+
+/// 合并后单次读取的大小上限
+const WINDOW_MAX_SIZE: u64 = 256 * 1024;
+
+/// 一次读取覆盖的连续内存及其中的结果
+pub(super) struct Window {
+    pub start: u64,
+    pub end: u64,
+    /// 在有序结果中的下标范围
+    pub items: Range<usize>,
+}
+
+/// 将有序结果的邻域 `[addr - distance, addr + distance + element_size)` 聚类为读取窗口
+pub(super) fn cluster_windows(addresses: &[ValuePair], distance: u64, element_size: u64) -> Vec<Window> {
+    let mut windows: Vec<Window> = Vec::new();
+    for (idx, pair) in addresses.iter().enumerate() {
+        let start = pair.addr.saturating_sub(distance);
+        let end = pair.addr.saturating_add(distance).saturating_add(element_size);
+        match windows.last_mut() {
+            Some(window) if start <= window.end.saturating_add(WINDOW_MAX_GAP) && end - window.start <= WINDOW_MAX_SIZE => {
+                window.end = window.end.max(end);
+                window.items.end = idx + 1;
+            },
+            _ => windows.push(Window {
+                start,
+                end,
+                items: idx..idx + 1,
+            }),
+        }
+    }
+    windows
+}
+
+/// 读取窗口，返回缓冲区和每页是否可读，页按窗口起始地址所在页编号
+pub(super) fn read_window(manager: &DriverManager, window: &Window) -> (Vec<u8>, Vec<bool>) {
+    let page_size = *PAGE_SIZE as u64;
+    let first_page = window.start / page_size;
+    let page_count = ((window.end - 1) / page_size - first_page + 1) as usize;
+    let mut buffer = vec![0u8; (window.end - window.start) as usize];
+
+    if manager.read_memory_unified(window.start, &mut buffer, None).is_ok() {
+        return (buffer, vec![true; page_count]);
+    }
+
+    let mut readable = vec![false; page_count];
+    for (page, ok) in readable.iter_mut().enumerate() {
+        let page_start = ((first_page + page as u64) * page_size).max(window.start);
+        let page_end = ((first_page + page as u64 + 1) * page_size).min(window.end);
+        let range = (page_start - window.start) as usize..(page_end - window.start) as usize;
+        *ok = manager.read_memory_unified(page_start, &mut buffer[range], None).is_ok();
+    }
+    (buffer, readable)
+}
+
+/// 窗口中 `[addr, addr + len)` 的字节，越界或所在页不可读时返回 None
+pub(super) fn window_slice<'a>(buffer: &'a [u8], readable: &[bool], window_start: u64, addr: u64, len: usize) -> Option<&'a [u8]> {
+    let offset = addr.checked_sub(window_start)? as usize;
+    let bytes = buffer.get(offset..offset + len)?;
+    let page_size = *PAGE_SIZE as u64;
+    let first_page = window_start / page_size;
+    let first = (addr / page_size - first_page) as usize;
+    let last = ((addr + len as u64 - 1) / page_size - first_page) as usize;
+    readable[first..=last].iter().all(|&ok| ok).then_some(bytes)
+}
+
+/// 扫描窗口内全部对齐位置，返回有序的命中地址
+fn scan_window(buffer: &[u8], readable: &[bool], window_start: u64, target: &SearchValue, element_size: usize) -> Vec<u64> {
+    let align = target.value_type().alignment() as u64;
+    let mut addr = window_start.div_ceil(align) * align;
+    let mut hits = Vec::new();
+    while addr + element_size as u64 <= window_start + buffer.len() as u64 {
+        if let Some(bytes) = window_slice(buffer, readable, window_start, addr, element_size)
+            && let Ok(true) = target.matched(bytes)
+        {
+            hits.push(addr);
+        }
+        addr += align;
+    }
+    hits
+}
+
+/// 在每个结果 ±`distance` 字节内查找 `target`，返回附近存在该值的结果
+///
+/// 邻居的起始地址落在 `[addr - distance, addr + distance]` 内即算命中，按 `target` 类型的对齐扫描。
+/// 结果的值类型不需要和 `target` 相同。
+pub(crate) fn refine_nearby_with_cancel<F, P>(
+    addresses: &[ValuePair],
+    target: &SearchValue,
+    distance: u64,
+    processed_counter: Option<&Arc<AtomicUsize>>,
+    total_found_counter: Option<&Arc<AtomicUsize>>,
+    check_cancelled: &F,
+    update_progress: &P,
+) -> Result<Vec<ValuePair>>
+where
+    F: Fn() -> bool + Sync,
+    P: Fn(usize, usize) + Sync,
+{
+    if addresses.is_empty() || check_cancelled() {
+        return Ok(Vec::new());
+    }
+    if distance == 0 || distance > MAX_NEARBY_DISTANCE {
+        return Err(anyhow!("Near-by distance must be in 1..={}", MAX_NEARBY_DISTANCE));
+    }
+
+    let mut sorted = addresses.to_vec();
+    sorted.sort_unstable();
+    let element_size = target.value_type().size();
+    let windows = cluster_windows(&sorted, distance, element_size as u64);
+    let cancelled = AtomicBool::new(false);
+
+    let results: Result<Vec<ValuePair>> = windows
+        .par_iter()
+        .take_any_while(|_| {
+            if cancelled.load(Ordering::Relaxed) {
+                return false;
+            }
+            if check_cancelled() {
+                cancelled.store(true, Ordering::Relaxed);
+                return false;
+            }
+            true
+        })
+        .try_fold(Vec::new, |mut acc, window| -> Result<Vec<ValuePair>> {
+            let (buffer, readable) = {
+                let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+                let _op = OP_QUEUE.bulk();
+                read_window(&driver_manager, window)
+            };
+            let hits = scan_window(&buffer, &readable, window.start, target, element_size);
+
+            let mut found = 0;
+            for pair in &sorted[window.items.clone()] {
+                let low = pair.addr.saturating_sub(distance);
+                let high = pair.addr.saturating_add(distance);
+                let first = hits.partition_point(|&hit| hit < low);
+                if hits[first..].iter().take_while(|&&hit| hit <= high).any(|&hit| hit != pair.addr) {
+                    acc.push(pair.clone());
+                    found += 1;
+                }
+            }
+
+            if let Some(counter) = total_found_counter {
+                counter.fetch_add(found, Ordering::Relaxed);
+            }
+            if let Some(counter) = processed_counter {
+                let processed = counter.fetch_add(window.items.len(), Ordering::Relaxed) + window.items.len();
+                let found = total_found_counter.map(|c| c.load(Ordering::Relaxed)).unwrap_or(0);
+                update_progress(processed, found);
+            }
+            Ok(acc)
+        })
+        .try_reduce(Vec::new, |mut a, b| {
+            a.extend(b);
+            Ok(a)
+        });
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(Vec::new());
+    }
+    let mut results = results?;
+    results.sort_unstable();
+
+    let found_count = total_found_counter.map(|c| c.load(Ordering::Relaxed)).unwrap_or(results.len());
+    update_progress(sorted.len(), found_count);
+
+    if log_enabled!(Level::Debug) {
+        debug!(
+            "Near-by refine: {} -> {} results, {} windows, distance {}",
+            sorted.len(),
+            results.len(),
+            windows.len(),
+            distance
+        );
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,262 @@
+//! 混淆值搜索
+//!
+//! 很多手游不直接存储明文数值，而是存经过简单变换的值。混淆改善对现有每个结果测试常见的变换，
+//! 只保留某个变换解出的值等于目标值的结果，并记下仍然匹配的变换：
+//! - 异或：存储值 = 值 ^ 密钥，密钥为前后 4 或 8 字节处的 4 字节
+//! - 缩放：存储值 = 值 × 倍数
+//! - 拆分：值 = 本字段 + 前或后 4 字节处的字段
+//!
+//! 变换用位掩码表示，与 Java 侧 `SearchEngine.OBF_*` 一致，全部按小端 i32 解码。
+//! 第一次混淆改善测试请求的全部变换，之后每个地址只测试上次仍匹配的变换，通常几次后只剩一个。
+//! 先用模糊搜索记录全部地址，再按已知的值变化做几次混淆改善即可找到加密的值。
+
+use super::super::types::ValueType;
+use super::manager::ValuePair;
+use super::nearby_search::{cluster_windows, read_window, window_slice};
+use crate::core::driver_manager::DriverManager;
+use crate::core::globals::OP_QUEUE;
+use crate::core::DRIVER_MANAGER;
+use anyhow::{anyhow, Result};
+use log::{debug, log_enabled, Level};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 存储值 ^ 前 4 字节
+pub const XOR_PREV: u32 = 1 << 0;
+/// 存储值 ^ 后 4 字节
+pub const XOR_NEXT: u32 = 1 << 1;
+/// 存储值 ^ 前 8 字节处的 4 字节
+pub const XOR_PREV_8: u32 = 1 << 2;
+/// 存储值 ^ 后 8 字节处的 4 字节
+pub const XOR_NEXT_8: u32 = 1 << 3;
+/// 存储值 / 2 ... / 1000，第 4 至 9 位依次对应 [`SCALES`]
+pub const SCALE_2: u32 = 1 << 4;
+pub const SCALE_4: u32 = 1 << 5;
+pub const SCALE_8: u32 = 1 << 6;
+pub const SCALE_10: u32 = 1 << 7;
+pub const SCALE_100: u32 = 1 << 8;
+pub const SCALE_1000: u32 = 1 << 9;
+/// 前 4 字节 + 存储值
+pub const SPLIT_PREV: u32 = 1 << 10;
+/// 存储值 + 后 4 字节
+pub const SPLIT_NEXT: u32 = 1 << 11;
+
+pub const ALL_TRANSFORMS: u32 = (1 << 12) - 1;
+
+/// 缩放变换的倍数
+const SCALES: [i32; 6] = [2, 4, 8, 10, 100, 1000];
+
+/// 解码需要读取的范围：结果前后各 8 字节
+const WINDOW_RADIUS: u64 = 8;
+
+/// 变换名称，用于报告和日志
+pub fn transform_name(bit: u32) -> &'static str {
+    match bit {
+        XOR_PREV => "xor-4",
+        XOR_NEXT => "xor+4",
+        XOR_PREV_8 => "xor-8",
+        XOR_NEXT_8 => "xor+8",
+        SCALE_2 => "x2",
+        SCALE_4 => "x4",
+        SCALE_8 => "x8",
+        SCALE_10 => "x10",
+        SCALE_100 => "x100",
+        SCALE_1000 => "x1000",
+        SPLIT_PREV => "split-4",
+        SPLIT_NEXT => "split+4",
+        _ => "unknown",
+    }
+}
+
+/// 变换用到的相邻字段相对结果地址的偏移
+fn neighbor_offset(bit: u32) -> Option<i64> {
+    match bit {
+        XOR_PREV | SPLIT_PREV => Some(-4),
+        XOR_NEXT | SPLIT_NEXT => Some(4),
+        XOR_PREV_8 => Some(-8),
+        XOR_NEXT_8 => Some(8),
+        _ => None,
+    }
+}
+
+/// 用单个变换 `bit` 解码，`neighbor` 为相邻字段（变换不需要时忽略）。缩放后不能整除时返回 None
+pub fn decode(bit: u32, stored: i32, neighbor: Option<i32>) -> Option<i32> {
+    match bit {
+        XOR_PREV | XOR_NEXT | XOR_PREV_8 | XOR_NEXT_8 => Some(stored ^ neighbor?),
+        SPLIT_PREV | SPLIT_NEXT => Some(stored.wrapping_add(neighbor?)),
+        _ => {
+            let index = bit.trailing_zeros().checked_sub(SCALE_2.trailing_zeros())? as usize;
+            let scale = *SCALES.get(index)?;
+            (stored % scale == 0).then_some(stored / scale)
+        },
+    }
+}
+
+/// 读取 `address` 处的值并用变换 `bit` 解码
+pub fn decode_at(manager: &DriverManager, address: u64, bit: u32) -> Result<i32> {
+    if bit.count_ones() != 1 || bit & ALL_TRANSFORMS == 0 {
+        return Err(anyhow!("Invalid transform: {:#x}", bit));
+    }
+    let read_i32 = |addr: u64| -> Result<i32> {
+        let mut buf = [0u8; 4];
+        manager.read_memory_unified(addr, &mut buf, None)?;
+        Ok(i32::from_le_bytes(buf))
+    };
+    let _op = OP_QUEUE.interactive();
+    let stored = read_i32(address)?;
+    let neighbor = match neighbor_offset(bit) {
+        Some(offset) => Some(read_i32(address.wrapping_add_signed(offset))?),
+        None => None,
+    };
+    decode(bit, stored, neighbor).ok_or_else(|| anyhow!("Value at 0x{:X} is not a multiple of the scale", address))
+}
+
+/// 对每个结果测试变换，返回解出 `value` 的结果及其仍匹配的变换
+///
+/// `masks` 为上次混淆改善留下的变换，不在其中的地址测试 `transforms` 的全部变换。
+/// 结果的值类型不影响解码，保留的结果类型改为 Dword。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn refine_obfuscated_with_cancel<F, P>(
+    addresses: &[ValuePair],
+    masks: &HashMap<u64, u32>,
+    value: i32,
+    transforms: u32,
+    processed_counter: Option<&Arc<AtomicUsize>>,
+    total_found_counter: Option<&Arc<AtomicUsize>>,
+    check_cancelled: &F,
+    update_progress: &P,
+) -> Result<Vec<(ValuePair, u32)>>
+where
+    F: Fn() -> bool + Sync,
+    P: Fn(usize, usize) + Sync,
+{
+    let transforms = transforms & ALL_TRANSFORMS;
+    if addresses.is_empty() || transforms == 0 || check_cancelled() {
+        return Ok(Vec::new());
+    }
+
+    let mut sorted = addresses.to_vec();
+    sorted.sort_unstable();
+    let windows = cluster_windows(&sorted, WINDOW_RADIUS, 4);
+    let cancelled = AtomicBool::new(false);
+
+    let results: Result<Vec<(ValuePair, u32)>> = windows
+        .par_iter()
+        .take_any_while(|_| {
+            if cancelled.load(Ordering::Relaxed) {
+                return false;
+            }
+            if check_cancelled() {
+                cancelled.store(true, Ordering::Relaxed);
+                return false;
+            }
+            true
+        })
+        .try_fold(Vec::new, |mut acc, window| -> Result<Vec<(ValuePair, u32)>> {
+            let (buffer, readable) = {
+                let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
+                let _op = OP_QUEUE.bulk();
+                read_window(&driver_manager, window)
+            };
+            let read_i32 = |addr: u64| {
+                window_slice(&buffer, &readable, window.start, addr, 4).map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()))
+            };
+
+            let mut found = 0;
+            for pair in &sorted[window.items.clone()] {
+                let Some(stored) = read_i32(pair.addr) else {
+                    continue;
+                };
+                let mut candidates = masks.get(&pair.addr).copied().unwrap_or(ALL_TRANSFORMS) & transforms;
+                let mut matched = 0;
+                while candidates != 0 {
+                    let bit = candidates & candidates.wrapping_neg();
+                    candidates &= !bit;
+                    let neighbor = neighbor_offset(bit).and_then(|offset| read_i32(pair.addr.wrapping_add_signed(offset)));
+                    if decode(bit, stored, neighbor) == Some(value) {
+                        matched |= bit;
+                    }
+                }
+                if matched != 0 {
+                    acc.push((ValuePair::new(pair.addr, ValueType::Dword), matched));
+                    found += 1;
+                }
+            }
+
+            if let Some(counter) = total_found_counter {
+                counter.fetch_add(found, Ordering::Relaxed);
+            }
+            if let Some(counter) = processed_counter {
+                let processed = counter.fetch_add(window.items.len(), Ordering::Relaxed) + window.items.len();
+                let found = total_found_counter.map(|c| c.load(Ordering::Relaxed)).unwrap_or(0);
+                update_progress(processed, found);
+            }
+            Ok(acc)
+        })
+        .try_reduce(Vec::new, |mut a, b| {
+            a.extend(b);
+            Ok(a)
+        });
+
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(Vec::new());
+    }
+    let mut results = results?;
+    results.sort_unstable_by_key(|(pair, _)| pair.addr);
+
+    let found_count = total_found_counter.map(|c| c.load(Ordering::Relaxed)).unwrap_or(results.len());
+    update_progress(sorted.len(), found_count);
+
+    if log_enabled!(Level::Debug) {
+        debug!("Obfuscated refine: {} -> {} results, value {}, transforms {:#x}", sorted.len(), results.len(), value, transforms);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_xor() {
+        let key = 0x5A5A_1234;
+        let stored = 1000 ^ key;
+        for bit in [XOR_PREV, XOR_NEXT, XOR_PREV_8, XOR_NEXT_8] {
+            assert_eq!(decode(bit, stored, Some(key)), Some(1000), "{}", transform_name(bit));
+            assert_eq!(decode(bit, stored, None), None);
+        }
+    }
+
+    #[test]
+    fn test_decode_scale() {
+        assert_eq!(decode(SCALE_2, 2000, None), Some(1000));
+        assert_eq!(decode(SCALE_4, -4000, None), Some(-1000));
+        assert_eq!(decode(SCALE_8, 8000, None), Some(1000));
+        assert_eq!(decode(SCALE_10, 10000, None), Some(1000));
+        assert_eq!(decode(SCALE_100, 100000, None), Some(1000));
+        assert_eq!(decode(SCALE_1000, 1_000_000, None), Some(1000));
+        // 不能整除
+        assert_eq!(decode(SCALE_10, 1001, None), None);
+        // 缩放不需要相邻字段
+        assert_eq!(decode(SCALE_2, 2000, Some(7)), Some(1000));
+    }
+
+    #[test]
+    fn test_decode_split() {
+        assert_eq!(decode(SPLIT_PREV, 600, Some(400)), Some(1000));
+        assert_eq!(decode(SPLIT_NEXT, -200, Some(1200)), Some(1000));
+        assert_eq!(decode(SPLIT_NEXT, i32::MAX, Some(1)), Some(i32::MIN));
+        assert_eq!(decode(SPLIT_PREV, 600, None), None);
+        assert_eq!(decode(SPLIT_NEXT, 600, None), None);
+    }
+
+    #[test]
+    fn test_decode_unknown_transform() {
+        assert_eq!(decode(0, 1000, Some(1)), None);
+        assert_eq!(decode(1 << 12, 1000, None), None);
+        assert_eq!(decode(1 << 31, 1000, None), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Run reports for async search operations.
+//!
+//! Every exact search, refine, fuzzy initial scan and fuzzy refine writes a
+//! JSON report (parameters, region stats, per-phase timings, result counts,
+//! warnings) to the cache directory, so a bug report can carry the numbers
+//! instead of a screenshot. Only the latest report is kept.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// File name of the latest run report inside the cache directory.
+pub const REPORT_FILE_NAME: &str = "mamu_run_report.json";
+
+/// How the operation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunOutcome {
+    Completed,
+    Cancelled,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub name: &'static str,
+    pub millis: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RegionStats {
+    pub count: usize,
+    pub total_bytes: u64,
+    /// Regions whose scan returned an error
+    pub failed: usize,
+}
+
+/// Structured summary of a single search operation.
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub operation: &'static str,
+    /// Start time, seconds since the unix epoch
+    pub started_at: u64,
+    pub parameters: BTreeMap<&'static str, String>,
+    pub regions: Option<RegionStats>,
+    pub phases: Vec<PhaseTiming>,
+    /// Number of results the operation started from (refines only)
+    pub input_count: Option<usize>,
+    pub result_count: usize,
+    pub total_millis: u64,
+    pub outcome: RunOutcome,
+    pub warnings: Vec<String>,
+    #[serde(skip)]
+    started: Instant,
+    #[serde(skip)]
+    last_mark: Instant,
+}
+
+impl RunReport {
+    pub fn new(operation: &'static str) -> Self {
+        let now = Instant::now();
+        Self {
+            operation,
+            started_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            parameters: BTreeMap::new(),
+            regions: None,
+            phases: Vec::new(),
+            input_count: None,
+            result_count: 0,
+            total_millis: 0,
+            outcome: RunOutcome::Completed,
+            warnings: Vec::new(),
+            started: now,
+            last_mark: now,
+        }
+    }
+
+    pub fn param(&mut self, key: &'static str, value: impl ToString) -> &mut Self {
+        self.parameters.insert(key, value.to_string());
+        self
+    }
+
+    /// Record the searched regions as `(start, end)` pairs.
+    pub fn regions(&mut self, regions: &[(u64, u64)]) -> &mut Self {
+        self.regions = Some(RegionStats {
+            count: regions.len(),
+            total_bytes: regions.iter().map(|(start, end)| end.saturating_sub(*start)).sum(),
+            failed: 0,
+        });
+        self
+    }
+
+    pub fn failed_regions(&mut self, failed: usize) -> &mut Self {
+        if let Some(ref mut stats) = self.regions {
+            stats.failed = failed;
+        }
+        if failed > 0 {
+            self.warn(format!("{} regions failed to scan", failed));
+        }
+        self
+    }
+
+    /// Close the current phase, timing it from the previous mark.
+    pub fn mark_phase(&mut self, name: &'static str) -> &mut Self {
+        let now = Instant::now();
+        self.phases.push(PhaseTiming {
+            name,
+            millis: now.duration_since(self.last_mark).as_millis() as u64,
+        });
+        self.last_mark = now;
+        self
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) -> &mut Self {
+        self.warnings.push(message.into());
+        self
+    }
+
+    pub fn finish(&mut self, outcome: RunOutcome, result_count: usize) -> &mut Self {
+        self.outcome = outcome;
+        self.result_count = result_count;
+        self.total_millis = self.started.elapsed().as_millis() as u64;
+        self
+    }
+
+    /// Write the report as JSON to `cache_dir`, replacing the previous one.
+    pub fn write(&self, cache_dir: &Path) -> Result<PathBuf> {
+        let path = cache_dir.join(REPORT_FILE_NAME);
+        let tmp_path = cache_dir.join(format!("{}.tmp", REPORT_FILE_NAME));
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(path)
+    }
+}
@@ -11,6 +11,8 @@
 //! [28-31] error_code     (Rust writes)  error code when status is Error
 //! ```
 
+use crate::core::jobs::JobKind;
+use crate::core::scan_events::{self, ScanKind};
 use std::sync::atomic::{AtomicPtr, Ordering, fence};
 
 /// Shared buffer size in bytes.
@@ -142,12 +144,19 @@ impl SharedBuffer {
         // Ensure all previous writes are visible before status change.
         fence(Ordering::Release);
         self.write_i32(offsets::STATUS, status as i32);
+        match status {
+            SearchStatus::Searching => scan_events::report_started(ScanKind::Search, JobKind::Search),
+            SearchStatus::Completed => scan_events::report_finished(ScanKind::Search, false),
+            SearchStatus::Cancelled => scan_events::report_finished(ScanKind::Search, true),
+            SearchStatus::Idle | SearchStatus::Error => {},
+        }
     }
 
     /// Writes progress value (0-100).
     #[inline]
     pub fn write_progress(&self, progress: i32) {
         self.write_i32(offsets::PROGRESS, progress.clamp(0, 100));
+        scan_events::report_progress(ScanKind::Search, progress.clamp(0, 100));
     }
 
     /// Writes completed region count.
@@ -160,6 +169,7 @@ impl SharedBuffer {
     #[inline]
     pub fn write_found_count(&self, count: i64) {
         self.write_i64(offsets::FOUND_COUNT, count);
+        scan_events::report_found(ScanKind::Search, count);
     }
 
     /// Writes heartbeat value.
@@ -172,6 +182,11 @@ impl SharedBuffer {
     #[inline]
     pub fn write_error_code(&self, code: SearchErrorCode) {
         self.write_i32(offsets::ERROR_CODE, code as i32);
+        match code {
+            SearchErrorCode::None => {},
+            SearchErrorCode::AlreadySearching => scan_events::report_rejected(ScanKind::Search, code as i32),
+            _ => scan_events::report_error(ScanKind::Search, code as i32),
+        }
     }
 
     /// Reads cancel flag that is set by Kotlin.
@@ -189,9 +204,9 @@ impl SharedBuffer {
     /// Updates progress information atomically.
     #[inline]
     pub fn update_progress(&self, progress: i32, regions_done: i32, found_count: i64) {
-        self.write_progress(progress);
-        self.write_regions_done(regions_done);
         self.write_found_count(found_count);
+        self.write_regions_done(regions_done);
+        self.write_progress(progress);
     }
 
     /// Updates heartbeat with random value.
@@ -0,0 +1,198 @@
+//! 精确搜索的页内比较
+//!
+//! aarch64 下用 NEON 每次比较 16 字节（4 个 i32/f32 或 2 个 i64），其他架构走标量实现。
+//! 比较语义与 `SearchValue::matched` 一致，`haystack` 的起点须按元素大小对齐。
+
+use super::super::types::{FloatCompare, SearchValue, ValueType};
+
+/// 与目标值比较的元素类型
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PageComparer {
+    I32(i32),
+    I64(i64),
+    /// 按 `|x as f64 - target| < f64::EPSILON` 判断
+    F32(f64),
+}
+
+impl PageComparer {
+    /// 只有定值的 Dword/Qword/Float（默认比较方式）走这里，其他情况返回 None
+    pub fn for_target(target: &SearchValue) -> Option<Self> {
+        match *target {
+            SearchValue::FixedInt {
+                value,
+                value_type: ValueType::Dword,
+            } => Some(Self::I32(i32::from_le_bytes(value[..4].try_into().ok()?))),
+            SearchValue::FixedInt {
+                value,
+                value_type: ValueType::Qword,
+            } => Some(Self::I64(i64::from_le_bytes(value[..8].try_into().ok()?))),
+            SearchValue::FixedFloat {
+                value,
+                value_type: ValueType::Float,
+                compare: FloatCompare::Exact,
+            } => Some(Self::F32(value)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn element_size(&self) -> usize {
+        match self {
+            Self::I32(_) | Self::F32(_) => 4,
+            Self::I64(_) => 8,
+        }
+    }
+
+    /// 对 `haystack` 中每个匹配元素回调其字节偏移，末尾不足一个元素的字节忽略
+    #[inline]
+    pub fn find(&self, haystack: &[u8], mut on_match: impl FnMut(usize)) {
+        #[cfg(target_arch = "aarch64")]
+        let done = unsafe { self.find_neon(haystack, &mut on_match) };
+        #[cfg(not(target_arch = "aarch64"))]
+        let done = 0;
+
+        self.find_scalar(&haystack[done..], |offset| on_match(done + offset));
+    }
+
+    fn find_scalar(&self, haystack: &[u8], mut on_match: impl FnMut(usize)) {
+        let size = self.element_size();
+        for (idx, element) in haystack.chunks_exact(size).enumerate() {
+            let matched = match *self {
+                Self::I32(target) => i32::from_le_bytes(element.try_into().unwrap()) == target,
+                Self::I64(target) => i64::from_le_bytes(element.try_into().unwrap()) == target,
+                Self::F32(target) => (f32::from_le_bytes(element.try_into().unwrap()) as f64 - target).abs() < f64::EPSILON,
+            };
+            if matched {
+                on_match(idx * size);
+            }
+        }
+    }
+
+    /// 按 16 字节一组比较，返回已处理的字节数，剩余部分交给标量实现
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn find_neon(&self, haystack: &[u8], on_match: &mut impl FnMut(usize)) -> usize {
+        use std::arch::aarch64::*;
+
+        let len = haystack.len() & !15;
+        let ptr = haystack.as_ptr();
+        let mut offset = 0;
+
+        unsafe {
+            match *self {
+                Self::I32(target) => {
+                    let needle = vdupq_n_s32(target);
+                    while offset < len {
+                        let eq = vceqq_s32(vld1q_s32(ptr.add(offset) as *const i32), needle);
+                        // 绝大多数分组没有命中，只在有命中时逐 lane 检查
+                        if vmaxvq_u32(eq) != 0 {
+                            let mut lanes = [0u32; 4];
+                            vst1q_u32(lanes.as_mut_ptr(), eq);
+                            for (lane, hit) in lanes.iter().enumerate() {
+                                if *hit != 0 {
+                                    on_match(offset + lane * 4);
+                                }
+                            }
+                        }
+                        offset += 16;
+                    }
+                },
+                Self::I64(target) => {
+                    let needle = vdupq_n_s64(target);
+                    while offset < len {
+                        let eq = vceqq_s64(vld1q_s64(ptr.add(offset) as *const i64), needle);
+                        if vmaxvq_u32(vreinterpretq_u32_u64(eq)) != 0 {
+                            if vgetq_lane_u64::<0>(eq) != 0 {
+                                on_match(offset);
+                            }
+                            if vgetq_lane_u64::<1>(eq) != 0 {
+                                on_match(offset + 8);
+                            }
+                        }
+                        offset += 16;
+                    }
+                },
+                Self::F32(target) => {
+                    let needle = vdupq_n_f64(target);
+                    let epsilon = vdupq_n_f64(f64::EPSILON);
+                    while offset < len {
+                        let values = vld1q_f32(ptr.add(offset) as *const f32);
+                        // 扩展为 f64 后比较，与标量实现的精度一致
+                        let low = vcltq_f64(vabdq_f64(vcvt_f64_f32(vget_low_f32(values)), needle), epsilon);
+                        let high = vcltq_f64(vabdq_f64(vcvt_high_f64_f32(values), needle), epsilon);
+                        let any = vorrq_u64(low, high);
+                        if vmaxvq_u32(vreinterpretq_u32_u64(any)) != 0 {
+                            let hits = [
+                                vgetq_lane_u64::<0>(low),
+                                vgetq_lane_u64::<1>(low),
+                                vgetq_lane_u64::<0>(high),
+                                vgetq_lane_u64::<1>(high),
+                            ];
+                            for (lane, hit) in hits.iter().enumerate() {
+                                if *hit != 0 {
+                                    on_match(offset + lane * 4);
+                                }
+                            }
+                        }
+                        offset += 16;
+                    }
+                },
+            }
+        }
+
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(comparer: PageComparer, haystack: &[u8]) -> Vec<usize> {
+        let mut hits = Vec::new();
+        comparer.find(haystack, |offset| hits.push(offset));
+        hits
+    }
+
+    #[test]
+    fn finds_i32_in_page_and_tail() {
+        let mut page = vec![0u8; 4096 + 12];
+        for offset in [0usize, 20, 4092, 4104] {
+            page[offset..offset + 4].copy_from_slice(&1234567i32.to_le_bytes());
+        }
+        let comparer = PageComparer::for_target(&SearchValue::fixed(1234567, ValueType::Dword)).unwrap();
+        assert_eq!(collect(comparer, &page), vec![0, 20, 4092, 4104]);
+    }
+
+    #[test]
+    fn finds_i64_lanes() {
+        let mut page = vec![0xFFu8; 64];
+        page[8..16].copy_from_slice(&(-42i64).to_le_bytes());
+        page[48..56].copy_from_slice(&(-42i64).to_le_bytes());
+        let comparer = PageComparer::for_target(&SearchValue::fixed(-42, ValueType::Qword)).unwrap();
+        assert_eq!(collect(comparer, &page), vec![8, 48]);
+    }
+
+    #[test]
+    fn f32_matches_scalar_semantics() {
+        let target = SearchValue::fixed_float(1.5, ValueType::Float);
+        let values = [1.5f32, 1.5000001, -1.5, 0.0, 1.5, f32::NAN, 3.0, 1.5, 1.5];
+        let page: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        let expected: Vec<usize> = page
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, element)| target.matched(element).unwrap())
+            .map(|(idx, _)| idx * 4)
+            .collect();
+        let comparer = PageComparer::for_target(&target).unwrap();
+        assert_eq!(collect(comparer, &page), expected);
+        assert_eq!(expected, vec![0, 16, 28, 32]);
+    }
+
+    #[test]
+    fn other_types_are_not_accelerated() {
+        assert!(PageComparer::for_target(&SearchValue::fixed(1, ValueType::Word)).is_none());
+        assert!(PageComparer::for_target(&SearchValue::fixed_float(1.0, ValueType::Double)).is_none());
+        assert!(PageComparer::for_target(&SearchValue::range(0, 10, ValueType::Dword, false)).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 use super::super::types::{SearchValue, ValueType};
 use super::manager::{ValuePair, BPLUS_TREE_ORDER};
+use crate::core::globals::OP_QUEUE;
 use crate::core::DRIVER_MANAGER;
 use crate::search::engine::memchr_ext::MemchrExt;
+use crate::search::engine::simd_compare::PageComparer;
 use crate::search::{PAGE_MASK, PAGE_SIZE};
 use crate::wuwa::PageStatusBitmap;
 use anyhow::{anyhow, Result};
@@ -33,6 +35,7 @@ pub(crate) fn search_in_chunks_with_status(
     region_start: u64,              // 搜索区域的起始地址
     region_end: u64,                // 搜索区域的结束地址
     element_size: usize,            // 元素大小
+    stride: usize,                  // 扫描步长，逐字节扫描时为 1
     target: &SearchValue,           // 目标搜索值
     value_type: ValueType,          // 目标值类型
     page_status: &PageStatusBitmap, // 页面状态位图
@@ -72,12 +75,36 @@ pub(crate) fn search_in_chunks_with_status(
         false
     };
 
+    // 按页整块比较要求元素对齐
+    let page_comparer = if stride == element_size { PageComparer::for_target(target) } else { None };
+
     let hits = ranges
         .into_par_iter()
         .map(|(rs, re)| {
-            let estimated_matches = ((re - rs) / element_size) >> 2;
+            let estimated_matches = ((re - rs) / stride) >> 2;
             let mut local = Vec::with_capacity(estimated_matches);
 
+            // Dword/Qword/Float 定值按页整块比较（aarch64 下为 NEON）
+            if let Some(comparer) = page_comparer {
+                for page_idx in (rs / *PAGE_SIZE)..re.div_ceil(*PAGE_SIZE) {
+                    if !page_status.is_page_success(page_idx) {
+                        continue;
+                    }
+
+                    // buffer_addr 页对齐，偏移对齐即地址对齐
+                    let page_start = first_aligned_pos(buffer_addr, (page_idx * *PAGE_SIZE).max(rs), element_size);
+                    let page_end = ((page_idx + 1) * *PAGE_SIZE).min(re);
+                    if page_start >= page_end {
+                        continue;
+                    }
+
+                    comparer.find(&buffer[page_start..page_end], |offset| {
+                        local.push(buffer_addr + (page_start + offset) as u64);
+                    });
+                }
+                return local;
+            }
+
             // 单字节搜索加速
             if fast_int // 现代cpu会学习，这里不需要优化，写这个注释是告诉ai无需检查这里
                 && let Ok(bytes) = target.bytes() && bytes.len() == 1
@@ -108,7 +135,7 @@ pub(crate) fn search_in_chunks_with_status(
                 // memchr 多字节加速路径
                 let bytes = target.bytes().unwrap();
                 let first_byte = bytes[0];
-                let align_mask = (element_size - 1) as u64; // 对齐掩码（2^n - 1）
+                let align_mask = (stride - 1) as u64; // 对齐掩码（2^n - 1）
 
                 // 按页遍历，只在成功页上搜索
                 let start_page_idx = rs / *PAGE_SIZE;
@@ -166,7 +193,7 @@ pub(crate) fn search_in_chunks_with_status(
             let mut pos = rs;
 
             // 注意：对齐必须按绝对地址算
-            pos = first_aligned_pos(buffer_addr, pos, element_size);
+            pos = first_aligned_pos(buffer_addr, pos, stride);
             let mut current_page_end = ((pos / *PAGE_SIZE + 1) * *PAGE_SIZE).min(re);
 
             while pos < re {
@@ -181,7 +208,7 @@ pub(crate) fn search_in_chunks_with_status(
                     let page_idx = pos / *PAGE_SIZE;
                     if !page_status.is_page_success(page_idx) {
                         let next_page = (page_idx + 1) * *PAGE_SIZE;
-                        pos = first_aligned_pos(buffer_addr, next_page, element_size);
+                        pos = first_aligned_pos(buffer_addr, next_page, stride);
                         current_page_end = ((pos / *PAGE_SIZE + 1) * *PAGE_SIZE).min(re);
                         continue;
                     }
@@ -204,7 +231,7 @@ pub(crate) fn search_in_chunks_with_status(
                     local.push(buffer_addr + pos as u64);
                 }
 
-                pos += element_size;
+                pos += stride;
             }
 
             local
@@ -224,6 +251,7 @@ pub(crate) fn search_region_single(
     start: u64,        // 区域起始地址
     end: u64,          // 区域结束地址
     chunk_size: usize, // 每次读取的块大小
+    stride: usize,     // 扫描步长，见 SearchQuery::stride
 ) -> Result<Vec<ValuePair>> {
     let driver_manager = DRIVER_MANAGER.read().map_err(|_| anyhow!("Failed to acquire DriverManager lock"))?;
 
@@ -244,7 +272,10 @@ pub(crate) fn search_region_single(
         let mut page_status = PageStatusBitmap::new(chunk_len, current as usize);
 
         // 这里读取内存，这里的current一定页对齐的
-        let read_result = driver_manager.read_memory_unified(current, &mut chunk_buffer[..chunk_len], Some(&mut page_status));
+        let read_result = {
+            let _op = OP_QUEUE.bulk();
+            driver_manager.read_memory_unified(current, &mut chunk_buffer[..chunk_len], Some(&mut page_status))
+        };
 
         match read_result {
             Ok(_) => {
@@ -257,6 +288,7 @@ pub(crate) fn search_region_single(
                         start,
                         end,
                         element_size,
+                        stride,
                         target,
                         value_type,
                         &page_status,
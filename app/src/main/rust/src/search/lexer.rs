@@ -10,6 +10,7 @@ pub enum Token<'a> {
     DoubleColon,
     Tilde,
     DoubleTilde,
+    Pipe,
 }
 
 pub struct Lexer<'a> {
@@ -148,6 +149,10 @@ impl<'a> Lexer<'a> {
                         Ok(Some(Token::Colon))
                     }
                 }
+                b'|' => {
+                    self.advance();
+                    Ok(Some(Token::Pipe))
+                }
                 b'~' => {
                     self.advance();
                     if self.peek() == Some(b'~') {
@@ -1,5 +1,5 @@
 use super::lexer::{Lexer, Token, parse_number, parse_float};
-use super::types::{SearchMode, SearchQuery, SearchValue, ValueType};
+use super::types::{SearchMode, SearchQuery, SearchValue, ValueType, VEC3_EPSILON};
 
 pub struct Parser<'a> {
     tokens: Vec<Token<'a>>,
@@ -58,6 +58,9 @@ impl<'a> Parser<'a> {
         let next_token = self.peek();
 
         match next_token {
+            Some(Token::Pipe) => {
+                self.parse_vector(num_token)
+            }
             Some(Token::Tilde) | Some(Token::DoubleTilde) => {
                 let exclude = matches!(next_token, Some(Token::DoubleTilde));
                 self.advance();
@@ -82,6 +85,35 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// 解析 `x|y|z` 形式的 Vec3，类型后缀只能是 V
+    fn parse_vector(&mut self, first_token: (&'a str, bool)) -> Result<SearchValue, String> {
+        let mut components = [0f32; 3];
+        components[0] = parse_float(first_token.0, first_token.1)? as f32;
+
+        for component in components.iter_mut().skip(1) {
+            self.expect(Token::Pipe)?;
+            *component = match self.advance() {
+                Some(Token::Number(s, is_hex)) => parse_float(s, *is_hex)? as f32,
+                Some(token) => return Err(format!("Expected vector component, got {:?}", token)),
+                None => return Err("Expected vector component, got EOF".to_string()),
+            };
+        }
+
+        match self.peek() {
+            Some(Token::Type(ValueType::Vec3)) => {
+                self.advance();
+            }
+            Some(Token::Type(value_type)) => {
+                return Err(format!("Vector value cannot have type {}", value_type));
+            }
+            Some(Token::Pipe) => return Err("Vec3 value must have exactly 3 components".to_string()),
+            Some(Token::Tilde) | Some(Token::DoubleTilde) => return Err("Range search is not supported for Vec3".to_string()),
+            _ => {}
+        }
+
+        Ok(SearchValue::fixed_vector(components, VEC3_EPSILON))
+    }
+
     fn parse_range(&mut self, start_token: (&'a str, bool), exclude: bool) -> Result<SearchValue, String> {
         let end_token = match self.advance() {
             Some(Token::Number(s, is_hex)) => (*s, *is_hex),
@@ -129,6 +161,10 @@ impl<'a> Parser<'a> {
     fn create_fixed_value(&self, num_token: (&'a str, bool), value_type: ValueType) -> Result<SearchValue, String> {
         let (num_str, is_hex) = num_token;
 
+        if value_type.is_vector() {
+            return Err(format!("Vec3 value must be written as x|y|z, got {}", num_str));
+        }
+
         if value_type.is_float_type() {
             let value = parse_float(num_str, is_hex)?;
             Ok(SearchValue::fixed_float(value, value_type))
@@ -154,6 +190,10 @@ impl<'a> Parser<'a> {
         let (start_str, start_is_hex) = start_token;
         let (end_str, end_is_hex) = end_token;
 
+        if value_type.is_vector() {
+            return Err("Range search is not supported for Vec3".to_string());
+        }
+
         if value_type.is_float_type() {
             let start = parse_float(start_str, start_is_hex)?;
             let end = parse_float(end_str, end_is_hex)?;
@@ -252,6 +292,7 @@ pub fn parse_search_query(input: &str, default_type: ValueType) -> Result<Search
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::types::FloatCompare;
 
     #[test]
     fn test_parse_simple() {
@@ -337,4 +378,46 @@ mod tests {
         assert_eq!(query.values.len(), 1);
         assert!(matches!(query.values[0], SearchValue::FixedFloat { .. }));
     }
+
+    #[test]
+    fn test_parse_vector() {
+        let query = parse_search_query("1.5|2|-0V;100D", ValueType::Dword);
+        assert!(query.is_err());
+
+        let query = parse_search_query("1.5|2|0.25V;100D", ValueType::Dword).unwrap();
+        assert_eq!(query.values.len(), 2);
+        assert_eq!(query.values[0].value_type(), ValueType::Vec3);
+        assert_eq!(query.values[0].value_type().size(), 12);
+
+        let mut bytes = Vec::new();
+        for component in [1.5f32, 2.00001, 0.25] {
+            bytes.extend_from_slice(&component.to_le_bytes());
+        }
+        assert!(query.values[0].matched(&bytes).unwrap());
+        bytes[4..8].copy_from_slice(&2.1f32.to_le_bytes());
+        assert!(!query.values[0].matched(&bytes).unwrap());
+
+        assert!(parse_search_query("1|2V", ValueType::Dword).is_err());
+        assert!(parse_search_query("1|2|3|4V", ValueType::Dword).is_err());
+        assert!(parse_search_query("1.0", ValueType::Vec3).is_err());
+    }
+
+    #[test]
+    fn test_float_compare() {
+        let stored = 99.99f32.to_le_bytes();
+        let query = parse_search_query("100F", ValueType::Float).unwrap();
+        assert!(!query.values[0].matched(&stored).unwrap());
+
+        let query = parse_search_query("100F", ValueType::Float).unwrap().with_float_compare(FloatCompare::Absolute(0.1));
+        assert!(query.values[0].matched(&stored).unwrap());
+
+        let query = parse_search_query("100F", ValueType::Float).unwrap().with_float_compare(FloatCompare::Relative(1e-5));
+        assert!(!query.values[0].matched(&stored).unwrap());
+
+        let query = parse_search_query("99F", ValueType::Float).unwrap().with_float_compare(FloatCompare::Truncated);
+        assert!(query.values[0].matched(&stored).unwrap());
+
+        assert_eq!(FloatCompare::from_id(1, -1.0), None);
+        assert_eq!(FloatCompare::from_id(3, f64::NAN), Some(FloatCompare::Truncated));
+    }
 }
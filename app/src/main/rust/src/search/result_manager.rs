@@ -1,12 +1,15 @@
 mod exact;
 mod fuzzy;
+mod snapshot;
 
 use super::types::ValueType;
 pub use crate::search::result_manager::exact::ExactSearchResultItem;
 use crate::search::result_manager::exact::ExactSearchResultManager;
 pub use crate::search::result_manager::fuzzy::{FuzzySearchResultItem, FuzzySearchResultManager};
+pub(crate) use crate::search::result_manager::snapshot::ValueSnapshot;
 use anyhow::{Result, anyhow};
 use log::{debug, error, info};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use crate::search::engine::ValuePair;
 
@@ -26,7 +29,7 @@ impl SearchResultItem {
         SearchResultItem::Exact(ExactSearchResultItem::new(address, value_type))
     }
 
-    pub fn new_fuzzy(address: u64, value: [u8; 8], value_type: ValueType) -> Self {
+    pub fn new_fuzzy(address: u64, value: [u8; 12], value_type: ValueType) -> Self {
         SearchResultItem::Fuzzy(FuzzySearchResultItem::new(address, value, value_type))
     }
 
@@ -51,6 +54,10 @@ pub(crate) struct SearchResultManager {
     current_mode: SearchResultMode,
     exact: ExactSearchResultManager,
     fuzzy: FuzzySearchResultManager,
+    /// 命名数值快照，不随结果清空
+    snapshots: BTreeMap<String, ValueSnapshot>,
+    cache_dir: PathBuf,
+    next_snapshot_id: u64,
 }
 
 impl SearchResultManager {
@@ -58,7 +65,10 @@ impl SearchResultManager {
         Self {
             current_mode: SearchResultMode::Exact,
             exact: ExactSearchResultManager::new(memory_buffer_size, cache_dir.clone()),
-            fuzzy: FuzzySearchResultManager::new(memory_buffer_size, cache_dir),
+            fuzzy: FuzzySearchResultManager::new(memory_buffer_size, cache_dir.clone()),
+            snapshots: BTreeMap::new(),
+            cache_dir,
+            next_snapshot_id: 0,
         }
     }
 
@@ -196,4 +206,30 @@ impl SearchResultManager {
         }
         self.fuzzy.replace_all(results)
     }
+
+    /// 保存命名快照，同名快照被替换。`items` 为拍快照时读到的值
+    pub fn save_snapshot(&mut self, name: &str, items: Vec<FuzzySearchResultItem>) -> Result<usize> {
+        let file_name = format!("value_snapshot_{}", self.next_snapshot_id);
+        self.next_snapshot_id += 1;
+
+        let snapshot = ValueSnapshot::create(&self.cache_dir, &file_name, items)?;
+        let count = snapshot.len();
+        self.snapshots.insert(name.to_string(), snapshot);
+        info!("Saved value snapshot '{}' with {} values", name, count);
+        Ok(count)
+    }
+
+    pub fn snapshot(&self, name: &str) -> Option<&ValueSnapshot> {
+        self.snapshots.get(name)
+    }
+
+    /// 删除命名快照及其文件
+    pub fn remove_snapshot(&mut self, name: &str) -> bool {
+        self.snapshots.remove(name).is_some()
+    }
+
+    /// 全部快照的名称和值数量，按名称排序
+    pub fn snapshot_names(&self) -> Vec<(String, usize)> {
+        self.snapshots.iter().map(|(name, snapshot)| (name.clone(), snapshot.len())).collect()
+    }
 }
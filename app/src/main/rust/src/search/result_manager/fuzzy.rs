@@ -1,5 +1,5 @@
 use crate::search::FuzzyCondition;
-use crate::search::types::ValueType;
+use crate::search::types::{VEC3_EPSILON, ValueType};
 use anyhow::{Result, anyhow};
 use log::{debug, info};
 use memmap2::MmapMut;
@@ -9,15 +9,15 @@ use std::mem::size_of;
 use std::path::PathBuf;
 
 /// 模糊搜索结果项 - 存储地址和当前值
-/// 使用 [u8; 8] 存储值（最大类型 Qword/Double 刚好 8 字节）
+/// 使用 [u8; 12] 存储值（最大类型 Vec3 为 12 字节）
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
 pub struct FuzzySearchResultItem {
     pub address: u64,          // 8 bytes
-    pub value: [u8; 8],        // 8 bytes - 原始字节存储
+    pub value: [u8; 12],       // 12 bytes - 原始字节存储
     pub value_type: ValueType, // 1 byte
 }
-// 总共 17 字节 (packed)
+// 总共 21 字节 (packed)
 
 // 为 packed 结构体手动实现比较 trait（按地址排序）
 impl PartialEq for FuzzySearchResultItem {
@@ -50,15 +50,15 @@ impl Ord for FuzzySearchResultItem {
 
 impl FuzzySearchResultItem {
     #[inline]
-    pub fn new(address: u64, value: [u8; 8], value_type: ValueType) -> Self {
+    pub fn new(address: u64, value: [u8; 12], value_type: ValueType) -> Self {
         FuzzySearchResultItem { address, value, value_type }
     }
 
     /// 从字节切片创建结果项
     #[inline]
     pub fn from_bytes(address: u64, bytes: &[u8], value_type: ValueType) -> Self {
-        let mut value = [0u8; 8];
-        let len = bytes.len().min(8);
+        let mut value = [0u8; 12];
+        let len = bytes.len().min(12);
         value[..len].copy_from_slice(&bytes[..len]);
         FuzzySearchResultItem { address, value, value_type }
     }
@@ -76,31 +76,42 @@ impl FuzzySearchResultItem {
             ValueType::Byte => self.value[0] as i8 as i64,
             ValueType::Word => i16::from_le_bytes(self.value[..2].try_into().unwrap()) as i64,
             ValueType::Dword | ValueType::Auto | ValueType::Xor => i32::from_le_bytes(self.value[..4].try_into().unwrap()) as i64,
-            ValueType::Qword => i64::from_le_bytes(self.value),
+            ValueType::Qword => i64::from_le_bytes(self.value[..8].try_into().unwrap()),
             ValueType::Float => f32::from_le_bytes(self.value[..4].try_into().unwrap()) as i64,
-            ValueType::Double => f64::from_le_bytes(self.value) as i64,
+            ValueType::Double => f64::from_le_bytes(self.value[..8].try_into().unwrap()) as i64,
+            ValueType::Vec3 => self.as_f64() as i64,
         }
     }
 
-    /// 读取为 f64 值（用于浮点数比较）
+    /// 读取为 f64 值（用于浮点数比较），Vec3 为向量长度
     #[inline]
     pub fn as_f64(&self) -> f64 {
         match self.value_type {
             ValueType::Byte => self.value[0] as i8 as f64,
             ValueType::Word => i16::from_le_bytes(self.value[..2].try_into().unwrap()) as f64,
             ValueType::Dword | ValueType::Auto | ValueType::Xor => i32::from_le_bytes(self.value[..4].try_into().unwrap()) as f64,
-            ValueType::Qword => i64::from_le_bytes(self.value) as f64,
+            ValueType::Qword => i64::from_le_bytes(self.value[..8].try_into().unwrap()) as f64,
             ValueType::Float => f32::from_le_bytes(self.value[..4].try_into().unwrap()) as f64,
-            ValueType::Double => f64::from_le_bytes(self.value),
+            ValueType::Double => f64::from_le_bytes(self.value[..8].try_into().unwrap()),
+            ValueType::Vec3 => self.as_vec3().iter().map(|&c| c as f64 * c as f64).sum::<f64>().sqrt(),
         }
     }
 
+    /// 读取为三个 f32 分量
+    #[inline]
+    pub fn as_vec3(&self) -> [f32; 3] {
+        let value = self.value;
+        std::array::from_fn(|i| f32::from_le_bytes(value[i * 4..i * 4 + 4].try_into().unwrap()))
+    }
+
     /// 检查新值是否满足模糊搜索条件
     #[inline]
     pub fn matches_condition(&self, new_bytes: &[u8], condition: FuzzyCondition) -> bool {
         let new_item = FuzzySearchResultItem::from_bytes(self.address, new_bytes, self.value_type);
 
-        if self.value_type.is_float_type() {
+        if self.value_type.is_vector() {
+            self.matches_condition_vector(&new_item, condition)
+        } else if self.value_type.is_float_type() {
             self.matches_condition_float(&new_item, condition)
         } else {
             self.matches_condition_int(&new_item, condition)
@@ -182,6 +193,19 @@ impl FuzzySearchResultItem {
         }
     }
 
+    /// 未改变/已改变按分量比较，容差为 [`VEC3_EPSILON`]，其余条件比较向量长度
+    fn matches_condition_vector(&self, new_item: &FuzzySearchResultItem, condition: FuzzyCondition) -> bool {
+        let old = self.as_vec3();
+        let new = new_item.as_vec3();
+        let unchanged = old.iter().zip(&new).all(|(a, b)| (a - b).abs() <= VEC3_EPSILON);
+
+        match condition {
+            FuzzyCondition::Unchanged => unchanged,
+            FuzzyCondition::Changed => !unchanged,
+            _ => self.matches_condition_float(new_item, condition),
+        }
+    }
+
     /// 更新值（用于细化搜索后保存新值）
     pub fn with_new_value(&self, new_bytes: &[u8]) -> Self {
         FuzzySearchResultItem::from_bytes(self.address, new_bytes, self.value_type)
@@ -0,0 +1,60 @@
+//! 命名数值快照
+//!
+//! 快照记录拍下时每个结果的地址和值，之后的改善可以和快照比较（自快照以来变化 / 未变化），
+//! 而不是和上一次改善比较。快照按 (地址, 类型) 排序写入 cache 目录下的 `MmapQueue`，查找时二分。
+
+use crate::pointer_scan::storage::MmapQueue;
+use crate::search::engine::ValuePair;
+use crate::search::engine::fuzzy_spill::SpillRecord;
+use crate::search::result_manager::fuzzy::FuzzySearchResultItem;
+use anyhow::{Result, anyhow};
+use std::cmp::Ordering;
+use std::path::Path;
+
+pub(crate) struct ValueSnapshot {
+    queue: MmapQueue<SpillRecord>,
+}
+
+impl ValueSnapshot {
+    /// 把 `items` 写入 `cache_dir` 下的 `file_name`，`items` 不要求有序
+    pub fn create(cache_dir: &Path, file_name: &str, mut items: Vec<FuzzySearchResultItem>) -> Result<Self> {
+        items.sort_unstable_by_key(|item| (item.address, item.value_type.to_id()));
+
+        let mut queue = MmapQueue::new_fixed(cache_dir, file_name)?;
+        for item in &items {
+            queue.push(&SpillRecord::from(item))?;
+        }
+        Ok(Self { queue })
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// 快照中 `pair` 的值，快照里没有该地址和类型时返回 None
+    pub fn get(&self, pair: &ValuePair) -> Result<Option<FuzzySearchResultItem>> {
+        let key = (pair.addr, pair.value_type.to_id());
+        let (mut lo, mut hi) = (0, self.queue.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let record = self.queue.get(mid).ok_or_else(|| anyhow!("Snapshot record {} out of range", mid))?;
+            match record.key().cmp(&key) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return record.to_fuzzy().map(Some),
+            }
+        }
+        Ok(None)
+    }
+
+    /// `pairs` 在快照中的旧值，快照里没有的地址被跳过
+    pub fn values_for(&self, pairs: &[ValuePair]) -> Result<Vec<FuzzySearchResultItem>> {
+        let mut items = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            if let Some(item) = self.get(pair)? {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+}
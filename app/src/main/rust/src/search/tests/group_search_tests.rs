@@ -1248,7 +1248,7 @@ mod tests {
                     anchor_index = Some(idx);
                     break;
                 }
-                SearchValue::FixedFloat { value, value_type } => {
+                SearchValue::FixedFloat { value, value_type, .. } => {
                     let size = value_type.size();
                     match value_type {
                         ValueType::Float => {
@@ -11,8 +11,13 @@ pub enum ValueType {
     Double,
     Auto,
     Xor,
+    /// 三个连续的 f32，常见于坐标、速度等向量
+    Vec3,
 }
 
+/// Vec3 每个分量的默认容差
+pub const VEC3_EPSILON: f32 = 1e-4;
+
 impl ValueType {
     #[inline]
     pub fn from_id(id: i32) -> Option<Self> {
@@ -25,6 +30,7 @@ impl ValueType {
             5 => Self::Double.into(),
             6 => Self::Auto.into(),
             7 => Self::Xor.into(),
+            8 => Self::Vec3.into(),
             _ => None,
         }
     }
@@ -40,6 +46,7 @@ impl ValueType {
             ValueType::Double => 5,
             ValueType::Auto => 6,
             ValueType::Xor => 7,
+            ValueType::Vec3 => 8,
         }
     }
 
@@ -54,6 +61,7 @@ impl ValueType {
             'E' => Some(ValueType::Double),
             'A' => Some(ValueType::Auto),
             'X' => Some(ValueType::Xor),
+            'V' => Some(ValueType::Vec3),
             _ => None,
         }
     }
@@ -69,6 +77,16 @@ impl ValueType {
             ValueType::Double => 8,
             ValueType::Auto => 4,
             ValueType::Xor => 4,
+            ValueType::Vec3 => 12,
+        }
+    }
+
+    /// 扫描时的对齐和步长，Vec3 按分量对齐，其余类型等于 [`Self::size`]
+    #[inline]
+    pub fn alignment(&self) -> usize {
+        match self {
+            ValueType::Vec3 => 4,
+            _ => self.size(),
         }
     }
 
@@ -76,6 +94,11 @@ impl ValueType {
     pub fn is_float_type(&self) -> bool {
         matches!(self, ValueType::Float | ValueType::Double)
     }
+
+    #[inline]
+    pub fn is_vector(&self) -> bool {
+        matches!(self, ValueType::Vec3)
+    }
 }
 
 impl fmt::Display for ValueType {
@@ -89,6 +112,45 @@ impl fmt::Display for ValueType {
             ValueType::Double => write!(f, "Double"),
             ValueType::Auto => write!(f, "Auto"),
             ValueType::Xor => write!(f, "Xor"),
+            ValueType::Vec3 => write!(f, "Vec3"),
+        }
+    }
+}
+
+/// 浮点精确搜索的比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FloatCompare {
+    /// 差小于 `f64::EPSILON`，float 需要与存储值完全一致
+    #[default]
+    Exact,
+    /// `|x - v| <= epsilon`
+    Absolute(f64),
+    /// `|x - v| <= epsilon * max(|x|, |v|)`
+    Relative(f64),
+    /// x 截断为整数后等于 v 的整数部分，例如 100 匹配 100.0 至 100.99
+    Truncated,
+}
+
+impl FloatCompare {
+    /// 从 ID 转换 (用于 JNI)，容差须为非负有限值
+    pub fn from_id(id: i32, epsilon: f64) -> Option<Self> {
+        let epsilon = (epsilon.is_finite() && epsilon >= 0.0).then_some(epsilon);
+        match id {
+            0 => Some(FloatCompare::Exact),
+            1 => Some(FloatCompare::Absolute(epsilon?)),
+            2 => Some(FloatCompare::Relative(epsilon?)),
+            3 => Some(FloatCompare::Truncated),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn matches(&self, target: f64, other: f64) -> bool {
+        match *self {
+            FloatCompare::Exact => (target - other).abs() < f64::EPSILON,
+            FloatCompare::Absolute(epsilon) => (target - other).abs() <= epsilon,
+            FloatCompare::Relative(epsilon) => (target - other).abs() <= epsilon * target.abs().max(other.abs()),
+            FloatCompare::Truncated => other.trunc() == target.trunc(),
         }
     }
 }
@@ -103,6 +165,7 @@ pub enum SearchValue {
     FixedFloat {
         value: f64,
         value_type: ValueType,
+        compare: FloatCompare,
     },
     /// 范围搜索，存储起始和结束的字节表示
     RangeInt {
@@ -117,6 +180,11 @@ pub enum SearchValue {
         value_type: ValueType,
         exclude: bool,
     },
+    /// Vec3 精确搜索，每个分量的差不超过 `epsilon` 即匹配
+    FixedVector {
+        value: [f32; 3],
+        epsilon: f32,
+    },
 }
 
 impl SearchValue {
@@ -130,7 +198,16 @@ impl SearchValue {
 
     #[inline]
     pub fn fixed_float(value: f64, value_type: ValueType) -> Self {
-        SearchValue::FixedFloat { value, value_type }
+        SearchValue::FixedFloat {
+            value,
+            value_type,
+            compare: FloatCompare::Exact,
+        }
+    }
+
+    #[inline]
+    pub fn fixed_vector(value: [f32; 3], epsilon: f32) -> Self {
+        SearchValue::FixedVector { value, epsilon }
     }
 
     #[inline]
@@ -160,12 +237,13 @@ impl SearchValue {
             SearchValue::RangeInt { value_type, .. } => *value_type,
             SearchValue::FixedFloat { value_type, .. } => *value_type,
             SearchValue::RangeFloat { value_type, .. } => *value_type,
+            SearchValue::FixedVector { .. } => ValueType::Vec3,
         }
     }
 
     #[inline]
     pub fn is_fixed(&self) -> bool {
-        matches!(self, SearchValue::FixedInt { .. } | SearchValue::FixedFloat { .. })
+        matches!(self, SearchValue::FixedInt { .. } | SearchValue::FixedFloat { .. } | SearchValue::FixedVector { .. })
     }
 
     #[inline]
@@ -199,7 +277,7 @@ impl SearchValue {
                 }
                 Ok(&value[..size] == &other[..size])
             },
-            SearchValue::FixedFloat { value, value_type } => {
+            SearchValue::FixedFloat { value, value_type, compare } => {
                 let size = value_type.size();
                 if other.len() < size {
                     return Err(anyhow!("Input slice too small: expected at least {} bytes, got {}", size, other.len()));
@@ -215,7 +293,7 @@ impl SearchValue {
                     },
                     _ => return Err(anyhow!("Invalid float size: {}", size)),
                 };
-                Ok((*value - other_value).abs() < f64::EPSILON)
+                Ok(compare.matches(*value, other_value))
             },
             SearchValue::RangeInt {
                 start,
@@ -280,6 +358,15 @@ impl SearchValue {
                     Ok(other_value >= *start && other_value <= *end)
                 }
             },
+            SearchValue::FixedVector { value, epsilon } => {
+                if other.len() < 12 {
+                    return Err(anyhow!("Input slice too small: expected at least 12 bytes, got {}", other.len()));
+                }
+                Ok(value.iter().zip(other[..12].chunks_exact(4)).all(|(component, bytes)| {
+                    let other_component = f32::from_le_bytes(bytes.try_into().unwrap());
+                    (component - other_component).abs() <= *epsilon
+                }))
+            },
         }
     }
 }
@@ -347,12 +434,40 @@ pub struct SearchQuery {
     pub values: Vec<SearchValue>,
     pub mode: SearchMode,
     pub range: u16,
+    /// 逐字节扫描，可以找到紧凑结构体中或字段中间的值，比较次数是按类型对齐时的 [`ValueType::alignment`] 倍
+    pub unaligned: bool,
 }
 
 impl SearchQuery {
     #[inline]
     pub fn new(values: Vec<SearchValue>, mode: SearchMode, range: u16) -> Self {
-        SearchQuery { values, mode, range }
+        SearchQuery {
+            values,
+            mode,
+            range,
+            unaligned: false,
+        }
+    }
+
+    /// 为全部浮点定值设置比较方式
+    pub fn with_float_compare(mut self, compare: FloatCompare) -> Self {
+        for value in &mut self.values {
+            if let SearchValue::FixedFloat { compare: current, .. } = value {
+                *current = compare;
+            }
+        }
+        self
+    }
+
+    pub fn with_unaligned(mut self, unaligned: bool) -> Self {
+        self.unaligned = unaligned;
+        self
+    }
+
+    /// 扫描 `value_type` 时的步长，逐字节扫描时为 1
+    #[inline]
+    pub fn stride(&self, value_type: ValueType) -> usize {
+        if self.unaligned { 1 } else { value_type.alignment() }
     }
 
     pub fn total_size(&self) -> usize {
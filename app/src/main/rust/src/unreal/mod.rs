@@ -0,0 +1,306 @@
+//! Unreal Engine 分析
+//!
+//! 在 libUE4.so 的数据段中定位 FNamePool 和 GUObjectArray，之后经驱动读取：
+//! 按名字搜索 UObject，沿 SuperStruct 展开类层级，列出 UClass/UScriptStruct 的属性偏移。
+//! 支持 UE 4.23+ 和 UE5 的 64 位构建；属性在 4.25 前为 UProperty 对象，之后为 FField，两种都会尝试。
+//! 结构偏移取引擎默认布局，个别游戏改过引擎时可能无法识别。
+
+mod names;
+mod objects;
+
+use crate::core::driver_manager::DriverManager;
+use crate::core::elf::RemoteElf;
+use crate::core::image_memory::ImageMemory;
+use crate::core::modules::find_module;
+use anyhow::{anyhow, Result};
+use log::info;
+use names::NamePool;
+use objects::ObjectArray;
+
+/// 去掉 arm64 TBI/MTE 标签字节
+const POINTER_MASK: u64 = 0x00FF_FFFF_FFFF_FFFF;
+
+// UObjectBase
+const OBJECT_INTERNAL_INDEX: u64 = 0x0C;
+const OBJECT_CLASS: u64 = 0x10;
+const OBJECT_NAME: u64 = 0x18;
+const OBJECT_OUTER: u64 = 0x20;
+const OBJECT_HEADER_SIZE: usize = 0x28;
+
+// UStruct，UField 之后是 FStructBaseChain
+const STRUCT_SUPER: u64 = 0x40;
+const STRUCT_CHILDREN: u64 = 0x48;
+const STRUCT_CHILD_PROPERTIES: u64 = 0x50;
+
+// UField / UProperty（4.25 之前）
+const UFIELD_NEXT: u64 = 0x28;
+const UPROPERTY_ARRAY_DIM: u64 = 0x30;
+const UPROPERTY_OFFSET: u64 = 0x44;
+
+// FField / FProperty（4.25 起）
+const FFIELD_CLASS: u64 = 0x08;
+const FFIELD_NEXT: u64 = 0x20;
+const FFIELD_NAME: u64 = 0x28;
+const FPROPERTY_ARRAY_DIM: u64 = 0x38;
+const FPROPERTY_OFFSET: u64 = 0x4C;
+
+/// 链表遍历的上限，防止读到错误数据时死循环
+const MAX_CHAIN: usize = 4096;
+/// 继承层级的上限
+const MAX_SUPER_DEPTH: usize = 64;
+/// 搜索对象时每批读取的对象头个数
+const OBJECT_BATCH: usize = 4096;
+
+#[inline]
+fn untag(pointer: u64) -> u64 {
+    pointer & POINTER_MASK
+}
+
+fn read_u64(manager: &DriverManager, address: u64) -> Option<u64> {
+    let mut buf = [0u8; 8];
+    manager.read_memory_unified(address, &mut buf, None).ok()?;
+    Some(u64::from_le_bytes(buf))
+}
+
+fn read_u32(manager: &DriverManager, address: u64) -> Option<u32> {
+    let mut buf = [0u8; 4];
+    manager.read_memory_unified(address, &mut buf, None).ok()?;
+    Some(u32::from_le_bytes(buf))
+}
+
+fn read_pointer(manager: &DriverManager, address: u64) -> Option<u64> {
+    read_u64(manager, address).map(untag).filter(|&pointer| pointer != 0)
+}
+
+/// UObject
+#[derive(Debug, Clone)]
+pub struct UObjectInfo {
+    /// GUObjectArray 中的槽位
+    pub index: u32,
+    pub address: u64,
+    pub name: String,
+    pub class_name: String,
+    pub class_address: u64,
+    /// 外层对象（通常是所在 Package）的名字
+    pub outer: Option<String>,
+}
+
+/// 属性
+#[derive(Debug, Clone)]
+pub struct UPropertyInfo {
+    /// 声明该属性的类或结构
+    pub owner: String,
+    pub name: String,
+    /// 属性类，如 `IntProperty`、`ObjectProperty`
+    pub type_name: String,
+    /// 相对对象起始的偏移
+    pub offset: i32,
+    /// 静态数组长度，普通属性为 1
+    pub array_dim: i32,
+}
+
+/// 对一个进程的分析会话
+pub struct UnrealSession {
+    /// 分析时绑定的进程
+    pub pid: i32,
+    pub module_path: String,
+    pub module_base: u64,
+    pub names_address: u64,
+    pub objects_address: u64,
+    names: NamePool,
+    objects: ObjectArray,
+}
+
+impl UnrealSession {
+    /// 定位绑定进程中的名字池和对象表，`module` 为引擎模块的文件名或路径
+    pub fn attach(manager: &DriverManager, module: &str) -> Result<Self> {
+        if !manager.is_process_bound() {
+            return Err(anyhow!("No process bound"));
+        }
+
+        let module = find_module(manager, module)?;
+        let image = RemoteElf::read(manager, module.base).ok_or_else(|| anyhow!("{} is not a valid ELF image", module.path))?;
+        let memory = ImageMemory::read(manager, &image);
+        let names = NamePool::locate(manager, &memory)?;
+        let objects = ObjectArray::locate(manager, &memory)?;
+
+        let session = Self {
+            pid: manager.get_bound_pid(),
+            module_path: module.path,
+            module_base: module.base,
+            names_address: names.address(),
+            objects_address: objects.address,
+            names,
+            objects,
+        };
+        info!(
+            "Unreal: FNamePool @ 0x{:X}, GUObjectArray @ 0x{:X}, {} 个对象槽位",
+            session.names_address,
+            session.objects_address,
+            session.objects.count(manager)
+        );
+        Ok(session)
+    }
+
+    /// 当前的对象槽位数
+    pub fn object_count(&self, manager: &DriverManager) -> u32 {
+        self.objects.count(manager)
+    }
+
+    /// 按 ComparisonIndex 取名字
+    pub fn name(&mut self, manager: &DriverManager, index: u32) -> Option<String> {
+        self.names.resolve(manager, index)
+    }
+
+    /// 解析位于 `address` 的 FName，Number 不为 0 时追加 `_N`
+    fn fname(&mut self, manager: &DriverManager, address: u64) -> Option<String> {
+        let value = read_u64(manager, address)?;
+        self.fname_value(manager, value)
+    }
+
+    fn fname_value(&mut self, manager: &DriverManager, value: u64) -> Option<String> {
+        let name = self.names.resolve(manager, value as u32)?;
+        match (value >> 32) as u32 {
+            0 => Some(name),
+            number => Some(format!("{}_{}", name, number - 1)),
+        }
+    }
+
+    fn object_name(&mut self, manager: &DriverManager, object: u64) -> Option<String> {
+        self.fname(manager, object + OBJECT_NAME)
+    }
+
+    /// 按对象名查找（不区分大小写的子串匹配），`query` 为空时按槽位顺序返回，最多 `max` 个
+    pub fn find_objects(&mut self, manager: &DriverManager, query: &str, max: usize) -> Vec<UObjectInfo> {
+        let query = query.to_lowercase();
+        let mut found = Vec::new();
+        for batch in self.objects.objects(manager).chunks(OBJECT_BATCH) {
+            let reads: Vec<(u64, usize)> = batch.iter().map(|&(_, address)| (address, OBJECT_HEADER_SIZE)).collect();
+            for (&(index, address), header) in batch.iter().zip(manager.read_memory_batch(&reads)) {
+                let Ok(header) = header else {
+                    continue;
+                };
+                let word = |offset: u64| u64::from_le_bytes(header[offset as usize..offset as usize + 8].try_into().unwrap());
+                let Some(name) = self.fname_value(manager, word(OBJECT_NAME)) else {
+                    continue;
+                };
+                if !query.is_empty() && !name.to_lowercase().contains(&query) {
+                    continue;
+                }
+
+                let class_address = untag(word(OBJECT_CLASS));
+                let class_name = self.object_name(manager, class_address).unwrap_or_default();
+                let outer = match untag(word(OBJECT_OUTER)) {
+                    0 => None,
+                    outer => self.object_name(manager, outer),
+                };
+                found.push(UObjectInfo {
+                    index,
+                    address,
+                    name,
+                    class_name,
+                    class_address,
+                    outer,
+                });
+                if found.len() >= max {
+                    return found;
+                }
+            }
+        }
+        found
+    }
+
+    /// 对象所属类及其全部基类的名字，从派生到基类排列
+    pub fn class_hierarchy(&mut self, manager: &DriverManager, object: u64) -> Vec<String> {
+        let mut hierarchy = Vec::new();
+        let mut class = read_pointer(manager, object + OBJECT_CLASS);
+        while let Some(address) = class
+            && hierarchy.len() < MAX_SUPER_DEPTH
+        {
+            let Some(name) = self.object_name(manager, address) else {
+                break;
+            };
+            hierarchy.push(name);
+            class = read_pointer(manager, address + STRUCT_SUPER);
+        }
+        hierarchy
+    }
+
+    /// UClass/UScriptStruct 的全部属性，包括继承的，基类的在前
+    pub fn properties(&mut self, manager: &DriverManager, structure: u64) -> Vec<UPropertyInfo> {
+        let mut chain = Vec::new();
+        let mut current = Some(structure);
+        while let Some(address) = current
+            && chain.len() < MAX_SUPER_DEPTH
+        {
+            chain.push(address);
+            current = read_pointer(manager, address + STRUCT_SUPER);
+        }
+
+        let mut properties = Vec::new();
+        for &address in chain.iter().rev() {
+            let owner = self.object_name(manager, address).unwrap_or_default();
+            let mut own = self.field_properties(manager, address, &owner);
+            if own.is_empty() {
+                own = self.object_properties(manager, address, &owner);
+            }
+            properties.extend(own);
+        }
+        properties
+    }
+
+    /// 4.25 起的 FProperty 链，从 ChildProperties 开始
+    fn field_properties(&mut self, manager: &DriverManager, structure: u64, owner: &str) -> Vec<UPropertyInfo> {
+        let mut properties = Vec::new();
+        let mut field = read_pointer(manager, structure + STRUCT_CHILD_PROPERTIES);
+        while let Some(address) = field
+            && properties.len() < MAX_CHAIN
+        {
+            // 4.25 之前这里是 PropertiesSize，读出的不是属性
+            let type_name = read_pointer(manager, address + FFIELD_CLASS).and_then(|class| self.fname(manager, class));
+            let (Some(type_name), Some(name)) = (type_name, self.fname(manager, address + FFIELD_NAME)) else {
+                break;
+            };
+            if !type_name.ends_with("Property") {
+                break;
+            }
+            properties.push(UPropertyInfo {
+                owner: owner.to_string(),
+                name,
+                type_name,
+                offset: read_u32(manager, address + FPROPERTY_OFFSET).unwrap_or(0) as i32,
+                array_dim: read_u32(manager, address + FPROPERTY_ARRAY_DIM).unwrap_or(1) as i32,
+            });
+            field = read_pointer(manager, address + FFIELD_NEXT);
+        }
+        properties
+    }
+
+    /// 4.25 之前的 UProperty，混在 Children 链中，按类名筛出
+    fn object_properties(&mut self, manager: &DriverManager, structure: u64, owner: &str) -> Vec<UPropertyInfo> {
+        let mut properties = Vec::new();
+        let mut field = read_pointer(manager, structure + STRUCT_CHILDREN);
+        let mut visited = 0;
+        while let Some(address) = field
+            && visited < MAX_CHAIN
+        {
+            visited += 1;
+            field = read_pointer(manager, address + UFIELD_NEXT);
+            let type_name = read_pointer(manager, address + OBJECT_CLASS).and_then(|class| self.object_name(manager, class));
+            let Some(type_name) = type_name.filter(|type_name| type_name.ends_with("Property")) else {
+                continue;
+            };
+            let Some(name) = self.object_name(manager, address) else {
+                continue;
+            };
+            properties.push(UPropertyInfo {
+                owner: owner.to_string(),
+                name,
+                type_name,
+                offset: read_u32(manager, address + UPROPERTY_OFFSET).unwrap_or(0) as i32,
+                array_dim: read_u32(manager, address + UPROPERTY_ARRAY_DIM).unwrap_or(1) as i32,
+            });
+        }
+        properties
+    }
+}
@@ -0,0 +1,128 @@
+//! FNamePool（UE 4.23+ / UE5）
+//!
+//! FName 的 ComparisonIndex 高 16 位为块号，低 16 位乘以条目对齐为块内偏移。
+//! 每个 FNameEntry 以 2 字节头开始：最低位表示宽字符，长度位于高位，
+//! 常见布局为 `bIsWide:1, LowercaseProbeHash:5, Len:10`，部分构建去掉了哈希位（`Len:15`）。
+//! 池中第一个名字固定是 "None"，据此在数据段中定位 `FNameEntryAllocator`。
+
+use super::untag;
+use crate::core::driver_manager::DriverManager;
+use crate::core::image_memory::ImageMemory;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// FNameEntryAllocator::Blocks 的容量
+const MAX_BLOCKS: usize = 8192;
+/// 块内偏移单位，即 alignof(FNameEntry)
+const ENTRY_STRIDE: u64 = 2;
+const BLOCK_SIZE: u64 = ENTRY_STRIDE << 16;
+/// 名字长度上限（NAME_SIZE）
+const MAX_NAME_LEN: usize = 1024;
+
+pub struct NamePool {
+    /// FNameEntryAllocator::Blocks 的地址
+    blocks_address: u64,
+    blocks: Vec<u64>,
+    /// 条目头中长度字段的位移
+    len_shift: u32,
+    cache: HashMap<u32, String>,
+}
+
+/// 根据 "None" 条目的头判断长度字段的位置
+fn len_shift_of_none(entry: &[u8]) -> Option<u32> {
+    if entry.get(2..6)? != b"None" {
+        return None;
+    }
+    let header = u16::from_le_bytes([entry[0], entry[1]]);
+    if header & 1 != 0 {
+        None
+    } else if header >> 6 == 4 {
+        Some(6)
+    } else if header == 4 << 1 {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+impl NamePool {
+    /// 在模块数据段中查找 `{CurrentBlock, CurrentByteCursor, Blocks[..]}`
+    pub fn locate(manager: &DriverManager, memory: &ImageMemory) -> Result<Self> {
+        memory
+            .scan::<3, _>(|address, [cursor, first, second]| {
+                let current_block = cursor & 0xFFFF_FFFF;
+                let byte_cursor = cursor >> 32;
+                if current_block >= MAX_BLOCKS as u64 || byte_cursor == 0 || byte_cursor > BLOCK_SIZE {
+                    return None;
+                }
+                // 已用的块都分配过，之后的必为空
+                if first == 0 || (current_block == 0) != (second == 0) || memory.is_data_pointer(untag(first)) {
+                    return None;
+                }
+
+                let mut entry = [0u8; 6];
+                manager.read_memory_unified(untag(first), &mut entry, None).ok()?;
+                let len_shift = len_shift_of_none(&entry)?;
+                let mut pool = Self {
+                    blocks_address: address + 8,
+                    blocks: Vec::new(),
+                    len_shift,
+                    cache: HashMap::new(),
+                };
+                pool.refresh(manager);
+                Some(pool)
+            })
+            .ok_or_else(|| anyhow!("FNamePool not found"))
+    }
+
+    /// FNameEntryAllocator::Blocks 的地址
+    pub fn address(&self) -> u64 {
+        self.blocks_address
+    }
+
+    /// 重新读取块指针表，名字池增长后新块才可见
+    fn refresh(&mut self, manager: &DriverManager) {
+        let mut table = vec![0u8; MAX_BLOCKS * 8];
+        if manager.read_memory_unified(self.blocks_address, &mut table, None).is_ok() {
+            self.blocks = table
+                .chunks_exact(8)
+                .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+                .take_while(|&block| block != 0)
+                .collect();
+        }
+    }
+
+    /// 按 ComparisonIndex 取名字，结果会被缓存
+    pub fn resolve(&mut self, manager: &DriverManager, index: u32) -> Option<String> {
+        if let Some(name) = self.cache.get(&index) {
+            return Some(name.clone());
+        }
+
+        let block = (index >> 16) as usize;
+        if block >= self.blocks.len() {
+            self.refresh(manager);
+        }
+        let entry = untag(*self.blocks.get(block)?) + (index & 0xFFFF) as u64 * ENTRY_STRIDE;
+
+        let mut header = [0u8; 2];
+        manager.read_memory_unified(entry, &mut header, None).ok()?;
+        let header = u16::from_le_bytes(header);
+        let wide = header & 1 != 0;
+        let len = (header >> self.len_shift) as usize;
+        if len == 0 || len > MAX_NAME_LEN {
+            return None;
+        }
+
+        let mut bytes = vec![0u8; if wide { len * 2 } else { len }];
+        manager.read_memory_unified(entry + 2, &mut bytes, None).ok()?;
+        let name = if wide {
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            // ANSI 条目为 Latin-1
+            bytes.iter().map(|&b| b as char).collect()
+        };
+        self.cache.insert(index, name.clone());
+        Some(name)
+    }
+}
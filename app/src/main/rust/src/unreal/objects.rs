@@ -0,0 +1,102 @@
+//! GUObjectArray（FUObjectArray）
+//!
+//! `ObjObjects` 为 FChunkedFixedUObjectArray：`Objects` 指向块指针表，每块 64K 个 FUObjectItem。
+//! 定位依据是其中计数字段的互相约束：NumElements ≤ MaxElements，
+//! MaxChunks、NumChunks 分别等于两者按块大小向上取整，在数据段中几乎不会偶然成立。
+
+use super::{read_u32, read_u64, untag, OBJECT_INTERNAL_INDEX};
+use crate::core::driver_manager::DriverManager;
+use crate::core::image_memory::ImageMemory;
+use anyhow::{anyhow, Result};
+
+const CHUNK_ELEMENTS: u64 = 64 * 1024;
+/// MaxElements 的上限，超出视为误匹配
+const MAX_ELEMENTS: u64 = 1 << 26;
+/// FUObjectArray 中 ObjObjects 的偏移
+const OBJ_OBJECTS_OFFSET: u64 = 0x10;
+/// ObjObjects 中 NumElements 的偏移
+const NUM_ELEMENTS_OFFSET: u64 = 0x14;
+/// FUObjectItem 的常见大小：Object、Flags、ClusterRootIndex、SerialNumber 为 24，
+/// 部分构建去掉了 SerialNumber 为 16
+const ITEM_SIZES: [u64; 2] = [24, 16];
+
+pub struct ObjectArray {
+    /// FUObjectArray 的地址
+    pub address: u64,
+    /// ObjObjects 的地址
+    objects: u64,
+    item_size: u64,
+}
+
+impl ObjectArray {
+    /// 在模块数据段中查找 `{Objects, PreAllocatedObjects, MaxElements, NumElements, MaxChunks, NumChunks}`
+    pub fn locate(manager: &DriverManager, memory: &ImageMemory) -> Result<Self> {
+        memory
+            .scan::<4, _>(|address, [table, _, elements, chunks]| {
+                let (max_elements, num_elements) = (elements & 0xFFFF_FFFF, elements >> 32);
+                let (max_chunks, num_chunks) = (chunks & 0xFFFF_FFFF, chunks >> 32);
+                if table == 0 || num_elements == 0 || num_elements > max_elements || max_elements > MAX_ELEMENTS {
+                    return None;
+                }
+                if max_chunks != max_elements.div_ceil(CHUNK_ELEMENTS) || num_chunks != num_elements.div_ceil(CHUNK_ELEMENTS) {
+                    return None;
+                }
+
+                // 前几个对象的 InternalIndex 应与所在槽位一致
+                ITEM_SIZES
+                    .into_iter()
+                    .map(|item_size| Self {
+                        address: address - OBJ_OBJECTS_OFFSET,
+                        objects: address,
+                        item_size,
+                    })
+                    .find(|array| {
+                        (1..4).all(|index| {
+                            array
+                                .object_at(manager, index)
+                                .is_some_and(|object| read_u32(manager, object + OBJECT_INTERNAL_INDEX) == Some(index))
+                        })
+                    })
+            })
+            .ok_or_else(|| anyhow!("GUObjectArray not found"))
+    }
+
+    /// 当前的对象槽位数（含已释放的空槽）
+    pub fn count(&self, manager: &DriverManager) -> u32 {
+        read_u32(manager, self.objects + NUM_ELEMENTS_OFFSET).unwrap_or(0)
+    }
+
+    fn chunk(&self, manager: &DriverManager, chunk: u64) -> Option<u64> {
+        let table = untag(read_u64(manager, self.objects)?);
+        read_u64(manager, table + chunk * 8).map(untag).filter(|&chunk| chunk != 0)
+    }
+
+    /// 槽位 `index` 上的对象地址，空槽返回 None
+    pub fn object_at(&self, manager: &DriverManager, index: u32) -> Option<u64> {
+        let index = index as u64;
+        let chunk = self.chunk(manager, index / CHUNK_ELEMENTS)?;
+        read_u64(manager, chunk + (index % CHUNK_ELEMENTS) * self.item_size).map(untag).filter(|&object| object != 0)
+    }
+
+    /// 全部 `(槽位, 对象地址)`，按块整块读取
+    pub fn objects(&self, manager: &DriverManager) -> Vec<(u32, u64)> {
+        let count = self.count(manager) as u64;
+        let mut objects = Vec::new();
+        for chunk_index in 0..count.div_ceil(CHUNK_ELEMENTS) {
+            let Some(chunk) = self.chunk(manager, chunk_index) else {
+                continue;
+            };
+            let first = chunk_index * CHUNK_ELEMENTS;
+            let items = (count - first).min(CHUNK_ELEMENTS);
+            let mut bytes = vec![0u8; (items * self.item_size) as usize];
+            if manager.read_memory_unified(chunk, &mut bytes, None).is_err() {
+                continue;
+            }
+            objects.extend(bytes.chunks_exact(self.item_size as usize).enumerate().filter_map(|(i, item)| {
+                let object = untag(u64::from_le_bytes(item[..8].try_into().unwrap()));
+                (object != 0).then_some(((first + i as u64) as u32, object))
+            }));
+        }
+        objects
+    }
+}
@@ -30,6 +30,7 @@
 //! This SDK provides direct physical memory access and kernel-level process manipulation.
 //! Requires root or CAP_NET_RAW. For defensive security research only.
 
+use crate::core::error::{ErrorCode, MamuError};
 use anyhow::anyhow;
 use log::{Level, debug, error, info, log_enabled};
 use nix::errno::Errno;
@@ -41,8 +42,21 @@ use std::ffi::c_void;
 use std::mem::{MaybeUninit, size_of};
 use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, PoisonError, mpsc};
+use std::time::Duration;
+
+/// Structured error for a failed read/write ioctl, call right after the ioctl so errno is still intact
+fn memory_fault(code: ErrorCode, request: Ioctl, va: usize, message: String) -> anyhow::Error {
+    MamuError::new(code, message)
+        .at(va as u64)
+        .with_ioctl(request as u32)
+        .with_errno(Errno::last())
+        .into()
+}
 
 // IOCTL command definitions (magic number 'W')
+
 const WUWA_IOCTL_ADDR_TRANSLATE: Ioctl = _IOWR::<WuwaAddrTranslateCmd>(b'W' as u32, 1);
 const WUWA_IOCTL_DEBUG_INFO: Ioctl = _IOR::<WuwaDebugInfoCmd>(b'W' as u32, 2);
 const WUWA_IOCTL_AT_S1E0R: Ioctl = _IOWR::<WuwaAtS1e0rCmd>(b'W' as u32, 3);
@@ -67,6 +81,8 @@ const WUWA_IOCTL_INSTALL_DRIVER: Ioctl = _IOWR::<WuwaInstallDriverCmd>(b'W' as u
 const WUWA_IOCTL_QUERY_MEM_REGIONS: Ioctl = _IOWR::<WuwaQueryMemRegionsCmd>(b'W' as u32, 22);
 const WUWA_IOCTL_READ_MEMORY: Ioctl = _IOWR::<WuwaReadMemoryCmd>(b'W' as u32, 23);
 const WUWA_IOCTL_WRITE_MEMORY: Ioctl = _IOWR::<WuwaWriteMemoryCmd>(b'W' as u32, 24);
+const WUWA_IOCTL_GET_CAPABILITIES: Ioctl = _IOWR::<WuwaCapabilitiesCmd>(b'W' as u32, 25);
+const WUWA_IOCTL_READ_PHYSICAL_ADDR: Ioctl = _IOWR::<WuwaReadPhysAddrCmd>(b'W' as u32, 26);
 
 // Memory permission flags for memory regions
 pub const MEM_READABLE: u32 = 0b00000000000000000000000000000001;
@@ -83,6 +99,66 @@ pub struct WuwaAddrTranslateCmd {
     pub va: usize,
 }
 
+// Capability handshake: the driver reports its version and a WUWA_CAP_* bitmap.
+// Builds that predate the handshake reject it with ENOTTY; they are assumed to
+// support the baseline set (WUWA_CAP_LEGACY).
+#[repr(C)]
+pub struct WuwaCapabilitiesCmd {
+    pub version: u32,  // Filled by the driver: major << 16 | minor << 8 | patch
+    pub features: u64, // Filled by the driver: WUWA_CAP_* bits
+}
+
+/// BindProc reads report per-page status
+pub const WUWA_CAP_PAGE_STATUS: u64 = 1 << 0;
+/// Physical memory reads/writes and VA->PA translation
+pub const WUWA_CAP_PHYSICAL: u64 = 1 << 1;
+/// Hiding processes and maps entries
+pub const WUWA_CAP_HIDE_MAPS: u64 = 1 << 2;
+/// Hardware watchpoints through BindProc
+pub const WUWA_CAP_WATCHPOINTS: u64 = 1 << 3;
+/// Vectorized BindProc reads/writes
+pub const WUWA_CAP_VECTORIZED: u64 = 1 << 4;
+/// Remote mmap/mprotect/call through BindProc
+pub const WUWA_CAP_REMOTE: u64 = 1 << 5;
+
+/// Features every driver build shipped before the handshake
+pub const WUWA_CAP_LEGACY: u64 = WUWA_CAP_PAGE_STATUS | WUWA_CAP_PHYSICAL | WUWA_CAP_HIDE_MAPS;
+
+/// Driver version and features, as reported by the capability handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriverCapabilities {
+    /// major << 16 | minor << 8 | patch, 0 when the driver predates the handshake
+    pub version: u32,
+    /// WUWA_CAP_* bits
+    pub features: u64,
+}
+
+impl DriverCapabilities {
+    #[inline]
+    pub fn supports(&self, feature: u64) -> bool {
+        self.features & feature == feature
+    }
+
+    /// "major.minor.patch", or "legacy" for drivers without the handshake
+    pub fn version_name(&self) -> String {
+        if self.version == 0 {
+            "legacy".to_string()
+        } else {
+            format!("{}.{}.{}", self.version >> 16, (self.version >> 8) & 0xFF, self.version & 0xFF)
+        }
+    }
+}
+
+// Raw physical read: copies from a physical address through the linear map,
+// no process or page table involved. The driver rejects addresses outside
+// System RAM and reads crossing a page boundary. Older builds reject it with ENOTTY.
+#[repr(C)]
+pub struct WuwaReadPhysAddrCmd {
+    pub phy_addr: u64,
+    pub dst_va: usize, // Userspace buffer
+    pub size: size_t,  // 1..=page size
+}
+
 #[repr(C)]
 pub struct WuwaDebugInfoCmd {
     pub ttbr0_el1: u64,
@@ -372,6 +448,209 @@ const WUWA_BP_IOCTL_SET_MEMORY_PROT: Ioctl = _IOWR::<c_int>(b'B' as u32, 1);
 const WUWA_BP_IOCTL_READ_MEMORY: Ioctl = _IOWR::<BpReadMemoryCmd>(b'B' as u32, 2);
 const WUWA_BP_IOCTL_WRITE_MEMORY: Ioctl = _IOWR::<BpWriteMemoryCmd>(b'B' as u32, 3);
 
+// Vectorized BindProc commands: one ioctl carries many small reads or writes.
+// Only used when the capability handshake reports WUWA_CAP_VECTORIZED; callers
+// fall back to one ioctl per entry otherwise.
+#[repr(C)]
+pub struct BpMemoryVecEntry {
+    pub va: usize,     // Virtual address in target process
+    pub buf: usize,    // Userspace buffer
+    pub size: size_t,
+    pub result: c_int, // Filled by the driver: 0 = ok, negative errno on failure
+}
+
+#[repr(C)]
+pub struct BpMemoryVecCmd {
+    pub entries: *mut BpMemoryVecEntry,
+    pub count: size_t,
+}
+
+const WUWA_BP_IOCTL_READ_MEMORY_VEC: Ioctl = _IOWR::<BpMemoryVecCmd>(b'B' as u32, 4);
+const WUWA_BP_IOCTL_WRITE_MEMORY_VEC: Ioctl = _IOWR::<BpMemoryVecCmd>(b'B' as u32, 5);
+
+/// Maximum entries submitted in a single vectorized ioctl
+pub const BP_VEC_MAX_ENTRIES: usize = 256;
+
+/// Largest read [`BindProc::read_memory_coalesced`] queues, bigger ones are read directly
+pub const BP_COALESCE_MAX_SIZE: usize = 256;
+
+/// A read waiting in [`ReadCoalescer`]: va, size and where to deliver the bytes
+type QueuedRead = (usize, usize, mpsc::Sender<Option<Vec<u8>>>);
+
+/// Coalesces small reads issued by different threads within a short window
+///
+/// The first read to arrive becomes the leader: it waits for the window to
+/// pass (or for [`BP_VEC_MAX_ENTRIES`] reads to queue up), then submits the
+/// whole queue at once and hands every waiting thread its bytes.
+struct ReadCoalescer {
+    window_us: AtomicU64,
+    queue: Mutex<Vec<QueuedRead>>,
+    full: Condvar,
+}
+
+impl ReadCoalescer {
+    fn new() -> Self {
+        Self {
+            window_us: AtomicU64::new(0),
+            queue: Mutex::new(Vec::new()),
+            full: Condvar::new(),
+        }
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_micros(self.window_us.load(Ordering::Relaxed))
+    }
+
+    /// Queue a read of `size` bytes at `va`
+    ///
+    /// `submit` is called by the leader with the (va, size) of every queued read
+    /// and returns the bytes of each, None for failed reads.
+    fn read<F>(&self, va: usize, size: usize, submit: F) -> Option<Vec<u8>>
+    where
+        F: FnOnce(&[(usize, usize)]) -> Vec<Option<Vec<u8>>>,
+    {
+        let (reply, result) = mpsc::channel();
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        let leader = queue.is_empty();
+        queue.push((va, size, reply));
+
+        if leader {
+            let (mut queue, _) = self
+                .full
+                .wait_timeout_while(queue, self.window(), |queue| queue.len() < BP_VEC_MAX_ENTRIES)
+                .unwrap_or_else(PoisonError::into_inner);
+            let batch = std::mem::take(&mut *queue);
+            drop(queue);
+
+            let reads: Vec<(usize, usize)> = batch.iter().map(|&(va, size, _)| (va, size)).collect();
+            for ((_, _, reply), bytes) in batch.into_iter().zip(submit(&reads)) {
+                let _ = reply.send(bytes);
+            }
+        } else {
+            if queue.len() >= BP_VEC_MAX_ENTRIES {
+                self.full.notify_one();
+            }
+            drop(queue);
+        }
+
+        // The leader dropping the sender without replying (panic) counts as a failed read
+        result.recv().ok().flatten()
+    }
+}
+
+// Hardware watchpoint commands: the driver programs an ARM64 debug watchpoint
+// (DBGWVR/DBGWCR) on every thread of the bound process and records each hit
+// into a ring buffer owned by the BindProc fd. Watchpoints are released when
+// the fd is closed. Builds without debug register support reject them with
+// ENOTTY/EOPNOTSUPP.
+pub const BP_WATCH_READ: u32 = 1;
+pub const BP_WATCH_WRITE: u32 = 2;
+pub const BP_WATCH_ACCESS: u32 = BP_WATCH_READ | BP_WATCH_WRITE;
+
+/// Largest range one watchpoint can cover, it must not cross an 8-byte boundary
+pub const BP_WATCH_MAX_LEN: usize = 8;
+
+#[repr(C)]
+pub struct BpWatchSetCmd {
+    pub va: usize,   // Watched address in target process
+    pub len: size_t, // 1..=BP_WATCH_MAX_LEN bytes
+    pub kind: u32,   // BP_WATCH_* access kind
+    pub id: c_int,   // Filled by the driver: watchpoint id
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BpWatchHit {
+    pub id: c_int,         // Watchpoint that fired
+    pub tid: pid_t,        // Thread that performed the access
+    pub pc: u64,           // Address of the accessing instruction
+    pub addr: u64,         // Data address reported by the exception (FAR_EL1)
+    pub regs: [u64; 31],   // x0..x30 at the time of the hit
+    pub sp: u64,
+    pub timestamp_ns: u64, // CLOCK_BOOTTIME
+}
+
+#[repr(C)]
+pub struct BpWatchCollectCmd {
+    pub hits: *mut BpWatchHit,
+    pub capacity: size_t,
+    pub count: size_t,   // Filled by the driver: hits copied out
+    pub dropped: size_t, // Filled by the driver: hits overwritten in the ring since the last collect
+}
+
+const WUWA_BP_IOCTL_WATCH_SET: Ioctl = _IOWR::<BpWatchSetCmd>(b'B' as u32, 6);
+const WUWA_BP_IOCTL_WATCH_CLEAR: Ioctl = _IOWR::<c_int>(b'B' as u32, 7);
+const WUWA_BP_IOCTL_WATCH_COLLECT: Ioctl = _IOWR::<BpWatchCollectCmd>(b'B' as u32, 8);
+
+// Remote memory commands: the driver maps anonymous private pages into the
+// bound process's mm and changes their protection, the way the process's own
+// mmap/munmap/mprotect would. New mappings are populated so they can be
+// written through BindProc right away. Older builds reject them with ENOTTY.
+#[repr(C)]
+pub struct BpRemoteMmapCmd {
+    pub addr: usize, // Address hint, 0 = anywhere; filled by the driver with the mapping address
+    pub size: size_t,
+    pub prot: c_int, // PROT_* flags
+}
+
+#[repr(C)]
+pub struct BpRemoteMprotectCmd {
+    pub addr: usize,
+    pub size: size_t,
+    pub prot: c_int, // PROT_* flags, ignored by munmap
+}
+
+const WUWA_BP_IOCTL_REMOTE_MMAP: Ioctl = _IOWR::<BpRemoteMmapCmd>(b'B' as u32, 9);
+const WUWA_BP_IOCTL_REMOTE_MUNMAP: Ioctl = _IOWR::<BpRemoteMprotectCmd>(b'B' as u32, 10);
+const WUWA_BP_IOCTL_REMOTE_MPROTECT: Ioctl = _IOWR::<BpRemoteMprotectCmd>(b'B' as u32, 11);
+
+// Remote call command: the driver parks one thread of the bound process,
+// enters `func` on it with x0..x5 taken from `args` and lr pointing at a
+// trap, then restores the thread's original context once `func` returns.
+#[repr(C)]
+pub struct BpRemoteCallCmd {
+    pub func: usize,
+    pub args: [u64; 6],
+    pub timeout_ms: u32,
+    pub ret: u64, // Filled by the driver: x0 on return
+}
+
+/// Maximum arguments passed to a remote call
+pub const BP_REMOTE_CALL_MAX_ARGS: usize = 6;
+
+const WUWA_BP_IOCTL_REMOTE_CALL: Ioctl = _IOWR::<BpRemoteCallCmd>(b'B' as u32, 12);
+
+// Thread commands: list the threads of the bound process and read the user
+// register set of one of them. The driver briefly stops a running thread to
+// sample its registers, sleeping threads report the state saved on kernel entry.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BpThreadInfo {
+    pub tid: pid_t,
+    pub state: u8,      // Scheduler state letter as in /proc/<pid>/task/<tid>/stat ('R', 'S', 'D', ...)
+    pub comm: [u8; 16], // Thread name, NUL terminated
+}
+
+#[repr(C)]
+pub struct BpListThreadsCmd {
+    pub threads: *mut BpThreadInfo,
+    pub capacity: size_t,
+    pub count: size_t, // Filled by the driver: total threads, may exceed capacity
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BpThreadRegsCmd {
+    pub tid: pid_t,
+    pub regs: [u64; 31], // Filled by the driver: x0..x30
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+const WUWA_BP_IOCTL_LIST_THREADS: Ioctl = _IOWR::<BpListThreadsCmd>(b'B' as u32, 13);
+const WUWA_BP_IOCTL_GET_THREAD_REGS: Ioctl = _IOWR::<BpThreadRegsCmd>(b'B' as u32, 14);
+
 /// Page status bitmap for tracking read success/failure
 ///
 /// Helper struct for managing page status bitmaps returned by read_physical_memory.
@@ -494,19 +773,27 @@ impl PageStatusBitmap {
 /// Wraps a file descriptor returned by bind_process(). Provides:
 /// - Efficient reads via cached ioremap pages
 /// - Configurable memory type (cached/device/etc)
+/// - Batched small reads/writes via vectorized commands
+/// - Coalescing of small reads from concurrent threads
 /// - RAII fd management
 pub struct BindProc {
     fd: OwnedFd,
+    vectorized: bool,
+    coalescer: ReadCoalescer,
 }
 
 impl BindProc {
     /// Create from raw file descriptor
-    pub fn from_fd(fd: c_int) -> Result<Self, anyhow::Error> {
+    ///
+    /// `capabilities` decides whether vectorized commands are used.
+    pub fn from_fd(fd: c_int, capabilities: DriverCapabilities) -> Result<Self, anyhow::Error> {
         if fd < 0 {
             return Err(anyhow!("Invalid file descriptor"));
         }
         Ok(Self {
             fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            vectorized: capabilities.supports(WUWA_CAP_VECTORIZED),
+            coalescer: ReadCoalescer::new(),
         })
     }
 
@@ -540,7 +827,7 @@ impl BindProc {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("BindProc read failed: va=0x{:x} size={}", va, buf.len()));
+                return Err(memory_fault(ErrorCode::ReadFault, WUWA_BP_IOCTL_READ_MEMORY, va, format!("BindProc read failed: va=0x{:x} size={}", va, buf.len())));
             }
         }
 
@@ -567,7 +854,7 @@ impl BindProc {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("BindProc write failed: va=0x{:x} size={}", va, buf.len()));
+                return Err(memory_fault(ErrorCode::WriteFault, WUWA_BP_IOCTL_WRITE_MEMORY, va, format!("BindProc write failed: va=0x{:x} size={}", va, buf.len())));
             }
         }
 
@@ -601,7 +888,7 @@ impl BindProc {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("BindProc read failed: va=0x{:x} size={}", va, size));
+                return Err(memory_fault(ErrorCode::ReadFault, WUWA_BP_IOCTL_READ_MEMORY, va, format!("BindProc read failed: va=0x{:x} size={}", va, size)));
             }
             Ok(buffer.assume_init())
         }
@@ -625,7 +912,7 @@ impl BindProc {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("BindProc write failed: va=0x{:x} size={}", va, size));
+                return Err(memory_fault(ErrorCode::WriteFault, WUWA_BP_IOCTL_WRITE_MEMORY, va, format!("BindProc write failed: va=0x{:x} size={}", va, size)));
             }
         }
 
@@ -653,6 +940,330 @@ impl BindProc {
         Ok(())
     }
 
+    /// Read several small ranges with as few ioctls as possible
+    ///
+    /// Uses the vectorized read command when the driver supports it,
+    /// otherwise issues one read per entry.
+    ///
+    /// # Returns
+    /// Per-entry success flags, in request order
+    pub fn read_memory_vec(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<bool> {
+        let mut entries: Vec<BpMemoryVecEntry> = requests
+            .iter_mut()
+            .map(|(va, buf)| BpMemoryVecEntry {
+                va: *va,
+                buf: buf.as_mut_ptr() as usize,
+                size: buf.len(),
+                result: 0,
+            })
+            .collect();
+
+        if self.submit_vec(WUWA_BP_IOCTL_READ_MEMORY_VEC, &mut entries) {
+            return entries.iter().map(|entry| entry.result == 0).collect();
+        }
+
+        requests.iter_mut().map(|(va, buf)| self.read_memory(*va, buf, None).is_ok()).collect()
+    }
+
+    /// Write several small buffers with as few ioctls as possible
+    ///
+    /// Writes to adjacent addresses are coalesced into one run first. Runs are
+    /// submitted with the vectorized write command when the driver supports it,
+    /// otherwise one write per run. The entries of a failed run are retried
+    /// individually.
+    ///
+    /// # Returns
+    /// Per-entry success flags, in request order
+    pub fn write_memory_vec(&self, requests: &[(usize, &[u8])]) -> Vec<bool> {
+        let mut order: Vec<usize> = (0..requests.len()).collect();
+        order.sort_unstable_by_key(|&i| requests[i].0);
+
+        // (start va, bytes, request indices)
+        let mut runs: Vec<(usize, Vec<u8>, Vec<usize>)> = Vec::new();
+        for i in order {
+            let (va, buf) = requests[i];
+            match runs.last_mut() {
+                Some((start, bytes, members)) if *start + bytes.len() == va => {
+                    bytes.extend_from_slice(buf);
+                    members.push(i);
+                },
+                _ => runs.push((va, buf.to_vec(), vec![i])),
+            }
+        }
+
+        let mut entries: Vec<BpMemoryVecEntry> = runs
+            .iter()
+            .map(|(va, bytes, _)| BpMemoryVecEntry {
+                va: *va,
+                buf: bytes.as_ptr() as usize,
+                size: bytes.len(),
+                result: 0,
+            })
+            .collect();
+
+        let run_ok: Vec<bool> = if self.submit_vec(WUWA_BP_IOCTL_WRITE_MEMORY_VEC, &mut entries) {
+            entries.iter().map(|entry| entry.result == 0).collect()
+        } else {
+            runs.iter().map(|(va, bytes, _)| self.write_memory(*va, bytes).is_ok()).collect()
+        };
+
+        let mut results = vec![false; requests.len()];
+        for ((_, _, members), ok) in runs.iter().zip(run_ok) {
+            for &i in members {
+                // A failed run may span an unmapped page; retry its writes one by one
+                // so entries on the valid side still succeed
+                results[i] = ok || (members.len() > 1 && self.write_memory(requests[i].0, requests[i].1).is_ok());
+            }
+        }
+        results
+    }
+
+    /// Read a small value, sharing one vectorized ioctl with reads other threads
+    /// issue within the coalescing window
+    ///
+    /// Reads directly when the window is zero, the driver lacks vectorized
+    /// commands or `buf` is larger than [`BP_COALESCE_MAX_SIZE`].
+    pub fn read_memory_coalesced(&self, va: usize, buf: &mut [u8]) -> Result<(), anyhow::Error> {
+        if !self.vectorized || buf.len() > BP_COALESCE_MAX_SIZE || self.coalescer.window().is_zero() {
+            return self.read_memory(va, buf, None);
+        }
+
+        let bytes = self.coalescer.read(va, buf.len(), |reads| {
+            let mut buffers: Vec<Vec<u8>> = reads.iter().map(|&(_, size)| vec![0u8; size]).collect();
+            let mut requests: Vec<(usize, &mut [u8])> =
+                reads.iter().zip(buffers.iter_mut()).map(|(&(va, _), buf)| (va, buf.as_mut_slice())).collect();
+            let succeeded = self.read_memory_vec(&mut requests);
+            buffers.into_iter().zip(succeeded).map(|(buf, ok)| ok.then_some(buf)).collect()
+        });
+
+        match bytes {
+            Some(bytes) => {
+                buf.copy_from_slice(&bytes);
+                Ok(())
+            },
+            None => Err(memory_fault(
+                ErrorCode::ReadFault,
+                WUWA_BP_IOCTL_READ_MEMORY_VEC,
+                va,
+                format!("BindProc coalesced read failed: va=0x{:x} size={}", va, buf.len()),
+            )),
+        }
+    }
+
+    /// Set how long the first of several concurrent small reads waits for others
+    /// to join it, zero (the default) disables coalescing
+    pub fn set_read_coalesce_window(&self, window: Duration) {
+        self.coalescer.window_us.store(window.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Whether the driver reported support for vectorized commands
+    pub fn supports_vectorized(&self) -> bool {
+        self.vectorized
+    }
+
+    /// Submit `entries` in chunks of [`BP_VEC_MAX_ENTRIES`]
+    ///
+    /// Returns false if the driver lacks the command; nothing was transferred then.
+    fn submit_vec(&self, request: Ioctl, entries: &mut [BpMemoryVecEntry]) -> bool {
+        if entries.is_empty() {
+            return true;
+        }
+        if !self.vectorized {
+            return false;
+        }
+
+        for chunk in entries.chunks_mut(BP_VEC_MAX_ENTRIES) {
+            let mut cmd = BpMemoryVecCmd {
+                entries: chunk.as_mut_ptr(),
+                count: chunk.len(),
+            };
+
+            let result = unsafe { ioctl(self.fd.as_raw_fd(), request, &mut cmd as *mut _ as *mut c_void) };
+            if result < 0 {
+                // The whole command failed, not individual entries
+                let errno = Errno::last();
+                for entry in chunk.iter_mut() {
+                    entry.result = -(errno as c_int);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Arm a hardware watchpoint on `len` bytes at `va`
+    ///
+    /// # Returns
+    /// The driver's watchpoint id, used to clear it and to match hits
+    pub fn set_watchpoint(&self, va: usize, len: usize, kind: u32) -> Result<i32, anyhow::Error> {
+        let mut cmd = BpWatchSetCmd { va, len, kind, id: -1 };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_WATCH_SET, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(match Errno::last() {
+                Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support hardware watchpoints"),
+                Errno::ENOSPC => anyhow!("No free hardware watchpoint slots"),
+                errno => anyhow!("BindProc set watchpoint failed: va=0x{:x} len={}: {}", va, len, errno),
+            });
+        }
+
+        Ok(cmd.id)
+    }
+
+    /// Release a watchpoint armed by [`Self::set_watchpoint`]
+    pub fn clear_watchpoint(&self, id: i32) -> Result<(), anyhow::Error> {
+        let mut id = id as c_int;
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_WATCH_CLEAR, &mut id as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(anyhow!("BindProc clear watchpoint {} failed: {}", id, Errno::last()));
+        }
+
+        Ok(())
+    }
+
+    /// Drain up to `capacity` hits from the driver's ring buffer
+    ///
+    /// # Returns
+    /// The hits in arrival order and the number of hits the ring overwrote
+    pub fn collect_watch_hits(&self, capacity: usize) -> Result<(Vec<BpWatchHit>, usize), anyhow::Error> {
+        let mut hits = vec![BpWatchHit::default(); capacity];
+        let mut cmd = BpWatchCollectCmd {
+            hits: hits.as_mut_ptr(),
+            capacity,
+            count: 0,
+            dropped: 0,
+        };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_WATCH_COLLECT, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(anyhow!("BindProc collect watch hits failed: {}", Errno::last()));
+        }
+
+        hits.truncate(cmd.count.min(capacity));
+        Ok((hits, cmd.dropped))
+    }
+
+    /// Map `size` bytes of anonymous memory into the bound process
+    ///
+    /// # Arguments
+    /// * `hint` - Preferred address, 0 lets the kernel choose
+    /// * `prot` - PROT_* flags
+    ///
+    /// # Returns
+    /// Address of the new mapping
+    pub fn remote_mmap(&self, hint: usize, size: usize, prot: c_int) -> Result<usize, anyhow::Error> {
+        let mut cmd = BpRemoteMmapCmd { addr: hint, size, prot };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_REMOTE_MMAP, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(match Errno::last() {
+                Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support remote memory allocation"),
+                errno => anyhow!("BindProc remote mmap failed: size={} prot={}: {}", size, prot, errno),
+            });
+        }
+
+        Ok(cmd.addr)
+    }
+
+    /// Unmap a range mapped by [`Self::remote_mmap`]
+    pub fn remote_munmap(&self, addr: usize, size: usize) -> Result<(), anyhow::Error> {
+        let mut cmd = BpRemoteMprotectCmd { addr, size, prot: 0 };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_REMOTE_MUNMAP, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(anyhow!("BindProc remote munmap failed: addr=0x{:x} size={}: {}", addr, size, Errno::last()));
+        }
+
+        Ok(())
+    }
+
+    /// Change the protection of a range in the bound process
+    pub fn remote_mprotect(&self, addr: usize, size: usize, prot: c_int) -> Result<(), anyhow::Error> {
+        let mut cmd = BpRemoteMprotectCmd { addr, size, prot };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_REMOTE_MPROTECT, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(anyhow!("BindProc remote mprotect failed: addr=0x{:x} size={} prot={}: {}", addr, size, prot, Errno::last()));
+        }
+
+        Ok(())
+    }
+
+    /// Call `func` inside the bound process and return its x0
+    ///
+    /// # Arguments
+    /// * `args` - Up to [`BP_REMOTE_CALL_MAX_ARGS`] integer arguments
+    /// * `timeout_ms` - How long to wait for a thread to park and for `func` to return
+    pub fn remote_call(&self, func: usize, args: &[u64], timeout_ms: u32) -> Result<u64, anyhow::Error> {
+        if args.len() > BP_REMOTE_CALL_MAX_ARGS {
+            return Err(anyhow!("Remote call takes at most {} arguments", BP_REMOTE_CALL_MAX_ARGS));
+        }
+        let mut cmd = BpRemoteCallCmd {
+            func,
+            args: [0; BP_REMOTE_CALL_MAX_ARGS],
+            timeout_ms,
+            ret: 0,
+        };
+        cmd.args[..args.len()].copy_from_slice(args);
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_REMOTE_CALL, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(match Errno::last() {
+                Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support remote calls"),
+                Errno::ETIMEDOUT => anyhow!("Remote call to 0x{:x} timed out", func),
+                errno => anyhow!("BindProc remote call to 0x{:x} failed: {}", func, errno),
+            });
+        }
+
+        Ok(cmd.ret)
+    }
+
+    /// List the threads of the bound process
+    pub fn list_threads(&self) -> Result<Vec<BpThreadInfo>, anyhow::Error> {
+        let mut capacity = 256;
+        loop {
+            let mut threads = vec![BpThreadInfo::default(); capacity];
+            let mut cmd = BpListThreadsCmd {
+                threads: threads.as_mut_ptr(),
+                capacity,
+                count: 0,
+            };
+
+            let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_LIST_THREADS, &mut cmd as *mut _ as *mut c_void) };
+            if result < 0 {
+                return Err(match Errno::last() {
+                    Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support thread enumeration"),
+                    errno => anyhow!("BindProc list threads failed: {}", errno),
+                });
+            }
+
+            // Threads were created between calls, retry with room to spare
+            if cmd.count > capacity {
+                capacity = cmd.count + 64;
+                continue;
+            }
+            threads.truncate(cmd.count);
+            return Ok(threads);
+        }
+    }
+
+    /// Read the user registers of thread `tid` in the bound process
+    pub fn get_thread_regs(&self, tid: pid_t) -> Result<BpThreadRegsCmd, anyhow::Error> {
+        let mut cmd = BpThreadRegsCmd { tid, ..Default::default() };
+
+        let result = unsafe { ioctl(self.fd.as_raw_fd(), WUWA_BP_IOCTL_GET_THREAD_REGS, &mut cmd as *mut _ as *mut c_void) };
+        if result < 0 {
+            return Err(match Errno::last() {
+                Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support reading thread registers"),
+                Errno::ESRCH => anyhow!("Thread {} does not belong to the bound process", tid),
+                errno => anyhow!("BindProc get thread {} registers failed: {}", tid, errno),
+            });
+        }
+
+        Ok(cmd)
+    }
+
     /// Get underlying file descriptor (for advanced use)
     pub fn raw_fd(&self) -> c_int {
         self.fd.as_raw_fd()
@@ -670,6 +1281,7 @@ pub struct MemRegionsResult {
 /// WuWa driver connection handle
 pub struct WuWaDriver {
     sock: OwnedFd,
+    capabilities: DriverCapabilities,
 }
 
 impl WuWaDriver {
@@ -725,7 +1337,8 @@ impl WuWaDriver {
     /// Connect to WuWa driver. Requires root or CAP_NET_RAW.
     pub fn new() -> Result<Self, anyhow::Error> {
         let sock = Self::driver_id()?;
-        Ok(Self { sock })
+        let capabilities = Self::query_capabilities(&sock);
+        Ok(Self { sock, capabilities })
     }
 
     /// Create WuWaDriver from existing file descriptor
@@ -739,9 +1352,47 @@ impl WuWaDriver {
     /// # Returns
     /// WuWaDriver instance that will close fd on destruction
     pub fn from_fd(fd: c_int) -> Self {
-        Self {
-            sock: unsafe { OwnedFd::from_raw_fd(fd) },
+        let sock = unsafe { OwnedFd::from_raw_fd(fd) };
+        let capabilities = Self::query_capabilities(&sock);
+        Self { sock, capabilities }
+    }
+
+    /// Capability handshake, falls back to WUWA_CAP_LEGACY when the driver predates it
+    fn query_capabilities(sock: &OwnedFd) -> DriverCapabilities {
+        let mut cmd = WuwaCapabilitiesCmd { version: 0, features: 0 };
+
+        let result = unsafe {
+            ioctl(
+                sock.as_raw_fd(),
+                WUWA_IOCTL_GET_CAPABILITIES,
+                &mut cmd as *mut _ as *mut c_void,
+            )
+        };
+        if result < 0 {
+            if log_enabled!(Level::Debug) {
+                debug!("Driver capability handshake unavailable: {}", Errno::last());
+            }
+            return DriverCapabilities {
+                version: 0,
+                features: WUWA_CAP_LEGACY,
+            };
         }
+
+        let capabilities = DriverCapabilities {
+            version: cmd.version,
+            features: cmd.features,
+        };
+        info!(
+            "WuWa driver {} features=0x{:x}",
+            capabilities.version_name(),
+            capabilities.features
+        );
+        capabilities
+    }
+
+    /// Version and features reported when the driver was opened
+    pub fn capabilities(&self) -> DriverCapabilities {
+        self.capabilities
     }
 
     /// Software page table walk: VA -> PA translation
@@ -762,6 +1413,31 @@ impl WuWaDriver {
         Ok(cmd.phy_addr)
     }
 
+    /// Read from a physical address, the read must stay within one page
+    pub fn read_physical_addr(&self, phy_addr: u64, buf: &mut [u8]) -> Result<(), anyhow::Error> {
+        let mut cmd = WuwaReadPhysAddrCmd {
+            phy_addr,
+            dst_va: buf.as_mut_ptr() as usize,
+            size: buf.len(),
+        };
+
+        let result = unsafe {
+            ioctl(
+                self.sock.as_raw_fd(),
+                WUWA_IOCTL_READ_PHYSICAL_ADDR,
+                &mut cmd as *mut _ as *mut c_void,
+            )
+        };
+        if result < 0 {
+            return Err(match Errno::last() {
+                Errno::ENOTTY | Errno::EOPNOTSUPP => anyhow!("Driver does not support raw physical reads"),
+                errno => anyhow!("Physical read failed: pa=0x{:x} size={}: {}", phy_addr, buf.len(), errno),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Get process debug info (TTBR0, task_struct, mm_struct, pgd)
     pub fn get_debug_info(&self, pid: pid_t) -> Result<WuwaDebugInfoCmd, anyhow::Error> {
         let mut cmd = WuwaDebugInfoCmd {
@@ -937,7 +1613,7 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("Physical memory read failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(ErrorCode::ReadFault, WUWA_IOCTL_READ_PHYSICAL_MEMORY, src_va, format!("Physical memory read failed: va=0x{:x} size={}", src_va, size)));
             }
         }
 
@@ -1000,14 +1676,11 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                let errno = Errno::last();
-                return Err(anyhow!(
-                    "read_physical_memory_with_status failed: va=0x{:x} size={}, result={}, errno={} ({})",
+                return Err(memory_fault(
+                    ErrorCode::ReadFault,
+                    WUWA_IOCTL_READ_PHYSICAL_MEMORY,
                     src_va,
-                    size,
-                    result,
-                    errno,
-                    errno
+                    format!("read_physical_memory_with_status failed: va=0x{:x} size={}, result={}", src_va, size, result),
                 ));
             }
         }
@@ -1038,7 +1711,12 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("Physical memory write failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(
+                    ErrorCode::WriteFault,
+                    WUWA_IOCTL_WRITE_PHYSICAL_MEMORY,
+                    dst_va,
+                    format!("Physical memory write failed: va=0x{:x} size={}", dst_va, size),
+                ));
             }
         }
 
@@ -1062,7 +1740,7 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("Memory read failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(ErrorCode::ReadFault, WUWA_IOCTL_READ_MEMORY, src_va, format!("Memory read failed: va=0x{:x} size={}", src_va, size)));
             }
         }
 
@@ -1092,7 +1770,7 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("Memory write failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(ErrorCode::WriteFault, WUWA_IOCTL_WRITE_MEMORY, dst_va, format!("Memory write failed: va=0x{:x} size={}", dst_va, size)));
             }
         }
 
@@ -1328,7 +2006,7 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("ioremap read failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(ErrorCode::ReadFault, WUWA_IOCTL_READ_MEMORY_IOREMAP, src_va, format!("ioremap read failed: va=0x{:x} size={}", src_va, size)));
             }
         }
 
@@ -1363,7 +2041,7 @@ impl WuWaDriver {
                 &mut cmd as *mut _ as *mut c_void,
             );
             if result < 0 {
-                return Err(anyhow!("ioremap write failed: va=0x{:x} size={}", src_va, size));
+                return Err(memory_fault(ErrorCode::WriteFault, WUWA_IOCTL_WRITE_MEMORY_IOREMAP, dst_va, format!("ioremap write failed: va=0x{:x} size={}", dst_va, size)));
             }
         }
 
@@ -1394,7 +2072,7 @@ impl WuWaDriver {
             }
         }
 
-        BindProc::from_fd(cmd.fd)
+        BindProc::from_fd(cmd.fd, self.capabilities)
     }
 
     /// Copy process with custom function pointer and stack
@@ -1663,3 +2341,48 @@ impl WuWaDriver {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+    use std::sync::atomic::AtomicUsize;
+
+    fn fill(reads: &[(usize, usize)]) -> Vec<Option<Vec<u8>>> {
+        reads.iter().map(|&(va, size)| (va != 0).then(|| vec![va as u8; size])).collect()
+    }
+
+    #[test]
+    fn test_coalescer_without_window_submits_alone() {
+        let coalescer = ReadCoalescer::new();
+        assert_eq!(coalescer.read(7, 3, fill), Some(vec![7; 3]));
+        assert_eq!(coalescer.read(0, 3, fill), None);
+    }
+
+    #[test]
+    fn test_coalescer_batches_concurrent_reads() {
+        let coalescer = ReadCoalescer::new();
+        coalescer.window_us.store(200_000, Ordering::Relaxed);
+        let submits = AtomicUsize::new(0);
+        let barrier = Barrier::new(4);
+
+        let results: Vec<Option<Vec<u8>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4usize)
+                .map(|i| {
+                    let (coalescer, submits, barrier) = (&coalescer, &submits, &barrier);
+                    scope.spawn(move || {
+                        barrier.wait();
+                        coalescer.read(i, i + 1, |reads| {
+                            submits.fetch_add(1, Ordering::Relaxed);
+                            fill(reads)
+                        })
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        assert_eq!(submits.load(Ordering::Relaxed), 1);
+        assert_eq!(results, vec![None, Some(vec![1; 2]), Some(vec![2; 3]), Some(vec![3; 4])]);
+    }
+}